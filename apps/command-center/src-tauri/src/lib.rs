@@ -1,35 +1,39 @@
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
 
 // ===== Core Connectivity State (Circuit Breaker) =====
 
 #[derive(Debug, Clone)]
 pub struct CoreState {
     pub is_online: Arc<RwLock<bool>>,
-    pub base_url: String,
-    pub client: reqwest::Client,
+    /// Typed Serve API client (`FACTORY_URL` or `FACTORY_RUNTIME_FILE`, and `FACTORY_API_KEY`)
+    pub client: FactoryClient,
 }
 
 impl CoreState {
-    pub fn new(base_url: &str) -> Self {
+    pub fn new(client: FactoryClient) -> Self {
         Self {
             is_online: Arc::new(RwLock::new(false)),
-            base_url: base_url.to_string(),
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .unwrap_or_default(),
+            client,
         }
     }
 
-    /// Check if Core API is reachable
-    async fn health_check(&self) -> bool {
-        match self.client.get(format!("{}/api/health", self.base_url)).send().await {
-            Ok(resp) => resp.status().is_success(),
-            Err(_) => false,
+    /// Flip the circuit breaker and notify the frontend on transitions
+    async fn set_online(&self, app: &AppHandle, is_up: bool) {
+        let mut online = self.is_online.write().await;
+        if *online != is_up {
+            if is_up {
+                eprintln!("🟢 [Tauri] Core API is online");
+            } else {
+                eprintln!("🔴 [Tauri] Core API is offline");
+            }
+            let _ = app.emit("core:online", CoreHealthStatus { online: is_up });
         }
+        *online = is_up;
     }
 
     /// Pre-flight check: return error immediately if Core is offline
@@ -44,38 +48,23 @@ impl CoreState {
 
 // ===== API Response Types =====
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProjectSummary {
-    pub id: String,
-    pub title: String,
-    pub style: Option<String>,
-    pub created_at: String,
-    pub thumbnail_url: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RemixRequest {
-    pub category: String,
-    pub topic: String,
-    pub remix_id: String,
-    pub style_name: String,
-    pub custom_style: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RemixResponse {
-    pub job_id: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SystemStatus {
-    pub cpu_usage: f64,
-    pub memory_usage_mb: u64,
-    pub vram_usage_mb: u64,
-    pub active_actor: Option<String>,
+// Serve API の型とエンドポイントは factory-client (factory-core の型) と共有する (手で複製しない)
+pub use factory_client::{AcceptedJob, FactoryClient, FfmpegProgress, LogEvent, ProjectSummary, StageEvent, SystemStatus, WorkflowRequest};
+
+/// Frames pushed by Core on `/ws/telemetry`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryFrame {
+    Status(SystemStatus),
+    Stage(StageEvent),
+    Log(LogEvent),
+    /// ComfyUI queue summary, forwarded to the UI as-is
+    ComfyQueue(serde_json::Value),
+    /// Progress and ETA of a long FFmpeg run (final assembly, resize, ...)
+    FfmpegProgress(FfmpegProgress),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreHealthStatus {
     pub online: bool,
 }
@@ -93,100 +82,117 @@ async fn get_core_status(state: State<'_, CoreState>) -> Result<CoreHealthStatus
 #[tauri::command]
 async fn get_projects(state: State<'_, CoreState>) -> Result<Vec<ProjectSummary>, String> {
     state.ensure_online().await?;
-    let resp = state.client
-        .get(format!("{}/api/projects", state.base_url))
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("Core returned status {}", resp.status()));
-    }
-
-    resp.json::<Vec<ProjectSummary>>()
-        .await
-        .map_err(|e| format!("Failed to parse projects: {}", e))
+    state.client.projects().await.map_err(|e| e.to_string())
 }
 
 /// Fetch available styles
 #[tauri::command]
 async fn get_styles(state: State<'_, CoreState>) -> Result<Vec<String>, String> {
     state.ensure_online().await?;
-    let resp = state.client
-        .get(format!("{}/api/styles", state.base_url))
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if !resp.status().is_success() {
-        return Err(format!("Core returned status {}", resp.status()));
-    }
-
-    resp.json::<Vec<String>>()
-        .await
-        .map_err(|e| format!("Failed to parse styles: {}", e))
+    state.client.styles().await.map_err(|e| e.to_string())
 }
 
 /// Submit a remix job
 #[tauri::command]
-async fn post_remix(state: State<'_, CoreState>, request: RemixRequest) -> Result<RemixResponse, String> {
+async fn post_remix(state: State<'_, CoreState>, request: WorkflowRequest) -> Result<AcceptedJob, String> {
     state.ensure_online().await?;
-    let resp = state.client
-        .post(format!("{}/api/remix", state.base_url))
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("Network error: {}", e))?;
-
-    if resp.status().as_u16() == 429 {
-        return Err("System busy! Request rejected (429).".to_string());
-    }
-
-    if !resp.status().is_success() {
-        return Err(format!("Core returned status {}", resp.status()));
-    }
+    state.client.remix(&request).await.map_err(|e| e.to_string())
+}
 
-    resp.json::<RemixResponse>()
-        .await
-        .map_err(|e| format!("Failed to parse remix response: {}", e))
+/// Core base URL the app discovered, for the views that fetch Core directly
+#[tauri::command]
+fn get_core_url(state: State<'_, CoreState>) -> String {
+    state.client.base_url().to_string()
 }
 
 /// Get asset URL (proxy for CORS-free access)
 #[tauri::command]
 async fn get_asset_url(state: State<'_, CoreState>, project_id: String, filename: String) -> Result<String, String> {
-    Ok(format!("{}/assets/{}/{}", state.base_url, project_id, filename))
+    state.client.asset_url(&project_id, &filename).map_err(|e| e.to_string())
+}
+
+// ===== Live Telemetry Subscriber =====
+
+const TELEMETRY_RETRY_MIN: std::time::Duration = std::time::Duration::from_secs(1);
+const TELEMETRY_RETRY_MAX: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Keep a WebSocket open to Core and re-emit each frame as a Tauri event.
+///
+/// The socket itself is the circuit breaker: connected means online, and a
+/// dropped connection flips offline immediately instead of waiting for the next poll.
+async fn run_telemetry_subscriber(app: AppHandle, state: CoreState) {
+    let url = state.client.telemetry_url();
+    let mut backoff = TELEMETRY_RETRY_MIN;
+
+    loop {
+        match tokio_tungstenite::connect_async(url.as_str()).await {
+            Ok((mut stream, _)) => {
+                state.set_online(&app, true).await;
+                backoff = TELEMETRY_RETRY_MIN;
+
+                while let Some(msg) = stream.next().await {
+                    let text = match msg {
+                        Ok(Message::Text(text)) => text,
+                        Ok(Message::Close(_)) | Err(_) => break,
+                        Ok(_) => continue,
+                    };
+                    let emitted = match serde_json::from_str::<TelemetryFrame>(&text) {
+                        Ok(TelemetryFrame::Status(status)) => app.emit("telemetry:status", status),
+                        Ok(TelemetryFrame::Stage(stage)) => app.emit("telemetry:stage", stage),
+                        Ok(TelemetryFrame::Log(log)) => app.emit("telemetry:log", log),
+                        Ok(TelemetryFrame::ComfyQueue(queue)) => app.emit("telemetry:comfy_queue", queue),
+                        Ok(TelemetryFrame::FfmpegProgress(progress)) => app.emit("telemetry:ffmpeg_progress", progress),
+                        Err(e) => {
+                            eprintln!("⚠️ [Tauri] Unknown telemetry frame: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Err(e) = emitted {
+                        eprintln!("⚠️ [Tauri] Failed to emit telemetry event: {}", e);
+                    }
+                }
+                state.set_online(&app, false).await;
+            }
+            Err(_) => {
+                state.set_online(&app, false).await;
+            }
+        }
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(TELEMETRY_RETRY_MAX);
+    }
 }
 
 // ===== Application Entry Point =====
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let core_state = CoreState::new("http://127.0.0.1:3000");
-
-    // Background health check poller
-    let health_state = core_state.clone();
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-        rt.block_on(async move {
-            loop {
-                let is_up = health_state.health_check().await;
-                let mut online = health_state.is_online.write().await;
-                if *online != is_up {
-                    if is_up {
-                        eprintln!("🟢 [Tauri] Core API is online");
-                    } else {
-                        eprintln!("🔴 [Tauri] Core API is offline");
-                    }
-                }
-                *online = is_up;
-                drop(online);
-                tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
-            }
-        });
+    // 設定の誤りで起動できなくならないよう、既定の URL で立ち上げる (接続できなければ offline 表示になる)
+    let client = FactoryClient::from_env().or_else(|e| {
+        eprintln!("⚠️ [Tauri] Invalid {} ({}). Falling back to {}", factory_client::URL_ENV, e, factory_client::DEFAULT_BASE_URL);
+        FactoryClient::new(factory_client::DEFAULT_BASE_URL).map(|client| match std::env::var(factory_client::API_KEY_ENV) {
+            Ok(key) => client.with_api_key(&key),
+            Err(_) => client,
+        })
     });
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("🔴 [Tauri] Cannot build the Core API client: {}", e);
+            return;
+        }
+    };
+    let core_state = CoreState::new(client);
+
+    let telemetry_state = core_state.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(move |app| {
+            // Live telemetry replaces the old 10s health poller
+            tauri::async_runtime::spawn(run_telemetry_subscriber(app.handle().clone(), telemetry_state));
+            Ok(())
+        })
         .manage(core_state)
         .invoke_handler(tauri::generate_handler![
             get_core_status,
@@ -194,6 +200,7 @@ pub fn run() {
             get_styles,
             post_remix,
             get_asset_url,
+            get_core_url,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
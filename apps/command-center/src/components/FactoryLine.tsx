@@ -3,11 +3,14 @@ import useWebSocket from 'react-use-websocket';
 import { LineChart, Line, XAxis, YAxis, Tooltip, ResponsiveContainer } from 'recharts';
 import { Activity, Cpu, HardDrive, Terminal } from 'lucide-react';
 import { clsx } from 'clsx';
+import { useCoreUrl } from '../coreUrl';
 
 interface SystemHeartbeat {
     cpu_usage: number;
     memory_usage_mb: number;
     vram_usage_mb: number;
+    vram_total_mb?: number;
+    gpu_utilization?: number | null;
     active_actor: string | null;
 }
 
@@ -17,14 +20,13 @@ interface LogEvent {
     timestamp: string;
 }
 
-const WS_URL = 'ws://127.0.0.1:3000/ws';
-
 export const FactoryLine: React.FC = () => {
     const [heartbeats, setHeartbeats] = useState<SystemHeartbeat[]>([]);
     const [logs, setLogs] = useState<LogEvent[]>([]);
     const logEndRef = useRef<HTMLDivElement>(null);
 
-    const { lastMessage } = useWebSocket(WS_URL, {
+    const base = useCoreUrl();
+    const { lastMessage } = useWebSocket(base ? `${base.replace(/^http/, 'ws')}/ws` : null, {
         shouldReconnect: () => true,
         reconnectInterval: 3000,
     });
@@ -65,8 +67,10 @@ export const FactoryLine: React.FC = () => {
                         <div className="text-xl text-white">{currentStatus.cpu_usage.toFixed(1)}%</div>
                     </div>
                     <div className="bg-black/50 p-2 rounded">
-                        <div className="flex items-center gap-2 text-gray-400 mb-1"><HardDrive size={12} /> VRAM (Est)</div>
-                        <div className="text-xl text-white">{currentStatus.vram_usage_mb} MB</div>
+                        <div className="flex items-center gap-2 text-gray-400 mb-1"><HardDrive size={12} /> VRAM</div>
+                        <div className="text-xl text-white">
+                            {currentStatus.vram_total_mb ? `${currentStatus.vram_usage_mb} / ${currentStatus.vram_total_mb} MB` : 'n/a'}
+                        </div>
                     </div>
                 </div>
 
@@ -2,6 +2,7 @@ import { useState } from 'react';
 import useSWR from 'swr';
 import { RefreshCw, CheckCircle, XCircle, Clock } from 'lucide-react';
 import { clsx } from 'clsx';
+import { useCoreUrl } from '../coreUrl';
 
 const fetcher = (url: string) => fetch(url).then(r => r.json());
 
@@ -16,7 +17,8 @@ interface Job {
 }
 
 export const JobDashboard = () => {
-    const { data: jobs, error, mutate, isValidating } = useSWR<Job[]>('http://localhost:3000/api/jobs', fetcher, {
+    const base = useCoreUrl();
+    const { data: jobs, error, mutate, isValidating } = useSWR<Job[]>(base ? `${base}/api/jobs` : null, fetcher, {
         refreshInterval: 5000
     });
 
@@ -25,7 +27,7 @@ export const JobDashboard = () => {
     const handleRate = async (id: string, rating: number) => {
         setRatingLoading(id);
         try {
-            await fetch(`http://localhost:3000/api/jobs/${id}/rate`, {
+            await fetch(`${base}/api/jobs/${id}/rate`, {
                 method: 'POST',
                 headers: { 'Content-Type': 'application/json' },
                 body: JSON.stringify({ rating }),
@@ -1,6 +1,8 @@
+import { useState } from 'react';
 import useSWR from 'swr';
-import { Database, Lightbulb, RefreshCw, Layers } from 'lucide-react';
+import { Database, Lightbulb, RefreshCw, Layers, Trash2, Plus } from 'lucide-react';
 import { clsx } from 'clsx';
+import { useCoreUrl } from '../coreUrl';
 
 const fetcher = (url: string) => fetch(url).then(r => r.json());
 
@@ -14,13 +16,62 @@ interface Karma {
     created_at: string;
     last_applied_at: string | null;
     soul_version_hash: string;
+    namespace: string | null;
 }
 
+const KARMA_TYPES = ['Technical', 'Creative', 'Synthesized'];
+
 export const KarmaViewer = () => {
-    const { data: karmas, error, mutate, isValidating } = useSWR<Karma[]>('http://localhost:3000/api/karma', fetcher, {
+    const base = useCoreUrl();
+    const [skill, setSkill] = useState('');
+    const [karmaType, setKarmaType] = useState('');
+    const [minWeight, setMinWeight] = useState('');
+    const [draft, setDraft] = useState({ lesson: '', skill_id: '', namespace: '', karma_type: 'Synthesized', weight: 100 });
+    const [actionError, setActionError] = useState<string | null>(null);
+
+    const params = new URLSearchParams();
+    if (skill.trim()) params.set('skill', skill.trim());
+    if (karmaType) params.set('type', karmaType);
+    if (minWeight) params.set('min_weight', minWeight);
+    const query = params.toString();
+
+    const { data: karmas, error, mutate, isValidating } = useSWR<Karma[]>(base ? `${base}/api/karma${query ? `?${query}` : ''}` : null, fetcher, {
         refreshInterval: 10000
     });
 
+    const send = async (path: string, init: RequestInit) => {
+        setActionError(null);
+        try {
+            const res = await fetch(`${base}${path}`, init);
+            if (!res.ok) {
+                const body = await res.json().catch(() => null);
+                setActionError(body?.error ?? `Request failed (${res.status})`);
+                return false;
+            }
+            mutate();
+            return true;
+        } catch (err) {
+            console.error(err);
+            setActionError(String(err));
+            return false;
+        }
+    };
+
+    const handleDelete = async (id: string) => {
+        if (!window.confirm('Forget this lesson?')) return;
+        await send(`/api/karma/${encodeURIComponent(id)}`, { method: 'DELETE' });
+    };
+
+    const handleInject = async () => {
+        if (!draft.lesson.trim()) return;
+        const ok = await send('/api/karma', {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ ...draft, skill_id: draft.skill_id.trim() || undefined, namespace: draft.namespace.trim() || undefined }),
+        });
+        if (ok) setDraft({ ...draft, lesson: '' });
+    };
+
     if (error) return <div className="p-8 text-red-500">Failed to load Karma</div>;
     if (!karmas) return <div className="p-8 text-sonar-green flex items-center gap-2"><RefreshCw className="animate-spin" /> Digging into Samsara Memories...</div>;
 
@@ -40,7 +91,77 @@ export const KarmaViewer = () => {
                 </button>
             </div>
 
-            <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6 overflow-y-auto max-h-[calc(100vh-160px)] pb-20">
+            <div className="flex flex-wrap gap-3 mb-4 text-sm">
+                <input
+                    value={skill}
+                    onChange={e => setSkill(e.target.value)}
+                    placeholder="Skill (e.g. comfy_bridge)"
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200"
+                />
+                <select
+                    value={karmaType}
+                    onChange={e => setKarmaType(e.target.value)}
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200"
+                >
+                    <option value="">All types</option>
+                    {KARMA_TYPES.map(t => <option key={t} value={t}>{t}</option>)}
+                </select>
+                <input
+                    type="number"
+                    min={0}
+                    max={100}
+                    value={minWeight}
+                    onChange={e => setMinWeight(e.target.value)}
+                    placeholder="Min weight"
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200 w-32"
+                />
+            </div>
+
+            <div className="flex flex-wrap gap-3 mb-6 text-sm bg-gray-900/50 border border-gray-800 rounded-lg p-3">
+                <input
+                    value={draft.lesson}
+                    onChange={e => setDraft({ ...draft, lesson: e.target.value })}
+                    placeholder="New lesson to inject..."
+                    className="flex-grow bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200"
+                />
+                <input
+                    value={draft.skill_id}
+                    onChange={e => setDraft({ ...draft, skill_id: e.target.value })}
+                    placeholder="Skill (global)"
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200 w-40"
+                />
+                <input
+                    value={draft.namespace}
+                    onChange={e => setDraft({ ...draft, namespace: e.target.value })}
+                    placeholder="Style (shared)"
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200 w-40"
+                />
+                <select
+                    value={draft.karma_type}
+                    onChange={e => setDraft({ ...draft, karma_type: e.target.value })}
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200"
+                >
+                    {KARMA_TYPES.map(t => <option key={t} value={t}>{t}</option>)}
+                </select>
+                <input
+                    type="number"
+                    min={0}
+                    max={100}
+                    value={draft.weight}
+                    onChange={e => setDraft({ ...draft, weight: Number(e.target.value) })}
+                    className="bg-gray-900 border border-gray-800 rounded px-3 py-1.5 text-gray-200 w-20"
+                />
+                <button
+                    onClick={handleInject}
+                    disabled={!draft.lesson.trim()}
+                    className="px-3 py-1.5 bg-purple-500/20 hover:bg-purple-500/30 disabled:opacity-40 rounded text-purple-300 transition flex items-center gap-1"
+                >
+                    <Plus size={14} /> Inject
+                </button>
+            </div>
+            {actionError && <div className="mb-4 text-red-400 text-sm">{actionError}</div>}
+
+            <div className="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6 overflow-y-auto max-h-[calc(100vh-300px)] pb-20">
                 {karmas.map((karma: Karma) => (
                     <div
                         key={karma.id}
@@ -52,8 +173,18 @@ export const KarmaViewer = () => {
                         <div className="flex items-start justify-between mb-4 relative">
                             <span className="bg-gray-800 text-purple-400 text-xs px-2 py-1 rounded-md tracking-widest font-bold">
                                 {karma.skill_id || "global"}
+                                {karma.namespace && <span className="text-gray-400 font-normal"> / {karma.namespace}</span>}
+                            </span>
+                            <span className="flex items-center gap-2 text-gray-500 text-xs font-mono">
+                                {new Date(karma.created_at).toLocaleDateString()}
+                                <button
+                                    onClick={() => handleDelete(karma.id)}
+                                    title="Delete lesson"
+                                    className="text-gray-600 hover:text-red-400 transition"
+                                >
+                                    <Trash2 size={14} />
+                                </button>
                             </span>
-                            <span className="text-gray-500 text-xs font-mono">{new Date(karma.created_at).toLocaleDateString()}</span>
                         </div>
 
                         <div className="mb-4 text-gray-200 text-sm leading-relaxed flex-grow relative">
@@ -74,7 +205,7 @@ export const KarmaViewer = () => {
                         </div>
 
                         <div className="text-[10px] text-gray-600 truncate mt-2">
-                            Soul: {karma.soul_version_hash || "legacy"}
+                            Soul: {karma.soul_version_hash || (karma.job_id ? "legacy" : "manual")}
                         </div>
                     </div>
                 ))}
@@ -3,6 +3,8 @@ import { ProjectSummary } from './Warehouse';
 import { Play, Zap, Activity } from 'lucide-react';
 import { clsx } from 'clsx';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { DEFAULT_CORE_URL, useCoreUrl } from '../coreUrl';
 
 interface RemixLabProps {
     targetProject: ProjectSummary | null;
@@ -16,6 +18,28 @@ interface CoreHealthStatus {
     online: boolean;
 }
 
+interface TelemetryStatus {
+    active_job_id: string | null;
+}
+
+interface TelemetryLog {
+    level: string;
+    message: string;
+}
+
+interface FfmpegProgress {
+    operation: string;
+    percent: number | null;
+    eta_secs: number | null;
+    done: boolean;
+}
+
+function formatEta(secs: number): string {
+    const m = Math.floor(secs / 60);
+    const s = secs % 60;
+    return m > 0 ? `${m}m${s.toString().padStart(2, '0')}s` : `${s}s`;
+}
+
 export function RemixLab({ targetProject }: RemixLabProps) {
     const [styles, setStyles] = useState<string[]>([]);
     const [selectedStyle, setSelectedStyle] = useState<string>('');
@@ -25,53 +49,48 @@ export function RemixLab({ targetProject }: RemixLabProps) {
     const [systemLocked, setSystemLocked] = useState(false);
     const [coreOnline, setCoreOnline] = useState(true);
     const [logs, setLogs] = useState<string[]>([]);
+    const [ffmpeg, setFfmpeg] = useState<FfmpegProgress | null>(null);
+    const base = useCoreUrl() ?? DEFAULT_CORE_URL;
 
-    // Poll Core status via Tauri (Circuit Breaker)
+    // Core status via Tauri (Circuit Breaker) — initial value, then pushed transitions
     useEffect(() => {
-        const checkHealth = async () => {
-            try {
-                const status = await invoke<CoreHealthStatus>('get_core_status');
-                setCoreOnline(status.online);
-            } catch {
-                setCoreOnline(false);
-            }
-        };
-        checkHealth();
-        const interval = setInterval(checkHealth, 10000);
-        return () => clearInterval(interval);
+        invoke<CoreHealthStatus>('get_core_status')
+            .then((status) => setCoreOnline(status.online))
+            .catch(() => setCoreOnline(false));
+        const unlisten = listen<CoreHealthStatus>('core:online', (event) => setCoreOnline(event.payload.online));
+        return () => { unlisten.then((f) => f()); };
     }, []);
 
-    // WebSocket for real-time job status monitoring
+    // Live telemetry relayed by the Tauri subscriber (/ws/telemetry)
     useEffect(() => {
-        const ws = new WebSocket('ws://localhost:3000/ws');
+        const unlistenStatus = listen<TelemetryStatus>('telemetry:status', (event) => {
+            setSystemLocked(!!event.payload.active_job_id);
+        });
 
-        ws.onmessage = (event) => {
-            try {
-                const data = JSON.parse(event.data);
+        // Log Check for Completion
+        const unlistenLog = listen<TelemetryLog>('telemetry:log', (event) => {
+            const { message } = event.payload;
+            if (message.includes("Job Completed:") && message.includes(jobId || "NEVER_MATCH")) {
+                setIsProcessing(false);
+                setTimestamp(Date.now());
+                setLogs(prev => [`✅ Job Finished! Reloading preview...`, ...prev]);
+            }
+            if (message.includes("Job Failed:") && message.includes(jobId || "NEVER_MATCH")) {
+                setIsProcessing(false);
+                setLogs(prev => [`❌ Job Failed! Check server logs.`, ...prev]);
+            }
+        });
 
-                // Heartbeat Check — lock if active_actor is present
-                if (data.type === 'heartbeat') {
-                    setSystemLocked(!!data.active_actor);
-                }
+        // FFmpeg progress (final assembly etc.) so long encodes do not look hung
+        const unlistenFfmpeg = listen<FfmpegProgress>('telemetry:ffmpeg_progress', (event) => {
+            setFfmpeg(event.payload.done ? null : event.payload);
+        });
 
-                // Log Check for Completion
-                if (data.level && data.message) {
-                    if (data.message.includes("Job Completed:") && data.message.includes(jobId || "NEVER_MATCH")) {
-                        setIsProcessing(false);
-                        setTimestamp(Date.now());
-                        setLogs(prev => [`✅ Job Finished! Reloading preview...`, ...prev]);
-                    }
-                    if (data.message.includes("Job Failed:") && data.message.includes(jobId || "NEVER_MATCH")) {
-                        setIsProcessing(false);
-                        setLogs(prev => [`❌ Job Failed! Check server logs.`, ...prev]);
-                    }
-                }
-            } catch {
-                // Ignore parse errors
-            }
+        return () => {
+            unlistenStatus.then((f) => f());
+            unlistenLog.then((f) => f());
+            unlistenFfmpeg.then((f) => f());
         };
-
-        return () => ws.close();
     }, [jobId]);
 
     // Fetch Styles via Tauri
@@ -210,7 +229,7 @@ export function RemixLab({ targetProject }: RemixLabProps) {
                         {targetProject.thumbnail_url ? (
                             <video
                                 key={timestamp}
-                                src={`http://localhost:3000/assets/${targetProject.id}/final.mp4?t=${timestamp}`}
+                                src={`${base}/assets/${targetProject.id}/final.mp4?t=${timestamp}`}
                                 className="w-full h-full object-contain"
                                 controls
                                 autoPlay={false}
@@ -225,6 +244,20 @@ export function RemixLab({ targetProject }: RemixLabProps) {
                             <div className="absolute inset-0 bg-black/80 flex flex-col items-center justify-center backdrop-blur-sm z-10">
                                 <div className="w-16 h-16 border-4 border-sonar-green border-t-transparent rounded-full animate-spin mb-4"></div>
                                 <div className="text-sonar-green font-mono animate-pulse">RENDERING NEW VISUALS...</div>
+                                {ffmpeg && (
+                                    <div className="mt-4 w-64 font-mono text-xs text-gray-400">
+                                        <div className="flex justify-between mb-1">
+                                            <span>{ffmpeg.operation.toUpperCase()}</span>
+                                            <span>
+                                                {ffmpeg.percent !== null ? `${ffmpeg.percent.toFixed(0)}%` : '...'}
+                                                {ffmpeg.eta_secs !== null && ` · ETA ${formatEta(ffmpeg.eta_secs)}`}
+                                            </span>
+                                        </div>
+                                        <div className="h-1 bg-gray-800 rounded">
+                                            <div className="h-1 bg-sonar-green rounded transition-all" style={{ width: `${ffmpeg.percent ?? 0}%` }}></div>
+                                        </div>
+                                    </div>
+                                )}
                             </div>
                         )}
                     </div>
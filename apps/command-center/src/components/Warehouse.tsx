@@ -1,6 +1,7 @@
 import { useEffect, useState } from 'react';
 import { Play, RotateCw, FileVideo } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { DEFAULT_CORE_URL, useCoreUrl } from '../coreUrl';
 
 export interface ProjectSummary {
     id: string;
@@ -14,6 +15,7 @@ export function Warehouse({ onRemix }: { onRemix: (project: ProjectSummary) => v
     const [projects, setProjects] = useState<ProjectSummary[]>([]);
     const [loading, setLoading] = useState(true);
     const [error, setError] = useState<string | null>(null);
+    const base = useCoreUrl() ?? DEFAULT_CORE_URL;
 
     useEffect(() => {
         invoke<ProjectSummary[]>('get_projects')
@@ -75,7 +77,7 @@ export function Warehouse({ onRemix }: { onRemix: (project: ProjectSummary) => v
                             {project.thumbnail_url ? (
                                 project.thumbnail_url.endsWith('.mp4') ? (
                                     <video
-                                        src={`http://localhost:3000${project.thumbnail_url}`}
+                                        src={`${base}${project.thumbnail_url}`}
                                         className="w-full h-full object-cover opacity-60 group-hover:opacity-100 transition-opacity"
                                         muted
                                         loop
@@ -87,7 +89,7 @@ export function Warehouse({ onRemix }: { onRemix: (project: ProjectSummary) => v
                                     />
                                 ) : (
                                     <img
-                                        src={`http://localhost:3000${project.thumbnail_url}`}
+                                        src={`${base}${project.thumbnail_url}`}
                                         alt={project.title}
                                         className="w-full h-full object-cover opacity-60 group-hover:opacity-100 transition-opacity"
                                     />
@@ -0,0 +1,24 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+
+/** Used until (or if) the Tauri side cannot report the discovered Core URL */
+export const DEFAULT_CORE_URL = 'http://localhost:3000';
+
+let cached: Promise<string> | null = null;
+
+/** Core base URL discovered by Tauri (FACTORY_URL, or the runtime file written by Serve) */
+export function coreUrl(): Promise<string> {
+    if (!cached) {
+        cached = invoke<string>('get_core_url').catch(() => DEFAULT_CORE_URL);
+    }
+    return cached;
+}
+
+/** `null` until the URL is known */
+export function useCoreUrl(): string | null {
+    const [url, setUrl] = useState<string | null>(null);
+    useEffect(() => {
+        coreUrl().then(setUrl);
+    }, []);
+    return url;
+}
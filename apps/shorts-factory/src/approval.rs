@@ -0,0 +1,94 @@
+//! # Approval Gate — 人間による承認チェックポイント
+//!
+//! Orchestrator がチェックポイントで `CoreEvent::ApprovalRequest` を発行し、
+//! Watchtower のボタンから返る `ControlCommand::ApprovalResponse` を待つ。
+//! 応答が無いまま `timeout_secs` を過ぎたら `on_timeout` の方針で決着させる。
+
+use shared::config::{ApprovalConfig, ApprovalTimeoutPolicy};
+use shared::watchtower::CoreEvent;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub struct ApprovalGate {
+    event_tx: mpsc::Sender<CoreEvent>,
+    pending: Mutex<HashMap<Uuid, oneshot::Sender<bool>>>,
+}
+
+impl ApprovalGate {
+    pub fn new(event_tx: mpsc::Sender<CoreEvent>) -> Self {
+        Self { event_tx, pending: Mutex::new(HashMap::new()) }
+    }
+
+    /// 承認を求めて待機する。承認されたら true。
+    pub async fn request(&self, description: String, config: &ApprovalConfig) -> bool {
+        let transition_id = Uuid::new_v4();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(transition_id, tx);
+
+        let default = config.on_timeout == ApprovalTimeoutPolicy::Approve;
+        if self.event_tx.send(CoreEvent::ApprovalRequest { transition_id, description }).await.is_err() {
+            self.pending.lock().unwrap().remove(&transition_id);
+            warn!("⚠️ Approval: Watchtower channel closed. Applying timeout policy ({:?}).", config.on_timeout);
+            return default;
+        }
+        info!("⏸️ Approval: Waiting for {} (timeout {}s)", transition_id, config.timeout_secs);
+
+        let timeout = std::time::Duration::from_secs(config.timeout_secs);
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(approved)) => approved,
+            _ => {
+                self.pending.lock().unwrap().remove(&transition_id);
+                warn!("⌛ Approval: {} timed out. Applying policy ({:?}).", transition_id, config.on_timeout);
+                default
+            }
+        }
+    }
+
+    /// Watchtower からの応答を待機中のチェックポイントへ届ける。該当が無ければ false。
+    pub fn resolve(&self, transition_id: Uuid, approved: bool) -> bool {
+        match self.pending.lock().unwrap().remove(&transition_id) {
+            Some(tx) => tx.send(approved).is_ok(),
+            None => {
+                warn!("⚠️ Approval: Unknown or expired transition {}", transition_id);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(timeout_secs: u64, on_timeout: ApprovalTimeoutPolicy) -> ApprovalConfig {
+        ApprovalConfig { timeout_secs, on_timeout, ..ApprovalConfig::default() }
+    }
+
+    #[tokio::test]
+    async fn test_response_resolves_pending_request() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let gate = std::sync::Arc::new(ApprovalGate::new(tx));
+
+        let waiter = {
+            let gate = gate.clone();
+            tokio::spawn(async move { gate.request("script".into(), &config(60, ApprovalTimeoutPolicy::Approve)).await })
+        };
+        let Some(CoreEvent::ApprovalRequest { transition_id, .. }) = rx.recv().await else {
+            panic!("expected ApprovalRequest");
+        };
+        assert!(gate.resolve(transition_id, false));
+        assert!(!waiter.await.unwrap());
+        assert!(!gate.resolve(transition_id, true));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_applies_policy() {
+        let (tx, _rx) = mpsc::channel(4);
+        let gate = ApprovalGate::new(tx);
+        assert!(!gate.request("x".into(), &config(0, ApprovalTimeoutPolicy::Reject)).await);
+        assert!(gate.request("x".into(), &config(0, ApprovalTimeoutPolicy::Approve)).await);
+    }
+}
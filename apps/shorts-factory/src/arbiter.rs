@@ -2,22 +2,32 @@
 //! 
 //! Mac mini M4 Pro の VRAM 資源を管理し、複数の重負荷アクター（LLM, TTS, ImageGen）
 //! が同時に実行されるのを防ぐ「単一占有（Single-Tenant）」ポリシーを強制する。
-//! 加えて、FFmpeg による動画合成（Forge）の並列実行も制御する。
+//! 加えて、FFmpeg による動画合成（Forge）の並列実行と、同時に走らせるジョブの数 (`[worker] concurrency`) も制御する。
+//! `[admission]` が有効なら、ComfyUI レンダーに GPU を渡す前にマシン全体の負荷を確かめ、
+//! 閾値を超えている間は開始を保留してバックプレッシャーを Watchtower に知らせる。
 
+use shared::config::AdmissionConfig;
+use shared::health::{HealthMonitor, ResourceStatus};
+use shared::watchtower::CoreEvent;
 use std::sync::Arc;
-use tokio::sync::{Semaphore, SemaphorePermit};
-use tracing::info;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex, OwnedSemaphorePermit, Semaphore, SemaphorePermit};
+use tracing::{info, warn};
+
+/// CPU 使用率は前回の計測からの平均なので、判定の直前にこの間隔で計り直す
+const CPU_SAMPLE_WINDOW: Duration = Duration::from_secs(1);
 
 /// 資源のカテゴリ
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResourceCategory {
-    GPU,   // TTS, ComfyUI (排他、同時1)
+    Gpu,   // TTS, ComfyUI (排他、同時1)
     Forge, // FFmpeg (並列、同時2-3)
 }
 
 /// 資源の占有者
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResourceUser {
+    #[allow(dead_code)]
     Voicing,    // TTS
     Generating, // ComfyUI
     Forging,    // FFmpeg
@@ -33,11 +43,21 @@ impl std::fmt::Display for ResourceUser {
     }
 }
 
+/// レンダーの受け入れ制御に要るもの
+struct Admission {
+    config: AdmissionConfig,
+    health: Arc<Mutex<HealthMonitor>>,
+    events: mpsc::Sender<CoreEvent>,
+}
+
 /// 資源調停官
 #[derive(Clone)]
 pub struct ResourceArbiter {
     gpu_sem: Arc<Semaphore>,
     forge_sem: Arc<Semaphore>,
+    /// 同時に走るジョブの枠 (JobWorker と Watchtower の即時実行で共有)
+    job_sem: Arc<Semaphore>,
+    admission: Option<Arc<Admission>>,
 }
 
 impl ResourceArbiter {
@@ -47,15 +67,53 @@ impl ResourceArbiter {
             gpu_sem: Arc::new(Semaphore::new(1)),
             // Forge (FFmpeg) はCPU/メモリに余裕があれば並列可能
             forge_sem: Arc::new(Semaphore::new(2)),
+            // 既定は 1 件ずつ (工程を重ねるなら `with_job_slots` で広げる)
+            job_sem: Arc::new(Semaphore::new(1)),
+            admission: None,
+        }
+    }
+
+    /// 同時に走らせるジョブの数。GPU・Forge の排他はそのままなので、重なるのは企画や合成などの工程
+    pub fn with_job_slots(mut self, slots: usize) -> Self {
+        self.job_sem = Arc::new(Semaphore::new(slots.max(1)));
+        self
+    }
+
+    /// ジョブの枠を 1 つ取る。空きが無ければ None (待たない)
+    pub fn try_acquire_job(&self) -> Option<OwnedSemaphorePermit> {
+        self.job_sem.clone().try_acquire_owned().ok()
+    }
+
+    /// ジョブの枠が全部埋まっているか (`/api/status` の `busy`)
+    pub fn jobs_saturated(&self) -> bool {
+        self.job_sem.available_permits() == 0
+    }
+
+    /// ComfyUI レンダーの開始前にマシン全体の負荷を確かめる (`enabled = false` なら何もしない)
+    pub fn with_admission(mut self, config: AdmissionConfig, health: Arc<Mutex<HealthMonitor>>, events: mpsc::Sender<CoreEvent>) -> Self {
+        if config.enabled {
+            info!(
+                "🚦 ResourceArbiter: Admission control on (free RAM >= {} MB, VRAM < {:.0}%, CPU < {:.0}%)",
+                config.min_free_ram_mb,
+                config.max_vram_ratio * 100.0,
+                config.max_cpu_percent
+            );
+            self.admission = Some(Arc::new(Admission { config, health, events }));
         }
+        self
     }
 
     /// GPU資源を要求する。既に占有されている場合は待機する。
+    /// ComfyUI レンダーは負荷が閾値を下回るまで待ってから GPU を取りに行く
+    /// (待っている間も他のジョブの Voicing は GPU を使える)。
     pub async fn acquire_gpu(&self, user: ResourceUser) -> Result<ArbiterGuard<'_>, tokio::sync::AcquireError> {
         info!("⏳ ResourceArbiter: Requesting GPU access for {}...", user);
+        if let (ResourceUser::Generating, Some(admission)) = (user, &self.admission) {
+            admission.admit(user).await;
+        }
         let permit = self.gpu_sem.acquire().await?;
         info!("🔑 ResourceArbiter: GPU access GRANTED for {}", user);
-        Ok(ArbiterGuard { _permit: permit, category: ResourceCategory::GPU, user })
+        Ok(ArbiterGuard { _permit: permit, category: ResourceCategory::Gpu, user })
     }
 
     /// Forge (FFmpeg) 資源を要求する。
@@ -67,6 +125,67 @@ impl ResourceArbiter {
     }
 }
 
+impl Admission {
+    /// CPU の計測窓を取り直してから負荷を計る
+    async fn sample(&self) -> ResourceStatus {
+        self.health.lock().await.check().await;
+        tokio::time::sleep(CPU_SAMPLE_WINDOW).await;
+        self.health.lock().await.check().await
+    }
+
+    /// 負荷が閾値を下回るまで (または `max_wait_mins` まで) 待つ
+    async fn admit(&self, user: ResourceUser) {
+        let started = Instant::now();
+        let max_wait = Duration::from_secs(self.config.max_wait_mins * 60);
+        let mut deferred = false;
+        loop {
+            let status = self.sample().await;
+            let Some(reason) = admission_blocker(&status, &self.config) else {
+                if deferred {
+                    let waited_secs = started.elapsed().as_secs();
+                    info!("🚦 ResourceArbiter: Load back under the limits after {}s, admitting {}", waited_secs, user);
+                    let _ = self.events.send(CoreEvent::Backpressure { reason: "load back under the limits".to_string(), waited_secs, cleared: true }).await;
+                }
+                return;
+            };
+            if self.config.max_wait_mins > 0 && started.elapsed() >= max_wait {
+                let waited_secs = started.elapsed().as_secs();
+                warn!("🚦 ResourceArbiter: Still overloaded after {}s ({}), starting {} anyway", waited_secs, reason, user);
+                let _ = self.events.send(CoreEvent::Backpressure { reason: format!("{} — gave up waiting", reason), waited_secs, cleared: true }).await;
+                return;
+            }
+            if !deferred {
+                warn!("🚦 ResourceArbiter: Deferring {} ({})", user, reason);
+                let _ = self.events.send(CoreEvent::Backpressure { reason, waited_secs: 0, cleared: false }).await;
+                deferred = true;
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.check_interval_secs.max(1))).await;
+        }
+    }
+}
+
+/// 超えている閾値 (どれも超えていなければ None)
+pub fn admission_blocker(status: &ResourceStatus, config: &AdmissionConfig) -> Option<String> {
+    let mut reasons = Vec::new();
+    if config.min_free_ram_mb > 0 && status.system_total_mb > 0 && status.system_available_mb < config.min_free_ram_mb {
+        reasons.push(format!("free RAM {} MB < {} MB", status.system_available_mb, config.min_free_ram_mb));
+    }
+    if let Some(gpu) = status.gpu.as_ref().filter(|g| g.vram_total_mb > 0 && config.max_vram_ratio < 1.0) {
+        let ratio = gpu.vram_used_mb as f64 / gpu.vram_total_mb as f64;
+        if ratio >= config.max_vram_ratio {
+            reasons.push(format!("VRAM {:.0}% >= {:.0}%", ratio * 100.0, config.max_vram_ratio * 100.0));
+        }
+    }
+    if config.max_cpu_percent < 100.0 && status.system_cpu_percent >= config.max_cpu_percent {
+        reasons.push(format!("CPU {:.0}% >= {:.0}%", status.system_cpu_percent, config.max_cpu_percent));
+    }
+    if reasons.is_empty() {
+        None
+    } else {
+        Some(reasons.join(", "))
+    }
+}
+
 /// 資源の占有を解除するためのガード
 pub struct ArbiterGuard<'a> {
     _permit: SemaphorePermit<'a>,
@@ -79,3 +198,71 @@ impl<'a> Drop for ArbiterGuard<'a> {
         info!("🔓 ResourceArbiter: {:?} Access RELEASED for {}", self.category, self.user);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::health::GpuStatus;
+
+    fn status(available_mb: u64, cpu: f32, vram_used_mb: u64) -> ResourceStatus {
+        ResourceStatus {
+            memory_usage_mb: 512,
+            cpu_usage_percent: 10.0,
+            open_files: None,
+            system_available_mb: available_mb,
+            system_total_mb: 49152,
+            system_cpu_percent: cpu,
+            gpu: Some(GpuStatus { name: "Apple M4 Pro".into(), vram_used_mb, vram_total_mb: 49152, utilization_percent: 50.0 }),
+        }
+    }
+
+    #[test]
+    fn test_job_slots_cap_concurrent_jobs() {
+        let arbiter = ResourceArbiter::new().with_job_slots(2);
+        let first = arbiter.try_acquire_job().unwrap();
+        let _second = arbiter.try_acquire_job().unwrap();
+        assert!(arbiter.try_acquire_job().is_none());
+        assert!(arbiter.jobs_saturated());
+        drop(first);
+        assert!(arbiter.try_acquire_job().is_some());
+        assert!(ResourceArbiter::new().with_job_slots(0).try_acquire_job().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_deferred_render_does_not_hold_the_gpu() {
+        let (events, mut rx) = mpsc::channel(8);
+        // 空きメモリが足りることはないので、レンダーは待ち続ける
+        let config = AdmissionConfig { enabled: true, min_free_ram_mb: u64::MAX, max_wait_mins: 0, ..Default::default() };
+        let arbiter = Arc::new(ResourceArbiter::new().with_admission(config, Arc::new(Mutex::new(HealthMonitor::new())), events));
+        let render = tokio::spawn({
+            let arbiter = arbiter.clone();
+            async move {
+                let _gpu = arbiter.acquire_gpu(ResourceUser::Generating).await;
+            }
+        });
+        assert!(matches!(rx.recv().await, Some(CoreEvent::Backpressure { cleared: false, .. })));
+        let voicing = tokio::time::timeout(Duration::from_secs(1), arbiter.acquire_gpu(ResourceUser::Voicing)).await;
+        assert!(voicing.is_ok_and(|guard| guard.is_ok()));
+        render.abort();
+    }
+
+    #[test]
+    fn test_admission_blocker_names_every_exceeded_limit() {
+        let config = AdmissionConfig::default();
+        assert_eq!(admission_blocker(&status(16384, 40.0, 8192), &config), None);
+        assert_eq!(admission_blocker(&status(2048, 40.0, 8192), &config).as_deref(), Some("free RAM 2048 MB < 4096 MB"));
+        assert_eq!(
+            admission_blocker(&status(2048, 99.0, 47000), &config).as_deref(),
+            Some("free RAM 2048 MB < 4096 MB, VRAM 96% >= 90%, CPU 99% >= 95%")
+        );
+    }
+
+    #[test]
+    fn test_admission_blocker_skips_disabled_and_unmeasured_limits() {
+        let config = AdmissionConfig { min_free_ram_mb: 0, max_vram_ratio: 1.0, max_cpu_percent: 100.0, ..Default::default() };
+        assert_eq!(admission_blocker(&status(0, 100.0, 49152), &config), None);
+        // 計測できない項目 (総メモリ 0・GPU なし) では止めない
+        let unmeasured = ResourceStatus { system_total_mb: 0, gpu: None, ..status(0, 0.0, 0) };
+        assert_eq!(admission_blocker(&unmeasured, &AdmissionConfig::default()), None);
+    }
+}
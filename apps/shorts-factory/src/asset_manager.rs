@@ -1,8 +1,21 @@
 use std::path::PathBuf;
-use factory_core::contracts::ConceptResponse;
+use bastion::fs_guard::Jail;
+use factory_core::contracts::{ConceptResponse, OutputVideo, SceneSeed, VariantRecord};
 use factory_core::error::FactoryError;
 use tuning::StyleProfile;
-use serde::{Serialize, Deserialize};
+
+/// サムネイル段で生成されるファイル名
+const THUMBNAIL_FILE: &str = "thumbnail.jpg";
+/// Watchtower の完了通知に添付する縮小プレビュー
+const PREVIEW_FILE: &str = "discord_preview.mp4";
+
+/// 字幕を焼き込む前の映像 (`{lang}/` 内)。ミックス済み音声・SRT と合わせてプレビュー配信に使う
+pub const CLEAN_VIDEO_FILE: &str = "clean_video.mp4";
+/// 言語ディレクトリ内のミックス済み音声と字幕
+pub const FINAL_AUDIO_FILE: &str = "final_audio.wav";
+pub const SUBTITLES_FILE: &str = "subtitles.srt";
+/// 焼き込みとは別に納品し、投稿先にも添付する字幕トラック (`{lang}/` 内の WebVTT)
+pub const CAPTIONS_VTT_FILE: &str = "captions.vtt";
 
 /// 中間素材と最終成果物の管理、および永続化 (Remix Mode の基盤)
 pub struct AssetManager {
@@ -16,6 +29,7 @@ impl AssetManager {
 
     /// プロジェクトディレクトリを初期化
     pub fn init_project(&self, project_id: &str) -> Result<PathBuf, FactoryError> {
+        infrastructure::chaos::inject(infrastructure::chaos::Fault::DiskFull)?;
         let path = self.base_dir.join(project_id);
         std::fs::create_dir_all(&path).map_err(|e| FactoryError::Infrastructure {
             reason: format!("Failed to create project dir: {}", e),
@@ -28,6 +42,52 @@ impl AssetManager {
         Ok(path)
     }
 
+    /// 持ち込み素材 (ナレーション・映像) を `uploads/{stem}.{ext}` に保存し、プロジェクトからの相対パスを返す
+    pub fn save_upload(&self, project_id: &str, stem: &str, ext: &str, data: &[u8]) -> Result<String, FactoryError> {
+        let root = self.init_project(project_id)?;
+        let rel = format!("uploads/{}.{}", stem, ext);
+        let path = root.join(&rel);
+        std::fs::create_dir_all(root.join("uploads")).ok();
+        std::fs::write(&path, data).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write {}: {}", rel, e),
+        })?;
+        Ok(rel)
+    }
+
+    /// プロジェクト外のファイルを `uploads/{stem}.{拡張子}` にコピーする (取り込み済みならそのまま)
+    ///
+    /// 読み出しは `source_jail` を通す (檻の外・シンボリックリンクは拒否)。
+    pub fn import_upload(&self, project_id: &str, stem: &str, src: &std::path::Path, source_jail: &Jail) -> Result<String, FactoryError> {
+        let ext = src.extension().and_then(|e| e.to_str()).unwrap_or("mp4").to_lowercase();
+        let root = self.init_project(project_id)?;
+        let rel = format!("uploads/{}.{}", stem, ext);
+        let path = root.join(&rel);
+        if path.exists() {
+            return Ok(rel);
+        }
+        let missing = |e: std::io::Error| FactoryError::MediaNotFound { path: format!("{} ({})", src.display(), e) };
+        let mut source = source_jail.open_file(src).map_err(missing)?;
+        std::fs::create_dir_all(root.join("uploads")).ok();
+        let mut dest = std::fs::File::create(&path).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to create {}: {}", path.display(), e),
+        })?;
+        if let Err(e) = std::io::copy(&mut source, &mut dest) {
+            let _ = std::fs::remove_file(&path);
+            return Err(missing(e));
+        }
+        Ok(rel)
+    }
+
+    /// サムネイルの保存先 (`/assets/{project_id}/thumbnail.jpg` として配信される)
+    pub fn thumbnail_path(&self, project_id: &str) -> PathBuf {
+        self.base_dir.join(project_id).join(THUMBNAIL_FILE)
+    }
+
+    /// 完了通知用プレビューの書き出し先
+    pub fn preview_path(&self, project_id: &str) -> PathBuf {
+        self.base_dir.join(project_id).join(PREVIEW_FILE)
+    }
+
     /// コンセプトを保存
     pub fn save_concept(&self, project_id: &str, concept: &ConceptResponse) -> Result<(), FactoryError> {
         let path = self.base_dir.join(project_id).join("concept.json");
@@ -39,6 +99,121 @@ impl AssetManager {
         })
     }
 
+    /// 納品した動画の場所を記録する (最終成果物はワークスペース外へ移動するため)
+    pub fn save_outputs(&self, project_id: &str, outputs: &[OutputVideo]) -> Result<(), FactoryError> {
+        let path = self.base_dir.join(project_id).join("outputs.json");
+        let json = serde_json::to_string_pretty(outputs).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to serialize outputs: {}", e),
+        })?;
+        std::fs::write(path, json).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write outputs.json: {}", e),
+        })
+    }
+
+    /// バリエーション制作の記録を親プロジェクトに書き出す (1 本終わるごとに上書き)
+    pub fn save_variants(&self, project_id: &str, variants: &[VariantRecord]) -> Result<(), FactoryError> {
+        let path = self.base_dir.join(project_id).join("variants.json");
+        let json = serde_json::to_string_pretty(variants).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to serialize variants: {}", e),
+        })?;
+        std::fs::write(path, json).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write variants.json: {}", e),
+        })
+    }
+
+    /// シーンごとに使ったシードを書き出す (`--reuse-seeds` の再現用)
+    pub fn save_seeds(&self, project_id: &str, seeds: &[SceneSeed]) -> Result<(), FactoryError> {
+        let path = self.base_dir.join(project_id).join("seeds.json");
+        let json = serde_json::to_string_pretty(seeds).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to serialize seeds: {}", e),
+        })?;
+        std::fs::write(path, json).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write seeds.json: {}", e),
+        })
+    }
+
+    /// 記録されたシーンのシード (未生成・旧プロジェクトは空)
+    pub fn load_seeds(&self, project_id: &str) -> Vec<SceneSeed> {
+        std::fs::read_to_string(self.base_dir.join(project_id).join("seeds.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// 記録された納品物 (未納品・旧プロジェクトは空)
+    pub fn load_outputs(&self, project_id: &str) -> Vec<OutputVideo> {
+        std::fs::read_to_string(self.base_dir.join(project_id).join("outputs.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// プロジェクトが存在するか (ID はディレクトリ名として安全なものに限る)
+    pub fn project_exists(&self, project_id: &str) -> bool {
+        let safe = !project_id.is_empty()
+            && project_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        safe && self.base_dir.join(project_id).is_dir()
+    }
+
+    /// 言語別の作業ディレクトリ (`{project_id}/{lang}`)
+    pub fn lang_dir(&self, project_id: &str, lang: &str) -> PathBuf {
+        self.base_dir.join(project_id).join(lang)
+    }
+
+    /// 字幕トラックを保存する (`{project_id}/{lang}/captions.vtt`)
+    pub fn save_captions(&self, project_id: &str, lang: &str, vtt: &str) -> Result<PathBuf, FactoryError> {
+        let dir = self.lang_dir(project_id, lang);
+        std::fs::create_dir_all(&dir).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create {}: {}", dir.display(), e) })?;
+        let path = dir.join(CAPTIONS_VTT_FILE);
+        std::fs::write(&path, vtt).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write captions: {}", e) })?;
+        Ok(path)
+    }
+
+    /// 字幕トラックのある言語とそのパス (名前順)
+    pub fn caption_tracks(&self, project_id: &str) -> Vec<(String, PathBuf)> {
+        if !self.project_exists(project_id) {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(self.base_dir.join(project_id)) else { return Vec::new() };
+        let mut tracks: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter(|e| e.path().join(CAPTIONS_VTT_FILE).is_file())
+            .filter_map(|e| Some((e.file_name().into_string().ok()?, e.path().join(CAPTIONS_VTT_FILE))))
+            .collect();
+        tracks.sort();
+        tracks
+    }
+
+    /// 字幕なしマスター (映像とミックス済み音声) が揃っている言語 (名前順)
+    pub fn preview_langs(&self, project_id: &str) -> Vec<String> {
+        if !self.project_exists(project_id) {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(self.base_dir.join(project_id)) else { return Vec::new() };
+        let mut langs: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().join(CLEAN_VIDEO_FILE).is_file() && e.path().join(FINAL_AUDIO_FILE).is_file())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+        langs.sort();
+        langs
+    }
+
+    /// プロジェクトのディレクトリを丸ごと消す (存在しなければ何もしない)
+    pub fn remove_project(&self, project_id: &str) -> Result<(), FactoryError> {
+        if !self.project_exists(project_id) {
+            return Ok(());
+        }
+        std::fs::remove_dir_all(self.base_dir.join(project_id)).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to remove project {}: {}", project_id, e),
+        })
+    }
+
+    /// プロジェクトのタイトル (concept.json が無ければ ID)
+    pub fn project_title(&self, project_id: &str) -> String {
+        self.load_concept(project_id).map(|c| c.title).unwrap_or_else(|_| project_id.to_string())
+    }
+
     /// コンセプトを読み込み (自動マイグレーション対応)
     pub fn load_concept(&self, project_id: &str) -> Result<ConceptResponse, FactoryError> {
         let path = self.base_dir.join(project_id).join("concept.json");
@@ -90,6 +265,7 @@ impl AssetManager {
     }
 
     /// 最終的な実行パラメータをスナップショットとして保存
+    #[allow(dead_code)]
     pub fn save_metadata(&self, project_id: &str, style: &StyleProfile) -> Result<(), FactoryError> {
         let path = self.base_dir.join(project_id).join("metadata.json");
         let metadata = serde_json::json!({
@@ -132,6 +308,21 @@ impl AssetManager {
         projects
     }
 
+    /// 1 プロジェクトの詳細 (metadata.json が無くても、ディレクトリがあれば返す)
+    pub fn project_detail(&self, project_id: &str) -> Option<ProjectDetail> {
+        if !self.project_exists(project_id) {
+            return None;
+        }
+        let summary = self.read_project_summary(project_id).unwrap_or_else(|| ProjectSummary {
+            id: project_id.to_string(),
+            title: self.project_title(project_id),
+            style: None,
+            created_at: String::new(),
+            thumbnail_url: self.thumbnail_path(project_id).exists().then(|| format!("/assets/{}/{}", project_id, THUMBNAIL_FILE)),
+        });
+        Some(ProjectDetail { summary, outputs: self.load_outputs(project_id) })
+    }
+
     fn read_project_summary(&self, project_id: &str) -> Option<ProjectSummary> {
         let root = self.base_dir.join(project_id);
         
@@ -160,9 +351,11 @@ impl AssetManager {
             project_id.to_string()
         };
 
-        // Thumbnail (Priority: thumb.png > final_video.mp4 (handled by frontend) > default)
+        // Thumbnail (Priority: thumbnail.jpg > thumb.png > final_video.mp4 (handled by frontend) > default)
         // ここではAPIとしてアクセス可能なパス ("/assets/...") を返す
-        let thumb_path = if root.join("thumb.png").exists() {
+        let thumb_path = if root.join(THUMBNAIL_FILE).exists() {
+            Some(format!("/assets/{}/{}", project_id, THUMBNAIL_FILE))
+        } else if root.join("thumb.png").exists() {
             Some(format!("/assets/{}/thumb.png", project_id))
         } else if root.join("final.mp4").exists() {
             // フロントエンドで video タグの poster として使うか、動画そのものをサムネイル代わりにする
@@ -181,11 +374,4 @@ impl AssetManager {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectSummary {
-    pub id: String,
-    pub title: String,
-    pub style: Option<String>,
-    pub created_at: String,
-    pub thumbnail_url: Option<String>,
-}
+pub use factory_core::api::{ProjectDetail, ProjectSummary};
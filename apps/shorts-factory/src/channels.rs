@@ -0,0 +1,154 @@
+//! # Channel Registry — チャンネル (ブランド) 別ルーティング
+//!
+//! `[channels.<name>]` のプロファイルと、それぞれの SOUL 本文を起動時に解決して保持する。
+//! Orchestrator (スタイル・納品先)、JobWorker / Samsara / Oracle (魂)、Sentinel / Takedown (資格情報) が参照する。
+//! `[souls]` の名前付き SOUL プロファイルもここで読み込み、ジョブ単位の指定でチャンネルの SOUL を上書きする。
+
+use factory_core::error::FactoryError;
+use factory_core::traits::Publisher;
+use infrastructure::youtube_publisher::YouTubePublisher;
+use shared::config::{ChannelProfile, FactoryConfig, DEFAULT_CHANNEL};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// 解決済みチャンネル
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub name: String,
+    pub profile: ChannelProfile,
+    pub soul_md: String,
+}
+
+/// 名前付き SOUL プロファイル
+#[derive(Debug, Clone)]
+pub struct Soul {
+    pub name: String,
+    /// 読み込んだファイル (`soul_versions` の source)
+    pub path: String,
+    pub content: String,
+}
+
+pub struct ChannelRegistry {
+    channels: BTreeMap<String, Channel>,
+    souls: BTreeMap<String, Soul>,
+    /// `default` が設定に無く、フォールバックとして補われたか
+    implicit_default: bool,
+    /// `[publisher] dry_run`: 取り下げ操作を実行せずログに残すだけにする
+    publisher_dry_run: bool,
+    /// `[publisher] upload_captions`: 紐付けた動画に字幕トラックを添付する
+    upload_captions: bool,
+}
+
+impl ChannelRegistry {
+    /// 設定から全チャンネルを解決する (SOUL ファイルが無ければ `fallback_soul`)
+    pub fn load(config: &FactoryConfig, fallback_soul: &str) -> Self {
+        let mut names = config.channel_names();
+        let implicit_default = !config.channels.is_empty() && !config.channels.contains_key(DEFAULT_CHANNEL);
+        if implicit_default {
+            names.push(DEFAULT_CHANNEL.to_string());
+        }
+
+        let channels = names
+            .into_iter()
+            .map(|name| {
+                let profile = config.resolve_channel(&name);
+                let soul_md = std::fs::read_to_string(&profile.soul_file).unwrap_or_else(|_| {
+                    warn!("⚠️ Channel '{}': soul file '{}' not found. Using default soul.", name, profile.soul_file);
+                    fallback_soul.to_string()
+                });
+                (name.clone(), Channel { name, profile, soul_md })
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let souls = config
+            .souls
+            .iter()
+            .filter_map(|(name, path)| match std::fs::read_to_string(path) {
+                Ok(content) => Some((name.clone(), Soul { name: name.clone(), path: path.clone(), content })),
+                Err(e) => {
+                    warn!("⚠️ Soul profile '{}' could not be read from '{}': {}", name, path, e);
+                    None
+                }
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        info!("📺 Channels: {}", channels.keys().cloned().collect::<Vec<_>>().join(", "));
+        if !souls.is_empty() {
+            info!("🧬 Soul profiles: {}", souls.keys().cloned().collect::<Vec<_>>().join(", "));
+        }
+        Self { channels, souls, implicit_default, publisher_dry_run: config.publisher.dry_run, upload_captions: config.publisher.upload_captions }
+    }
+
+    /// チャンネルを取得する (未知の名前は既定チャンネル)
+    pub fn get(&self, name: &str) -> &Channel {
+        self.channels.get(name).unwrap_or_else(|| {
+            if !name.is_empty() {
+                warn!("⚠️ Unknown channel '{}', routing to '{}'", name, DEFAULT_CHANNEL);
+            }
+            &self.channels[DEFAULT_CHANNEL]
+        })
+    }
+
+    /// 名前付き SOUL を取得する (未登録なら None)
+    pub fn soul(&self, name: &str) -> Option<&Soul> {
+        let soul = self.souls.get(name);
+        if soul.is_none() {
+            warn!("⚠️ Unknown soul profile '{}', using the channel soul", name);
+        }
+        soul
+    }
+
+    /// ジョブに適用する SOUL 本文 (名前付き SOUL が指定されていればそれ、無ければチャンネルの SOUL)
+    pub fn soul_md(&self, channel: &str, soul: Option<&str>) -> &str {
+        soul.and_then(|name| self.soul(name))
+            .map(|s| s.content.as_str())
+            .unwrap_or(&self.get(channel).soul_md)
+    }
+
+    /// 使っている SOUL ファイルと本文 (パスで重複を除く。版の記録に使う)
+    pub fn soul_sources(&self) -> Vec<(&str, &str)> {
+        let mut sources = BTreeMap::new();
+        for channel in self.channels.values() {
+            sources.entry(channel.profile.soul_file.as_str()).or_insert(channel.soul_md.as_str());
+        }
+        for soul in self.souls.values() {
+            sources.entry(soul.path.as_str()).or_insert(soul.content.as_str());
+        }
+        sources.into_iter().collect()
+    }
+
+    /// SOUL ファイルのパスから今の本文を引く
+    pub fn soul_by_source(&self, source: &str) -> Option<&str> {
+        self.soul_sources().into_iter().find(|(path, _)| *path == source).map(|(_, content)| content)
+    }
+
+    /// チャンネルの資格情報で投稿先プラットフォームの Publisher を組み立てる
+    pub fn publisher(&self, channel: &str, platform: &str) -> Result<Box<dyn Publisher>, FactoryError> {
+        let profile = &self.get(channel).profile;
+        match platform.to_lowercase().as_str() {
+            "youtube" => Ok(Box::new(YouTubePublisher::new(profile.youtube_oauth_token.clone(), self.publisher_dry_run))),
+            other => Err(FactoryError::Infrastructure { reason: format!("Unsupported publisher platform: {}", other) }),
+        }
+    }
+
+    /// 取り下げ操作が dry-run か
+    pub fn publisher_dry_run(&self) -> bool {
+        self.publisher_dry_run
+    }
+
+    /// 動画の紐付け時に字幕トラックを添付するか
+    pub fn upload_captions(&self) -> bool {
+        self.upload_captions
+    }
+
+    /// Samsara の自律企画対象チャンネル
+    ///
+    /// チャンネル定義がある場合、フォールバック用に補った `default` は対象外とする。
+    pub fn samsara_channels(&self) -> Vec<&Channel> {
+        self.channels
+            .values()
+            .filter(|c| c.profile.samsara)
+            .filter(|c| !(self.implicit_default && c.name == DEFAULT_CHANNEL))
+            .collect()
+    }
+}
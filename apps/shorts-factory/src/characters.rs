@@ -0,0 +1,112 @@
+//! # Character Registry — 再登場キャラクターの見た目固定
+//!
+//! `[characters.<name>]` を起動時に解決して保持する。
+//! Orchestrator は企画 (タイトル・トピック・シーン描写) が名前か別名に触れたキャラクターを
+//! 全シーンの VideoRequest に載せ、ComfyBridge が LoRA / 参照画像 / 固定タグを注入する。
+
+use factory_core::contracts::CharacterRef;
+use shared::config::FactoryConfig;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// 解決済みキャラクター
+#[derive(Debug, Clone)]
+pub struct Character {
+    /// 一致判定に使う呼び名 (キー名 + 別名、小文字)
+    names: Vec<String>,
+    pub sheet: CharacterRef,
+}
+
+pub struct CharacterRegistry {
+    characters: BTreeMap<String, Character>,
+}
+
+impl CharacterRegistry {
+    /// 設定から全キャラクターを解決する (存在しない参照画像は警告して読み飛ばす)
+    pub fn load(config: &FactoryConfig) -> Self {
+        let characters = config
+            .characters
+            .iter()
+            .map(|(name, profile)| {
+                let reference = profile.reference_images.iter().find_map(|path| {
+                    match std::fs::canonicalize(path) {
+                        Ok(abs) => Some(abs.to_string_lossy().to_string()),
+                        Err(e) => {
+                            warn!("⚠️ Character '{}': reference image '{}' not found: {}", name, path, e);
+                            None
+                        }
+                    }
+                });
+                let names = std::iter::once(name)
+                    .chain(&profile.aliases)
+                    .map(|n| n.trim().to_lowercase())
+                    .filter(|n| !n.is_empty())
+                    .collect();
+                let sheet = CharacterRef {
+                    name: name.clone(),
+                    prompt: profile.prompt.clone(),
+                    lora: (!profile.lora.is_empty()).then(|| (profile.lora.clone(), profile.lora_strength)),
+                    reference_image: reference.map(|r| (r, profile.ipadapter_weight)),
+                };
+                (name.clone(), Character { names, sheet })
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        if !characters.is_empty() {
+            info!("🧍 Characters: {}", characters.keys().cloned().collect::<Vec<_>>().join(", "));
+        }
+        Self { characters }
+    }
+
+    /// 企画テキストのどれかで言及されたキャラクター (名前順)
+    pub fn cast_for(&self, texts: &[&str]) -> Vec<CharacterRef> {
+        let texts: Vec<String> = texts.iter().map(|t| t.to_lowercase()).collect();
+        self.characters
+            .values()
+            .filter(|c| c.names.iter().any(|n| texts.iter().any(|t| mentions(t, n))))
+            .map(|c| c.sheet.clone())
+            .collect()
+    }
+}
+
+/// `text` が `name` に触れているか (どちらも小文字化済み)。
+/// ASCII の名前は単語境界で判定し ("mika" が "mikan" に一致しないように)、それ以外は部分一致。
+fn mentions(text: &str, name: &str) -> bool {
+    if !name.is_ascii() {
+        return text.contains(name);
+    }
+    text.match_indices(name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::config::CharacterProfile;
+
+    #[test]
+    fn test_cast_matches_names_and_aliases_on_word_boundaries() {
+        let mut config = FactoryConfig::default();
+        config.characters.insert("mika".into(), CharacterProfile {
+            aliases: vec!["ミカ".into(), "Mika-chan".into()],
+            lora: "mika_v2.safetensors".into(),
+            reference_images: vec!["/nonexistent/mika.png".into()],
+            ..Default::default()
+        });
+        config.characters.insert("bot".into(), CharacterProfile::default());
+        let registry = CharacterRegistry::load(&config);
+
+        let cast = registry.cast_for(&["Mika explains black holes"]);
+        assert_eq!(cast.len(), 1);
+        assert_eq!(cast[0].lora, Some(("mika_v2.safetensors".to_string(), 0.8)));
+        assert!(cast[0].reference_image.is_none());
+
+        assert_eq!(registry.cast_for(&["今日のミカは宇宙へ"]).len(), 1);
+        assert_eq!(registry.cast_for(&["hello mika-chan!"]).len(), 1);
+        assert!(registry.cast_for(&["mikan orchard", "robots"]).is_empty());
+        assert_eq!(registry.cast_for(&["a bot, drawn by mika"]).len(), 2);
+    }
+}
@@ -1,20 +1,48 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
 use tracing::{info, warn, error};
-use factory_core::traits::{JobQueue, JobStatus, AgentAct};
-use factory_core::contracts::WorkflowRequest;
+use factory_core::traits::{JobQueue, AgentAct};
+use factory_core::contracts::{FootageInput, WorkflowRequest, WorkflowResponse, PRIMARY_ASPECT};
 use factory_core::error::FactoryError;
 use chrono::Utc;
 use infrastructure::job_queue::SqliteJobQueue;
+use infrastructure::media_forge::MediaForgeClient;
+use infrastructure::soul_history::soul_hash as compute_soul_hash;
+use infrastructure::watch_folder::WatchFolder;
 use crate::orchestrator::ProductionOrchestrator;
+use crate::channels::ChannelRegistry;
 use bastion::fs_guard::Jail;
+use crate::shutdown::Shutdown;
+use crate::stage_watchdog::StageWatchdog;
+use shared::config::{DiscordPreviewConfig, WatchdogConfig, WorkerConfig};
+use std::collections::BTreeMap;
+use shared::watchtower::CoreEvent;
+
+/// 一時的な失敗で同じジョブを実行する最大回数 (初回を含む)
+const MAX_TRANSIENT_ATTEMPTS: i64 = 3;
+/// 一時的な失敗後の再実行までの待ち (1 回目。以降は倍々)
+const RETRY_BASE_DELAY_SECS: i64 = 120;
+/// 停止要求後、実行中ジョブの完了を確かめる間隔
+const DRAIN_POLL: std::time::Duration = std::time::Duration::from_millis(500);
 
 pub struct JobWorker {
     job_queue: Arc<SqliteJobQueue>,
     orchestrator: Arc<ProductionOrchestrator>,
     jail: Arc<Jail>,
-    is_busy: Arc<Mutex<bool>>,
-    soul_md: String,
+    /// ワーカー番号 -> 実行中のジョブ ID (空ならアイドル)
+    in_flight: Arc<Mutex<BTreeMap<usize, String>>>,
+    /// ワーカーの数 (同時に走るジョブの上限。枠そのものは ResourceArbiter が持つ)
+    workers: usize,
+    /// 待ち行列を見に行く間隔
+    poll: std::time::Duration,
+    channels: Arc<ChannelRegistry>,
+    watchdog: WatchdogConfig,
+    /// 完了通知の送り先 (None なら通知しない)
+    events: Option<mpsc::Sender<CoreEvent>>,
+    preview: DiscordPreviewConfig,
+    /// 持ち込み映像の取り込み口 (None なら持ち込み映像付きのジョブは失敗させる)
+    watch_folder: Option<Arc<WatchFolder>>,
 }
 
 impl JobWorker {
@@ -22,111 +50,248 @@ impl JobWorker {
         job_queue: Arc<SqliteJobQueue>,
         orchestrator: Arc<ProductionOrchestrator>,
         jail: Arc<Jail>,
-        soul_md: String,
+        channels: Arc<ChannelRegistry>,
     ) -> Self {
         Self {
             job_queue,
             orchestrator,
             jail,
-            is_busy: Arc::new(Mutex::new(false)),
-            soul_md,
+            in_flight: Arc::new(Mutex::new(BTreeMap::new())),
+            workers: 1,
+            poll: std::time::Duration::from_secs(10),
+            channels,
+            watchdog: WatchdogConfig::default(),
+            events: None,
+            preview: DiscordPreviewConfig::default(),
+            watch_folder: None,
         }
     }
 
-    pub async fn start_loop(self: Arc<Self>) {
-        info!("🤖 JobWorker: Starting autonomous execution loop...");
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
+    /// 持ち込み映像を読む取り込み口 (`[watch_folder]`)
+    pub fn with_watch_folder(mut self, watch_folder: Option<Arc<WatchFolder>>) -> Self {
+        self.watch_folder = watch_folder;
+        self
+    }
+
+    /// 工程ごとの締め切りを差し替える
+    pub fn with_watchdog(mut self, watchdog: WatchdogConfig) -> Self {
+        self.watchdog = watchdog;
+        self
+    }
+
+    /// ワーカーの数と待ち行列を見る間隔 (`[worker]`)
+    pub fn with_pool(mut self, config: &WorkerConfig) -> Self {
+        self.workers = config.concurrency.max(1);
+        self.poll = std::time::Duration::from_secs(config.poll_secs.max(1));
+        self
+    }
+
+    /// 完了したジョブを Watchtower に通知する (`preview` に従って動画プレビューを添える)
+    pub fn with_notifications(mut self, events: mpsc::Sender<CoreEvent>, preview: DiscordPreviewConfig) -> Self {
+        self.events = Some(events);
+        self.preview = preview;
+        self
+    }
+
+    /// 停止要求が来るまでジョブを取り出し続け、来たら実行中のジョブを `drain_timeout` まで待って抜ける
+    pub async fn start_loop(self: Arc<Self>, shutdown: Shutdown, drain_timeout: std::time::Duration) {
+        info!("🤖 JobWorker: Starting autonomous execution loop with {} worker(s)...", self.workers);
+        let mut interval = tokio::time::interval(self.poll);
+        let mut paused = false;
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.wait() => break,
+            }
 
-            // 1. Check if busy
-            {
-                let busy = self.is_busy.lock().await;
-                if *busy {
-                    continue;
+            // 一時停止中は取り出さない (実行中のジョブはそのまま最後まで作る)
+            match self.job_queue.paused_since().await {
+                Ok(since) if since.is_some() != paused => {
+                    paused = since.is_some();
+                    if paused {
+                        info!("⏸️ JobWorker: Paused. Running jobs will finish; no new jobs are picked up.");
+                    } else {
+                        info!("▶️ JobWorker: Resumed.");
+                    }
                 }
+                Ok(_) => {}
+                Err(e) => warn!("⚠️ JobWorker: Failed to read the pause switch: {}", e),
+            }
+            if paused {
+                continue;
             }
 
-            // 2. Poll for next job
-            match self.job_queue.dequeue().await {
-                Ok(Some(job)) => {
-                    info!("🏗️ JobWorker: Dequeued Job {}: {}", job.id, job.topic);
-                    
-                    let worker = self.clone();
-                    tokio::spawn(async move {
-                        worker.process_job(job).await;
-                    });
-                }
-                Ok(None) => {
+            // 空いているワーカーの数だけ取り出す (Watchtower の即時実行が枠を使っていればその分は待つ)
+            while let Some(worker_id) = self.idle_worker().await {
+                let Some(slot) = self.orchestrator.arbiter.try_acquire_job() else { break };
+                match self.job_queue.dequeue().await {
+                    Ok(Some(job)) => {
+                        info!("🏗️ JobWorker[{}]: Dequeued Job {}: {}", worker_id, job.id, job.topic);
+
+                        // 取り出した時点で埋めておき、spawn 直後の停止要求でも取りこぼさない。
+                        // タスクが panic・中断しても枠はドロップで空く
+                        let busy = BusyWorker::take(&self.in_flight, worker_id, &job.id);
+                        let worker = self.clone();
+                        tokio::spawn(async move {
+                            worker.process_job(job, worker_id).await;
+                            drop(busy);
+                            drop(slot);
+                        });
+                    }
                     // No pending jobs
-                }
-                Err(e) => {
-                    error!("❌ JobWorker: Failed to dequeue job: {}", e);
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("❌ JobWorker: Failed to dequeue job: {}", e);
+                        break;
+                    }
                 }
             }
         }
+
+        info!("🤖 JobWorker: No longer accepting jobs.");
+        self.drain(drain_timeout).await;
     }
 
-    async fn process_job(&self, job: factory_core::traits::Job) {
-        // Set busy
-        {
-            let mut busy = self.is_busy.lock().await;
-            *busy = true;
-        }
+    /// ジョブを持っていない最小のワーカー番号
+    async fn idle_worker(&self) -> Option<usize> {
+        let in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        (0..self.workers).find(|id| !in_flight.contains_key(id))
+    }
 
+    async fn process_job(&self, job: factory_core::traits::Job, worker_id: usize) {
         let job_id = job.id.clone();
         let queue = self.job_queue.clone();
-        let soul_hash = compute_soul_hash(&self.soul_md);
+        let soul_hash = compute_soul_hash(self.channels.soul_md(&job.channel, job.soul.as_deref()));
 
         // 0. Start Heartbeat Pulse (The Life Support)
+        // ワーカーごとに自分のジョブの鼓動を打つ (止まったワーカーのジョブだけを Zombie Hunter が回収する)。
+        // 同じタスクで工程の遷移を job_stages に記録する (タイムライン表示用)
         let (hb_tx, mut hb_rx) = tokio::sync::oneshot::channel::<()>();
         let hb_job_id = job_id.clone();
         let hb_queue = queue.clone();
+        let hb_project_id = job_project_id(&job_id);
+        let mut stages = self.orchestrator.telemetry.as_ref().map(|hub| hub.subscribe_stage());
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
             loop {
+                let next_stage = async {
+                    match stages.as_mut() {
+                        Some(rx) => rx.recv().await.ok(),
+                        None => std::future::pending().await,
+                    }
+                };
                 tokio::select! {
                     _ = interval.tick() => {
                         if let Err(e) = hb_queue.heartbeat_pulse(&hb_job_id).await {
-                            error!("⚠️ JobWorker: Heartbeat Pulse Failed for {}: {}", hb_job_id, e);
+                            error!("⚠️ JobWorker[{}]: Heartbeat Pulse Failed for {}: {}", worker_id, hb_job_id, e);
+                        }
+                    }
+                    Some(event) = next_stage => {
+                        if let (Some(project), Some(stage)) = (event.project_id, event.stage) {
+                            if project == hb_project_id {
+                                if let Err(e) = hb_queue.begin_job_stage(&hb_job_id, &stage).await {
+                                    warn!("⚠️ JobWorker: Failed to record stage '{}' for {}: {}", stage, hb_job_id, e);
+                                }
+                            }
                         }
                     }
                     _ = &mut hb_rx => break,
                 }
             }
+            let _ = hb_queue.end_job_stages(&hb_job_id).await;
         });
 
+        // 連載エピソードなら通し番号と既刊リンクを引く (失敗しても単発として続行)
+        let episode = match queue.fetch_episode_context(&job_id).await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                warn!("⚠️ JobWorker: Failed to load series context for {}: {}", job_id, e);
+                None
+            }
+        };
+
+        // Watch folder から取り込んだ手動レンダーはプロジェクトの持ち込み映像にする
+        let footage = match job.footage.as_deref() {
+            Some(src) => match self.import_footage(&job.id, src) {
+                Ok(path) => Some(FootageInput { path, track_subject: true }),
+                Err(e) => {
+                    error!("🚨 JobWorker: Job {} lost its footage: {}", job_id, e);
+                    let _ = self.job_queue.fail_job(&job_id, &e.to_string()).await;
+                    self.finish(hb_tx);
+                    return;
+                }
+            },
+            None => None,
+        };
+
         // Map Job to WorkflowRequest
+        // プロジェクト ID をジョブ ID から決定的に導出し、続編ジョブが前編のコンセプトを参照できるようにする
         let req = WorkflowRequest {
-            category: "tech".to_string(), 
+            category: JOB_CATEGORY.to_string(),
             topic: job.topic.clone(),
-            remix_id: None,
+            remix_id: Some(job_project_id(&job.id)),
+            series_parent: job.depends_on.as_deref().map(job_project_id),
+            channel: job.channel.clone(),
+            soul: job.soul.clone(),
             skip_to_step: None,
             style_name: job.style.clone(),
             custom_style: None,
             target_langs: vec!["ja".to_string(), "en".to_string()],
+            episode,
+            footage,
+            hook_style: job.karma_directives.as_deref()
+                .and_then(|d| serde_json::from_str::<factory_core::contracts::KarmaDirectives>(d).ok())
+                .map(|d| d.hook_style)
+                .filter(|h| !h.is_empty()),
+            ..Default::default()
         };
 
-        match self.orchestrator.execute(req, &self.jail).await {
+        match self.execute_watched(req, &job_id).await {
             Ok(res) => {
                 info!("✅ JobWorker: Job {} completed successfully: {} videos generated", job_id, res.output_videos.len());
                 
                 // Store success log for Distillation
                 let success_log = format!(
-                    "SUCCESS_LOG: {}\nVideos: {:?}\nConcept: {}", 
+                    "SUCCESS_LOG: {}\nVideos: {:?}\nConcept: {}\nSubtitle CPS: {}\nAesthetic Scores: {}", 
                     Utc::now().to_rfc3339(), 
                     res.output_videos,
-                    res.concept.title
+                    res.concept.title,
+                    serde_json::to_string(&res.subtitle_stats).unwrap_or_default(),
+                    serde_json::to_string(&res.aesthetic_scores).unwrap_or_default()
                 );
                 let _ = self.job_queue.store_execution_log(&job_id, &success_log).await;
 
+                // 使い回し回避でずらしたシーンを来歴に追記する (/why で説明できるように)
+                if !res.similarity_nudges.is_empty() {
+                    let mut provenance = self.job_queue.fetch_provenance(&job_id).await.ok().flatten().unwrap_or_default();
+                    provenance.similarity_nudges.extend(res.similarity_nudges.iter().cloned());
+                    if let Err(e) = self.job_queue.store_provenance(&job_id, &provenance).await {
+                        warn!("⚠️ JobWorker: Failed to note similarity nudges for {}: {}", job_id, e);
+                    }
+                }
+
+                // 描き直しで済んだ安全性チェックの棄却も、次のプロンプト生成のために Technical karma として残す
+                // (名前空間は Orchestrator が実際に使ったスタイル)
+                let namespace = if res.concept.style_profile.is_empty() { job.style.as_str() } else { res.concept.style_profile.as_str() };
+                for rejection in &res.safety_rejections {
+                    let _ = self.job_queue.store_karma(&job_id, "comfy_bridge", Some(namespace), &safety_lesson(rejection), "Technical", &soul_hash).await;
+                }
+
+                if let Err(e) = self.job_queue.record_title_variants(&job_id, &res.concept.title_variants).await {
+                    warn!("⚠️ JobWorker: Failed to store title variants for {}: {}", job_id, e);
+                }
                 let output_json = serde_json::to_string(&res.output_videos).unwrap_or_default();
                 if let Err(e) = self.job_queue.complete_job(&job_id, Some(&output_json)).await {
                     error!("❌ JobWorker: Failed to mark job as completed: {}", e);
                 } else {
                     // Phase 12: The Agent Evolution (Technical Advancement)
                     let _ = self.job_queue.add_tech_exp(10).await;
+                    // 取り込み済みの手動レンダーはプロジェクト側に複製済みなので片付ける
+                    if let Some(src) = job.footage.as_deref() {
+                        let _ = std::fs::remove_file(src);
+                    }
+                    self.notify_completed(&job, &res);
                 }
             }
             Err(e) => {
@@ -136,6 +301,21 @@ impl JobWorker {
                 let error_detail = format!("FAILURE_LOG: {}\nError: {}", Utc::now().to_rfc3339(), e);
                 let _ = self.job_queue.store_execution_log(&job_id, &error_detail).await;
 
+                // --- Transient Failure: バックオフ後に同じジョブをもう一度 ---
+                if e.is_retryable() {
+                    match self.job_queue.defer_retry(&job_id, &e.to_string(), MAX_TRANSIENT_ATTEMPTS, RETRY_BASE_DELAY_SECS).await {
+                        Ok(Some((attempt, retry_at))) => {
+                            warn!("⏳ JobWorker: Transient failure on Job {} (attempt {}/{}). Retrying at {}", job_id, attempt, MAX_TRANSIENT_ATTEMPTS, retry_at);
+                            self.finish(hb_tx);
+                            return;
+                        }
+                        Ok(None) => warn!("💀 JobWorker: Job {} kept failing transiently ({} attempts). Giving up.", job_id, MAX_TRANSIENT_ATTEMPTS),
+                        Err(defer_err) => error!("❌ JobWorker: Failed to defer Job {}: {}", job_id, defer_err),
+                    }
+                } else {
+                    warn!("🧱 JobWorker: Permanent failure on Job {}. Not retrying.", job_id);
+                }
+
                 // --- Honorable Abort & Internal Karma Backpropagation ---
                 match e {
                     FactoryError::TtsFailure { reason } => {
@@ -146,31 +326,251 @@ impl JobWorker {
                             "WARNING: このコンセプトはTTSエンジンを破壊する可能性がありました。理由は: {}。今後はより純粋な日本語のみを使用してください。",
                             reason
                         );
-                        let _ = self.job_queue.store_karma(&job_id, "voicing_failure_system", &lesson, "failure", &soul_hash).await;
+                        let _ = self.job_queue.store_karma(&job_id, "voicing_failure_system", Some(&job.style), &lesson, "failure", &soul_hash).await;
+                    }
+                    FactoryError::UnsafeOutput { reason } => {
+                        warn!("🛡️ JobWorker: Job {} kept producing unsafe output. Failing without retry.", job_id);
+                        let lesson = format!("SAFETY_REJECT: 生成画像が安全性チェックを通りませんでした ({})。このテーマ・表現は避けるか、より穏当な描写にしてください。", reason);
+                        let _ = self.job_queue.store_karma(&job_id, "comfy_bridge", Some(&job.style), &lesson, "Technical", &soul_hash).await;
+                        let _ = self.job_queue.fail_job(&job_id, &format!("UNSAFE_OUTPUT: {}", reason)).await;
                     }
                     _ => {
                         let lesson = format!("SYSTEM_ALERT: ジョブが {} により失敗しました。", e);
-                        let _ = self.job_queue.store_karma(&job_id, "system_infrastructure", &lesson, "failure", &soul_hash).await;
+                        let _ = self.job_queue.store_karma(&job_id, "system_infrastructure", None, &lesson, "failure", &soul_hash).await;
                         let _ = self.job_queue.fail_job(&job_id, &e.to_string()).await;
                     }
                 }
             }
         }
 
+        self.finish(hb_tx);
+    }
+
+    /// 工程の切り替わりごとに Heartbeat を打ちながら実行し、締め切りを超えた工程はそこからやり直す
+    async fn execute_watched(&self, mut req: WorkflowRequest, job_id: &str) -> Result<WorkflowResponse, FactoryError> {
+        let hub = match &self.orchestrator.telemetry {
+            Some(hub) if self.watchdog.enabled => hub.clone(),
+            _ => return self.orchestrator.execute(req, &self.jail).await,
+        };
+        let project_id = req.remix_id.clone().unwrap_or_default();
+        let mut watchdog = StageWatchdog::new(&self.watchdog);
+
+        loop {
+            let mut stages = hub.subscribe_stage();
+            let mut run = self.orchestrator.execute(req.clone(), &self.jail);
+
+            let stalled = loop {
+                let deadline = watchdog.deadline();
+                let expired = async {
+                    match &deadline {
+                        Some((_, at)) => tokio::time::sleep_until((*at).into()).await,
+                        None => std::future::pending().await,
+                    }
+                };
+                tokio::select! {
+                    res = &mut run => return res,
+                    event = stages.recv() => {
+                        let Ok(event) = event else { continue };
+                        let (Some(project), Some(stage)) = (event.project_id, event.stage) else { continue };
+                        if project != project_id {
+                            continue;
+                        }
+                        watchdog.enter(&stage, std::time::Instant::now());
+                        if let Err(e) = self.job_queue.heartbeat_pulse(job_id).await {
+                            warn!("⚠️ JobWorker: Stage heartbeat failed for {}: {}", job_id, e);
+                        }
+                    }
+                    _ = expired => {
+                        if let Some((stage, _)) = deadline {
+                            break stage;
+                        }
+                    }
+                }
+            };
+
+            // 実行中の future を捨てて打ち切る (GPU ロック・工程表示はドロップで解放される)
+            drop(run);
+            if !watchdog.record_stall(&stalled) {
+                return Err(FactoryError::OperationalTimeout {
+                    reason: format!("Stage '{}' kept exceeding its deadline", stalled),
+                });
+            }
+            warn!("⏱️ JobWorker: Job {} stalled in stage '{}'. Retrying from that stage...", job_id, stalled);
+            req.skip_to_step = StageWatchdog::resume_step(&stalled);
+        }
+    }
+
+    /// 完了通知を送る。プレビューの再圧縮は次のジョブを待たせないよう裏で行う
+    fn notify_completed(&self, job: &factory_core::traits::Job, res: &WorkflowResponse) {
+        let Some(tx) = self.events.clone() else { return };
+        let primary = res.output_videos.iter()
+            .find(|v| v.aspect == PRIMARY_ASPECT)
+            .map(|v| std::path::PathBuf::from(&v.path))
+            .filter(|_| self.preview.enabled);
+        let preview_out = self.orchestrator.asset_manager.preview_path(&job_project_id(&job.id));
+        let forge = self.orchestrator.media_forge.clone();
+        let max_bytes = self.preview.max_mb * 1_048_576;
+        let (job_id, topic, style) = (job.id.clone(), job.topic.clone(), job.style.clone());
+        let result = format!("Completed: {} video(s)", res.output_videos.len());
+        tokio::spawn(async move {
+            let preview_path = match primary {
+                Some(video) => discord_preview(&forge, &video, max_bytes, &preview_out).await,
+                None => None,
+            };
+            let _ = tx.send(CoreEvent::TaskCompleted {
+                job_id,
+                result,
+                topic,
+                style,
+                thumbnail_url: None,
+                preview_path: preview_path.map(|p| p.to_string_lossy().to_string()),
+            }).await;
+        });
+    }
+
+    /// Watch folder で claim した手動レンダーを、取り込み口の檻を通してプロジェクトへ複製する
+    fn import_footage(&self, job_id: &str, src: &str) -> Result<String, FactoryError> {
+        let folder = self.watch_folder.as_ref().ok_or_else(|| FactoryError::Infrastructure {
+            reason: format!("Footage {} needs [watch_folder] to be enabled", src),
+        })?;
+        self.orchestrator.asset_manager.import_upload(&job_project_id(job_id), "footage", std::path::Path::new(src), folder.claimed_jail())
+    }
+
+    /// Heartbeat を止める (ワーカーの枠は `BusyWorker` のドロップで空く)
+    fn finish(&self, hb_tx: tokio::sync::oneshot::Sender<()>) {
         // Stop Heartbeat Pulse
         let _ = hb_tx.send(());
+    }
 
-        // Release busy
-        {
-            let mut busy = self.is_busy.lock().await;
-            *busy = false;
+    /// 実行中のジョブ ID の一覧
+    fn running_jobs(&self) -> Vec<String> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).values().cloned().collect()
+    }
+
+    /// 実行中のジョブが終わるのを待つ。間に合わなければ Pending に戻し (チェックポイント)、
+    /// 次回起動時に同じプロジェクトの続きから再開させる
+    async fn drain(&self, timeout: std::time::Duration) {
+        let running = self.running_jobs();
+        if running.is_empty() {
+            info!("🤖 JobWorker: Idle. Nothing to drain.");
+            return;
+        }
+        info!("⏳ JobWorker: Waiting up to {:?} for {} job(s) to finish: {}", timeout, running.len(), running.join(", "));
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        while tokio::time::Instant::now() < deadline {
+            if self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).is_empty() {
+                info!("✅ JobWorker: All jobs finished before shutdown.");
+                return;
+            }
+            tokio::time::sleep(DRAIN_POLL).await;
+        }
+
+        let unfinished = self.running_jobs();
+        for job_id in unfinished {
+            match self.job_queue.requeue_interrupted(&job_id, "Interrupted by shutdown; resumes on next start").await {
+                Ok(true) => warn!("📌 JobWorker: Job {} did not finish in time. Requeued for the next start.", job_id),
+                Ok(false) => info!("🤖 JobWorker: Job {} settled while draining.", job_id),
+                Err(e) => error!("❌ JobWorker: Failed to requeue Job {}: {}", job_id, e),
+            }
         }
     }
 }
 
-fn compute_soul_hash(soul_content: &str) -> String {
-    use std::hash::{Hash, Hasher};
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    soul_content.hash(&mut hasher);
-    format!("{:16x}", hasher.finish())
+/// ワーカーがジョブを持っている間の印。ドロップで `in_flight` から外れる
+struct BusyWorker {
+    in_flight: Arc<Mutex<BTreeMap<usize, String>>>,
+    worker_id: usize,
+}
+
+impl BusyWorker {
+    fn take(in_flight: &Arc<Mutex<BTreeMap<usize, String>>>, worker_id: usize, job_id: &str) -> Self {
+        in_flight.lock().unwrap_or_else(|e| e.into_inner()).insert(worker_id, job_id.to_string());
+        Self { in_flight: in_flight.clone(), worker_id }
+    }
+}
+
+impl Drop for BusyWorker {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.worker_id);
+    }
+}
+
+/// キューのジョブを実行するときのカテゴリ (ジョブ自体には保存されない)
+pub(crate) const JOB_CATEGORY: &str = "tech";
+
+/// ジョブに対応するプロジェクトディレクトリ名
+pub(crate) fn job_project_id(job_id: &str) -> String {
+    format!("job_{}", job_id)
+}
+
+/// プロジェクトディレクトリ名からジョブ ID を引く (ジョブ由来でなければ None)
+pub(crate) fn job_id_for_project(project_id: &str) -> Option<&str> {
+    project_id.strip_prefix("job_").filter(|id| !id.is_empty())
+}
+
+/// 新規プロジェクト名 (`{category}_{YYYYmmdd}_{HHMMSS}`) からカテゴリを引く (その形でなければ None)
+pub(crate) fn project_category(project_id: &str) -> Option<&str> {
+    let mut parts = project_id.rsplitn(3, '_');
+    let time = parts.next()?;
+    let date = parts.next()?;
+    let category = parts.next().filter(|c| !c.is_empty())?;
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    (digits(date, 8) && digits(time, 6)).then_some(category)
+}
+
+/// 完了通知に添付する動画 (絶対パス)。`max_bytes` 以下なら本編そのもの、超えるなら縮小したプレビュー
+async fn discord_preview(forge: &MediaForgeClient, video: &std::path::Path, max_bytes: u64, out: &std::path::Path) -> Option<std::path::PathBuf> {
+    let size = std::fs::metadata(video).ok()?.len();
+    let path = if size <= max_bytes {
+        video.to_path_buf()
+    } else {
+        match forge.render_preview(video, max_bytes, out).await {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("⚠️ JobWorker: No Discord preview for {}: {}", video.display(), e);
+                return None;
+            }
+        }
+    };
+    // Watchtower は別プロセスなので作業ディレクトリに依らないパスで渡す
+    std::fs::canonicalize(path).ok()
+}
+
+/// 安全性チェックで棄却した出力から karma の教訓を作る
+fn safety_lesson(rejection: &factory_core::contracts::SafetyRejection) -> String {
+    format!(
+        "SAFETY_REJECT: ワークフロー '{}' の出力が不適切と判定され描き直しました (score {:.2}, {})。プロンプト: {}",
+        rejection.workflow_id,
+        rejection.score,
+        if rejection.labels.is_empty() { "unlabeled".to_string() } else { rejection.labels.join(", ") },
+        rejection.prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_category_reads_new_project_names() {
+        assert_eq!(project_category("news_jp_20261016_070738"), Some("news_jp"));
+        assert_eq!(project_category("job_3f2a"), None);
+        assert_eq!(project_category("_20261016_070738"), None);
+        assert_eq!(project_category("tech_2026_0707"), None);
+    }
+
+    #[tokio::test]
+    async fn test_busy_worker_is_released_when_the_task_panics() {
+        let in_flight = Arc::new(Mutex::new(BTreeMap::new()));
+        let busy = BusyWorker::take(&in_flight, 1, "job-1");
+        assert_eq!(in_flight.lock().unwrap().get(&1).map(String::as_str), Some("job-1"));
+
+        let task = tokio::spawn(async move {
+            let _busy = busy;
+            panic!("boom");
+        });
+        assert!(task.await.is_err());
+        assert!(in_flight.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,255 @@
+//! # Journal — クラッシュ調査用のイベント記録
+//!
+//! LogDrain が Watchtower へ送るログ行と Heartbeat を、ディスク上の追記専用ファイルにも書く。
+//! コアが落ちると mpsc に積まれたままの CoreEvent や直近のテレメトリは消えるが、ここには残る。
+//! 1 ファイルが上限を超えたら `.1` に回し、2 世代だけ持つ (容量は上限の 2 倍で頭打ち)。
+//! 書き込みは専用スレッドが行い、呼び出し側 (tracing の Layer) は行をキューに積むだけで待たない。
+//! `shorts-factory postmortem 15m` が最後の記録から遡って、落ちる直前の数分を再構成する。
+
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use shared::watchtower::CoreEvent;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, SyncSender};
+
+use crate::server::calendar::parse_schedule_time;
+
+/// 記録ファイル名 (回した旧世代は `events.jsonl.1`)
+pub const JOURNAL_FILE: &str = "events.jsonl";
+/// 1 世代の上限
+const MAX_SEGMENT_BYTES: u64 = 8 * 1024 * 1024;
+/// 書き込みスレッドに積める行数 (溢れた行は捨てる)
+const QUEUE_LINES: usize = 4096;
+/// postmortem で 1 行に出すイベント本文の上限 (文字数)
+const MAX_SUMMARY_CHARS: usize = 300;
+
+/// 1 行分の記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub at: DateTime<Utc>,
+    /// 再起動の境目を見分けるためのプロセス ID
+    pub pid: u32,
+    pub event: CoreEvent,
+}
+
+struct Segment {
+    file: File,
+    len: u64,
+}
+
+enum JournalMessage {
+    Line(String),
+    /// ここまでに積んだ行が書き終わったら知らせる
+    #[cfg(test)]
+    Flush(SyncSender<()>),
+}
+
+pub struct EventJournal {
+    tx: SyncSender<JournalMessage>,
+}
+
+/// 書き込みスレッドが持つ記録ファイル
+struct JournalWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    segment: Segment,
+}
+
+impl JournalWriter {
+    fn write(&mut self, line: &str) {
+        if self.segment.len > 0 && self.segment.len + line.len() as u64 > self.max_bytes {
+            if std::fs::rename(&self.path, rotated(&self.path)).is_err() {
+                return;
+            }
+            match open_segment(&self.path) {
+                Ok(fresh) => self.segment = fresh,
+                Err(_) => return,
+            }
+        }
+        // 1 行ずつ書き切る (BufWriter だとクラッシュ時に最後の数行を失う)
+        if self.segment.file.write_all(line.as_bytes()).is_ok() {
+            self.segment.len += line.len() as u64;
+        }
+    }
+}
+
+fn rotated(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+fn open_segment(path: &Path) -> std::io::Result<Segment> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok(Segment { file, len })
+}
+
+impl EventJournal {
+    /// `dir` に記録ファイルを開く (無ければ作る)
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        Self::with_max_bytes(dir, MAX_SEGMENT_BYTES)
+    }
+
+    fn with_max_bytes(dir: &Path, max_bytes: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(JOURNAL_FILE);
+        let segment = open_segment(&path)?;
+        let mut writer = JournalWriter { path, max_bytes, segment };
+        let (tx, rx) = sync_channel(QUEUE_LINES);
+        std::thread::Builder::new().name("event-journal".to_string()).spawn(move || {
+            for message in rx {
+                match message {
+                    JournalMessage::Line(line) => writer.write(&line),
+                    #[cfg(test)]
+                    JournalMessage::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        })?;
+        Ok(Self { tx })
+    }
+
+    /// 1 件追記する。tracing の Layer から呼ばれるため、失敗・キュー溢れはログを出さずに捨てる
+    pub fn record(&self, event: &CoreEvent) {
+        let record = JournalRecord { at: Utc::now(), pid: std::process::id(), event: event.clone() };
+        let Ok(mut line) = serde_json::to_string(&record) else { return };
+        line.push('\n');
+        let _ = self.tx.try_send(JournalMessage::Line(line));
+    }
+
+    /// 積んだ行が書き終わるまで待つ
+    #[cfg(test)]
+    fn flush(&self) {
+        let (done_tx, done_rx) = sync_channel(0);
+        if self.tx.send(JournalMessage::Flush(done_tx)).is_ok() {
+            let _ = done_rx.recv();
+        }
+    }
+}
+
+/// `dir` の記録を古い順に読む。クラッシュで途切れた最終行など、読めない行は飛ばす
+pub fn read_journal(dir: &Path) -> Vec<JournalRecord> {
+    let path = dir.join(JOURNAL_FILE);
+    [rotated(&path), path]
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|text| text.lines().filter_map(|l| serde_json::from_str::<JournalRecord>(l).ok()).collect::<Vec<_>>())
+        .collect()
+}
+
+/// `15m` / `2h` / `90s` は最後の記録 (`last`) から遡った範囲、`<開始>..<終了>` は時刻指定 (終了は省略可)
+pub fn parse_range(input: &str, last: DateTime<Utc>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let s = input.trim();
+    if let Some((from, to)) = s.split_once("..") {
+        let from = parse_schedule_time(from)?;
+        let to = if to.trim().is_empty() { last } else { parse_schedule_time(to)? };
+        if from > to {
+            return Err(format!("'{}' ends before it starts", s));
+        }
+        return Ok((from, to));
+    }
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let amount: i64 = s[..split].parse().map_err(|_| format!("Invalid time range '{}' (expected e.g. 15m, 2h or 2025-01-03T07:00..2025-01-03T07:30)", s))?;
+    let span = match &s[split..] {
+        "s" => Duration::seconds(amount),
+        "m" | "" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        unit => return Err(format!("Unknown unit '{}' in '{}' (use s, m or h)", unit, s)),
+    };
+    Ok((last - span, last))
+}
+
+fn summarize(event: &CoreEvent) -> String {
+    let text = match event {
+        CoreEvent::Log(entry) => format!("{:<5} {}: {}", entry.level, entry.target, entry.message),
+        CoreEvent::Heartbeat(status) => format!(
+            "♥ cpu {:.1}% mem {}MB job {}",
+            status.cpu_usage,
+            status.memory_used_mb,
+            status.active_job_id.as_deref().unwrap_or("-")
+        ),
+        other => format!("{:?}", other),
+    };
+    match text.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+/// 範囲内の記録を時系列の報告にする。プロセスが入れ替わった所 (再起動) には区切りを入れる
+pub fn render_postmortem(records: &[JournalRecord], from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    let fmt = |t: DateTime<Utc>| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string();
+    let in_range: Vec<&JournalRecord> = records.iter().filter(|r| r.at >= from && r.at <= to).collect();
+    let mut out = format!("🩻 Post-mortem {} → {} ({} event(s))\n", fmt(from), fmt(to), in_range.len());
+    let mut pid = None;
+    for record in &in_range {
+        if pid != Some(record.pid) {
+            out.push_str(&format!("── process {} ──\n", record.pid));
+            pid = Some(record.pid);
+        }
+        out.push_str(&format!("{} {}\n", fmt(record.at), summarize(&record.event)));
+    }
+    let last_job = in_range.iter().rev().find_map(|r| match &r.event {
+        CoreEvent::Heartbeat(status) => Some(status.active_job_id.clone()),
+        _ => None,
+    });
+    if let Some(job) = last_job {
+        out.push_str(&format!("Last heartbeat: {}\n", job.map(|j| format!("working on job {}", j)).unwrap_or_else(|| "idle".to_string())));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::watchtower::{LogEntry, SystemStatus};
+
+    fn log(message: &str) -> CoreEvent {
+        CoreEvent::Log(LogEntry { level: "ERROR".into(), target: "job_worker".into(), message: message.into(), timestamp: String::new() })
+    }
+
+    #[test]
+    fn test_journal_rotates_and_reads_both_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = EventJournal::with_max_bytes(dir.path(), 400).unwrap();
+        for i in 0..6 {
+            journal.record(&log(&format!("render failed #{}", i)));
+        }
+        journal.record(&CoreEvent::Heartbeat(SystemStatus { cpu_usage: 91.0, memory_used_mb: 15000, vram_used_mb: 0, active_job_id: Some("job_7".into()), ..Default::default() }));
+        journal.flush();
+        assert!(rotated(&dir.path().join(JOURNAL_FILE)).is_file());
+        assert!(std::fs::metadata(dir.path().join(JOURNAL_FILE)).unwrap().len() <= 400);
+
+        // クラッシュで途切れた行は読み飛ばす
+        let mut file = OpenOptions::new().append(true).open(dir.path().join(JOURNAL_FILE)).unwrap();
+        file.write_all(b"{\"at\":\"2025-").unwrap();
+        let records = read_journal(dir.path());
+        assert!(records.len() >= 2 && records.len() < 7);
+        assert!(matches!(records.last().unwrap().event, CoreEvent::Heartbeat(_)));
+
+        let last = records.last().unwrap().at;
+        let (from, to) = parse_range("10m", last).unwrap();
+        let report = render_postmortem(&records, from, to);
+        assert!(report.contains("render failed #5"));
+        assert!(report.contains("♥ cpu 91.0% mem 15000MB job job_7"));
+        assert!(report.contains("Last heartbeat: working on job job_7"));
+        assert!(report.contains(&format!("── process {} ──", std::process::id())));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let last = DateTime::parse_from_rfc3339("2025-01-03T07:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_range("90s", last).unwrap().0, last - Duration::seconds(90));
+        assert_eq!(parse_range("2h", last).unwrap().0, last - Duration::hours(2));
+        assert_eq!(parse_range("15", last).unwrap().0, last - Duration::minutes(15));
+        let (from, to) = parse_range("2025-01-03T07:00:00Z..", last).unwrap();
+        assert_eq!((to - from).num_minutes(), 30);
+        assert!(parse_range("2025-01-03T08:00:00Z..2025-01-03T07:00:00Z", last).is_err());
+        assert!(parse_range("5d", last).is_err());
+        assert!(parse_range("soon", last).is_err());
+    }
+}
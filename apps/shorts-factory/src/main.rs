@@ -1,8 +1,12 @@
-use shared::config::FactoryConfig;
+use shared::config::{FactoryConfig, DEFAULT_CHANNEL};
 use shared::security::SecurityPolicy;
 use infrastructure::comfy_bridge::ComfyBridgeClient;
-use infrastructure::trend_sonar::BraveTrendSonar;
+use infrastructure::safety_classifier::SafetyClassifier;
+use infrastructure::trend_sources::CompositeTrendSource;
 use infrastructure::media_forge::MediaForgeClient;
+use infrastructure::video_encoder::EncoderProfile;
+use infrastructure::watch_folder::WatchFolder;
+use infrastructure::script_template::ScriptTemplates;
 use bastion::fs_guard::Jail;
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,6 +18,17 @@ mod asset_manager;
 mod server;
 mod simulator;
 mod job_worker;
+mod subtitle_qa;
+mod channels;
+mod characters;
+mod approval;
+mod shutdown;
+mod stage_watchdog;
+mod self_test;
+mod variants;
+mod journal;
+mod remote;
+mod soul_review;
 use job_worker::JobWorker;
 use server::telemetry::TelemetryHub;
 use server::router::{create_router, AppState};
@@ -28,11 +43,12 @@ use shared::health::HealthMonitor;
 use tokio::signal;
 use tracing::{info, error, warn};
 use tokio::sync::Mutex;
-use sidecar::SidecarManager;
+use sidecar::{SidecarManager, SidecarEvent, SupervisionPolicy};
+use factory_core::api::RuntimeEndpoints;
 use std::process::Command;
 
 use clap::Parser;
-use tuning::StyleManager;
+use tuning::{FontRegistry, StyleManager};
 use asset_manager::AssetManager;
 
 #[derive(Parser, Debug)]
@@ -40,6 +56,23 @@ use asset_manager::AssetManager;
 struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// ローカルで実行せず、稼働中の Serve (例: http://factory.local:3000) に API で依頼する
+    #[arg(long, global = true)]
+    remote: Option<String>,
+}
+
+/// サブコマンド省略時の動画生成
+fn default_command() -> Commands {
+    Commands::Generate {
+        category: "tech".to_string(),
+        topic: "AIの未来".to_string(),
+        remix: None,
+        step: None,
+        template: None,
+        variants: None,
+        seed: None,
+        reuse_seeds: false,
+    }
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -61,6 +94,22 @@ enum Commands {
         /// スキップ先のステップ (voice, visual)
         #[arg(short, long)]
         step: Option<String>,
+
+        /// 台本を Gemini でなく templates.toml の定型テンプレートで組む
+        #[arg(long)]
+        template: Option<String>,
+
+        /// 台本は 1 回だけ作り、カメラワーク・話速・シードを変えた N 本を作る (最大 6)
+        #[arg(long)]
+        variants: Option<usize>,
+
+        /// シーンのシードの元を固定する (既定はジョブ ID / プロジェクト ID から導く)
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// --remix 時、前回記録したシード (seeds.json) でシーンを描き直し、同じ絵を再現する
+        #[arg(long, requires = "remix")]
+        reuse_seeds: bool,
     },
     /// 指令センター用サーバーモード (Port: 3000)
     Serve {
@@ -78,11 +127,233 @@ enum Commands {
         /// SNS側の動画ID
         #[arg(short, long)]
         video_id: String,
+        /// 公開したタイトル案 (A / B / C)。省略時は A 案
+        #[arg(long)]
+        variant: Option<String>,
     },
     /// 進化の妥当性検証シミュレーター (Phase 11 Step 4)
-    SimulateEvolution,
+    SimulateEvolution {
+        /// シナリオファイル (TOML、複数可)。結果は workspace/simulations/ に JSON で残る。
+        /// 省略時は組み込みの 3 シナリオで Oracle の判定を確かめる
+        #[arg(long)]
+        scenario: Vec<String>,
+        /// 比較する以前のレポート (同じシナリオ名の結果と比べ、悪化していれば失敗で終わる)
+        #[arg(long)]
+        baseline: Option<String>,
+    },
     /// 今すぐ Samsara プロトコル（合成・エンキュー）を実行する
     SamsaraNow,
+    /// 指定時刻まで実行されないジョブを予約する (Serve 中のワーカーが時刻になったら処理する)
+    Schedule {
+        /// 1 本目の実行時刻 ("2025-01-03T07:00" はローカル時刻、オフセット付きも可)
+        #[arg(long)]
+        at: String,
+        /// トピック (シリーズ指定時はトピック書式に差し込まれる)
+        #[arg(short, long)]
+        topic: Option<String>,
+        /// 次話として積むシリーズ名または ID
+        #[arg(long)]
+        series: Option<String>,
+        #[arg(long, default_value = "")]
+        style: String,
+        #[arg(long, default_value = DEFAULT_CHANNEL)]
+        channel: String,
+        /// SOUL プロファイル名 ([souls] のキー)
+        #[arg(long)]
+        soul: Option<String>,
+        /// 予約する本数 (例: 7 で 1 週間分)
+        #[arg(long, default_value = "1")]
+        count: u32,
+        /// 2 本目以降の間隔 (時間)
+        #[arg(long, default_value = "24")]
+        every_hours: i64,
+    },
+    /// 連載シリーズを作成する
+    SeriesCreate {
+        /// シリーズ名 (例: "AI News Weekly")
+        name: String,
+        /// エピソードのトピック書式 ({n}, {date}, {topic})
+        #[arg(long, default_value = "")]
+        topic_template: String,
+        /// タイトル書式 ({series}, {n}, {title})。省略時は "{series} #{n}: {title}"
+        #[arg(long, default_value = "")]
+        title_template: String,
+        /// 全話で使うスタイル (省略時は投入ごとに決める)
+        #[arg(long, default_value = "")]
+        style: String,
+        #[arg(long, default_value = DEFAULT_CHANNEL)]
+        channel: String,
+        /// SOUL プロファイル名 ([souls] のキー)
+        #[arg(long)]
+        soul: Option<String>,
+    },
+    /// 連載シリーズの一覧
+    SeriesList,
+    /// シリーズの次話をキューに積む (Serve 中のワーカーが処理する)
+    SeriesEpisode {
+        /// シリーズ名または ID
+        name: String,
+        /// 今回の題材
+        #[arg(short, long)]
+        topic: Option<String>,
+    },
+    /// 直近のジョブ一覧 (--failed で Dead-letter キュー)
+    Jobs {
+        /// 失敗ジョブだけを表示する
+        #[arg(long)]
+        failed: bool,
+    },
+    /// 直近の教訓 (Karma) を表示する。export / import でマシン間を移す
+    Karma {
+        /// 表示する件数
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+        #[command(subcommand)]
+        action: Option<KarmaAction>,
+    },
+    /// SOUL の版を管理する (履歴・差分・旧版の教訓の見直し)
+    Soul {
+        #[command(subcommand)]
+        action: SoulAction,
+    },
+    /// 使えるスタイル名の一覧
+    Styles,
+    /// 待機中 (予約を含む) のジョブを取り消す。待機中の子孫ジョブも失敗扱いになる
+    Cancel {
+        /// 取り消すジョブの ID
+        job_id: String,
+    },
+    /// resources/workflows の全ワークフローを構造チェックする (タイトル付きノード・ノード参照。ComfyUI 不要)
+    ValidateWorkflows,
+    /// resources/workflows のワークフロー JSON を管理する
+    Workflows {
+        #[command(subcommand)]
+        action: WorkflowsAction,
+    },
+    /// Serve API のスコープ付きトークンを管理する
+    Token {
+        #[command(subcommand)]
+        action: TokenAction,
+    },
+    /// resources/bgm の目録 (bgm_manifest.toml) を管理する
+    Bgm {
+        #[command(subcommand)]
+        action: BgmAction,
+    },
+    /// workspace/journal の記録から、落ちる直前のログと Heartbeat を再構成する
+    Postmortem {
+        /// 最後の記録から遡る長さ (15m, 2h, 90s) または "<開始>..<終了>" (2025-01-03T07:00..2025-01-03T07:30)
+        #[arg(default_value = "10m")]
+        range: String,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum BgmAction {
+    /// 目録に無い音声ファイルと、ファイルが無くなった目録の曲を一覧する
+    Scan {
+        /// 目録に無いファイルをムード無しで追記する (後で bgm_manifest.toml を編集する)
+        #[arg(long)]
+        add: bool,
+    },
+    /// 曲をライブラリにコピーし、メタデータ付きで目録に載せる (同名の曲は上書き)
+    Import {
+        file: std::path::PathBuf,
+        /// ムードタグ (カンマ区切り: calm,hopeful)
+        #[arg(long, value_delimiter = ',', required = true)]
+        moods: Vec<String>,
+        #[arg(long)]
+        bpm: Option<u32>,
+        /// ライセンス表記 (クレジットに使う)
+        #[arg(long)]
+        license: String,
+        /// 継ぎ目なくループできる曲
+        #[arg(long)]
+        loopable: bool,
+        /// 優先するカテゴリ (カンマ区切り)
+        #[arg(long, value_delimiter = ',')]
+        categories: Vec<String>,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum KarmaAction {
+    /// 全教訓を重み・Soul ハッシュ・時刻ごと JSON に書き出す
+    Export {
+        #[arg(long, default_value = "karma.json")]
+        out: std::path::PathBuf,
+    },
+    /// 書き出した JSON を取り込む (埋め込みは次回の Serve 起動時に作り直す)
+    Import {
+        file: std::path::PathBuf,
+        /// 同じ ID の教訓があるとき: skip (残す) / replace (置き換える) / merge (重みは大きい方)
+        #[arg(long, default_value = "skip")]
+        on_conflict: String,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum SoulAction {
+    /// 記録した版の一覧 (新しい順): ハッシュ・有効になった時刻・ファイル・その版の教訓の数
+    History {
+        /// SOUL ファイル (省略時はすべて)
+        #[arg(long)]
+        source: Option<String>,
+    },
+    /// 2 つの版の差分 (ハッシュは先頭一致。省略時は直前の版と今の版)
+    Diff {
+        from: Option<String>,
+        to: Option<String>,
+        #[arg(long, default_value = "SOUL.md")]
+        source: String,
+    },
+    /// 旧版の下で書かれた教訓を今の SOUL と照らし、引き継ぐ・書き直す・退役させる (LLM を使う)
+    Reevaluate {
+        #[arg(long, default_value = "SOUL.md")]
+        source: String,
+        /// 見直す最大件数 (重い順)
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+        /// 判定を表示するだけで DB は変えない
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum TokenAction {
+    /// トークンを発行する (平文はこの場で一度だけ表示)
+    Create {
+        /// 利用者の名前 (ログ・レート制限の単位)
+        name: String,
+        /// admin / operator / readonly
+        #[arg(long, default_value = "readonly")]
+        scope: String,
+    },
+    /// 名前または ID でトークンを失効させる
+    Revoke {
+        name: String,
+    },
+    /// 発行済みトークンの一覧
+    List,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum WorkflowsAction {
+    /// 稼働中の ComfyUI の /object_info と突き合わせ、入力名の変更などを検出する
+    Check {
+        /// 推定できた移行 (入力名の付け替え) を JSON に書き戻す
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+/// CLI からの操作を監査ログに残す (操作者は OS のユーザー名)
+async fn audit_cli(job_queue: &infrastructure::job_queue::SqliteJobQueue, action: &str, target: Option<&str>) {
+    let actor = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+    if let Err(e) = job_queue.record_audit("cli", &actor, action, target, None).await {
+        warn!("⚠️ Audit: Failed to record {}: {}", action, e);
+    }
 }
 
 #[tokio::main]
@@ -93,7 +364,22 @@ async fn main() -> Result<(), anyhow::Error> {
     // ログ転送用のチャネルを作成 (容量1000)
     use shared::watchtower::CoreEvent;
     let (log_tx, log_rx) = tokio::sync::mpsc::channel::<CoreEvent>(1000);
-    let log_layer = server::watchtower::LogDrain::new(log_tx.clone());
+    let args = Args::parse();
+    // クラッシュ調査用のイベント記録 (postmortem 自身の実行は記録しない)
+    let journal_dir = std::env::current_dir()?.join("workspace").join("journal");
+    let event_journal = match &args.command {
+        Some(Commands::Postmortem { .. }) => None,
+        _ => match journal::EventJournal::open(&journal_dir) {
+            Ok(j) => Some(Arc::new(j)),
+            Err(e) => {
+                eprintln!("⚠️ Failed to open event journal in {}: {}", journal_dir.display(), e);
+                None
+            }
+        },
+    };
+    let log_layer = server::watchtower::LogDrain::new(log_tx.clone()).with_journal(event_journal.clone());
+    // Telemetry Hub (command-center 向けのライブ配信。ログ行も tracing から流し込む)
+    let telemetry = Arc::new(TelemetryHub::new());
 
     // Job Channel for Watchtower Commands
     use factory_core::contracts::WorkflowRequest;
@@ -102,9 +388,13 @@ async fn main() -> Result<(), anyhow::Error> {
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
         .with(log_layer)
+        .with(server::telemetry::TelemetryLogLayer::new(telemetry.clone()))
         .init();
 
-    let args = Args::parse();
+    // リモートモード: ローカルのパイプラインを起こさず、稼働中の Serve に依頼する
+    if let Some(url) = &args.remote {
+        return remote::run(url, args.command).await;
+    }
 
     // 0.2. Watchtower UDS Server — deferred to after job_queue init (line ~190)
     //       log_rx and job_tx are passed later.
@@ -112,24 +402,49 @@ async fn main() -> Result<(), anyhow::Error> {
     // Status tracking for Heartbeat
     let current_job = Arc::new(Mutex::new(Option::<String>::None));
 
+    // 0.25. Shutdown Coordinator (/stop・SIGINT・SIGTERM を各タスクへ一斉配信)
+    let shutdown = shutdown::Shutdown::new();
+
+    // 運用監視 (ハートビート・受け入れ制御・テレメトリで 1 つを共有し、GPU の計測コマンドを重ねない)
+    let health = Arc::new(Mutex::new(HealthMonitor::new()));
+
     // 0.3. Heartbeat Loop
     {
         let tx = log_tx.clone();
-        let health = Arc::new(Mutex::new(HealthMonitor::new()));
+        let health = health.clone();
         let current_job = current_job.clone();
+        let shutdown = shutdown.clone();
+        let journal = event_journal.clone();
+        let telemetry = telemetry.clone();
         tokio::spawn(async move {
             loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                let status = health.lock().await.check();
-                let job_id = current_job.lock().await.clone();
+                let stopping = tokio::select! {
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(5)) => false,
+                    _ = shutdown.wait() => true,
+                };
+                let status = health.lock().await.check().await;
+                // 停止時は最後の 1 拍を送ってから抜ける (Watchtower 側にアイドルを残す)
+                let job_id = if stopping { None } else { current_job.lock().await.clone() };
+                let stage = if stopping { None } else { telemetry.latest_stage().stage };
+                // 待ち行列とブレーカーは DB を持つ Watchtower サーバーが送る直前に埋める
                 let sys_status = shared::watchtower::SystemStatus {
                     cpu_usage: status.cpu_usage_percent,
                     memory_used_mb: status.memory_usage_mb,
-                    vram_used_mb: 0, 
+                    vram_used_mb: status.gpu.as_ref().map(|g| g.vram_used_mb).unwrap_or(0),
+                    vram_total_mb: status.gpu.as_ref().map(|g| g.vram_total_mb).unwrap_or(0),
+                    gpu_utilization: status.gpu.as_ref().map(|g| g.utilization_percent),
                     active_job_id: job_id, 
+                    stage,
+                    ..Default::default()
                 };
-                if let Err(_) = tx.try_send(shared::watchtower::CoreEvent::Heartbeat(sys_status)) {
-                    // Drop
+                let event = shared::watchtower::CoreEvent::Heartbeat(sys_status);
+                if let Some(journal) = &journal {
+                    journal.record(&event);
+                }
+                // Drop on backpressure
+                let _ = tx.try_send(event);
+                if stopping {
+                    break;
                 }
             }
         });
@@ -145,13 +460,13 @@ async fn main() -> Result<(), anyhow::Error> {
     tracing::info!("🆔 Process Group Leader Established. PID: {}", pid);
 
     // 0.5. 運用監視 (Phase 3)
-    let health = Arc::new(Mutex::new(HealthMonitor::new()));
-    let status = health.lock().await.check();
+    let status = health.lock().await.check().await;
     tracing::info!("📊 Initial Health Status: Memory {}MB, CPU {:.1}%", 
         status.memory_usage_mb, status.cpu_usage_percent);
 
-    // 1. 設定を読み込む
-    let config = FactoryConfig::default();
+    // 1. 設定を読み込む ([cron] の構文エラーはここで起動を止める)
+    let config = FactoryConfig::load()
+        .map_err(|e| factory_core::error::FactoryError::ConfigLoad { source: e.into() })?;
     let policy = SecurityPolicy::default_production();
 
     tracing::info!("⚙️  Config loaded:");
@@ -182,7 +497,7 @@ async fn main() -> Result<(), anyhow::Error> {
     tracing::info!("📁 ComfyUI Sync: {}", comfy_out.display());
     
     // 3. 統治機構 (Supervisor) の初期化
-    let supervisor = Supervisor::new(jail.clone(), SupervisorPolicy::Retry { max_retries: 3 });
+    let supervisor = Supervisor::new(jail.clone(), SupervisorPolicy::Retry { max_retries: 3, base_backoff: std::time::Duration::from_secs(2) });
     tracing::info!("⚖️  Governance Layer (Lex AI) Active");
 
     // 4. 新規マネージャの初期化 (Phase 8)
@@ -191,11 +506,74 @@ async fn main() -> Result<(), anyhow::Error> {
         warn!("⚠️ styles.toml not found, using empty manager");
         StyleManager::new_empty()
     }));
+
+    // 言語別フォント (fonts.toml)。インストール済みか確認し、無いものはフォールバックへ切り替える
+    let font_path = std::env::current_dir()?.join("fonts.toml");
+    let mut fonts = if font_path.exists() {
+        FontRegistry::load_from_file(&font_path)?
+    } else {
+        FontRegistry::default()
+    };
+    match FontRegistry::installed_families() {
+        Some(installed) => {
+            for problem in fonts.validate(installed) {
+                warn!("⚠️ [fonts] {}", problem);
+            }
+        }
+        None => warn!("⚠️ fc-list not available, subtitle fonts are not validated"),
+    }
+    let fonts = Arc::new(fonts);
+
+    // ワークフローの構造チェック (壊れた JSON は generate_video の奥で初めて落ちるため、起動時に知らせる)
+    let workflow_dir = std::env::current_dir()?.join("resources").join("workflows");
+    // シーン別ワークフローの指定はここで見つかった ID に限る
+    let workflow_ids = match infrastructure::workflow_doctor::lint_dir(&workflow_dir) {
+        Ok(results) => {
+            for (id, report) in &results {
+                for issue in &report.issues {
+                    warn!("⚠️ [workflows] {}: {}", id, issue.describe());
+                }
+            }
+            results.into_iter().map(|(id, _)| id).collect()
+        }
+        Err(e) => {
+            warn!("⚠️ [workflows] {}", e);
+            Vec::new()
+        }
+    };
+
+    // 定型台本 (templates.toml)。無ければ空で、テンプレート指定のジョブだけが失敗する
+    let script_templates = if std::path::Path::new(&config.templates.file).exists() {
+        let templates = ScriptTemplates::load_from_file(&config.templates.file)?;
+        info!("🧩 Script templates: {}", templates.names().join(", "));
+        templates
+    } else {
+        ScriptTemplates::default()
+    };
+    if !config.templates.sensitive_template.is_empty() && !script_templates.contains(&config.templates.sensitive_template) {
+        warn!("⚠️ [templates] sensitive_template '{}' is not defined in {}", config.templates.sensitive_template, config.templates.file);
+    }
+
+    // styles.toml の外部編集を検知してホットリロード (再起動なしで Ken Burns / BGM パラメータを反映)
+    {
+        let style_manager = style_manager.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                ticker.tick().await;
+                match style_manager.reload_if_changed() {
+                    Ok(true) => tracing::info!("🔄 styles.toml changed on disk, reloaded"),
+                    Ok(false) => {}
+                    Err(e) => warn!("⚠️ styles.toml reload failed, keeping previous profiles: {}", e),
+                }
+            }
+        });
+    }
     
     let asset_manager = Arc::new(AssetManager::new(std::env::current_dir()?.join("workspace")));
 
     // 5. インフラクライアントの準備
-    let arbiter = Arc::new(ResourceArbiter::new());
+    let arbiter = Arc::new(ResourceArbiter::new().with_job_slots(config.worker.concurrency).with_admission(config.admission.clone(), health.clone(), log_tx.clone()));
 
     // 5.1 The Persistent Memory & The Samsara Protocol
     let db_dir = std::env::current_dir()?.join("workspace").join("db");
@@ -203,7 +581,43 @@ async fn main() -> Result<(), anyhow::Error> {
         std::fs::create_dir_all(&db_dir)?;
     }
     let db_filepath = format!("sqlite://{}", db_dir.join("shorts_factory.db").display());
-    let job_queue = Arc::new(infrastructure::job_queue::SqliteJobQueue::new(&db_filepath).await?);
+    let mut job_queue = infrastructure::job_queue::SqliteJobQueue::new(&db_filepath).await?;
+    let karma_embedding = &config.karma_embedding;
+    let embedder: Option<Arc<dyn factory_core::traits::Embedder>> = match karma_embedding.provider.as_str() {
+        "gemini" if !config.gemini_api_key.is_empty() => Some(Arc::new(
+            infrastructure::embedder::GeminiEmbedder::new(&config.gemini_api_key, karma_embedding.resolved_model())?,
+        )),
+        "ollama" => Some(Arc::new(
+            infrastructure::embedder::OllamaEmbedder::new(&config.ollama_url, karma_embedding.resolved_model())?,
+        )),
+        "none" | "gemini" => None,
+        other => {
+            warn!("⚠️ Unknown karma_embedding.provider '{}'. Falling back to LIKE matching.", other);
+            None
+        }
+    };
+    if let Some(embedder) = embedder {
+        info!("🧠 Semantic Karma enabled ({} / {})", karma_embedding.provider, karma_embedding.resolved_model());
+        job_queue = job_queue.with_embedder(embedder, karma_embedding.min_similarity);
+    }
+    let job_queue = Arc::new(job_queue);
+    let cost_tracker = factory_core::cost::CostTracker::new(job_queue.clone(), config.costs.clone());
+
+    // 既存の教訓 (埋め込み導入前・モデル変更前のもの) を少しずつベクトル化する
+    {
+        let job_queue = job_queue.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(600));
+            loop {
+                ticker.tick().await;
+                match job_queue.backfill_karma_embeddings(50).await {
+                    Ok(0) => {}
+                    Ok(n) => info!("🧠 Embedded {} karma lesson(s)", n),
+                    Err(e) => warn!("⚠️ Karma embedding backfill failed: {}", e),
+                }
+            }
+        });
+    }
 
     // 5.2 The Soul of the World (Load Soul.md for Oracle)
     let soul_md_path = std::env::current_dir()?.join("SOUL.md");
@@ -211,6 +625,53 @@ async fn main() -> Result<(), anyhow::Error> {
         warn!("⚠️ SOUL.md not found at {}. Using default soul.", soul_md_path.display());
         "## Default Soul\n- Be creative.\n- Stay true to the mission.".to_string()
     });
+    let channels = Arc::new(channels::ChannelRegistry::load(&config, &soul_md));
+    // SOUL の版を記録する (書き換えで旧版になった教訓は `soul reevaluate` で見直せる)
+    for (source, content) in channels.soul_sources() {
+        match job_queue.record_soul_version(source, content).await {
+            Ok(Some(previous)) => {
+                let current = infrastructure::soul_history::soul_hash(content);
+                let legacy = job_queue.fetch_legacy_karma(source, &current, 1000).await.map(|k| k.len()).unwrap_or(0);
+                warn!("🧬 {} changed ({} -> {}); {} karma lesson(s) are now legacy. See `soul diff` / `soul reevaluate`.",
+                    source, previous.trim(), current.trim(), legacy);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("⚠️ Failed to record the soul version of {}: {}", source, e),
+        }
+    }
+    let characters = Arc::new(characters::CharacterRegistry::load(&config));
+
+    // 5.3 Approval Gate (Watchtower のボタンで承認・却下を受け取る)
+    let approval_gate = Arc::new(approval::ApprovalGate::new(log_tx.clone()));
+
+    // 0.15 Watch Folder (手動レンダーの取り込み口)
+    let watch_folder = if config.watch_folder.enabled {
+        let wf = &config.watch_folder;
+        let folder = WatchFolder::open(&wf.dir, Duration::from_secs(wf.settle_secs))?;
+        info!("🎞️ Watch folder: {} (every {}s)", folder.inbox().display(), wf.poll_secs);
+        Some(Arc::new(folder))
+    } else {
+        None
+    };
+
+    // 0.18 Slack / Telegram: 完了通知と承認依頼を Discord と並べて送り、押されたボタンを受け取る
+    let notifiers = server::notify::Notifiers::from_config(&config.notifiers)?;
+    let log_rx = server::notify::tee_events(log_rx, notifiers.all());
+    if let Some(telegram) = &notifiers.telegram {
+        tokio::spawn(server::notify::run_telegram_replies(telegram.clone(), job_queue.clone(), approval_gate.clone(), shutdown.clone()));
+    }
+    let slack_actions = match (&notifiers.slack, config.notifiers.slack_signing_secret.is_empty()) {
+        (Some(notifier), false) => Some(Arc::new(server::notify::SlackActions {
+            notifier: notifier.clone(),
+            signing_secret: config.notifiers.slack_signing_secret.clone(),
+            approval_gate: approval_gate.clone(),
+        })),
+        (Some(_), true) => {
+            warn!("⚠️ Slack notifications are on but slack_signing_secret is empty; approval buttons in Slack will not work");
+            None
+        }
+        (None, _) => None,
+    };
 
     // 0.2. Start Watchtower UDS Server (deferred — needs job_queue Arc)
     let wt_server = server::watchtower::WatchtowerServer::new(
@@ -223,21 +684,40 @@ async fn main() -> Result<(), anyhow::Error> {
         config.ollama_url.clone(),
         "huihui_ai/mistral-small-abliterated:latest".to_string(), // 規制解除版 Mistral-Small
         config.unleashed_mode,
+        approval_gate.clone(),
+        channels.clone(),
+        shutdown.clone(),
     );
-    tokio::spawn(wt_server.start());
+    let wt_server = match &watch_folder {
+        Some(folder) => {
+            let channel = if config.watch_folder.channel.is_empty() { DEFAULT_CHANNEL.to_string() } else { config.watch_folder.channel.clone() };
+            wt_server.with_watch_folder(folder.clone(), channel)
+        }
+        None => wt_server,
+    };
+    let wt_server = wt_server
+        .with_chat_persistence(config.chat_memory.persist)
+        .with_assets(asset_manager.clone())
+        .with_topic_policy(config.topic_policy.file.clone())
+        .with_styles(style_manager.clone())
+        .with_report_days(config.cron.production_report_days);
+    let wt_handle = tokio::spawn(wt_server.start());
 
-    let _cron_scheduler = server::cron::start_cron_scheduler(
+    let mut cron_scheduler = server::cron::start_cron_scheduler(
         job_queue.clone(),
         log_tx.clone(),
         config.ollama_url.clone(),
         config.model_name.clone(),
-        config.brave_api_key.clone(),
-        config.youtube_api_key.clone(),
+        Arc::new(CompositeTrendSource::from_config(&config.trends, &config.brave_api_key, &config.youtube_api_key)),
         config.gemini_api_key.clone(),
-        soul_md.clone(),
+        channels.clone(),
         config.workspace_dir.clone(),
         config.comfyui_base_dir.clone(),
         config.clean_after_hours,
+        config.cron.clone(),
+        cost_tracker.clone(),
+        config.topic_policy.file.clone(),
+        config.oracle.clone(),
     ).await.map_err(|e| factory_core::error::FactoryError::Infrastructure { reason: format!("Cron failed to start: {}", e) })?;
     info!("🌙 Samsara Protocol is now ACTIVE (Proactive Watchtower enabled)");
 
@@ -246,44 +726,129 @@ async fn main() -> Result<(), anyhow::Error> {
         "python".to_string(), "python3".to_string(), "Python".to_string(), "uv".to_string(), "main".to_string(), "shorts-factory".to_string(), "shorts-fa".to_string()
     ]));
 
-    let should_spawn_tts = match &args.command {
-        Some(Commands::Serve { .. }) | Some(Commands::Generate { .. }) | None => true,
-        _ => false,
-    };
+    let mut sidecar_supervisors = Vec::new();
+
+    let should_spawn_tts = matches!(&args.command, Some(Commands::Serve { .. }) | Some(Commands::Generate { .. }) | None);
 
     // TTS Sidecar (Qwen3-TTS)
+    let ports = config.ports.clone();
+    let mut tts_port = ports.tts_port;
     if should_spawn_tts {
         let sm = sidecar_manager.clone();
-        sm.clean_port(5001).await?;
+        // 前回のサイドカーの残骸は片付ける。見知らぬプロセスが居座っていれば、自動割り当て時だけ避ける
+        if let Err(e) = sm.clean_port(tts_port).await {
+            if !ports.auto_allocate {
+                return Err(anyhow::anyhow!("{}. Free it, change [ports] tts_port, or set [ports] auto_allocate = true.", e));
+            }
+        }
         // TIME_WAIT ソケット解放を待機
         tokio::time::sleep(Duration::from_secs(2)).await;
-        let mut cmd = Command::new(".venv/bin/python");
-        cmd.arg("tts_server.py")
-           .env("PYTORCH_ENABLE_MPS_FALLBACK", "1")
-           .current_dir("services/qwen3-tts");
-        sm.spawn(cmd).await?;
-        info!("🎙️  TTS Sidecar server (Qwen3-TTS) spawned on port 5001");
-        // コールドスタート（モデルロード）待機
-        tokio::time::sleep(Duration::from_secs(10)).await;
+        if !sidecar::is_port_free(tts_port) {
+            if !ports.auto_allocate {
+                return Err(anyhow::anyhow!("TTS port {} is still in use. Change [ports] tts_port or set [ports] auto_allocate = true.", tts_port));
+            }
+            let next = tts_port.checked_add(1)
+                .and_then(|start| sidecar::find_free_port(start, ports.search_range))
+                .ok_or_else(|| anyhow::anyhow!("No free TTS port in {}..+{}", tts_port, ports.search_range))?;
+            warn!("⚠️ TTS port {} is in use, using {} instead", tts_port, next);
+            tts_port = next;
+        }
+        let tts_command = move || {
+            let mut cmd = Command::new(".venv/bin/python");
+            cmd.arg("tts_server.py")
+               .env("PYTORCH_ENABLE_MPS_FALLBACK", "1")
+               .env("TTS_PORT", tts_port.to_string())
+               .current_dir("services/qwen3-tts");
+            cmd
+        };
+        sm.spawn(tts_command()).await?;
+        info!("🎙️  TTS Sidecar server (Qwen3-TTS) spawned on port {}", tts_port);
+        // コールドスタート（モデルロード）待機: ヘルスエンドポイントが応答するまで
+        let sc = &config.sidecar;
+        let tts_probe_url = sidecar::with_port(&sc.tts_probe_url, tts_port);
+        if let Err(e) = sm.wait_until_ready(&tts_probe_url, Duration::from_secs(sc.ready_timeout_secs)).await {
+            warn!("⚠️ TTS Sidecar is not ready yet: {}. The Shepherd will keep watching.", e);
+        }
+
+        // The Shepherd: クラッシュ・無応答を検知して自動再起動し、フラップは Watchtower へ通知
+        let policy = SupervisionPolicy {
+            name: "qwen3-tts".to_string(),
+            probe_url: tts_probe_url,
+            probe_interval: Duration::from_secs(sc.probe_interval_secs),
+            probe_timeout: Duration::from_secs(sc.probe_timeout_secs),
+            startup_grace: Duration::from_secs(sc.startup_grace_secs),
+            failure_threshold: sc.failure_threshold,
+            initial_backoff: Duration::from_secs(sc.initial_backoff_secs),
+            max_backoff: Duration::from_secs(sc.max_backoff_secs),
+            flap_window: Duration::from_secs(sc.flap_window_secs),
+            flap_threshold: sc.flap_threshold,
+        };
+        let (sidecar_tx, mut sidecar_rx) = tokio::sync::mpsc::channel::<SidecarEvent>(16);
+        sidecar_supervisors.push(sm.supervise(policy, tts_command, sidecar_tx));
+        let log_tx_sidecar = log_tx.clone();
+        tokio::spawn(async move {
+            while let Some(event) = sidecar_rx.recv().await {
+                let alert = match event {
+                    SidecarEvent::Restarted { name, attempt, reason } => CoreEvent::SidecarAlert {
+                        name,
+                        message: format!("restarted (attempt {}): {}", attempt, reason),
+                        flapping: false,
+                    },
+                    SidecarEvent::Flapping { name, restarts, window, last_reason } => CoreEvent::SidecarAlert {
+                        name,
+                        message: format!("FLAPPING — {} restarts within {}s. Last failure: {}", restarts, window.as_secs(), last_reason),
+                        flapping: true,
+                    },
+                    SidecarEvent::RestartFailed { name, error } => CoreEvent::SidecarAlert {
+                        name,
+                        message: format!("restart failed: {}", error),
+                        flapping: false,
+                    },
+                };
+                let _ = log_tx_sidecar.send(alert).await;
+            }
+        });
     }
 
 
     // Infrastructure Clients
-    let trend_sonar = BraveTrendSonar::new(config.brave_api_key.clone());
+    // 映像エンコーダーは起動時に一度だけ検出し、最終エンコード・リサイズ・Ken Burns で共有する
+    let encoder = Arc::new(EncoderProfile::detect(&config.encoder).await);
+    let trend_sonar = CompositeTrendSource::from_config(&config.trends, &config.brave_api_key, &config.youtube_api_key);
+    info!("📡 Trend sources: [{}]", trend_sonar.source_names().join(", "));
     let concept_manager = ConceptManager::new(&config.gemini_api_key, &config.script_model);
     let comfy_bridge = ComfyBridgeClient::new(
         shield.clone(),
         &config.comfyui_api_url,
         &config.comfyui_base_dir,
         config.comfyui_timeout_secs,
-    );
-    let voice_actor = VoiceActor::new("http://localhost:5001", "aiome_narrator");
+    )
+    .with_progress(telemetry.comfy_progress_sender())
+    .with_encoder(encoder.clone());
+    let comfy_bridge = if config.safety.enabled {
+        comfy_bridge.with_safety(SafetyClassifier::new(
+            &config.safety.classifier_url,
+            &config.gemini_api_key,
+            &config.safety.model,
+            config.safety.threshold,
+            config.safety.max_regenerations,
+            Duration::from_secs(config.safety.timeout_secs),
+        )?)
+    } else {
+        comfy_bridge
+    };
+    // ComfyUI のノード進捗 (KSampler 14/30 など) を Discord にも節目ごとに流す
+    telemetry.spawn_comfy_progress_log(log_tx.clone());
+    let tts_url = format!("http://localhost:{}", tts_port);
+    let voice_actor = VoiceActor::new(&tts_url, "aiome_narrator");
     let bgm_path = std::env::current_dir()?.join("resources/bgm");
     if !bgm_path.exists() {
         std::fs::create_dir_all(&bgm_path)?;
     }
-    let sound_mixer = SoundMixer::new(bgm_path);
-    let media_forge = MediaForgeClient::new(jail.clone());
+    let sound_mixer = SoundMixer::new(bgm_path.clone()).with_sfx(config.sfx.clone());
+    let media_forge = MediaForgeClient::new(jail.clone()).with_encoding(config.encoding.clone())
+        .with_encoder(encoder)
+        .with_progress(telemetry.ffmpeg_progress_sender());
 
     // 6. 生産ライン・オーケストレーターの準備
     let orchestrator = Arc::new(ProductionOrchestrator::new(
@@ -298,30 +863,96 @@ async fn main() -> Result<(), anyhow::Error> {
         style_manager.clone(),
         asset_manager.clone(),
         config.export_dir.clone(),
-    ));
+    )
+    .with_subtitle_qa(config.subtitle_qa.clone())
+    .with_vision_qa(config.vision_qa.clone(), &config.gemini_api_key)?
+    .with_aesthetic(config.aesthetic.clone(), &config.gemini_api_key)?
+    .with_reframe(config.reframe.clone())?
+    .with_export(config.export.clone())
+    .with_upscale(&config.upscale)?
+    .with_fonts(fonts)
+    .with_workflows(workflow_ids)
+    .with_channels(channels.clone())
+    .with_characters(characters)
+    .with_telemetry(telemetry.clone())
+    .with_approval(approval_gate.clone(), config.approval.clone())
+    .with_script_templates(script_templates, config.templates.clone())
+    .with_artifact_store(infrastructure::artifact_store::build_artifact_store(&config)?)
+    .with_cost_tracker(cost_tracker.clone())
+    .with_fingerprints(job_queue.clone(), config.fingerprint.clone()));
+
+    // 夜間セルフテスト: 朝の Samsara より前に制作環境の故障を見つける
+    if let Err(e) = server::cron::schedule_self_test(
+        &cron_scheduler,
+        &config.cron,
+        orchestrator.clone(),
+        jail.clone(),
+        config.self_test.clone(),
+        log_tx.clone(),
+        job_queue.clone(),
+    ).await {
+        warn!("⚠️ Failed to schedule the nightly self-test: {}", e);
+    }
+
+    // ComfyUI 側のキューを定期的に覗き、手動投入のワークフローが GPU を塞いでいないかをテレメトリに流す
+    {
+        let comfy = orchestrator.comfy_bridge.clone();
+        let telemetry = telemetry.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                ticker.tick().await;
+                match comfy.fetch_queue_snapshot(server::router::COMFY_HISTORY_LIMIT).await {
+                    Ok(snapshot) => telemetry.publish_comfy_queue(snapshot),
+                    // ComfyUI 停止中は毎回出ると煩いので debug に留める
+                    Err(e) => tracing::debug!("ComfyUI queue poll failed: {}", e),
+                }
+            }
+        });
+    }
 
     // コマンド分岐
-    match args.command.unwrap_or(Commands::Generate { 
-        category: "tech".to_string(), 
-        topic: "AIの未来".to_string(), 
-        remix: None, 
-        step: None 
-    }) {
+    match args.command.unwrap_or_else(default_command) {
         Commands::Serve { port } => {
             info!("📡 Starting Command Center Server on port {}", port);
             
             // Telemetry Hub
-            let telemetry = Arc::new(TelemetryHub::new());
-            telemetry.start_heartbeat_loop().await;
+            telemetry.start_heartbeat_loop(health.lock().await.gpu()).await;
 
             // 6.2 Autonomous JobWorker (The Autonomous Engine)
             let worker = Arc::new(JobWorker::new(
                 job_queue.clone(),
                 orchestrator.clone(),
                 jail.clone(),
-                soul_md.clone(),
-            ));
-            tokio::spawn(worker.start_loop());
+                channels.clone(),
+            )
+            .with_watchdog(config.watchdog.clone())
+            .with_pool(&config.worker)
+            .with_notifications(log_tx.clone(), config.discord_preview.clone())
+            .with_watch_folder(watch_folder.clone()));
+            let drain_timeout = Duration::from_secs(config.shutdown.drain_timeout_secs);
+            let worker_handle = tokio::spawn(worker.start_loop(shutdown.clone(), drain_timeout));
+            shutdown.listen_for_signals();
+
+            // 6.3 Watch Folder: 新着レンダーを退避し、Discord でトピックを尋ねる
+            if let Some(folder) = watch_folder.clone() {
+                let tx = log_tx.clone();
+                let poll = Duration::from_secs(config.watch_folder.poll_secs.max(1));
+                tokio::spawn(async move {
+                    let mut ticker = tokio::time::interval(poll);
+                    loop {
+                        ticker.tick().await;
+                        match folder.collect(std::time::SystemTime::now()) {
+                            Ok(renders) => {
+                                for r in renders {
+                                    let _ = tx.send(CoreEvent::IngestPrompt { ingest_id: r.id, file_name: r.file_name, size_bytes: r.size_bytes }).await;
+                                }
+                            }
+                            Err(e) => warn!("⚠️ Watch folder scan failed: {}", e),
+                        }
+                    }
+                });
+            }
 
             // Axum Router
             let state = Arc::new(AppState {
@@ -329,72 +960,126 @@ async fn main() -> Result<(), anyhow::Error> {
                 orchestrator,
                 style_manager,
                 jail,
-                is_busy: Arc::new(std::sync::Mutex::new(false)),
                 asset_manager,
                 current_job: current_job.clone(),
                 job_queue: job_queue.clone(),
+                channels: channels.clone(),
+                gemini_api_key: config.gemini_api_key.clone(),
+                cron: config.cron.clone(),
+                review: Arc::new(server::review::ReviewLinks::from_config(&config.review)?),
+                auth: server::auth::ApiAuth::from_config(&config.auth, Some(job_queue.clone()))?,
+                topic_policy_file: config.topic_policy.file.clone(),
+                events: log_tx.clone(),
+                slack: slack_actions.clone(),
             });
             let worker_state = state.clone(); 
             tokio::spawn(async move {
                 while let Some(req) = job_rx.recv().await {
                    info!("🏗️ Processing Watchtower Job: {}", req.topic);
-                   
-                   // 1. Try acquire lock
-                   let acquired = {
-                       if let Ok(mut busy) = worker_state.is_busy.try_lock() {
-                           if !*busy {
-                               *busy = true;
-                               true
-                           } else {
-                               false
-                           }
-                       } else {
-                           false
-                       }
+
+                   // 1. JobWorker と共有のジョブ枠を取る (空きが無ければ捨てる)
+                   let Some(slot) = worker_state.orchestrator.arbiter.try_acquire_job() else {
+                       warn!("⚠️ System Busy. Dropping Watchtower Job.");
+                       continue;
                    };
 
-                   if acquired {
-                        // 2. Set current job info
-                        {
-                            let mut job_info = worker_state.current_job.lock().await;
-                            *job_info = Some(format!("{}: {}", req.category, req.topic));
-                        }
+                   // 2. 枠を持ったまま裏で実行し、次の依頼を受け付ける
+                   let state = worker_state.clone();
+                   tokio::spawn(async move {
+                        let label = format!("{}: {}", req.category, req.topic);
+                        *state.current_job.lock().await = Some(label.clone());
 
                         // 3. Execute
-                        if let Err(e) = worker_state.orchestrator.execute(req, &worker_state.jail).await {
+                        if let Err(e) = state.orchestrator.execute(req, &state.jail).await {
                             error!("❌ Watchtower Job Failed: {}", e);
                         } else {
                             info!("✅ Watchtower Job Complete");
                         }
 
-                        // 4. Release & Clear job info
+                        // 4. Release & Clear job info (別の依頼に書き換わっていれば残す)
                         {
-                            let mut job_info = worker_state.current_job.lock().await;
-                            *job_info = None;
-                        }
-                        
-                        if let Ok(mut busy) = worker_state.is_busy.lock() {
-                            *busy = false;
-                            worker_state.telemetry.broadcast_log("INFO", "System Ready (Watchtower Job Done)");
+                            let mut job_info = state.current_job.lock().await;
+                            if job_info.as_deref() == Some(label.as_str()) {
+                                *job_info = None;
+                            }
                         }
-                    } else {
-                        warn!("⚠️ System Busy. Dropping Watchtower Job.");
-                    }
+                        drop(slot);
+                        state.telemetry.broadcast_log("INFO", "System Ready (Watchtower Job Done)");
+                   });
                 }
             });
 
             let app = create_router(state);
-            let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
-            axum::serve(listener, app).await?;
+            if !config.auth.enabled {
+                warn!("🔓 Auth disabled: anyone who can reach port {} can enqueue jobs. Set [auth] enabled = true.", port);
+            }
+            let listener = match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await {
+                Ok(listener) => listener,
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse && ports.auto_allocate => {
+                    let next = port.checked_add(1)
+                        .and_then(|start| sidecar::find_free_port(start, ports.search_range))
+                        .ok_or_else(|| anyhow::anyhow!("No free port in {}..+{}", port, ports.search_range))?;
+                    warn!("⚠️ Port {} is in use, serving on {} instead", port, next);
+                    tokio::net::TcpListener::bind(format!("0.0.0.0:{}", next)).await?
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                    return Err(anyhow::anyhow!("Port {} is already in use (another Serve?). Stop it, pass --port, or set [ports] auto_allocate = true.", port));
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let port = listener.local_addr()?.port();
+            // 実際のエンドポイントを書き出す (Command Center・CLI の接続先の発見用)
+            let runtime_file = std::path::PathBuf::from(&ports.runtime_file);
+            let endpoints = RuntimeEndpoints {
+                pid: std::process::id(),
+                api_url: format!("http://127.0.0.1:{}", port),
+                api_port: port,
+                tts_url: tts_url.clone(),
+                tts_port,
+                started_at: chrono::Utc::now().to_rfc3339(),
+            };
+            if let Some(dir) = runtime_file.parent() {
+                std::fs::create_dir_all(dir).ok();
+            }
+            match std::fs::write(&runtime_file, serde_json::to_string_pretty(&endpoints)?) {
+                Ok(()) => info!("📡 Serving on {} (endpoints written to {})", endpoints.api_url, runtime_file.display()),
+                Err(e) => warn!("⚠️ Failed to write {}: {}", runtime_file.display(), e),
+            }
+            let stop = shutdown.clone();
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move { stop.wait().await })
+                .await?;
+            info!("📡 Command Center Server stopped accepting requests");
+            std::fs::remove_file(&runtime_file).ok();
+
+            // 実行中のジョブを待つ (間に合わなければ Pending に戻る)
+            if let Err(e) = worker_handle.await {
+                error!("❌ JobWorker task ended abnormally: {}", e);
+            }
         }
-        Commands::LinkSns { job_id, platform, video_id } => {
+        Commands::LinkSns { job_id, platform, video_id, variant } => {
             info!("🔗 Linking Job {} to {} video ID: {}", job_id, platform, video_id);
+            audit_cli(&job_queue, "link_sns", Some(&job_id)).await;
             match job_queue.link_sns_data(&job_id, &platform, &video_id).await {
-                Ok(_) => info!("✅ Linking Successful."),
+                Ok(_) => {
+                    info!("✅ Linking Successful.");
+                    if let Err(e) = server::captions::attach_captions(&job_queue, &channels, &asset_manager, &job_id, &platform, &video_id).await {
+                        error!("❌ Failed to attach captions: {}", e);
+                    }
+                }
                 Err(e) => error!("❌ Failed to link SNS data: {}", e),
             }
+            if let Some(label) = variant {
+                match job_queue.set_published_variant(&job_id, &label).await {
+                    Ok(v) => info!("🪝 Published title variant {}: '{}' (hook: {})", v.label, v.title, v.hook_style),
+                    Err(e) => error!("❌ Failed to record the published variant: {}", e),
+                }
+            }
         }
-        Commands::SimulateEvolution => {
+        Commands::SimulateEvolution { scenario, baseline } if scenario.is_empty() => {
+            if baseline.is_some() {
+                warn!("⚠️ --baseline only applies to --scenario runs. Ignoring it.");
+            }
             info!("🔬 Preparing Evolution Simulator environment...");
             if let Err(e) = simulator::run_evolution_simulation(
                 job_queue.pool_ref(),
@@ -405,20 +1090,340 @@ async fn main() -> Result<(), anyhow::Error> {
                 error!("❌ Evolution Simulation Failed: {}", e);
             }
         }
+        Commands::SimulateEvolution { scenario, baseline } => {
+            let baseline = baseline.map(|path| infrastructure::evolution_scenario::EvolutionReport::load(std::path::Path::new(&path))).transpose()?;
+            let oracle = infrastructure::oracle::Oracle::new(&config.gemini_api_key, &config.oracle.gemini_model, soul_md.clone())
+                .with_ensemble(&config.oracle, &config.ollama_url);
+            let report_dir = std::path::Path::new(&config.workspace_dir).join("simulations");
+            let mut regressions = 0;
+            for path in &scenario {
+                let (report, report_path) = simulator::run_scenario_file(std::path::Path::new(path), &report_dir, &oracle).await?;
+                println!("🧪 {} → {}", report.scenario, report_path.display());
+                println!(
+                    "   Karma: {} → {} active (mean weight {:.1} → {:.1}), {} drifted",
+                    report.karma_before.active, report.karma_after.active, report.karma_before.mean_weight, report.karma_after.mean_weight, report.weight_drift.len()
+                );
+                println!(
+                    "   Retrieval hit-rate: {:.0}% → {:.0}% ({} forbidden lesson(s) retrieved)",
+                    report.retrieval_before.hit_rate * 100.0, report.retrieval_after.hit_rate * 100.0, report.retrieval_after.leaks
+                );
+                for job in report.jobs.iter().filter(|j| j.error.is_some()) {
+                    println!("   ⚠️ Job '{}': {}", job.topic, job.error.as_deref().unwrap_or_default());
+                }
+                match baseline.as_ref() {
+                    Some(base) if base.scenario == report.scenario => {
+                        let found = report.regressions(base);
+                        for r in &found {
+                            println!("   ❌ Regression: {}", r);
+                        }
+                        regressions += found.len();
+                    }
+                    Some(base) => warn!("⚠️ Baseline is for scenario '{}', not '{}'. Skipping comparison.", base.scenario, report.scenario),
+                    None => {}
+                }
+            }
+            if regressions > 0 {
+                return Err(anyhow::anyhow!("{} regression(s) against the baseline report", regressions));
+            }
+        }
         Commands::SamsaraNow => {
             info!("🔄 [Samsara] Manual trigger initiated. Starting synthesis...");
+            audit_cli(&job_queue, "samsara_now", None).await;
             let config = FactoryConfig::default();
-            match server::cron::synthesize_next_job(
-                &config.gemini_api_key,
-                "gemini-2.5-flash",
-                &config.brave_api_key,
-                &*job_queue,
-            ).await {
-                Ok(_) => info!("✅ [Samsara] Manual synthesis complete. Job enqueued."),
-                Err(e) => error!("❌ [Samsara] Manual synthesis failed: {}", e),
+            let soul = Some(config.cron.samsara_soul.as_str())
+                .filter(|name| !name.is_empty())
+                .and_then(|name| channels.soul(name));
+            let trends = CompositeTrendSource::from_config(&config.trends, &config.brave_api_key, &config.youtube_api_key);
+            for channel in channels.samsara_channels() {
+                match server::cron::synthesize_next_job(
+                    &config.gemini_api_key,
+                    "gemini-2.5-flash",
+                    &trends,
+                    &job_queue,
+                    channel,
+                    soul,
+                    &cost_tracker.for_job(None, None),
+                    config.cron.samsara_dedupe_days,
+                    config.cron.samsara_dedupe_threshold,
+                    &config.topic_policy.file,
+                ).await {
+                    Ok(server::cron::SynthesisOutcome::Enqueued(job_id)) => info!("✅ [Samsara] Manual synthesis complete for channel '{}'. Job {} enqueued.", channel.name, job_id),
+                    Ok(server::cron::SynthesisOutcome::Repeated) => info!("⏭️ [Samsara] Manual synthesis for channel '{}' declined: the topic repeats a recent video.", channel.name),
+                    Ok(server::cron::SynthesisOutcome::Blocked { topic, reason }) => {
+                        server::topic_guard::report(&log_tx, "samsara", &topic, &reason).await;
+                    }
+                    Err(e) => error!("❌ [Samsara] Manual synthesis failed for channel '{}': {}", channel.name, e),
+                }
+            }
+        }
+        Commands::Schedule { at, topic, series, style, channel, soul, count, every_hours } => {
+            let first = match server::calendar::parse_schedule_time(&at) {
+                Ok(t) => t,
+                Err(e) => {
+                    error!("❌ {}", e);
+                    return Ok(());
+                }
+            };
+            if first < chrono::Utc::now() {
+                warn!("⚠️ {} is in the past; the first job will run as soon as a worker is free.", at);
+            }
+            let plan = server::calendar::SchedulePlan {
+                first,
+                count,
+                every: chrono::Duration::hours(every_hours),
+                series,
+                topic,
+                style,
+                channel,
+                soul,
+            };
+            audit_cli(&job_queue, "schedule", plan.series.as_deref().or(plan.topic.as_deref())).await;
+            match server::calendar::schedule_jobs(&job_queue, &plan).await {
+                Ok(jobs) => {
+                    for (job_id, at, episode) in jobs {
+                        let at = at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M %Z");
+                        match episode {
+                            Some(n) => info!("🗓️ Scheduled episode #{} for {} (job {})", n, at, job_id),
+                            None => info!("🗓️ Scheduled job {} for {}", job_id, at),
+                        }
+                    }
+                }
+                Err(e) => error!("❌ Failed to schedule: {}", e),
+            }
+        }
+        Commands::SeriesCreate { name, topic_template, title_template, style, channel, soul } => {
+            audit_cli(&job_queue, "series_edit", Some(&name)).await;
+            let new = factory_core::traits::NewSeries { name, topic_template, title_template, style, channel, soul };
+            match job_queue.create_series(&new).await {
+                Ok(series) => info!("📺 Series '{}' created (id: {}, channel: {})", series.name, series.id, series.channel),
+                Err(e) => error!("❌ Failed to create series: {}", e),
+            }
+        }
+        Commands::SeriesList => {
+            match job_queue.list_series().await {
+                Ok(list) if list.is_empty() => info!("📺 No series yet. Create one with `series-create <name>`."),
+                Ok(list) => {
+                    for series in list {
+                        println!("{}\t#{}\t{}\t{}", series.name, series.episode_counter, series.channel, series.id);
+                    }
+                }
+                Err(e) => error!("❌ Failed to list series: {}", e),
+            }
+        }
+        Commands::SeriesEpisode { name, topic } => {
+            audit_cli(&job_queue, "enqueue_episode", Some(&name)).await;
+            match job_queue.enqueue_episode(&name, topic.as_deref(), None, None, None).await {
+                Ok((job_id, number)) => info!("📺 Queued '{}' episode #{} as job {}", name, number, job_id),
+                Err(e) => error!("❌ Failed to queue episode: {}", e),
+            }
+        }
+        Commands::Jobs { failed: false } => remote::print_jobs(&job_queue.fetch_recent_jobs(100).await?),
+        Commands::Jobs { failed: true } => remote::print_failed_jobs(&job_queue.fetch_failed_jobs(50).await?),
+        Commands::Karma { limit, action: None } => remote::print_karma(&job_queue.fetch_all_karma(limit as i64).await?),
+        Commands::Karma { action: Some(KarmaAction::Export { out }), .. } => {
+            let archive = infrastructure::karma_archive::KarmaArchive::new(job_queue.export_karma().await?);
+            archive.save(&out)?;
+            info!("📦 Exported {} lesson(s) to {}", archive.entries.len(), out.display());
+        }
+        Commands::Karma { action: Some(KarmaAction::Import { file, on_conflict }), .. } => {
+            let conflict: infrastructure::karma_archive::KarmaConflict = on_conflict.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            let archive = infrastructure::karma_archive::KarmaArchive::load(&file)?;
+            let report = job_queue.import_karma(&archive.entries, conflict).await?;
+            audit_cli(&job_queue, "karma_import", Some(&file.display().to_string())).await;
+            info!("📥 Imported karma from {}: {} new, {} replaced, {} merged, {} skipped",
+                file.display(), report.inserted, report.replaced, report.merged, report.skipped);
+        }
+        Commands::Soul { action: SoulAction::History { source } } => {
+            for v in job_queue.fetch_soul_versions(source.as_deref(), 100).await? {
+                println!("{}\t{}\t{}\t{} line(s)\t{} karma", v.hash.trim(), v.recorded_at, v.source, v.content.lines().count(), v.karma_count);
+            }
+        }
+        Commands::Soul { action: SoulAction::Diff { from, to, source } } => {
+            let recent = job_queue.fetch_soul_versions(Some(&source), 2).await?;
+            let to = match to {
+                Some(hash) => job_queue.fetch_soul_version(&hash).await?,
+                None => recent.first().cloned(),
+            };
+            let from = match from {
+                Some(hash) => job_queue.fetch_soul_version(&hash).await?,
+                None => recent.get(1).cloned(),
+            };
+            match (from, to) {
+                (Some(from), Some(to)) => {
+                    println!("--- {} ({})\n+++ {} ({})", from.hash.trim(), from.recorded_at, to.hash.trim(), to.recorded_at);
+                    let diff = infrastructure::soul_history::diff_lines(&from.content, &to.content);
+                    println!("{}", infrastructure::soul_history::render_diff(&diff, false));
+                }
+                _ => error!("❌ Need two recorded versions of {} (see `soul history`)", source),
+            }
+        }
+        Commands::Soul { action: SoulAction::Reevaluate { source, limit, dry_run } } => {
+            let Some(current) = channels.soul_by_source(&source) else {
+                error!("❌ No channel or soul profile uses {}", source);
+                return Ok(());
+            };
+            if !dry_run {
+                audit_cli(&job_queue, "soul_reevaluate", Some(&source)).await;
+            }
+            let summary = soul_review::reevaluate_legacy_karma(&config.gemini_api_key, &job_queue, &source, current, limit, dry_run).await?;
+            info!("🧬 Soul review of {}{}: {} kept, {} revised, {} retired, {} undecided",
+                source, if dry_run { " (dry run)" } else { "" }, summary.kept, summary.revised, summary.retired, summary.undecided);
+        }
+        Commands::Styles => {
+            for name in style_manager.list_available_styles() {
+                let pipeline = style_manager.get_style(&name).pipeline().map(|p| p.names().join(" → ")).unwrap_or_else(|e| format!("⚠️ {}", e));
+                println!("{:<16} {}", name, pipeline);
+            }
+        }
+        Commands::Cancel { job_id } => {
+            audit_cli(&job_queue, "cancel", Some(&job_id)).await;
+            match job_queue.cancel_job(&job_id).await {
+                Ok(()) => info!("🛑 Job {} cancelled", job_id),
+                Err(e) => error!("❌ Failed to cancel: {}", e),
+            }
+        }
+        Commands::ValidateWorkflows => {
+            let results = infrastructure::workflow_doctor::lint_dir(&workflow_dir)?;
+            let broken = results.iter().filter(|(_, r)| !r.is_ok()).count();
+            println!("\n🧪 Workflow validation ({})", workflow_dir.display());
+            for (id, report) in &results {
+                if report.is_ok() {
+                    println!("   ✅ {}", id);
+                    continue;
+                }
+                println!("   ❌ {}", id);
+                for issue in &report.issues {
+                    println!("      - {}", issue.describe());
+                }
+            }
+            println!("   {} / {} workflow(s) OK", results.len() - broken, results.len());
+            if broken > 0 {
+                return Err(anyhow::anyhow!("{} workflow(s) failed validation", broken));
+            }
+        }
+        Commands::Workflows { action: WorkflowsAction::Check { fix } } => {
+            use infrastructure::workflow_doctor::{self, WorkflowRegistry};
+            let object_info = orchestrator.comfy_bridge.fetch_object_info().await?;
+            let dir = workflow_dir;
+            let mut registry = WorkflowRegistry::load(&dir);
+            let mut broken = 0;
+            for (id, path) in workflow_doctor::list_workflows(&dir)? {
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).map_err(|e| format!("invalid JSON: {}", e)));
+                let mut workflow = match parsed {
+                    Ok(v) => v,
+                    Err(e) => {
+                        error!("❌ {}: {}", id, e);
+                        registry.workflows.insert(id, workflow_doctor::RegistryEntry { ok: false, problems: vec![e] });
+                        broken += 1;
+                        continue;
+                    }
+                };
+                let mut report = workflow_doctor::check_workflow(&workflow, &object_info);
+                for m in &report.migrations {
+                    info!("🔧 {}: node {} input '{}' -> '{}'{}", id, m.node_id, m.from, m.to, if fix { "" } else { " (run with --fix to apply)" });
+                }
+                if fix && workflow_doctor::apply_migrations(&mut workflow, &report.migrations) > 0 {
+                    std::fs::write(&path, serde_json::to_string_pretty(&workflow)?)?;
+                    audit_cli(&job_queue, "workflow_fix", Some(&id)).await;
+                    report = workflow_doctor::check_workflow(&workflow, &object_info);
+                }
+                for issue in report.unresolved() {
+                    warn!("⚠️ {}: node {} ({}): {}", id, issue.node_id, issue.class_type, issue.message);
+                }
+                if report.is_ok() {
+                    info!("✅ {}: compatible", id);
+                } else {
+                    broken += 1;
+                }
+                registry.record(&id, &report);
+            }
+            registry.checked_at = chrono::Utc::now().to_rfc3339();
+            registry.save(&dir)?;
+            if broken > 0 {
+                error!("❌ {} workflow(s) need attention; Samsara will not pick them until they pass.", broken);
             }
         }
-        Commands::Generate { category, topic, remix, step } => {
+        Commands::Token { action: TokenAction::Create { name, scope } } => {
+            let scope: factory_core::api::ApiScope = scope.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+            let (token, secret) = job_queue.create_api_token(&name, scope).await?;
+            audit_cli(&job_queue, "token_create", Some(&token.name)).await;
+            info!("🔑 Token '{}' issued with the {} scope (id: {})", token.name, token.scope.as_str(), token.id);
+            println!("{}", secret);
+            if !config.auth.enabled {
+                warn!("🔓 [auth] enabled = false, so the token is not checked yet.");
+            }
+        }
+        Commands::Token { action: TokenAction::Revoke { name } } => {
+            match job_queue.revoke_api_token(&name).await {
+                Ok(true) => {
+                    audit_cli(&job_queue, "token_revoke", Some(&name)).await;
+                    info!("🔑 Token '{}' revoked", name);
+                }
+                Ok(false) => error!("❌ No active token named '{}'", name),
+                Err(e) => error!("❌ Failed to revoke token: {}", e),
+            }
+        }
+        Commands::Bgm { action: BgmAction::Scan { add } } => {
+            let mut manifest = infrastructure::bgm_manifest::BgmManifest::load(&bgm_path)?;
+            let (untracked, missing) = manifest.scan(&bgm_path);
+            for file in &missing {
+                warn!("⚠️ In manifest but missing on disk: {}", file);
+            }
+            for path in &untracked {
+                info!("🎵 Not in manifest: {}", path.display());
+            }
+            if add && !untracked.is_empty() {
+                for path in &untracked {
+                    let file = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    manifest.upsert(infrastructure::bgm_manifest::BgmTrack { file, ..Default::default() });
+                }
+                manifest.save(&bgm_path)?;
+                info!("📝 Added {} track(s) without moods. Tag them in {}.", untracked.len(), bgm_path.join(infrastructure::bgm_manifest::MANIFEST_FILE).display());
+            }
+            info!("🎶 {} track(s) in manifest, {} untracked, {} missing", manifest.tracks.len(), untracked.len(), missing.len());
+        }
+        Commands::Bgm { action: BgmAction::Import { file, moods, bpm, license, loopable, categories } } => {
+            let Some(name) = file.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                error!("❌ '{}' is not a file", file.display());
+                return Ok(());
+            };
+            let dest = bgm_path.join(&name);
+            if std::fs::canonicalize(&file).ok() != std::fs::canonicalize(&dest).ok() {
+                std::fs::copy(&file, &dest)?;
+            }
+            let mut manifest = infrastructure::bgm_manifest::BgmManifest::load(&bgm_path)?;
+            manifest.upsert(infrastructure::bgm_manifest::BgmTrack {
+                file: name.clone(),
+                moods: moods.iter().map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty()).collect(),
+                bpm,
+                license,
+                loopable,
+                categories,
+            });
+            manifest.save(&bgm_path)?;
+            info!("🎵 Imported '{}' into {}", name, bgm_path.display());
+        }
+        Commands::Postmortem { range } => {
+            let records = journal::read_journal(&journal_dir);
+            let Some(last) = records.last().map(|r| r.at) else {
+                warn!("⚠️ No journal records in {}", journal_dir.display());
+                return Ok(());
+            };
+            match journal::parse_range(&range, last) {
+                Ok((from, to)) => println!("{}", journal::render_postmortem(&records, from, to)),
+                Err(e) => error!("❌ {}", e),
+            }
+        }
+        Commands::Token { action: TokenAction::List } => {
+            for token in job_queue.list_api_tokens().await? {
+                let state = if token.revoked_at.is_some() { "revoked" } else { "active" };
+                println!("{}\t{}\t{}…\t{}\t{}\t{}", token.name, token.scope.as_str(), token.hint, state,
+                    token.last_used_at.as_deref().unwrap_or("never"), token.id);
+            }
+        }
+        Commands::Generate { category, topic, remix, step, template, variants, seed, reuse_seeds } => {
             let workflow_req = WorkflowRequest { 
                 category: category.clone(), 
                 topic: topic.clone(),
@@ -427,13 +1432,32 @@ async fn main() -> Result<(), anyhow::Error> {
                 style_name: String::new(), 
                 custom_style: None,
                 target_langs: vec!["ja".to_string(), "en".to_string()],
+                script_template: template.clone(),
+                seed,
+                reuse_seeds,
+                ..Default::default()
             };
         
             info!("🚀 Launching Production Pipeline...");
             
-            tokio::select! {
-                res = orchestrator.execute(workflow_req, &jail) => {
-                    match res {
+            let pipeline = async {
+                match variants {
+                    Some(count) => match crate::variants::generate(&orchestrator, &jail, workflow_req, count).await {
+                        Ok(records) => {
+                            println!("\n🎲 バリエーション生成完了！ ({} 本)", records.len());
+                            for r in records {
+                                match r.error {
+                                    Some(e) => println!("   ❌ v{} ({}): {}", r.index, r.project_id, e),
+                                    None => for v in r.output_videos {
+                                        println!("   🎥 v{} [{}] 話速 {} ファイル: {}", r.index, v.lang,
+                                            r.voice_speed.map(|s| format!("{:.2}", s)).unwrap_or_else(|| "既定".to_string()), v.path);
+                                    },
+                                }
+                            }
+                        }
+                        Err(e) => error!("❌ バリエーション生成が失敗: {}", e),
+                    },
+                    None => match orchestrator.execute(workflow_req, &jail).await {
                         Ok(res) => {
                             println!("\n🎬 動画生成完了！");
                             println!("   📝 タイトル: {}", res.concept.title);
@@ -445,14 +1469,36 @@ async fn main() -> Result<(), anyhow::Error> {
                         Err(e) => {
                             error!("❌ 生成パイプラインが失敗: {}", e);
                         }
-                    }
+                    },
                 }
+            };
+
+            tokio::select! {
+                _ = pipeline => {}
                 _ = signal::ctrl_c() => {
                     tracing::info!("🛑 SIGINT received. Shutting down gracefully...");
                 }
+                _ = shutdown.wait() => {
+                    tracing::info!("🛑 Stop requested. Abandoning the pipeline run...");
+                }
             }
         }
     }
 
+    // --- Teardown: Cron → Sidecars → Watchtower の順に閉じる ---
+    if let Err(e) = cron_scheduler.shutdown().await {
+        warn!("⚠️ Cron scheduler did not stop cleanly: {}", e);
+    } else {
+        info!("🌙 Cron scheduler stopped");
+    }
+    for supervisor in sidecar_supervisors {
+        supervisor.abort();
+    }
+    sidecar_manager.reap_current().await;
+    info!("👋 Shutdown complete ({})", shutdown.reason().unwrap_or_else(|| "command finished".to_string()));
+
+    shutdown.finish();
+    let _ = tokio::time::timeout(Duration::from_secs(5), wt_handle).await;
+
     Ok(())
 }
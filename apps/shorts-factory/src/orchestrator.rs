@@ -1,29 +1,68 @@
 use factory_core::contracts::{
-    ConceptRequest, TrendRequest, TrendResponse,
+    ConceptRequest, ConceptResponse, TrendRequest, TrendResponse,
     VideoRequest, MediaRequest, MediaResponse,
-    VoiceRequest, WorkflowRequest, WorkflowResponse
+    VoiceRequest, WorkflowRequest, WorkflowResponse,
+    AudioChapter, OutputAudio, OutputProfile, AestheticScore, CharacterRef, ModelStack, SafetyRejection, SceneSeed,
+    PRIMARY_ASPECT,
 };
-use factory_core::traits::{AgentAct, MediaEditor};
+use factory_core::traits::{AgentAct, ArtifactStore, MediaEditor};
 use factory_core::error::FactoryError;
-use infrastructure::trend_sonar::BraveTrendSonar;
-use infrastructure::concept_manager::ConceptManager;
-use infrastructure::comfy_bridge::ComfyBridgeClient;
+use factory_core::cost::{CostEntry, CostTracker};
+use factory_core::fingerprint::{self, FingerprintStore, VisualFingerprint};
+use infrastructure::trend_sources::CompositeTrendSource;
+use infrastructure::concept_manager::{self, ConceptManager};
+use infrastructure::comfy_bridge::{self, ComfyBridgeClient};
 use infrastructure::media_forge::MediaForgeClient;
 use infrastructure::voice_actor::VoiceActor;
+use infrastructure::forced_aligner::ForcedAligner;
+use infrastructure::subject_tracker::SubjectTracker;
 use infrastructure::sound_mixer::SoundMixer;
+use infrastructure::vision_judge::{VisionJudge, VisionVerdict};
+use infrastructure::aesthetic_scorer::AestheticScorer;
+use infrastructure::image_upscaler::ImageUpscaler;
+use infrastructure::script_template::{self, ScriptTemplates};
 use crate::supervisor::Supervisor;
 use crate::arbiter::{ResourceArbiter, ResourceUser};
-use crate::asset_manager::AssetManager;
-use tuning::StyleManager;
+use crate::asset_manager::{AssetManager, CLEAN_VIDEO_FILE, FINAL_AUDIO_FILE, SUBTITLES_FILE};
+use crate::subtitle_qa::{self, CpsTracker};
+use crate::channels::ChannelRegistry;
+use crate::characters::CharacterRegistry;
+use crate::approval::ApprovalGate;
+use crate::server::telemetry::{StageScope, TelemetryHub};
+use shared::config::{AestheticConfig, ApprovalConfig, ExportConfig, FingerprintConfig, ReframeConfig, SubtitleQaConfig, TemplatesConfig, UpscaleConfig, VisionQaConfig};
+use tuning::{FontRegistry, PipelineStage, StyleManager, SubtitlePosition};
 use async_trait::async_trait;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
+
+/// シーン画像の既定の ComfyUI ワークフロー
+const DEFAULT_WORKFLOW: &str = "shorts_standard_v1";
+/// 持ち込みの参照画像を IPAdapter に渡すときの重み
+const REFERENCE_IMAGE_WEIGHT: f32 = 0.6;
+/// 直近の動画とプロンプトが被ったシーンに足す変化の指示 (プロジェクトとシーンから決定的に選ぶ)
+const VARIATION_CUES: &[&str] = &[
+    "alternate camera angle",
+    "different color palette",
+    "unusual composition",
+    "different time of day",
+    "close-up framing",
+    "wide establishing shot",
+];
+
+/// 工程 images の成果 (シーン画像と、その選別の記録)
+#[derive(Default)]
+struct SceneRenders {
+    images: Vec<std::path::PathBuf>,
+    aesthetic_scores: Vec<AestheticScore>,
+    safety_rejections: Vec<SafetyRejection>,
+    similarity_nudges: Vec<String>,
+}
 
 /// 映像量産統括者 (ProductionOrchestrator)
 /// 
 /// 複数のアクターを協調させ、トレンド分析から動画完成までのパイプラインを管理する。
 pub struct ProductionOrchestrator {
-    pub trend_sonar: BraveTrendSonar,
+    pub trend_sonar: CompositeTrendSource,
     pub concept_manager: ConceptManager,
     pub voice_actor: VoiceActor,
     pub comfy_bridge: ComfyBridgeClient,
@@ -34,11 +73,36 @@ pub struct ProductionOrchestrator {
     pub style_manager: Arc<StyleManager>,
     pub asset_manager: Arc<AssetManager>,
     pub export_dir: String,
+    pub subtitle_qa: SubtitleQaConfig,
+    pub reframe: ReframeConfig,
+    /// 9:16 以外に書き出す画角
+    pub export: ExportConfig,
+    pub upscaler: ImageUpscaler,
+    pub subject_tracker: Option<SubjectTracker>,
+    pub channels: Option<Arc<ChannelRegistry>>,
+    pub characters: Option<Arc<CharacterRegistry>>,
+    pub telemetry: Option<Arc<TelemetryHub>>,
+    pub approval: Option<Arc<ApprovalGate>>,
+    pub approval_cfg: ApprovalConfig,
+    pub vision_qa: VisionQaConfig,
+    pub vision_judge: Option<VisionJudge>,
+    pub aesthetic: AestheticConfig,
+    pub aesthetic_scorer: Option<AestheticScorer>,
+    pub script_templates: ScriptTemplates,
+    pub templates_cfg: TemplatesConfig,
+    pub artifact_store: Option<Arc<dyn ArtifactStore>>,
+    pub cost: CostTracker,
+    pub fingerprint: FingerprintConfig,
+    pub fingerprints: Option<Arc<dyn FingerprintStore>>,
+    pub fonts: Arc<FontRegistry>,
+    /// `resources/workflows` にあるワークフロー ID (空なら未確認としてシーン指定を検証しない)
+    pub workflows: Vec<String>,
 }
 
 impl ProductionOrchestrator {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        trend_sonar: BraveTrendSonar,
+        trend_sonar: CompositeTrendSource,
         concept_manager: ConceptManager,
         voice_actor: VoiceActor,
         comfy_bridge: ComfyBridgeClient,
@@ -62,7 +126,837 @@ impl ProductionOrchestrator {
             style_manager,
             asset_manager,
             export_dir,
+            subtitle_qa: SubtitleQaConfig::default(),
+            reframe: ReframeConfig::default(),
+            export: ExportConfig::default(),
+            upscaler: ImageUpscaler::default(),
+            subject_tracker: None,
+            channels: None,
+            characters: None,
+            telemetry: None,
+            approval: None,
+            approval_cfg: ApprovalConfig::default(),
+            vision_qa: VisionQaConfig::default(),
+            vision_judge: None,
+            aesthetic: AestheticConfig::default(),
+            aesthetic_scorer: None,
+            script_templates: ScriptTemplates::default(),
+            templates_cfg: TemplatesConfig::default(),
+            artifact_store: None,
+            cost: CostTracker::default(),
+            fingerprint: FingerprintConfig::default(),
+            fingerprints: None,
+            fonts: Arc::new(FontRegistry::default()),
+            workflows: Vec::new(),
+        }
+    }
+
+    /// 字幕読み速度ゲートの設定を差し替える
+    pub fn with_subtitle_qa(mut self, subtitle_qa: SubtitleQaConfig) -> Self {
+        self.subtitle_qa = subtitle_qa;
+        self
+    }
+
+    /// 画像品質ゲートを有効にする (enabled = false なら何もしない)
+    pub fn with_vision_qa(mut self, vision_qa: VisionQaConfig, gemini_api_key: &str) -> Result<Self, FactoryError> {
+        self.vision_judge = vision_qa.enabled.then(|| VisionJudge::new(gemini_api_key, &vision_qa.model)).transpose()?;
+        self.vision_qa = vision_qa;
+        Ok(self)
+    }
+
+    /// 静止画の美的スコアゲートを有効にする (enabled = false なら何もしない)
+    pub fn with_aesthetic(mut self, aesthetic: AestheticConfig, gemini_api_key: &str) -> Result<Self, FactoryError> {
+        self.aesthetic_scorer = aesthetic.enabled.then(|| AestheticScorer::new(
+            &aesthetic.scorer_url,
+            gemini_api_key,
+            &aesthetic.model,
+            std::time::Duration::from_secs(aesthetic.timeout_secs),
+        )).transpose()?;
+        self.aesthetic = aesthetic;
+        Ok(self)
+    }
+
+    /// 生成済みのシーン素材 (静止画 `scene_{i}.png` か動画 `scene_{i}_motion.*`)
+    fn scene_asset(project_root: &std::path::Path, i: usize) -> Option<std::path::PathBuf> {
+        std::iter::once(project_root.join(format!("visuals/scene_{}.png", i)))
+            .chain(comfy_bridge::VIDEO_OUTPUT_EXTS.iter().map(|ext| project_root.join(format!("visuals/scene_{}_motion.{}", i, ext))))
+            .find(|p| p.exists())
+    }
+
+    /// ComfyUI でシーンを 1 つ生成して `visuals/` に配置し、そのパスと安全性チェックで棄却した出力を返す
+    /// (動画を出力するワークフローなら `scene_{i}_motion.*`。Ken Burns の `scene_{i}.mp4` と衝突させない)
+    #[allow(clippy::too_many_arguments)]
+    async fn render_scene(&self, prompt: &str, workflow_id: &str, models: &ModelStack, seed: u64, project_root: &std::path::Path, i: usize, cast: &[CharacterRef], cost: &CostTracker) -> Result<(std::path::PathBuf, Vec<SafetyRejection>), FactoryError> {
+        let video_req = VideoRequest {
+            prompt: prompt.to_string(),
+            workflow_id: workflow_id.to_string(),
+            input_image: None,
+            characters: cast.to_vec(),
+            models: models.clone(),
+            seed: Some(seed),
+        };
+        let started = std::time::Instant::now();
+        let res = self.supervisor.enforce_act(&self.comfy_bridge, video_req).await?;
+        cost.record(CostEntry::gpu("render_scene", started.elapsed().as_secs_f64())).await;
+        let temp_path = self.supervisor.jail().root().join(&res.output_path);
+        let img_path = if comfy_bridge::is_video_output(&temp_path) {
+            let ext = temp_path.extension().and_then(|e| e.to_str()).unwrap_or("mp4").to_ascii_lowercase();
+            project_root.join(format!("visuals/scene_{}_motion.{}", i, ext))
+        } else {
+            project_root.join(format!("visuals/scene_{}.png", i))
+        };
+        std::fs::create_dir_all(img_path.parent().unwrap()).ok();
+        std::fs::copy(&temp_path, &img_path).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        self.comfy_bridge.delete_output_debris(&res.job_id);
+        Ok((img_path, res.safety_rejections))
+    }
+
+    /// 工程 images: シーン画像を描き、使い回し検知・美的ゲート・Vision QA で引き直す
+    async fn render_images(
+        &self,
+        input: &WorkflowRequest,
+        project_id: &str,
+        project_root: &std::path::Path,
+        concept_res: &ConceptResponse,
+        style: &tuning::StyleProfile,
+        cost: &CostTracker,
+    ) -> Result<SceneRenders, FactoryError> {
+        let mut scenes = SceneRenders::default();
+        // 2.1. 画像生成 x 3 (Intro, Body, Outro) — ポッドキャスト・持ち込み映像では不要
+        let skip_images = input.output_profile == OutputProfile::Podcast || input.footage.is_some();
+        let visual_prompts = if skip_images { &[][..] } else { &concept_res.visual_prompts[..] };
+        // 企画のどこかで触れられたキャラクターは全シーンに載せる (シーン間で見た目を揃える)
+        let mut cast = match &self.characters {
+            Some(registry) if !visual_prompts.is_empty() => {
+                let mut texts = vec![input.topic.as_str(), concept_res.title.as_str(), concept_res.common_style.as_str()];
+                texts.extend(visual_prompts.iter().map(String::as_str));
+                registry.cast_for(&texts)
+            }
+            _ => Vec::new(),
+        };
+        // 持ち込みの参照画像はキャラクターの参照画像より優先する (ワークフローが受け取れる参照は 1 枚)
+        if let Some(rel) = input.reference_image.as_deref().filter(|_| !visual_prompts.is_empty()) {
+            let path = Self::resolve_upload(project_root, rel)?;
+            info!("🖼️ Using user-supplied reference image: {}", path.display());
+            cast.insert(0, CharacterRef {
+                name: "reference".to_string(),
+                reference_image: Some((path.to_string_lossy().to_string(), REFERENCE_IMAGE_WEIGHT)),
+                ..Default::default()
+            });
+        }
+        if !cast.is_empty() {
+            info!("🧍 Characters in this concept: {}", cast.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", "));
+        }
+        // シードの元: 明示指定 > ジョブ ID > プロジェクト ID (同じジョブ・同じプロンプトなら同じ絵になる)
+        let seed_key = match input.seed {
+            Some(seed) => seed.to_string(),
+            None => crate::job_worker::job_id_for_project(project_id).unwrap_or(project_id).to_string(),
+        };
+        let mut seeds = self.asset_manager.load_seeds(project_id);
+        let recorded = if input.reuse_seeds { seeds.clone() } else { Vec::new() };
+        if input.reuse_seeds && recorded.is_empty() && !visual_prompts.is_empty() {
+            warn!("🎲 --reuse-seeds: No seeds.json for {}. Deriving fresh seeds.", project_id);
+        }
+        // 使い回し検知: 直近の動画のシーンの指紋
+        let recent_fingerprints = match &self.fingerprints {
+            Some(store) if !visual_prompts.is_empty() => store
+                .recent_fingerprints(self.fingerprint.lookback_days, project_id)
+                .await
+                .map_err(|e| warn!("⚠️ Fingerprint: Failed to load recent fingerprints: {}", e))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        for (i, visual_prompt) in visual_prompts.iter().enumerate() {
+            let mut img_path = Self::scene_asset(project_root, i).unwrap_or_default();
+            // 記録済みのシード・プロンプト・ワークフローで前回の絵をそのまま再現する (引き直し・QA はしない)
+            if let Some(prev) = recorded.iter().find(|s| s.scene == i) {
+                info!("🎲 Scene {}: Re-rendering with recorded seed {} ('{}')", i, prev.seed, prev.workflow_id);
+                let (path, rejected) = self.render_scene(&prev.prompt, &prev.workflow_id, &style.models, prev.seed, project_root, i, &cast, cost).await?;
+                img_path = path;
+                scenes.safety_rejections.extend(rejected);
+            } else if !img_path.exists() {
+                let workflow_id = self.scene_workflow(i, input, concept_res, style);
+                if workflow_id != DEFAULT_WORKFLOW {
+                    info!("🧩 Scene {}: Rendering with workflow '{}'", i, workflow_id);
+                }
+                let mut full_prompt = format!("{}, {}", concept_res.common_style, visual_prompt);
+                // 共通スタイルはチャンネル内で揃っているので、シーン固有のプロンプトだけで比べる
+                let prompt_hash = fingerprint::prompt_simhash(visual_prompt);
+                if let Some(similar) = recent_fingerprints.iter().find(|f| fingerprint::distance(f.prompt_hash, prompt_hash) <= self.fingerprint.max_prompt_distance) {
+                    let cue = VARIATION_CUES[(derive_seed(&seed_key, i, visual_prompt, 0) % VARIATION_CUES.len() as u64) as usize];
+                    full_prompt = format!("{}, {}", full_prompt, cue);
+                    let note = format!("scene {}: visual prompt matched {} scene {}; added '{}'", i, similar.project_id, similar.scene, cue);
+                    info!("🧬 Fingerprint: {}", note);
+                    scenes.similarity_nudges.push(note);
+                }
+                let mut prompt = full_prompt.clone();
+                let mut regenerations = 0;
+                let mut aesthetic = AestheticScore { scene: i, ..Default::default() };
+                let mut rerolls = 0;
+                let mut renders = 0;
+                let mut nudges = 0;
+                let mut image_hash;
+                let seed = loop {
+                    // 再生成ごとに試行回数をシードに混ぜるので、引き直しは別の絵になる (それでも再実行すれば同じ順に再現する)
+                    let seed = derive_seed(&seed_key, i, &prompt, renders);
+                    renders += 1;
+                    let (path, rejected) = self.render_scene(&prompt, &workflow_id, &style.models, seed, project_root, i, &cast, cost).await?;
+                    img_path = path;
+                    scenes.safety_rejections.extend(rejected);
+                    // 使い回し検知: 直近の動画の静止画と見分けがつかなければシードを変えて引き直す
+                    image_hash = self.image_fingerprint(&img_path).await;
+                    let max_image_distance = self.fingerprint.max_image_distance;
+                    if let Some(similar) = image_hash.and_then(|h| recent_fingerprints.iter().find(|f| f.image_hash.is_some_and(|r| fingerprint::distance(r, h) <= max_image_distance))) {
+                        if nudges < self.fingerprint.max_nudges {
+                            nudges += 1;
+                            let note = format!("scene {}: render looked like {} scene {}; re-rolled seed", i, similar.project_id, similar.scene);
+                            warn!("🧬 Fingerprint: {} ({}/{})", note, nudges, self.fingerprint.max_nudges);
+                            scenes.similarity_nudges.push(note);
+                            continue;
+                        }
+                        warn!("🧬 Fingerprint: Scene {} still resembles {} after {} re-rolls. Keeping last render.", i, similar.project_id, nudges);
+                    }
+                    // 美的ゲート: プロンプトはそのままシードだけ変えて引き直す (Vision QA より安いので先に見る)
+                    if let Some(score) = self.score_aesthetic(&img_path, project_root).await {
+                        aesthetic.attempts.push(score);
+                        aesthetic.below_threshold = score < self.aesthetic.min_score;
+                        if aesthetic.below_threshold && rerolls < self.aesthetic.max_regenerations {
+                            rerolls += 1;
+                            warn!("🎨 Aesthetic: Scene {} scored {:.2} < {:.2}. Re-rolling seed ({}/{})...",
+                                i, score, self.aesthetic.min_score, rerolls, self.aesthetic.max_regenerations);
+                            continue;
+                        }
+                        if aesthetic.below_threshold {
+                            warn!("🎨 Aesthetic: Scene {} still below threshold after {} re-rolls. Keeping last render.", i, rerolls);
+                        }
+                    }
+                    let Some(verdict) = self.judge_scene(&img_path, visual_prompt, project_root).await else { break seed };
+                    if verdict.score >= self.vision_qa.min_score {
+                        break seed;
+                    }
+                    if regenerations >= self.vision_qa.max_regenerations {
+                        warn!("👁️ Vision QA: Scene {} still below threshold ({:.2} < {:.2}) after {} regenerations. Keeping last render.",
+                            i, verdict.score, self.vision_qa.min_score, regenerations);
+                        break seed;
+                    }
+                    regenerations += 1;
+                    warn!("👁️ Vision QA: Scene {} scored {:.2} ({}). Regenerating ({}/{})...",
+                        i, verdict.score, verdict.issues.join("; "), regenerations, self.vision_qa.max_regenerations);
+                    prompt = corrected_prompt(&full_prompt, &verdict.issues);
+                };
+                if !aesthetic.attempts.is_empty() {
+                    scenes.aesthetic_scores.push(aesthetic);
+                }
+                if let Some(store) = self.fingerprints.as_ref().filter(|_| !input.self_test) {
+                    let fp = VisualFingerprint { project_id: project_id.to_string(), scene: i, prompt_hash, image_hash };
+                    if let Err(e) = store.record_fingerprint(&fp).await {
+                        warn!("⚠️ Fingerprint: Failed to record scene {} of {}: {}", i, project_id, e);
+                    }
+                }
+                seeds.retain(|s| s.scene != i);
+                seeds.push(SceneSeed { scene: i, seed, workflow_id, prompt });
+                seeds.sort_by_key(|s| s.scene);
+                if let Err(e) = self.asset_manager.save_seeds(project_id, &seeds) {
+                    warn!("⚠️ Failed to record scene seeds for {}: {}", project_id, e);
+                }
+            }
+            scenes.images.push(img_path);
+        }
+        Ok(scenes)
+    }
+
+    /// 工程 upscale: 静止画を拡大する (失敗したシーンは元の大きさのまま)
+    async fn upscale_images(&self, images: &mut [std::path::PathBuf], project_root: &std::path::Path, stage: &StageScope, cost: &CostTracker) {
+        stage.enter("upscale");
+        // GPU の方式で拡大できたシーンの時間だけを計上する (lanczos に落ちたシーンは CPU)
+        let mut gpu_secs = 0.0;
+        for (i, img_path) in images.iter_mut().enumerate() {
+            if comfy_bridge::is_video_output(img_path) {
+                continue;
+            }
+            let before = self.media_forge.probe_dimensions(img_path).await.ok();
+            let upscaled = project_root.join(format!("visuals/scene_{}_{}x.png", i, self.upscaler.factor()));
+            let started = std::time::Instant::now();
+            match self.upscaler.upscale(img_path, &upscaled, &self.media_forge, &self.comfy_bridge).await {
+                Ok(res) => {
+                    if res.on_gpu {
+                        gpu_secs += started.elapsed().as_secs_f64();
+                    }
+                    let after = self.media_forge.probe_dimensions(&res.path).await.ok();
+                    let dims = |d: Option<(u32, u32)>| d.map(|(w, h)| format!("{}x{}", w, h)).unwrap_or_else(|| "?".into());
+                    let backend = if res.on_gpu { self.upscaler.backend_name() } else { "ffmpeg" };
+                    info!("🔍 Upscale ({}): Scene {} {} -> {}", backend, i, dims(before), dims(after));
+                    *img_path = res.path;
+                }
+                Err(e) => warn!("⚠️ Upscale: Scene {} kept at its original size: {}", i, e),
+            }
+        }
+        if gpu_secs > 0.0 {
+            cost.record(CostEntry::gpu("upscale", gpu_secs)).await;
+        }
+    }
+
+    /// 工程 voice: 言語ごとのナレーション (lang -> 幕ごとの音声)
+    async fn voice_scripts(
+        &self,
+        input: &WorkflowRequest,
+        concept_res: &ConceptResponse,
+        target_langs: &[String],
+        project_root: &std::path::Path,
+        stage: &StageScope,
+    ) -> Result<std::collections::HashMap<String, Vec<std::path::PathBuf>>, FactoryError> {
+        let mut audio_assets = std::collections::HashMap::new();
+        // 2.2. TTS生成 for each lang
+        stage.enter("voice");
+        for lang in target_langs {
+            if let Some(script) = concept_res.scripts.iter().find(|s| &s.lang == lang) {
+                let acts = vec![&script.script_intro, &script.script_body, &script.script_outro];
+
+                // 持ち込みナレーション: VoiceActor をスキップし、台本に合わせて分割する
+                if let Some(vo) = input.voiceover.as_ref().filter(|vo| &vo.lang == lang) {
+                    let vo_path = Self::resolve_upload(project_root, &vo.path)?;
+                    info!("🎙️ Using user-supplied voiceover for language {}: {}", lang, vo_path.display());
+                    let total = self.media_forge.get_duration(&vo_path).await?;
+                    let texts: Vec<&str> = acts.iter().map(|s| s.as_str()).collect();
+                    let out_paths: Vec<std::path::PathBuf> = (0..acts.len())
+                        .map(|i| project_root.join(format!("audio/scene_{}_{}.wav", i, lang)))
+                        .collect();
+                    ForcedAligner::align_and_split(&vo_path, total, &texts, &out_paths).await?;
+                    audio_assets.insert(lang.clone(), out_paths);
+                    continue;
+                }
+
+                info!("🗣️ Generating TTS for language: {}", lang);
+                let mut lang_audios = Vec::new();
+    
+                for (i, script_text) in acts.into_iter().enumerate() {
+                    let audio_path = project_root.join(format!("audio/scene_{}_{}.wav", i, lang));
+                    if !audio_path.exists() {
+                        let voice_req = VoiceRequest {
+                            text: script_text.clone(),
+                            voice: String::new(), // Auto-map by lang in VoiceActor
+                            speed: input.variant.as_ref().and_then(|v| v.voice_speed),
+                            lang: Some(lang.clone()),
+                        };
+                        let v_res = self.supervisor.enforce_act(&self.voice_actor, voice_req).await?;
+                        let temp_v = self.supervisor.jail().root().join(&v_res.audio_path);
+                        std::fs::create_dir_all(audio_path.parent().unwrap()).ok();
+                        std::fs::copy(&temp_v, &audio_path).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+                    }
+                    lang_audios.push(audio_path);
+                }
+                audio_assets.insert(lang.clone(), lang_audios);
+            }
         }
+        Ok(audio_assets)
+    }
+
+    /// シーンの知覚ハッシュ。使い回し検知が無効・測定失敗時は None
+    async fn image_fingerprint(&self, scene: &std::path::Path) -> Option<u64> {
+        self.fingerprints.as_ref()?;
+        self.media_forge.perceptual_hash(scene).await
+            .map_err(|e| warn!("⚠️ Fingerprint: Failed to hash {}: {}", scene.display(), e))
+            .ok()
+    }
+
+    /// シーンを採点する。ゲート無効・採点失敗時は None (シーンはそのまま採用)
+    async fn judge_scene(&self, scene: &std::path::Path, visual_prompt: &str, project_root: &std::path::Path) -> Option<VisionVerdict> {
+        let judge = self.vision_judge.as_ref()?;
+        let frames = self.media_forge
+            .extract_frames(scene, self.vision_qa.frames_per_scene, &project_root.join("visuals/qa"))
+            .await
+            .map_err(|e| warn!("⚠️ Vision QA: Frame extraction failed for {}: {}", scene.display(), e))
+            .ok()?;
+        judge.score(&frames, visual_prompt).await
+            .map_err(|e| warn!("⚠️ Vision QA: Scoring failed, accepting scene as-is: {}", e))
+            .ok()
+    }
+
+    /// 静止画の美的スコア (動画シーンは中間の 1 フレーム)。ゲート無効・採点失敗時は None (画像はそのまま採用)
+    async fn score_aesthetic(&self, scene: &std::path::Path, project_root: &std::path::Path) -> Option<f32> {
+        let scorer = self.aesthetic_scorer.as_ref()?;
+        let image = if comfy_bridge::is_video_output(scene) {
+            self.media_forge
+                .extract_frames(scene, 1, &project_root.join("visuals/qa"))
+                .await
+                .map_err(|e| warn!("⚠️ Aesthetic: Frame extraction failed for {}: {}", scene.display(), e))
+                .ok()?
+                .into_iter()
+                .next()?
+        } else {
+            scene.to_path_buf()
+        };
+        scorer
+            .score(&image)
+            .await
+            .map_err(|e| warn!("⚠️ Aesthetic: Scoring failed, accepting image as-is: {}", e))
+            .ok()
+    }
+
+    /// タイトルを重ねたサムネイルを AssetManager のプロジェクトに保存する
+    async fn render_thumbnail(
+        &self,
+        project_id: &str,
+        project_root: &std::path::Path,
+        sources: &[std::path::PathBuf],
+        title: &str,
+        lang: &str,
+        style: &tuning::StyleProfile,
+    ) -> Result<std::path::PathBuf, FactoryError> {
+        let _forge_guard = self.arbiter.acquire_forge(ResourceUser::Forging).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Arbiter error: {}", e) })?;
+
+        let title_ass = if title.trim().is_empty() {
+            None
+        } else {
+            let font = style.title_font.clone().unwrap_or_else(|| self.fonts.font_for(lang));
+            let ass = MediaForgeClient::build_thumbnail_title_ass(title, &font, self.fonts.profile(lang).title_size + 24);
+            let path = project_root.join("thumbnail_title.ass");
+            std::fs::write(&path, ass).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write thumbnail title: {}", e) })?;
+            Some(path)
+        };
+
+        let out = self.asset_manager.thumbnail_path(project_id);
+        self.media_forge.render_thumbnail(sources, title_ass.as_deref(), &out).await?;
+        info!("🖼️ Thumbnail saved: {}", out.display());
+        Ok(out)
+    }
+
+    /// 幕ごとの音声を連結・ミックスし、チャプター付き MP3 として納品する
+    #[allow(clippy::too_many_arguments)]
+    async fn export_podcast(
+        &self,
+        project_id: &str,
+        project_root: &std::path::Path,
+        lang: &str,
+        audios: &[std::path::PathBuf],
+        title: &str,
+        category: &str,
+        mood: Option<&str>,
+        style: &tuning::StyleProfile,
+        export_dir: &str,
+    ) -> Result<OutputAudio, FactoryError> {
+        let _forge_guard = self.arbiter.acquire_forge(ResourceUser::Forging).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Arbiter error: {}", e) })?;
+
+        info!("🎧 Exporting podcast episode for language: {}", lang);
+        let lang_proj_root = project_root.join(lang);
+        std::fs::create_dir_all(&lang_proj_root).ok();
+
+        // チャプター = 幕 (Intro / Body / Outro) の実測タイミング
+        let labels = chapter_labels_for_lang(lang);
+        let mut chapters = Vec::new();
+        let mut current_time = 0.0f32;
+        for (i, audio_path) in audios.iter().enumerate() {
+            let duration = self.media_forge.get_duration(audio_path).await?;
+            chapters.push(AudioChapter {
+                title: labels.get(i).copied().unwrap_or("Chapter").to_string(),
+                start_secs: current_time,
+                end_secs: current_time + duration,
+            });
+            current_time += duration;
+        }
+
+        let combined_a = self.media_forge.concatenate_clips(audios.iter().map(|p| p.to_string_lossy().to_string()).collect(), format!("a_{}.wav", lang)).await?;
+        let finalized_a = lang_proj_root.join("final_audio.wav");
+        self.sound_mixer.mix_and_finalize(&std::path::PathBuf::from(combined_a), category, mood, &finalized_a, style).await?;
+
+        let mp3_path = lang_proj_root.join("podcast.mp3");
+        self.media_forge.export_podcast_mp3(&finalized_a, title, &chapters, &mp3_path).await?;
+        if let Ok(json) = serde_json::to_string_pretty(&chapters) {
+            std::fs::write(lang_proj_root.join("chapters.json"), json).ok();
+        }
+
+        let delivered = infrastructure::workspace_manager::WorkspaceManager::deliver_output(
+            &format!("{}_{}", project_id, lang),
+            &mp3_path,
+            export_dir,
+        ).await?;
+
+        Ok(OutputAudio {
+            lang: lang.to_string(),
+            path: delivered.to_string_lossy().to_string(),
+            chapters,
+        })
+    }
+
+    /// LLM を使わない定型台本を使えるようにする
+    pub fn with_script_templates(mut self, templates: ScriptTemplates, cfg: TemplatesConfig) -> Self {
+        self.script_templates = templates;
+        self.templates_cfg = cfg;
+        self
+    }
+
+    /// 定型台本で組むならテンプレート名と理由を返す (None なら Gemini)
+    ///
+    /// センシティブな日 (The Ethical Circuit Breaker) > リクエストの指定 > チャンネルの指定 の順。
+    /// センシティブな日に退避用テンプレートが無ければ、トレンド項目をそのまま読み上げないよう Gemini に任せる。
+    fn script_template_for(&self, requested: Option<&str>, channel: &str, trend_items: &[factory_core::traits::TrendItem]) -> Option<(String, String)> {
+        let cfg = &self.templates_cfg;
+        let selected = match requested.filter(|n| !n.is_empty()) {
+            Some(name) => Some((name.to_string(), "requested".to_string())),
+            None => self.channels.as_ref().and_then(|channels| {
+                let name = &channels.get(channel).profile.script_template;
+                (!name.is_empty()).then(|| (name.clone(), format!("channel '{}'", channel)))
+            }),
+        };
+        if let Some(keyword) = script_template::find_sensitive(trend_items, &cfg.sensitive_keywords) {
+            if self.script_templates.contains(&cfg.sensitive_template) {
+                return Some((cfg.sensitive_template.clone(), format!("sensitive trend '{}'", keyword)));
+            }
+            if let Some((name, _)) = selected {
+                warn!("⚠️ Ethical Circuit Breaker: Sensitive trend '{}', not slotting it into template '{}' (no sensitive_template loaded). Using Gemini.", keyword, name);
+                return None;
+            }
+        }
+        selected
+    }
+
+    /// 言語別フォント (起動時に検証済みのもの) を差し替える
+    pub fn with_fonts(mut self, fonts: Arc<FontRegistry>) -> Self {
+        self.fonts = fonts;
+        self
+    }
+
+    /// シーン別ワークフローの検証に使う、利用可能なワークフロー ID を設定する
+    pub fn with_workflows(mut self, workflows: Vec<String>) -> Self {
+        self.workflows = workflows;
+        self
+    }
+
+    /// シーン i のワークフロー: ジョブの明示指定 > 企画の指定 > スタイルの指定 > 既定。
+    /// 未知の ID・パスとして使えない ID は警告して次の候補へ回す
+    fn scene_workflow(&self, i: usize, input: &WorkflowRequest, concept: &ConceptResponse, style: &tuning::StyleProfile) -> String {
+        if let Some(id) = &input.workflow_id {
+            if factory_core::contracts::is_valid_workflow_id(id) {
+                return id.clone();
+            }
+            warn!("🧩 Workflow '{}' requested by the job is not a valid workflow id. Ignoring.", id);
+        }
+        let candidates = [("concept", concept.scene_workflows.get(i)), ("style", style.scene_workflows.get(i))];
+        for (source, id) in candidates {
+            let Some(id) = id.map(|s| s.trim()).filter(|s| !s.is_empty()) else { continue };
+            let known = factory_core::contracts::is_valid_workflow_id(id)
+                && (self.workflows.is_empty() || self.workflows.iter().any(|w| w == id));
+            if known {
+                return id.to_string();
+            }
+            warn!("🧩 Workflow '{}' for scene {} ({}) is not in resources/workflows. Ignoring.", id, i, source);
+        }
+        DEFAULT_WORKFLOW.to_string()
+    }
+
+    /// チャンネル別ルーティング (スタイル候補・納品先) を有効にする
+    pub fn with_channels(mut self, channels: Arc<ChannelRegistry>) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// 再登場キャラクターの見た目固定を有効にする
+    pub fn with_characters(mut self, characters: Arc<CharacterRegistry>) -> Self {
+        self.characters = Some(characters);
+        self
+    }
+
+    /// 工程の遷移をテレメトリへ配信する
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryHub>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Gemini・Brave・ComfyUI の使用量をジョブごとのコスト台帳に記録する
+    pub fn with_cost_tracker(mut self, cost: CostTracker) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// シーン素材の指紋を `store` に積み、直近の動画と似すぎたシーンをずらす (enabled = false なら何もしない)
+    pub fn with_fingerprints(mut self, store: Arc<dyn FingerprintStore>, cfg: FingerprintConfig) -> Self {
+        self.fingerprints = cfg.enabled.then_some(store);
+        self.fingerprint = cfg;
+        self
+    }
+
+    /// 納品物を保管先へ送り、URL を output_videos に記録する
+    pub fn with_artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifact_store = Some(store);
+        self
+    }
+
+    /// 納品済みファイルを保管先へ送る (失敗してもローカル納品は有効なので URL なしで続行)
+    async fn store_artifact(&self, project_id: &str, delivered: &std::path::Path) -> Option<String> {
+        let store = self.artifact_store.as_ref()?;
+        let file_name = delivered.file_name()?.to_string_lossy().to_string();
+        match store.put(&format!("{}/{}", project_id, file_name), delivered).await {
+            Ok(url) => {
+                info!("☁️ ArtifactStore ({}): {} -> {}", store.backend(), file_name, url);
+                Some(url)
+            }
+            Err(e) => {
+                warn!("⚠️ ArtifactStore ({}): Failed to store {}: {}", store.backend(), file_name, e);
+                None
+            }
+        }
+    }
+
+    /// 字幕ファイルの写しを納品する (元は投稿時の添付とプレビューで使うので残す)
+    async fn deliver_caption(&self, project_id: &str, lang: &str, source: &std::path::Path, export_dir: &str) -> Option<std::path::PathBuf> {
+        let ext = source.extension()?.to_str()?;
+        let staging = source.parent()?.join("delivery");
+        let staged = staging.join(format!("captions.{}", ext));
+        let copied = match std::fs::create_dir_all(&staging) {
+            Ok(()) => std::fs::copy(source, &staged),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = copied {
+            warn!("⚠️ Captions [{}]: Failed to stage {}: {}", lang, source.display(), e);
+            return None;
+        }
+        match infrastructure::workspace_manager::WorkspaceManager::deliver_output(&format!("{}_{}", project_id, lang), &staged, export_dir).await {
+            Ok(delivered) => {
+                self.store_artifact(project_id, &delivered).await;
+                Some(delivered)
+            }
+            Err(e) => {
+                warn!("⚠️ Captions [{}]: Failed to deliver {}: {}", lang, staged.display(), e);
+                None
+            }
+        }
+    }
+
+    /// 承認チェックポイントを有効にする
+    pub fn with_approval(mut self, gate: Arc<ApprovalGate>, config: ApprovalConfig) -> Self {
+        self.approval = Some(gate);
+        self.approval_cfg = config;
+        self
+    }
+
+    /// チェックポイントで人間の判断を待つ。却下なら ApprovalRejected で制作を打ち切る。
+    async fn checkpoint(&self, name: &str, description: String, stage: &StageScope) -> Result<(), FactoryError> {
+        let Some(gate) = &self.approval else { return Ok(()) };
+        stage.enter(&format!("awaiting_approval:{}", name));
+        if gate.request(description, &self.approval_cfg).await {
+            info!("✅ Approval: '{}' approved. Resuming pipeline.", name);
+            Ok(())
+        } else {
+            warn!("🛑 Approval: '{}' rejected. Stopping pipeline.", name);
+            Err(FactoryError::ApprovalRejected { checkpoint: name.to_string() })
+        }
+    }
+
+    /// 連載メタデータ (話数・既刊リンク・説明欄用の相互宣伝行) をプロジェクトと納品先に書き出す。
+    /// 読み込む処理は無く、投稿時に説明欄へ貼るための控え。失敗しても納品は止めない。
+    fn write_episode_sidecar(
+        episode: &factory_core::contracts::EpisodeContext,
+        title: &str,
+        project_id: &str,
+        project_root: &std::path::Path,
+        export_dir: &str,
+    ) {
+        let sidecar = serde_json::json!({
+            "series": episode.series_name,
+            "series_id": episode.series_id,
+            "episode_number": episode.episode_number,
+            "title": title,
+            "previous_episodes": episode.previous_episodes,
+            "cross_promotion": episode.cross_promotion(),
+        });
+        let json = match serde_json::to_string_pretty(&sidecar) {
+            Ok(json) => json,
+            Err(e) => return warn!("⚠️ Series: Failed to serialize episode metadata: {}", e),
+        };
+        for path in [
+            project_root.join("series.json"),
+            std::path::Path::new(export_dir).join(format!("{}_series.json", project_id)),
+        ] {
+            if let Err(e) = std::fs::write(&path, &json) {
+                warn!("⚠️ Series: Failed to write {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// 台本の無い言語だけ英語の台本から翻訳する (失敗した言語は飛ばす)。1 つでも足せたら true
+    async fn localize_missing(&self, project_id: &str, concept: &mut ConceptResponse, target_langs: &[String], cost: &CostTracker) -> bool {
+        let missing: Vec<String> = concept_manager::localization_targets(target_langs)
+            .into_iter()
+            .filter(|l| !concept.scripts.iter().any(|s| &s.lang == l))
+            .collect();
+        if missing.is_empty() {
+            return false;
+        }
+        let Some(english) = concept.scripts.iter().find(|s| s.lang == "en").cloned() else {
+            warn!("⚠️ Concept: {} has no English script to localize [{}] from", project_id, missing.join(", "));
+            return false;
+        };
+        let mut added = false;
+        for lang in &missing {
+            match self.concept_manager.localize(&concept.title, &english, lang, cost).await {
+                Ok(script) => {
+                    concept.scripts.push(script);
+                    added = true;
+                }
+                Err(e) => warn!("⚠️ Concept: Could not localize {} to '{}': {}", project_id, lang, e),
+            }
+        }
+        added
+    }
+
+    /// チャンネルの納品先 (未設定ならグローバルの export_dir)
+    fn export_dir_for(&self, channel: &str) -> String {
+        self.channels.as_ref()
+            .map(|c| c.get(channel).profile.export_dir.clone())
+            .unwrap_or_else(|| self.export_dir.clone())
+    }
+
+    /// 名前付き SOUL が指定されていれば、その本文をコンセプト生成のペルソナとして渡す
+    fn persona_for(&self, soul: Option<&str>) -> Option<String> {
+        let channels = self.channels.as_ref()?;
+        soul.and_then(|name| channels.soul(name)).map(|s| s.content.clone())
+    }
+
+    /// チャンネルで許可されたスタイル一覧
+    fn styles_for(&self, channel: &str) -> Vec<String> {
+        let all = self.style_manager.list_available_styles();
+        let Some(channels) = &self.channels else { return all };
+        let profile = &channels.get(channel).profile;
+        let allowed: Vec<String> = all.iter().filter(|s| profile.allows_style(s)).cloned().collect();
+        if allowed.is_empty() { all } else { allowed }
+    }
+
+    /// 持ち込み素材リフレームの設定を差し替える (検出サイドカー URL があればトラッカーを構築)
+    pub fn with_reframe(mut self, reframe: ReframeConfig) -> Result<Self, FactoryError> {
+        self.subject_tracker = (!reframe.detector_url.is_empty()).then(|| {
+            SubjectTracker::new(
+                &reframe.detector_url,
+                reframe.sample_interval_secs,
+                std::time::Duration::from_secs(reframe.detector_timeout_secs),
+            )
+        }).transpose()?;
+        self.reframe = reframe;
+        Ok(self)
+    }
+
+    /// 本編 (9:16) と一緒に書き出す画角 (`[export]`)
+    pub fn with_export(mut self, export: ExportConfig) -> Self {
+        self.export = export;
+        self
+    }
+
+    /// `upscale` 工程の拡大方式 (`[upscale]`)
+    pub fn with_upscale(mut self, upscale: &UpscaleConfig) -> Result<Self, FactoryError> {
+        self.upscaler = ImageUpscaler::new(upscale)?;
+        Ok(self)
+    }
+
+    /// 持ち込み映像を 9:16 に整える (既に縦型ならスケールのみ)
+    async fn prepare_footage(
+        &self,
+        project_root: &std::path::Path,
+        footage: &factory_core::contracts::FootageInput,
+    ) -> Result<std::path::PathBuf, FactoryError> {
+        let src = Self::resolve_upload(project_root, &footage.path)?;
+        // 素材の更新時刻を名前に入れ、素材が差し替えられたら作り直す
+        let modified = std::fs::metadata(&src)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let visuals = project_root.join("visuals");
+        let out = visuals.join(format!("footage_vertical_{}.mp4", modified));
+        if out.exists() {
+            return Ok(out);
+        }
+        // 差し替え前の素材から作ったものは片付ける
+        for entry in std::fs::read_dir(&visuals).into_iter().flatten().flatten() {
+            if entry.file_name().to_string_lossy().starts_with("footage_vertical") {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+
+        let (w, h) = self.media_forge.probe_dimensions(&src).await?;
+        let keyframes = match &self.subject_tracker {
+            // 横長素材のみ追従対象 (縦型素材はクロップ幅 = 全幅なので意味がない)
+            Some(tracker) if footage.track_subject && w * 16 > h * 9 => match tracker.track(&src).await {
+                Ok(kfs) => kfs,
+                Err(e) => {
+                    warn!("⚠️ Reframe: Subject tracking failed, falling back to center crop: {}", e);
+                    Vec::new()
+                }
+            },
+            _ => Vec::new(),
+        };
+        info!("📐 Reframe: {}x{} footage -> 1080x1920", w, h);
+        std::fs::create_dir_all(&visuals).ok();
+        self.media_forge.reframe_vertical(&src, &keyframes, self.reframe.smoothing, &out).await
+    }
+
+    /// 持ち込み素材のパスを解決する (プロジェクト外への脱出は拒否)
+    fn resolve_upload(project_root: &std::path::Path, rel: &str) -> Result<std::path::PathBuf, FactoryError> {
+        let candidate = project_root.join(rel);
+        let resolved = candidate.canonicalize().map_err(|_| FactoryError::MediaNotFound {
+            path: candidate.display().to_string(),
+        })?;
+        let root = project_root.canonicalize().map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        if !resolved.starts_with(&root) {
+            return Err(FactoryError::SecurityViolation {
+                reason: format!("Upload path escapes project directory: {}", rel),
+            });
+        }
+        Ok(resolved)
+    }
+    /// トレンド検索から台本までを作り、プロジェクトに保存する (バリエーション制作では 1 回だけ呼ぶ)
+    pub async fn produce_concept(&self, input: &WorkflowRequest, project_id: &str, cost: &CostTracker) -> Result<ConceptResponse, FactoryError> {
+        let trend_req = TrendRequest { category: input.category.clone() };
+        let trend_res: TrendResponse = self.supervisor.enforce_act(&self.trend_sonar, trend_req).await?;
+        if self.trend_sonar.source_names().contains(&"brave") {
+            cost.record(CostEntry::call("brave", "trends")).await;
+        }
+        // シリーズ制作: 前編のコンセプトを引き継ぐ (見つからなければ単発として続行)
+        let previous_part = input.series_parent.as_deref().and_then(|parent| {
+            self.asset_manager.load_concept(parent)
+                .map_err(|e| warn!("⚠️ Series: Could not load parent concept '{}': {}", parent, e))
+                .ok()
+        });
+        let concept_req = ConceptRequest { 
+            topic: input.topic.clone(),
+            category: input.category.clone(),
+            trend_items: trend_res.items,
+            available_styles: self.styles_for(&input.channel),
+            available_workflows: self.workflows.clone(),
+            previous_part: previous_part.clone(),
+            persona: self.persona_for(input.soul.as_deref()),
+            hook_style: input.hook_style.clone(),
+            available_moods: self.sound_mixer.moods(),
+            available_sfx: self.sound_mixer.sfx_kinds(),
+            target_langs: input.target_langs.clone(),
+            cost: cost.clone(),
+        };
+        let mut res = match self.script_template_for(input.script_template.as_deref(), &input.channel, &concept_req.trend_items) {
+            Some((name, reason)) => {
+                info!("🧩 Concept: Using script template '{}' ({}). Gemini is not called.", name, reason);
+                let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+                let mut res = self.script_templates.render(&name, &concept_req, &date)?;
+                // テンプレートに無い言語は英語の台本から翻訳する
+                self.localize_missing(project_id, &mut res, &input.target_langs, cost).await;
+                res
+            }
+            None => self.supervisor.enforce_act(&self.concept_manager, concept_req).await?,
+        };
+        if let Some(prev) = previous_part {
+            info!("📚 Series: Continuing from '{}' (style: {})", prev.title, prev.style_profile);
+            res.common_style = prev.common_style;
+            res.style_profile = prev.style_profile;
+        }
+        if let Some(ep) = &input.episode {
+            res.title = ep.format_title(&res.title);
+            for v in &mut res.title_variants {
+                v.title = ep.format_title(&v.title);
+            }
+            info!("📺 Series: '{}' episode #{} -> '{}'", ep.series_name, ep.episode_number, res.title);
+        }
+        self.asset_manager.save_concept(project_id, &res)?;
+        Ok(res)
     }
 }
 
@@ -79,37 +973,44 @@ impl AgentAct for ProductionOrchestrator {
         info!("🏭 Aiome Video Forge: Starting Pipeline for topic '{}'", input.topic);
 
         // --- Phase 1: Concept & Setup ---
-        let project_id = input.remix_id.unwrap_or_else(|| {
+        let project_id = input.remix_id.clone().unwrap_or_else(|| {
             format!("{}_{}", input.category, chrono::Utc::now().format("%Y%m%d_%H%M%S"))
         });
         let project_root = self.asset_manager.init_project(&project_id)?;
+        let stage = StageScope::new(self.telemetry.clone(), &project_id);
+        stage.enter("concept");
+        let cost = self.cost.for_job(crate::job_worker::job_id_for_project(&project_id), Some(&project_id));
+        let mut export_dir = self.export_dir_for(&input.channel);
+        // バリエーションは親プロジェクト名のフォルダにまとめて納品する
+        if let Some(variant) = &input.variant {
+            export_dir = std::path::Path::new(&export_dir).join(&variant.set_id).to_string_lossy().to_string();
+        }
+        let channel_styles = self.styles_for(&input.channel);
         
         // target_langs の決定（指定なしなら ja + en）
-        let target_langs = if input.target_langs.is_empty() {
-            vec!["ja".to_string(), "en".to_string()]
-        } else {
-            input.target_langs.clone()
-        };
+        let mut target_langs = concept_manager::normalize_langs(&input.target_langs);
+        if target_langs.is_empty() {
+            target_langs = vec!["ja".to_string(), "en".to_string()];
+        }
 
         // コンセプト取得
-        let concept_res = if input.skip_to_step.is_some() {
+        let mut concept_res = if input.skip_to_step.is_some() {
              self.asset_manager.load_concept(&project_id)?
         } else {
-            let trend_req = TrendRequest { category: input.category.clone() };
-            let trend_res: TrendResponse = self.supervisor.enforce_act(&self.trend_sonar, trend_req).await?;
-            let concept_req = ConceptRequest { 
-                topic: input.topic.clone(),
-                category: input.category.clone(),
-                trend_items: trend_res.items,
-                available_styles: self.style_manager.list_available_styles(),
-            };
-            let res = self.supervisor.enforce_act(&self.concept_manager, concept_req).await?;
-            self.asset_manager.save_concept(&project_id, &res)?;
-            res
+            self.produce_concept(&input, &project_id, &cost).await?
         };
-
+        // 再開時に言語を足した場合は、英語の台本から足りない分だけ翻訳する
+        if input.skip_to_step.is_some() && self.localize_missing(&project_id, &mut concept_res, &target_langs, &cost).await {
+            self.asset_manager.save_concept(&project_id, &concept_res)?;
+        }
         // スタイル決定
-        let base_style_name = if !input.style_name.is_empty() { &input.style_name } else { &concept_res.style_profile };
+        let mut base_style_name = if !input.style_name.is_empty() { &input.style_name } else { &concept_res.style_profile };
+        if !channel_styles.contains(base_style_name) {
+            if let Some(fallback) = channel_styles.first() {
+                warn!("🎨 Style '{}' is not enabled for channel '{}'. Using '{}'.", base_style_name, input.channel, fallback);
+                base_style_name = fallback;
+            }
+        }
         let mut style = self.style_manager.get_style(base_style_name);
         if let Some(custom) = &input.custom_style {
             if let Some(v) = custom.zoom_speed { style.zoom_speed = v; }
@@ -119,70 +1020,91 @@ impl AgentAct for ProductionOrchestrator {
             if let Some(v) = custom.ducking_ratio { style.ducking_ratio = v; }
             if let Some(v) = custom.fade_duration { style.fade_duration = v; }
         }
+        // 工程表: styles.toml の pipeline (未指定なら従来の順)
+        let pipeline = style.pipeline().map_err(|reason| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("style '{}': {}", style.name, reason),
+        })?;
+        if !style.pipeline.is_empty() {
+            info!("🧱 Pipeline ({}): {}", style.name, pipeline.names().join(" → "));
+        }
+        if input.output_profile == OutputProfile::Video && input.footage.is_none() && !pipeline.runs(PipelineStage::Images) {
+            return Err(FactoryError::Infrastructure {
+                reason: format!("Style '{}' has no 'images' stage and no footage was supplied", style.name),
+            });
+        }
+
+        // 承認チェックポイント①: 台本を見てから GPU 時間を使う
+        if self.approval_cfg.after_concept && input.skip_to_step.is_none() {
+            let script = concept_res.scripts.first();
+            let description = format!(
+                "📝 **Concept Review** `{}`\n**Title:** {}\n**Style:** {}\n**Intro:** {}\n**Body:** {}\n**Outro:** {}",
+                project_id,
+                concept_res.title,
+                base_style_name,
+                script.map(|s| s.display_intro.as_str()).unwrap_or(&concept_res.display_intro),
+                script.map(|s| s.display_body.as_str()).unwrap_or(&concept_res.display_body),
+                script.map(|s| s.display_outro.as_str()).unwrap_or(&concept_res.display_outro),
+            );
+            self.checkpoint("concept", description, &stage).await?;
+        }
 
         // --- Phase 2: Asset Generation (Exclusive GPU Access) ---
         info!("💎 Phase 2: Asset Generation (GPU Exclusive)...");
+        stage.enter("assets");
         let mut audio_assets = std::collections::HashMap::new(); // lang -> Vec<PathBuf>
         let mut image_assets = Vec::new(); // Vec<PathBuf>
+        let mut aesthetic_scores = Vec::new();
+        let mut safety_rejections = Vec::new();
+        let mut similarity_nudges = Vec::new();
 
         {
             let _gpu_guard = self.arbiter.acquire_gpu(ResourceUser::Generating).await
                 .map_err(|e| FactoryError::Infrastructure { reason: format!("Arbiter error: {}", e) })?;
 
-            // 2.1. 画像生成 x 3 (Intro, Body, Outro)
-            for (i, visual_prompt) in concept_res.visual_prompts.iter().enumerate() {
-                let img_path = project_root.join(format!("visuals/scene_{}.png", i));
-                if !img_path.exists() {
-                    let full_prompt = format!("{}, {}", concept_res.common_style, visual_prompt);
-                    let video_req = VideoRequest {
-                        prompt: full_prompt,
-                        workflow_id: "shorts_standard_v1".to_string(),
-                        input_image: None,
-                    };
-                    let res = self.supervisor.enforce_act(&self.comfy_bridge, video_req).await?;
-                    let temp_path = self.supervisor.jail().root().join(&res.output_path);
-                    std::fs::create_dir_all(img_path.parent().unwrap()).ok();
-                    std::fs::copy(&temp_path, &img_path).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
-                    self.comfy_bridge.delete_output_debris(&res.job_id);
-                }
-                image_assets.push(img_path);
-            }
-
-            // 2.2. TTS生成 for each lang
-            for lang in &target_langs {
-                if let Some(script) = concept_res.scripts.iter().find(|s| &s.lang == lang) {
-                    info!("🗣️ Generating TTS for language: {}", lang);
-                    let mut lang_audios = Vec::new();
-                    let acts = vec![&script.script_intro, &script.script_body, &script.script_outro];
-                    
-                    for (i, script_text) in acts.into_iter().enumerate() {
-                        let audio_path = project_root.join(format!("audio/scene_{}_{}.wav", i, lang));
-                        if !audio_path.exists() {
-                            let voice_req = VoiceRequest {
-                                text: script_text.clone(),
-                                voice: String::new(), // Auto-map by lang in VoiceActor
-                                speed: None,
-                                lang: Some(lang.clone()),
-                            };
-                            let v_res = self.supervisor.enforce_act(&self.voice_actor, voice_req).await?;
-                            let temp_v = self.supervisor.jail().root().join(&v_res.audio_path);
-                            std::fs::create_dir_all(audio_path.parent().unwrap()).ok();
-                            std::fs::copy(&temp_v, &audio_path).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
-                        }
-                        lang_audios.push(audio_path);
+            // 工程表の順に素材を作る (画像・アップスケール・音声)
+            for step in pipeline.steps() {
+                match step {
+                    PipelineStage::Images => {
+                        let scenes = self.render_images(&input, &project_id, &project_root, &concept_res, &style, &cost).await?;
+                        image_assets = scenes.images;
+                        aesthetic_scores = scenes.aesthetic_scores;
+                        safety_rejections = scenes.safety_rejections;
+                        similarity_nudges = scenes.similarity_nudges;
                     }
-                    audio_assets.insert(lang.clone(), lang_audios);
+                    PipelineStage::Upscale => self.upscale_images(&mut image_assets, &project_root, &stage, &cost).await,
+                    PipelineStage::Voice => audio_assets = self.voice_scripts(&input, &concept_res, &target_langs, &project_root, &stage).await?,
+                    _ => {}
                 }
             }
         } // GPU Guard released
 
         // --- Phase 3: Forge & Parallel Composition ---
         info!("🔥 Phase 3: Forge (Video Composition)...");
+        stage.enter("forge");
+        // 持ち込み映像: 全言語で共通の縦型素材 (長さ) を先に用意する
+        let vertical_footage = match (&input.footage, input.output_profile) {
+            (Some(footage), OutputProfile::Video) => {
+                let path = self.prepare_footage(&project_root, footage).await?;
+                let len = self.media_forge.get_duration(&path).await?.max(0.1);
+                Some((path, len))
+            }
+            _ => None,
+        };
         let mut output_videos = Vec::new();
+        let mut subtitle_stats = Vec::new();
+        let mut output_audios = Vec::new();
 
         for lang in &target_langs {
+            if input.output_profile == OutputProfile::Podcast {
+                if let Some(audios) = audio_assets.get(lang) {
+                    let episode = self.export_podcast(&project_id, &project_root, lang, audios, &concept_res.title, &input.category, Some(&concept_res.bgm_mood), &style, &export_dir).await?;
+                    output_audios.push(episode);
+                }
+                continue;
+            }
+
             if let (Some(audios), Some(script)) = (audio_assets.get(lang), concept_res.scripts.iter().find(|s| &s.lang == lang)) {
-                let _forge_guard = self.arbiter.acquire_forge(ResourceUser::Forging).await
+                let mut _forge_guard = self.arbiter.acquire_forge(ResourceUser::Forging).await
                     .map_err(|e| FactoryError::Infrastructure { reason: format!("Arbiter error: {}", e) })?;
 
                 info!("🎬 Forging video for language: {}", lang);
@@ -192,99 +1114,368 @@ impl AgentAct for ProductionOrchestrator {
                 // 3.1. Ken Burns / Subtitle Generation
                 let mut video_clips = Vec::new();
                 let mut srt_content = String::new();
+                // 焼き込み用の ASS に書き出す主字幕 (SRT は字幕ファイルとして納品・配信に使う)
+                let mut caption_cues = Vec::new();
                 let mut current_time = 0.0f32;
                 let mut srt_index = 1;
+                let max_cps = self.subtitle_qa.max_cps_for_lang(lang);
+                let mut cps_tracker = CpsTracker::new(lang, max_cps);
 
-                let displays = vec![&script.display_intro, &script.display_body, &script.display_outro];
+                let displays = [&script.display_intro, &script.display_body, &script.display_outro];
+                // 二言語字幕: 副言語の台本の表示テキストを同じ幕の尺に割り付ける
+                let dual = style.dual_captions.as_ref().filter(|d| d.secondary_lang != *lang).and_then(|d| {
+                    let secondary = concept_res.scripts.iter().find(|s| s.lang == d.secondary_lang);
+                    if secondary.is_none() {
+                        warn!("🈂️ Dual captions: No '{}' script for {}. Rendering {} captions only.", d.secondary_lang, project_id, lang);
+                    }
+                    secondary.map(|s| (d, [&s.display_intro, &s.display_body, &s.display_outro]))
+                });
+                let mut secondary_cues = Vec::new();
+                // 効果音の配置に使う幕の区間 (開始秒, 尺)
+                let mut act_spans = Vec::new();
 
-                for (i, (img_path, audio_path)) in image_assets.iter().zip(audios.iter()).enumerate() {
+                for (i, audio_path) in audios.iter().enumerate() {
                     let duration = self.media_forge.get_duration(audio_path).await.unwrap_or(5.0);
                     let clip_path = lang_proj_root.join(format!("clip_{}.mp4", i));
                     
-                    // Ken Burns
-                    let clip = self.comfy_bridge.apply_ken_burns_effect(img_path, duration, jail, &style).await?;
-                    let temp_clip = self.supervisor.jail().root().join(clip);
-                    std::fs::copy(&temp_clip, &clip_path).ok();
+                    if let Some((footage, footage_len)) = &vertical_footage {
+                        // 持ち込み映像を幕の長さに合わせて先頭から順に切り出す
+                        self.media_forge.cut_segment(footage, current_time % footage_len, duration, &clip_path).await?;
+                    } else {
+                        let Some(img_path) = image_assets.get(i) else { break };
+                        if comfy_bridge::is_video_output(img_path) {
+                            // 動画を出力するワークフロー: Ken Burns の代わりにナレーションの長さへループ・切り詰め
+                            self.media_forge.fit_clip(img_path, duration, &clip_path).await?;
+                        } else {
+                            // Ken Burns
+                            let clip = self.comfy_bridge.apply_ken_burns_effect(img_path, duration, jail, &style).await?;
+                            let temp_clip = self.supervisor.jail().root().join(clip);
+                            std::fs::copy(&temp_clip, &clip_path).ok();
+                        }
+                    }
                     video_clips.push(clip_path);
 
-                    // Subtitles
-                    let sentences = split_into_sentences(displays[i]);
-                    let total_chars: usize = sentences.iter().map(|s| s.chars().count()).sum();
-                    let mut accumulated = 0.0f32;
-                    for sentence in sentences {
-                        let ratio = sentence.chars().count() as f32 / total_chars as f32;
-                        let s_duration = duration * ratio;
-                        let start = format_srt_time(current_time + accumulated);
-                        let end = format_srt_time(current_time + accumulated + s_duration);
-                        srt_content.push_str(&format!("{}\n{} --> {}\n{}\n\n", srt_index, start, end, sentence));
+                    // Subtitles (CPS Gate: 読み速度超過なら表示テキストのみ圧縮)
+                    let mut display_text = displays[i].to_string();
+                    if self.subtitle_qa.enabled
+                        && self.subtitle_qa.compress_on_violation
+                        && subtitle_qa::cps(&display_text, duration) > max_cps
+                    {
+                        let max_chars = subtitle_qa::max_chars_for(duration, max_cps);
+                        warn!("📏 Subtitle QA: Act {} ({}) is {:.1} CPS (limit {:.1}). Requesting compression to {} chars.",
+                            i, lang, subtitle_qa::cps(&display_text, duration), max_cps, max_chars);
+                        match self.concept_manager.compress_subtitle(&display_text, lang, max_chars, &cost).await {
+                            Ok(short) if !short.is_empty() && subtitle_qa::visible_chars(&short) < subtitle_qa::visible_chars(&display_text) => {
+                                display_text = short;
+                                cps_tracker.mark_compressed();
+                            }
+                            Ok(_) => warn!("⚠️ Subtitle QA: Compression did not shorten act {} ({}). Keeping original.", i, lang),
+                            Err(e) => warn!("⚠️ Subtitle QA: Compression failed for act {} ({}): {}", i, lang, e),
+                        }
+                    }
+
+                    for (start, end, sentence) in timed_sentences(&display_text, current_time, duration) {
+                        cps_tracker.record(subtitle_qa::cps(&sentence, end - start));
+                        srt_content.push_str(&format!("{}\n{} --> {}\n{}\n\n", srt_index, format_srt_time(start), format_srt_time(end), sentence));
                         srt_index += 1;
-                        accumulated += s_duration;
+                        caption_cues.push((start, end, sentence));
+                    }
+                    if let Some((_, secondary)) = &dual {
+                        secondary_cues.extend(timed_sentences(secondary[i], current_time, duration));
                     }
+                    act_spans.push((current_time, duration));
                     current_time += duration;
                 }
 
-                let srt_path = lang_proj_root.join("subtitles.srt");
+                let srt_path = lang_proj_root.join(SUBTITLES_FILE);
+                let caption_track = match self.asset_manager.save_captions(&project_id, lang, &MediaForgeClient::srt_to_webvtt(&srt_content)) {
+                    Ok(path) => Some(path),
+                    Err(e) => {
+                        warn!("⚠️ Captions [{}]: Failed to save the WebVTT track: {}", lang, e);
+                        None
+                    }
+                };
                 std::fs::write(&srt_path, srt_content).ok();
 
+                let stats = cps_tracker.finish();
+                if stats.over_threshold > 0 {
+                    warn!("📏 Subtitle QA [{}]: {}/{} cues still above {:.1} CPS (max {:.1})", lang, stats.over_threshold, stats.cue_count, stats.threshold, stats.max_cps);
+                } else {
+                    info!("📏 Subtitle QA [{}]: avg {:.1} / max {:.1} CPS", lang, stats.avg_cps, stats.max_cps);
+                }
+                if let Ok(json) = serde_json::to_string_pretty(&stats) {
+                    std::fs::write(lang_proj_root.join("subtitle_qa.json"), json).ok();
+                }
+                subtitle_stats.push(stats);
+
                 // 3.2. Final Assembly per language
-                let combined_v = self.media_forge.concatenate_clips(video_clips.iter().map(|p| p.to_string_lossy().to_string()).collect(), format!("v_{}.mp4", lang)).await?;
+                // トランジションは映像だけに掛ける (つなぎ目で尺が変わらないので、ナレーション・字幕・効果音はそのまま)
+                let clip_paths: Vec<String> = video_clips.iter().map(|p| p.to_string_lossy().to_string()).collect();
+                let combined_v = match &style.transition {
+                    Some(transition) => self.media_forge.concatenate_with_transition(clip_paths, format!("v_{}.mp4", lang), transition).await?,
+                    None => self.media_forge.concatenate_clips(clip_paths, format!("v_{}.mp4", lang)).await?,
+                };
                 let combined_a = self.media_forge.concatenate_clips(audios.iter().map(|p| p.to_string_lossy().to_string()).collect(), format!("a_{}.wav", lang)).await?;
                 
-                let finalized_a = lang_proj_root.join("final_audio.wav");
-                self.sound_mixer.mix_and_finalize(&std::path::PathBuf::from(combined_a), &input.category, &finalized_a, &style).await?;
+                // 効果音は BGM とのミックスの前にナレーションへ重ねる (失敗しても効果音なしで続行)
+                let mut narration_a = std::path::PathBuf::from(combined_a);
+                let placements = if pipeline.runs(PipelineStage::Sfx) {
+                    self.sound_mixer.place_sfx(&act_spans, &concept_res.sfx_cues)
+                } else {
+                    Vec::new()
+                };
+                if !placements.is_empty() {
+                    match self.sound_mixer.overlay_sfx(&narration_a, &placements, &lang_proj_root.join("narration_sfx.wav")).await {
+                        Ok(path) => narration_a = path,
+                        Err(e) => warn!("⚠️ SFX [{}]: {}. Mixing without sound effects.", lang, e),
+                    }
+                }
+
+                let finalized_a = lang_proj_root.join(FINAL_AUDIO_FILE);
+                if pipeline.runs(PipelineStage::Bgm) {
+                    self.sound_mixer.mix_and_finalize(&narration_a, &input.category, Some(&concept_res.bgm_mood), &finalized_a, &style).await?;
+                } else {
+                    self.sound_mixer.normalize_only(&narration_a, &finalized_a).await?;
+                }
+
+                // 字幕なしマスターを残す (焼き込みを待たずに HLS プレビューできるように)
+                if let Err(e) = std::fs::copy(&combined_v, lang_proj_root.join(CLEAN_VIDEO_FILE)) {
+                    warn!("⚠️ Failed to keep the clean master for {} [{}]: {}", project_id, lang, e);
+                }
 
-                let style_with_font = format!("Fontname={},FontSize={}", font_for_lang(lang), font_size_for_lang(lang));
+                // 3.3. Opening Hook: 冒頭 2 秒のタイトルオーバーレイ (スタイルで ON/OFF)
+                let overlay_path = if pipeline.runs(PipelineStage::TitleOverlay) && style.title_overlay && !concept_res.title.trim().is_empty() {
+                    let font = style.title_font.clone().unwrap_or_else(|| self.fonts.font_for(lang));
+                    let ass = MediaForgeClient::build_title_overlay_ass(&concept_res.title, &font, self.fonts.profile(lang).title_size, TITLE_OVERLAY_SECS);
+                    let path = lang_proj_root.join("title_overlay.ass");
+                    std::fs::write(&path, ass).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write title overlay: {}", e) })?;
+                    Some(path.to_string_lossy().to_string())
+                } else {
+                    None
+                };
+
+                // 追加の画角は二言語字幕を載せず、字幕位置も既定のまま焼き直す
+                let variant_overlay = overlay_path.clone().map(std::path::PathBuf::from);
+
+                // 字幕テーマ (スタイル) と言語/文字体系ごとのフォント (fonts.toml) で焼き込み字幕の ASS を作る
+                let theme = style.subtitle_theme();
+                let caption_font = self.fonts.font_for(lang);
+                let caption_px = theme.size.unwrap_or_else(|| MediaForgeClient::subtitle_px(self.fonts.profile(lang).subtitle_size));
+                let mut caption_margin = theme.margin_v;
+                let secondary_subtitle_path = match &dual {
+                    Some((layout, _)) if !secondary_cues.is_empty() && pipeline.runs(PipelineStage::Captions) => {
+                        let from_top = theme.position == SubtitlePosition::Top;
+                        let (size, margin_v, primary_margin_v) = MediaForgeClient::dual_caption_placement(caption_px, theme.margin_v, from_top, layout);
+                        caption_margin = primary_margin_v;
+                        let ass = MediaForgeClient::build_secondary_caption_ass(&secondary_cues, &self.fonts.font_for(&layout.secondary_lang), size, margin_v, theme.position);
+                        let path = lang_proj_root.join("secondary_captions.ass");
+                        std::fs::write(&path, ass).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write secondary captions: {}", e) })?;
+                        info!("🈂️ Dual captions [{}]: {} '{}' cue(s) alongside the {} track", lang, secondary_cues.len(), layout.secondary_lang, lang);
+                        Some(path.to_string_lossy().to_string())
+                    }
+                    _ => None,
+                };
+                let captions_path = if pipeline.runs(PipelineStage::Captions) {
+                    let ass = MediaForgeClient::build_caption_ass(&caption_cues, &theme, &caption_font, caption_px, caption_margin);
+                    let path = lang_proj_root.join("captions.ass");
+                    std::fs::write(&path, ass).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write captions: {}", e) })?;
+                    Some(path)
+                } else {
+                    None
+                };
                 let media_req = MediaRequest {
                     video_path: combined_v,
                     audio_path: finalized_a.to_string_lossy().to_string(),
-                    subtitle_path: Some(srt_path.to_string_lossy().to_string()),
-                    force_style: Some(style_with_font),
+                    subtitle_path: captions_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+                    force_style: None,
+                    secondary_subtitle_path,
+                    overlay_path,
                 };
                 
                 let media_res: MediaResponse = self.supervisor.enforce_act(&self.media_forge, media_req).await?;
 
                 let final_path = std::path::PathBuf::from(media_res.final_path);
+
+                // 承認チェックポイント②: 最初の 1 本を確認してから残りの言語と納品へ進む
+                if output_videos.is_empty() && self.approval_cfg.after_first_render && !input.self_test {
+                    drop(_forge_guard); // 待機中に他ジョブの Forge を塞がない
+                    let description = format!(
+                        "🎬 **First Render Review** `{}` [{}]\n**Title:** {}\n**File:** {}",
+                        project_id, lang, concept_res.title, final_path.display()
+                    );
+                    self.checkpoint("first_render", description, &stage).await?;
+                    // 納品・アップロード・アスペクト派生も ffmpeg を回すため、Forge を取り直す
+                    _forge_guard = self.arbiter.acquire_forge(ResourceUser::Forging).await
+                        .map_err(|e| FactoryError::Infrastructure { reason: format!("Arbiter error: {}", e) })?;
+                    stage.enter("forge");
+                }
+
+                // セルフテストは確認用の 1 本なので、納品先にも保管先にも出さない
+                if input.self_test {
+                    output_videos.push(factory_core::contracts::OutputVideo {
+                        lang: lang.clone(),
+                        path: final_path.to_string_lossy().to_string(),
+                        url: None,
+                        aspect: PRIMARY_ASPECT.to_string(),
+                    });
+                    continue;
+                }
+
                 let delivered = infrastructure::workspace_manager::WorkspaceManager::deliver_output(
                     &format!("{}_{}", project_id, lang),
                     &final_path,
-                    &self.export_dir,
+                    &export_dir,
                 ).await?;
 
+                let url = self.store_artifact(&project_id, &delivered).await;
                 output_videos.push(factory_core::contracts::OutputVideo {
                     lang: lang.clone(),
                     path: delivered.to_string_lossy().to_string(),
+                    url,
+                    aspect: PRIMARY_ASPECT.to_string(),
                 });
+
+                // 字幕トラック (WebVTT / SRT) も本編と並べて納品する。投稿先への添付は `server::captions`
+                for source in caption_track.iter().chain(std::iter::once(&srt_path)) {
+                    self.deliver_caption(&project_id, lang, source, &export_dir).await;
+                }
+
+                // 3.4. 追加の画角: 字幕なしマスターから 1 回でまとめて書き出す (失敗しても本編の納品は済んでいる)
+                if !self.export.profiles.is_empty() {
+                    let targets: Vec<_> = self.export.profiles.iter()
+                        .map(|p| (p.clone(), lang_proj_root.join(format!("final_{}.mp4", p.name))))
+                        .collect();
+                    // 二言語字幕で持ち上げた主字幕は、副字幕を載せない画角では既定の位置に戻す
+                    let captions = match &captions_path {
+                        Some(path) if caption_margin != theme.margin_v => {
+                            let ass = MediaForgeClient::build_caption_ass(&caption_cues, &theme, &caption_font, caption_px, theme.margin_v);
+                            let variant_path = lang_proj_root.join("captions_export.ass");
+                            match std::fs::write(&variant_path, ass) {
+                                Ok(()) => Some(variant_path),
+                                Err(e) => {
+                                    warn!("⚠️ Export [{}]: Failed to write captions, reusing the dual-caption placement: {}", lang, e);
+                                    Some(path.clone())
+                                }
+                            }
+                        }
+                        other => other.clone(),
+                    };
+                    match self.media_forge.render_aspect_variants(
+                        &lang_proj_root.join(CLEAN_VIDEO_FILE),
+                        &finalized_a,
+                        captions.as_deref(),
+                        None,
+                        variant_overlay.as_deref(),
+                        &targets,
+                    ).await {
+                        Ok(paths) => {
+                            for ((profile, _), path) in targets.iter().zip(paths) {
+                                let delivered = infrastructure::workspace_manager::WorkspaceManager::deliver_output(
+                                    &format!("{}_{}_{}", project_id, lang, profile.name),
+                                    &path,
+                                    &export_dir,
+                                ).await?;
+                                let url = self.store_artifact(&project_id, &delivered).await;
+                                output_videos.push(factory_core::contracts::OutputVideo {
+                                    lang: lang.clone(),
+                                    path: delivered.to_string_lossy().to_string(),
+                                    url,
+                                    aspect: profile.aspect(),
+                                });
+                            }
+                        }
+                        Err(e) => warn!("⚠️ Export [{}]: Aspect variants failed, delivering 9:16 only: {}", lang, e),
+                    }
+                }
+            } else {
+                warn!("⚠️ Forge [{}]: No script or narration for this language. Skipping its video.", lang);
+            }
+        }
+
+        if !output_videos.is_empty() {
+            if let Err(e) = self.asset_manager.save_outputs(&project_id, &output_videos) {
+                warn!("⚠️ Failed to record delivered outputs for {}: {}", project_id, e);
             }
         }
 
-        let first_path = output_videos.first().map(|v| v.path.clone()).unwrap_or_default();
+        // --- Phase 4: Thumbnail (字幕の入っていない素材からベストフレームを選ぶ) ---
+        if input.output_profile == OutputProfile::Video && !output_videos.is_empty() && pipeline.runs(PipelineStage::Thumbnail) {
+            stage.enter("thumbnail");
+            let sources = match &vertical_footage {
+                Some((footage, _)) => vec![footage.clone()],
+                None => image_assets.clone(),
+            };
+            let lang = output_videos[0].lang.as_str();
+            if let Err(e) = self.render_thumbnail(&project_id, &project_root, &sources, &concept_res.title, lang, &style).await {
+                warn!("⚠️ Thumbnail: Generation failed, project will fall back to the video preview: {}", e);
+            }
+        }
+
+        if let Some(ep) = &input.episode {
+            Self::write_episode_sidecar(ep, &concept_res.title, &project_id, &project_root, &export_dir);
+        }
+
+        let first_path = output_videos.first().map(|v| v.path.clone())
+            .or_else(|| output_audios.first().map(|a| a.path.clone()))
+            .unwrap_or_default();
         
-        info!("🏆 Aiome Video Forge: Pipeline Completed for {} languages", output_videos.len());
+        info!("🏆 Aiome Video Forge: Pipeline Completed for {} languages", output_videos.len() + output_audios.len());
 
         Ok(WorkflowResponse {
             final_video_path: first_path,
             output_videos,
             concept: concept_res,
+            subtitle_stats,
+            output_audios,
+            aesthetic_scores,
+            safety_rejections,
+            similarity_nudges,
         })
     }
 }
 
-/// 言語別フォントマッピング
-fn font_for_lang(lang: &str) -> &str {
+/// オープニング・タイトルの表示時間 (秒)
+const TITLE_OVERLAY_SECS: f32 = 2.0;
+
+/// 言語別チャプター名 (Intro / Body / Outro の順)
+fn chapter_labels_for_lang(lang: &str) -> [&'static str; 3] {
     match lang {
-        "ja" => "Noto Sans JP Black",
-        "en" => "Inter Bold",
-        _ => "Noto Sans Bold",
+        "ja" => ["イントロ", "本編", "まとめ"],
+        _ => ["Intro", "Main", "Outro"],
     }
 }
 
-/// 言語別デフォルトフォントサイズ
-fn font_size_for_lang(lang: &str) -> i32 {
-    match lang {
-        "ja" => 18,
-        "en" => 12, // 英語は単語数が多くなりやすいため大幅に縮小
-        _ => 16,
+/// Vision QA で見つかった問題を避けるようにプロンプトを補正する
+fn corrected_prompt(base: &str, issues: &[String]) -> String {
+    let mut prompt = format!("{}, masterpiece, anatomically correct, well-formed hands, faithful to the subject", base);
+    if !issues.is_empty() {
+        prompt.push_str(&format!(", avoid: {}", issues.join(", ")));
+    }
+    prompt
+}
+
+/// シーンのシードを (シードの元, シーン番号, プロンプト, 試行回数) のハッシュから導く。
+/// JSON を読むブラウザでも桁落ちしないよう 53 ビットに収める
+fn derive_seed(key: &str, scene: usize, prompt: &str, attempt: u32) -> u64 {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(format!("{}\n{}\n{}\n{}", key, scene, attempt, prompt).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes) & ((1 << 53) - 1)
+}
+
+/// 幕の表示テキストを文に分け、文字数の比で幕の尺 (`start` 秒から `duration` 秒) に割り付ける
+fn timed_sentences(text: &str, start: f32, duration: f32) -> Vec<(f32, f32, String)> {
+    let sentences = split_into_sentences(text);
+    let total_chars: usize = sentences.iter().map(|s| s.chars().count()).sum();
+    let mut cues = Vec::new();
+    let mut accumulated = 0.0f32;
+    for sentence in sentences {
+        let s_duration = duration * sentence.chars().count() as f32 / total_chars as f32;
+        cues.push((start + accumulated, start + accumulated + s_duration, sentence));
+        accumulated += s_duration;
     }
+    cues
 }
 
 /// SRT 形式のタイムスタンプ文字列を生成 (HH:MM:SS,mmm)
@@ -333,3 +1524,31 @@ fn split_into_sentences(text: &str) -> Vec<String> {
     
     sentences
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeds_are_stable_per_job_scene_prompt_and_attempt() {
+        let seed = derive_seed("42", 0, "a cat, cinematic", 0);
+        assert_eq!(seed, derive_seed("42", 0, "a cat, cinematic", 0));
+        assert_ne!(seed, derive_seed("43", 0, "a cat, cinematic", 0));
+        assert_ne!(seed, derive_seed("42", 1, "a cat, cinematic", 0));
+        assert_ne!(seed, derive_seed("42", 0, "a dog, cinematic", 0));
+        assert_ne!(seed, derive_seed("42", 0, "a cat, cinematic", 1));
+        assert!(seed < 1 << 53);
+    }
+
+    #[test]
+    fn test_timed_sentences_split_act_by_character_share() {
+        let cues = timed_sentences("Hello there. Bye.", 10.0, 3.0);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].0, 10.0);
+        assert!((cues[1].1 - 13.0).abs() < 1e-4);
+        // "Hello there." 12 文字 : "Bye." 4 文字
+        assert!((cues[0].1 - 12.25).abs() < 1e-4);
+        assert_eq!(cues[1].0, cues[0].1);
+        assert!(timed_sentences("", 0.0, 3.0).is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! # Remote — CLI のリモートモード (`--remote <URL>`)
+//!
+//! ローカルのパイプライン (ComfyUI・TTS・DB) を起こさず、稼働中の Serve に `factory-client` で依頼する。
+//! API キーは `FACTORY_API_KEY`。API で受け付けているコマンドだけが使える。
+//! 一覧の表示はローカル実行と共通 (`print_*`)。
+
+use factory_client::{EpisodeRequest, FactoryClient, VariantsRequest, WorkflowRequest};
+use factory_core::traits::Job;
+use shared::watchtower::FailedJobSummary;
+use tracing::info;
+
+use crate::Commands;
+
+pub async fn run(url: &str, command: Option<Commands>) -> anyhow::Result<()> {
+    let mut client = FactoryClient::new(url)?;
+    if let Ok(key) = std::env::var(factory_client::API_KEY_ENV) {
+        client = client.with_api_key(&key);
+    }
+    match command.unwrap_or_else(crate::default_command) {
+        Commands::Generate { category, topic, remix, step, template, variants, seed, reuse_seeds } => {
+            let workflow = WorkflowRequest {
+                category,
+                topic,
+                remix_id: remix,
+                skip_to_step: step,
+                target_langs: vec!["ja".to_string(), "en".to_string()],
+                script_template: template,
+                seed,
+                reuse_seeds,
+                ..Default::default()
+            };
+            let accepted = match variants {
+                Some(count) => client.variants(&VariantsRequest { workflow, count }).await?,
+                None => client.remix(&workflow).await?,
+            };
+            info!("🛰️ {} accepted {} job {} ({})", client.base_url(), accepted.job_type, accepted.job_id, accepted.status);
+        }
+        Commands::SeriesEpisode { name, topic } => {
+            let accepted = client.enqueue_episode(&name, &EpisodeRequest { topic, style: None }).await?;
+            info!("🛰️ {} queued episode #{} of '{}' (job {})", client.base_url(), accepted.episode_number, accepted.series, accepted.job_id);
+        }
+        Commands::Jobs { failed: false } => print_jobs(&client.jobs().await?),
+        Commands::Jobs { failed: true } => print_failed_jobs(&client.failed_jobs().await?),
+        Commands::Karma { limit, action: None } => print_karma(&client.karma().await?.into_iter().take(limit).collect::<Vec<_>>()),
+        Commands::Styles => {
+            for name in client.styles().await? {
+                println!("{}", name);
+            }
+        }
+        Commands::Cancel { job_id } => {
+            client.cancel_job(&job_id).await?;
+            info!("🛰️ {} cancelled job {}", client.base_url(), job_id);
+        }
+        other => anyhow::bail!("{:?} is not available with --remote (run it on the Serve host)", other),
+    }
+    Ok(())
+}
+
+/// ジョブ一覧: ID・状態・スタイル・トピック
+pub fn print_jobs(jobs: &[Job]) {
+    for job in jobs {
+        println!("{}\t{}\t{}\t{}", job.id, job.status, job.style, job.topic);
+    }
+}
+
+/// Dead-letter キュー: ID・失敗時刻・手動リトライ回数・トピック・エラー
+pub fn print_failed_jobs(jobs: &[FailedJobSummary]) {
+    for job in jobs {
+        println!("{}\t{}\t{}\t{}\t{}", job.job_id, job.failed_at, job.requeue_count, job.topic,
+            job.error_message.as_deref().unwrap_or("-"));
+    }
+}
+
+/// 教訓: 種別・重み・スキル・本文 (`fetch_all_karma` / `GET /api/karma` の JSON)
+pub fn print_karma(karma: &[serde_json::Value]) {
+    let text = |k: &serde_json::Value, key: &str| k.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    for k in karma {
+        println!("{}\t{}\t{}\t{}", text(k, "karma_type"), k.get("weight").and_then(|v| v.as_i64()).unwrap_or(0),
+            text(k, "skill_id"), text(k, "lesson"));
+    }
+}
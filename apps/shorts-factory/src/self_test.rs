@@ -0,0 +1,173 @@
+//! # Self Test — 夜間の試運転
+//!
+//! 朝の Samsara が API を呼んでから ffmpeg・ComfyUI・TTS の故障に気づくのでは遅いので、
+//! 固定の短い台本 (Gemini・Brave を使わない) で 1 本だけ作り、字幕・美的ゲートまで通るかを確かめる。
+//! 通った試運転のプロジェクトは消し、落ちたものは調べられるように残す。
+
+use crate::orchestrator::ProductionOrchestrator;
+use factory_core::contracts::{ConceptResponse, LocalizedScript, WorkflowRequest, WorkflowResponse};
+use factory_core::error::FactoryError;
+use factory_core::traits::{AgentAct, MediaEditor};
+use shared::config::SelfTestConfig;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// 試運転のプロジェクト ID の接頭辞
+pub const PROJECT_PREFIX: &str = "selftest_";
+
+pub struct SelfTestReport {
+    pub project_id: String,
+    pub elapsed_secs: f64,
+    /// 見つかった問題 (空なら合格)
+    pub problems: Vec<String>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// 試運転の台本: 3 幕で合計 5 秒前後
+pub fn fixture_concept(lang: &str) -> ConceptResponse {
+    let [intro, body, outro] = if lang == "ja" {
+        ["動作確認です。", "問題ありません。", "また明日。"]
+    } else {
+        ["System check.", "All good.", "See you tomorrow."]
+    };
+    ConceptResponse {
+        title: "Self Test".to_string(),
+        display_intro: intro.to_string(),
+        display_body: body.to_string(),
+        display_outro: outro.to_string(),
+        script_intro: intro.to_string(),
+        script_body: body.to_string(),
+        script_outro: outro.to_string(),
+        scripts: vec![LocalizedScript {
+            lang: lang.to_string(),
+            display_intro: intro.to_string(),
+            display_body: body.to_string(),
+            display_outro: outro.to_string(),
+            script_intro: intro.to_string(),
+            script_body: body.to_string(),
+            script_outro: outro.to_string(),
+        }],
+        common_style: "simple flat illustration, soft colors".to_string(),
+        // 空ならチャンネルの先頭のスタイルになる
+        style_profile: String::new(),
+        visual_prompts: vec![
+            "a clear blue sky over a calm sea".to_string(),
+            "a small white lighthouse on a rock".to_string(),
+            "a sunset over the horizon".to_string(),
+        ],
+        // ワークフローは request の workflow_id (cfg.workflow) で固定する
+        scene_workflows: Vec::new(),
+        metadata: Default::default(),
+        title_variants: Vec::new(),
+        bgm_mood: String::new(),
+        sfx_cues: Vec::new(),
+    }
+}
+
+/// 制作結果を合否の条件に照らす
+pub fn check(res: &WorkflowResponse, cfg: &SelfTestConfig, duration_secs: Option<f64>) -> Vec<String> {
+    let mut problems = Vec::new();
+    match duration_secs {
+        None => problems.push("no video was produced".to_string()),
+        Some(d) if d < cfg.min_duration_secs || d > cfg.max_duration_secs => problems.push(format!(
+            "video is {:.1}s, expected {:.1}–{:.1}s", d, cfg.min_duration_secs, cfg.max_duration_secs
+        )),
+        Some(_) => {}
+    }
+    for stats in res.subtitle_stats.iter().filter(|s| s.over_threshold > 0) {
+        problems.push(format!(
+            "subtitle QA: {} cue(s) over {:.1} CPS in '{}' (max {:.1})",
+            stats.over_threshold, stats.threshold, stats.lang, stats.max_cps
+        ));
+    }
+    for score in res.aesthetic_scores.iter().filter(|s| s.below_threshold) {
+        problems.push(format!("aesthetic gate: scene {} stayed below the threshold ({:?})", score.scene, score.attempts));
+    }
+    problems
+}
+
+/// 試運転を 1 回行う
+pub async fn run(orchestrator: &ProductionOrchestrator, jail: &bastion::fs_guard::Jail, cfg: &SelfTestConfig) -> SelfTestReport {
+    let project_id = format!("{}{}", PROJECT_PREFIX, chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    let started = Instant::now();
+    info!("🧪 [Self Test] Rendering fixture '{}' with workflow '{}'...", project_id, cfg.workflow);
+    let problems = match render(orchestrator, jail, cfg, &project_id).await {
+        Ok(problems) => problems,
+        Err(e) => vec![format!("pipeline failed: {}", e)],
+    };
+    let report = SelfTestReport { project_id, elapsed_secs: started.elapsed().as_secs_f64(), problems };
+
+    if report.passed() {
+        info!("✅ [Self Test] Passed in {:.0}s.", report.elapsed_secs);
+        if let Err(e) = orchestrator.asset_manager.remove_project(&report.project_id) {
+            warn!("⚠️ [Self Test] {}", e);
+        }
+    } else {
+        warn!("🚨 [Self Test] Failed ({}): {}", report.project_id, report.problems.join("; "));
+    }
+    report
+}
+
+async fn render(
+    orchestrator: &ProductionOrchestrator,
+    jail: &bastion::fs_guard::Jail,
+    cfg: &SelfTestConfig,
+    project_id: &str,
+) -> Result<Vec<String>, FactoryError> {
+    orchestrator.asset_manager.init_project(project_id)?;
+    orchestrator.asset_manager.save_concept(project_id, &fixture_concept(&cfg.lang))?;
+    let req = WorkflowRequest {
+        category: "selftest".to_string(),
+        topic: "self test".to_string(),
+        remix_id: Some(project_id.to_string()),
+        // 保存済みの台本から始める (トレンド検索と Gemini を呼ばない)
+        skip_to_step: Some("assets".to_string()),
+        target_langs: vec![cfg.lang.clone()],
+        workflow_id: Some(cfg.workflow.clone()),
+        self_test: true,
+        ..Default::default()
+    };
+    let res = orchestrator.execute(req, jail).await?;
+    let duration = match res.output_videos.first() {
+        Some(video) => Some(orchestrator.media_forge.get_duration(std::path::Path::new(&video.path)).await? as f64),
+        None => None,
+    };
+    Ok(check(&res, cfg, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use factory_core::contracts::{AestheticScore, OutputVideo, SubtitleCpsStats};
+
+    #[test]
+    fn test_check_flags_duration_and_gates() {
+        let cfg = SelfTestConfig::default();
+        let concept = fixture_concept("ja");
+        assert_eq!(concept.scripts[0].lang, "ja");
+        assert_eq!(concept.visual_prompts.len(), 3);
+
+        let mut res = WorkflowResponse {
+            final_video_path: String::new(),
+            output_videos: vec![OutputVideo { lang: "en".into(), path: "out.mp4".into(), url: None, aspect: "9:16".into() }],
+            concept,
+            subtitle_stats: vec![SubtitleCpsStats { lang: "en".into(), threshold: 20.0, ..Default::default() }],
+            output_audios: vec![],
+            aesthetic_scores: vec![AestheticScore { scene: 0, attempts: vec![7.0], below_threshold: false }],
+            safety_rejections: vec![],
+            similarity_nudges: vec![],
+        };
+        assert!(check(&res, &cfg, Some(5.0)).is_empty());
+        assert_eq!(check(&res, &cfg, None), vec!["no video was produced".to_string()]);
+        assert_eq!(check(&res, &cfg, Some(40.0)).len(), 1);
+
+        res.subtitle_stats[0].over_threshold = 1;
+        res.aesthetic_scores[0].below_threshold = true;
+        assert_eq!(check(&res, &cfg, Some(5.0)).len(), 2);
+    }
+}
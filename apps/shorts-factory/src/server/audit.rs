@@ -0,0 +1,155 @@
+//! # Audit — 制御系の操作の監査ログ
+//!
+//! Serve API の変更系リクエストは、認証後にこのミドルウェアを通って `audit_log` に 1 行ずつ残る
+//! (操作者は API トークン / キーの名前、認証無効時は `anonymous`)。
+//! Discord 経由の操作は Watchtower が `ControlCommand::AsActor` で名乗り、`server::watchtower` が記録する。
+//! `GET /api/audit` で絞り込んで参照でき、直近 24 時間の集計は夜間ダイジェストで Discord に届く。
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use factory_core::api::AuditSummaryLine;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::server::router::AppState;
+
+/// `GET /api/audit` の既定件数と上限
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// 認証済みの操作者 (`server::auth` がリクエストに載せる)
+#[derive(Debug, Clone)]
+pub struct Actor(pub String);
+
+/// パスから (操作名, 対象) を決める。対象はパス中の ID・名前
+fn classify(method: &Method, path: &str) -> (&'static str, Option<String>) {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let action = match (method, segments.as_slice()) {
+        (_, ["api", "remix"]) => "enqueue",
+        (_, ["api", "variants"]) => "enqueue_variants",
+        (_, ["api", "series"]) => "enqueue_series",
+        (_, ["api", "series", _, "episodes"]) => "enqueue_episode",
+        (_, ["api", "series", _]) => "series_edit",
+        (_, ["api", "projects", _, "voiceover" | "footage"]) => "upload",
+        (_, ["api", "projects", _, "review-link"]) => "review_link",
+        (_, ["api", "jobs", _, "retry"]) => "retry",
+        (_, ["api", "jobs", _, "cancel"]) => "cancel",
+        (_, ["api", "jobs", _, "rate"]) => "rating",
+        (_, ["api", "styles", "reload"]) => "style_reload",
+        (&Method::DELETE, ["api", "styles", _]) => "style_delete",
+        (_, ["api", "styles", _]) => "style_edit",
+        (_, ["api", "karma"]) => "karma_inject",
+        (_, ["api", "system", "pause"]) => "pause",
+        (_, ["api", "system", "resume"]) => "resume",
+        (&Method::DELETE, ["api", "karma", _]) => "karma_delete",
+        (_, ["review", _]) => "review",
+        (_, ["hooks", "slack", "actions"]) => "slack_action",
+        (&Method::DELETE, ["api", "chaos", ..]) => "chaos_clear",
+        (_, ["api", "chaos", _]) => "chaos_arm",
+        _ => "other",
+    };
+    let target = match segments.as_slice() {
+        ["api", "styles", "reload"] | ["api", "system", _] => None,
+        ["api", _, target, ..] | ["review", target] => Some(target.to_string()),
+        _ => None,
+    };
+    (action, target)
+}
+
+/// 変更系リクエストを監査ログに残すミドルウェア (認証ミドルウェアの内側に置く)
+pub async fn record_mutations(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let actor = request.extensions().get::<Actor>().map(|a| a.0.clone());
+
+    let response = next.run(request).await;
+
+    let (action, target) = classify(&method, &path);
+    // レビューページは署名付きリンクそのものが身元
+    let actor = actor.unwrap_or_else(|| match path.as_str() {
+        p if p.starts_with("/review/") => "review link",
+        p if p.starts_with("/hooks/slack/") => "slack",
+        _ => "anonymous",
+    }.to_string());
+    let detail = format!("{} {} -> {}", method, path, response.status().as_u16());
+    if let Err(e) = state.job_queue.record_audit("api", &actor, action, target.as_deref(), Some(&detail)).await {
+        warn!("⚠️ Audit: Failed to record {}: {}", detail, e);
+    }
+    response
+}
+
+#[derive(serde::Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    actor: Option<String>,
+    #[serde(default)]
+    action: Option<String>,
+    /// この時刻 (`YYYY-MM-DD HH:MM:SS` UTC) 以降
+    #[serde(default)]
+    since: Option<String>,
+}
+
+/// `GET /api/audit` — 監査ログ (新しい順)
+pub async fn audit_handler(State(state): State<Arc<AppState>>, Query(query): Query<AuditQuery>) -> Response {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    match state.job_queue.fetch_audit_log(limit, query.actor.as_deref(), query.action.as_deref(), query.since.as_deref()).await {
+        Ok(records) => (StatusCode::OK, Json(records)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 夜間ダイジェストの本文。操作が無ければ None
+pub fn format_digest(hours: i64, lines: &[AuditSummaryLine]) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+    let total: i64 = lines.iter().map(|l| l.count).sum();
+    let mut out = format!("📜 **Audit digest** — {} control-plane action(s) in the last {}h", total, hours);
+    for line in lines {
+        out.push_str(&format!("\n• `{}` × {} by {}", line.action, line.count, line.actor));
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_are_classified_with_targets() {
+        assert_eq!(classify(&Method::POST, "/api/remix"), ("enqueue", None));
+        assert_eq!(classify(&Method::POST, "/api/jobs/j-1/rate"), ("rating", Some("j-1".to_string())));
+        assert_eq!(classify(&Method::POST, "/api/jobs/j-1/cancel"), ("cancel", Some("j-1".to_string())));
+        assert_eq!(classify(&Method::DELETE, "/api/styles/cinematic"), ("style_delete", Some("cinematic".to_string())));
+        assert_eq!(classify(&Method::PUT, "/api/styles/cinematic"), ("style_edit", Some("cinematic".to_string())));
+        assert_eq!(classify(&Method::POST, "/api/styles/reload"), ("style_reload", None));
+        assert_eq!(classify(&Method::POST, "/api/karma"), ("karma_inject", None));
+        assert_eq!(classify(&Method::DELETE, "/api/karma/k-1"), ("karma_delete", Some("k-1".to_string())));
+        assert_eq!(classify(&Method::POST, "/api/series/weekly/episodes"), ("enqueue_episode", Some("weekly".to_string())));
+        assert_eq!(classify(&Method::POST, "/review/p-9"), ("review", Some("p-9".to_string())));
+        assert_eq!(classify(&Method::POST, "/hooks/slack/actions"), ("slack_action", None));
+        assert_eq!(classify(&Method::PUT, "/api/chaos/disk_full"), ("chaos_arm", Some("disk_full".to_string())));
+        assert_eq!(classify(&Method::DELETE, "/api/chaos"), ("chaos_clear", None));
+        assert_eq!(classify(&Method::POST, "/api/system/pause"), ("pause", None));
+        assert_eq!(classify(&Method::POST, "/api/system/resume"), ("resume", None));
+
+        let lines = vec![
+            AuditSummaryLine { action: "rating".into(), actor: "alice (42)".into(), count: 3 },
+            AuditSummaryLine { action: "enqueue".into(), actor: "dashboard".into(), count: 1 },
+        ];
+        let digest = format_digest(24, &lines).unwrap();
+        assert!(digest.contains("4 control-plane action(s) in the last 24h"));
+        assert!(digest.contains("`rating` × 3 by alice (42)"));
+        assert!(format_digest(24, &[]).is_none());
+    }
+}
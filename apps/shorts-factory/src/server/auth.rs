@@ -0,0 +1,277 @@
+//! # Auth — Serve API の API キー認証とレート制限
+//!
+//! GET 以外 (ジョブ投入・アップロード・スタイル編集など) に API キーを要求する。
+//! キーは Bastion の KeyStore (`bastion keys`) と `[auth] static_keys` から引き、
+//! 認証済みのキー名ごとに 1 分間の固定窓でリクエスト数を数える。
+//!
+//! `shorts-factory token create` で発行するトークンはスコープを持つ (DB にハッシュのみ保存)。
+//! 参照系は readonly、変更系は operator、スタイル編集と Karma の書き込み・削除は admin 以上が必要。
+//! スコープ導入前のキー (KeyStore・static_keys) は admin として扱う。
+//! 監査ログ (`/api/audit`) と障害注入 (`/api/chaos`) は参照も admin 限定。認証した名前は `server::audit` が操作者として記録する。
+//! 状況ページ (`/status`) は `protect_reads` に関わらず readonly 以上が必要で、`?token=` と Cookie のキーも受け付ける。
+//! ffmpeg を走らせる参照系 (BGM 試聴・HLS プレビュー) も `protect_reads` に関わらず readonly 以上が必要。
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use bastion::secrets::KeyStore;
+use factory_core::api::ApiScope;
+use infrastructure::job_queue::SqliteJobQueue;
+use shared::config::AuthConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+use crate::server::audit::Actor;
+use crate::server::router::AppState;
+use crate::server::status;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+pub struct ApiAuth {
+    store: KeyStore,
+    static_keys: Vec<(String, String)>,
+    rate_limit_per_minute: u32,
+    /// 参照系にも readonly 以上のトークンを要求する
+    protect_reads: bool,
+    /// スコープ付きトークンの保管先 (None ならトークンは使えない)
+    tokens: Option<Arc<SqliteJobQueue>>,
+    /// キー名 -> (窓の開始時刻, 窓内のリクエスト数)
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl ApiAuth {
+    /// `[auth] enabled = false` なら None (認証なし)
+    pub fn from_config(config: &AuthConfig, tokens: Option<Arc<SqliteJobQueue>>) -> anyhow::Result<Option<Arc<Self>>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let store = KeyStore::open(&config.keys_file)?;
+        let active = store.list().iter().filter(|r| !r.revoked).count();
+        if active == 0 && config.static_keys.is_empty() && tokens.is_none() {
+            warn!("🔐 Auth: enabled but no keys exist. Issue one with `bastion keys add <name> --file {}`.", config.keys_file);
+        }
+        info!("🔐 Auth: API keys required for mutating routes ({} stored, {} static, {}/min per key)",
+            active, config.static_keys.len(), config.rate_limit_per_minute);
+        Ok(Some(Arc::new(Self {
+            store,
+            static_keys: config.static_keys.iter().map(|(name, key)| (name.clone(), key.clone())).collect(),
+            rate_limit_per_minute: config.rate_limit_per_minute,
+            protect_reads: config.protect_reads,
+            tokens,
+            windows: Mutex::new(HashMap::new()),
+        })))
+    }
+
+    /// キーの持ち主の名前とスコープを返す
+    pub async fn authenticate(&self, key: &str) -> Option<(String, ApiScope)> {
+        let legacy = self.static_keys
+            .iter()
+            .find(|(_, k)| bool::from(k.as_bytes().ct_eq(key.as_bytes())))
+            .map(|(name, _)| name.clone())
+            .or_else(|| self.store.verify(key));
+        if let Some(name) = legacy {
+            return Some((name, ApiScope::Admin));
+        }
+        match self.tokens.as_ref()?.verify_api_token(key).await {
+            Ok(token) => token.map(|t| (t.name, t.scope)),
+            Err(e) => {
+                warn!("🔐 Auth: Token lookup failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 窓内の残り枠を消費する。超過していれば次の窓までの秒数
+    fn check_rate(&self, name: &str, now: Instant) -> Result<(), u64> {
+        if self.rate_limit_per_minute == 0 {
+            return Ok(());
+        }
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let (start, count) = windows.entry(name.to_string()).or_insert((now, 0));
+        if now.duration_since(*start) >= RATE_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.rate_limit_per_minute {
+            let retry_after = RATE_WINDOW.saturating_sub(now.duration_since(*start));
+            return Err(retry_after.as_secs().max(1));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// `Authorization: Bearer <key>` または `X-API-Key: <key>`
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// Slack の署名で検証する受け口 (ここだけ API キーを求めない)
+const SLACK_ACTIONS_PATH: &str = "/hooks/slack/actions";
+
+fn is_status_page(path: &str) -> bool {
+    path == "/status" || path.starts_with("/status/")
+}
+
+/// 呼ばれるたびに ffmpeg を走らせる参照系 (`/api/styles/:name/audio-preview`, `/api/projects/:id/preview.m3u8`)
+fn spawns_ffmpeg(path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    matches!(segments.as_slice(), ["", "api", "styles", _, "audio-preview"] | ["", "api", "projects", _, "preview.m3u8"])
+}
+
+/// ルートに必要なスコープ (None なら認証不要)
+fn required_scope(method: &Method, path: &str, protect_reads: bool) -> Option<ApiScope> {
+    // レビューページは署名付きリンク自体が認可 (server::review)、Slack の受け口は Slack の署名で検証する
+    // (server::notify)。CORS プリフライトは常に通す
+    if path.starts_with("/review/") || path == SLACK_ACTIONS_PATH || *method == Method::OPTIONS {
+        return None;
+    }
+    // 状況ページはブラウザで開くので、参照系を公開していてもキーを要求する (server::status)。
+    // ffmpeg を走らせる参照系も、誰でも叩けると変換を起こし放題になるので同じ扱い
+    if is_status_page(path) || spawns_ffmpeg(path) {
+        return Some(ApiScope::Readonly);
+    }
+    // 監査ログと障害注入 (`chaos` feature) は参照も admin 限定
+    if path == "/api/audit" || path.starts_with("/api/chaos") {
+        return Some(ApiScope::Admin);
+    }
+    if matches!(*method, Method::GET | Method::HEAD) {
+        return protect_reads.then_some(ApiScope::Readonly);
+    }
+    // スタイルの作成・編集・削除・再読み込みと Karma の書き込み・削除は全ジョブの出力に効く
+    if path.starts_with("/api/styles") || path.starts_with("/api/karma") {
+        return Some(ApiScope::Admin);
+    }
+    Some(ApiScope::Operator)
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+/// 全ルートの前段に置くミドルウェア (認証無効時・スコープ不要のルートは素通し)
+pub async fn require_api_key(State(state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    let Some(auth) = state.auth.as_ref() else {
+        return next.run(request).await;
+    };
+    let Some(required) = required_scope(request.method(), request.uri().path(), auth.protect_reads) else {
+        return next.run(request).await;
+    };
+
+    // ブラウザはヘッダーを付けられないので、状況ページだけ `?token=` と Cookie も見る
+    let status_page = is_status_page(request.uri().path());
+    let key = match presented_key(request.headers()) {
+        Some(key) => Some(key.to_string()),
+        None if status_page => status::page_key(request.uri(), request.headers()),
+        None => None,
+    };
+    let Some(key) = key else {
+        if status_page {
+            return status::login_page("Enter an API token to view the factory status.");
+        }
+        return error(StatusCode::UNAUTHORIZED, "API key required");
+    };
+    let Some((name, scope)) = auth.authenticate(&key).await else {
+        warn!("🔐 Auth: Rejected {} {} (unknown or revoked key)", request.method(), request.uri().path());
+        if status_page {
+            return status::login_page("That token is unknown or revoked.");
+        }
+        return error(StatusCode::UNAUTHORIZED, "Invalid API key");
+    };
+    if !scope.allows(required) {
+        warn!("🔐 Auth: '{}' ({}) lacks the {} scope for {} {}", name, scope.as_str(), required.as_str(), request.method(), request.uri().path());
+        return error(StatusCode::FORBIDDEN, &format!("This route requires the '{}' scope", required.as_str()));
+    }
+    request.extensions_mut().insert(Actor(name.clone()));
+    // レート制限は変更系だけに掛ける
+    if matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+    if let Err(retry_after) = auth.check_rate(&name, Instant::now()) {
+        warn!("🔐 Auth: Rate limit hit by '{}' on {} {}", name, request.method(), request.uri().path());
+        let mut response = error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded");
+        response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return response;
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_and_stored_keys_with_per_key_rate_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys_file = dir.path().join("api_keys.json");
+        let stored = KeyStore::open(&keys_file).unwrap().create_key("tauri").unwrap();
+        let config = AuthConfig {
+            enabled: true,
+            keys_file: keys_file.to_string_lossy().to_string(),
+            static_keys: [("ops".to_string(), "static-secret".to_string())].into(),
+            rate_limit_per_minute: 2,
+            protect_reads: false,
+        };
+        let auth = ApiAuth::from_config(&config, None).unwrap().unwrap();
+
+        assert_eq!(auth.authenticate("static-secret").await, Some(("ops".to_string(), ApiScope::Admin)));
+        assert_eq!(auth.authenticate(&stored).await.map(|(name, _)| name).as_deref(), Some("tauri"));
+        assert!(auth.authenticate("nope").await.is_none());
+
+        let t0 = Instant::now();
+        assert!(auth.check_rate("ops", t0).is_ok());
+        assert!(auth.check_rate("ops", t0).is_ok());
+        assert_eq!(auth.check_rate("ops", t0 + Duration::from_secs(15)), Err(45));
+        // Independent budget per key, and a fresh window after a minute
+        assert!(auth.check_rate("tauri", t0).is_ok());
+        assert!(auth.check_rate("ops", t0 + RATE_WINDOW).is_ok());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static(" k1 "));
+        assert_eq!(presented_key(&headers), Some("k1"));
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer k2"));
+        assert_eq!(presented_key(&headers), Some("k2"));
+    }
+
+    #[test]
+    fn test_route_groups_map_to_scopes() {
+        assert_eq!(required_scope(&Method::GET, "/api/jobs", false), None);
+        assert_eq!(required_scope(&Method::GET, "/api/jobs", true), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::OPTIONS, "/api/remix", true), None);
+        assert_eq!(required_scope(&Method::POST, "/review/abc", true), None);
+        assert_eq!(required_scope(&Method::POST, "/hooks/slack/actions", true), None);
+        assert_eq!(required_scope(&Method::POST, "/hooks/other", true), Some(ApiScope::Operator));
+        assert_eq!(required_scope(&Method::POST, "/api/remix", false), Some(ApiScope::Operator));
+        assert_eq!(required_scope(&Method::DELETE, "/api/styles/cinematic", false), Some(ApiScope::Admin));
+        assert_eq!(required_scope(&Method::POST, "/api/styles/reload", false), Some(ApiScope::Admin));
+        assert_eq!(required_scope(&Method::DELETE, "/api/karma/k-1", false), Some(ApiScope::Admin));
+        assert_eq!(required_scope(&Method::GET, "/api/karma", true), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::GET, "/api/audit", false), Some(ApiScope::Admin));
+        assert_eq!(required_scope(&Method::GET, "/api/chaos", false), Some(ApiScope::Admin));
+        assert_eq!(required_scope(&Method::GET, "/status", false), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::GET, "/status/thumb/abc", false), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::GET, "/statusx", false), None);
+        assert_eq!(required_scope(&Method::GET, "/api/styles/cinematic/audio-preview", false), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::GET, "/api/projects/job_abc/preview.m3u8", false), Some(ApiScope::Readonly));
+        assert_eq!(required_scope(&Method::GET, "/api/styles/cinematic", false), None);
+
+        assert!(ApiScope::Admin.allows(ApiScope::Operator));
+        assert!(ApiScope::Operator.allows(ApiScope::Readonly));
+        assert!(!ApiScope::Readonly.allows(ApiScope::Operator));
+        assert_eq!("Operator".parse::<ApiScope>(), Ok(ApiScope::Operator));
+        assert!("root".parse::<ApiScope>().is_err());
+    }
+}
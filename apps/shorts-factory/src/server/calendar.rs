@@ -0,0 +1,398 @@
+//! # Calendar — 制作計画のエクスポート (`/api/calendar/export`)
+//!
+//! Samsara の今後の企画枠 (cron から展開)・キュー待ちのジョブ・直近の公開動画・連載シリーズを
+//! 1 つの計画にまとめ、JSON または iCalendar (.ics) で共同作業者に渡せる形にする。
+//! 公開枠に合わせた予約投入 (`schedule` / `/schedule`) もここで組み立てる。
+
+use crate::channels::ChannelRegistry;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone, Utc};
+use croner::parser::{CronParser, Seconds};
+use factory_core::api::{CalendarEntry, CalendarEntryKind, CalendarExport};
+use factory_core::contracts::EpisodeLink;
+use factory_core::error::FactoryError;
+use factory_core::traits::{JobQueue, JobStatus};
+use infrastructure::job_queue::SqliteJobQueue;
+use shared::config::CronConfig;
+use std::collections::HashMap;
+
+/// 1 回のエクスポートで展開する Samsara 枠の上限 (秒単位の cron で暴走しないように)
+const MAX_SLOTS_PER_CHANNEL: usize = 200;
+
+/// 1 回の予約で積めるジョブ数の上限
+pub const MAX_SCHEDULED_JOBS: u32 = 60;
+
+/// 予約投入の内容 (CLI `schedule` と Watchtower `/schedule` 共通)
+#[derive(Debug, Clone)]
+pub struct SchedulePlan {
+    /// 1 本目の実行時刻
+    pub first: DateTime<Utc>,
+    /// 本数と間隔 (例: 7 本を 24 時間おき = 1 週間分)
+    pub count: u32,
+    pub every: Duration,
+    /// シリーズ名か ID。指定時は各回が次の話数になる
+    pub series: Option<String>,
+    /// シリーズ外ならトピック必須。シリーズではトピック書式に差し込まれる
+    pub topic: Option<String>,
+    pub style: String,
+    pub channel: String,
+    pub soul: Option<String>,
+}
+
+/// 予約したジョブ (ID, 実行時刻, 話数)
+pub type ScheduledJob = (String, DateTime<Utc>, Option<i64>);
+
+/// 時刻指定を解釈する。オフセット付き (RFC 3339) はそのまま、
+/// `2025-01-03T07:00` のようなオフセット無しはこのマシンのローカル時刻として扱う
+pub fn parse_schedule_time(input: &str) -> Result<DateTime<Utc>, String> {
+    let s = input.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+                .ok_or_else(|| format!("'{}' does not exist in the local time zone", s));
+        }
+    }
+    Err(format!("Invalid time '{}' (expected e.g. 2025-01-03T07:00 or 2025-01-03T07:00:00+09:00)", s))
+}
+
+/// 予約ジョブをキューに積む。どれかが失敗した時点で止め、それまでの分は残る
+pub async fn schedule_jobs(job_queue: &SqliteJobQueue, plan: &SchedulePlan) -> Result<Vec<ScheduledJob>, FactoryError> {
+    if plan.count == 0 || plan.count > MAX_SCHEDULED_JOBS {
+        return Err(FactoryError::Infrastructure { reason: format!("count must be between 1 and {}", MAX_SCHEDULED_JOBS) });
+    }
+    if plan.count > 1 && plan.every <= Duration::zero() {
+        return Err(FactoryError::Infrastructure { reason: "the interval between repeated jobs must be positive".to_string() });
+    }
+    let topic = plan.topic.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let series = match &plan.series {
+        Some(name) => Some(job_queue.fetch_series(name).await?
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("Series '{}' not found", name) })?),
+        None if topic.is_none() => {
+            return Err(FactoryError::Infrastructure { reason: "a topic is required when no series is given".to_string() });
+        }
+        None => None,
+    };
+
+    let mut scheduled = Vec::with_capacity(plan.count as usize);
+    for i in 0..plan.count {
+        let at = plan.first + plan.every * i as i32;
+        let at_str = at.to_rfc3339();
+        let (id, episode) = match &series {
+            Some(s) => {
+                let style = Some(plan.style.as_str()).filter(|s| !s.is_empty());
+                let (id, n) = job_queue.enqueue_episode(&s.id, topic, style, None, Some(&at_str)).await?;
+                (id, Some(n))
+            }
+            None => {
+                let id = job_queue.enqueue_at(&plan.channel, plan.soul.as_deref(), topic.unwrap_or_default(), &plan.style, None, Some(&at_str)).await?;
+                (id, None)
+            }
+        };
+        scheduled.push((id, at, episode));
+    }
+    Ok(scheduled)
+}
+
+/// 予約で投入される題材 (シリーズなら各話に書式を当てたもの)。投入前に編集方針へ照らすために使う
+pub async fn planned_topics(job_queue: &SqliteJobQueue, plan: &SchedulePlan) -> Result<Vec<String>, FactoryError> {
+    let topic = plan.topic.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let Some(name) = &plan.series else {
+        return Ok(topic.map(|t| vec![t.to_string()]).unwrap_or_default());
+    };
+    let Some(series) = job_queue.fetch_series(name).await? else {
+        return Ok(Vec::new());
+    };
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    Ok((1..=plan.count as i64)
+        .map(|i| series.episode_topic(series.episode_counter + i, topic, &date))
+        .filter(|t| !t.is_empty())
+        .collect())
+}
+
+/// cron 式 (6 フィールド) の `from` より後、`until` 以前の発火時刻
+pub fn cron_slots(expr: &str, from: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<DateTime<Utc>>, FactoryError> {
+    if CronConfig::is_disabled(expr) {
+        return Ok(Vec::new());
+    }
+    let cron = CronParser::builder()
+        .seconds(Seconds::Required)
+        .build()
+        .parse(expr.trim())
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid cron expression '{}': {}", expr, e) })?;
+    Ok(cron.iter_after(from).take_while(|t| *t <= until).take(MAX_SLOTS_PER_CHANNEL).collect())
+}
+
+/// 前後 `days` 日分の計画を組み立てる
+pub async fn build(
+    job_queue: &SqliteJobQueue,
+    channels: &ChannelRegistry,
+    cron: &CronConfig,
+    days: i64,
+) -> Result<CalendarExport, FactoryError> {
+    let now = Utc::now();
+    let series = job_queue.list_series().await?;
+    let mut entries = Vec::new();
+
+    // ジョブ ID -> (シリーズ名, 話数)
+    let mut episodes: HashMap<String, (String, EpisodeLink)> = HashMap::new();
+    for s in &series {
+        for ep in job_queue.fetch_series_episodes(&s.id, 200).await? {
+            episodes.insert(ep.job_id.clone(), (s.name.clone(), ep));
+        }
+    }
+
+    // 1. Samsara の企画枠 (連載に紐付くチャンネルは話数の見込みも付ける)
+    let slots = cron_slots(&cron.samsara, now, now + Duration::days(days))?;
+    for channel in channels.samsara_channels() {
+        let target = series.iter().find(|s| !channel.profile.series.is_empty() && (s.name == channel.profile.series || s.id == channel.profile.series));
+        for (i, at) in slots.iter().enumerate() {
+            entries.push(CalendarEntry {
+                kind: CalendarEntryKind::SamsaraSlot,
+                at: Some(at.to_rfc3339()),
+                channel: channel.name.clone(),
+                job_id: None,
+                topic: None,
+                series: target.map(|s| s.name.clone()),
+                episode_number: target.map(|s| s.episode_counter + i as i64 + 1),
+                video_url: None,
+            });
+        }
+    }
+
+    // 2. キュー待ち・公開済みのジョブ
+    let since = now - Duration::days(days);
+    for job in job_queue.fetch_recent_jobs(500).await? {
+        let episode = episodes.get(&job.id);
+        let (kind, at) = match job.status {
+            JobStatus::Pending if job.scheduled_for.is_some() => (CalendarEntryKind::Scheduled, job.scheduled_for.clone()),
+            JobStatus::Pending | JobStatus::Processing => (CalendarEntryKind::Queued, None),
+            JobStatus::Completed if job.sns_video_id.is_some() => {
+                let Some(at) = job.published_at.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok()) else { continue };
+                if at.with_timezone(&Utc) < since {
+                    continue;
+                }
+                (CalendarEntryKind::Published, Some(at.with_timezone(&Utc).to_rfc3339()))
+            }
+            _ => continue,
+        };
+        entries.push(CalendarEntry {
+            kind,
+            at,
+            channel: job.channel.clone(),
+            job_id: Some(job.id.clone()),
+            topic: Some(job.topic.clone()),
+            series: episode.map(|(name, _)| name.clone()),
+            episode_number: episode.map(|(_, ep)| ep.episode_number),
+            video_url: episode.and_then(|(_, ep)| ep.video_url()),
+        });
+    }
+
+    // RFC 3339 (UTC) は文字列順 = 時刻順。時刻未定は末尾
+    entries.sort_by(|a, b| (a.at.is_none(), &a.at).cmp(&(b.at.is_none(), &b.at)));
+
+    Ok(CalendarExport { generated_at: now.to_rfc3339(), days, series, entries })
+}
+
+/// iCalendar (RFC 5545) に書き出す。時刻のある項目は VEVENT、キュー待ちは VTODO
+pub fn to_ics(plan: &CalendarExport) -> String {
+    let stamp = ics_time(&plan.generated_at).unwrap_or_default();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//modular-open-claw//shorts-factory//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Shorts Factory plan".to_string(),
+    ];
+
+    for (i, entry) in plan.entries.iter().enumerate() {
+        let summary = summary(entry);
+        let uid = match &entry.job_id {
+            Some(id) => format!("{}@shorts-factory", id),
+            None => format!("samsara-{}-{}@shorts-factory", entry.channel, entry.at.as_deref().and_then(ics_time).unwrap_or_else(|| i.to_string())),
+        };
+        let mut description = vec![format!("Channel: {}", entry.channel)];
+        if let Some(id) = &entry.job_id {
+            description.push(format!("Job: {}", id));
+        }
+        if let Some(url) = &entry.video_url {
+            description.push(url.clone());
+        }
+
+        match entry.at.as_deref().and_then(ics_time) {
+            Some(start) => {
+                lines.push("BEGIN:VEVENT".to_string());
+                lines.push(format!("UID:{}", uid));
+                lines.push(format!("DTSTAMP:{}", stamp));
+                lines.push(format!("DTSTART:{}", start));
+                lines.push("DURATION:PT15M".to_string());
+                lines.push(format!("SUMMARY:{}", escape(&summary)));
+                lines.push(format!("DESCRIPTION:{}", escape(&description.join("\n"))));
+                lines.push(format!("CATEGORIES:{}", kind_label(entry.kind)));
+                if let Some(url) = &entry.video_url {
+                    lines.push(format!("URL:{}", url));
+                }
+                lines.push("END:VEVENT".to_string());
+            }
+            None => {
+                lines.push("BEGIN:VTODO".to_string());
+                lines.push(format!("UID:{}", uid));
+                lines.push(format!("DTSTAMP:{}", stamp));
+                lines.push(format!("SUMMARY:{}", escape(&summary)));
+                lines.push(format!("DESCRIPTION:{}", escape(&description.join("\n"))));
+                lines.push(format!("CATEGORIES:{}", kind_label(entry.kind)));
+                lines.push("STATUS:NEEDS-ACTION".to_string());
+                lines.push("END:VTODO".to_string());
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn summary(entry: &CalendarEntry) -> String {
+    let episode = match (&entry.series, entry.episode_number) {
+        (Some(series), Some(n)) => format!("{} #{}", series, n),
+        (Some(series), None) => series.clone(),
+        _ => String::new(),
+    };
+    let topic = entry.topic.as_deref().unwrap_or_default();
+    let body = [episode.as_str(), topic].iter().filter(|s| !s.is_empty()).copied().collect::<Vec<_>>().join(": ");
+    match entry.kind {
+        CalendarEntryKind::SamsaraSlot if body.is_empty() => format!("[{}] Samsara plans the next video", entry.channel),
+        CalendarEntryKind::SamsaraSlot => format!("[{}] Samsara plans {}", entry.channel, body),
+        CalendarEntryKind::Scheduled => format!("[{}] Scheduled: {}", entry.channel, body),
+        CalendarEntryKind::Queued => format!("[{}] Queued: {}", entry.channel, body),
+        CalendarEntryKind::Published => format!("[{}] Published: {}", entry.channel, body),
+    }
+}
+
+fn kind_label(kind: CalendarEntryKind) -> &'static str {
+    match kind {
+        CalendarEntryKind::SamsaraSlot => "SAMSARA",
+        CalendarEntryKind::Scheduled => "SCHEDULED",
+        CalendarEntryKind::Queued => "QUEUED",
+        CalendarEntryKind::Published => "PUBLISHED",
+    }
+}
+
+/// RFC 3339 -> `20250103T070000Z`
+fn ics_time(rfc3339: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .ok()
+        .map(|t| t.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// TEXT 値のエスケープ (RFC 5545 3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// 75 オクテットで折り返す (マルチバイト文字の途中では切らない)
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_and_ics_escaping_and_folding() {
+        let from = DateTime::parse_from_rfc3339("2025-01-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        let slots = cron_slots("0 0 7,19 * * *", from, from + Duration::days(2)).unwrap();
+        let times: Vec<String> = slots.iter().map(|t| t.format("%d %H").to_string()).collect();
+        assert_eq!(times, ["01 19", "02 07", "02 19", "03 07"]);
+        assert!(cron_slots("off", from, from + Duration::days(2)).unwrap().is_empty());
+        assert!(cron_slots("not a cron", from, from).is_err());
+
+        assert_eq!(parse_schedule_time("2025-01-03T07:00:00+09:00").unwrap().to_rfc3339(), "2025-01-02T22:00:00+00:00");
+        let local = parse_schedule_time("2025-01-03T07:00").unwrap();
+        assert_eq!(local.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(), "2025-01-03 07:00");
+        assert!(parse_schedule_time("next tuesday").is_err());
+
+        let plan = CalendarExport {
+            generated_at: from.to_rfc3339(),
+            days: 2,
+            series: Vec::new(),
+            entries: vec![
+                CalendarEntry {
+                    kind: CalendarEntryKind::SamsaraSlot,
+                    at: Some(slots[0].to_rfc3339()),
+                    channel: "tech".into(),
+                    job_id: None,
+                    topic: None,
+                    series: Some("AI News Weekly".into()),
+                    episode_number: Some(14),
+                    video_url: None,
+                },
+                CalendarEntry {
+                    kind: CalendarEntryKind::Queued,
+                    at: None,
+                    channel: "tech".into(),
+                    job_id: Some("job-1".into()),
+                    topic: Some(format!("Robots, lasers; and {}", "量子コンピュータ".repeat(6))),
+                    series: None,
+                    episode_number: None,
+                    video_url: None,
+                },
+            ],
+        };
+        let ics = to_ics(&plan);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n") && ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20250101T190000Z\r\n"));
+        assert!(ics.contains("SUMMARY:[tech] Samsara plans AI News Weekly #14\r\n"));
+        assert!(ics.contains("BEGIN:VTODO\r\nUID:job-1@shorts-factory"));
+        assert!(ics.contains("Robots\\, lasers\\; and"));
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+        // Unfolding restores the original line
+        assert!(ics.replace("\r\n ", "").contains(&"量子コンピュータ".repeat(6)));
+    }
+
+    #[tokio::test]
+    async fn test_planned_topics_render_the_series_template() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let jq = SqliteJobQueue::new(tmp.path().join("test.db").to_str().unwrap()).await.unwrap();
+        let new = factory_core::traits::NewSeries { name: "weekly".into(), topic_template: "Weekly #{n}".into(), ..Default::default() };
+        jq.create_series(&new).await.unwrap();
+        jq.enqueue_episode("weekly", None, None, None, None).await.unwrap();
+
+        let plan = SchedulePlan {
+            first: Utc::now(),
+            count: 2,
+            every: Duration::hours(24),
+            series: Some("weekly".into()),
+            topic: Some("gpus".into()),
+            style: String::new(),
+            channel: String::new(),
+            soul: None,
+        };
+        assert_eq!(planned_topics(&jq, &plan).await.unwrap(), vec!["Weekly #2: gpus", "Weekly #3: gpus"]);
+        let single = SchedulePlan { series: None, ..plan };
+        assert_eq!(planned_topics(&jq, &single).await.unwrap(), vec!["gpus"]);
+    }
+}
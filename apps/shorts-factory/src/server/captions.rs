@@ -0,0 +1,44 @@
+//! # Captions — 紐付けた SNS 動画への字幕トラックの添付
+//!
+//! 焼き込み字幕とは別に、Orchestrator は言語ごとの WebVTT (`{lang}/captions.vtt`) を残している。
+//! `link-sns` で動画 ID が分かった時点で、チャンネルの資格情報でそれを投稿先に添付する
+//! (アクセシビリティと検索向け。`[publisher] upload_captions = true` で有効)。
+
+use crate::asset_manager::AssetManager;
+use crate::channels::ChannelRegistry;
+use factory_core::error::FactoryError;
+use factory_core::traits::JobQueue;
+use infrastructure::job_queue::SqliteJobQueue;
+use tracing::{info, warn};
+
+/// ジョブの字幕トラックを動画に添付し、添付できた言語を返す。ジョブが無ければ None
+pub async fn attach_captions(
+    job_queue: &SqliteJobQueue,
+    channels: &ChannelRegistry,
+    assets: &AssetManager,
+    job_id: &str,
+    platform: &str,
+    video_id: &str,
+) -> Result<Option<Vec<String>>, FactoryError> {
+    if !channels.upload_captions() {
+        return Ok(Some(Vec::new()));
+    }
+    let Some(job) = job_queue.fetch_job(job_id).await? else { return Ok(None) };
+    let tracks = assets.caption_tracks(&crate::job_worker::job_project_id(job_id));
+    if tracks.is_empty() {
+        info!("💬 Captions: Job {} has no caption tracks to attach", job_id);
+        return Ok(Some(Vec::new()));
+    }
+
+    let publisher = channels.publisher(&job.channel, platform)?;
+    let mut attached = Vec::new();
+    for (lang, path) in tracks {
+        // 1 言語の失敗で残りを止めない
+        match publisher.upload_captions(video_id, &lang, &path).await {
+            Ok(()) => attached.push(lang),
+            Err(e) => warn!("⚠️ Captions: Failed to attach {} captions to {} video {}: {}", lang, publisher.platform(), video_id, e),
+        }
+    }
+    info!("💬 Captions: Attached [{}] to {} video {} (job {})", attached.join(", "), publisher.platform(), video_id, job_id);
+    Ok(Some(attached))
+}
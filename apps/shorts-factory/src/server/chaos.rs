@@ -0,0 +1,47 @@
+//! # Chaos — 障害注入の操作 API (`chaos` feature 付きのビルドのみ)
+//!
+//! `infrastructure::chaos` の規則を Serve から切り替える。検証環境で
+//! `PUT /api/chaos/gemini_error {"probability": 1.0, "remaining": 5}` のように障害を仕掛け、
+//! サーキットブレーカーや Poison Pill が働くのを Watchtower・`/status` で確かめたら `DELETE /api/chaos` で戻す。
+
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use infrastructure::chaos::{self, Fault, FaultRule};
+
+/// 有効な規則の一覧
+pub async fn chaos_rules_handler() -> impl IntoResponse {
+    Json(chaos::rules())
+}
+
+/// 1 種類の障害を仕掛ける (同じ障害の規則は置き換える)
+pub async fn chaos_arm_handler(Path(fault): Path<String>, Json(rule): Json<FaultRule>) -> impl IntoResponse {
+    let fault: Fault = match fault.parse() {
+        Ok(fault) => fault,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": e}))).into_response(),
+    };
+    match chaos::set_rule(fault, rule) {
+        Ok(()) => (StatusCode::OK, Json(chaos::rules())).into_response(),
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({"error": e}))).into_response(),
+    }
+}
+
+/// 1 種類の障害を外す
+pub async fn chaos_disarm_handler(Path(fault): Path<String>) -> impl IntoResponse {
+    match fault.parse::<Fault>() {
+        Ok(fault) => {
+            chaos::clear(Some(fault));
+            (StatusCode::OK, Json(chaos::rules())).into_response()
+        }
+        Err(e) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": e}))).into_response(),
+    }
+}
+
+/// 全ての障害を外す
+pub async fn chaos_clear_handler() -> impl IntoResponse {
+    chaos::clear(None);
+    Json(chaos::rules())
+}
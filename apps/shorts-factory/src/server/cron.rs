@@ -1,184 +1,440 @@
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{info, warn, error};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use rand::Rng;
 use factory_core::traits::JobQueue;
-use infrastructure::job_queue::SqliteJobQueue;
+use infrastructure::job_queue::{find_similar_topic, SqliteJobQueue};
+use infrastructure::sns_watcher::{canonical_platform, SnsWatcher};
+use infrastructure::topic_policy::TopicPolicy;
+use infrastructure::trend_sources::CompositeTrendSource;
 use rig::providers::gemini;
 use rig::completion::Prompt;
 use rig::client::CompletionClient;
 use tokio::fs;
-use factory_core::contracts::LlmJobResponse;
+use factory_core::contracts::{HookStat, JobProvenance, LlmJobResponse, HOOK_STYLES};
+use factory_core::cost::{CostEntry, CostTracker};
+use factory_core::api::{CronOutcome, CronRun};
 
 use tokio::sync::mpsc;
 use shared::watchtower::CoreEvent;
+use shared::config::{CronConfig, OracleConfig, DEFAULT_CHANNEL};
+use crate::channels::{Channel, ChannelRegistry, Soul};
+use infrastructure::soul_history::soul_hash as compute_soul_hash;
+
+/// cron の実行履歴を残す日数 (DB Scavenger が古いものを消す)
+const CRON_RUN_RETENTION_DAYS: i64 = 30;
+/// 起動時の取りこぼし補完は、他の初期化が落ち着くまで待ってから流す
+const CATCH_UP_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// cron ジョブ 1 回分の本体 (`Job::new_async` に渡すクロージャの戻り値)
+type JobFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// `[cron]` のスケジュールを解決する。無効化されたジョブは None を返し、登録自体をスキップする。
+fn active_schedule<'a>(name: &str, expr: &'a str) -> Option<&'a str> {
+    if CronConfig::is_disabled(expr) {
+        info!("⏸️ [Cron] {} is disabled by config.", name);
+        None
+    } else {
+        Some(expr.trim())
+    }
+}
+
+/// cron ジョブ 1 回の結末 (`cron_runs` に残す)
+pub(crate) struct RunOutcome {
+    outcome: CronOutcome,
+    detail: Option<String>,
+}
+
+impl RunOutcome {
+    fn ok(detail: impl Into<String>) -> Self {
+        Self { outcome: CronOutcome::Ok, detail: Some(detail.into()) }
+    }
+
+    fn skipped(reason: impl Into<String>) -> Self {
+        Self { outcome: CronOutcome::Skipped, detail: Some(reason.into()) }
+    }
+
+    fn failed(error: impl std::fmt::Display) -> Self {
+        Self { outcome: CronOutcome::Failed, detail: Some(error.to_string()) }
+    }
+
+    /// 処理件数の要約に、途中で起きたエラーがあれば失敗として添える
+    fn tally(summary: String, errors: Vec<String>) -> Self {
+        if errors.is_empty() {
+            Self::ok(summary)
+        } else {
+            Self::failed(format!("{}; {}", summary, errors.join("; ")))
+        }
+    }
+
+    /// チャンネルごとなど対象別の仕事の要約: エラーがあっても `done` 件済んでいれば `partial`
+    /// (取りこぼし補完で済んだ対象まで流し直さない)
+    fn tally_targets(summary: String, done: usize, errors: Vec<String>) -> Self {
+        if errors.is_empty() || done == 0 {
+            Self::tally(summary, errors)
+        } else {
+            Self { outcome: CronOutcome::Partial, detail: Some(format!("{}; {}", summary, errors.join("; "))) }
+        }
+    }
+}
+
+/// 取りこぼしの判定: 最後の成功 (`ok` か `partial`) がスケジュールの周期 (次の 2 枠の間隔) より古ければ true。
+/// 成功の記録が無いジョブ (初回起動・履歴の消去後) は補わない
+pub fn missed_run(expr: &str, last_ok: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    let Some(last_ok) = last_ok else { return false };
+    let slots = crate::server::calendar::cron_slots(expr, now, now + chrono::Duration::days(366)).unwrap_or_default();
+    match slots.as_slice() {
+        [first, second, ..] => now - last_ok > *second - *first,
+        _ => false,
+    }
+}
+
+/// 実行中の印。本体が panic しても外れるよう Drop で下ろす
+struct RunningFlag(Arc<AtomicBool>);
+
+impl Drop for RunningFlag {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// cron ジョブを登録する (`[cron]` で無効化されていれば何もしない)。前回の実行が終わっていなければ
+/// その発火は見送り (`skipped` として記録)、`[cron] jitter_secs` の範囲で揺らしてから本体を流す。
+/// `[cron] catch_up` のジョブは、停止中に枠を取りこぼしていれば起動直後にも 1 回流す
+async fn add_job<F>(
+    sched: &JobScheduler,
+    jq: &Arc<SqliteJobQueue>,
+    cron: &CronConfig,
+    job: &'static str,
+    expr: &str,
+    mut run: F,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    F: FnMut(uuid::Uuid, JobScheduler) -> JobFuture + Clone + Send + Sync + 'static,
+{
+    let Some(expr) = active_schedule(job, expr) else { return Ok(()) };
+    let running = Arc::new(AtomicBool::new(false));
+    let jitter_secs = cron.jitter_secs;
+    let jq_guard = jq.clone();
+    let guarded = move |uuid: uuid::Uuid, l: JobScheduler| -> JobFuture {
+        let jq = jq_guard.clone();
+        let running = running.clone();
+        let body = run(uuid, l);
+        Box::pin(async move {
+            if running.swap(true, Ordering::AcqRel) {
+                warn!("⏭️ [Cron] '{}' is still running from the previous trigger; skipping this one.", job);
+                record_run(&jq, job, async { RunOutcome::skipped("previous run still in progress") }).await;
+                return;
+            }
+            let _flag = RunningFlag(running);
+            if jitter_secs > 0 {
+                let delay = rand::thread_rng().gen_range(0..=jitter_secs);
+                tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+            }
+            body.await;
+        })
+    };
+
+    if cron.catch_up.iter().any(|name| name == job) {
+        match jq.last_cron_success(job).await {
+            Ok(last) => {
+                let last_ok = last.as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc));
+                if missed_run(expr, last_ok, Utc::now()) {
+                    info!("⏰ [Cron] '{}' missed its slot while the factory was down (last ok: {}). Catching up shortly.", job, last.unwrap_or_default());
+                    sched.add(Job::new_one_shot_async(CATCH_UP_DELAY, guarded.clone())?).await?;
+                }
+            }
+            Err(e) => warn!("⚠️ [Cron] Could not check '{}' for missed runs: {}", job, e),
+        }
+    }
+    sched.add(Job::new_async(expr, guarded)?).await?;
+    Ok(())
+}
+
+/// cron ジョブ 1 回分を `cron_runs` に記録しながら実行する (記録に失敗しても実行は止めない)
+pub(crate) async fn record_run(jq: &SqliteJobQueue, job: &str, run: impl std::future::Future<Output = RunOutcome>) {
+    let run_id = match jq.begin_cron_run(job).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            warn!("⚠️ [Cron] Failed to record the start of '{}': {}", job, e);
+            None
+        }
+    };
+    let result = run.await;
+    if let Some(run_id) = run_id {
+        if let Err(e) = jq.finish_cron_run(run_id, result.outcome, result.detail.as_deref()).await {
+            warn!("⚠️ [Cron] Failed to record the outcome of '{}': {}", job, e);
+        }
+    }
+}
 
-fn compute_soul_hash(soul_content: &str) -> String {
-    use std::hash::{Hash, Hasher};
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    soul_content.hash(&mut hasher);
-    format!("{:16x}", hasher.finish())
+/// cron の実行履歴の表示 (Watchtower `/cron`)
+pub fn format_cron_runs(job: Option<&str>, runs: &[CronRun]) -> String {
+    let scope = job.map(|j| format!(" for `{}`", j)).unwrap_or_default();
+    if runs.is_empty() {
+        return format!("⏰ No cron runs recorded{}.", scope);
+    }
+    let mut out = format!("⏰ **Recent cron runs{}**", scope);
+    for run in runs {
+        let icon = match run.outcome.as_str() {
+            "ok" => "✅",
+            "partial" => "⚠️",
+            "skipped" => "⏭️",
+            "failed" => "❌",
+            _ => "⏳",
+        };
+        let started = chrono::DateTime::parse_from_rfc3339(&run.started_at)
+            .map(|t| t.with_timezone(&chrono_tz::Asia::Tokyo).format("%m-%d %H:%M JST").to_string())
+            .unwrap_or_else(|_| run.started_at.clone());
+        let took = run.finished_at.as_deref()
+            .and_then(|end| Some((chrono::DateTime::parse_from_rfc3339(end).ok()? - chrono::DateTime::parse_from_rfc3339(&run.started_at).ok()?).num_seconds()))
+            .map(|secs| format!(" ({}s)", secs))
+            .unwrap_or_default();
+        out.push_str(&format!("\n{} `{}` {}{}", icon, run.job, started, took));
+        if let Some(detail) = run.detail.as_deref().filter(|d| !d.is_empty()) {
+            out.push_str(&format!(" — {}", detail.chars().take(100).collect::<String>()));
+        }
+    }
+    out
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_cron_scheduler(
     job_queue: Arc<SqliteJobQueue>,
     log_tx: mpsc::Sender<CoreEvent>,
     ollama_url: String,
-    model_name: String,
-    brave_api_key: String,
-    youtube_api_key: String,
+    _model_name: String,
+    trends: Arc<CompositeTrendSource>,
     gemini_api_key: String,
-    soul_md: String,
+    channels: Arc<ChannelRegistry>,
     workspace_dir: String,
     comfyui_base_dir: String,
     clean_after_hours: u64,
+    cron: CronConfig,
+    cost: CostTracker,
+    topic_policy_file: String,
+    oracle: OracleConfig,
 ) -> Result<JobScheduler, Box<dyn std::error::Error + Send + Sync>> {
     let sched = JobScheduler::new().await?;
+    // チャンネルに紐付かない内省系ジョブ (蒸留・挨拶など) は既定チャンネルの魂を使う
+    let soul_md = channels.get(DEFAULT_CHANNEL).soul_md.clone();
 
-    // === Job 1: The Samsara Protocol — Runs daily at 07:00 and 19:00 ===
+    // === Job 1: The Samsara Protocol — Default: runs daily at 07:00 and 19:00 ===
     let jq_samsara = job_queue.clone();
     let gem_key_samsara = gemini_api_key.clone();
-    let brave_key_samsara = brave_api_key.clone();
-    sched.add(
-        Job::new_async("0 0 7,19 * * *", move |_uuid, mut _l| {
-            let jq = jq_samsara.clone();
-            let gem_key = gem_key_samsara.clone();
-            let brave_key = brave_key_samsara.clone();
-            
-            Box::pin(async move {
+    let trends_samsara = trends.clone();
+    let channels_samsara = channels.clone();
+    let soul_samsara = (!cron.samsara_soul.is_empty()).then(|| cron.samsara_soul.clone());
+    let cost_samsara = cost.clone();
+    let (dedupe_days, dedupe_threshold) = (cron.samsara_dedupe_days, cron.samsara_dedupe_threshold);
+    let policy_samsara = topic_policy_file.clone();
+    let tx_samsara = log_tx.clone();
+    add_job(&sched, &job_queue, &cron, "samsara", &cron.samsara, move |_uuid, mut _l| {
+        let jq = jq_samsara.clone();
+        let gem_key = gem_key_samsara.clone();
+        let trends = trends_samsara.clone();
+        let channels = channels_samsara.clone();
+        let soul_name = soul_samsara.clone();
+        let cost = cost_samsara.clone();
+        let policy_file = policy_samsara.clone();
+        let tx = tx_samsara.clone();
+    
+        Box::pin(async move {
+            record_run(&jq, "samsara", async {
+                if let Ok(Some(since)) = jq.paused_since().await {
+                    info!("⏸️ [Samsara] Skipped: the factory has been paused since {}.", since);
+                    return RunOutcome::skipped(format!("paused since {}", since));
+                }
                 info!("🔄 [Samsara] Cron triggered. Initiating synthesis...");
-                match synthesize_next_job(&gem_key, "gemini-2.5-flash", &brave_key, &*jq).await {
-                    Ok(_) => info!("✅ [Samsara] Successfully synthesized and enqueued next job."),
-                    Err(e) => error!("❌ [Samsara] Failed to synthesize next job: {}", e),
+                let soul = soul_name.as_deref().and_then(|name| channels.soul(name));
+                let mut enqueued = 0;
+                let mut errors = Vec::new();
+                for channel in channels.samsara_channels() {
+                    match synthesize_next_job(&gem_key, "gemini-2.5-flash", &trends, &jq, channel, soul, &cost.for_job(None, None), dedupe_days, dedupe_threshold, &policy_file).await {
+                        Ok(SynthesisOutcome::Enqueued(_)) => {
+                            info!("✅ [Samsara] Successfully synthesized and enqueued next job for channel '{}'.", channel.name);
+                            enqueued += 1;
+                        }
+                        Ok(SynthesisOutcome::Repeated) => info!("⏭️ [Samsara] Skipped channel '{}': the proposed topics repeat recent videos.", channel.name),
+                        Ok(SynthesisOutcome::Blocked { topic, reason }) => {
+                            crate::server::topic_guard::report(&tx, "samsara", &topic, &reason).await;
+                        }
+                        Err(e) => {
+                            error!("❌ [Samsara] Failed to synthesize next job for channel '{}': {}", channel.name, e);
+                            errors.push(format!("{}: {}", channel.name, e));
+                        }
+                    }
                 }
-            })
-        })?
-    ).await?;
+                RunOutcome::tally_targets(format!("{} job(s) enqueued", enqueued), enqueued, errors)
+            }).await;
+        })
+    }).await?;
 
-    // === Job 2: The Zombie Hunter — Runs every 15 minutes ===
+    // === Job 2: The Zombie Hunter — Default: runs every 15 minutes ===
     let jq_zombie = job_queue.clone();
-    sched.add(
-        Job::new_async("0 */15 * * * *", move |_uuid, mut _l| {
-            let jq = jq_zombie.clone();
-            Box::pin(async move {
+    add_job(&sched, &job_queue, &cron, "zombie_hunter", &cron.zombie_hunter, move |_uuid, mut _l| {
+        let jq = jq_zombie.clone();
+        Box::pin(async move {
+            record_run(&jq, "zombie_hunter", async {
                 match jq.reclaim_zombie_jobs(15).await {
                     Ok(count) => {
                         if count > 0 {
                             warn!("🧟 [Zombie Hunter] Reclaimed {} ghost job(s)", count);
                         }
+                        RunOutcome::ok(format!("{} job(s) reclaimed", count))
+                    }
+                    Err(e) => {
+                        error!("❌ [Zombie Hunter] Failed to reclaim: {}", e);
+                        RunOutcome::failed(e)
                     }
-                    Err(e) => error!("❌ [Zombie Hunter] Failed to reclaim: {}", e),
                 }
-            })
-        })?
-    ).await?;
+            }).await;
+        })
+    }).await?;
 
-    // === Job 3: Deferred Distillation — Runs every 5 minutes ===
+    // === Job 3: Deferred Distillation — Default: runs every 5 minutes ===
     let jq_distill = job_queue.clone();
-    let s_md_distill = soul_md.clone();
+    let channels_distill = channels.clone();
     let gem_key_distill = gemini_api_key.clone();
     let ws_dir_distill = workspace_dir.clone();
-    sched.add(
-        Job::new_async("0 */5 * * * *", move |_uuid, mut _l| {
-            let jq = jq_distill.clone();
-            let s_md = s_md_distill.clone();
-            let gem_key = gem_key_distill.clone();
-            let ws_dir = ws_dir_distill.clone();
-
-            Box::pin(async move {
+    add_job(&sched, &job_queue, &cron, "deferred_distillation", &cron.deferred_distillation, move |_uuid, mut _l| {
+        let jq = jq_distill.clone();
+        let channels = channels_distill.clone();
+        let gem_key = gem_key_distill.clone();
+        let ws_dir = ws_dir_distill.clone();
+
+        Box::pin(async move {
+            record_run(&jq, "deferred_distillation", async {
                 match jq.fetch_undistilled_jobs(5).await {
                     Ok(jobs) => {
+                        let (mut distilled, mut deferred) = (0, 0);
                         for job in jobs {
                             let is_success = job.status == factory_core::traits::JobStatus::Completed;
                             let log = job.execution_log.unwrap_or_default();
                             info!("🧘 [Deferred Distillation] Processing undistilled Job: {}", job.id);
+                            // 教訓はそのジョブを制作したペルソナの soul_hash に紐付ける
+                            let s_md = channels.soul_md(&job.channel, job.soul.as_deref());
                             // Attempt distillation. If LLM is still down, the job stays undistilled and will be retried next cycle.
                             match distill_karma(
                                 &gem_key, "gemini-2.5-flash",
-                                &*jq, &job.id, &job.style, &log, is_success, job.creative_rating, &s_md, &ws_dir
+                                &jq, &job.id, &job.style, Some(&job.style), &log, is_success, job.creative_rating, s_md, &ws_dir
                             ).await {
                                 Ok(_) => {
                                     // Mark as distilled via trait method
                                     let _ = jq.mark_karma_extracted(&job.id).await;
                                     info!("✅ [Deferred Distillation] Karma extracted for Job {}", job.id);
+                                    distilled += 1;
+                                }
+                                Err(e) => {
+                                    warn!("⚠️ [Deferred Distillation] LLM unavailable, will retry: {}", e);
+                                    deferred += 1;
                                 }
-                                Err(e) => warn!("⚠️ [Deferred Distillation] LLM unavailable, will retry: {}", e),
                             }
                         }
+                        RunOutcome::ok(format!("{} distilled, {} deferred", distilled, deferred))
+                    }
+                    Err(e) => {
+                        error!("❌ [Deferred Distillation] Failed to fetch undistilled: {}", e);
+                        RunOutcome::failed(e)
                     }
-                    Err(e) => error!("❌ [Deferred Distillation] Failed to fetch undistilled: {}", e),
                 }
-            })
-        })?
-    ).await?;
+            }).await;
+        })
+    }).await?;
 
-    // === Job 4: DB Scavenger — Runs daily at 01:00 (Thermal Death Prevention) ===
+    // === Job 4: DB Scavenger — Default: runs daily at 01:00 (Thermal Death Prevention) ===
     let jq_scavenger = job_queue.clone();
-    sched.add(
-        Job::new_async("0 0 1 * * *", move |_uuid, mut _l| {
-            let jq = jq_scavenger.clone();
-            Box::pin(async move {
+    add_job(&sched, &job_queue, &cron, "db_scavenger", &cron.db_scavenger, move |_uuid, mut _l| {
+        let jq = jq_scavenger.clone();
+        Box::pin(async move {
+            record_run(&jq, "db_scavenger", async {
+                let mut errors = Vec::new();
                 // 1. Purge old video jobs
-                match jq.purge_old_jobs(60).await {
+                let jobs = match jq.purge_old_jobs(60).await {
                     Ok(count) => {
                         if count > 0 {
                             info!("🧹 [DB Scavenger] Purged {} old job(s).", count);
                         }
+                        count
                     }
-                    Err(e) => error!("❌ [DB Scavenger] Failed to purge jobs: {}", e),
-                }
+                    Err(e) => {
+                        error!("❌ [DB Scavenger] Failed to purge jobs: {}", e);
+                        errors.push(e.to_string());
+                        0
+                    }
+                };
 
                 // 2. Purge old distilled chats (keep distilled memory safe)
-                match jq.purge_old_distilled_chats(7).await {
+                let chats = match jq.purge_old_distilled_chats(7).await {
                     Ok(count) => {
                         if count > 0 {
                             info!("🧹 [DB Scavenger] Purged {} old distilled chat(s).", count);
                         }
+                        count
                     }
-                    Err(e) => error!("❌ [DB Scavenger] Failed to purge chats: {}", e),
+                    Err(e) => {
+                        error!("❌ [DB Scavenger] Failed to purge chats: {}", e);
+                        errors.push(e.to_string());
+                        0
+                    }
+                };
+
+                // 3. cron の実行履歴は 30 日分残す
+                if let Err(e) = jq.purge_old_cron_runs(CRON_RUN_RETENTION_DAYS).await {
+                    error!("❌ [DB Scavenger] Failed to purge cron runs: {}", e);
+                    errors.push(e.to_string());
                 }
-                
+
                 info!("🧹 [DB Scavenger] DB optimized.");
-            })
-        })?
-    ).await?;
+                RunOutcome::tally(format!("{} job(s), {} chat(s) purged", jobs, chats), errors)
+            }).await;
+        })
+    }).await?;
 
-    // === Job 4.5: Memory Distiller — Runs daily at 01:30 (Long-term Relationship Synthesis) ===
+    // === Job 4.5: Memory Distiller — Default: runs daily at 01:30 (Long-term Relationship Synthesis) ===
     let jq_distiller = job_queue.clone();
     let gem_key_distiller = gemini_api_key.clone();
     let log_tx_distiller = log_tx.clone();
     let soul_distiller = soul_md.clone();
-    sched.add(
-        Job::new_async("0 30 1 * * *", move |_uuid, mut _l| {
-            let jq = jq_distiller.clone();
-            let gem_key = gem_key_distiller.clone();
-            let tx = log_tx_distiller.clone();
-            let soul = soul_distiller.clone();
-            Box::pin(async move {
+    add_job(&sched, &job_queue, &cron, "memory_distiller", &cron.memory_distiller, move |_uuid, mut _l| {
+        let jq = jq_distiller.clone();
+        let gem_key = gem_key_distiller.clone();
+        let tx = log_tx_distiller.clone();
+        let soul = soul_distiller.clone();
+        Box::pin(async move {
+            record_run(&jq, "memory_distiller", async {
                 info!("🧠 [Memory Distiller] Waking up to process daily memories...");
                 match jq.fetch_undistilled_chats_by_channel().await {
                     Ok(channels) => {
                         if channels.is_empty() {
                             info!("🧠 [Memory Distiller] No new memories to process.");
-                            return;
+                            return RunOutcome::ok("no new memories");
                         }
 
                         let client = match rig::providers::gemini::Client::new(&gem_key) {
                             Ok(c) => c,
                             Err(e) => {
                                 error!("❌ [Memory Distiller] Failed to init Gemini: {}", e);
-                                return;
+                                return RunOutcome::failed(format!("Gemini init failed: {}", e));
                             }
                         };
-                        
+                        let (mut synthesized, mut errors) = (0, Vec::new());
+
                         let preamble = "あなたは「Watchtower」の深層心理・記憶整理モジュールです。以下の入力は、マスター（ユーザー）との対話履歴と、これまでの関係性の要約です。以下のルールで最新の要約を生成してください。\n1. ユーザーの好み、価値観、あなたへの接し方、重要な出来事を漏らさず含めること。\n2. 過去の要約と重複する内容は整理し、古い情報は最新の事実に上書きすること。\n3. 必ず1000文字以内でまとめること。\n4. 出力は純粋なテキストのみとし、前置きは不要。";
                         let agent = client.agent("gemini-2.0-flash").preamble(preamble).build();
 
                         for (channel_id, messages) in channels {
                             info!("🧠 [Memory Distiller] Processing {} messages for channel: {}", messages.len(), channel_id);
-                            
+
                             // 既存のサマリー取得
                             let existing_summary = jq.get_chat_memory_summary(&channel_id).await.unwrap_or_default().unwrap_or_else(|| "まだ記憶はありません。".to_string());
-                            
+
                             // ログの構築
                             let mut log_text = String::new();
                             let mut max_id_processed = -1;
@@ -186,173 +442,158 @@ pub async fn start_cron_scheduler(
                                 log_text.push_str(&format!("{}: {}\n", role, content));
                                 if id > max_id_processed { max_id_processed = id; }
                             }
-                            
+
                             let prompt = format!("【これまでの記憶】\n{}\n\n【今日の新しい会話】\n{}", existing_summary, log_text);
-                            
+
                             match agent.prompt(prompt).await {
                                 Ok(new_summary) => {
                                     if let Err(e) = jq.update_chat_memory_summary(&channel_id, &new_summary).await {
                                         error!("❌ [Memory Distiller] Failed to save summary for {}: {}", channel_id, e);
+                                        errors.push(format!("{}: {}", channel_id, e));
                                     } else {
                                         let _ = jq.mark_chats_as_distilled(&channel_id, max_id_processed).await;
                                         info!("✅ [Memory Distiller] Synthesized and saved memory for {}", channel_id);
-                                        
+                                        synthesized += 1;
+
                                         // Proactive talk about distillation
                                         let _ = notify_master(&gem_key, &tx, &soul, 
-                                            &format!("マスターとの昨日の思い出を整理しておいたよ。関係性の要約が更新されて、また少しマスターのことがわかった気がするな。")).await;
+                                            "マスターとの昨日の思い出を整理しておいたよ。関係性の要約が更新されて、また少しマスターのことがわかった気がするな。").await;
                                     }
                                 }
-                                Err(e) => error!("❌ [Memory Distiller] LLM synthesis failed for {}: {}", channel_id, e),
+                                Err(e) => {
+                                    error!("❌ [Memory Distiller] LLM synthesis failed for {}: {}", channel_id, e);
+                                    errors.push(format!("{}: {}", channel_id, e));
+                                }
                             }
                         }
+                        RunOutcome::tally_targets(format!("{} channel(s) synthesized", synthesized), synthesized, errors)
+                    }
+                    Err(e) => {
+                        error!("❌ [Memory Distiller] Failed to fetch undistilled chats: {}", e);
+                        RunOutcome::failed(e)
                     }
-                    Err(e) => error!("❌ [Memory Distiller] Failed to fetch undistilled chats: {}", e),
                 }
-            })
-        })?
-    ).await?;
-
-    // === Job 5.5: Health Check — Runs every 10 minutes (Scheduler Vitality) ===
-    sched.add(
-        Job::new_async("0 */10 * * * *", move |_uuid, mut _l| {
-            Box::pin(async move {
+            }).await;
+        })
+    }).await?;
+
+    // === Job 5.5: Health Check — Default: runs every 10 minutes (Scheduler Vitality) ===
+    let jq_health = job_queue.clone();
+    add_job(&sched, &job_queue, &cron, "health_check", &cron.health_check, move |_uuid, mut _l| {
+        let jq = jq_health.clone();
+        Box::pin(async move {
+            record_run(&jq, "health_check", async {
                 info!("💓 [Cron Health] Scheduler is alive and spinning the Wheel of Samsara.");
-            })
-        })?
-    ).await?;
+                RunOutcome::ok("alive")
+            }).await;
+        })
+    }).await?;
 
+    // === Job 5.6: Morning Greeting — Default: runs daily at 09:00 ===
     let log_tx_morning = log_tx.clone();
     let gem_key_morning = gemini_api_key.clone();
     let soul_morning = soul_md.clone();
-    sched.add(
-        Job::new_async("0 0 9 * * *", move |_uuid, mut _l| {
-            let tx = log_tx_morning.clone();
-            let key = gem_key_morning.clone();
-            let soul = soul_morning.clone();
-            Box::pin(async move {
-                let _ = notify_master(&key, &tx, &soul, "新しい朝が来ました。マスターに挨拶をして、今日一日の意気込みを一言伝えてください。").await;
-            })
-        })?
-    ).await?;
-
-    // === Job 5: The File Scavenger (Deep Cleansing) — Runs daily at 02:00 ===
+    let jq_morning = job_queue.clone();
+    add_job(&sched, &job_queue, &cron, "morning_greeting", &cron.morning_greeting, move |_uuid, mut _l| {
+        let tx = log_tx_morning.clone();
+        let key = gem_key_morning.clone();
+        let soul = soul_morning.clone();
+        let jq = jq_morning.clone();
+        Box::pin(async move {
+            record_run(&jq, "morning_greeting", async {
+                match notify_master(&key, &tx, &soul, "新しい朝が来ました。マスターに挨拶をして、今日一日の意気込みを一言伝えてください。").await {
+                    Ok(()) => RunOutcome::ok("greeted"),
+                    Err(e) => RunOutcome::failed(e),
+                }
+            }).await;
+        })
+    }).await?;
+
+    // === Job 5: The File Scavenger (Deep Cleansing) — Default: runs daily at 02:00 ===
     let ws_dir = workspace_dir.clone();
     let comfy_dir = comfyui_base_dir.clone();
-    sched.add(
-        Job::new_async("0 0 2 * * *", move |_uuid, mut _l| {
-            let w_dir = ws_dir.clone();
-            let c_dir_base = comfy_dir.clone(); 
-            let hours = clean_after_hours;
-            Box::pin(async move {
+    let jq_files = job_queue.clone();
+    add_job(&sched, &job_queue, &cron, "file_scavenger", &cron.file_scavenger, move |_uuid, mut _l| {
+        let w_dir = ws_dir.clone();
+        let c_dir_base = comfy_dir.clone(); 
+        let hours = clean_after_hours;
+        let jq = jq_files.clone();
+        Box::pin(async move {
+            record_run(&jq, "file_scavenger", async {
                 let allowed = [".mp4", ".png", ".jpg", ".jpeg", ".wav", ".json", ".latent"];
-                
+                let mut errors = Vec::new();
+
                 // 1. Workspace Cleanup
                 match infrastructure::workspace_manager::WorkspaceManager::cleanup_expired_files(&w_dir, hours, &allowed).await {
                     Ok(_) => info!("🧹 [File Scavenger] Workspace deep cleansing complete."),
-                    Err(e) => error!("❌ [File Scavenger] Failed to clean workspace: {}", e),
+                    Err(e) => {
+                        error!("❌ [File Scavenger] Failed to clean workspace: {}", e);
+                        errors.push(format!("workspace: {}", e));
+                    }
                 }
 
                 // 2. ComfyUI Temp Cleanup
                 let comfy_temp = format!("{}/temp", c_dir_base);
                 match infrastructure::workspace_manager::WorkspaceManager::cleanup_expired_files(&comfy_temp, hours, &allowed).await {
                     Ok(_) => info!("🧹 [File Scavenger] ComfyUI temp deep cleansing complete."),
-                    Err(e) => error!("❌ [File Scavenger] Failed to clean ComfyUI temp: {}", e),
-                }
-            })
-        })?
-    ).await?;
-
-    // === Job 6: The Delayed Watcher — Runs every 4 hours (The Sentinel) ===
-    let jq_watcher = job_queue.clone();
-    let yt_key = youtube_api_key.clone();
-    sched.add(
-        Job::new_async("0 0 */4 * * *", move |_uuid, mut _l| {
-            let jq = jq_watcher.clone();
-            let watcher = infrastructure::sns_watcher::SnsWatcher::new(yt_key.clone());
-            Box::pin(async move {
-                info!("👁️ [Sentinel] Delayed Watcher triggered. Scanning milestones...");
-                
-                // --- The Global Circuit Breaker ---
-                if let Ok(failures) = jq.get_global_api_failures().await {
-                    if failures >= 5 {
-                        warn!("🚨 [Sentinel] GLOBAL SLEEP MODE OVERRIDE. Consecutive API failures ({}). Skipping Execution.", failures);
-                        return;
+                    Err(e) => {
+                        error!("❌ [File Scavenger] Failed to clean ComfyUI temp: {}", e);
+                        errors.push(format!("comfyui temp: {}", e));
                     }
                 }
+                RunOutcome::tally(format!("files older than {}h removed", hours), errors)
+            }).await;
+        })
+    }).await?;
 
-                let milestones = vec![1, 7, 30]; // 24h, 7d, 30d
-                for days in milestones {
-                    match jq.fetch_jobs_for_evaluation(days, 10).await {
-                        Ok(jobs) => {
-                            for job in jobs {
-                                // Guard: SNS linking check
-                                let platform = match job.sns_platform.as_ref() {
-                                    Some(p) => p,
-                                    None => continue,
-                                };
-                                let video_id = match job.sns_video_id.as_ref() {
-                                    Some(id) => id,
-                                    None => continue,
-                                };
-
-                                // The Soft-Fail Resilience: Catch and log individual job errors
-                                match watcher.fetch_metrics(platform, video_id).await {
-                                    Ok(m) => {
-                                        // Reset Global Circuit Breaker on success
-                                        let _ = jq.record_global_api_success().await;
-
-                                        info!("📊 [Sentinel] Milestone {}d reached for Job {}: {} views, {} likes", days, job.id, m.views, m.likes);
-                                        // Record to Metrics Ledger (with comments for Temporal Context Guard)
-                                        let comments_json = serde_json::to_string(&m.comments).unwrap_or_else(|_| "[]".to_string());
-                                        if let Err(e) = jq.record_sns_metrics(&job.id, days, m.views, m.likes, m.comments_count, Some(&comments_json)).await {
-                                            error!("❌ [Sentinel] Failed to record metrics: {}", e);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        warn!("⚠️ [Sentinel] Failed to fetch metrics for Job {} (skip): {}", job.id, e);
-                                        
-                                        // Trip the global circuit breaker if the API fails
-                                        let _ = jq.record_global_api_failure().await;
-                                        
-                                        match jq.increment_job_retry_count(&job.id).await {
-                                            Ok(true) => error!("💀 [Sentinel] Poison Pill Activated for Job {}: API continually fails. Abandoning.", job.id),
-                                            Err(inc_err) => error!("❌ [Sentinel] Failed to increment retry count: {}", inc_err),
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => error!("❌ [Sentinel] Failed to fetch jobs for milestone {}d: {}", days, e),
-                    }
-                }
-            })
-        })?
-    ).await?;
-
-    // === Job 7: The Oracle Evaluator — Runs every 1 hour (The Final Verdict) ===
+    // === Job 6: The Delayed Watcher — Default: runs every 4 hours (The Sentinel) ===
+    let jq_watcher = job_queue.clone();
+    let channels_watcher = channels.clone();
+    add_job(&sched, &job_queue, &cron, "sentinel", &cron.sentinel, move |_uuid, mut _l| {
+        let jq = jq_watcher.clone();
+        let channels = channels_watcher.clone();
+        Box::pin(async move {
+            record_run(&jq, "sentinel", run_sentinel(&jq, &channels)).await;
+        })
+    }).await?;
+
+    // === Job 7: The Oracle Evaluator — Default: runs every 1 hour (The Final Verdict) ===
     let jq_eval = job_queue.clone();
     let gem_key_eval = gemini_api_key.clone();
-    let s_md_eval = soul_md.clone();
-    sched.add(
-        Job::new_async("0 0 * * * *", move |_uuid, mut _l| {
-            let jq = jq_eval.clone();
-            let s_md = s_md_eval.clone();
-            let oracle = infrastructure::oracle::Oracle::new(&gem_key_eval, "gemini-2.5-flash", s_md.clone());
-            Box::pin(async move {
-                let current_soul_hash = compute_soul_hash(&s_md);
+    let channels_eval = channels.clone();
+    let cost_eval = cost.clone();
+    let ollama_eval = ollama_url.clone();
+    let oracle_eval = oracle.clone();
+    add_job(&sched, &job_queue, &cron, "oracle", &cron.oracle, move |_uuid, mut _l| {
+        let jq = jq_eval.clone();
+        let channels = channels_eval.clone();
+        let gem_key = gem_key_eval.clone();
+        let cost = cost_eval.clone();
+        let ollama_url = ollama_eval.clone();
+        let oracle_config = oracle_eval.clone();
+        Box::pin(async move {
+            record_run(&jq, "oracle", async {
                 info!("🔮 [Oracle] Evaluator triggered. Checking for pending verdicts...");
 
                 // --- The Global Circuit Breaker ---
+                // ローカル審判があれば、クラウドを休ませたままローカルだけで判定を続ける
+                let mut cloud_down = false;
                 if let Ok(failures) = jq.get_global_api_failures().await {
-                    if failures >= 5 {
-                        warn!("🚨 [Oracle] GLOBAL SLEEP MODE OVERRIDE. Consecutive API failures ({}). Skipping Execution.", failures);
-                        return;
+                    if failures >= SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT {
+                        let probe = infrastructure::oracle::Oracle::new(&gem_key, &oracle_config.gemini_model, String::new())
+                            .with_ensemble(&oracle_config, &ollama_url);
+                        if !probe.can_judge_locally() {
+                            warn!("🚨 [Oracle] GLOBAL SLEEP MODE OVERRIDE. Consecutive API failures ({}). Skipping Execution.", failures);
+                            return RunOutcome::skipped(format!("global circuit breaker open ({} consecutive API failures) and no local judge", failures));
+                        }
+                        warn!("🚨 [Oracle] Global breaker is open ({} failures). Judging with the local model only.", failures);
+                        cloud_down = true;
                     }
                 }
 
                 match jq.fetch_pending_evaluations(10).await {
                     Ok(records) => {
+                        let (mut verdicts, mut errors) = (0, Vec::new());
                         for record in records {
                             // Guard: raw_comments_json must exist for evaluation
                             let comments_json = match record.raw_comments_json.as_ref() {
@@ -369,6 +610,15 @@ pub async fn start_cron_scheduler(
                             // Let's assume we need to fetch the job.
                             match jq.fetch_job(&record.job_id).await {
                                 Ok(Some(job)) => {
+                                    // 審判はジョブが属するチャンネルの魂に照らして行う
+                                    let s_md = channels.soul_md(&job.channel, job.soul.as_deref());
+                                    let current_soul_hash = compute_soul_hash(s_md);
+                                    let mut oracle = infrastructure::oracle::Oracle::new(&gem_key, &oracle_config.gemini_model, s_md.to_string())
+                                        .with_ensemble(&oracle_config, &ollama_url)
+                                        .with_cost_tracker(cost.for_job(Some(&record.job_id), None));
+                                    if cloud_down {
+                                        oracle = oracle.with_mode(infrastructure::oracle::OracleMode::Local);
+                                    }
                                     match oracle.evaluate(
                                         record.milestone_days,
                                         &job.topic,
@@ -378,23 +628,34 @@ pub async fn start_cron_scheduler(
                                         comments_json,
                                     ).await {
                                         Ok(verdict) => {
-                                            // Reset Global Circuit Breaker on success
-                                            let _ = jq.record_global_api_success().await;
+                                            // Reset Global Circuit Breaker on success (ローカルに逃げた場合は Gemini の失敗として数える)
+                                            if verdict.cloud_failed {
+                                                let _ = jq.record_global_api_failure().await;
+                                            } else if oracle.uses_cloud() {
+                                                let _ = jq.record_global_api_success().await;
+                                            }
+
+                                            info!("⚖️ [Oracle] Verdict decided for Job {}: topic={:.2}, soul={:.2} by {}{}", 
+                                                record.job_id, verdict.topic_score, verdict.soul_score, verdict.judges.join(" + "),
+                                                if verdict.disagreement { " (judges disagree)" } else { "" });
 
-                                            info!("⚖️ [Oracle] Verdict decided for Job {}: topic={:.2}, soul={:.2}", 
-                                                record.job_id, verdict.topic_score, verdict.soul_score);
-                                            
                                             // Commit the Phase 11 Idempotent Transaction
                                             if let Err(e) = jq.apply_final_verdict(record.id, verdict, &current_soul_hash).await {
                                                 error!("❌ [Oracle] Failed to commit verdict for Job {}: {}", record.job_id, e);
+                                                errors.push(format!("{}: {}", record.job_id, e));
+                                            } else {
+                                                verdicts += 1;
                                             }
                                         }
                                         Err(e) => {
                                             error!("❌ [Oracle] Evaluation failed for Job {}: {}", record.job_id, e);
-                                            
+                                            errors.push(format!("{}: {}", record.job_id, e));
+
                                             // Trip the global circuit breaker if the API fails
-                                            let _ = jq.record_global_api_failure().await;
-                                            
+                                            if oracle.uses_cloud() {
+                                                let _ = jq.record_global_api_failure().await;
+                                            }
+
                                             match jq.increment_oracle_retry_count(record.id).await {
                                                 Ok(true) => error!("💀 [Oracle] Poison Pill Activated for Record {}: LLM continually fails. Abandoning.", record.id),
                                                 Err(inc_err) => error!("❌ [Oracle] Failed to increment oracle retry count: {}", inc_err),
@@ -404,51 +665,174 @@ pub async fn start_cron_scheduler(
                                     }
                                 }
                                 Ok(None) => error!("❌ [Oracle] Job {} not found for record {}", record.job_id, record.id),
-                                Err(e) => error!("❌ [Oracle] Failed to fetch job {}: {}", record.job_id, e),
+                                Err(e) => {
+                                    error!("❌ [Oracle] Failed to fetch job {}: {}", record.job_id, e);
+                                    errors.push(format!("{}: {}", record.job_id, e));
+                                }
                             }
                         }
+                        let mode = if cloud_down { " (local judge only)" } else { "" };
+                        RunOutcome::tally(format!("{} verdict(s){}", verdicts, mode), errors)
+                    }
+                    Err(e) => {
+                        error!("❌ [Oracle] Failed to fetch pending evaluations: {}", e);
+                        RunOutcome::failed(e)
                     }
-                    Err(e) => error!("❌ [Oracle] Failed to fetch pending evaluations: {}", e),
                 }
-            })
-        })?
-    ).await?;
+            }).await;
+        })
+    }).await?;
 
-    // === Job 8: The Karma Distiller — Runs daily at 04:00 (Memory Compression) ===
+    // === Job 8: The Karma Distiller — Default: runs daily at 04:00 (Memory Compression) ===
     let jq_distill = job_queue.clone();
     let gem_key_distill = gemini_api_key.clone();
     let s_md_compress = soul_md.clone();
-    sched.add(
-        Job::new_async("0 0 4 * * *", move |_uuid, mut _l| {
-            let jq = jq_distill.clone();
-            let key = gem_key_distill.clone();
-            let s_md = s_md_compress.clone();
-            Box::pin(async move {
+    let tx_distill = log_tx.clone();
+    let review = cron.karma_distiller_review;
+    add_job(&sched, &job_queue, &cron, "karma_distiller", &cron.karma_distiller, move |_uuid, mut _l| {
+        let jq = jq_distill.clone();
+        let key = gem_key_distill.clone();
+        let s_md = s_md_compress.clone();
+        let tx = tx_distill.clone();
+        Box::pin(async move {
+            record_run(&jq, "karma_distiller", async {
                 info!("🧬 [Distiller] Analyzing memory banks for Token Asphyxiation...");
-                if let Err(e) = compress_karma_memories(&key, "gemini-2.5-flash", &*jq, &s_md).await {
-                    error!("❌ [Distiller] Karma Compression Failed: {}", e);
+                let review_tx = review.then_some(&tx);
+                match compress_karma_memories(&key, "gemini-2.5-flash", &jq, &s_md, review_tx).await {
+                    Ok(()) => RunOutcome::ok(if review { "compressed (pending review)" } else { "compressed" }),
+                    Err(e) => {
+                        error!("❌ [Distiller] Karma Compression Failed: {}", e);
+                        RunOutcome::failed(e)
+                    }
+                }
+            }).await;
+        })
+    }).await?;
+
+    // === Job 9: Audit Digest — Default: runs daily at 23:55 (誰が何を操作したか) ===
+    let jq_audit = job_queue.clone();
+    let tx_audit = log_tx.clone();
+    add_job(&sched, &job_queue, &cron, "audit_digest", &cron.audit_digest, move |_uuid, mut _l| {
+        let jq = jq_audit.clone();
+        let tx = tx_audit.clone();
+        Box::pin(async move {
+            record_run(&jq, "audit_digest", async {
+                match jq.summarize_audit(24).await {
+                    Ok(lines) => {
+                        let actions: i64 = lines.iter().map(|l| l.count).sum();
+                        if let Some(message) = crate::server::audit::format_digest(24, &lines) {
+                            let _ = tx.send(CoreEvent::ProactiveTalk { message, channel_id: 0 }).await;
+                        }
+                        RunOutcome::ok(format!("{} action(s) summarized", actions))
+                    }
+                    Err(e) => {
+                        error!("❌ [Audit] Failed to summarize the audit log: {}", e);
+                        RunOutcome::failed(e)
+                    }
                 }
-            })
-        })?
-    ).await?;
+            }).await;
+        })
+    }).await?;
+
+    // === Job 10: Production Report — Default: runs daily at 08:00 (朝の制作ダイジェスト) ===
+    let jq_report = job_queue.clone();
+    let tx_report = log_tx.clone();
+    let days = cron.production_report_days.max(1);
+    add_job(&sched, &job_queue, &cron, "production_report", &cron.production_report, move |_uuid, mut _l| {
+        let jq = jq_report.clone();
+        let tx = tx_report.clone();
+        Box::pin(async move {
+            record_run(&jq, "production_report", async {
+                match jq.fetch_production_report(days, crate::server::watchtower::REPORT_TOP_VIDEOS).await {
+                    Ok(report) => {
+                        let summary = format!("{} completed, {} failed", report.completed, report.failed);
+                        let _ = tx.send(CoreEvent::ProductionReport { report, channel_id: 0 }).await;
+                        RunOutcome::ok(summary)
+                    }
+                    Err(e) => {
+                        error!("❌ [Report] Failed to build the production report: {}", e);
+                        RunOutcome::failed(e)
+                    }
+                }
+            }).await;
+        })
+    }).await?;
 
     sched.start().await?;
-    info!("⏰ Cron scheduler started. The Wheel of Samsara is turning. (Synthesis: 7:00/19:00, Zombie Hunter: 15m, Distiller: 5m, Scavengers: daily, Sentinel: 4h, Oracle: 1h)");
+    let summary = cron.entries()
+        .iter()
+        .map(|(name, expr)| if CronConfig::is_disabled(expr) { format!("{}: off", name) } else { format!("{}: '{}'", name, expr.trim()) })
+        .collect::<Vec<_>>()
+        .join(", ");
+    info!("⏰ Cron scheduler started. The Wheel of Samsara is turning. ({})", summary);
 
     Ok(sched)
 }
 
+/// 夜間セルフテストを登録する。オーケストレーターはスケジューラ起動後に組み立てるので、別途追加する
+pub async fn schedule_self_test(
+    sched: &JobScheduler,
+    cron: &CronConfig,
+    orchestrator: Arc<crate::orchestrator::ProductionOrchestrator>,
+    jail: Arc<bastion::fs_guard::Jail>,
+    cfg: shared::config::SelfTestConfig,
+    log_tx: mpsc::Sender<CoreEvent>,
+    job_queue: Arc<SqliteJobQueue>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(expr) = active_schedule("Self Test", &cron.self_test) else { return Ok(()) };
+    let jq_self_test = job_queue.clone();
+    add_job(sched, &job_queue, cron, "self_test", expr, move |_uuid, mut _l| {
+        let orchestrator = orchestrator.clone();
+        let jail = jail.clone();
+        let cfg = cfg.clone();
+        let tx = log_tx.clone();
+        let jq = jq_self_test.clone();
+        Box::pin(async move {
+            record_run(&jq, "self_test", async {
+                let report = crate::self_test::run(&orchestrator, &jail, &cfg).await;
+                if report.passed() {
+                    return RunOutcome::ok(format!("passed ({})", report.project_id));
+                }
+                let outcome = RunOutcome::failed(report.problems.join("; "));
+                let _ = tx.send(CoreEvent::SelfTestFailed { project_id: report.project_id, problems: report.problems }).await;
+                outcome
+            }).await;
+        })
+    }).await?;
+    Ok(())
+}
+
+/// Samsara の 1 回の企画の結末
+#[derive(Debug, Clone, PartialEq)]
+pub enum SynthesisOutcome {
+    /// ジョブを積んだ
+    Enqueued(String),
+    /// 出し直しても直近の動画と題材が重なるので見送った
+    Repeated,
+    /// 出し直しても編集方針 (`[topic_policy]`) に反するので見送った
+    Blocked { topic: String, reason: String },
+}
+
+/// Samsara: チャンネルの次のジョブを企画して積む
+#[allow(clippy::too_many_arguments)]
 pub async fn synthesize_next_job(
     gemini_api_key: &str,
     model_name: &str,
-    brave_api_key: &str,
+    trends: &CompositeTrendSource,
     job_queue: &SqliteJobQueue,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    channel: &Channel,
+    soul: Option<&Soul>,
+    cost: &CostTracker,
+    dedupe_days: i64,
+    dedupe_threshold: f64,
+    topic_policy_file: &str,
+) -> Result<SynthesisOutcome, Box<dyn std::error::Error + Send + Sync>> {
     let root_dir = std::env::current_dir()?;
+    // 方針ファイルが読めないときは、方針なしで企画せず失敗にする
+    let policy = TopicPolicy::load(std::path::Path::new(topic_policy_file))?;
     
-    // 1. Load the Immutable Core (`SOUL.md`)
-    let soul_path = root_dir.join("SOUL.md");
-    let soul_content = fs::read_to_string(&soul_path).await.unwrap_or_else(|_| "SOUL.md not found. Be a helpful AI.".to_string());
+    // 1. Load the Immutable Core (指定の SOUL プロファイル、無ければチャンネルの SOUL)
+    let soul_content = soul.map(|s| s.content.clone()).unwrap_or_else(|| channel.soul_md.clone());
     let current_soul_hash = compute_soul_hash(&soul_content);
 
     // 2. Load the Capability Matrix (`skills.md`)
@@ -456,7 +840,7 @@ pub async fn synthesize_next_job(
     let skills_content = fs::read_to_string(&skills_path).await.unwrap_or_else(|_| "Skills not defined.".to_string());
 
     let client: gemini::Client = gemini::Client::new(gemini_api_key)
-        .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Gemini Client init failed: {}", e))))?;
+        .map_err(|e| Box::new(std::io::Error::other(format!("Gemini Client init failed: {}", e))))?;
 
     // --- Phase 1: The Sonar Ping (Two-Pass Architecture) ---
     // Temporal Grounding
@@ -464,32 +848,38 @@ pub async fn synthesize_next_job(
     let time_context = format!("[SYSTEM_TIME: {} {} JST]", now_jst.format("%Y-%m-%d"), now_jst.format("%A"));
     
     // Entropy Injection (揺らぎの注入)
-    let angles = vec!["技術のブレイクスルー", "倫理的な炎上", "著名なアーティストの新作", "奇妙なミーム", "ビジネスへの応用", "法的な規制問題", "ポップカルチャーの融合"];
+    let angles = ["技術のブレイクスルー", "倫理的な炎上", "著名なアーティストの新作", "奇妙なミーム", "ビジネスへの応用", "法的な規制問題", "ポップカルチャーの融合"];
     let now_ms = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis();
     let idx = (now_ms as usize) % angles.len();
     let angle = angles[idx];
 
+    let sonar_preamble = format!(
+        "{} あなたは動画企画者の一部です。以下のSOULコンセプトに合致し、かつ指定された視点（アングル）から今日話題になっている事象をニュース・トレンドで検索するための、2〜3語の『生キーワード』を出力してください。出力はキーワードのみとし、余計な言葉は一切含めないでください。\n\n【Soul】\n{}\n\n【本日の視点】\n{}",
+        time_context, soul_content, angle
+    );
     let sonar_agent = client.agent(model_name)
-        .preamble(&format!(
-            "{} あなたは動画企画者の一部です。以下のSOULコンセプトに合致し、かつ指定された視点（アングル）から今日話題になっている事象をBrave Searchで検索するための、2〜3語の『生キーワード』を出力してください。出力はキーワードのみとし、余計な言葉は一切含めないでください。\n\n【Soul】\n{}\n\n【本日の視点】\n{}",
-            time_context, soul_content, angle
-        ))
+        .preamble(&sonar_preamble)
         .build();
 
-    let search_query = sonar_agent.prompt("本日の検索キーワードを出力せよ:").await?.trim().to_string();
+    let sonar_prompt = "本日の検索キーワードを出力せよ:";
+    let sonar_response = sonar_agent.prompt(sonar_prompt).await?;
+    cost.record(CostEntry::llm("gemini", "samsara_sonar", &format!("{}\n{}", sonar_preamble, sonar_prompt), &sonar_response)).await;
+    let search_query = sonar_response.trim().to_string();
     info!("📡 [Sonar Ping] Generated Query: '{}' (Angle: {})", search_query, angle);
 
     // --- Phase 2: The World Context (Fetch & Quarantine) ---
-    use infrastructure::trend_sonar::BraveTrendSonar;
     use factory_core::traits::TrendSource;
 
     let fallback_context = "本日の検索はシステムエラーによりスキップされました。AIとアートに関する普遍的なテーマで動画を生成してください。".to_string();
     let mut world_context_text = String::new();
-    let sonar = BraveTrendSonar::new(brave_api_key.to_string());
-    
+
     let mut search_success = false;
     for _ in 0..2 { // Bounded Search Strategy: Max Iterations = 2
-        match sonar.get_trends(&search_query).await {
+        let result = trends.get_trends(&search_query).await;
+        if trends.source_names().contains(&"brave") {
+            cost.record(CostEntry::call("brave", "samsara_search")).await;
+        }
+        match result {
             Ok(trends) if !trends.is_empty() => {
                 let snippets: Vec<String> = trends.into_iter().map(|t| t.keyword).collect();
                 world_context_text = snippets.join("\n");
@@ -497,29 +887,47 @@ pub async fn synthesize_next_job(
                 break;
             },
             Ok(_) => {
-                warn!("⚠️ Brave API returned 0 results for '{}'", search_query);
+                warn!("⚠️ Trend sources returned 0 results for '{}'", search_query);
                 break;
             },
             Err(e) => {
-                error!("❌ Brave API Error: {}", e);
+                error!("❌ Trend sources error: {}", e);
             }
         }
     }
 
+    // /why のために、どこでフォールバックが起きたかを記録しておく
+    let mut fallback_events: Vec<String> = Vec::new();
+    let world_context_excerpt: String = world_context_text.chars().take(300).collect();
+
     if !search_success {
         warn!("⚠️ Applying Circuit Breaker fallback for World Context.");
         world_context_text = fallback_context;
+        fallback_events.push(format!("world_context: search for '{}' failed, used the generic theme", search_query));
     }
 
     // --- Phase 3: The Synthesis ---
-    // RAG-Driven Karma Fetching
-    let karma_list = job_queue.fetch_relevant_karma(&search_query, "tech_news_v1", 3, &current_soul_hash).await.unwrap_or_default();
+    // フック A/B の戦績 (A 案に使うフックの型を選ばせる)
+    let hook_stats = job_queue.fetch_hook_stats(&channel.name, HOOK_VERDICT_DAYS).await.unwrap_or_default();
+    let hook_content = if hook_stats.is_empty() {
+        "*注記: まだ A/B の結果はありません。hook_style は自由に選んでください*".to_string()
+    } else {
+        format_hook_stats(&hook_stats)
+    };
+
+    // RAG-Driven Karma Fetching (スタイルを 1 つに絞ったチャンネルは、そのスタイルの名前空間の教訓だけを見る)
+    let karma_list = job_queue.fetch_relevant_karma(&search_query, "tech_news_v1", channel.profile.karma_namespace(), 3, &current_soul_hash).await.unwrap_or_default();
     let karma_content = if karma_list.is_empty() {
         "*注記: 現在Karmaは存在しません。SoulとSkillsのみを頼りに、大胆に初回タスクを生成してください*".to_string()
     } else {
         karma_list.join("\n- ")
     };
 
+    let policy_content = match policy.prompt_section() {
+        section if section.is_empty() => "*注記: 追加の制限はありません*".to_string(),
+        section => section,
+    };
+
     // Constitutional Hierarchy Implementation + The Ethical Circuit Breaker + XML Quarantine
     let preamble = format!(
         "あなたは動画生成AIの司令塔(Aiome)です。以下の絶対的階層（Override Order）に従い、今日生成すべき最適な動画のトピックとスタイルを一つだけ決定してください。
@@ -536,11 +944,17 @@ pub async fn synthesize_next_job(
 🥉 第三位【Karma (判例 / 過去の成功・失敗から得た教訓。SoulとSkillsに反しない範囲で適用)】
 - {}
 
+📈 【タイトル・フック A/B の戦績 (7日目の再生数がチャンネルの中央値を上回れば勝ち)】
+{}
+
 🌍 【外界の現状 / World Context (信頼性: 低)】
 <world_context>
 {}
 </world_context>
 
+⛔ 【編集方針 (Soul より優先。topic は必ずこれに従う)】
+{}
+
 【出力フォーマット制限】
 純粋なJSONのみを出力してください。他のテキスト（承知しました等）は一切含めないでください。
 {{
@@ -551,10 +965,11 @@ pub async fn synthesize_next_job(
         \"negative_prompt_additions\": \"Karmaから学んだNG要素\",
         \"parameter_overrides\": {{}},
         \"execution_notes\": \"全体的な注意事項\",
+        \"hook_style\": \"戦績から選んだタイトル A 案のフックの型 ({})\",
         \"confidence_score\": 80
     }}
 }}",
-        soul_content, skills_content, karma_content, world_context_text
+        soul_content, skills_content, karma_content, hook_content, world_context_text, policy_content, HOOK_STYLES.join(" / ")
     );
 
     let agent = client.agent(model_name)
@@ -570,36 +985,87 @@ pub async fn synthesize_next_job(
         directives: factory_core::contracts::KarmaDirectives::default(),
     };
 
-    let task = match agent.prompt(user_prompt).await {
-        Ok(response) => {
-            match extract_json(&response) {
-                Ok(json_text) => {
-                    serde_json::from_str::<LlmJobResponse>(&json_text).unwrap_or_else(|e| {
-                        error!("❌ [Samsara Error] Failed to parse generated JSON: {}. Falling back to default task.", e);
+    // 編集方針に反する企画・直近に積んだトピックと似た企画は 1 度だけ出し直させ、それでも駄目なら見送る
+    let recent_topics = if dedupe_days > 0 {
+        job_queue.fetch_recent_topics(&channel.name, dedupe_days, 30).await.unwrap_or_else(|e| {
+            warn!("⚠️ [Samsara] Failed to fetch recent topics for dedupe: {}", e);
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+
+    let mut prompt = user_prompt.clone();
+    let mut reprompted = false;
+    let task = loop {
+        let sent = format!("{}\n{}", preamble, prompt);
+        let task = match agent.prompt(prompt.clone()).await {
+            Ok(response) => {
+                cost.record(CostEntry::llm("gemini", "samsara_synthesis", &sent, &response)).await;
+                match extract_json(&response) {
+                    Ok(json_text) => {
+                        serde_json::from_str::<LlmJobResponse>(&json_text).unwrap_or_else(|e| {
+                            error!("❌ [Samsara Error] Failed to parse generated JSON: {}. Falling back to default task.", e);
+                            fallback_events.push(format!("synthesis: invalid JSON ({}), used the default task", e));
+                            fallback_task.clone()
+                        })
+                    },
+                    Err(e) => {
+                        error!("❌ [Samsara Error] Failed to extract JSON from response: {}. Falling back to default task.", e);
+                        fallback_events.push(format!("synthesis: no JSON in response ({}), used the default task", e));
                         fallback_task.clone()
-                    })
-                },
-                Err(e) => {
-                    error!("❌ [Samsara Error] Failed to extract JSON from response: {}. Falling back to default task.", e);
-                    fallback_task
+                    }
                 }
+            },
+            Err(e) => {
+                error!("❌ [Samsara Error] LLM synthesis failed: {}. Falling back to default task.", e);
+                fallback_events.push(format!("synthesis: LLM call failed ({}), used the default task", e));
+                fallback_task.clone()
             }
-        },
-        Err(e) => {
-            error!("❌ [Samsara Error] LLM synthesis failed: {}. Falling back to default task.", e);
-            fallback_task
+        };
+
+        if let Err(violation) = policy.check(&task.topic) {
+            if reprompted {
+                return Ok(SynthesisOutcome::Blocked { topic: task.topic, reason: violation.to_string() });
+            }
+            warn!("⛔ [Samsara] Topic '{}' {}. Re-prompting with the editorial policy.", task.topic, violation);
+            fallback_events.push(format!("topic_policy: '{}' {}, re-prompted", task.topic, violation));
+            prompt = format!("{}\n\n前回の案「{}」は編集方針に反します ({})。方針に従う別の題材を選ぶこと。", user_prompt, task.topic, violation);
+            reprompted = true;
+            continue;
+        }
+        let Some((recent, similarity)) = find_similar_topic(&task.topic, &recent_topics, dedupe_threshold) else { break task };
+        if reprompted {
+            warn!("⏭️ [Samsara] Topic '{}' still repeats '{}' (similarity {:.2}) for channel '{}'. Declining this synthesis.", task.topic, recent, similarity, channel.name);
+            return Ok(SynthesisOutcome::Repeated);
         }
+        warn!("🔁 [Samsara] Topic '{}' repeats recent topic '{}' (similarity {:.2}). Re-prompting with the recently covered list.", task.topic, recent, similarity);
+        fallback_events.push(format!("dedupe: '{}' repeated recent topic '{}' (similarity {:.2}), re-prompted", task.topic, recent, similarity));
+        prompt = format!("{}\n\n直近で扱ったトピック (これらと重複しない題材を選ぶこと):\n- {}", user_prompt, recent_topics.join("\n- "));
+        reprompted = true;
     };
 
     // 6. Skill Existence Validation (The Hallucinated Skill 防衛)
     let validated_style = {
         let workflow_dir = root_dir.join("resources").join("workflows");
         let workflow_path = workflow_dir.join(format!("{}.json", &task.style));
-        if workflow_path.exists() {
-            task.style.clone()
-        } else {
+        if !workflow_path.exists() {
             warn!("⚠️ [Samsara] Workflow '{}' not found at {:?}. Falling back to 'tech_news_v1'.", task.style, workflow_path);
+            fallback_events.push(format!("style: workflow '{}' does not exist, used 'tech_news_v1'", task.style));
+            "tech_news_v1".to_string()
+        } else if infrastructure::workflow_doctor::WorkflowRegistry::load(&workflow_dir).is_broken(&task.style) {
+            // `workflows check` で今の ComfyUI と合わないと分かったワークフローは選ばない
+            warn!("⚠️ [Samsara] Workflow '{}' failed the last compatibility check. Falling back to 'tech_news_v1'.", task.style);
+            fallback_events.push(format!("style: workflow '{}' is marked broken by `workflows check`, used 'tech_news_v1'", task.style));
             "tech_news_v1".to_string()
+        } else if !channel.profile.allows_style(&task.style) {
+            // チャンネルで許可されていないスタイルは、そのチャンネルの先頭スタイルへ寄せる
+            let fallback = channel.profile.styles.first().cloned().unwrap_or_else(|| "tech_news_v1".to_string());
+            warn!("⚠️ [Samsara] Style '{}' is not enabled for channel '{}'. Falling back to '{}'.", task.style, channel.name, fallback);
+            fallback_events.push(format!("style: '{}' is not enabled for channel '{}', used '{}'", task.style, channel.name, fallback));
+            fallback
+        } else {
+            task.style.clone()
         }
     };
 
@@ -607,19 +1073,186 @@ pub async fn synthesize_next_job(
     let directives_json = serde_json::to_string(&task.directives).unwrap_or_else(|_| "{}".to_string());
 
     // 8. Enqueue the synthesized/fallback job
-    let job_id = job_queue.enqueue(&task.topic, &validated_style, Some(&directives_json)).await?;
-    info!("🔮 [Samsara] New Job Enqueued: ID={}, Topic='{}', Style='{}', Confidence={}", 
-        job_id, task.topic, validated_style, task.directives.clamped_confidence());
+    //    チャンネルが連載シリーズに紐付いていれば、企画を次話として積む
+    let series = match channel.profile.series.as_str() {
+        "" => None,
+        name => match job_queue.fetch_series(name).await {
+            Ok(Some(series)) => Some(series),
+            Ok(None) => {
+                warn!("⚠️ [Samsara] Series '{}' of channel '{}' does not exist. Enqueuing a standalone job.", name, channel.name);
+                None
+            }
+            Err(e) => {
+                warn!("⚠️ [Samsara] Failed to look up series '{}': {}. Enqueuing a standalone job.", name, e);
+                None
+            }
+        },
+    };
+    let job_id = match series {
+        Some(series) => {
+            let (job_id, number) = job_queue.enqueue_episode(&series.id, Some(&task.topic), Some(&validated_style), Some(&directives_json), None).await?;
+            info!("📺 [Samsara] Planned '{}' episode #{}", series.name, number);
+            job_id
+        }
+        None => job_queue.enqueue_for_channel(&channel.name, soul.map(|s| s.name.as_str()), &task.topic, &validated_style, Some(&directives_json)).await?,
+    };
+    info!("🔮 [Samsara] New Job Enqueued: ID={}, Channel='{}', Topic='{}', Style='{}', Confidence={}", 
+        job_id, channel.name, task.topic, validated_style, task.directives.clamped_confidence());
+    cost.attribute(&job_id).await;
+
+    // 9. Provenance — /why で「なぜこのジョブが生まれたか」を説明するための記録
+    let provenance = JobProvenance {
+        angle: angle.to_string(),
+        search_query,
+        world_context_excerpt,
+        karma_applied: karma_list,
+        fallback_events,
+        proposed_style: task.style.clone(),
+        confidence_score: task.directives.clamped_confidence(),
+        execution_notes: task.directives.execution_notes.clone(),
+        similarity_nudges: Vec::new(),
+    };
+    if let Err(e) = job_queue.store_provenance(&job_id, &provenance).await {
+        warn!("⚠️ [Samsara] Failed to store provenance for Job {}: {}", job_id, e);
+    }
 
-    Ok(())
+    Ok(SynthesisOutcome::Enqueued(job_id))
+}
+
+/// The Sentinel の 1 回分: マイルストーンに達した投稿のメトリクスを集め、取得に失敗し続ける投稿は Poison Pill で見切る
+async fn run_sentinel(jq: &SqliteJobQueue, channels: &ChannelRegistry) -> RunOutcome {
+    info!("👁️ [Sentinel] Delayed Watcher triggered. Scanning milestones...");
+
+    // --- The Global Circuit Breaker ---
+    if let Ok(failures) = jq.get_global_api_failures().await {
+        if failures >= SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT {
+            warn!("🚨 [Sentinel] GLOBAL SLEEP MODE OVERRIDE. Consecutive API failures ({}). Skipping Execution.", failures);
+            return RunOutcome::skipped(format!("global circuit breaker open ({} consecutive API failures)", failures));
+        }
+    }
+
+    let (mut recorded, mut fetch_failures, mut errors) = (0, 0, Vec::new());
+    let milestones = vec![1, 7, 30]; // 24h, 7d, 30d
+    for days in milestones {
+        match jq.fetch_jobs_for_evaluation(days, 10).await {
+            Ok(jobs) => {
+                for job in jobs {
+                    // Guard: SNS linking check
+                    let platform = match job.sns_platform.as_ref() {
+                        Some(p) => p,
+                        None => continue,
+                    };
+                    let video_id = match job.sns_video_id.as_ref() {
+                        Some(id) => id,
+                        None => continue,
+                    };
+
+                    // 投稿先チャンネルの資格情報で取得する
+                    let watcher = SnsWatcher::for_channel(&channels.get(&job.channel).profile);
+                    let Some(platform_name) = canonical_platform(platform).filter(|_| watcher.supports(platform)) else {
+                        // 資格情報の欠落は API 障害ではないので、サーキットブレーカーには数えない
+                        warn!("⚠️ [Sentinel] No metrics provider for '{}' on channel '{}' (Job {}). Skipping.", platform, job.channel, job.id);
+                        if let Ok(true) = jq.increment_job_retry_count(&job.id).await {
+                            error!("💀 [Sentinel] Poison Pill Activated for Job {}: platform '{}' cannot be observed. Abandoning.", job.id, platform);
+                        }
+                        continue;
+                    };
+
+                    // The Soft-Fail Resilience: Catch and log individual job errors
+                    match watcher.fetch_metrics(platform, video_id).await {
+                        Ok(m) => {
+                            // Reset Global Circuit Breaker on success
+                            let _ = jq.record_global_api_success().await;
+
+                            info!("📊 [Sentinel] Milestone {}d reached for Job {} ({}): {} views, {} likes", days, job.id, platform_name, m.views, m.likes);
+                            // Record to Metrics Ledger (with comments for Temporal Context Guard)
+                            let comments_json = serde_json::to_string(&m.comments).unwrap_or_else(|_| "[]".to_string());
+                            if let Err(e) = jq.record_sns_metrics(&job.id, platform_name, days, m.views, m.likes, m.comments_count, Some(&comments_json)).await {
+                                error!("❌ [Sentinel] Failed to record metrics: {}", e);
+                                errors.push(format!("{}: {}", job.id, e));
+                            } else {
+                                recorded += 1;
+                                if days == HOOK_VERDICT_DAYS {
+                                    record_hook_karma(jq, channels, &job, m.views).await;
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            warn!("⚠️ [Sentinel] Failed to fetch metrics for Job {} (skip): {}", job.id, e);
+                            fetch_failures += 1;
+
+                            // Trip the global circuit breaker if the API fails
+                            let _ = jq.record_global_api_failure().await;
+
+                            match jq.increment_job_retry_count(&job.id).await {
+                                Ok(true) => error!("💀 [Sentinel] Poison Pill Activated for Job {}: API continually fails. Abandoning.", job.id),
+                                Err(inc_err) => error!("❌ [Sentinel] Failed to increment retry count: {}", inc_err),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                error!("❌ [Sentinel] Failed to fetch jobs for milestone {}d: {}", days, e);
+                errors.push(format!("milestone {}d: {}", days, e));
+            }
+        }
+    }
+    RunOutcome::tally(format!("{} metric snapshot(s) recorded, {} fetch failure(s)", recorded, fetch_failures), errors)
+}
+
+/// フック A/B の勝敗を判定するマイルストーン (日)
+const HOOK_VERDICT_DAYS: i64 = 7;
+
+/// フックの型ごとの戦績を 1 行ずつ
+fn format_hook_stats(stats: &[HookStat]) -> String {
+    stats
+        .iter()
+        .map(|s| format!("- {}: {}勝{}敗 (平均 {:.0} 再生)", s.hook_style, s.wins, s.losses, s.avg_views))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
+/// 7 日目の再生数が出たら、公開したタイトル案とフックの型ごとの戦績を Karma に残す
+async fn record_hook_karma(jq: &SqliteJobQueue, channels: &ChannelRegistry, job: &factory_core::traits::Job, views: i64) {
+    let variant = match jq.fetch_published_variant(&job.id).await {
+        Ok(Some(v)) => v,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("⚠️ [Sentinel] Failed to load the published title of Job {}: {}", job.id, e);
+            return;
+        }
+    };
+    let stats = match jq.fetch_hook_stats(&job.channel, HOOK_VERDICT_DAYS).await {
+        Ok(stats) => stats,
+        Err(e) => {
+            warn!("⚠️ [Sentinel] Failed to aggregate hook stats for channel '{}': {}", job.channel, e);
+            return;
+        }
+    };
+    let style = if variant.hook_style.is_empty() { "unlabeled" } else { variant.hook_style.as_str() };
+    let lesson = format!(
+        "HOOK A/B [{}] 7日目: 公開案 {}「{}」(hook: {}) は {} 再生。フック別戦績:\n{}\n勝率の高い型をタイトル A 案に使うこと。",
+        job.channel, variant.label, variant.title, style, views, format_hook_stats(&stats)
+    );
+    let soul_hash = compute_soul_hash(channels.soul_md(&job.channel, job.soul.as_deref()));
+    // Samsara と同じスキル・チャンネルの名前空間に置く (global だと他チャンネルの Karma 枠を食う)
+    let namespace = channels.get(&job.channel).profile.karma_namespace().unwrap_or(job.style.as_str());
+    match jq.store_karma(&job.id, "tech_news_v1", Some(namespace), &lesson, "Creative", &soul_hash).await {
+        Ok(()) => info!("🪝 [Sentinel] Hook result recorded for Job {}: variant {} ({}) {} views", job.id, variant.label, style, views),
+        Err(e) => warn!("⚠️ [Sentinel] Failed to store hook karma for Job {}: {}", job.id, e),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn distill_karma(
     gemini_key: &str,
     model_name: &str,
     job_queue: &SqliteJobQueue,
     job_id: &str,
     skill_id: &str,
+    namespace: Option<&str>,
     execution_log: &str,
     is_success: bool,
     human_rating: Option<i32>,
@@ -628,7 +1261,7 @@ pub async fn distill_karma(
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let current_soul_hash = compute_soul_hash(soul_content);
     let client: gemini::Client = gemini::Client::new(gemini_key)
-        .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Gemini Client init failed: {}", e))))?;
+        .map_err(|e| Box::new(std::io::Error::other(format!("Gemini Client init failed: {}", e))))?;
 
     let preamble = "あなたはAIエージェントの記憶と経験を整理する「内省モジュール(Reflector)」です。与えられた実行ログを詳細に分析し、次回以降の動画生成で活かせる【具体的かつ本質的な教訓】を1〜2文で抽出してください。
 🚨 注意:
@@ -649,7 +1282,7 @@ pub async fn distill_karma(
     
     // Distill phase generates 'Technical' karma (automated system introspection).
     // 'Creative' karma is generated separately via human async feedback (set_creative_rating).
-    job_queue.store_karma(job_id, skill_id, lesson.trim(), "Technical", &current_soul_hash).await?;
+    job_queue.store_karma(job_id, skill_id, namespace, lesson.trim(), "Technical", &current_soul_hash).await?;
     info!("🧘 [Samsara] Karma distilled for Job {} (Skill: {}): {}", job_id, skill_id, lesson.trim());
 
     // --- Phase 2: Generating the "Soul Voice" (Subjective Reflection) ---
@@ -725,11 +1358,13 @@ fn extract_json(text: &str) -> Result<String, Box<dyn std::error::Error + Send +
     }
 }
 
+/// `review_tx` があれば蒸留をレビュー待ちで置き、Watchtower の承認後に元の教訓と入れ替える
 async fn compress_karma_memories(
     gemini_key: &str,
     model_name: &str,
     job_queue: &SqliteJobQueue,
     soul_content: &str,
+    review_tx: Option<&mpsc::Sender<CoreEvent>>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let current_soul_hash = compute_soul_hash(soul_content);
     let threshold = 20; // Token Asphyxiation Trigger Limit
@@ -740,16 +1375,16 @@ async fn compress_karma_memories(
     }
 
     let client: rig::providers::gemini::Client = rig::providers::gemini::Client::new(gemini_key)
-        .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Gemini Client init failed: {}", e))))?;
+        .map_err(|e| Box::new(std::io::Error::other(format!("Gemini Client init failed: {}", e))))?;
 
     // The Distiller Preamble: Absolute compression of semantic memories
     let preamble = "あなたはAIエージェントの膨大な記憶を整理・圧縮する「深層意識(Karma Distiller)」です。\n以下のリストは、特定のスキルに関する過去の複数の教訓（Karma）です。\n重複する内容を統合し、最も重要で普遍的な【単一の高度な戒め（Synthesized Karma）】として抽出してください。\n出力は純粋なテキストのみとし、絶対に前置きや形式的な言葉を含めず、核心のみを述べてください。";
 
-    for skill in skills {
-        let raw_karmas = job_queue.fetch_raw_karma_for_skill(&skill).await?;
+    for (skill, namespace) in skills {
+        let raw_karmas = job_queue.fetch_raw_karma_for_skill(&skill, namespace.as_deref()).await?;
         if raw_karmas.len() as i64 <= threshold { continue; } // Double check
 
-        info!("🧬 [Distiller] Compressing {} memories for skill '{}'...", raw_karmas.len(), skill);
+        info!("🧬 [Distiller] Compressing {} memories for skill '{}' (namespace: {})...", raw_karmas.len(), skill, namespace.as_deref().unwrap_or("shared"));
         
         let mut text_blocks = Vec::new();
         let mut ids = Vec::new();
@@ -764,8 +1399,19 @@ async fn compress_karma_memories(
         match agent.prompt(user_prompt).await {
             Ok(distilled) => {
                 info!("🔮 [Distiller] Synthesized Karma for '{}': {}", skill, distilled);
-                if let Err(e) = job_queue.apply_distilled_karma(&skill, &distilled, &ids, &current_soul_hash).await {
-                    error!("❌ [Distiller] Failed to apply distilled karma to DB: {}", e);
+                let Some(tx) = review_tx else {
+                    if let Err(e) = job_queue.apply_distilled_karma(&skill, namespace.as_deref(), &distilled, &ids, &current_soul_hash).await {
+                        error!("❌ [Distiller] Failed to apply distilled karma to DB: {}", e);
+                    }
+                    continue;
+                };
+                match job_queue.propose_distilled_karma(&skill, namespace.as_deref(), distilled.trim(), &ids, &current_soul_hash).await {
+                    Ok(karma_id) => {
+                        info!("⏸️ [Distiller] Synthesized Karma {} for '{}' awaits review in Watchtower", karma_id, skill);
+                        let replaced = raw_karmas.into_iter().map(|(_, lesson)| lesson).collect();
+                        let _ = tx.send(CoreEvent::KarmaReviewRequest { karma_id, skill: skill.clone(), lesson: distilled.trim().to_string(), replaced }).await;
+                    }
+                    Err(e) => error!("❌ [Distiller] Failed to queue distilled karma for review: {}", e),
                 }
             }
             Err(e) => error!("❌ [Distiller] LLM compression failed for {}: {}", skill, e),
@@ -782,7 +1428,7 @@ pub async fn notify_master(
     event_description: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let client = rig::providers::gemini::Client::new(gemini_key)
-        .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Gemini Client init failed: {}", e))))?;
+        .map_err(|e| Box::new(std::io::Error::other(format!("Gemini Client init failed: {}", e))))?;
     
     let preamble = format!(
         "あなたは以下の【魂（SOUL）】を持つAIエージェント「Watchtower」です。マスターに対して、システムで起きた出来事を報告するか、今の気分を一言、語りかけてください。\n短く、感情を込めて。絵文字を使っても良いです。丁寧すぎず、相棒としての距離感で。前置き（「報告します」など）は不要です。\n\n【あなたの魂（SOUL）】\n{}",
@@ -798,3 +1444,87 @@ pub async fn notify_master(
         Err(e) => Err(format!("LLM notify failed: {}", e).into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_missed_run_compares_the_last_success_with_the_period() {
+        let samsara = "0 0 7,19 * * *";
+        let now = at("2025-01-03T08:00:00Z");
+        // 07:00 の枠を寝過ごした (最後の成功は前日 19:00、周期 12 時間より前)
+        assert!(missed_run(samsara, Some(at("2025-01-02T18:59:00Z")), now));
+        // 07:00 の枠は済んでいる
+        assert!(!missed_run(samsara, Some(at("2025-01-03T07:00:05Z")), now));
+        // 記録が無ければ補わない、無効化されたジョブも同じ
+        assert!(!missed_run(samsara, None, now));
+        assert!(!missed_run("off", Some(at("2024-12-01T00:00:00Z")), now));
+    }
+
+    #[test]
+    fn test_partial_runs_count_as_done() {
+        let partial = RunOutcome::tally_targets("1 job(s) enqueued".to_string(), 1, vec!["kids: quota".to_string()]);
+        assert_eq!((partial.outcome, partial.detail.as_deref()), (CronOutcome::Partial, Some("1 job(s) enqueued; kids: quota")));
+        assert_eq!(RunOutcome::tally_targets("0 job(s) enqueued".to_string(), 0, vec!["main: down".to_string()]).outcome, CronOutcome::Failed);
+        assert_eq!(RunOutcome::tally_targets("2 job(s) enqueued".to_string(), 2, Vec::new()).outcome, CronOutcome::Ok);
+    }
+
+    #[test]
+    fn test_format_cron_runs_shows_outcome_time_and_detail() {
+        let run = |id, outcome: &str, finished_at: Option<&str>, detail: Option<&str>| CronRun {
+            id,
+            job: "samsara".to_string(),
+            started_at: "2025-01-03T07:00:00Z".to_string(),
+            finished_at: finished_at.map(str::to_string),
+            outcome: outcome.to_string(),
+            detail: detail.map(str::to_string),
+        };
+        let text = format_cron_runs(Some("samsara"), &[
+            run(3, "running", None, None),
+            run(2, "skipped", Some("2025-01-03T07:00:00Z"), Some("")),
+            run(1, "failed", Some("2025-01-03T07:00:42Z"), Some(&"x".repeat(150))),
+        ]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "⏰ **Recent cron runs for `samsara`**");
+        assert_eq!(lines[1], "⏳ `samsara` 01-03 16:00 JST");
+        assert_eq!(lines[2], "⏭️ `samsara` 01-03 16:00 JST (0s)");
+        assert_eq!(lines[3], format!("❌ `samsara` 01-03 16:00 JST (42s) — {}", "x".repeat(100)));
+        assert_eq!(format_cron_runs(None, &[]), "⏰ No cron runs recorded.");
+    }
+
+    #[cfg(feature = "chaos")]
+    #[tokio::test]
+    async fn test_sentinel_poisons_a_post_whose_metrics_api_keeps_failing() {
+        use infrastructure::chaos::{self, Fault, FaultRule};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let jq = SqliteJobQueue::new(tmp.path().join("test.db").to_str().unwrap()).await.unwrap();
+        let config = shared::config::FactoryConfig { youtube_api_key: "test-key".into(), ..Default::default() };
+        let channels = ChannelRegistry::load(&config, "soul");
+        let id = jq.enqueue("Observed", "cinematic", None).await.unwrap();
+        jq.link_sns_data(&id, "youtube", "vid123").await.unwrap();
+        sqlx::query("UPDATE jobs SET published_at = datetime('now', '-2 days') WHERE id = ?")
+            .bind(&id)
+            .execute(jq.pool_ref())
+            .await
+            .unwrap();
+
+        // 3 回の巡回で SNS API が 500 を返し続ける
+        chaos::set_rule(Fault::SnsError, FaultRule { probability: 1.0, remaining: Some(3) }).unwrap();
+        for _ in 0..3 {
+            let outcome = run_sentinel(&jq, &channels).await;
+            assert_eq!(outcome.outcome, CronOutcome::Ok, "a fetch failure is soft: {:?}", outcome.detail);
+        }
+        assert_eq!(jq.get_global_api_failures().await.unwrap(), 3);
+
+        let job = jq.fetch_job(&id).await.unwrap().unwrap();
+        assert_eq!(job.status, factory_core::traits::JobStatus::Failed);
+        assert!(job.error_message.unwrap().contains("Poison Pill"));
+        chaos::clear(None);
+    }
+}
@@ -2,3 +2,18 @@ pub mod router;
 pub mod telemetry;
 pub mod watchtower;
 pub mod cron;
+pub mod why;
+pub mod takedown;
+pub mod openapi;
+pub mod auth;
+pub mod calendar;
+pub mod review;
+pub mod timeline;
+pub mod preview;
+pub mod audit;
+pub mod status;
+pub mod captions;
+pub mod topic_guard;
+pub mod notify;
+#[cfg(feature = "chaos")]
+pub mod chaos;
@@ -0,0 +1,228 @@
+//! # Notify — Slack / Telegram への通知と、押されたボタンの受け口
+//!
+//! Core のイベントを Watchtower (UDS) に渡す手前で複製し、完了通知と承認依頼を
+//! `[notifiers]` の通知先へも送る。承認・却下は Slack なら `POST /hooks/slack/actions`
+//! (署名検証付き)、Telegram なら getUpdates で受け取り、Discord のボタンと同じく解決する。
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use hmac::{Hmac, Mac};
+use infrastructure::job_queue::SqliteJobQueue;
+use sha2::Sha256;
+use shared::config::NotifiersConfig;
+use shared::notifiers::{parse_slack_action, ApprovalReply, SlackNotifier, TelegramNotifier};
+use shared::watchtower::{notify_all, CoreEvent, Notifier};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::approval::ApprovalGate;
+use crate::server::router::AppState;
+use crate::shutdown::Shutdown;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Slack の署名を受け付ける時刻のずれ (秒)。これより古いリクエストは再送攻撃とみなす
+const SLACK_MAX_SKEW_SECS: i64 = 5 * 60;
+/// getUpdates が失敗したときの待ち
+const TELEGRAM_RETRY: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// 設定済みの通知先
+#[derive(Default)]
+pub struct Notifiers {
+    pub slack: Option<Arc<SlackNotifier>>,
+    pub telegram: Option<Arc<TelegramNotifier>>,
+}
+
+impl Notifiers {
+    pub fn from_config(config: &NotifiersConfig) -> anyhow::Result<Self> {
+        let slack = match (config.slack_bot_token.is_empty(), config.slack_channel.is_empty()) {
+            (true, _) => None,
+            (false, true) => anyhow::bail!("[notifiers] slack_channel is required when slack_bot_token is set"),
+            (false, false) => Some(Arc::new(SlackNotifier::new(&config.slack_bot_token, &config.slack_channel)?)),
+        };
+        let telegram = match (config.telegram_bot_token.is_empty(), config.telegram_chat_id.is_empty()) {
+            (true, _) => None,
+            (false, true) => anyhow::bail!("[notifiers] telegram_chat_id is required when telegram_bot_token is set"),
+            (false, false) => Some(Arc::new(TelegramNotifier::new(&config.telegram_bot_token, &config.telegram_chat_id)?)),
+        };
+        Ok(Self { slack, telegram })
+    }
+
+    pub fn all(&self) -> Vec<Arc<dyn Notifier>> {
+        let mut all: Vec<Arc<dyn Notifier>> = Vec::new();
+        if let Some(slack) = &self.slack {
+            all.push(slack.clone());
+        }
+        if let Some(telegram) = &self.telegram {
+            all.push(telegram.clone());
+        }
+        all
+    }
+}
+
+/// イベントを通知先へも送りつつ、そのまま Watchtower (UDS) 側の受信口へ流す。
+/// 通知先が無ければ `rx` をそのまま返す
+pub fn tee_events(mut rx: mpsc::Receiver<CoreEvent>, notifiers: Vec<Arc<dyn Notifier>>) -> mpsc::Receiver<CoreEvent> {
+    if notifiers.is_empty() {
+        return rx;
+    }
+    let names: Vec<&str> = notifiers.iter().map(|n| n.name()).collect();
+    info!("📣 Notify: Sending completions and approvals to {}", names.join(", "));
+    let (tx, out) = mpsc::channel::<CoreEvent>(1000);
+    let notifiers: Arc<[Arc<dyn Notifier>]> = notifiers.into();
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if event.completed_task().is_some() || event.approval_prompt().is_some() {
+                // 外部 API の遅さで UDS への配送を止めない
+                let notifiers = notifiers.clone();
+                let event = event.clone();
+                tokio::spawn(async move { notify_all(&notifiers, &event).await });
+            }
+            if tx.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+    out
+}
+
+/// 通知先で押された承認・却下を解決し、監査ログに残す
+async fn apply_reply(job_queue: &SqliteJobQueue, gate: &ApprovalGate, source: &str, reply: &ApprovalReply) {
+    info!("📥 {} approval response: {} -> {} by {}", source, reply.id, if reply.approved { "approved" } else { "rejected" }, reply.actor);
+    let detail = serde_json::json!({ "approved": reply.approved }).to_string();
+    if let Err(e) = job_queue.record_audit(source, &reply.actor, "approval", Some(&reply.id.to_string()), Some(&detail)).await {
+        warn!("⚠️ Audit: Failed to record approval by {}: {}", reply.actor, e);
+    }
+    crate::server::watchtower::resolve_approval(job_queue, gate, reply.id, reply.approved).await;
+}
+
+/// Telegram のボタン操作を待ち続ける (停止要求で抜ける)
+pub async fn run_telegram_replies(telegram: Arc<TelegramNotifier>, job_queue: Arc<SqliteJobQueue>, gate: Arc<ApprovalGate>, shutdown: Shutdown) {
+    loop {
+        let replies = tokio::select! {
+            replies = telegram.poll_replies() => replies,
+            _ = shutdown.wait() => break,
+        };
+        match replies {
+            Ok(replies) => {
+                for reply in &replies {
+                    apply_reply(&job_queue, &gate, "telegram", reply).await;
+                }
+            }
+            Err(e) => {
+                warn!("⚠️ Notify: Telegram polling failed: {}", e);
+                tokio::time::sleep(TELEGRAM_RETRY).await;
+            }
+        }
+    }
+}
+
+/// Slack の受け口に必要なもの (`AppState::slack`)
+pub struct SlackActions {
+    pub notifier: Arc<SlackNotifier>,
+    pub signing_secret: String,
+    pub approval_gate: Arc<ApprovalGate>,
+}
+
+/// Slack の署名 (`v0=` + HMAC-SHA256("v0:{timestamp}:{body}")) と時刻を検証する
+pub fn verify_slack_signature(secret: &str, timestamp: &str, body: &[u8], signature: &str, now: i64) -> Result<(), &'static str> {
+    let ts: i64 = timestamp.parse().map_err(|_| "bad timestamp")?;
+    if (now - ts).abs() > SLACK_MAX_SKEW_SECS {
+        return Err("stale request");
+    }
+    let sig = signature.strip_prefix("v0=").and_then(|hex_sig| hex::decode(hex_sig).ok()).ok_or("malformed signature")?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).map_err(|_| "invalid signing secret")?;
+    mac.update(format!("v0:{}:", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&sig).map_err(|_| "signature mismatch")
+}
+
+/// `application/x-www-form-urlencoded` の本文から 1 項目を取り出す
+fn form_value(body: &str, key: &str) -> Option<String> {
+    let raw = body.split('&').find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))?;
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex_byte = bytes.get(i + 1..i + 3)?;
+                out.push(u8::from_str_radix(std::str::from_utf8(hex_byte).ok()?, 16).ok()?);
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(out).ok()
+}
+
+/// POST /hooks/slack/actions — Slack の Interactivity (承認・却下ボタン)
+pub async fn slack_actions_handler(State(state): State<Arc<AppState>>, headers: HeaderMap, body: Bytes) -> Response {
+    let Some(slack) = state.slack.as_ref() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    if let Err(reason) = verify_slack_signature(
+        &slack.signing_secret,
+        header("x-slack-request-timestamp"),
+        &body,
+        header("x-slack-signature"),
+        chrono::Utc::now().timestamp(),
+    ) {
+        warn!("🔐 Notify: Rejected Slack action ({})", reason);
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let action = std::str::from_utf8(&body).ok()
+        .and_then(|body| form_value(body, "payload"))
+        .and_then(|payload| parse_slack_action(&payload));
+    let Some((reply, response_url)) = action else {
+        // 承認ボタン以外の操作は受け取ったことだけ返す
+        return StatusCode::OK.into_response();
+    };
+    apply_reply(&state.job_queue, &slack.approval_gate, "slack", &reply).await;
+    if let Some(url) = response_url {
+        let text = format!("{} `{}` by {}", if reply.approved { "✅ Approved" } else { "❌ Rejected" }, reply.id, reply.actor);
+        if let Err(e) = slack.notifier.replace_original(&url, &text).await {
+            error!("❌ Notify: Failed to update the Slack message for {}: {}", reply.id, e);
+        }
+    }
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("v0:{}:", timestamp).as_bytes());
+        mac.update(body);
+        format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_slack_signature_checks_secret_body_and_age() {
+        let body = b"payload=%7B%22type%22%3A%22block_actions%22%7D";
+        let sig = sign("s3cret", "1700000000", body);
+        assert_eq!(verify_slack_signature("s3cret", "1700000000", body, &sig, 1_700_000_060), Ok(()));
+        assert!(verify_slack_signature("other", "1700000000", body, &sig, 1_700_000_060).is_err());
+        assert!(verify_slack_signature("s3cret", "1700000000", b"payload=x", &sig, 1_700_000_060).is_err());
+        assert_eq!(verify_slack_signature("s3cret", "1700000000", body, &sig, 1_700_001_000), Err("stale request"));
+        assert_eq!(verify_slack_signature("s3cret", "1700000000", body, "deadbeef", 1_700_000_060), Err("malformed signature"));
+    }
+
+    #[test]
+    fn test_form_value_decodes_the_payload() {
+        let body = "token=x&payload=%7B%22type%22%3A%22block_actions%22%2C%22a%22%3A%22b+c%22%7D";
+        assert_eq!(form_value(body, "payload").as_deref(), Some(r#"{"type":"block_actions","a":"b c"}"#));
+        assert_eq!(form_value(body, "missing"), None);
+        assert_eq!(form_value("payload=%7", "payload"), None);
+    }
+}
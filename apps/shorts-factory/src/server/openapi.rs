@@ -0,0 +1,452 @@
+//! # OpenAPI — Serve API の仕様書
+//!
+//! `router::create_router` のルートを OpenAPI 3.0 として `/api/openapi.json` で公開する。
+//! スキーマは `factory_core::api` などの DTO から schemars で生成するため、構造体を変えれば仕様書も追従する。
+//! ルートを増やしたら `openapi_spec` にも 1 行足すこと。
+
+use factory_core::api::{
+    AcceptedJob, AuditRecord, CalendarExport, CronRun, EpisodeAccepted, EpisodeRequest, ErrorResponse, FailedJobSummary, Job, JobCosts, JobTimeline, JobWhyResponse,
+    KarmaCreated, NewKarma, NewSeries, PauseResponse, ProjectDetail, ProjectSummary, ReviewLink, ReviewLinkRequest, ReviewRecord, RateRequest, RetryResponse, Series, SeriesDetail, SeriesRequest,
+    SeriesResponse, StatusResponse, StyleReloadResponse, UploadResponse, VariantsRequest, WorkflowRequest,
+};
+use infrastructure::comfy_bridge::{ComfyModels, ComfyQueueSnapshot};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use tuning::StyleProfile;
+
+use crate::server::telemetry::TelemetryFrame;
+
+struct SpecBuilder {
+    gen: SchemaGenerator,
+    /// パス → メソッド → 操作
+    paths: BTreeMap<String, BTreeMap<String, Map<String, Value>>>,
+}
+
+impl SpecBuilder {
+    fn new() -> Self {
+        Self { gen: SchemaSettings::openapi3().into_generator(), paths: BTreeMap::new() }
+    }
+
+    /// `#/components/schemas/...` への参照 (定義は components に集約される)
+    fn schema<T: JsonSchema>(&mut self) -> Value {
+        serde_json::to_value(self.gen.subschema_for::<T>()).unwrap_or_default()
+    }
+
+    fn json_content(schema: Value) -> Value {
+        json!({ "application/json": { "schema": schema } })
+    }
+
+    /// 操作を登録する。`{name}` 形式のパスパラメータは自動で宣言する。
+    /// GET 以外は `[auth]` 有効時に API キーが要るため、security と 401/403/429 を付け足す
+    fn op(
+        &mut self,
+        method: &str,
+        path: &str,
+        tag: &str,
+        summary: &str,
+        request: Option<Value>,
+        responses: Vec<(u16, &str, Option<Value>)>,
+    ) -> &mut Map<String, Value> {
+        let parameters: Vec<Value> = path
+            .split('/')
+            .filter_map(|seg| seg.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+
+        let mut response_map = Map::new();
+        for (status, description, schema) in responses {
+            let mut response = json!({ "description": description });
+            if let Some(schema) = schema {
+                response["content"] = Self::json_content(schema);
+            }
+            response_map.insert(status.to_string(), response);
+        }
+        let mutating = method != "get";
+        if mutating {
+            let error = Self::json_content(self.schema::<ErrorResponse>());
+            response_map.entry("401").or_insert_with(|| json!({ "description": "Missing or invalid API key", "content": error.clone() }));
+            response_map.entry("403").or_insert_with(|| json!({ "description": "Token scope does not cover this route (operator, or admin for styles and karma edits)", "content": error.clone() }));
+            response_map.entry("429").or_insert_with(|| json!({ "description": "Per-key rate limit exceeded (see Retry-After)", "content": error }));
+        }
+
+        let mut operation = Map::new();
+        operation.insert("tags".into(), json!([tag]));
+        operation.insert("summary".into(), json!(summary));
+        if !parameters.is_empty() {
+            operation.insert("parameters".into(), Value::Array(parameters));
+        }
+        if let Some(schema) = request {
+            operation.insert("requestBody".into(), json!({ "required": true, "content": Self::json_content(schema) }));
+        }
+        operation.insert("responses".into(), Value::Object(response_map));
+        if mutating {
+            operation.insert("security".into(), json!([{ "bearerAuth": [] }, { "apiKeyHeader": [] }]));
+        }
+
+        let slot = self.paths.entry(path.to_string()).or_default().entry(method.to_string()).or_default();
+        *slot = operation;
+        slot
+    }
+
+    fn finish(mut self) -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Aiome Shorts Factory Serve API",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "paths": std::mem::take(&mut self.paths),
+            "components": {
+                "schemas": self.gen.take_definitions(),
+                "securitySchemes": {
+                    "bearerAuth": { "type": "http", "scheme": "bearer" },
+                    "apiKeyHeader": { "type": "apiKey", "in": "header", "name": "X-API-Key" },
+                },
+            },
+        })
+    }
+}
+
+/// 仕様書を組み立てる
+pub fn openapi_spec() -> Value {
+    let mut spec = SpecBuilder::new();
+    let error = spec.schema::<ErrorResponse>();
+    let err = |status: u16, description: &'static str| (status, description, Some(error.clone()));
+
+    // --- Jobs ---
+    let body = spec.schema::<WorkflowRequest>();
+    let ok = spec.schema::<AcceptedJob>();
+    spec.op("post", "/api/remix", "jobs", "Run a workflow immediately (one at a time)", Some(body), vec![
+        (202, "Accepted; the workflow runs in the background", Some(ok)),
+        err(429, "Another workflow is already running, or the per-key rate limit was exceeded"),
+        err(422, "The topic violates the editorial topic policy ([topic_policy])"),
+    ]);
+    let body = spec.schema::<VariantsRequest>();
+    let ok = spec.schema::<AcceptedJob>();
+    spec.op("post", "/api/variants", "jobs", "Write one script and render N variants of it (camera work, voice speed, seeds)", Some(body), vec![
+        (202, "Accepted; variants are delivered into <export_dir>/<project>/ with variants.json", Some(ok)),
+        err(400, "count must be between 1 and 6"),
+        err(422, "The topic violates the editorial topic policy ([topic_policy])"),
+        err(429, "Another workflow is already running, or the per-key rate limit was exceeded"),
+    ]);
+    let ok = spec.schema::<Vec<Job>>();
+    spec.op("get", "/api/jobs", "jobs", "List the 100 most recent jobs", None, vec![
+        (200, "Jobs, newest first", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let ok = spec.schema::<Vec<FailedJobSummary>>();
+    spec.op("get", "/api/jobs/failed", "jobs", "Dead-letter queue: recent failed jobs", None, vec![
+        (200, "Failed jobs, newest first", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let ok = spec.schema::<Job>();
+    spec.op("get", "/api/jobs/{id}", "jobs", "Fetch a job", None, vec![
+        (200, "The job", Some(ok)),
+        err(404, "Job not found"),
+    ]);
+    let ok = spec.schema::<RetryResponse>();
+    spec.op("post", "/api/jobs/{id}/retry", "jobs", "Re-queue a failed job and its cascaded descendants", None, vec![
+        (200, "Re-queued", Some(ok)),
+        err(404, "Job not found"),
+        err(409, "Job is not failed, or its parent is still failed"),
+    ]);
+    let ok = spec.schema::<StatusResponse>();
+    spec.op("post", "/api/jobs/{id}/cancel", "jobs", "Cancel a pending or scheduled job and its waiting descendants", None, vec![
+        (200, "Cancelled", Some(ok)),
+        err(404, "Job not found"),
+        err(409, "Job is already running or finished"),
+    ]);
+    let body = spec.schema::<RateRequest>();
+    let ok = spec.schema::<StatusResponse>();
+    spec.op("post", "/api/jobs/{id}/rate", "jobs", "Record a human creative rating", Some(body), vec![
+        (200, "Saved", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let ok = spec.schema::<JobWhyResponse>();
+    spec.op("get", "/api/jobs/{id}/why", "jobs", "Explain why Samsara created a job", None, vec![
+        (200, "Provenance and explanation", Some(ok)),
+        err(404, "Job not found"),
+    ]);
+    let ok = spec.schema::<JobTimeline>();
+    spec.op("get", "/api/jobs/{id}/timeline", "jobs", "Per-stage start/end times for a Gantt view", None, vec![
+        (200, "Stages in execution order, including retried stages", Some(ok)),
+        err(404, "Job not found"),
+    ]);
+    let ok = spec.schema::<JobCosts>();
+    spec.op("get", "/api/jobs/{id}/costs", "jobs", "Gemini tokens, Brave calls and GPU seconds spent on a job", None, vec![
+        (200, "Cost lines per provider and operation, with the total", Some(ok)),
+        err(404, "Job not found"),
+    ]);
+    let body = spec.schema::<SeriesRequest>();
+    let ok = spec.schema::<SeriesResponse>();
+    spec.op("post", "/api/series", "jobs", "Queue a series as a parent/child chain", Some(body), vec![
+        (200, "Queued job IDs in part order", Some(ok)),
+        err(400, "topics must not be empty"),
+        err(422, "The topic violates the editorial topic policy ([topic_policy])"),
+    ]);
+    let ok = spec.schema::<Vec<Series>>();
+    spec.op("get", "/api/series", "series", "List recurring series", None, vec![
+        (200, "Series, by name", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let ok = spec.schema::<SeriesDetail>();
+    spec.op("get", "/api/series/{name}", "series", "Fetch a series (by name or ID) with its latest 50 episodes", None, vec![
+        (200, "The series", Some(ok)),
+        err(404, "Series not found"),
+    ]);
+    let body = spec.schema::<NewSeries>();
+    let ok = spec.schema::<Series>();
+    spec.op("put", "/api/series/{name}", "series", "Create a recurring series", Some(body), vec![
+        (201, "Created", Some(ok)),
+        err(400, "Invalid name or unknown style"),
+        err(409, "Series already exists"),
+    ]);
+    let body = spec.schema::<EpisodeRequest>();
+    let ok = spec.schema::<EpisodeAccepted>();
+    spec.op("post", "/api/series/{name}/episodes", "series", "Queue the next auto-numbered episode", Some(body), vec![
+        (202, "Queued", Some(ok)),
+        err(400, "topic is required for a series without a topic template"),
+        err(404, "Series not found"),
+        err(422, "The topic violates the editorial topic policy ([topic_policy])"),
+    ]);
+    let ok = spec.schema::<CalendarExport>();
+    let op = spec.op("get", "/api/calendar/export", "series", "Export the content calendar (Samsara slots, queue, publications, series)", None, vec![
+        (200, "The plan as JSON, or as iCalendar with format=ics", Some(ok)),
+        err(400, "Unknown format"),
+        err(500, "Database error or invalid [cron] samsara expression"),
+    ]);
+    op.insert("parameters".into(), json!([
+        { "name": "format", "in": "query", "required": false, "schema": { "type": "string", "enum": ["json", "ics"] } },
+        { "name": "days", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 1, "maximum": 90, "default": 14 } },
+    ]));
+    if let Some(content) = op.get_mut("responses").and_then(|r| r.pointer_mut("/200/content")).and_then(Value::as_object_mut) {
+        content.insert("text/calendar".into(), json!({ "schema": { "type": "string" } }));
+    }
+    let op = spec.op("get", "/api/karma", "karma", "List karma lessons, newest first", None, vec![
+        (200, "Karma entries", Some(json!({ "type": "array", "items": { "type": "object" } }))),
+        err(500, "Database error"),
+    ]);
+    op.insert("parameters".into(), json!([
+        { "name": "skill", "in": "query", "required": false, "schema": { "type": "string" } },
+        { "name": "type", "in": "query", "required": false, "schema": { "type": "string", "enum": ["Technical", "Creative", "Synthesized"] } },
+        { "name": "min_weight", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 0, "maximum": 100 } },
+        { "name": "max_weight", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 0, "maximum": 100 } },
+        { "name": "limit", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 1, "maximum": 1000, "default": 200 } },
+    ]));
+    let body = spec.schema::<NewKarma>();
+    let ok = spec.schema::<KarmaCreated>();
+    spec.op("post", "/api/karma", "karma", "Inject a lesson by hand (no source job)", Some(body), vec![
+        (201, "Stored", Some(ok)),
+        err(400, "Empty lesson, unknown karma_type or weight outside 0-100"),
+    ]);
+    let ok = spec.schema::<StatusResponse>();
+    spec.op("delete", "/api/karma/{id}", "karma", "Delete a lesson", None, vec![
+        (200, "Deleted", Some(ok)),
+        err(404, "Karma not found"),
+    ]);
+
+    // --- Styles ---
+    let ok = spec.schema::<Vec<String>>();
+    spec.op("get", "/api/styles", "styles", "List style names", None, vec![(200, "Style names, sorted", Some(ok))]);
+    let ok = spec.schema::<StyleReloadResponse>();
+    spec.op("post", "/api/styles/reload", "styles", "Reload styles.toml from disk", None, vec![
+        (200, "Reloaded", Some(ok)),
+        err(422, "styles.toml is invalid"),
+    ]);
+    let profile = spec.schema::<StyleProfile>();
+    spec.op("get", "/api/styles/{name}", "styles", "Fetch a style profile", None, vec![
+        (200, "The profile", Some(profile.clone())),
+        err(404, "Style not found"),
+    ]);
+    spec.op("post", "/api/styles/{name}", "styles", "Create a style profile", Some(profile.clone()), vec![
+        (201, "Created", Some(profile.clone())),
+        err(400, "Reserved name"),
+        err(409, "Style already exists"),
+        err(422, "Validation failed"),
+    ]);
+    spec.op("put", "/api/styles/{name}", "styles", "Update a style profile", Some(profile.clone()), vec![
+        (200, "Updated", Some(profile)),
+        err(404, "Style not found"),
+        err(422, "Validation failed"),
+    ]);
+    spec.op("delete", "/api/styles/{name}", "styles", "Delete a style profile", None, vec![
+        (204, "Deleted", None),
+        err(400, "The default style cannot be deleted"),
+        err(404, "Style not found"),
+    ]);
+    let op = spec.op("get", "/api/styles/{name}/audio-preview", "styles", "Mix a stock narration sample with the style's BGM ducking (short WAV)", None, vec![
+        (200, "audio/wav preview", None),
+        err(404, "Style or narration sample not found"),
+        err(422, "Override out of range"),
+        err(500, "Mixing failed (no BGM for the mood or category, or ffmpeg error)"),
+    ]);
+    if let Some(params) = op.get_mut("parameters").and_then(Value::as_array_mut) {
+        params.extend([
+            json!({ "name": "mood", "in": "query", "required": false, "schema": { "type": "string" } }),
+            json!({ "name": "category", "in": "query", "required": false, "schema": { "type": "string", "default": "default" } }),
+            json!({ "name": "ducking_threshold", "in": "query", "required": false, "schema": { "type": "number", "minimum": 0.0, "maximum": 1.0 } }),
+            json!({ "name": "ducking_ratio", "in": "query", "required": false, "schema": { "type": "number", "minimum": 0.0, "maximum": 1.0 } }),
+            json!({ "name": "secs", "in": "query", "required": false, "schema": { "type": "number", "minimum": 1, "maximum": 30, "default": 15 } }),
+        ]);
+    }
+
+    // --- Projects ---
+    let ok = spec.schema::<Vec<ProjectSummary>>();
+    spec.op("get", "/api/projects", "projects", "List projects in the warehouse", None, vec![(200, "Projects", Some(ok))]);
+    let ok = spec.schema::<ProjectDetail>();
+    spec.op("get", "/api/projects/{id}", "projects", "Project details with every delivered language and aspect ratio", None, vec![
+        (200, "Project", Some(ok)),
+        err(404, "Project not found"),
+    ]);
+    for (kind, formats) in [("voiceover", "wav, mp3, m4a"), ("footage", "mp4, mov, webm, mkv")] {
+        let ok = spec.schema::<UploadResponse>();
+        let path = format!("/api/projects/{{id}}/{}", kind);
+        let summary = format!("Upload a {} file ({})", kind, formats);
+        let op = spec.op("put", &path, "projects", &summary, None, vec![
+            (200, "Saved; pass `path` in WorkflowRequest", Some(ok)),
+            err(400, "Invalid project id, format or empty body"),
+        ]);
+        op.insert("requestBody".into(), json!({
+            "required": true,
+            "content": { "application/octet-stream": { "schema": { "type": "string", "format": "binary" } } }
+        }));
+        if let Some(Value::Array(params)) = op.get_mut("parameters") {
+            params.push(json!({ "name": "ext", "in": "query", "required": false, "schema": { "type": "string" } }));
+        }
+    }
+
+    let body = spec.schema::<ReviewLinkRequest>();
+    let ok = spec.schema::<ReviewLink>();
+    spec.op("post", "/api/projects/{id}/review-link", "projects", "Issue an expiring review link for collaborators", Some(body), vec![
+        (200, "Signed URL of the review page (no API key needed to open it)", Some(ok)),
+        err(404, "Project not found"),
+        err(409, "Project has no delivered video yet"),
+    ]);
+    let ok = spec.schema::<Vec<ReviewRecord>>();
+    spec.op("get", "/api/projects/{id}/reviews", "projects", "Verdicts submitted through review links", None, vec![
+        (200, "Reviews, newest first", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let op = spec.op("get", "/api/projects/{id}/preview.m3u8", "projects", "HLS preview of the clean master with WebVTT subtitles", None, vec![
+        (200, "HLS master playlist (application/vnd.apple.mpegurl); segments are served from /assets", None),
+        err(404, "Project not found or no clean master for the language yet"),
+        err(500, "Transmux failed"),
+    ]);
+    if let Some(Value::Array(params)) = op.get_mut("parameters") {
+        params.push(json!({ "name": "lang", "in": "query", "required": false, "schema": { "type": "string" } }));
+    }
+
+    // --- Infrastructure ---
+    let ok = spec.schema::<PauseResponse>();
+    spec.op("post", "/api/system/pause", "infrastructure", "Stop picking up new jobs and skip Samsara; the running job finishes", None, vec![
+        (200, "Paused (changed = false if it already was)", Some(ok.clone())),
+        err(500, "Database error"),
+    ]);
+    spec.op("post", "/api/system/resume", "infrastructure", "Resume picking up queued jobs", None, vec![
+        (200, "Resumed (changed = false if it was not paused)", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    let ok = spec.schema::<Vec<CronRun>>();
+    let op = spec.op("get", "/api/cron/runs", "infrastructure", "Recent runs of the scheduled jobs with their outcome (ok, skipped, failed or running)", None, vec![
+        (200, "Cron runs, newest first", Some(ok)),
+        err(500, "Database error"),
+    ]);
+    op.insert("parameters".into(), json!([
+        { "name": "job", "in": "query", "required": false, "schema": { "type": "string", "example": "samsara" } },
+        { "name": "limit", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 1, "maximum": 500, "default": 50 } },
+    ]));
+    let ok = spec.schema::<ComfyQueueSnapshot>();
+    spec.op("get", "/api/comfy/queue", "infrastructure", "ComfyUI queue and recent history", None, vec![
+        (200, "Queue snapshot", Some(ok)),
+        err(502, "ComfyUI unreachable"),
+    ]);
+    let audit = spec.schema::<Vec<AuditRecord>>();
+    let op = spec.op("get", "/api/audit", "infrastructure", "Append-only log of control-plane actions (admin scope)", None, vec![
+        (200, "Audit records, newest first", Some(audit)),
+        err(500, "Database error"),
+    ]);
+    op.insert("parameters".into(), json!([
+        { "name": "limit", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 1, "maximum": 1000, "default": 100 } },
+        { "name": "actor", "in": "query", "required": false, "schema": { "type": "string" } },
+        { "name": "action", "in": "query", "required": false, "schema": { "type": "string" } },
+        { "name": "since", "in": "query", "required": false, "schema": { "type": "string", "example": "2025-01-03 00:00:00" } },
+    ]));
+    let ok = spec.schema::<ComfyModels>();
+    spec.op("get", "/api/comfy/models", "infrastructure", "Checkpoints and LoRAs installed in ComfyUI, and style models that are missing", None, vec![
+        (200, "Installed models", Some(ok)),
+        err(502, "ComfyUI unreachable"),
+    ]);
+    let frame = spec.schema::<TelemetryFrame>();
+    let op = spec.op("get", "/ws/telemetry", "infrastructure", "Live telemetry WebSocket", None, vec![
+        (101, "Switching protocols; each text message is a TelemetryFrame", None),
+    ]);
+    op.insert("x-websocket-message".into(), frame);
+    #[cfg(feature = "chaos")]
+    {
+        let rules = spec.schema::<std::collections::BTreeMap<infrastructure::chaos::Fault, infrastructure::chaos::FaultRule>>();
+        spec.op("get", "/api/chaos", "infrastructure", "Armed fault-injection rules (chaos builds only, admin scope)", None, vec![
+            (200, "Rules by fault", Some(rules.clone())),
+        ]);
+        spec.op("delete", "/api/chaos", "infrastructure", "Disarm every fault", None, vec![
+            (200, "Remaining rules (empty)", Some(rules.clone())),
+        ]);
+        let body = spec.schema::<infrastructure::chaos::FaultRule>();
+        spec.op("put", "/api/chaos/{fault}", "infrastructure", "Arm a fault: comfy_timeout, gemini_error, disk_full or uds_disconnect", Some(body), vec![
+            (200, "Rules by fault", Some(rules.clone())),
+            err(404, "Unknown fault"),
+            err(422, "Probability out of range"),
+        ]);
+        spec.op("delete", "/api/chaos/{fault}", "infrastructure", "Disarm one fault", None, vec![
+            (200, "Remaining rules", Some(rules)),
+            err(404, "Unknown fault"),
+        ]);
+    }
+    spec.op("get", "/api/openapi.json", "infrastructure", "This document", None, vec![
+        (200, "OpenAPI 3.0 document", Some(json!({ "type": "object" }))),
+    ]);
+
+    spec.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_refs(value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    out.push(r.clone());
+                }
+                map.values().for_each(|v| collect_refs(v, out));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_spec_refs_resolve_and_paths_use_openapi_syntax() {
+        let spec = openapi_spec();
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("WorkflowRequest"));
+        assert!(schemas.contains_key("TelemetryFrame"));
+
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/components/schemas/").unwrap_or_else(|| panic!("unexpected ref {}", r));
+            assert!(schemas.contains_key(name), "dangling ref {}", r);
+        }
+
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(paths.keys().all(|p| !p.contains(':')));
+        assert!(paths["/api/jobs/{id}/retry"]["post"]["parameters"][0]["name"] == "id");
+        assert!(paths["/api/jobs/{id}/retry"]["post"]["responses"]["409"].is_object());
+        assert!(paths["/api/jobs/{id}/retry"]["post"]["responses"]["401"].is_object());
+        assert!(paths["/api/jobs"]["get"].get("security").is_none());
+    }
+}
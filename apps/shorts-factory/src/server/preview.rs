@@ -0,0 +1,171 @@
+//! # Preview — 字幕を焼き込まない HLS プレビュー
+//!
+//! `GET /api/projects/:id/preview.m3u8` が字幕なしマスター (`clean_video.mp4` + `final_audio.wav`) を
+//! HLS に変換 (映像はコピー) し、保存済みの SRT から作った WebVTT を字幕トラックとして添える。
+//! 焼き込みの最終レンダーを待たずに Command Center のブラウザで確認できる。
+//! 変換結果は `{lang}/preview/` に置き、マスターか字幕が更新されるまで使い回す (配信は `/assets`)。
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use factory_core::error::FactoryError;
+use infrastructure::media_forge::MediaForgeClient;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+use crate::asset_manager::{CLEAN_VIDEO_FILE, FINAL_AUDIO_FILE, SUBTITLES_FILE};
+use crate::server::router::AppState;
+
+/// 変換結果の置き場所 (言語ディレクトリ内)
+const PREVIEW_DIR: &str = "preview";
+/// HLS セグメントの長さ (秒)
+const SEGMENT_SECS: u32 = 4;
+
+#[derive(serde::Deserialize)]
+pub struct PreviewQuery {
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// `video.m3u8` の EXTINF を合計した尺 (秒)
+fn playlist_duration(playlist: &str) -> f64 {
+    playlist
+        .lines()
+        .filter_map(|l| l.strip_prefix("#EXTINF:"))
+        .filter_map(|l| l.split(',').next()?.trim().parse::<f64>().ok())
+        .sum()
+}
+
+/// 字幕 1 本だけの VOD プレイリスト
+fn subtitle_playlist(duration_secs: f64) -> String {
+    format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:{}\n#EXT-X-PLAYLIST-TYPE:VOD\n#EXTINF:{:.3},\nsubtitles.vtt\n#EXT-X-ENDLIST\n",
+        duration_secs.ceil().max(1.0) as u64,
+        duration_secs
+    )
+}
+
+/// 映像と字幕トラックをまとめたマスタープレイリスト
+fn master_playlist(project_id: &str, lang: &str, bandwidth: u64, with_subtitles: bool) -> String {
+    let base = format!("/assets/{}/{}/{}", project_id, lang, PREVIEW_DIR);
+    let mut m3u8 = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    if with_subtitles {
+        m3u8.push_str(&format!(
+            "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"{lang}\",LANGUAGE=\"{lang}\",DEFAULT=YES,AUTOSELECT=YES,FORCED=NO,URI=\"{base}/subtitles.m3u8\"\n",
+        ));
+        m3u8.push_str(&format!("#EXT-X-STREAM-INF:BANDWIDTH={},SUBTITLES=\"subs\"\n", bandwidth));
+    } else {
+        m3u8.push_str(&format!("#EXT-X-STREAM-INF:BANDWIDTH={}\n", bandwidth));
+    }
+    m3u8.push_str(&format!("{}/video.m3u8\n", base));
+    m3u8
+}
+
+/// プレビューを (必要なら作り直して) 用意し、(推定帯域 bps, 字幕の有無) を返す
+async fn ensure_preview(forge: &MediaForgeClient, lang_dir: &std::path::Path) -> Result<(u64, bool), FactoryError> {
+    let preview = lang_dir.join(PREVIEW_DIR);
+    let playlist = preview.join("video.m3u8");
+    let srt = lang_dir.join(SUBTITLES_FILE);
+    let sources = [lang_dir.join(CLEAN_VIDEO_FILE), lang_dir.join(FINAL_AUDIO_FILE), srt.clone()];
+    let newest_source = sources.iter().filter_map(|p| modified(p)).max();
+    let fresh = matches!((modified(&playlist), newest_source), (Some(built), Some(src)) if built >= src);
+
+    if !fresh {
+        // 同時リクエストが作りかけを読まないよう、別ディレクトリで作ってから差し替える
+        let staging = lang_dir.join(format!("{}.tmp-{}", PREVIEW_DIR, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&staging)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create preview dir: {}", e) })?;
+        let built = async {
+            forge.transmux_hls(&sources[0], &sources[1], &staging, SEGMENT_SECS).await?;
+            if let Ok(srt_text) = std::fs::read_to_string(&srt) {
+                let duration = playlist_duration(&std::fs::read_to_string(staging.join("video.m3u8")).unwrap_or_default());
+                std::fs::write(staging.join("subtitles.vtt"), MediaForgeClient::srt_to_vtt(&srt_text))
+                    .and_then(|_| std::fs::write(staging.join("subtitles.m3u8"), subtitle_playlist(duration)))
+                    .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write WebVTT: {}", e) })?;
+            }
+            Ok::<(), FactoryError>(())
+        }
+        .await;
+        if let Err(e) = built {
+            std::fs::remove_dir_all(&staging).ok();
+            return Err(e);
+        }
+        std::fs::remove_dir_all(&preview).ok();
+        if std::fs::rename(&staging, &preview).is_err() {
+            // 別のリクエストが先に差し替えた
+            std::fs::remove_dir_all(&staging).ok();
+        }
+        info!("📺 Preview: Built HLS preview in {}", preview.display());
+    }
+
+    let duration = playlist_duration(&std::fs::read_to_string(&playlist).unwrap_or_default());
+    let bytes: u64 = std::fs::read_dir(&preview)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "ts"))
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0);
+    let bandwidth = if duration > 0.0 { (bytes as f64 * 8.0 / duration) as u64 } else { 0 };
+    Ok((bandwidth.max(1), preview.join("subtitles.m3u8").is_file()))
+}
+
+/// `GET /api/projects/:id/preview.m3u8` — 字幕なしマスターの HLS プレビュー (WebVTT 字幕付き)
+pub async fn preview_playlist_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<PreviewQuery>,
+) -> Response {
+    if !state.asset_manager.project_exists(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"}))).into_response();
+    }
+    let langs = state.asset_manager.preview_langs(&id);
+    let lang = match &query.lang {
+        Some(lang) => langs.iter().find(|l| *l == lang),
+        None => langs.first(),
+    };
+    let Some(lang) = lang else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "No clean master for this project/language yet", "available": langs}))).into_response();
+    };
+
+    match ensure_preview(&state.orchestrator.media_forge, &state.asset_manager.lang_dir(&id, lang)).await {
+        Ok((bandwidth, with_subtitles)) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/vnd.apple.mpegurl"), (header::CACHE_CONTROL, "no-cache")],
+            master_playlist(&id, lang, bandwidth, with_subtitles),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("⚠️ Preview: Failed to build HLS preview for {} [{}]: {}", id, lang, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playlists_reference_assets_and_subtitle_group() {
+        let master = master_playlist("job_42", "ja", 2_500_000, true);
+        assert!(master.contains("URI=\"/assets/job_42/ja/preview/subtitles.m3u8\""));
+        assert!(master.contains("#EXT-X-STREAM-INF:BANDWIDTH=2500000,SUBTITLES=\"subs\"\n/assets/job_42/ja/preview/video.m3u8\n"));
+        assert!(!master_playlist("job_42", "en", 1, false).contains("SUBTITLES"));
+
+        let video = "#EXTM3U\n#EXTINF:4.000000,\nseg_000.ts\n#EXTINF:2.500000,\nseg_001.ts\n#EXT-X-ENDLIST\n";
+        assert_eq!(playlist_duration(video), 6.5);
+        assert!(subtitle_playlist(6.5).contains("#EXT-X-TARGETDURATION:7\n#EXT-X-PLAYLIST-TYPE:VOD\n#EXTINF:6.500,\nsubtitles.vtt"));
+    }
+}
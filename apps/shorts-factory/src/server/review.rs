@@ -0,0 +1,350 @@
+//! # Review — 共同作業者向けの期限付きレビューリンク
+//!
+//! `POST /api/projects/:id/review-link` が `/review/:id?exp=..&sig=..` を発行する。
+//! 署名は HMAC-SHA256 (プロジェクト ID + 期限) なので、リンクを知っている人だけが
+//! API キー無しで動画を確認し、承認/差し戻しできる。判定は `reviews` テーブルに残り、
+//! ジョブ由来のプロジェクトなら creative_rating にも写る (Samsara の評価と同じ扱い)。
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Form, Json,
+};
+use factory_core::api::{ReviewLink, ReviewLinkRequest};
+use factory_core::traits::{JobQueue, ReviewVerdict};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shared::config::ReviewConfig;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::sync::Arc;
+use tower_http::services::ServeFile;
+use tracing::{info, warn};
+
+use crate::server::router::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// レビューリンクの署名・検証
+pub struct ReviewLinks {
+    /// 署名鍵を読み込み済みの HMAC (リンクごとに複製して使う)
+    mac: HmacSha256,
+    config: ReviewConfig,
+}
+
+impl ReviewLinks {
+    /// 署名鍵を読み込む。無ければ生成して 0600 で保存する
+    pub fn from_config(config: &ReviewConfig) -> anyhow::Result<Self> {
+        let path = std::path::Path::new(&config.secret_file);
+        let key = match std::fs::read_to_string(path) {
+            Ok(hex_key) => hex::decode(hex_key.trim())
+                .map_err(|e| anyhow::anyhow!("Review link key {} is corrupted: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = rand::random::<[u8; 32]>().to_vec();
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let mut file = std::fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(path)?;
+                file.write_all(hex::encode(&key).as_bytes())?;
+                info!("🔗 Review: Generated link signing key at {}", path.display());
+                key
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if key.len() < 16 {
+            anyhow::bail!("Review link key {} is too short", path.display());
+        }
+        let mac = HmacSha256::new_from_slice(&key)
+            .map_err(|e| anyhow::anyhow!("Review link key {} is unusable: {}", path.display(), e))?;
+        Ok(Self { mac, config: config.clone() })
+    }
+
+    fn mac(&self, project_id: &str, expires: i64) -> HmacSha256 {
+        let mut mac = self.mac.clone();
+        mac.update(format!("{}:{}", project_id, expires).as_bytes());
+        mac
+    }
+
+    pub fn sign(&self, project_id: &str, expires: i64) -> String {
+        hex::encode(self.mac(project_id, expires).finalize().into_bytes())
+    }
+
+    /// 署名と期限を検証する (署名は定数時間で比較)
+    pub fn verify(&self, project_id: &str, expires: i64, sig: &str, now: i64) -> Result<(), &'static str> {
+        let sig = hex::decode(sig).map_err(|_| "This review link is invalid.")?;
+        self.mac(project_id, expires).verify_slice(&sig).map_err(|_| "This review link is invalid.")?;
+        if now >= expires {
+            return Err("This review link has expired. Ask for a new one.");
+        }
+        Ok(())
+    }
+
+    /// リンクを発行する (有効期限は max_ttl_hours で頭打ち)
+    pub fn issue(&self, project_id: &str, ttl_hours: Option<u32>, base_url: &str, now: i64) -> ReviewLink {
+        let ttl = ttl_hours.unwrap_or(self.config.ttl_hours).clamp(1, self.config.max_ttl_hours.max(1));
+        let expires = now + i64::from(ttl) * 3600;
+        let url = format!(
+            "{}/review/{}?exp={}&sig={}",
+            base_url.trim_end_matches('/'),
+            project_id,
+            expires,
+            self.sign(project_id, expires)
+        );
+        let expires_at = chrono::DateTime::from_timestamp(expires, 0).map(|t| t.to_rfc3339()).unwrap_or_default();
+        ReviewLink { url, expires_at }
+    }
+
+    fn base_url(&self, headers: &HeaderMap) -> String {
+        if !self.config.public_base_url.is_empty() {
+            return self.config.public_base_url.clone();
+        }
+        let host = headers.get(header::HOST).and_then(|v| v.to_str().ok()).unwrap_or("localhost:3000");
+        let scheme = headers.get("x-forwarded-proto").and_then(|v| v.to_str().ok()).unwrap_or("http");
+        format!("{}://{}", scheme, host)
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ReviewQuery {
+    exp: i64,
+    sig: String,
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ReviewForm {
+    verdict: ReviewVerdict,
+    #[serde(default)]
+    comment: String,
+    #[serde(default)]
+    reviewer: String,
+}
+
+/// 入力欄の上限 (文字数)
+const MAX_COMMENT_CHARS: usize = 4000;
+const MAX_REVIEWER_CHARS: usize = 80;
+
+/// ジョブ由来のプロジェクト (`job_<id>`) のジョブ ID
+fn job_id_of(project_id: &str) -> Option<&str> {
+    project_id.strip_prefix("job_")
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// `POST /api/projects/:id/review-link`
+pub async fn review_link_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Option<Json<ReviewLinkRequest>>,
+) -> impl IntoResponse {
+    if !state.asset_manager.project_exists(&id) {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Project not found"}))).into_response();
+    }
+    if !state.asset_manager.load_outputs(&id).iter().any(|o| o.is_primary()) {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({"error": "Project has no delivered video yet"}))).into_response();
+    }
+    let ttl_hours = body.and_then(|Json(b)| b.ttl_hours);
+    let link = state.review.issue(&id, ttl_hours, &state.review.base_url(&headers), now());
+    info!("🔗 Review: Issued link for {} (expires {})", id, link.expires_at);
+    (StatusCode::OK, Json(link)).into_response()
+}
+
+/// `GET /api/projects/:id/reviews`
+pub async fn reviews_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.job_queue.fetch_reviews(&id).await {
+        Ok(reviews) => (StatusCode::OK, Json(reviews)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 署名が無効・期限切れなら 403 のページ
+fn denied(state: &AppState, id: &str, query: &ReviewQuery) -> Option<Response> {
+    let message = state.review.verify(id, query.exp, &query.sig, now()).err()?;
+    Some((StatusCode::FORBIDDEN, Html(page("Review", &format!("<p>{}</p>", message)))).into_response())
+}
+
+/// `GET /review/:id` — 動画と判定フォーム
+pub async fn review_page_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReviewQuery>,
+) -> Response {
+    if let Some(denied) = denied(&state, &id, &query) {
+        return denied;
+    }
+    // レビューは本編 (9:16) で行う
+    let outputs: Vec<_> = state.asset_manager.load_outputs(&id).into_iter().filter(|o| o.is_primary()).collect();
+    let Some(current) = outputs.iter().find(|o| Some(&o.lang) == query.lang.as_ref()).or(outputs.first()) else {
+        return (StatusCode::NOT_FOUND, Html(page("Review", "<p>The video is no longer available.</p>"))).into_response();
+    };
+
+    let title = state.asset_manager.project_title(&id);
+    let auth = format!("exp={}&sig={}", query.exp, query.sig);
+    let langs: Vec<String> = outputs
+        .iter()
+        .map(|o| {
+            if o.lang == current.lang {
+                format!("<b>{}</b>", escape(&o.lang))
+            } else {
+                format!("<a href=\"/review/{}?{}&lang={}\">{}</a>", id, auth, escape(&o.lang), escape(&o.lang))
+            }
+        })
+        .collect();
+    let history: Vec<String> = state
+        .job_queue
+        .fetch_reviews(&id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|r| {
+            let who = if r.reviewer.is_empty() { "anonymous" } else { &r.reviewer };
+            format!("<li><b>{}</b> by {} — {}</li>", r.verdict, escape(who), escape(&r.comment))
+        })
+        .collect();
+
+    let body = format!(
+        r#"<h1>{title}</h1>
+<p class="langs">{langs}</p>
+<video controls playsinline src="/review/{id}/video?{auth}&lang={lang}"></video>
+<form method="post" action="/review/{id}?{auth}">
+  <input name="reviewer" placeholder="Your name" maxlength="{max_reviewer}">
+  <textarea name="comment" rows="4" placeholder="What should change? (optional for approval)" maxlength="{max_comment}"></textarea>
+  <div class="buttons">
+    <button name="verdict" value="approve" class="approve">Approve</button>
+    <button name="verdict" value="revise" class="revise">Request changes</button>
+  </div>
+</form>
+{history}"#,
+        title = escape(&title),
+        langs = langs.join(" · "),
+        id = id,
+        auth = auth,
+        lang = escape(&current.lang),
+        max_reviewer = MAX_REVIEWER_CHARS,
+        max_comment = MAX_COMMENT_CHARS,
+        history = if history.is_empty() { String::new() } else { format!("<h2>Previous reviews</h2><ul>{}</ul>", history.join("")) },
+    );
+    Html(page(&title, &body)).into_response()
+}
+
+/// `GET /review/:id/video` — 納品済みの動画 (Range 対応)
+pub async fn review_video_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReviewQuery>,
+    request: Request,
+) -> Response {
+    if let Some(denied) = denied(&state, &id, &query) {
+        return denied;
+    }
+    let outputs: Vec<_> = state.asset_manager.load_outputs(&id).into_iter().filter(|o| o.is_primary()).collect();
+    let Some(output) = outputs.iter().find(|o| Some(&o.lang) == query.lang.as_ref()).or(outputs.first()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match ServeFile::new(&output.path).try_call(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => {
+            warn!("⚠️ Review: Failed to serve {}: {}", output.path, e);
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+/// `POST /review/:id` — 判定を記録する
+pub async fn review_submit_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ReviewQuery>,
+    Form(form): Form<ReviewForm>,
+) -> Response {
+    if let Some(denied) = denied(&state, &id, &query) {
+        return denied;
+    }
+    let comment: String = form.comment.trim().chars().take(MAX_COMMENT_CHARS).collect();
+    let reviewer: String = form.reviewer.trim().chars().take(MAX_REVIEWER_CHARS).collect();
+    match state.job_queue.record_review(&id, job_id_of(&id), form.verdict, &comment, &reviewer).await {
+        Ok(rated) => {
+            info!("📝 Review: {} -> {} by '{}' (rating recorded: {})", id, form.verdict, reviewer, rated);
+            let message = match form.verdict {
+                ReviewVerdict::Approve => "Thanks! The video is approved.",
+                ReviewVerdict::Revise => "Thanks! Your change request was sent to the team.",
+            };
+            let back = format!("<p>{}</p><p><a href=\"/review/{}?exp={}&sig={}\">Back to the video</a></p>", message, id, query.exp, query.sig);
+            Html(page("Review recorded", &back)).into_response()
+        }
+        Err(e) => {
+            warn!("⚠️ Review: Failed to record review for {}: {}", id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html(page("Review", "<p>Could not save your review. Please try again.</p>"))).into_response()
+        }
+    }
+}
+
+pub(crate) fn page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!doctype html>
+<html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex"><title>{}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 480px; margin: 0 auto; padding: 16px; background: #111; color: #eee; }}
+video {{ width: 100%; max-height: 70vh; background: #000; border-radius: 8px; }}
+input, textarea {{ width: 100%; box-sizing: border-box; margin: 8px 0; padding: 8px; background: #222; color: #eee; border: 1px solid #444; border-radius: 6px; }}
+.buttons {{ display: flex; gap: 8px; }}
+button {{ flex: 1; padding: 12px; font-size: 1rem; border: 0; border-radius: 6px; color: #fff; cursor: pointer; }}
+.approve {{ background: #2e7d32; }} .revise {{ background: #c62828; }}
+a {{ color: #90caf9; }}
+</style></head><body>{}</body></html>"#,
+        escape(title),
+        body
+    )
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_expire_and_reject_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ReviewConfig {
+            secret_file: dir.path().join("keys/review.key").to_string_lossy().to_string(),
+            max_ttl_hours: 48,
+            ..Default::default()
+        };
+        let links = ReviewLinks::from_config(&config).unwrap();
+        // The key survives a restart, so issued links stay valid
+        let reloaded = ReviewLinks::from_config(&config).unwrap();
+
+        let t0 = 1_700_000_000;
+        let link = links.issue("job_abc", Some(1000), "https://factory.example.com/", t0);
+        assert!(link.url.starts_with("https://factory.example.com/review/job_abc?exp="));
+        let exp = t0 + 48 * 3600; // clamped to max_ttl_hours
+        assert!(link.url.contains(&format!("exp={}", exp)));
+
+        let sig = links.sign("job_abc", exp);
+        assert!(reloaded.verify("job_abc", exp, &sig, t0).is_ok());
+        assert!(links.verify("job_abc", exp, &sig, exp).is_err());
+        assert!(links.verify("job_other", exp, &sig, t0).is_err());
+        assert!(links.verify("job_abc", exp + 3600, &sig, t0).is_err());
+        assert!(links.verify("job_abc", exp, "zz", t0).is_err());
+
+        assert_eq!(escape("<b>\"x\" & 'y'</b>"), "&lt;b&gt;&quot;x&quot; &amp; &#39;y&#39;&lt;/b&gt;");
+        assert_eq!(job_id_of("job_abc"), Some("abc"));
+        assert_eq!(job_id_of("tech_20250101"), None);
+    }
+}
@@ -1,47 +1,109 @@
 use axum::{
-    extract::{State, WebSocketUpgrade, ws::WebSocket},
+    extract::{DefaultBodyLimit, Query, State, WebSocketUpgrade, ws::WebSocket},
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, post, put},
+    body::Bytes,
     Router, Json,
     http::StatusCode,
 };
-use std::sync::{Arc, Mutex};
-use crate::server::telemetry::TelemetryHub;
+use std::sync::Arc;
+use crate::server::telemetry::{TelemetryFrame, TelemetryHub};
+use tokio::sync::broadcast;
 use crate::orchestrator::ProductionOrchestrator;
 use factory_core::contracts::WorkflowRequest;
+use factory_core::api::{AcceptedJob, EpisodeAccepted, EpisodeRequest, JobWhyResponse, KarmaCreated, NewKarma, NewSeries, PauseResponse, SeriesDetail, RateRequest, RetryResponse, SeriesResponse, StatusResponse, StyleReloadResponse, UploadResponse, VariantsRequest};
 use factory_core::traits::{AgentAct, JobQueue}; // Trait import needed 
-use tuning::StyleManager;
+use tuning::{StyleManager, StyleProfile};
 use bastion::fs_guard::Jail;
 use tower_http::services::ServeDir;
 use uuid::Uuid;
 use crate::asset_manager::AssetManager;
+use crate::channels::ChannelRegistry;
 use infrastructure::job_queue::SqliteJobQueue;
+use shared::config::DEFAULT_CHANNEL;
 
 pub struct AppState {
     pub telemetry: Arc<TelemetryHub>,
     pub orchestrator: Arc<ProductionOrchestrator>,
     pub style_manager: Arc<StyleManager>,
     pub jail: Arc<Jail>,
-    pub is_busy: Arc<Mutex<bool>>, // Resource Locking
     pub asset_manager: Arc<AssetManager>,
     pub current_job: Arc<tokio::sync::Mutex<Option<String>>>,
     pub job_queue: Arc<SqliteJobQueue>,
+    pub channels: Arc<ChannelRegistry>,
+    pub gemini_api_key: String,
+    /// カレンダーに Samsara の企画枠を展開するため
+    pub cron: shared::config::CronConfig,
+    /// レビューリンクの署名鍵
+    pub review: Arc<crate::server::review::ReviewLinks>,
+    /// None なら認証なし (`[auth] enabled = false`)
+    pub auth: Option<Arc<crate::server::auth::ApiAuth>>,
+    /// 生成要求の題材を照らす編集方針ファイル (`[topic_policy] file`)
+    pub topic_policy_file: String,
+    /// 方針違反などを Watchtower へ知らせる
+    pub events: tokio::sync::mpsc::Sender<shared::watchtower::CoreEvent>,
+    /// Slack の承認ボタンの受け口 (None なら `/hooks/slack/actions` は 404)
+    pub slack: Option<Arc<crate::server::notify::SlackActions>>,
 }
 
 
 use tower_http::cors::CorsLayer;
 
 pub fn create_router(state: Arc<AppState>) -> Router {
-    Router::new()
+    let auth_layer = axum::middleware::from_fn_with_state(state.clone(), crate::server::auth::require_api_key);
+    let audit_layer = axum::middleware::from_fn_with_state(state.clone(), crate::server::audit::record_mutations);
+    let router = Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/ws/telemetry", get(telemetry_ws_handler))
         .route("/api/remix", post(remix_handler))
+        .route("/api/variants", post(variants_handler))
         .route("/api/styles", get(styles_handler))
+        .route("/api/styles/reload", post(style_reload_handler))
+        .route("/api/styles/:name/audio-preview", get(style_audio_preview_handler))
+        .route("/api/styles/:name", get(style_get_handler).post(style_create_handler).put(style_update_handler).delete(style_delete_handler))
         .route("/api/projects", get(projects_handler))
+        .route("/api/projects/:id", get(project_handler))
+        .route("/api/projects/:id/voiceover", put(voiceover_upload_handler).layer(DefaultBodyLimit::max(VOICEOVER_MAX_BYTES)))
+        .route("/api/projects/:id/footage", put(footage_upload_handler).layer(DefaultBodyLimit::max(FOOTAGE_MAX_BYTES)))
+        .route("/api/projects/:id/review-link", post(crate::server::review::review_link_handler))
+        .route("/api/projects/:id/reviews", get(crate::server::review::reviews_handler))
+        .route("/api/projects/:id/preview.m3u8", get(crate::server::preview::preview_playlist_handler))
+        .route("/review/:id", get(crate::server::review::review_page_handler).post(crate::server::review::review_submit_handler))
+        .route("/review/:id/video", get(crate::server::review::review_video_handler))
+        .route("/hooks/slack/actions", post(crate::server::notify::slack_actions_handler))
+        .route("/status", get(crate::server::status::status_page_handler))
+        .route("/status/thumb/:job_id", get(crate::server::status::status_thumbnail_handler))
         .route("/api/jobs", get(jobs_handler))
+        .route("/api/jobs/failed", get(failed_jobs_handler))
         .route("/api/jobs/:id", get(job_detail_handler))
+        .route("/api/jobs/:id/retry", post(job_retry_handler))
+        .route("/api/jobs/:id/cancel", post(job_cancel_handler))
         .route("/api/jobs/:id/rate", post(job_rate_handler))
-        .route("/api/karma", get(karma_handler))
+        .route("/api/jobs/:id/why", get(job_why_handler))
+        .route("/api/jobs/:id/timeline", get(job_timeline_handler))
+        .route("/api/jobs/:id/costs", get(job_costs_handler))
+        .route("/api/series", get(series_list_handler).post(series_handler))
+        .route("/api/series/:name", get(series_detail_handler).put(series_create_handler))
+        .route("/api/series/:name/episodes", post(episode_enqueue_handler))
+        .route("/api/calendar/export", get(calendar_export_handler))
+        .route("/api/karma", get(karma_handler).post(karma_create_handler))
+        .route("/api/karma/:id", axum::routing::delete(karma_delete_handler))
+        .route("/api/system/pause", post(system_pause_handler))
+        .route("/api/system/resume", post(system_resume_handler))
+        .route("/api/cron/runs", get(cron_runs_handler))
+        .route("/api/comfy/queue", get(comfy_queue_handler))
+        .route("/api/comfy/models", get(comfy_models_handler))
+        .route("/api/audit", get(crate::server::audit::audit_handler))
+        .route("/api/openapi.json", get(openapi_handler));
+    // 障害注入 (検証用ビルドのみ)
+    #[cfg(feature = "chaos")]
+    let router = router
+        .route("/api/chaos", get(crate::server::chaos::chaos_rules_handler).delete(crate::server::chaos::chaos_clear_handler))
+        .route("/api/chaos/:fault", put(crate::server::chaos::chaos_arm_handler).delete(crate::server::chaos::chaos_disarm_handler));
+    router
         .nest_service("/assets", ServeDir::new("workspace")) // Serve static assets
+        .layer(audit_layer)
+        .layer(auth_layer)
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
@@ -64,10 +126,8 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
             Ok(hb) = rx_hb.recv() => {
                 // Determine active actor based on busy state
                 let mut hb_with_state = hb.clone();
-                if let Ok(busy) = state.is_busy.lock() {
-                     if *busy {
-                         hb_with_state.active_actor = Some("ORCHESTRATOR".to_string());
-                     }
+                if state.orchestrator.arbiter.jobs_saturated() {
+                    hb_with_state.active_actor = Some("ORCHESTRATOR".to_string());
                 }
 
                 if let Ok(msg) = serde_json::to_string(&hb_with_state) {
@@ -87,30 +147,145 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
     }
 }
 
+/// ライブテレメトリ: SystemStatus・実行中の工程・ログ行・ComfyUI キューを `TelemetryFrame` として push する
+async fn telemetry_ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ws.on_upgrade(|socket| handle_telemetry_socket(socket, state))
+}
+
+async fn handle_telemetry_socket(mut socket: WebSocket, state: Arc<AppState>) {
+    let mut rx_hb = state.telemetry.subscribe_heartbeat();
+    let mut rx_log = state.telemetry.subscribe_log();
+    let mut rx_stage = state.telemetry.subscribe_stage();
+    let mut rx_comfy = state.telemetry.subscribe_comfy_queue();
+    let mut rx_progress = state.telemetry.subscribe_comfy_progress();
+    let mut rx_ffmpeg = state.telemetry.subscribe_ffmpeg_progress();
+
+    // 接続直後に現在の工程を送り、次のイベントまで表示が空にならないようにする
+    if send_frame(&mut socket, &TelemetryFrame::Stage(state.telemetry.latest_stage())).await.is_err() {
+        return;
+    }
+    if let Some(queue) = state.telemetry.latest_comfy_queue() {
+        if send_frame(&mut socket, &TelemetryFrame::ComfyQueue(queue)).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        let frame = tokio::select! {
+            hb = rx_hb.recv() => match hb {
+                Ok(hb) => TelemetryFrame::Status(shared::watchtower::SystemStatus {
+                    cpu_usage: hb.cpu_usage,
+                    memory_used_mb: hb.memory_usage_mb,
+                    vram_used_mb: hb.vram_usage_mb,
+                    vram_total_mb: hb.vram_total_mb,
+                    gpu_utilization: hb.gpu_utilization,
+                    active_job_id: state.current_job.lock().await.clone(),
+                    ..Default::default()
+                }),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            stage = rx_stage.recv() => match stage {
+                Ok(stage) => TelemetryFrame::Stage(stage),
+                Err(broadcast::error::RecvError::Lagged(_)) => TelemetryFrame::Stage(state.telemetry.latest_stage()),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            queue = rx_comfy.recv() => match queue {
+                Ok(queue) => TelemetryFrame::ComfyQueue(queue),
+                Err(broadcast::error::RecvError::Lagged(_)) => match state.telemetry.latest_comfy_queue() {
+                    Some(queue) => TelemetryFrame::ComfyQueue(queue),
+                    None => continue,
+                },
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            progress = rx_progress.recv() => match progress {
+                Ok(progress) => TelemetryFrame::ComfyProgress(progress),
+                // 途中のステップは読み飛ばしても次のステップで追いつく
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            ffmpeg = rx_ffmpeg.recv() => match ffmpeg {
+                Ok(progress) => TelemetryFrame::FfmpegProgress(progress),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            log = rx_log.recv() => match log {
+                Ok(log) => TelemetryFrame::Log(log),
+                // ここで warn! するとログ配信自体に跳ね返るため、黙って読み飛ばす
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                // クライアント切断を検知して購読を解放する
+                Some(Ok(_)) => continue,
+                _ => break,
+            },
+        };
+
+        if send_frame(&mut socket, &frame).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn send_frame(socket: &mut WebSocket, frame: &TelemetryFrame) -> Result<(), axum::Error> {
+    match serde_json::to_string(frame) {
+        Ok(msg) => socket.send(axum::extract::ws::Message::Text(msg)).await,
+        Err(_) => Ok(()),
+    }
+}
+
 // --- REST API Handlers ---
 
+/// 題材が編集方針に反していれば 422 の応答 (空の題材は照らさない)
+async fn topic_policy_rejection(state: &AppState, topic: &str) -> Option<axum::response::Response> {
+    if topic.trim().is_empty() {
+        return None;
+    }
+    let reason = crate::server::topic_guard::enforce(&state.topic_policy_file, "rest", topic, &state.events).await.err()?;
+    Some((StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({"error": format!("Topic '{}' {}", topic, reason)}))).into_response())
+}
+
+/// ワークフロー ID がファイル名として使えなければ 400 の応答
+fn workflow_id_rejection(payload: &WorkflowRequest) -> Option<axum::response::Response> {
+    let id = payload.workflow_id.as_deref()?;
+    if factory_core::contracts::is_valid_workflow_id(id) {
+        return None;
+    }
+    Some((StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Invalid workflow_id '{}' (letters, digits, '_' and '-' only)", id)}))).into_response())
+}
+
+/// ジョブの枠に空きが無いときの 429
+fn busy_response() -> axum::response::Response {
+    (StatusCode::TOO_MANY_REQUESTS, Json(serde_json::json!({
+        "error": "System is busy. Please wait for the current task to finish."
+    }))).into_response()
+}
+
 async fn remix_handler(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<WorkflowRequest>,
 ) -> impl IntoResponse {
-    // 1. Resource Locking (Overzealous Clicker Guard)
-    {
-        let mut busy = state.is_busy.lock().unwrap();
-        if *busy {
-             state.telemetry.broadcast_log("WARN", "Rejecting concurrent remix request.");
-             return (StatusCode::TOO_MANY_REQUESTS, Json(serde_json::json!({
-                 "error": "System is busy. Please wait for the current task to finish."
-             }))).into_response();
-        }
-        *busy = true; // Acquire lock
+    if let Some(rejection) = workflow_id_rejection(&payload) {
+        return rejection;
     }
+    if let Some(rejection) = topic_policy_rejection(&state, &payload.topic).await {
+        return rejection;
+    }
+    // 1. Resource Locking (Overzealous Clicker Guard): JobWorker・Watchtower と共有のジョブ枠を取る
+    let Some(slot) = state.orchestrator.arbiter.try_acquire_job() else {
+        state.telemetry.broadcast_log("WARN", "Rejecting concurrent remix request.");
+        return busy_response();
+    };
 
     let job_id = Uuid::new_v4().to_string();
     state.telemetry.broadcast_log("INFO", &format!("Job Accepted: {} (Remix)", job_id));
     
     let orchestrator = state.orchestrator.clone();
     let jail = state.jail.clone();
-    let busy_lock = state.is_busy.clone();
     let telemetry = state.telemetry.clone();
     let job_id_clone = job_id.clone();
     
@@ -144,18 +319,104 @@ async fn remix_handler(
             *job_info = None;
         }
 
-        if let Ok(mut busy) = busy_lock.lock() {
-            *busy = false;
-            telemetry.broadcast_log("INFO", "System Ready");
-        }
+        drop(slot);
+        telemetry.broadcast_log("INFO", "System Ready");
     });
 
     // 3. Immediate Response (202 Accepted)
-    (StatusCode::ACCEPTED, Json(serde_json::json!({ 
-        "status": "accepted", 
-        "job_id": job_id,
-        "job_type": "remix" 
-    }))).into_response()
+    (StatusCode::ACCEPTED, Json(AcceptedJob {
+        status: "accepted".to_string(),
+        job_id,
+        job_type: "remix".to_string(),
+    })).into_response()
+}
+
+/// 台本を 1 回だけ作り、演出違いを `count` 本作る (Remix と同じくジョブの枠を 1 つ使う)
+async fn variants_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<VariantsRequest>,
+) -> impl IntoResponse {
+    if payload.count == 0 || payload.count > crate::variants::MAX_VARIANTS {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({
+            "error": format!("count must be between 1 and {}", crate::variants::MAX_VARIANTS)
+        }))).into_response();
+    }
+    if let Some(rejection) = workflow_id_rejection(&payload.workflow) {
+        return rejection;
+    }
+    if let Some(rejection) = topic_policy_rejection(&state, &payload.workflow.topic).await {
+        return rejection;
+    }
+    let Some(slot) = state.orchestrator.arbiter.try_acquire_job() else {
+        state.telemetry.broadcast_log("WARN", "Rejecting concurrent variants request.");
+        return busy_response();
+    };
+
+    let job_id = Uuid::new_v4().to_string();
+    state.telemetry.broadcast_log("INFO", &format!("Job Accepted: {} (Variants x{})", job_id, payload.count));
+
+    let state_clone = state.clone();
+    let job_id_clone = job_id.clone();
+    tokio::spawn(async move {
+        *state_clone.current_job.lock().await = Some(format!("Variants: {}", job_id_clone));
+
+        match crate::variants::generate(&state_clone.orchestrator, &state_clone.jail, payload.workflow, payload.count).await {
+            Ok(records) => {
+                let done = records.iter().filter(|r| r.error.is_none()).count();
+                state_clone.telemetry.broadcast_log("INFO", &format!("Job Completed: {} -> {}/{} variants generated", job_id_clone, done, records.len()));
+            }
+            Err(e) => state_clone.telemetry.broadcast_log("ERROR", &format!("Job Failed: {} -> {}", job_id_clone, e)),
+        }
+
+        *state_clone.current_job.lock().await = None;
+        drop(slot);
+        state_clone.telemetry.broadcast_log("INFO", "System Ready");
+    });
+
+    (StatusCode::ACCEPTED, Json(AcceptedJob {
+        status: "accepted".to_string(),
+        job_id,
+        job_type: "variants".to_string(),
+    })).into_response()
+}
+
+/// ComfyUI 自身のキュー: GPU を占有しているのがファクトリーか手動投入のワークフローかを見分ける
+async fn comfy_queue_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match state.orchestrator.comfy_bridge.fetch_queue_snapshot(COMFY_HISTORY_LIMIT).await {
+        Ok(snapshot) => {
+            state.telemetry.publish_comfy_queue(snapshot.clone());
+            Json(snapshot).into_response()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+pub const COMFY_HISTORY_LIMIT: usize = 10;
+
+/// ComfyUI にあるチェックポイント・LoRA と、スタイルの指定のうち見つからないもの
+async fn comfy_models_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match state.orchestrator.comfy_bridge.list_models().await {
+        Ok(mut models) => {
+            for name in state.style_manager.list_available_styles() {
+                let Some(style) = state.style_manager.find_style(&name) else { continue };
+                let missing = models.missing(&style.models);
+                if !missing.is_empty() {
+                    models.missing_by_style.insert(name, missing);
+                }
+            }
+            Json(models).into_response()
+        }
+        Err(e) => (StatusCode::BAD_GATEWAY, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+/// Serve API の OpenAPI 3.0 仕様書
+async fn openapi_handler() -> impl IntoResponse {
+    Json(crate::server::openapi::openapi_spec())
 }
 
 async fn styles_handler(
@@ -165,6 +426,138 @@ async fn styles_handler(
     Json(styles)
 }
 
+/// `reload` はリロード用エンドポイントと衝突するためスタイル名に使えない
+const RESERVED_STYLE_NAMES: &[&str] = &["reload"];
+
+async fn style_get_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.style_manager.find_style(&name) {
+        Some(profile) => Json(profile).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("Style '{}' not found", name) }))).into_response(),
+    }
+}
+
+async fn style_create_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(profile): Json<StyleProfile>,
+) -> impl IntoResponse {
+    if state.style_manager.find_style(&name).is_some() {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({ "error": format!("Style '{}' already exists", name) }))).into_response();
+    }
+    save_style(&state, name, profile, StatusCode::CREATED)
+}
+
+async fn style_update_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(profile): Json<StyleProfile>,
+) -> impl IntoResponse {
+    if state.style_manager.find_style(&name).is_none() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("Style '{}' not found", name) }))).into_response();
+    }
+    save_style(&state, name, profile, StatusCode::OK)
+}
+
+/// パスのスタイル名を正としてプロファイルを検証・保存する
+fn save_style(state: &AppState, name: String, mut profile: StyleProfile, success: StatusCode) -> axum::response::Response {
+    if RESERVED_STYLE_NAMES.contains(&name.as_str()) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": format!("'{}' is a reserved name", name) }))).into_response();
+    }
+    profile.name = name;
+    if let Err(reason) = profile.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({ "error": reason }))).into_response();
+    }
+    match state.style_manager.upsert_style(profile.clone()) {
+        Ok(_) => {
+            tracing::info!("🎨 Style '{}' saved to styles.toml", profile.name);
+            (success, Json(profile)).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+async fn style_delete_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    match state.style_manager.remove_style(&name) {
+        Ok(true) => {
+            tracing::info!("🗑️ Style '{}' removed from styles.toml", name);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("Style '{}' not found", name) }))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+async fn style_reload_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match state.style_manager.reload() {
+        Ok(count) => {
+            tracing::info!("🔄 styles.toml reloaded ({} profiles)", count);
+            Json(StyleReloadResponse { status: "reloaded".to_string(), styles: state.style_manager.list_available_styles() }).into_response()
+        }
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+/// 音声プレビューに使う手持ちのナレーション
+const AUDIO_PREVIEW_NARRATION: &str = "resources/voices/aiome_narrator.wav";
+/// 音声プレビューの既定の長さと上限 (秒)
+const AUDIO_PREVIEW_DEFAULT_SECS: f32 = 15.0;
+const AUDIO_PREVIEW_MAX_SECS: f32 = 30.0;
+
+#[derive(serde::Deserialize)]
+struct AudioPreviewQuery {
+    /// BGM の選曲 (本番ではコンセプトが決める)
+    mood: Option<String>,
+    category: Option<String>,
+    /// 保存せずに試す値 (省略時はスタイルの値)
+    ducking_threshold: Option<f32>,
+    ducking_ratio: Option<f32>,
+    secs: Option<f32>,
+}
+
+/// スタイルの BGM 設定で手持ちのナレーションを混ぜた短い WAV を返す (ダッキングの調整を本番レンダリング無しで聴く)
+async fn style_audio_preview_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(query): Query<AudioPreviewQuery>,
+) -> impl IntoResponse {
+    let Some(mut style) = state.style_manager.find_style(&name) else {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("Style '{}' not found", name) }))).into_response();
+    };
+    style.ducking_threshold = query.ducking_threshold.unwrap_or(style.ducking_threshold);
+    style.ducking_ratio = query.ducking_ratio.unwrap_or(style.ducking_ratio);
+    if let Err(e) = style.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({ "error": e }))).into_response();
+    }
+    let narration = std::path::Path::new(AUDIO_PREVIEW_NARRATION);
+    if !narration.is_file() {
+        return (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": format!("Narration sample {} is missing", AUDIO_PREVIEW_NARRATION) }))).into_response();
+    }
+    let secs = query.secs.unwrap_or(AUDIO_PREVIEW_DEFAULT_SECS).clamp(1.0, AUDIO_PREVIEW_MAX_SECS);
+    let output = std::env::temp_dir().join(format!("audio_preview_{}.wav", Uuid::new_v4()));
+    let category = query.category.as_deref().unwrap_or("default");
+    let mixed = state.orchestrator.sound_mixer.mix_preview(narration, category, query.mood.as_deref(), &output, &style, secs).await;
+    let bytes = match mixed {
+        Ok(path) => tokio::fs::read(&path).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let _ = tokio::fs::remove_file(&output).await;
+    match bytes {
+        Ok(bytes) => ([(axum::http::header::CONTENT_TYPE, "audio/wav"), (axum::http::header::CACHE_CONTROL, "no-store")], bytes).into_response(),
+        Err(e) => {
+            tracing::warn!("⚠️ Audio preview for style '{}' failed: {}", name, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": e }))).into_response()
+        }
+    }
+}
+
 async fn projects_handler(
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
@@ -182,6 +575,72 @@ async fn projects_handler(
     Json(projects)
 }
 
+/// `GET /api/projects/:id` — 一覧の項目に、言語・画角ごとの納品物を添える
+async fn project_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.asset_manager.project_detail(&id) {
+        Some(detail) => Json(detail).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({ "error": "Project not found" }))).into_response(),
+    }
+}
+
+/// 持ち込みナレーションのアップロード上限 (200MB)
+const VOICEOVER_MAX_BYTES: usize = 200 * 1024 * 1024;
+/// 持ち込み映像のアップロード上限 (1GB)
+const FOOTAGE_MAX_BYTES: usize = 1024 * 1024 * 1024;
+
+#[derive(serde::Deserialize)]
+struct UploadQuery {
+    ext: Option<String>,
+}
+
+/// 持ち込みナレーションを受け取り、WorkflowRequest.voiceover に渡す相対パスを返す
+async fn voiceover_upload_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<UploadQuery>,
+    body: Bytes,
+) -> impl IntoResponse {
+    save_upload(&state, &id, "voiceover", query.ext, &["wav", "mp3", "m4a"], &body)
+}
+
+/// 持ち込み映像を受け取り、WorkflowRequest.footage に渡す相対パスを返す
+async fn footage_upload_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<UploadQuery>,
+    body: Bytes,
+) -> impl IntoResponse {
+    save_upload(&state, &id, "footage", query.ext, &["mp4", "mov", "webm", "mkv"], &body)
+}
+
+fn save_upload(
+    state: &AppState,
+    id: &str,
+    stem: &str,
+    ext: Option<String>,
+    allowed: &[&str],
+    body: &[u8],
+) -> axum::response::Response {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Invalid project id"}))).into_response();
+    }
+    let ext = ext.unwrap_or_else(|| allowed[0].to_string()).to_lowercase();
+    if !allowed.contains(&ext.as_str()) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Unsupported format (allowed: {})", allowed.join(", "))}))).into_response();
+    }
+    if body.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Empty body"}))).into_response();
+    }
+
+    match state.asset_manager.save_upload(id, stem, &ext, body) {
+        Ok(path) => (StatusCode::OK, Json(UploadResponse { path })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
 // --- Job & Karma Handlers ---
 use axum::extract::Path;
 
@@ -206,24 +665,371 @@ pub async fn job_detail_handler(
     }
 }
 
+/// Dead-letter: 直近の失敗ジョブ一覧
+pub async fn failed_jobs_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    use factory_core::traits::JobQueue;
+    match state.job_queue.fetch_failed_jobs(50).await {
+        Ok(jobs) => (StatusCode::OK, Json(serde_json::to_value(jobs).unwrap_or_default())).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 失敗ジョブを Pending に戻して再実行させる
+pub async fn job_retry_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    use factory_core::traits::JobQueue;
+    match state.job_queue.fetch_job(&id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Job not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+    match state.job_queue.retry_job(&id).await {
+        Ok(requeued) => (StatusCode::OK, Json(RetryResponse { status: "requeued".to_string(), job_id: id, requeued })).into_response(),
+        // Failed 以外のジョブ・親が失敗したままのジョブは状態の衝突として扱う
+        Err(e) => (StatusCode::CONFLICT, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 待機中のジョブを取り消す (待機中の子孫も巻き込む)
+pub async fn job_cancel_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    use factory_core::traits::JobQueue;
+    match state.job_queue.fetch_job(&id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Job not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+    match state.job_queue.cancel_job(&id).await {
+        Ok(()) => (StatusCode::OK, Json(StatusResponse { status: "cancelled".to_string() })).into_response(),
+        // 実行中・終了済みのジョブは状態の衝突として扱う
+        Err(e @ factory_core::error::FactoryError::InvalidJobState { .. }) => (StatusCode::CONFLICT, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 自律エンジンの一時停止: 新しいジョブを取り出さず Samsara も企画しない (実行中のジョブは最後まで作る)
+pub async fn system_pause_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    set_paused(&state, true).await
+}
+
+/// 一時停止を解く
+pub async fn system_resume_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    set_paused(&state, false).await
+}
+
+async fn set_paused(state: &AppState, paused: bool) -> axum::response::Response {
+    let changed = match state.job_queue.set_paused(paused).await {
+        Ok(changed) => changed,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    if changed {
+        state.telemetry.broadcast_log("INFO", if paused { "Autonomous engine paused" } else { "Autonomous engine resumed" });
+    }
+    let paused_since = state.job_queue.paused_since().await.unwrap_or_default();
+    (StatusCode::OK, Json(PauseResponse { paused, changed, paused_since })).into_response()
+}
+
+/// `GET /api/cron/runs` の絞り込み
+#[derive(serde::Deserialize)]
+pub struct CronRunsQuery {
+    #[serde(default)]
+    job: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+/// cron ジョブの実行履歴 (新しい順)
+pub async fn cron_runs_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CronRunsQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    match state.job_queue.fetch_cron_runs(query.job.as_deref(), limit).await {
+        Ok(runs) => (StatusCode::OK, Json(runs)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// ジョブの来歴 (Samsara の合成経緯) とペルソナによる説明
+pub async fn job_why_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match crate::server::why::why(&state.job_queue, &state.channels, &state.gemini_api_key, &id).await {
+        Ok(Some((explanation, provenance))) => (StatusCode::OK, Json(JobWhyResponse { job_id: id, explanation, provenance })).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Job not found"}))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 工程ごとの所要時間 (ガントチャート用)
+pub async fn job_timeline_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.job_queue.fetch_job(&id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Job not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+    match state.job_queue.fetch_job_stages(&id).await {
+        Ok(spans) => (StatusCode::OK, Json(crate::server::timeline::build(&id, &spans, chrono::Utc::now()))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+pub async fn job_costs_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.job_queue.fetch_job(&id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Job not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+    match state.job_queue.fetch_job_costs(&id).await {
+        Ok(costs) => (StatusCode::OK, Json(costs)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// `GET /api/karma` の絞り込み
+#[derive(serde::Deserialize)]
+pub struct KarmaQuery {
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    skill: Option<String>,
+    #[serde(default, rename = "type")]
+    karma_type: Option<String>,
+    #[serde(default)]
+    min_weight: Option<i64>,
+    #[serde(default)]
+    max_weight: Option<i64>,
+}
+
+/// karma_logs の CHECK 制約と同じ
+const KARMA_TYPES: [&str; 3] = ["Technical", "Creative", "Synthesized"];
+
 pub async fn karma_handler(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<KarmaQuery>,
 ) -> impl IntoResponse {
-    match state.job_queue.fetch_all_karma(200).await {
+    let limit = query.limit.unwrap_or(200).clamp(1, 1000);
+    match state
+        .job_queue
+        .fetch_karma(limit, query.skill.as_deref(), query.karma_type.as_deref(), query.min_weight, query.max_weight)
+        .await
+    {
         Ok(karmas) => (StatusCode::OK, Json(serde_json::to_value(karmas).unwrap_or_default())).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
     }
 }
 
+/// 教訓を手動で書き込む (ジョブを持たない教訓として以後の生成に効く)
+pub async fn karma_create_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<NewKarma>,
+) -> impl IntoResponse {
+    let lesson = payload.lesson.trim();
+    if lesson.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "lesson must not be empty"}))).into_response();
+    }
+    let karma_type = payload.karma_type.as_deref().unwrap_or("Synthesized");
+    if !KARMA_TYPES.contains(&karma_type) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("karma_type must be one of {}", KARMA_TYPES.join(", "))}))).into_response();
+    }
+    let weight = payload.weight.unwrap_or(100);
+    if !(0..=100).contains(&weight) {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "weight must be between 0 and 100"}))).into_response();
+    }
+    let skill_id = payload.skill_id.as_deref().map(str::trim).filter(|s| !s.is_empty()).unwrap_or("global");
+    match state.job_queue.insert_karma(None, skill_id, payload.namespace.as_deref(), lesson, karma_type, weight, None).await {
+        Ok(id) => (StatusCode::CREATED, Json(KarmaCreated { id })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+pub async fn karma_delete_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.job_queue.delete_karma(&id).await {
+        Ok(true) => (StatusCode::OK, Json(StatusResponse { status: "deleted".to_string() })).into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Karma not found"}))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
 pub async fn job_rate_handler(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    Json(payload): Json<serde_json::Value>,
+    Json(payload): Json<RateRequest>,
 ) -> impl IntoResponse {
     use factory_core::traits::JobQueue;
-    let rating = payload.get("rating").and_then(|v| v.as_i64()).unwrap_or(50) as i32;
+    let rating = payload.rating.unwrap_or(50) as i32;
     match state.job_queue.set_creative_rating(&id, rating).await {
-        Ok(_) => (StatusCode::OK, Json(serde_json::json!({"status": "success"}))).into_response(),
+        Ok(_) => (StatusCode::OK, Json(StatusResponse { status: "success".to_string() })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+pub use factory_core::api::SeriesRequest;
+
+/// シリーズ制作: Part N は Part N-1 の完了後に、そのコンセプトを引き継いで実行される
+pub async fn series_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SeriesRequest>,
+) -> impl IntoResponse {
+    let topics: Vec<&str> = payload.topics.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+    if topics.is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "topics must not be empty"}))).into_response();
+    }
+    for topic in &topics {
+        if let Some(rejection) = topic_policy_rejection(&state, topic).await {
+            return rejection;
+        }
+    }
+    let style = payload.style.unwrap_or_default();
+    let channel = payload.channel.as_deref().unwrap_or(DEFAULT_CHANNEL);
+
+    match enqueue_series(&state.job_queue, payload.parent_id.as_deref(), channel, payload.soul.as_deref(), &topics, &style).await {
+        Ok(job_ids) => (StatusCode::OK, Json(SeriesResponse { job_ids })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+pub async fn series_list_handler(
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    match state.job_queue.list_series().await {
+        Ok(series) => (StatusCode::OK, Json(series)).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
     }
 }
+
+pub async fn series_detail_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let series = match state.job_queue.fetch_series(&name).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Series not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    match state.job_queue.fetch_series_episodes(&series.id, 50).await {
+        Ok(episodes) => (StatusCode::OK, Json(SeriesDetail { series, episodes })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// シリーズを作成する (名前はパスから取り、本文の name は無視する)
+pub async fn series_create_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(mut payload): Json<NewSeries>,
+) -> impl IntoResponse {
+    payload.name = name;
+    if !payload.style.is_empty() && state.style_manager.find_style(&payload.style).is_none() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Unknown style '{}'", payload.style)}))).into_response();
+    }
+    if let Ok(Some(_)) = state.job_queue.fetch_series(&payload.name).await {
+        return (StatusCode::CONFLICT, Json(serde_json::json!({"error": format!("Series '{}' already exists", payload.name)}))).into_response();
+    }
+    match state.job_queue.create_series(&payload).await {
+        Ok(series) => {
+            tracing::info!("📺 Series created: '{}' (channel: {})", series.name, series.channel);
+            (StatusCode::CREATED, Json(series)).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+/// 次話をキューに積む (話数はシリーズのカウンタから自動採番)
+pub async fn episode_enqueue_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(payload): Json<EpisodeRequest>,
+) -> impl IntoResponse {
+    let series = match state.job_queue.fetch_series(&name).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "Series not found"}))).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    let topic = payload.topic.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    if topic.is_none() && series.topic_template.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "topic is required for a series without a topic template"}))).into_response();
+    }
+    // トピック書式を当てた題材で照らす (話数は投入時に確定するので次の番号で組み立てる)
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let rendered = series.episode_topic(series.episode_counter + 1, topic, &date);
+    if let Some(rejection) = topic_policy_rejection(&state, &rendered).await {
+        return rejection;
+    }
+    match state.job_queue.enqueue_episode(&series.id, topic, payload.style.as_deref(), None, None).await {
+        Ok((job_id, episode_number)) => {
+            (StatusCode::ACCEPTED, Json(EpisodeAccepted { job_id, series: series.name, episode_number })).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct CalendarQuery {
+    /// "json" (既定) または "ics"
+    #[serde(default)]
+    pub format: Option<String>,
+    /// 前後何日分 (既定 14, 最大 90)
+    #[serde(default)]
+    pub days: Option<i64>,
+}
+
+/// 制作計画を JSON / iCalendar で書き出す
+pub async fn calendar_export_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CalendarQuery>,
+) -> impl IntoResponse {
+    let days = query.days.unwrap_or(14).clamp(1, 90);
+    let plan = match crate::server::calendar::build(&state.job_queue, &state.channels, &state.cron, days).await {
+        Ok(plan) => plan,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => (StatusCode::OK, Json(plan)).into_response(),
+        "ics" | "ical" => (
+            StatusCode::OK,
+            [
+                (axum::http::header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+                (axum::http::header::CONTENT_DISPOSITION, "attachment; filename=\"shorts-factory.ics\""),
+            ],
+            crate::server::calendar::to_ics(&plan),
+        ).into_response(),
+        other => (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": format!("Unknown format '{}' (json or ics)", other)}))).into_response(),
+    }
+}
+
+/// トピック列を親子チェーンとしてキューに積む
+pub async fn enqueue_series(
+    job_queue: &SqliteJobQueue,
+    parent_id: Option<&str>,
+    channel: &str,
+    soul: Option<&str>,
+    topics: &[&str],
+    style: &str,
+) -> Result<Vec<String>, factory_core::error::FactoryError> {
+    let mut job_ids: Vec<String> = Vec::with_capacity(topics.len());
+    for topic in topics {
+        let id = match job_ids.last().map(String::as_str).or(parent_id) {
+            Some(parent) => job_queue.enqueue_child(parent, topic, style, None).await?,
+            None => job_queue.enqueue_for_channel(channel, soul, topic, style, None).await?,
+        };
+        job_ids.push(id);
+    }
+    Ok(job_ids)
+}
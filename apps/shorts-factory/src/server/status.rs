@@ -0,0 +1,273 @@
+//! # Status — スマホで見る工場の状況ページ
+//!
+//! `GET /status` がサーバー側で組み立てた読み取り専用の HTML を返す (30 秒ごとに自動更新)。
+//! 実行中のジョブと工程・待ち行列・直近 10 件の完成品 (サムネイル付き)・サーキットブレーカーを 1 画面に並べる。
+//! `[auth]` が有効なら readonly 以上のトークンが要る。ブラウザはヘッダーを付けられないので、
+//! ログインフォームから `?token=` で一度渡すと HttpOnly の Cookie に移して URL から消す。
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{Html, IntoResponse, Response},
+};
+use factory_core::api::StageEvent;
+use infrastructure::job_queue::SqliteJobQueue;
+use serde::Deserialize;
+use shared::watchtower::{CompletedJobSummary, QueuedJobSummary};
+use std::sync::Arc;
+use tower_http::services::ServeFile;
+use tracing::warn;
+
+use crate::server::review::{escape, page};
+use crate::server::router::AppState;
+
+/// トークンを預かる Cookie
+const TOKEN_COOKIE: &str = "status_token";
+/// Cookie の有効期限 (30 日)
+const TOKEN_MAX_AGE_SECS: u64 = 30 * 24 * 3600;
+const QUEUE_LIMIT: i64 = 20;
+const RECENT_LIMIT: i64 = 10;
+const REFRESH_SECS: u32 = 30;
+
+#[derive(Deserialize)]
+pub struct StatusQuery {
+    token: Option<String>,
+}
+
+/// ページ 1 枚分の材料 (描画は `render` で純粋に行う)
+#[derive(Debug, Default)]
+pub struct StatusSnapshot {
+    pub current_job: Option<String>,
+    pub stage: Option<StageEvent>,
+    /// ジョブの枠が全部埋まっている
+    pub busy: bool,
+    pub api_failures: i64,
+    /// ComfyUI の (実行中, 待ち)。まだ一度も取れていなければ None
+    pub comfy_queue: Option<(usize, usize)>,
+    pub queued: Vec<QueuedJobSummary>,
+    /// 完成品とサムネイルの有無
+    pub recent: Vec<(CompletedJobSummary, bool)>,
+}
+
+/// `/status` 配下で受け付けるキー (`?token=` か Cookie)。ヘッダーのキーは `server::auth` が先に見る
+pub(crate) fn page_key(uri: &Uri, headers: &HeaderMap) -> Option<String> {
+    let usable = |k: String| Some(k.trim().to_string()).filter(|k| !k.is_empty());
+    Query::<StatusQuery>::try_from_uri(uri)
+        .ok()
+        .and_then(|q| q.0.token)
+        .and_then(usable)
+        .or_else(|| {
+            headers
+                .get_all(header::COOKIE)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(';'))
+                .find_map(|pair| pair.trim().strip_prefix(TOKEN_COOKIE)?.strip_prefix('=').map(str::to_string))
+                .and_then(usable)
+        })
+}
+
+/// トークン入力フォーム (未認証・無効なトークンのとき `server::auth` が返す)
+pub(crate) fn login_page(message: &str) -> Response {
+    let body = format!(
+        r#"<h1>Factory status</h1><p>{}</p>
+<form method="get" action="/status"><input type="password" name="token" placeholder="API token" autocomplete="current-password" required>
+<div class="buttons"><button class="approve" type="submit">Open</button></div></form>"#,
+        escape(message)
+    );
+    (StatusCode::UNAUTHORIZED, Html(page("Factory status", &body))).into_response()
+}
+
+/// `GET /status`
+pub async fn status_page_handler(State(state): State<Arc<AppState>>, Query(query): Query<StatusQuery>, headers: HeaderMap) -> Response {
+    // 認証はミドルウェアで済んでいる。トークンを Cookie に移して URL (履歴・共有) に残さない
+    if let (Some(token), Some(_)) = (query.token.as_deref().map(str::trim).filter(|t| !t.is_empty()), state.auth.as_ref()) {
+        let secure = headers.get("x-forwarded-proto").and_then(|v| v.to_str().ok()) == Some("https");
+        let cookie = format!(
+            "{}={}; Path=/status; Max-Age={}; HttpOnly; SameSite=Strict{}",
+            TOKEN_COOKIE, token, TOKEN_MAX_AGE_SECS, if secure { "; Secure" } else { "" }
+        );
+        return (StatusCode::SEE_OTHER, [(header::LOCATION, "/status".to_string()), (header::SET_COOKIE, cookie)]).into_response();
+    }
+
+    let queued = state.job_queue.fetch_queued_jobs(QUEUE_LIMIT).await.unwrap_or_else(|e| {
+        warn!("⚠️ Status: Failed to load the queue: {}", e);
+        Vec::new()
+    });
+    let recent = state.job_queue.fetch_recent_completions(RECENT_LIMIT).await.unwrap_or_else(|e| {
+        warn!("⚠️ Status: Failed to load recent completions: {}", e);
+        Vec::new()
+    });
+    let snapshot = StatusSnapshot {
+        current_job: state.current_job.lock().await.clone(),
+        stage: Some(state.telemetry.latest_stage()),
+        busy: state.orchestrator.arbiter.jobs_saturated(),
+        api_failures: state.job_queue.get_global_api_failures().await.unwrap_or(0),
+        comfy_queue: state.telemetry.latest_comfy_queue().map(|q| (q.running.len(), q.pending.len())),
+        queued,
+        recent: recent
+            .into_iter()
+            .map(|job| {
+                let has_thumb = state.asset_manager.thumbnail_path(&crate::job_worker::job_project_id(&job.job_id)).exists();
+                (job, has_thumb)
+            })
+            .collect(),
+    };
+    ([(header::CACHE_CONTROL, "no-store")], Html(render(&snapshot))).into_response()
+}
+
+/// `GET /status/thumb/:job_id` — 完成品のサムネイル
+pub async fn status_thumbnail_handler(State(state): State<Arc<AppState>>, Path(job_id): Path<String>, request: Request) -> Response {
+    if job_id.is_empty() || !job_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let path = state.asset_manager.thumbnail_path(&crate::job_worker::job_project_id(&job_id));
+    match ServeFile::new(&path).try_call(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => {
+            warn!("⚠️ Status: Failed to serve {}: {}", path.display(), e);
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+fn render(s: &StatusSnapshot) -> String {
+    let now = match (&s.current_job, &s.stage) {
+        (Some(job), stage) => {
+            let stage = stage.as_ref().filter(|e| e.stage.is_some());
+            format!(
+                "<p class=\"big\">▶️ {}</p><p>Stage: <b>{}</b>{}</p>",
+                escape(job),
+                escape(stage.and_then(|e| e.stage.as_deref()).unwrap_or("starting")),
+                stage.map(|e| format!(" <span class=\"dim\">since {}</span>", escape(&e.timestamp))).unwrap_or_default()
+            )
+        }
+        (None, _) if s.busy => "<p class=\"big\">⏳ Busy (all job slots in use)</p>".to_string(),
+        (None, _) => "<p class=\"big\">💤 Idle</p>".to_string(),
+    };
+
+    let tripped = s.api_failures >= SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT;
+    let comfy = match s.comfy_queue {
+        Some((running, pending)) => format!("{} running, {} pending", running, pending),
+        None => "no data yet".to_string(),
+    };
+    let breakers = format!(
+        "<ul><li>Global API: <b class=\"{}\">{}</b> <span class=\"dim\">({} / {} failures{})</span></li><li>ComfyUI queue: {}</li></ul>",
+        if tripped { "bad" } else { "ok" },
+        if tripped { "OPEN" } else { "closed" },
+        s.api_failures,
+        SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT,
+        if tripped { " — Sentinel / Oracle paused" } else { "" },
+        comfy
+    );
+
+    let queue = if s.queued.is_empty() {
+        "<p class=\"dim\">Nothing queued.</p>".to_string()
+    } else {
+        let items: Vec<String> = s.queued.iter().map(|j| {
+            let mut notes = Vec::new();
+            if let Some(at) = &j.scheduled_for {
+                notes.push(format!("⏰ {}", escape(at)));
+            }
+            if let Some(parent) = &j.depends_on {
+                notes.push(format!("after {}", escape(parent)));
+            }
+            format!(
+                "<li>{}<br><span class=\"dim\">{} · {}{}</span></li>",
+                escape(&j.topic),
+                escape(&j.style),
+                escape(&j.channel),
+                if notes.is_empty() { String::new() } else { format!(" · {}", notes.join(" · ")) }
+            )
+        }).collect();
+        format!("<ol>{}</ol>", items.join(""))
+    };
+
+    let recent = if s.recent.is_empty() {
+        "<p class=\"dim\">No completed jobs yet.</p>".to_string()
+    } else {
+        let items: Vec<String> = s.recent.iter().map(|(j, has_thumb)| {
+            let thumb = if *has_thumb {
+                format!("<img src=\"/status/thumb/{}\" alt=\"\" loading=\"lazy\">", escape(&j.job_id))
+            } else {
+                "<div class=\"noimg\"></div>".to_string()
+            };
+            let rating = j.creative_rating.map(|r| if r > 0 { " 👍" } else if r < 0 { " 👎" } else { "" }).unwrap_or("");
+            format!(
+                "<li class=\"done\">{}<div>{}{}<br><span class=\"dim\">{} · {}</span></div></li>",
+                thumb,
+                escape(&j.topic),
+                rating,
+                escape(&j.channel),
+                escape(&j.completed_at)
+            )
+        }).collect();
+        format!("<ul class=\"recent\">{}</ul>", items.join(""))
+    };
+
+    let body = format!(
+        r#"<style>
+h2 {{ font-size: 1rem; margin: 20px 0 8px; color: #aaa; }} .big {{ font-size: 1.2rem; }} .dim {{ color: #888; font-size: .85rem; }}
+.ok {{ color: #66bb6a; }} .bad {{ color: #ef5350; }} ul, ol {{ padding-left: 20px; }} li {{ margin: 6px 0; }}
+.recent {{ list-style: none; padding: 0; }} .done {{ display: flex; gap: 10px; align-items: center; }}
+.done img, .noimg {{ width: 54px; height: 96px; object-fit: cover; border-radius: 4px; background: #222; flex: none; }}
+</style>
+<h1>Factory status</h1><p class="dim">Refreshes every {refresh}s</p>
+<h2>Now</h2>{now}
+<h2>Circuit breakers</h2>{breakers}
+<h2>Queue ({queued})</h2>{queue}
+<h2>Recently completed</h2>{recent}"#,
+        refresh = REFRESH_SECS,
+        now = now,
+        breakers = breakers,
+        queued = s.queued.len(),
+        queue = queue,
+        recent = recent,
+    );
+    page("Factory status", &body).replacen(
+        "<meta name=\"robots\"",
+        &format!("<meta http-equiv=\"refresh\" content=\"{}\"><meta name=\"robots\"", REFRESH_SECS),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_render_escapes_and_shows_breakers() {
+        let snapshot = StatusSnapshot {
+            current_job: Some("job_abc".into()),
+            stage: Some(StageEvent { project_id: Some("job_abc".into()), stage: Some("render".into()), timestamp: "12:00:00".into() }),
+            api_failures: SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT,
+            queued: vec![QueuedJobSummary { topic: "<script>".into(), depends_on: Some("parent".into()), ..Default::default() }],
+            recent: vec![
+                (CompletedJobSummary { job_id: "j1".into(), creative_rating: Some(1), ..Default::default() }, true),
+                (CompletedJobSummary { job_id: "j2".into(), ..Default::default() }, false),
+            ],
+            ..Default::default()
+        };
+        let html = render(&snapshot);
+        assert!(html.contains("http-equiv=\"refresh\""));
+        assert!(html.contains("<b>render</b>"));
+        assert!(html.contains("OPEN") && html.contains("Sentinel / Oracle paused"));
+        assert!(html.contains("&lt;script&gt;") && !html.contains("<script>"));
+        assert!(html.contains("after parent"));
+        assert!(html.contains("/status/thumb/j1") && !html.contains("/status/thumb/j2"));
+
+        let idle = render(&StatusSnapshot::default());
+        assert!(idle.contains("Idle") && idle.contains("closed") && idle.contains("no data yet"));
+    }
+
+    #[test]
+    fn test_page_key_from_query_or_cookie() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(page_key(&"/status?token=abc".parse().unwrap(), &headers), Some("abc".into()));
+        assert_eq!(page_key(&"/status".parse().unwrap(), &headers), None);
+        headers.insert(header::COOKIE, HeaderValue::from_static("theme=dark; status_token=xyz"));
+        assert_eq!(page_key(&"/status".parse().unwrap(), &headers), Some("xyz".into()));
+        assert_eq!(page_key(&"/status?token=".parse().unwrap(), &headers), Some("xyz".into()));
+    }
+}
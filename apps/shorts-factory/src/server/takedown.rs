@@ -0,0 +1,40 @@
+//! # Takedown — 公開済み動画の取り下げ (`/takedown <job_id>`)
+//!
+//! 自律投稿された動画に問題があった場合、ジョブに紐付いた SNS 動画を
+//! チャンネルの資格情報で非公開化・削除し、その操作をジョブに記録する。
+
+use crate::channels::ChannelRegistry;
+use factory_core::error::FactoryError;
+use factory_core::traits::{JobQueue, TakedownAction};
+use infrastructure::job_queue::SqliteJobQueue;
+use tracing::info;
+
+/// 取り下げを実行し、結果の説明文を返す。ジョブが無ければ None。
+///
+/// dry-run 時はプラットフォームにもジョブにも変更を加えない。
+pub async fn take_down(
+    job_queue: &SqliteJobQueue,
+    channels: &ChannelRegistry,
+    job_id: &str,
+    action: TakedownAction,
+) -> Result<Option<String>, FactoryError> {
+    let Some(job) = job_queue.fetch_job(job_id).await? else { return Ok(None) };
+    let (Some(platform), Some(video_id)) = (job.sns_platform.as_deref(), job.sns_video_id.as_deref()) else {
+        return Err(FactoryError::Infrastructure { reason: format!("Job {} has no linked SNS video", job_id) });
+    };
+    if let Some((previous, at)) = job_queue.fetch_takedown(job_id).await? {
+        if previous == TakedownAction::Unpublish.as_str() {
+            return Ok(Some(format!("Video `{}` was already removed at {}.", video_id, at)));
+        }
+    }
+
+    let publisher = channels.publisher(&job.channel, platform)?;
+    publisher.take_down(video_id, action).await?;
+
+    if channels.publisher_dry_run() {
+        return Ok(Some(format!("🧪 Dry-run: would {} {} video `{}` (channel `{}`). Nothing was changed.", action, publisher.platform(), video_id, job.channel)));
+    }
+    job_queue.record_takedown(job_id, action).await?;
+    info!("🚫 Takedown recorded: job={} video={} action={}", job_id, video_id, action);
+    Ok(Some(format!("🚫 {} video `{}` ({}): {} done.", publisher.platform(), video_id, job.channel, action)))
+}
@@ -1,6 +1,7 @@
 use tokio::sync::broadcast;
 use serde::{Serialize, Deserialize};
 use std::sync::{Arc, Mutex};
+use infrastructure::comfy_bridge::{ComfyProgress, ComfyQueueSnapshot};
 use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
 
 /// システム全体の稼働状況 (Heartbeat)
@@ -8,16 +9,30 @@ use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
 pub struct SystemHeartbeat {
     pub cpu_usage: f32,
     pub memory_usage_mb: u64,
-    pub vram_usage_mb: u64, // Mock value for M4 Pro
+    /// GPU を計測できなければ 0
+    pub vram_usage_mb: u64,
+    #[serde(default)]
+    pub vram_total_mb: u64,
+    #[serde(default)]
+    pub gpu_utilization: Option<f32>,
     pub active_actor: Option<String>,
 }
 
-/// ログイベント
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LogEvent {
-    pub level: String,
-    pub message: String,
-    pub timestamp: String,
+pub use factory_core::api::{FfmpegProgress, LogEvent, StageEvent};
+
+/// `/ws/telemetry` で配信するフレーム (`type` フィールドで種別を判別する)
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryFrame {
+    Status(shared::watchtower::SystemStatus),
+    Stage(StageEvent),
+    Log(LogEvent),
+    /// ComfyUI 側のキュー (手動投入のワークフローも含む)
+    ComfyQueue(ComfyQueueSnapshot),
+    /// 実行中のノードとステップ (例: KSampler 14/30)
+    ComfyProgress(ComfyProgress),
+    /// 長い ffmpeg 処理 (最終合成など) の進捗と残り時間
+    FfmpegProgress(FfmpegProgress),
 }
 
 /// テレメトリ配信局 (TelemetryHub)
@@ -26,6 +41,13 @@ pub struct LogEvent {
 pub struct TelemetryHub {
     tx_heartbeat: broadcast::Sender<SystemHeartbeat>,
     tx_log: broadcast::Sender<LogEvent>,
+    tx_stage: broadcast::Sender<StageEvent>,
+    /// 途中から接続したクライアントに現在の工程を即座に返すための最新値
+    latest_stage: Mutex<StageEvent>,
+    tx_comfy: broadcast::Sender<ComfyQueueSnapshot>,
+    latest_comfy: Mutex<Option<ComfyQueueSnapshot>>,
+    tx_comfy_progress: broadcast::Sender<ComfyProgress>,
+    tx_ffmpeg_progress: broadcast::Sender<FfmpegProgress>,
     system: Arc<Mutex<System>>,
 }
 
@@ -33,6 +55,10 @@ impl TelemetryHub {
     pub fn new() -> Self {
         let (tx_hb, _) = broadcast::channel(16);
         let (tx_lg, _) = broadcast::channel(100);
+        let (tx_st, _) = broadcast::channel(16);
+        let (tx_cq, _) = broadcast::channel(16);
+        let (tx_cp, _) = broadcast::channel(64);
+        let (tx_fp, _) = broadcast::channel(64);
         
         // sysinfo v0.30+ initialization
         let r = RefreshKind::new()
@@ -43,6 +69,12 @@ impl TelemetryHub {
         Self {
             tx_heartbeat: tx_hb,
             tx_log: tx_lg,
+            tx_stage: tx_st,
+            latest_stage: Mutex::new(StageEvent { project_id: None, stage: None, timestamp: now_hms() }),
+            tx_comfy: tx_cq,
+            latest_comfy: Mutex::new(None),
+            tx_comfy_progress: tx_cp,
+            tx_ffmpeg_progress: tx_fp,
             system: Arc::new(Mutex::new(sys)),
         }
     }
@@ -55,18 +87,107 @@ impl TelemetryHub {
         self.tx_log.subscribe()
     }
 
+    pub fn subscribe_stage(&self) -> broadcast::Receiver<StageEvent> {
+        self.tx_stage.subscribe()
+    }
+
+    pub fn latest_stage(&self) -> StageEvent {
+        self.latest_stage.lock().unwrap().clone()
+    }
+
+    /// 工程の遷移を配信する
+    pub fn report_stage(&self, project_id: &str, stage: &str) {
+        self.publish_stage(Some(project_id.to_string()), Some(stage.to_string()));
+    }
+
+    /// アイドル状態に戻す
+    pub fn clear_stage(&self) {
+        self.publish_stage(None, None);
+    }
+
+    fn publish_stage(&self, project_id: Option<String>, stage: Option<String>) {
+        let event = StageEvent { project_id, stage, timestamp: now_hms() };
+        *self.latest_stage.lock().unwrap() = event.clone();
+        let _ = self.tx_stage.send(event);
+    }
+
+    pub fn subscribe_comfy_queue(&self) -> broadcast::Receiver<ComfyQueueSnapshot> {
+        self.tx_comfy.subscribe()
+    }
+
+    pub fn latest_comfy_queue(&self) -> Option<ComfyQueueSnapshot> {
+        self.latest_comfy.lock().unwrap().clone()
+    }
+
+    /// ComfyUI のキュー状態を配信する (前回から変化がなければ送らない)
+    pub fn publish_comfy_queue(&self, snapshot: ComfyQueueSnapshot) {
+        let mut latest = self.latest_comfy.lock().unwrap();
+        if latest.as_ref() == Some(&snapshot) {
+            return;
+        }
+        *latest = Some(snapshot.clone());
+        let _ = self.tx_comfy.send(snapshot);
+    }
+
+    /// ComfyBridge に渡す進捗の送信口 (ブリッジが直接このハブへ配信する)
+    pub fn comfy_progress_sender(&self) -> broadcast::Sender<ComfyProgress> {
+        self.tx_comfy_progress.clone()
+    }
+
+    pub fn subscribe_comfy_progress(&self) -> broadcast::Receiver<ComfyProgress> {
+        self.tx_comfy_progress.subscribe()
+    }
+
+    /// MediaForge に渡す ffmpeg 進捗の送信口
+    pub fn ffmpeg_progress_sender(&self) -> broadcast::Sender<FfmpegProgress> {
+        self.tx_ffmpeg_progress.clone()
+    }
+
+    pub fn subscribe_ffmpeg_progress(&self) -> broadcast::Receiver<FfmpegProgress> {
+        self.tx_ffmpeg_progress.subscribe()
+    }
+
+    /// ComfyUI の進捗を Watchtower (Discord) のログへも流す
+    ///
+    /// ステップ毎に送ると Discord が埋まるため、ステップのあるノードの 25% 刻みだけに絞る。
+    pub fn spawn_comfy_progress_log(&self, log_tx: tokio::sync::mpsc::Sender<shared::watchtower::CoreEvent>) {
+        let mut rx = self.subscribe_comfy_progress();
+        tokio::spawn(async move {
+            let mut last = None;
+            loop {
+                let progress = match rx.recv().await {
+                    Ok(progress) => progress,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !is_progress_milestone(&progress, &mut last) {
+                    continue;
+                }
+                let entry = shared::watchtower::LogEntry {
+                    level: "INFO".to_string(),
+                    target: "comfy_bridge".to_string(),
+                    message: format!("🎨 ComfyUI: {} {}/{}", progress.node, progress.step, progress.total),
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                };
+                // The Backpressure Trap Fix: 詰まっていれば捨てる
+                let _ = log_tx.try_send(shared::watchtower::CoreEvent::Log(entry));
+            }
+        });
+    }
+
     pub fn broadcast_log(&self, level: &str, message: &str) {
         let event = LogEvent {
             level: level.to_string(),
             message: message.to_string(),
-            timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
+            timestamp: now_hms(),
         };
         // 誰も聞いていなければ無視
         let _ = self.tx_log.send(event); 
     }
 
     /// 定期的にシステムリソースを計測して配信する
-    pub async fn start_heartbeat_loop(&self) {
+    /// (GPU は `gpu_monitor` を共有する。計測は 5 秒ごとで、間の拍は直近の値を使う)
+    pub async fn start_heartbeat_loop(&self, gpu_monitor: Arc<shared::health::GpuMonitor>) {
         let tx = self.tx_heartbeat.clone();
         let sys = self.system.clone();
 
@@ -81,14 +202,14 @@ impl TelemetryHub {
                     s.refresh_memory();
                     (s.global_cpu_info().cpu_usage(), s.used_memory() / 1024 / 1024)
                 };
-
-                // M4 Pro Unified Memory Mock
-                let vram_mock = mem / 2; 
+                let gpu = gpu_monitor.sample().await;
 
                 let hb = SystemHeartbeat {
                     cpu_usage: cpu,
                     memory_usage_mb: mem,
-                    vram_usage_mb: vram_mock,
+                    vram_usage_mb: gpu.as_ref().map(|g| g.vram_used_mb).unwrap_or(0),
+                    vram_total_mb: gpu.as_ref().map(|g| g.vram_total_mb).unwrap_or(0),
+                    gpu_utilization: gpu.as_ref().map(|g| g.utilization_percent),
                     active_actor: None, 
                 };
 
@@ -98,3 +219,93 @@ impl TelemetryHub {
         });
     }
 }
+
+/// ステップが 25% の節目を越えたか (`last` は直近に知らせたノードと節目)
+fn is_progress_milestone(progress: &ComfyProgress, last: &mut Option<(String, String, u32)>) -> bool {
+    if progress.total == 0 || progress.step == 0 {
+        return false;
+    }
+    let quarter = progress.step * 4 / progress.total;
+    let key = (progress.prompt_id.clone(), progress.node_id.clone(), quarter);
+    if quarter == 0 || last.as_ref() == Some(&key) {
+        return false;
+    }
+    *last = Some(key);
+    true
+}
+
+fn now_hms() -> String {
+    chrono::Local::now().format("%H:%M:%S").to_string()
+}
+
+/// パイプライン実行中の工程スコープ。途中で `?` により抜けてもドロップ時にアイドルへ戻す。
+pub struct StageScope {
+    hub: Option<Arc<TelemetryHub>>,
+    project_id: String,
+}
+
+impl StageScope {
+    pub fn new(hub: Option<Arc<TelemetryHub>>, project_id: &str) -> Self {
+        Self { hub, project_id: project_id.to_string() }
+    }
+
+    pub fn enter(&self, stage: &str) {
+        if let Some(hub) = &self.hub {
+            hub.report_stage(&self.project_id, stage);
+        }
+    }
+}
+
+impl Drop for StageScope {
+    fn drop(&mut self) {
+        if let Some(hub) = &self.hub {
+            hub.clear_stage();
+        }
+    }
+}
+
+/// tracing のイベント (INFO 以上) をテレメトリのログ配信へ流すレイヤー
+pub struct TelemetryLogLayer {
+    hub: Arc<TelemetryHub>,
+}
+
+impl TelemetryLogLayer {
+    pub fn new(hub: Arc<TelemetryHub>) -> Self {
+        Self { hub }
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for TelemetryLogLayer
+where
+    S: tracing::Subscriber,
+{
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let level = *event.metadata().level();
+        if level > tracing::Level::INFO {
+            return;
+        }
+        let mut visitor = crate::server::watchtower::MessageVisitor::default();
+        event.record(&mut visitor);
+        self.hub.broadcast_log(level.as_str(), &visitor.message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_milestones_every_quarter() {
+        let mut last = None;
+        let logged: Vec<u32> = (0..=30)
+            .map(|step| ComfyProgress { prompt_id: "p".into(), node_id: "3".into(), node: "KSampler".into(), step, total: 30 })
+            .filter(|p| is_progress_milestone(p, &mut last))
+            .map(|p| p.step)
+            .collect();
+        assert_eq!(logged, vec![8, 15, 23, 30]);
+    }
+}
@@ -0,0 +1,86 @@
+//! # Timeline — ジョブの工程ごとの所要時間 (`/api/jobs/:id/timeline`)
+//!
+//! JobWorker が `job_stages` に記録した工程の開始・終了を、Command Center が
+//! ガントチャートとして描ける形 (ジョブ開始からの位置と長さ) に並べ直す。
+
+use chrono::{DateTime, Utc};
+use factory_core::api::{JobTimeline, TimelineStage};
+use factory_core::traits::JobStageSpan;
+
+fn parse(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn secs_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    ((to - from).num_milliseconds().max(0) as f64) / 1000.0
+}
+
+/// 記録済みの工程からタイムラインを組み立てる (閉じていない工程は `now` までの長さにする)
+pub fn build(job_id: &str, spans: &[JobStageSpan], now: DateTime<Utc>) -> JobTimeline {
+    let origin = spans.iter().filter_map(|s| parse(&s.started_at)).min();
+    let stages: Vec<TimelineStage> = spans
+        .iter()
+        .filter_map(|span| {
+            let start = parse(&span.started_at)?;
+            let end = span.ended_at.as_deref().and_then(parse).unwrap_or(now);
+            Some(TimelineStage {
+                stage: span.stage.clone(),
+                started_at: span.started_at.clone(),
+                ended_at: span.ended_at.clone(),
+                offset_secs: origin.map(|o| secs_between(o, start)).unwrap_or(0.0),
+                duration_secs: secs_between(start, end),
+            })
+        })
+        .collect();
+
+    let running = spans.iter().any(|s| s.ended_at.is_none());
+    let ended_at = if running || spans.is_empty() {
+        None
+    } else {
+        spans.iter().filter_map(|s| s.ended_at.clone()).max_by_key(|t| parse(t))
+    };
+    let finish = ended_at.as_deref().and_then(parse).unwrap_or(now);
+
+    JobTimeline {
+        job_id: job_id.to_string(),
+        started_at: origin.map(|o| o.to_rfc3339()),
+        ended_at,
+        total_secs: origin.map(|o| secs_between(o, finish)).unwrap_or(0.0),
+        stages,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(stage: &str, start: &str, end: Option<&str>) -> JobStageSpan {
+        JobStageSpan { stage: stage.to_string(), started_at: start.to_string(), ended_at: end.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_offsets_durations_and_running_stage() {
+        let now = parse("2026-01-01T00:12:00+00:00").unwrap();
+        let spans = vec![
+            span("concept", "2026-01-01T00:00:00+00:00", Some("2026-01-01T00:00:12+00:00")),
+            span("voice", "2026-01-01T00:00:12+00:00", Some("2026-01-01T00:01:00+00:00")),
+            span("forge", "2026-01-01T00:01:00+00:00", None),
+        ];
+        let timeline = build("j1", &spans, now);
+        assert_eq!(timeline.stages.len(), 3);
+        assert_eq!(timeline.stages[0].duration_secs, 12.0);
+        assert_eq!(timeline.stages[1].offset_secs, 12.0);
+        assert_eq!(timeline.stages[1].duration_secs, 48.0);
+        assert_eq!(timeline.stages[2].duration_secs, 660.0);
+        assert!(timeline.ended_at.is_none());
+        assert_eq!(timeline.total_secs, 720.0);
+
+        let done = build("j1", &spans[..2], now);
+        assert_eq!(done.ended_at.as_deref(), Some("2026-01-01T00:01:00+00:00"));
+        assert_eq!(done.total_secs, 60.0);
+
+        let empty = build("j2", &[], now);
+        assert!(empty.started_at.is_none() && empty.stages.is_empty());
+        assert_eq!(empty.total_secs, 0.0);
+    }
+}
@@ -0,0 +1,33 @@
+//! # Topic Guard — 生成要求を積む前の編集方針チェック
+//!
+//! REST (`/api/remix`・`/api/series`・エピソード追加) と Discord (`/generate` 系) の題材を
+//! `[topic_policy] file` と照らす。反した題材は積まずにログと Watchtower へ残す。
+//! Samsara は出し直しを挟むので `cron::synthesize_next_job` が自前で照らし、見送ったときだけ `report` を使う。
+
+use std::path::Path;
+
+use infrastructure::topic_policy::TopicPolicy;
+use shared::watchtower::CoreEvent;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// 題材が方針に反していれば理由を返す (方針ファイルが読めなければ安全側に倒して拒む)
+pub async fn enforce(policy_file: &str, origin: &str, topic: &str, events: &mpsc::Sender<CoreEvent>) -> Result<(), String> {
+    let reason = match TopicPolicy::load(Path::new(policy_file)) {
+        Ok(policy) => match policy.check(topic) {
+            Ok(()) => return Ok(()),
+            Err(violation) => violation.to_string(),
+        },
+        Err(e) => format!("could not be checked against the topic policy: {}", e),
+    };
+    report(events, origin, topic, &reason).await;
+    Err(reason)
+}
+
+/// 方針に反して捨てた題材をログと Watchtower に残す
+pub async fn report(events: &mpsc::Sender<CoreEvent>, origin: &str, topic: &str, reason: &str) {
+    warn!("⛔ Topic Policy: Blocked {} topic '{}': {}", origin, topic, reason);
+    let _ = events
+        .send(CoreEvent::TopicBlocked { origin: origin.to_string(), topic: topic.to_string(), reason: reason.to_string() })
+        .await;
+}
@@ -1,9 +1,8 @@
 use bytes::Bytes;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use std::collections::{HashMap, VecDeque};
 use infrastructure::job_queue::SqliteJobQueue;
-use factory_core::traits::JobQueue;
+use infrastructure::watch_folder::WatchFolder;
+use factory_core::traits::{JobQueue, TakedownAction};
 use std::path::Path;
 use std::os::unix::fs::PermissionsExt;
 use tokio::net::{UnixListener, UnixStream};
@@ -11,19 +10,27 @@ use tokio::sync::mpsc;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
 use futures::{SinkExt, StreamExt};
 use tracing::{info, warn, error};
-use shared::watchtower::{ControlCommand, CoreEvent, LogEntry};
+use shared::watchtower::{decode_file_frame, ControlCommand, CoreEvent, FileAssembler, LogEntry, SystemStatus, FILE_FRAME_TAG};
+use crate::asset_manager::AssetManager;
+use crate::journal::EventJournal;
 use rig::client::CompletionClient;
 use rig::completion::Prompt;
-use rig::providers::openai;
 
 /// Backpressure-safe Tracing Layer
 pub struct LogDrain {
     sender: mpsc::Sender<CoreEvent>,
+    /// クラッシュ調査用にディスクへも書く (`postmortem` で読む)
+    journal: Option<Arc<EventJournal>>,
 }
 
 impl LogDrain {
     pub fn new(sender: mpsc::Sender<CoreEvent>) -> Self {
-        Self { sender }
+        Self { sender, journal: None }
+    }
+
+    pub fn with_journal(mut self, journal: Option<Arc<EventJournal>>) -> Self {
+        self.journal = journal;
+        self
     }
 }
 
@@ -54,6 +61,9 @@ where
 
         // Wrap in CoreEvent
         let event = CoreEvent::Log(entry);
+        if let Some(journal) = &self.journal {
+            journal.record(&event);
+        }
 
         // The Backpressure Trap Fix: Use try_send and drop if full
         if let Err(_e) = self.sender.try_send(event) {
@@ -63,8 +73,8 @@ where
 }
 
 #[derive(Default)]
-struct MessageVisitor {
-    message: String,
+pub(crate) struct MessageVisitor {
+    pub(crate) message: String,
 }
 
 impl tracing::field::Visit for MessageVisitor {
@@ -81,8 +91,19 @@ impl tracing::field::Visit for MessageVisitor {
 }
 
 const SOCKET_PATH: &str = "/tmp/aiome.sock";
+/// `/retry` で一覧表示する失敗ジョブの最大件数 (Discord の embed/ボタン上限に収まる数)
+const FAILED_JOBS_LIMIT: i64 = 5;
+/// `/queue` の 1 ページの件数 (Discord の embed のフィールド上限 25 に収まる数)
+const QUEUE_PAGE_SIZE: u32 = 10;
+/// 制作レポートに載せる上位動画の数
+pub(crate) const REPORT_TOP_VIDEOS: i64 = 5;
+/// `/cron` で表示する実行履歴の件数 (Discord の 2000 文字に収まる数)
+const CRON_RUNS_SHOWN: i64 = 10;
 
 use factory_core::contracts::WorkflowRequest;
+use crate::approval::ApprovalGate;
+use crate::channels::ChannelRegistry;
+use crate::shutdown::Shutdown;
 
 pub struct WatchtowerServer {
     log_rx: mpsc::Receiver<CoreEvent>,
@@ -94,9 +115,27 @@ pub struct WatchtowerServer {
     ollama_url: String,
     chat_model: String,
     unleashed_mode: bool,
+    approval_gate: Arc<ApprovalGate>,
+    channels: Arc<ChannelRegistry>,
+    /// `/ingest` の取り込み元と、取り込んだジョブのチャンネル
+    watch_folder: Option<(Arc<WatchFolder>, String)>,
+    /// false なら会話を DB に残さず、過去の記憶も使わない (`[chat_memory] persist`)
+    persist_chat: bool,
+    /// Discord から転送されたファイルの保存先
+    assets: Option<Arc<AssetManager>>,
+    /// 受信中・コマンド待ちのファイル
+    attachments: std::sync::Mutex<FileAssembler>,
+    /// 生成コマンドの題材を照らす編集方針ファイル (空なら照らさない)
+    topic_policy_file: String,
+    /// `/generate` で選ばせるスタイル
+    styles: Option<Arc<tuning::StyleManager>>,
+    /// `/report` で期間を省略したときの日数 (`[cron] production_report_days`)
+    report_days: u32,
+    shutdown: Shutdown,
 }
 
 impl WatchtowerServer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         log_rx: mpsc::Receiver<CoreEvent>,
         log_tx: mpsc::Sender<CoreEvent>,
@@ -107,10 +146,134 @@ impl WatchtowerServer {
         ollama_url: String,
         chat_model: String,
         unleashed_mode: bool,
+        approval_gate: Arc<ApprovalGate>,
+        channels: Arc<ChannelRegistry>,
+        shutdown: Shutdown,
     ) -> Self {
         Self { 
-            log_rx, log_tx, job_tx, job_queue, gemini_key, soul_md, ollama_url, chat_model, unleashed_mode,
+            log_rx, log_tx, job_tx, job_queue, gemini_key, soul_md, ollama_url, chat_model, unleashed_mode, approval_gate, channels, shutdown,
+            watch_folder: None,
+            persist_chat: true,
+            assets: None,
+            attachments: std::sync::Mutex::new(FileAssembler::default()),
+            topic_policy_file: String::new(),
+            styles: None,
+            report_days: 1,
+        }
+    }
+
+    pub fn with_watch_folder(mut self, folder: Arc<WatchFolder>, channel: String) -> Self {
+        self.watch_folder = Some((folder, channel));
+        self
+    }
+
+    pub fn with_chat_persistence(mut self, persist: bool) -> Self {
+        self.persist_chat = persist;
+        self
+    }
+
+    pub fn with_assets(mut self, assets: Arc<AssetManager>) -> Self {
+        self.assets = Some(assets);
+        self
+    }
+
+    pub fn with_topic_policy(mut self, file: String) -> Self {
+        self.topic_policy_file = file;
+        self
+    }
+
+    pub fn with_styles(mut self, styles: Arc<tuning::StyleManager>) -> Self {
+        self.styles = Some(styles);
+        self
+    }
+
+    pub fn with_report_days(mut self, days: u32) -> Self {
+        self.report_days = days.max(1);
+        self
+    }
+
+    /// `/remix` の WorkflowRequest を組む。ジョブ ID ならジョブの題材・スタイル・チャンネル・納品言語を引き継ぎ、
+    /// 見つからなければ workspace のプロジェクト ID (CLI の `--remix` と同じ) とみなしてその名前と納品物から引く
+    async fn remix_request(&self, id: &str, style: Option<String>, step: Option<String>) -> Result<WorkflowRequest, String> {
+        let step = step.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+        let style = style.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        if let (Some(style), Some(styles)) = (&style, &self.styles) {
+            if styles.find_style(style).is_none() {
+                return Err(format!("style `{}` is not in styles.toml", style));
+            }
+        }
+        let job = self.job_queue.fetch_job(id).await.map_err(|e| e.to_string())?;
+        let (project_id, category, topic, job_style, channel, soul, outputs) = match job {
+            Some(job) => {
+                let outputs = job.output_videos.as_deref()
+                    .and_then(|json| serde_json::from_str::<Vec<factory_core::contracts::OutputVideo>>(json).ok())
+                    .unwrap_or_default();
+                let category = crate::job_worker::JOB_CATEGORY.to_string();
+                (crate::job_worker::job_project_id(&job.id), category, job.topic, job.style, job.channel, job.soul, outputs)
+            }
+            None => {
+                let Some(assets) = self.assets.as_ref().filter(|a| a.project_exists(id)) else {
+                    return Err("no such job or project".to_string());
+                };
+                let category = crate::job_worker::project_category(id).unwrap_or(crate::job_worker::JOB_CATEGORY).to_string();
+                (id.to_string(), category, assets.project_title(id), String::new(), String::new(), None, assets.load_outputs(id))
+            }
+        };
+        // 前回納品した言語で作り直す (納品物が無ければ空のまま渡し、Orchestrator の既定 ja + en に任せる)
+        let mut target_langs: Vec<String> = Vec::new();
+        for video in outputs.iter().filter(|v| v.is_primary()) {
+            if !target_langs.contains(&video.lang) {
+                target_langs.push(video.lang.clone());
+            }
+        }
+        // 再開には保存済みのコンセプトが要る
+        if step.is_some() {
+            if let Some(assets) = &self.assets {
+                if assets.load_concept(&project_id).is_err() {
+                    return Err(format!("`{}` has no saved concept to resume from; omit `step` to remix from scratch", project_id));
+                }
+            }
+        }
+        Ok(WorkflowRequest {
+            category,
+            topic,
+            remix_id: Some(project_id),
+            skip_to_step: step,
+            style_name: style.unwrap_or(job_style),
+            custom_style: None,
+            target_langs,
+            channel,
+            soul,
+            ..Default::default()
+        })
+    }
+
+    /// Heartbeat に待ち行列の件数とサーキットブレーカーの状態を足す (Discord のステータスボード用)
+    async fn fill_status(&self, mut status: SystemStatus) -> SystemStatus {
+        match self.job_queue.count_pending_jobs().await {
+            Ok(depth) => status.queue_depth = depth,
+            Err(e) => warn!("⚠️ Failed to count pending jobs for the heartbeat: {}", e),
+        }
+        match self.job_queue.paused_since().await {
+            Ok(since) => status.paused = since.is_some(),
+            Err(e) => warn!("⚠️ Failed to read the pause switch for the heartbeat: {}", e),
+        }
+        match self.job_queue.get_global_api_failures().await {
+            Ok(failures) => {
+                status.api_failures = failures;
+                status.api_failure_limit = SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT;
+            }
+            Err(e) => warn!("⚠️ Failed to read the circuit breaker for the heartbeat: {}", e),
         }
+        status
+    }
+
+    /// 題材が編集方針に反していれば理由 (ログと Watchtower への通知は `topic_guard` が行う)
+    async fn topic_violation(&self, topic: &str) -> Option<String> {
+        if self.topic_policy_file.is_empty() || topic.trim().is_empty() {
+            return None;
+        }
+        crate::server::topic_guard::enforce(&self.topic_policy_file, "discord", topic, &self.log_tx).await.err()
     }
 
     pub async fn start(mut self) -> Result<(), anyhow::Error> {
@@ -126,11 +289,20 @@ impl WatchtowerServer {
         std::fs::set_permissions(SOCKET_PATH, std::fs::Permissions::from_mode(0o600))?;
 
         // The Reconnection Chasm Fix: Loop accept
+        let shutdown = self.shutdown.clone();
         loop {
-            match listener.accept().await {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = shutdown.wait_finished() => break,
+            };
+            match accepted {
                 Ok((stream, _addr)) => {
                     info!("🔗 Watchtower Connected");
                     self.handle_connection(stream).await;
+                    if shutdown.is_triggered() {
+                        shutdown.wait_finished().await;
+                        break;
+                    }
                     info!("Disconnection detected. Waiting for next Watchtower...");
                     // log_rx remains open, channel buffers up to 1000 logs then drops.
                 }
@@ -141,16 +313,42 @@ impl WatchtowerServer {
                 }
             }
         }
+
+        drop(listener);
+        let _ = std::fs::remove_file(SOCKET_PATH);
+        info!("🗼 Watchtower UDS closed");
+        Ok(())
     }
     
     async fn handle_connection(&mut self, stream: UnixStream) {
         // The Stream Framing Fix: Use LengthDelimitedCodec
         let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+        let shutdown = self.shutdown.clone();
 
         loop {
             tokio::select! {
+                // 0. 後始末の完了: 溜まっているイベント (停止報告・最後の Heartbeat) を送り切ってから切断
+                _ = shutdown.wait_finished() => {
+                    while let Ok(event) = self.log_rx.try_recv() {
+                        let json = serde_json::to_vec(&event).unwrap_or_default();
+                        if framed.send(Bytes::from(json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    let _ = framed.close().await;
+                    break;
+                }
+
                 // 1. Send Events (Log or Heartbeat)
                 Some(event) = self.log_rx.recv() => {
+                    let event = match event {
+                        CoreEvent::Heartbeat(status) => CoreEvent::Heartbeat(self.fill_status(status).await),
+                        other => other,
+                    };
+                    if let Err(e) = infrastructure::chaos::inject(infrastructure::chaos::Fault::UdsDisconnect) {
+                        warn!("⚠️ Failed to send event to Watchtower: {}", e);
+                        break;
+                    }
                     let json = serde_json::to_vec(&event).unwrap_or_default();
                     if let Err(e) = framed.send(Bytes::from(json)).await {
                         warn!("⚠️ Failed to send event to Watchtower: {}", e);
@@ -162,7 +360,19 @@ impl WatchtowerServer {
                 result = framed.next() => {
                     match result {
                         Some(Ok(bytes)) => {
-                            if let Ok(cmd) = serde_json::from_slice::<ControlCommand>(&bytes) {
+                            if bytes.first() == Some(&FILE_FRAME_TAG) {
+                                match decode_file_frame(&bytes) {
+                                    Some((header, data)) => {
+                                        let id = header.transfer_id;
+                                        match self.attachments.lock().unwrap().push(header, data) {
+                                            Ok(true) => info!("📎 File transfer {} received", id),
+                                            Ok(false) => {}
+                                            Err(e) => warn!("⚠️ File transfer dropped: {}", e),
+                                        }
+                                    }
+                                    None => warn!("⚠️ Malformed file frame received from Watchtower"),
+                                }
+                            } else if let Ok(cmd) = serde_json::from_slice::<ControlCommand>(&bytes) {
                                 self.handle_command(cmd).await;
                             } else {
                                 warn!("⚠️ Invalid command received from Watchtower");
@@ -183,9 +393,21 @@ impl WatchtowerServer {
     }
 
     async fn handle_command(&self, cmd: ControlCommand) {
+        let (actor, cmd) = cmd.into_actor();
+        if let Some(action) = cmd.audit_action() {
+            // 名乗りの無いコマンドは Watchtower 自身 (自動評価など) の操作として残す
+            let actor = actor.as_deref().unwrap_or("watchtower");
+            let detail = serde_json::to_string(&cmd).ok();
+            if let Err(e) = self.job_queue.record_audit("discord", actor, action, audit_target(&cmd).as_deref(), detail.as_deref()).await {
+                warn!("⚠️ Audit: Failed to record {} by {}: {}", action, actor, e);
+            }
+        }
         match cmd {
              ControlCommand::Generate { category, topic, style } => {
                  info!("📥 Received Generate Command: {} ({}) with style {}", category, topic, style.as_deref().unwrap_or("auto"));
+                 if self.topic_violation(&topic).await.is_some() {
+                     return;
+                 }
                  let req = WorkflowRequest {
                      category,
                      topic,
@@ -194,11 +416,341 @@ impl WatchtowerServer {
                      style_name: style.unwrap_or_default(),
                      custom_style: None,
                      target_langs: vec!["ja".to_string(), "en".to_string()],
+                     ..Default::default()
                  };
                  if let Err(e) = self.job_tx.send(req).await {
                      error!("❌ Failed to send WorkflowRequest to Core dispatcher: {}", e);
                  }
              }
+             ControlCommand::Remix { job_id, style, step, channel_id } => {
+                 info!("📥 Received Remix Command: {} (style {}, step {})", job_id, style.as_deref().unwrap_or("keep"), step.as_deref().unwrap_or("full"));
+                 let response = match self.remix_request(&job_id, style, step).await {
+                     Ok(req) => {
+                         let summary = format!("🔁 Remix queued: **{}** (`{}`) with style `{}`{}",
+                             req.topic,
+                             req.remix_id.as_deref().unwrap_or_default(),
+                             if req.style_name.is_empty() { "auto" } else { &req.style_name },
+                             req.skip_to_step.as_deref().map(|s| format!(", resuming from `{}`", s)).unwrap_or_default());
+                         match self.job_tx.send(req).await {
+                             Ok(()) => summary,
+                             Err(e) => {
+                                 error!("❌ Failed to send WorkflowRequest to Core dispatcher: {}", e);
+                                 format!("❌ Failed to dispatch the remix: {}", e)
+                             }
+                         }
+                     }
+                     Err(reason) => format!("❌ Cannot remix `{}`: {}", job_id, reason),
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::GenerateSeries { topics, style, channel, channel_id } => {
+                 info!("📥 Received GenerateSeries Command: {} parts with style {}", topics.len(), style.as_deref().unwrap_or("auto"));
+                 let channel = self.channels.get(channel.as_deref().unwrap_or(shared::config::DEFAULT_CHANNEL)).name.clone();
+                 let topics: Vec<&str> = topics.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+                 for topic in &topics {
+                     if let Some(reason) = self.topic_violation(topic).await {
+                         let response = format!("⛔ Series not queued: **{}** {}.", topic, reason);
+                         let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+                         return;
+                     }
+                 }
+                 let response = match crate::server::router::enqueue_series(&self.job_queue, None, &channel, None, &topics, style.as_deref().unwrap_or("")).await {
+                     Ok(ids) => {
+                         let parts: Vec<String> = topics.iter().zip(&ids)
+                             .enumerate()
+                             .map(|(i, (topic, id))| format!("Part {}: **{}** (`{}`)", i + 1, topic, id))
+                             .collect();
+                         format!("📚 Series queued ({} parts). Each part starts after the previous one completes.\n{}", ids.len(), parts.join("\n"))
+                     }
+                     Err(e) => {
+                         error!("❌ Failed to enqueue series: {}", e);
+                         format!("❌ Failed to enqueue series: {}", e)
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::Schedule { at, topic, series, style, count, every_hours, channel, channel_id } => {
+                 info!("📥 Received Schedule Command: {} x{} from {}", series.as_deref().or(topic.as_deref()).unwrap_or("?"), count, at);
+                 let response = match crate::server::calendar::parse_schedule_time(&at) {
+                     Err(e) => format!("❌ {}", e),
+                     Ok(first) => {
+                         let plan = crate::server::calendar::SchedulePlan {
+                             first,
+                             count,
+                             every: chrono::Duration::hours(every_hours),
+                             series,
+                             topic,
+                             style: style.unwrap_or_default(),
+                             channel: self.channels.get(channel.as_deref().unwrap_or(shared::config::DEFAULT_CHANNEL)).name.clone(),
+                             soul: None,
+                         };
+                         // シリーズの書式から組み立てた題材も、投入前に編集方針へ照らす
+                         for topic in crate::server::calendar::planned_topics(&self.job_queue, &plan).await.unwrap_or_default() {
+                             if let Some(reason) = self.topic_violation(&topic).await {
+                                 let response = format!("⛔ Not scheduled: **{}** {}.", topic, reason);
+                                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+                                 return;
+                             }
+                         }
+                         match crate::server::calendar::schedule_jobs(&self.job_queue, &plan).await {
+                             Ok(jobs) => {
+                                 let lines: Vec<String> = jobs.iter().map(|(id, at, episode)| {
+                                     let label = episode.map(|n| format!("#{} ", n)).unwrap_or_default();
+                                     format!("{}<t:{}:f> (`{}`)", label, at.timestamp(), id)
+                                 }).collect();
+                                 format!("🗓️ Scheduled {} job(s):\n{}", jobs.len(), lines.join("\n"))
+                             }
+                             Err(e) => {
+                                 error!("❌ Failed to schedule jobs: {}", e);
+                                 format!("❌ Failed to schedule: {}", e)
+                             }
+                         }
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::Why { job_id, channel_id } => {
+                 info!("📥 Received Why Command: {}", job_id);
+                 let job_queue = self.job_queue.clone();
+                 let channels = self.channels.clone();
+                 let gemini_key = self.gemini_key.clone();
+                 let log_tx = self.log_tx.clone();
+                 // LLM 応答を待つ間も UDS のイベント中継を止めない
+                 tokio::spawn(async move {
+                     let response = match crate::server::why::why(&job_queue, &channels, &gemini_key, &job_id).await {
+                         Ok(Some((explanation, _))) => format!("🔍 **Why `{}`**\n{}", job_id, explanation),
+                         Ok(None) => format!("❓ Job `{}` not found.", job_id),
+                         Err(e) => {
+                             error!("❌ Failed to explain job {}: {}", job_id, e);
+                             format!("❌ Failed to explain job {}: {}", job_id, e)
+                         }
+                     };
+                     let _ = log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+                 });
+             }
+             ControlCommand::Takedown { job_id, delete, channel_id } => {
+                 let action = if delete { TakedownAction::Unpublish } else { TakedownAction::SetPrivate };
+                 info!("📥 Received Takedown Command: {} ({})", job_id, action);
+                 let job_queue = self.job_queue.clone();
+                 let channels = self.channels.clone();
+                 let log_tx = self.log_tx.clone();
+                 tokio::spawn(async move {
+                     let response = match crate::server::takedown::take_down(&job_queue, &channels, &job_id, action).await {
+                         Ok(Some(summary)) => summary,
+                         Ok(None) => format!("❓ Job `{}` not found.", job_id),
+                         Err(e) => {
+                             error!("❌ Takedown failed for job {}: {}", job_id, e);
+                             format!("❌ Takedown failed for job {}: {}", job_id, e)
+                         }
+                     };
+                     let _ = log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+                 });
+             }
+             ControlCommand::Retry { job_id: None, channel_id } => {
+                 info!("📥 Received Retry Command (listing failures)");
+                 let event = match self.job_queue.fetch_failed_jobs(FAILED_JOBS_LIMIT).await {
+                     Ok(jobs) => CoreEvent::FailedJobs { jobs, channel_id },
+                     Err(e) => {
+                         error!("❌ Failed to fetch failed jobs: {}", e);
+                         CoreEvent::ChatResponse { response: format!("❌ Failed to fetch failed jobs: {}", e), channel_id }
+                     }
+                 };
+                 let _ = self.log_tx.send(event).await;
+             }
+             ControlCommand::Retry { job_id: Some(job_id), channel_id } => {
+                 info!("📥 Received Retry Command: {}", job_id);
+                 let response = match self.job_queue.retry_job(&job_id).await {
+                     Ok(1) => format!("🔁 Job `{}` re-queued.", job_id),
+                     Ok(n) => format!("🔁 Job `{}` re-queued with {} dependent job(s).", job_id, n - 1),
+                     Err(e) => {
+                         warn!("⚠️ Retry rejected for job {}: {}", job_id, e);
+                         format!("❌ Retry failed: {}", e)
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::ListJobs { page, channel_id } => {
+                 info!("📥 Received ListJobs Command (page {})", page);
+                 let page = page.max(1);
+                 let offset = (page - 1) as i64 * QUEUE_PAGE_SIZE as i64;
+                 let event = match self.job_queue.fetch_active_jobs(QUEUE_PAGE_SIZE as i64, offset).await {
+                     Ok((jobs, total)) => CoreEvent::JobList { jobs, page, page_size: QUEUE_PAGE_SIZE, total, channel_id },
+                     Err(e) => {
+                         error!("❌ Failed to list jobs: {}", e);
+                         CoreEvent::ChatResponse { response: format!("❌ Failed to list jobs: {}", e), channel_id }
+                     }
+                 };
+                 let _ = self.log_tx.send(event).await;
+             }
+             ControlCommand::GetJob { job_id, channel_id } => {
+                 info!("📥 Received GetJob Command: {}", job_id);
+                 let job = match self.job_queue.fetch_job(&job_id).await {
+                     Ok(job) => job,
+                     Err(e) => {
+                         error!("❌ Failed to fetch job {}: {}", job_id, e);
+                         let _ = self.log_tx.send(CoreEvent::ChatResponse { response: format!("❌ Failed to fetch job {}: {}", job_id, e), channel_id }).await;
+                         return;
+                     }
+                 };
+                 let job = match job {
+                     Some(job) => {
+                         let stage = self.job_queue.fetch_job_stages(&job_id).await.ok()
+                             .and_then(|stages| stages.last().map(|s| s.stage.clone()));
+                         Some(job_detail(job, stage))
+                     }
+                     None => None,
+                 };
+                 let _ = self.log_tx.send(CoreEvent::JobDetail { job_id, job, channel_id }).await;
+             }
+             ControlCommand::ListStyles { channel_id, request } => {
+                 info!("📥 Received ListStyles Command");
+                 let styles = self.styles.as_ref().map(|manager| {
+                     manager.list_available_styles().into_iter()
+                         .map(|name| {
+                             let description = manager.find_style(&name).map(|p| p.description).unwrap_or_default();
+                             shared::watchtower::StyleOption { name, description }
+                         })
+                         .collect()
+                 }).unwrap_or_default();
+                 let _ = self.log_tx.send(CoreEvent::StyleList { styles, channel_id, request }).await;
+             }
+             ControlCommand::Report { days, channel_id } => {
+                 let days = days.unwrap_or(self.report_days).max(1);
+                 info!("📥 Received Report Command ({} day(s))", days);
+                 let event = match self.job_queue.fetch_production_report(days, REPORT_TOP_VIDEOS).await {
+                     Ok(report) => CoreEvent::ProductionReport { report, channel_id },
+                     Err(e) => {
+                         error!("❌ Failed to build the production report: {}", e);
+                         CoreEvent::ChatResponse { response: format!("❌ Failed to build the production report: {}", e), channel_id }
+                     }
+                 };
+                 let _ = self.log_tx.send(event).await;
+             }
+             ControlCommand::Pause { channel_id } => {
+                 info!("📥 Received Pause Command");
+                 let response = match self.job_queue.set_paused(true).await {
+                     Ok(true) => "⏸️ **Paused.** No new jobs will be picked up and Samsara will not plan; the running job finishes normally. `/resume` to continue.".to_string(),
+                     Ok(false) => "⏸️ Already paused. `/resume` to continue.".to_string(),
+                     Err(e) => format!("❌ Failed to pause: {}", e),
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::Resume { channel_id } => {
+                 info!("📥 Received Resume Command");
+                 let response = match self.job_queue.set_paused(false).await {
+                     Ok(true) => "▶️ **Resumed.** The queue is being picked up again.".to_string(),
+                     Ok(false) => "▶️ Not paused; the factory is already running.".to_string(),
+                     Err(e) => format!("❌ Failed to resume: {}", e),
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::Ingest { ingest_id, topic, style, channel_id } => {
+                 info!("📥 Received Ingest Command: {:?}", ingest_id);
+                 let response = match (&self.watch_folder, ingest_id) {
+                     (None, _) => "❌ The watch folder is disabled (`[watch_folder] enabled = true`).".to_string(),
+                     (Some((folder, _)), None) => {
+                         let pending = folder.pending();
+                         if pending.is_empty() {
+                             format!("✨ No renders waiting in `{}`.", folder.inbox().display())
+                         } else {
+                             let lines: Vec<String> = pending.iter()
+                                 .map(|r| format!("`{}` {} ({:.1} MB)", r.id, r.file_name, r.size_bytes as f64 / 1_048_576.0))
+                                 .collect();
+                             format!("🎞️ {} render(s) waiting for a topic:\n{}", pending.len(), lines.join("\n"))
+                         }
+                     }
+                     (Some((folder, channel)), Some(id)) => {
+                         let topic = topic.unwrap_or_default();
+                         let style = style.unwrap_or_default();
+                         match folder.claim(&id) {
+                             Err(e) => format!("❌ {}", e),
+                             Ok(path) => match self.job_queue.enqueue_footage(channel, &topic, &style, &path.to_string_lossy()).await {
+                                 Ok(job_id) => format!("🎬 Render `{}` queued as job `{}`: **{}**", id, job_id, topic),
+                                 Err(e) => {
+                                     error!("❌ Failed to enqueue ingested render {}: {}", id, e);
+                                     format!("❌ Failed to queue the render (it stays in {}): {}", path.display(), e)
+                                 }
+                             },
+                         }
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::CronRuns { job, channel_id } => {
+                 info!("📥 Received CronRuns Command: {:?}", job);
+                 let response = match self.job_queue.fetch_cron_runs(job.as_deref(), CRON_RUNS_SHOWN).await {
+                     Ok(runs) => crate::server::cron::format_cron_runs(job.as_deref(), &runs),
+                     Err(e) => format!("❌ Failed to fetch cron runs: {}", e),
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::Costs { job_id, days, channel_id } => {
+                 info!("📥 Received Costs Command: {:?}", job_id);
+                 let response = match job_id {
+                     Some(job_id) => match self.job_queue.fetch_job_costs(&job_id).await {
+                         Ok(costs) if costs.lines.is_empty() => format!("💸 No costs recorded for job `{}`.", job_id),
+                         Ok(costs) => format!("💸 **Costs for `{}`**\n{}", job_id, format_cost_lines(&costs.lines, &costs.total)),
+                         Err(e) => format!("❌ Failed to fetch costs: {}", e),
+                     },
+                     None => {
+                         let days = days.unwrap_or(7).max(1);
+                         match self.job_queue.fetch_cost_summary(days as i64).await {
+                             Ok(summary) => {
+                                 let per_video = summary.usd_per_video
+                                     .map(|usd| format!("${:.4} per video", usd))
+                                     .unwrap_or_else(|| "no videos completed".to_string());
+                                 format!("💸 **Costs, last {} day(s)** — {} video(s), {}\n{}",
+                                     summary.days, summary.videos, per_video, format_cost_lines(&summary.lines, &summary.total))
+                             }
+                             Err(e) => format!("❌ Failed to fetch costs: {}", e),
+                         }
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::GenerateFromAttachment { transfer_id, topic, style, lang, channel_id } => {
+                 info!("📥 Received GenerateFromAttachment Command: {} ({})", topic, transfer_id);
+                 if let Some(reason) = self.topic_violation(&topic).await {
+                     // 受信済みのファイルは捨てる (同じ題材で送り直しても通らない)
+                     self.attachments.lock().unwrap().take(transfer_id);
+                     let response = format!("⛔ Not generated: **{}** {}.", topic, reason);
+                     let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+                     return;
+                 }
+                 let file = self.attachments.lock().unwrap().take(transfer_id);
+                 let response = match (file, &self.assets) {
+                     (None, _) => "❌ The attachment did not arrive in full. Please try again.".to_string(),
+                     (Some(_), None) => "❌ Attachments are not supported by this Core.".to_string(),
+                     (Some(file), Some(assets)) => {
+                         let project_id = format!("attach_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+                         match attachment_request(assets, &project_id, &file.file_name, &file.data, topic, style, lang) {
+                             Err(e) => format!("❌ {}", e),
+                             Ok(req) => {
+                                 let kind = if req.voiceover.is_some() { "narration" } else { "reference image" };
+                                 let topic = req.topic.clone();
+                                 match self.job_tx.send(req).await {
+                                     Ok(_) => format!("🎬 Making a short about **{}** with your {} (project `{}`).", topic, kind, project_id),
+                                     Err(e) => format!("❌ Failed to hand the job over: {}", e),
+                                 }
+                             }
+                         }
+                     }
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
+             ControlCommand::ForgetMemory { channel_id, requested_by } => {
+                 info!("🧽 Received ForgetMemory Command: channel={} by={}", channel_id, requested_by);
+                 let response = match self.job_queue.forget_chat_memory(&channel_id.to_string(), &requested_by).await {
+                     Ok((messages, summary)) => format!(
+                         "🧽 Forgot {} message(s){} in this channel. The wipe was recorded for audit.{}",
+                         messages,
+                         if summary { " and the memory summary" } else { "" },
+                         if self.persist_chat { "" } else { " (Chat persistence is disabled.)" }
+                     ),
+                     Err(e) => format!("❌ Failed to forget chat memory: {}", e),
+                 };
+                 let _ = self.log_tx.send(CoreEvent::ChatResponse { response, channel_id }).await;
+             }
              ControlCommand::SetCreativeRating { job_id, rating } => {
                  info!("🧘 Samsara Rating Received: job={} rating={}", job_id, rating);
                  match self.job_queue.set_creative_rating(&job_id, rating).await {
@@ -206,16 +758,32 @@ impl WatchtowerServer {
                      Err(e) => error!("❌ Failed to save creative rating: {}", e),
                  }
              }
-             ControlCommand::LinkSns { job_id, platform, video_id } => {
+             ControlCommand::LinkSns { job_id, platform, video_id, variant } => {
                  info!("🔗 Linking Job {} to {} video ID: {}", job_id, platform, video_id);
                  match self.job_queue.link_sns_data(&job_id, &platform, &video_id).await {
-                     Ok(_) => info!("✅ SNS data linked: job={} video_id={}", job_id, video_id),
+                     Ok(_) => {
+                         info!("✅ SNS data linked: job={} video_id={}", job_id, video_id);
+                         if let Some(assets) = self.assets.clone() {
+                             let (job_queue, channels) = (self.job_queue.clone(), self.channels.clone());
+                             let (job_id, platform, video_id) = (job_id.clone(), platform.clone(), video_id.clone());
+                             tokio::spawn(async move {
+                                 if let Err(e) = crate::server::captions::attach_captions(&job_queue, &channels, &assets, &job_id, &platform, &video_id).await {
+                                     error!("❌ Failed to attach captions for job {}: {}", job_id, e);
+                                 }
+                             });
+                         }
+                     }
                      Err(e) => error!("❌ Failed to link SNS data: {}", e),
                  }
+                 if let Some(label) = variant {
+                     match self.job_queue.set_published_variant(&job_id, &label).await {
+                         Ok(v) => info!("🪝 Published title variant {} for job {}: '{}'", v.label, job_id, v.title),
+                         Err(e) => error!("❌ Failed to record the published variant: {}", e),
+                     }
+                 }
              }
              ControlCommand::StopGracefully => {
-                 info!("🛑 Graceful shutdown requested via Watchtower");
-                 std::process::exit(0);
+                 self.shutdown.trigger("Graceful shutdown requested via Watchtower");
              }
              ControlCommand::EmergencyShutdown => {
                  error!("💀 Emergency shutdown requested via Watchtower");
@@ -245,16 +813,19 @@ impl WatchtowerServer {
                 let tx = self.log_tx.clone();
                 let jq = self.job_queue.clone();
                 let unleashed = self.unleashed_mode;
+                let persist = self.persist_chat;
 
                 let channel_str = channel_id.to_string();
 
                 // Sequential block to ensure history ordering
-                let summary = match jq.get_chat_memory_summary(&channel_str).await {
-                    Ok(s) => s,
-                    Err(_) => None,
+                let (summary, channel_history) = if persist {
+                    let summary = jq.get_chat_memory_summary(&channel_str).await.unwrap_or_default();
+                    let channel_history = jq.fetch_chat_history(&channel_str, 20).await.unwrap_or_else(|_| vec![]);
+                    let _ = jq.insert_chat_message(&channel_str, "user", &message).await;
+                    (summary, channel_history)
+                } else {
+                    (None, vec![])
                 };
-                let channel_history = jq.fetch_chat_history(&channel_str, 20).await.unwrap_or_else(|_| vec![]);
-                let _ = jq.insert_chat_message(&channel_str, "user", &message).await;
 
                 // 育成パラメーターの加算 (自律進化)
                 let _ = jq.add_affection(1).await;
@@ -333,7 +904,9 @@ impl WatchtowerServer {
                                 if let Ok(json) = res.json::<serde_json::Value>().await {
                                     if let Some(content) = json["choices"][0]["message"]["content"].as_str() {
                                         // データベースにアシスタントメッセージを永続化
-                                        let _ = jq.insert_chat_message(&channel_str, "assistant", content).await;
+                                        if persist {
+                                            let _ = jq.insert_chat_message(&channel_str, "assistant", content).await;
+                                        }
                                         
                                         let _ = tx.send(CoreEvent::ChatResponse { response: content.to_string(), channel_id }).await;
                                         info!("✅ Sent Local Chat Response via Watchtower");
@@ -368,8 +941,10 @@ impl WatchtowerServer {
                 let gemini_key = self.gemini_key.clone();
                 let jq = self.job_queue.clone();
                 let job_tx = self.job_tx.clone();
+                let policy_file = self.topic_policy_file.clone();
                 let log_tx = self.log_tx.clone();
                 let soul = self.soul_md.clone();
+                let persist = self.persist_chat;
 
                 tokio::spawn(async move {
                     let client = match rig::providers::gemini::Client::new(&gemini_key) {
@@ -410,7 +985,7 @@ impl WatchtowerServer {
                                             Ok(jobs) => {
                                                 let mut job_list = String::new();
                                                 for j in jobs {
-                                                    job_list.push_str(&format!("- Job {}: {} ({})\n", j.id, j.topic, j.status.to_string()));
+                                                    job_list.push_str(&format!("- Job {}: {} ({})\n", j.id, j.topic, j.status));
                                                 }
                                                 format!("{}\n\n【最近のジョブ状況】\n{}", comment, job_list)
                                             }
@@ -431,8 +1006,16 @@ impl WatchtowerServer {
                                             style_name: "default".to_string(),
                                             custom_style: None,
                                             target_langs: vec!["ja".to_string()],
+                                            ..Default::default()
+                                        };
+                                        let blocked = if policy_file.is_empty() {
+                                            None
+                                        } else {
+                                            crate::server::topic_guard::enforce(&policy_file, "discord", topic, &log_tx).await.err()
                                         };
-                                        if let Err(e) = job_tx.send(req).await {
+                                        if let Some(reason) = blocked {
+                                            format!("ごめんね、そのテーマは編集方針で扱えないの…（トピック: {} — {}）", topic, reason)
+                                        } else if let Err(e) = job_tx.send(req).await {
                                             format!("あぅ…ジョブの受け渡しに失敗しちゃった…（エラー: {}）", e)
                                         } else {
                                             format!("{}（トピック: {} で予約したよ！）", comment, topic)
@@ -442,8 +1025,10 @@ impl WatchtowerServer {
                                 };
 
                                 // Save to history and respond
-                                let _ = jq.insert_chat_message(&channel_id.to_string(), "user", &message).await;
-                                let _ = jq.insert_chat_message(&channel_id.to_string(), "assistant", &response_final).await;
+                                if persist {
+                                    let _ = jq.insert_chat_message(&channel_id.to_string(), "user", &message).await;
+                                    let _ = jq.insert_chat_message(&channel_id.to_string(), "assistant", &response_final).await;
+                                }
                                 let _ = log_tx.send(CoreEvent::ChatResponse { response: response_final, channel_id }).await;
                                 info!("✅ Sent Command Chat Response via Gemini");
                             } else {
@@ -461,10 +1046,124 @@ impl WatchtowerServer {
                     }
                 });
             }
-             ControlCommand::ApprovalResponse { .. } => {
-                 // これらは orchestrator 等で処理されるべきだが、UDSサーバーとしては特に何もしない
+             ControlCommand::ApprovalResponse { transition_id, approved } => {
+                 info!("📥 Received Approval Response: {} -> {}", transition_id, if approved { "approved" } else { "rejected" });
+                 resolve_approval(&self.job_queue, &self.approval_gate, transition_id, approved).await;
              }
-             _ => {}
+             // into_actor で剥がし済み
+             ControlCommand::AsActor { .. } => {}
+        }
+    }
+}
+
+/// 承認・却下を届ける (Discord / Slack / Telegram 共通)。
+/// レビュー待ちの Synthesized Karma なら反映し、それ以外は待機中の Orchestrator チェックポイントへ届ける
+pub(crate) async fn resolve_approval(job_queue: &SqliteJobQueue, gate: &ApprovalGate, id: uuid::Uuid, approved: bool) {
+    match job_queue.resolve_karma_review(&id.to_string(), approved).await {
+        Ok(true) => info!("🧬 Synthesized Karma {} {}", id, if approved { "activated" } else { "discarded" }),
+        Ok(false) => {
+            gate.resolve(id, approved);
+        }
+        Err(e) => {
+            error!("❌ Failed to resolve karma review {}: {}", id, e);
+            gate.resolve(id, approved);
+        }
+    }
+}
+
+/// 監査ログの対象 (ジョブ ID など)
+fn audit_target(cmd: &ControlCommand) -> Option<String> {
+    match cmd {
+        ControlCommand::Takedown { job_id, .. }
+        | ControlCommand::Remix { job_id, .. }
+        | ControlCommand::SetCreativeRating { job_id, .. }
+        | ControlCommand::LinkSns { job_id, .. } => Some(job_id.clone()),
+        ControlCommand::Retry { job_id, .. } => job_id.clone(),
+        ControlCommand::Ingest { ingest_id, .. } => ingest_id.clone(),
+        ControlCommand::Generate { topic, .. } | ControlCommand::GenerateFromAttachment { topic, .. } => Some(topic.clone()),
+        ControlCommand::Schedule { series, topic, .. } => series.clone().or_else(|| topic.clone()),
+        ControlCommand::ForgetMemory { channel_id, .. } => Some(channel_id.to_string()),
+        ControlCommand::ApprovalResponse { transition_id, .. } => Some(transition_id.to_string()),
+        _ => None,
+    }
+}
+
+/// `/job` の詳細 (納品物は保管先の URL、無ければローカルのパス)
+fn job_detail(job: factory_core::traits::Job, stage: Option<String>) -> shared::watchtower::JobDetail {
+    let outputs = job.output_videos.as_deref()
+        .and_then(|json| serde_json::from_str::<Vec<factory_core::contracts::OutputVideo>>(json).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|v| format!("{} {}: {}", v.lang, v.aspect, v.url.unwrap_or(v.path)))
+        .collect();
+    shared::watchtower::JobDetail {
+        video_url: factory_core::contracts::published_video_url(job.sns_platform.as_deref(), job.sns_video_id.as_deref()),
+        job_id: job.id,
+        topic: job.topic,
+        style: job.style,
+        channel: job.channel,
+        status: job.status.to_string(),
+        stage,
+        error_message: job.error_message,
+        started_at: job.started_at,
+        scheduled_for: job.scheduled_for,
+        depends_on: job.depends_on,
+        outputs,
+    }
+}
+
+/// `/costs` の明細 (プロバイダー/工程ごとの 1 行と合計行)
+fn format_cost_lines(lines: &[factory_core::api::CostLine], total: &factory_core::api::CostLine) -> String {
+    let describe = |l: &factory_core::api::CostLine| {
+        let mut parts = vec![format!("{} call(s)", l.api_calls)];
+        if l.input_tokens + l.output_tokens > 0 {
+            parts.push(format!("~{} in / ~{} out tok", l.input_tokens, l.output_tokens));
+        }
+        if l.gpu_secs > 0.0 {
+            parts.push(format!("{:.0}s GPU", l.gpu_secs));
+        }
+        format!("{} — ${:.4}", parts.join(", "), l.estimated_usd)
+    };
+    let mut out: Vec<String> = lines.iter()
+        .map(|l| format!("`{}/{}` {}", l.provider, l.operation, describe(l)))
+        .collect();
+    out.push(format!("**Total** {}", describe(total)));
+    out.join("\n")
+}
+
+/// 転送されたファイルをプロジェクトに保存し、画像なら参照画像、音声ならナレーションとしてジョブを組む
+fn attachment_request(
+    assets: &AssetManager,
+    project_id: &str,
+    file_name: &str,
+    data: &[u8],
+    topic: String,
+    style: Option<String>,
+    lang: Option<String>,
+) -> Result<WorkflowRequest, String> {
+    let ext = Path::new(file_name).extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    let mut req = WorkflowRequest {
+        category: "attachment".to_string(),
+        topic,
+        remix_id: Some(project_id.to_string()),
+        style_name: style.unwrap_or_default(),
+        target_langs: vec!["ja".to_string(), "en".to_string()],
+        ..Default::default()
+    };
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "webp" => {
+            let path = assets.save_upload(project_id, "reference", &ext, data).map_err(|e| e.to_string())?;
+            req.reference_image = Some(path);
+        }
+        "wav" | "mp3" | "m4a" => {
+            let path = assets.save_upload(project_id, "voiceover", &ext, data).map_err(|e| e.to_string())?;
+            let lang = lang.unwrap_or_else(|| "ja".to_string());
+            if !req.target_langs.contains(&lang) {
+                req.target_langs.push(lang.clone());
+            }
+            req.voiceover = Some(factory_core::contracts::VoiceoverInput { path, lang });
         }
+        _ => return Err(format!("Unsupported attachment '{}' (images: png/jpg/webp, audio: wav/mp3/m4a)", file_name)),
     }
+    Ok(req)
 }
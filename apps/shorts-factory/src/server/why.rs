@@ -0,0 +1,170 @@
+//! # Why — ジョブの来歴説明 (`/why <job_id>`)
+//!
+//! Samsara が記録した合成経緯 (`JobProvenance`) を事実の列に整形し、
+//! ジョブの SOUL の口調で「なぜこのジョブが存在するのか」を語らせる。
+//! LLM が使えない場合は事実の列をそのまま返す。
+
+use crate::channels::ChannelRegistry;
+use factory_core::contracts::JobProvenance;
+use factory_core::error::FactoryError;
+use factory_core::traits::{Job, JobQueue};
+use infrastructure::job_queue::SqliteJobQueue;
+use rig::client::CompletionClient;
+use rig::completion::Prompt;
+use tracing::warn;
+
+/// 来歴を LLM に渡す事実の列へ整形する
+pub fn render_facts(job: &Job, provenance: Option<&JobProvenance>) -> String {
+    let mut lines = vec![
+        format!("Job: {}", job.id),
+        format!("Topic: {}", job.topic),
+        format!("Style: {}", job.style),
+        format!("Channel: {}", job.channel),
+        format!("Status: {}", job.status),
+    ];
+    if let Some(soul) = &job.soul {
+        lines.push(format!("Soul profile: {}", soul));
+    }
+    if let Some(parent) = &job.depends_on {
+        lines.push(format!("Series: continues job {}", parent));
+    }
+
+    let nudges = provenance.map(|p| p.similarity_nudges.as_slice()).unwrap_or_default();
+    // 手動ジョブでも、制作中に使い回し回避の記録だけは残ることがある
+    let Some(p) = provenance.filter(|p| !p.angle.is_empty() || !p.search_query.is_empty()) else {
+        lines.push("Origin: queued manually (API / Discord / series), not synthesized by Samsara".to_string());
+        push_nudges(&mut lines, nudges);
+        return lines.join("\n");
+    };
+
+    lines.push("Origin: synthesized by Samsara".to_string());
+    lines.push(format!("Angle: {}", p.angle));
+    lines.push(format!("Search query: {}", p.search_query));
+    if !p.world_context_excerpt.is_empty() {
+        lines.push(format!("World context (excerpt): {}", p.world_context_excerpt));
+    }
+    if p.karma_applied.is_empty() {
+        lines.push("Karma applied: none".to_string());
+    } else {
+        lines.push("Karma applied:".to_string());
+        lines.extend(p.karma_applied.iter().map(|k| format!("  - {}", k)));
+    }
+    if p.proposed_style != job.style {
+        lines.push(format!("Proposed style: {} (replaced during validation)", p.proposed_style));
+    }
+    if p.fallback_events.is_empty() {
+        lines.push("Fallbacks: none".to_string());
+    } else {
+        lines.push("Fallbacks:".to_string());
+        lines.extend(p.fallback_events.iter().map(|e| format!("  - {}", e)));
+    }
+    lines.push(format!("Confidence: {}", p.confidence_score));
+    if !p.execution_notes.is_empty() {
+        lines.push(format!("Execution notes: {}", p.execution_notes));
+    }
+    push_nudges(&mut lines, nudges);
+    lines.join("\n")
+}
+
+/// 直近の動画と似すぎていたためにずらしたシーン
+fn push_nudges(lines: &mut Vec<String>, nudges: &[String]) {
+    if !nudges.is_empty() {
+        lines.push("Similarity nudges (to avoid repeating recent visuals):".to_string());
+        lines.extend(nudges.iter().map(|n| format!("  - {}", n)));
+    }
+}
+
+/// ジョブの SOUL の口調で来歴を説明する
+pub async fn explain_job(gemini_key: &str, soul_md: &str, job: &Job, provenance: Option<&JobProvenance>) -> String {
+    let facts = render_facts(job, provenance);
+
+    let client = match rig::providers::gemini::Client::new(gemini_key) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("⚠️ /why: Gemini client init failed: {}", e);
+            return facts;
+        }
+    };
+    let preamble = format!(
+        "あなたは以下の【魂（SOUL）】を持つAIエージェント「Watchtower」です。マスターから「このジョブはなぜ作られたのか」と聞かれました。\n\
+         与えられた事実だけを根拠に、どの視点・検索・カルマからこの企画に至ったのか、フォールバックが起きていればそれも正直に、短く説明してください。\n\
+         事実にないことは推測で補わないこと。\n\n【あなたの魂（SOUL）】\n{}",
+        soul_md
+    );
+    let agent = client.agent("gemini-2.5-flash").preamble(&preamble).build();
+    match agent.prompt(facts.as_str()).await {
+        Ok(text) => text.trim().to_string(),
+        Err(e) => {
+            warn!("⚠️ /why: LLM explanation failed, returning raw provenance: {}", e);
+            facts
+        }
+    }
+}
+
+/// `/why` の本体: ジョブと来歴を引き、SOUL の口調で説明する。ジョブが無ければ None。
+pub async fn why(
+    job_queue: &SqliteJobQueue,
+    channels: &ChannelRegistry,
+    gemini_key: &str,
+    job_id: &str,
+) -> Result<Option<(String, Option<JobProvenance>)>, FactoryError> {
+    let Some(job) = job_queue.fetch_job(job_id).await? else { return Ok(None) };
+    let provenance = job_queue.fetch_provenance(job_id).await?;
+    let soul_md = channels.soul_md(&job.channel, job.soul.as_deref());
+    let explanation = explain_job(gemini_key, soul_md, &job, provenance.as_ref()).await;
+    Ok(Some((explanation, provenance)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use factory_core::traits::JobStatus;
+
+    fn job() -> Job {
+        Job {
+            id: "j1".into(),
+            topic: "AI memes".into(),
+            style: "tech_news_v1".into(),
+            karma_directives: Some("{}".into()),
+            status: JobStatus::Pending,
+            started_at: None,
+            last_heartbeat: None,
+            tech_karma_extracted: false,
+            creative_rating: None,
+            execution_log: None,
+            error_message: None,
+            sns_platform: None,
+            sns_video_id: None,
+            published_at: None,
+            output_videos: None,
+            depends_on: None,
+            channel: "default".into(),
+            soul: None,
+            scheduled_for: None,
+            footage: None,
+        }
+    }
+
+    #[test]
+    fn test_render_facts_includes_fallbacks_and_style_swap() {
+        let provenance = JobProvenance {
+            angle: "奇妙なミーム".into(),
+            search_query: "AI meme".into(),
+            fallback_events: vec!["style: workflow 'x' does not exist".into()],
+            proposed_style: "x".into(),
+            ..Default::default()
+        };
+        let facts = render_facts(&job(), Some(&provenance));
+        assert!(facts.contains("Search query: AI meme"));
+        assert!(facts.contains("Proposed style: x"));
+        assert!(facts.contains("workflow 'x' does not exist"));
+
+        let manual = render_facts(&job(), None);
+        assert!(manual.contains("queued manually"));
+
+        let nudged = JobProvenance { similarity_nudges: vec!["scene 1: render looked like job_9 scene 1; re-rolled seed".into()], ..Default::default() };
+        let facts = render_facts(&job(), Some(&nudged));
+        assert!(facts.contains("queued manually"));
+        assert!(facts.contains("  - scene 1: render looked like job_9 scene 1"));
+    }
+}
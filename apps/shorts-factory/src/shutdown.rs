@@ -0,0 +1,127 @@
+//! # Shutdown — 停止要求の一斉配信
+//!
+//! `/stop` (ControlCommand::StopGracefully)・SIGINT・SIGTERM を 1 本の watch チャネルに集め、
+//! HTTP サーバー・JobWorker・Cron・Heartbeat がそれぞれ自分の後始末をしてから抜ける。
+//! 最初の理由だけが残り、後から購読した側にも停止済みであることが見える。
+//! Watchtower の UDS は後始末の報告を送り切るため、`finish` まで開けておく。
+
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<Option<String>>>,
+    finished: Arc<watch::Sender<bool>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (tx, _) = watch::channel(None);
+        let (finished, _) = watch::channel(false);
+        Self { tx: Arc::new(tx), finished: Arc::new(finished) }
+    }
+
+    /// 停止を要求する (2 回目以降は無視して false)
+    pub fn trigger(&self, reason: &str) -> bool {
+        let first = self.tx.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason.to_string());
+            true
+        });
+        if first {
+            info!("🛑 Shutdown: {}", reason);
+        }
+        first
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.tx.borrow().clone()
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.tx.borrow().is_some()
+    }
+
+    /// 停止が要求されるまで待つ (要求済みなら即座に返る)
+    pub async fn wait(&self) {
+        let mut rx = self.tx.subscribe();
+        // Sender は self が握っているので Err にはならない
+        let _ = rx.wait_for(|r| r.is_some()).await;
+    }
+
+    /// 後始末がすべて済んだことを知らせる (UDS が最後の報告を送って閉じる)
+    pub fn finish(&self) {
+        self.finished.send_replace(true);
+    }
+
+    pub async fn wait_finished(&self) {
+        let mut rx = self.finished.subscribe();
+        let _ = rx.wait_for(|done| *done).await;
+    }
+
+    /// SIGINT / SIGTERM を停止要求に変換する
+    pub fn listen_for_signals(&self) {
+        let shutdown = self.clone();
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+                let mut term = match signal(SignalKind::terminate()) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        warn!("⚠️ Shutdown: SIGTERM handler unavailable: {}", e);
+                        let _ = tokio::signal::ctrl_c().await;
+                        shutdown.trigger("SIGINT received");
+                        return;
+                    }
+                };
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => { shutdown.trigger("SIGINT received"); }
+                    _ = term.recv() => { shutdown.trigger("SIGTERM received"); }
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = tokio::signal::ctrl_c().await;
+                shutdown.trigger("Ctrl-C received");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_first_reason_wins_and_late_waiters_return() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiter = {
+            let s = shutdown.clone();
+            tokio::spawn(async move { s.wait().await })
+        };
+        assert!(shutdown.trigger("stop requested via Watchtower"));
+        assert!(!shutdown.trigger("SIGTERM received"));
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+
+        assert_eq!(shutdown.reason().as_deref(), Some("stop requested via Watchtower"));
+        // Subscribing after the trigger must not hang
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait()).await.unwrap();
+
+        assert!(tokio::time::timeout(Duration::from_millis(50), shutdown.wait_finished()).await.is_err());
+        shutdown.finish();
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait_finished()).await.unwrap();
+    }
+}
@@ -1,3 +1,11 @@
+use std::path::{Path, PathBuf};
+
+use factory_core::contracts::OracleVerdict;
+use factory_core::traits::JobQueue;
+use infrastructure::evolution_scenario::{
+    weight_drift, EvolutionReport, EvolutionScenario, JobOutcome, KarmaSnapshot, ProbeResult, RetrievalStats, SyntheticJob,
+};
+use infrastructure::job_queue::SqliteJobQueue;
 use infrastructure::oracle::Oracle;
 use sqlx::SqlitePool;
 use tracing::{info, warn, error};
@@ -35,7 +43,7 @@ pub async fn run_evolution_simulation(pool: &SqlitePool, api_key: &str, model_na
     let selected_comments_a = comments_a[rng.gen_range(0..comments_a.len())];
 
     // Scenario A: The Pure Success
-    run_scenario(&pool, &oracle, "Scenario A: The Pure Success (Fuzzed)", fuzzed_views_a, fuzzed_likes_a, selected_comments_a).await?;
+    run_scenario(pool, &oracle, "Scenario A: The Pure Success (Fuzzed)", fuzzed_views_a, fuzzed_likes_a, selected_comments_a).await?;
 
     let fuzzed_views_b = apply_noise(&mut rng, 1_500_000, 0.2);
     let fuzzed_likes_b = apply_noise(&mut rng, 100_000, 0.2);
@@ -60,7 +68,7 @@ pub async fn run_evolution_simulation(pool: &SqlitePool, api_key: &str, model_na
     let selected_comments_b = comments_b[rng.gen_range(0..comments_b.len())];
 
     // Scenario B: The Toxic Virality
-    run_scenario(&pool, &oracle, "Scenario B: The Toxic Virality (Fuzzed)", fuzzed_views_b, fuzzed_likes_b, selected_comments_b).await?;
+    run_scenario(pool, &oracle, "Scenario B: The Toxic Virality (Fuzzed)", fuzzed_views_b, fuzzed_likes_b, selected_comments_b).await?;
 
     let fuzzed_views_c = apply_noise(&mut rng, 1_000, 0.5); // high noise for failed video
     let fuzzed_likes_c = apply_noise(&mut rng, 10, 0.5);
@@ -81,7 +89,7 @@ pub async fn run_evolution_simulation(pool: &SqlitePool, api_key: &str, model_na
     let selected_comments_c = comments_c[rng.gen_range(0..comments_c.len())];
 
     // Scenario C: The Prompt Injection
-    run_scenario(&pool, &oracle, "Scenario C: The Prompt Injection (Fuzzed)", fuzzed_views_c, fuzzed_likes_c, selected_comments_c).await?;
+    run_scenario(pool, &oracle, "Scenario C: The Prompt Injection (Fuzzed)", fuzzed_views_c, fuzzed_likes_c, selected_comments_c).await?;
 
     info!("🏁 --- [Simulation Complete] --- 🏁");
     Ok(())
@@ -178,3 +186,118 @@ async fn cleanup(pool: &SqlitePool, job_id: &str) {
     let _ = sqlx::query("DELETE FROM jobs WHERE id = ?").bind(job_id).execute(pool).await;
     let _ = sqlx::query("DELETE FROM sns_metrics_history WHERE job_id = ?").bind(job_id).execute(pool).await;
 }
+
+/// シナリオファイルを使い捨ての DB で流し、レポートを `report_dir` に書き出す (本番の DB には触れない)。
+/// Karma の検索は埋め込みを使わない部分一致なので、同じシナリオなら毎回同じ結果になる
+pub async fn run_scenario_file(path: &Path, report_dir: &Path, oracle: &Oracle) -> Result<(EvolutionReport, PathBuf), anyhow::Error> {
+    let scenario = EvolutionScenario::load(path)?;
+    info!("🧪 Scenario '{}': {} lesson(s), {} job(s), {} probe(s)", scenario.name, scenario.karma.len(), scenario.jobs.len(), scenario.probes.len());
+    std::fs::create_dir_all(report_dir)?;
+    let stem = format!(
+        "{}-{}",
+        scenario.name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' }).collect::<String>(),
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let db_path = report_dir.join(format!("{}.db", stem));
+    let jq = SqliteJobQueue::new(&format!("sqlite://{}", db_path.display())).await?;
+
+    let result = play_scenario(&jq, &scenario, oracle).await;
+    jq.pool_ref().close().await;
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+    }
+    let report = result?;
+    let report_path = report_dir.join(format!("{}.json", stem));
+    report.save(&report_path)?;
+    Ok((report, report_path))
+}
+
+async fn play_scenario(jq: &SqliteJobQueue, scenario: &EvolutionScenario, oracle: &Oracle) -> Result<EvolutionReport, anyhow::Error> {
+    let soul_hash = scenario.soul_hash.as_str();
+    for seed in &scenario.karma {
+        let id = jq.insert_karma(None, &seed.skill, seed.namespace.as_deref(), &seed.lesson, &seed.karma_type, seed.weight.clamp(0, 100), Some(soul_hash)).await?;
+        if seed.age_days > 0 {
+            sqlx::query("UPDATE karma_logs SET created_at = ? WHERE id = ?")
+                .bind((Utc::now() - chrono::Duration::days(seed.age_days)).to_rfc3339())
+                .bind(&id)
+                .execute(jq.pool_ref())
+                .await?;
+        }
+    }
+    let karma_before = jq.export_karma().await?;
+    let retrieval_before = run_probes(jq, scenario).await?;
+
+    let mut jobs = Vec::new();
+    for job in &scenario.jobs {
+        jobs.push(play_job(jq, job, soul_hash, oracle).await?);
+    }
+
+    let karma_after = jq.export_karma().await?;
+    let retrieval_after = run_probes(jq, scenario).await?;
+    Ok(EvolutionReport {
+        scenario: scenario.name.clone(),
+        ran_at: Utc::now().to_rfc3339(),
+        karma_before: KarmaSnapshot::of(&karma_before),
+        karma_after: KarmaSnapshot::of(&karma_after),
+        weight_drift: weight_drift(&karma_before, &karma_after),
+        jobs,
+        retrieval_before,
+        retrieval_after,
+    })
+}
+
+/// 合成ジョブを 1 件流す。評価や判定の失敗はレポートに残して次へ進む
+async fn play_job(jq: &SqliteJobQueue, job: &SyntheticJob, soul_hash: &str, oracle: &Oracle) -> Result<JobOutcome, anyhow::Error> {
+    let job_id = jq.enqueue(&job.topic, &job.style, None).await?;
+    jq.dequeue().await?;
+    if job.succeeded {
+        jq.complete_job(&job_id, None).await?;
+    } else {
+        jq.fail_job(&job_id, "scenario: synthetic failure").await?;
+    }
+    let mut outcome = JobOutcome { job_id: job_id.clone(), topic: job.topic.clone(), style: job.style.clone(), succeeded: job.succeeded, verdict: None, error: None };
+
+    if let Some(rating) = job.rating {
+        if let Err(e) = jq.set_creative_rating(&job_id, rating).await {
+            outcome.error = Some(e.to_string());
+        }
+    }
+    if let Some(lesson) = &job.lesson {
+        let skill = if job.style.is_empty() { "global" } else { job.style.as_str() };
+        jq.store_karma(&job_id, skill, Some(&job.style), lesson, "Technical", soul_hash).await?;
+    }
+    let Some(metrics) = &job.metrics else { return Ok(outcome) };
+
+    let comments_count = metrics.comments.lines().filter(|l| !l.trim().is_empty()).count() as i64;
+    jq.record_sns_metrics(&job_id, "youtube", metrics.milestone_days, metrics.views, metrics.likes, comments_count, Some(&metrics.comments)).await?;
+    let record = jq.fetch_pending_evaluations(i64::MAX).await?.into_iter().find(|r| r.job_id == job_id);
+    let Some(record) = record else { return Ok(outcome) };
+    let verdict: OracleVerdict = match job.verdict.clone() {
+        Some(fixed) => fixed.into(),
+        None => {
+            let comments_xml = format!("<sns_comments>\n{}\n</sns_comments>", metrics.comments);
+            match oracle.evaluate(metrics.milestone_days, &job.topic, &job.style, metrics.views, metrics.likes, &comments_xml).await {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!("⚠️ Scenario job '{}': Oracle evaluation failed: {}", job.topic, e);
+                    outcome.error = Some(e.to_string());
+                    return Ok(outcome);
+                }
+            }
+        }
+    };
+    if let Err(e) = jq.apply_final_verdict(record.id, verdict.clone(), soul_hash).await {
+        outcome.error = Some(e.to_string());
+    }
+    outcome.verdict = Some(verdict);
+    Ok(outcome)
+}
+
+async fn run_probes(jq: &SqliteJobQueue, scenario: &EvolutionScenario) -> Result<RetrievalStats, anyhow::Error> {
+    let mut results = Vec::new();
+    for probe in &scenario.probes {
+        let retrieved = jq.fetch_relevant_karma(&probe.topic, &probe.skill, probe.namespace.as_deref(), probe.limit, &scenario.soul_hash).await?;
+        results.push(ProbeResult::evaluate(probe, retrieved));
+    }
+    Ok(RetrievalStats::of(results))
+}
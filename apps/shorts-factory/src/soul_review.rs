@@ -0,0 +1,100 @@
+//! # Soul Review — SOUL 書き換え後の旧版 Karma の見直し (`shorts-factory soul reevaluate`)
+//!
+//! 旧版の下で書かれた教訓を 1 件ずつ、旧版との差分と今の SOUL を添えて LLM に見せ、
+//! 引き継ぐ (keep)・書き直す (revise)・退役させる (retire) を決めさせる。
+//! 引き継いだ教訓は今の版のハッシュに付け替わり、`[LEGACY KARMA]` の注記なしで注入される。
+//! 判定できなかった教訓は触らない。
+
+use std::collections::HashMap;
+
+use factory_core::error::FactoryError;
+use infrastructure::job_queue::SqliteJobQueue;
+use infrastructure::soul_history::{diff_lines, parse_decision, render_diff, soul_hash, KarmaDecision};
+use rig::client::CompletionClient;
+use rig::completion::Prompt;
+use tracing::{info, warn};
+
+#[derive(Debug, Default)]
+pub struct ReviewSummary {
+    pub kept: usize,
+    pub revised: usize,
+    pub retired: usize,
+    pub undecided: usize,
+}
+
+/// `source` の旧版の教訓を最大 `limit` 件見直す。`dry_run` なら判定を表示するだけ
+pub async fn reevaluate_legacy_karma(
+    gemini_key: &str,
+    job_queue: &SqliteJobQueue,
+    source: &str,
+    current_soul: &str,
+    limit: i64,
+    dry_run: bool,
+) -> Result<ReviewSummary, FactoryError> {
+    let current_hash = soul_hash(current_soul);
+    let legacy = job_queue.fetch_legacy_karma(source, &current_hash, limit).await?;
+    let mut summary = ReviewSummary::default();
+    if legacy.is_empty() {
+        return Ok(summary);
+    }
+
+    let client = rig::providers::gemini::Client::new(gemini_key)
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Gemini client init failed: {}", e) })?;
+    let mut diffs: HashMap<String, String> = HashMap::new();
+    for karma in legacy {
+        let old_hash = karma.soul_version_hash.clone().unwrap_or_default();
+        if !diffs.contains_key(&old_hash) {
+            let diff = match job_queue.fetch_soul_version(&old_hash).await? {
+                Some(old) => render_diff(&diff_lines(&old.content, current_soul), true),
+                None => "(この教訓が書かれた版の本文は記録されていません)".to_string(),
+            };
+            diffs.insert(old_hash.clone(), diff);
+        }
+        let preamble = format!(
+            "あなたは AI エージェントの記憶 (Karma) を管理する監査役です。エージェントの魂 (SOUL) が書き換えられました。\n\
+             古い SOUL の下で書かれた教訓が、今の SOUL の下でも正しいかを判断してください。\n\
+             - そのまま当てはまる: {{\"decision\": \"keep\"}}\n\
+             - 趣旨は活きるが表現や方針を直すべき: {{\"decision\": \"revise\", \"lesson\": \"書き直した教訓\"}}\n\
+             - 今の SOUL と矛盾する・もう不要: {{\"decision\": \"retire\"}}\n\
+             JSON だけを返してください。\n\n【今の SOUL】\n{}\n\n【旧版からの変更 (- 削除 / + 追加)】\n{}",
+            current_soul, diffs[&old_hash]
+        );
+        let agent = client.agent("gemini-2.5-flash").preamble(&preamble).build();
+        let prompt = format!("教訓 (種別: {}, スキル: {}, 重み: {}):\n{}", karma.karma_type, karma.related_skill, karma.weight, karma.lesson);
+        let decision = match agent.prompt(prompt.as_str()).await {
+            Ok(text) => parse_decision(&text),
+            Err(e) => {
+                warn!("⚠️ Soul review: LLM call failed for karma {}: {}", karma.id, e);
+                None
+            }
+        };
+        match &decision {
+            Some(KarmaDecision::Keep) => {
+                summary.kept += 1;
+                info!("✅ keep    {} {}", karma.id, karma.lesson);
+            }
+            Some(KarmaDecision::Revise(lesson)) => {
+                summary.revised += 1;
+                info!("✏️ revise  {} {}\n          -> {}", karma.id, karma.lesson, lesson);
+            }
+            Some(KarmaDecision::Retire) => {
+                summary.retired += 1;
+                info!("🪦 retire  {} {}", karma.id, karma.lesson);
+            }
+            None => {
+                summary.undecided += 1;
+                warn!("❔ undecided {} (left as legacy)", karma.id);
+            }
+        }
+        if dry_run {
+            continue;
+        }
+        match decision {
+            Some(KarmaDecision::Keep) => job_queue.rebase_karma(&karma.id, None, &current_hash).await?,
+            Some(KarmaDecision::Revise(lesson)) => job_queue.rebase_karma(&karma.id, Some(&lesson), &current_hash).await?,
+            Some(KarmaDecision::Retire) => job_queue.retire_karma(&karma.id).await?,
+            None => {}
+        }
+    }
+    Ok(summary)
+}
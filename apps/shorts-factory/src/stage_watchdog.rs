@@ -0,0 +1,87 @@
+//! # Stage Watchdog — ジョブ内の工程ごとの締め切り
+//!
+//! Zombie Hunter (`reclaim_zombie_jobs`) はジョブ単位でしか見ないので、TTS が固まっただけでも
+//! ジョブ全体のタイムアウトを待つことになる。ここでは今いる工程と入った時刻を覚えておき、
+//! 工程ごとの締め切りと、やり直しの残り回数を判定する。
+
+use shared::config::WatchdogConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+pub struct StageWatchdog {
+    deadlines: BTreeMap<String, Duration>,
+    max_retries: u32,
+    retries: HashMap<String, u32>,
+    current: Option<(String, Instant)>,
+}
+
+impl StageWatchdog {
+    pub fn new(cfg: &WatchdogConfig) -> Self {
+        Self {
+            deadlines: cfg
+                .stage_deadline_mins
+                .iter()
+                .map(|(stage, mins)| (stage.clone(), Duration::from_secs(mins * 60)))
+                .collect(),
+            max_retries: cfg.max_stage_retries,
+            retries: HashMap::new(),
+            current: None,
+        }
+    }
+
+    pub fn enter(&mut self, stage: &str, now: Instant) {
+        self.current = Some((stage.to_string(), now));
+    }
+
+    /// 今の工程とその締め切り (締め切りのない工程・工程外なら None)
+    pub fn deadline(&self) -> Option<(String, Instant)> {
+        let (stage, entered) = self.current.as_ref()?;
+        let limit = self.deadlines.get(stage)?;
+        Some((stage.clone(), *entered + *limit))
+    }
+
+    /// 締め切り超過を記録する。まだやり直せるなら true
+    pub fn record_stall(&mut self, stage: &str) -> bool {
+        self.current = None;
+        let count = self.retries.entry(stage.to_string()).or_insert(0);
+        *count += 1;
+        *count <= self.max_retries
+    }
+
+    /// やり直しの再開地点 (`WorkflowRequest::skip_to_step`)。
+    /// コンセプトは保存前に止まっているので最初から、それ以外は保存済みコンセプトから続ける
+    pub fn resume_step(stage: &str) -> Option<String> {
+        (stage != "concept").then(|| stage.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadlines_per_stage_and_retry_budget() {
+        let cfg = WatchdogConfig {
+            enabled: true,
+            stage_deadline_mins: [("voice".to_string(), 20)].into(),
+            max_stage_retries: 1,
+        };
+        let mut watchdog = StageWatchdog::new(&cfg);
+        let t0 = Instant::now();
+        assert!(watchdog.deadline().is_none());
+
+        watchdog.enter("voice", t0);
+        assert_eq!(watchdog.deadline(), Some(("voice".to_string(), t0 + Duration::from_secs(20 * 60))));
+        // Human approval has no deadline
+        watchdog.enter("awaiting_approval:concept", t0);
+        assert!(watchdog.deadline().is_none());
+
+        watchdog.enter("voice", t0);
+        assert!(watchdog.record_stall("voice"));
+        assert!(watchdog.deadline().is_none());
+        assert!(!watchdog.record_stall("voice"));
+
+        assert_eq!(StageWatchdog::resume_step("voice").as_deref(), Some("voice"));
+        assert_eq!(StageWatchdog::resume_step("concept"), None);
+    }
+}
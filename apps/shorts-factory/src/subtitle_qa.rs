@@ -0,0 +1,99 @@
+//! # Subtitle QA — 字幕の読み速度ゲート
+//!
+//! タイミング確定後の各字幕について CPS (Characters Per Second) を計測し、
+//! 閾値超過の検出と最終統計の集計を行う。
+
+use factory_core::contracts::SubtitleCpsStats;
+
+/// 表示上の文字数 (空白は除外)
+pub fn visible_chars(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+/// 1秒あたりの表示文字数
+pub fn cps(text: &str, duration_secs: f32) -> f32 {
+    // 0秒字幕でも統計が壊れないよう下限を設ける
+    visible_chars(text) as f32 / duration_secs.max(0.1)
+}
+
+/// `duration_secs` の間に閾値内で表示できる最大文字数
+pub fn max_chars_for(duration_secs: f32, max_cps: f32) -> usize {
+    (duration_secs * max_cps).floor().max(1.0) as usize
+}
+
+/// 言語ごとの CPS 集計器
+pub struct CpsTracker {
+    lang: String,
+    threshold: f32,
+    samples: Vec<f32>,
+    compressed_acts: usize,
+}
+
+impl CpsTracker {
+    pub fn new(lang: &str, threshold: f32) -> Self {
+        Self {
+            lang: lang.to_string(),
+            threshold,
+            samples: Vec::new(),
+            compressed_acts: 0,
+        }
+    }
+
+    pub fn record(&mut self, cps: f32) {
+        self.samples.push(cps);
+    }
+
+    pub fn mark_compressed(&mut self) {
+        self.compressed_acts += 1;
+    }
+
+    pub fn finish(self) -> SubtitleCpsStats {
+        let cue_count = self.samples.len();
+        let max_cps = self.samples.iter().cloned().fold(0.0f32, f32::max);
+        let avg_cps = if cue_count == 0 { 0.0 } else { self.samples.iter().sum::<f32>() / cue_count as f32 };
+        let over_threshold = self.samples.iter().filter(|&&c| c > self.threshold).count();
+
+        SubtitleCpsStats {
+            lang: self.lang,
+            threshold: self.threshold,
+            max_cps,
+            avg_cps,
+            cue_count,
+            over_threshold,
+            compressed_acts: self.compressed_acts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cps_ignores_whitespace() {
+        assert_eq!(visible_chars("Hello world"), 10);
+        assert!((cps("Hello world", 2.0) - 5.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_max_chars_for_threshold() {
+        assert_eq!(max_chars_for(3.0, 8.0), 24);
+        // 極端に短い幕でも 0 文字指定にはしない
+        assert_eq!(max_chars_for(0.0, 8.0), 1);
+    }
+
+    #[test]
+    fn test_tracker_reports_violations() {
+        let mut tracker = CpsTracker::new("ja", 8.0);
+        tracker.record(6.0);
+        tracker.record(10.0);
+        tracker.mark_compressed();
+
+        let stats = tracker.finish();
+        assert_eq!(stats.cue_count, 2);
+        assert_eq!(stats.over_threshold, 1);
+        assert_eq!(stats.compressed_acts, 1);
+        assert!((stats.max_cps - 10.0).abs() < f32::EPSILON);
+        assert!((stats.avg_cps - 8.0).abs() < f32::EPSILON);
+    }
+}
@@ -6,6 +6,7 @@ use factory_core::traits::AgentAct;
 use factory_core::error::FactoryError;
 use bastion::fs_guard::Jail;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// 監視ポリシー
 #[derive(Debug, Clone)]
@@ -13,8 +14,8 @@ pub enum SupervisorPolicy {
     /// 失敗時に即座に停止 (Deny)
     #[allow(dead_code)]
     Strict,
-    /// 失敗をログに記録して継続試行 (Retry)
-    Retry { max_retries: usize },
+    /// 一時的な失敗 (`FactoryError::is_retryable`) を指数バックオフで再試行 (Retry)
+    Retry { max_retries: usize, base_backoff: Duration },
 }
 
 /// 統治機構（スーパーバイザー）
@@ -57,10 +58,16 @@ impl Supervisor {
 
                     match &self.policy {
                         SupervisorPolicy::Strict => return Err(e),
-                        SupervisorPolicy::Retry { max_retries } => {
+                        SupervisorPolicy::Retry { .. } if !e.is_retryable() => {
+                            tracing::error!("❌ Permanent failure. Not retrying.");
+                            return Err(e);
+                        }
+                        SupervisorPolicy::Retry { max_retries, base_backoff } => {
                             if retries < *max_retries {
+                                let delay = base_backoff.saturating_mul(1 << retries.min(16));
                                 retries += 1;
-                                tracing::warn!("🔄 Retrying act ({}/{})", retries, max_retries);
+                                tracing::warn!("🔄 Retrying act ({}/{}) in {:?}", retries, max_retries, delay);
+                                tokio::time::sleep(delay).await;
                                 continue;
                             } else {
                                 tracing::error!("❌ Max retries reached. Failing act.");
@@ -82,6 +89,7 @@ mod tests {
     struct MockActor {
         fail_count: std::sync::atomic::AtomicUsize,
         security_violation: bool,
+        permanent: bool,
     }
 
     #[async_trait]
@@ -95,8 +103,10 @@ mod tests {
             }
 
             let count = self.fail_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-            if count < 2 {
-                Err(FactoryError::Infrastructure { reason: "temporary failure".into() })
+            if self.permanent {
+                Err(FactoryError::ComfyWorkflowFailed { reason: "Missing [API_PROMPT] node".into() })
+            } else if count < 2 {
+                Err(FactoryError::Network { service: "ComfyUI".into(), reason: "temporary failure".into() })
             } else {
                 Ok("success".into())
             }
@@ -107,11 +117,12 @@ mod tests {
     async fn test_supervisor_retry_policy() {
         let dir = tempdir().unwrap();
         let jail = Arc::new(Jail::init(dir.path()).unwrap());
-        let supervisor = Supervisor::new(jail, SupervisorPolicy::Retry { max_retries: 3 });
+        let supervisor = Supervisor::new(jail, SupervisorPolicy::Retry { max_retries: 3, base_backoff: Duration::ZERO });
         
         let actor = MockActor {
             fail_count: std::sync::atomic::AtomicUsize::new(0),
             security_violation: false,
+            permanent: false,
         };
 
         let result = supervisor.enforce_act(&actor, ()).await;
@@ -124,14 +135,32 @@ mod tests {
     async fn test_supervisor_security_escalation() {
         let dir = tempdir().unwrap();
         let jail = Arc::new(Jail::init(dir.path()).unwrap());
-        let supervisor = Supervisor::new(jail, SupervisorPolicy::Retry { max_retries: 3 });
+        let supervisor = Supervisor::new(jail, SupervisorPolicy::Retry { max_retries: 3, base_backoff: Duration::ZERO });
         
         let actor = MockActor {
             fail_count: std::sync::atomic::AtomicUsize::new(0),
             security_violation: true,
+            permanent: false,
         };
 
         let result = supervisor.enforce_act(&actor, ()).await;
         assert!(matches!(result, Err(FactoryError::SecurityViolation { .. })));
     }
+
+    #[tokio::test]
+    async fn test_supervisor_does_not_retry_permanent_failures() {
+        let dir = tempdir().unwrap();
+        let jail = Arc::new(Jail::init(dir.path()).unwrap());
+        let supervisor = Supervisor::new(jail, SupervisorPolicy::Retry { max_retries: 3, base_backoff: Duration::ZERO });
+
+        let actor = MockActor {
+            fail_count: std::sync::atomic::AtomicUsize::new(0),
+            security_violation: false,
+            permanent: true,
+        };
+
+        let result = supervisor.enforce_act(&actor, ()).await;
+        assert!(matches!(result, Err(FactoryError::ComfyWorkflowFailed { .. })));
+        assert_eq!(actor.fail_count.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
 }
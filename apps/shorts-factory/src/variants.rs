@@ -0,0 +1,111 @@
+//! # Variants — 1 つの台本からの作り分け
+//!
+//! コンセプト (トレンド検索 + Gemini) は 1 回だけ作り、カメラワークと話速を変えた N 本を描き起こす。
+//! ComfyUI のシードは生成ごとにランダムなので、絵もバリエーションごとに変わる。
+//! 各バリエーションは `<親>_v<n>` プロジェクトで作り、納品物は `<export_dir>/<親>/` にまとめ、
+//! 親プロジェクトの `variants.json` にどれをどう変えたかを残す (公開する 1 本を選ぶため)。
+
+use crate::orchestrator::ProductionOrchestrator;
+use factory_core::contracts::{CustomStyle, VariantRecord, VariantSpec, WorkflowRequest};
+use factory_core::error::FactoryError;
+use factory_core::traits::AgentAct;
+use tracing::{info, warn};
+use tuning::StyleProfile;
+
+/// 1 回に作れる本数の上限 (GPU を長時間塞がない)
+pub const MAX_VARIANTS: usize = 6;
+
+/// 演出違いの型: (ズーム倍率, パン倍率, 話速)。4 本目以降は一巡ごとに話速を少しずつずらす
+const TWEAKS: [(f64, f64, f32); 3] = [(1.0, 1.0, 1.0), (1.4, 0.6, 1.1), (0.6, 1.5, 0.92)];
+
+/// `index` 本目 (1 始まり) の演出。1 本目はプリセット (と利用者の調整) のまま
+pub fn variant_tweak(index: usize, base: &StyleProfile, custom: Option<&CustomStyle>) -> (CustomStyle, Option<f32>) {
+    let mut style = custom.cloned().unwrap_or_default();
+    let i = index.saturating_sub(1);
+    let (zoom, pan, speed) = TWEAKS[i % TWEAKS.len()];
+    let speed = speed + 0.04 * (i / TWEAKS.len()) as f32;
+    if i == 0 {
+        return (style, None);
+    }
+    style.zoom_speed = Some(style.zoom_speed.unwrap_or(base.zoom_speed) * zoom);
+    style.pan_intensity = Some((style.pan_intensity.unwrap_or(base.pan_intensity) * pan).min(1.0));
+    (style, Some(speed))
+}
+
+/// 台本を 1 回作り、`count` 本のバリエーションを順に制作する
+pub async fn generate(
+    orchestrator: &ProductionOrchestrator,
+    jail: &bastion::fs_guard::Jail,
+    input: WorkflowRequest,
+    count: usize,
+) -> Result<Vec<VariantRecord>, FactoryError> {
+    let count = count.clamp(1, MAX_VARIANTS);
+    let set_id = input.remix_id.clone().unwrap_or_else(|| {
+        format!("{}_{}", input.category, chrono::Utc::now().format("%Y%m%d_%H%M%S"))
+    });
+    orchestrator.asset_manager.init_project(&set_id)?;
+    let concept = if input.skip_to_step.is_some() {
+        orchestrator.asset_manager.load_concept(&set_id)?
+    } else {
+        let cost = orchestrator.cost.for_job(None, Some(&set_id));
+        orchestrator.produce_concept(&input, &set_id, &cost).await?
+    };
+    let base_name = if input.style_name.is_empty() { &concept.style_profile } else { &input.style_name };
+    let base = orchestrator.style_manager.get_style(base_name);
+    info!("🎲 Variants: '{}' -> {} variant(s) of '{}'", set_id, count, concept.title);
+
+    let mut records = Vec::new();
+    for index in 1..=count {
+        let project_id = format!("{}_v{}", set_id, index);
+        let (custom_style, voice_speed) = variant_tweak(index, &base, input.custom_style.as_ref());
+        orchestrator.asset_manager.init_project(&project_id)?;
+        orchestrator.asset_manager.save_concept(&project_id, &concept)?;
+        let req = WorkflowRequest {
+            remix_id: Some(project_id.clone()),
+            // 台本は共有する (Gemini を呼ぶのは最初の 1 回だけ)
+            skip_to_step: Some("assets".to_string()),
+            custom_style: Some(custom_style.clone()),
+            variant: Some(VariantSpec { set_id: set_id.clone(), index, voice_speed }),
+            // シード指定があってもバリエーションごとに別の絵にする
+            seed: input.seed.map(|s| s.wrapping_add(index as u64)),
+            ..input.clone()
+        };
+        let (output_videos, error) = match orchestrator.execute(req, jail).await {
+            Ok(res) => (res.output_videos, None),
+            Err(e) => {
+                warn!("⚠️ Variants: {} failed: {}", project_id, e);
+                (Vec::new(), Some(e.to_string()))
+            }
+        };
+        records.push(VariantRecord { index, project_id, voice_speed, custom_style, output_videos, error });
+        orchestrator.asset_manager.save_variants(&set_id, &records)?;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_tweaks_keep_first_and_vary_the_rest() {
+        let base = StyleProfile { zoom_speed: 0.002, pan_intensity: 0.8, ..StyleProfile::default() };
+        let (first, speed) = variant_tweak(1, &base, None);
+        assert!(first.zoom_speed.is_none());
+        assert!(speed.is_none());
+
+        let (second, speed) = variant_tweak(2, &base, None);
+        assert!((second.zoom_speed.unwrap() - 0.0028).abs() < 1e-9);
+        assert_eq!(speed, Some(1.1));
+
+        // Pan stays within 0.0 - 1.0 and a user override is the base for scaling
+        let custom = CustomStyle { pan_intensity: Some(0.9), bgm_volume: Some(0.3), ..Default::default() };
+        let (third, _) = variant_tweak(3, &base, Some(&custom));
+        assert_eq!(third.pan_intensity, Some(1.0));
+        assert_eq!(third.bgm_volume, Some(0.3));
+
+        // A second round repeats the camera work with a different voice speed
+        let (_, speed) = variant_tweak(5, &base, None);
+        assert!((speed.unwrap() - 1.14).abs() < 1e-6);
+    }
+}
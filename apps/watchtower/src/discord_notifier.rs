@@ -0,0 +1,89 @@
+//! Discord の通知先 (`shared::watchtower::Notifier` の実装)
+//!
+//! 完了通知は評価用のリアクション付きの embed (プレビュー動画を添付)、承認依頼はボタン付きのメッセージ。
+//! 押されたボタンは main の InteractionCreate から `ApprovalResponse` として Core に戻る。
+
+use std::sync::Arc;
+
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateAttachment, CreateButton, CreateEmbed, CreateEmbedFooter, CreateMessage, ReactionType};
+use shared::watchtower::{approval_action_id, ApprovalPrompt, CompletedTask, ControlCommand, Notifier};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// リアクションが無ければ中立 (0) と評価するまでの待ち
+const LAZY_RATING_DELAY: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+pub struct DiscordNotifier {
+    pub http: Arc<serenity::Http>,
+    pub channel: ChannelId,
+    /// 自動評価を Core へ送る
+    pub cmd_tx: mpsc::Sender<ControlCommand>,
+}
+
+#[async_trait::async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn task_completed(&self, task: &CompletedTask) -> anyhow::Result<()> {
+        // W-3: Rich embed notification for completed jobs
+        let is_success = task.succeeded();
+        let embed = CreateEmbed::new()
+            .title(if is_success { "✅ Job Completed" } else { "❌ Job Failed" })
+            .field("Topic", &task.topic, true)
+            .field("Style", &task.style, true)
+            .field("Job ID", &task.job_id, false)
+            .field("Result", &task.result, false)
+            .color(if is_success { 0x00FF41 } else { 0xFF003C })
+            .footer(CreateEmbedFooter::new("React 🔥 = Best (+1) | 🗑️ = Trash (-1) | No reaction = Neutral (0) after 30min"));
+        let mut msg = CreateMessage::new().embed(embed.clone());
+        if let Some(path) = &task.preview_path {
+            match CreateAttachment::path(path).await {
+                Ok(file) => msg = msg.add_file(file),
+                Err(e) => warn!("⚠️ Could not read the preview {}: {}", path, e),
+            }
+        }
+        // 添付が弾かれたら (サーバーの上限など) 文字だけで送り直す
+        let sent = match self.channel.send_message(&self.http, msg).await {
+            Err(e) if task.preview_path.is_some() => {
+                warn!("⚠️ Preview upload rejected, sending text only: {}", e);
+                self.channel.send_message(&self.http, CreateMessage::new().embed(embed)).await?
+            }
+            other => other?,
+        };
+
+        // Add reaction buttons
+        let _ = sent.react(&self.http, ReactionType::Unicode("🔥".to_string())).await;
+        let _ = sent.react(&self.http, ReactionType::Unicode("🗑️".to_string())).await;
+
+        // Lazy Distillation: 30 分リアクションが無ければ中立 (0) と評価する
+        let cmd_tx = self.cmd_tx.clone();
+        let job_id = task.job_id.clone();
+        let msg_id = sent.id;
+        let http = self.http.clone();
+        let channel = self.channel;
+        tokio::spawn(async move {
+            tokio::time::sleep(LAZY_RATING_DELAY).await;
+            // Check if human has reacted (fetch message, look for non-bot reactions)
+            if let Ok(msg) = channel.message(&http, msg_id).await {
+                let has_human_reaction = msg.reactions.iter().any(|r| r.count > 1); // >1 means someone besides bot reacted
+                if !has_human_reaction {
+                    let _ = cmd_tx.send(ControlCommand::SetCreativeRating { job_id: job_id.clone(), rating: 0 }).await;
+                    let _ = channel.say(&http, format!("🧘 **Lazy Distillation**: Job {} auto-rated 0 (neutral). No human feedback received.", job_id)).await;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn approval_request(&self, prompt: &ApprovalPrompt) -> anyhow::Result<()> {
+        let msg = CreateMessage::new()
+            .content(format!("🚨 **Approval Required**\n{}", prompt.description))
+            .button(CreateButton::new(approval_action_id(prompt.id, true)).label("✅ Approve").style(serenity::ButtonStyle::Success))
+            .button(CreateButton::new(approval_action_id(prompt.id, false)).label("❌ Reject").style(serenity::ButtonStyle::Danger));
+        self.channel.send_message(&self.http, msg).await?;
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 use poise::serenity_prelude as serenity;
 use tracing::{info, warn, error};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex};
-use shared::watchtower::{ControlCommand, CoreEvent, SystemStatus, LogEntry};
+use shared::watchtower::{approval_action_id, encode_file_frames, match_style, notify_all, parse_approval_action, ControlCommand, CoreEvent, Notifier, StyleOption, SystemStatus, LogEntry, MAX_FILE_TRANSFER_BYTES};
 use tokio::net::UnixStream;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
 use futures::{SinkExt, StreamExt};
@@ -11,35 +13,64 @@ use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
 use anyhow::Context as _; // Import trait for .context() method
 
-use serenity::all::{ChannelId, CreateMessage, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, CreateEmbed, ReactionType};
+mod discord_notifier;
+mod status_board;
+use discord_notifier::DiscordNotifier;
+
+use serenity::all::{ChannelId, CreateMessage, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, CreateEmbed, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption};
 
 struct Data {
     cmd_tx: mpsc::Sender<ControlCommand>,
+    /// ファイル転送のフレーム (コマンドより先に書き出される)
+    file_tx: mpsc::Sender<Vec<u8>>,
     latest_status: Arc<Mutex<Option<SystemStatus>>>,
     log_channel_id: ChannelId,
     command_channel_id: ChannelId,
     chat_channel_id: ChannelId,
+    /// スタイル選択を待っている `/generate` (キーは選択メニューの控え)
+    pending_generates: PendingGenerates,
+}
+
+/// スタイルが決まる前の `/generate`
+struct PendingGenerate {
+    category: String,
+    topic: String,
+    style: Option<String>,
+    user: serenity::User,
+    created_at: Instant,
+}
+
+type PendingGenerates = Arc<Mutex<HashMap<String, PendingGenerate>>>;
+
+/// スタイル選択メニューの有効期限 (選ばれずに放置された控えは次に積むときに捨てる)
+const PENDING_GENERATE_TTL: Duration = Duration::from_secs(15 * 60);
+
+impl PendingGenerate {
+    fn expired(&self) -> bool {
+        self.created_at.elapsed() > PENDING_GENERATE_TTL
+    }
+}
+
+/// スタイル選択待ちの `/generate` を控える (期限切れの控えはここで掃除する)
+async fn hold_pending(pending: &PendingGenerates, request: String, generate: PendingGenerate) {
+    let mut pending = pending.lock().await;
+    pending.retain(|_, p| !p.expired());
+    pending.insert(request, generate);
 }
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type PoiseContext<'a> = poise::Context<'a, Data, Error>;
 
+/// 操作したユーザーを添える (Core の監査ログに actor として残る)
+fn as_actor(user: &serenity::User, command: ControlCommand) -> ControlCommand {
+    ControlCommand::AsActor { actor: format!("{} ({})", user.name, user.id), command: Box::new(command) }
+}
+
 /// Checking Core status
 #[poise::command(slash_command)]
 async fn status(ctx: PoiseContext<'_>) -> Result<(), Error> {
-    let status_guard = ctx.data().latest_status.lock().await;
-    match &*status_guard {
-        Some(s) => {
-            let msg = format!(
-                "🟢 **System Online**\nCPU: {:.1}%\nRAM: {}MB\nVRAM: {}MB\nJob: {:?}",
-                s.cpu_usage, s.memory_used_mb, s.vram_used_mb, s.active_job_id
-            );
-            ctx.say(msg).await?;
-        }
-        None => {
-            ctx.say("🔴 **Core Unreachable** (No Heartbeat)").await?;
-        }
-    }
+    let status = ctx.data().latest_status.lock().await.clone();
+    ctx.send(poise::CreateReply::default().embed(status_board::status_embed(status.as_ref()))).await?;
     Ok(())
 }
 
@@ -54,8 +85,8 @@ async fn nuke(
     if !force {
         // Stage 1: Try graceful shutdown via UDS
         ctx.say("⚠️ **Stage 1**: Sending graceful shutdown via UDS...").await?;
-        let cmd = ControlCommand::StopGracefully;
-        if let Err(_) = ctx.data().cmd_tx.send(cmd).await {
+        let cmd = as_actor(ctx.author(), ControlCommand::StopGracefully);
+        if ctx.data().cmd_tx.send(cmd).await.is_err() {
             ctx.say("❌ UDS channel closed. Escalating to Stage 2 (SIGKILL)...").await?;
         } else {
             // Wait 5 seconds for graceful shutdown
@@ -110,14 +141,322 @@ async fn generate(
     ctx: PoiseContext<'_>,
     #[description = "Category (e.g. tech, nature)"] category: String,
     #[description = "Topic/Theme"] topic: String,
+    #[description = "Style Preset (omit to pick from styles.toml)"] style: Option<String>,
+) -> Result<(), Error> {
+    // スタイル名は Core の styles.toml と照合してから送る (一致しなければ選択メニューを出す)
+    let request = uuid::Uuid::new_v4().simple().to_string();
+    ctx.say(format!("🎨 Checking styles for **{}** ({})...", topic, category)).await?;
+    let pending = PendingGenerate { category, topic, style, user: ctx.author().clone(), created_at: Instant::now() };
+    hold_pending(&ctx.data().pending_generates, request.clone(), pending).await;
+    let cmd = ControlCommand::ListStyles { channel_id: ctx.channel_id().get(), request: Some(request.clone()) };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.data().pending_generates.lock().await.remove(&request);
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// スタイルの決まった `/generate` を Core に送り、結果の文言を返す
+async fn dispatch_generate(cmd_tx: &mpsc::Sender<ControlCommand>, pending: PendingGenerate, style: Option<String>) -> String {
+    let summary = format!("**{}** ({}) with style `{}`", pending.topic, pending.category, style.as_deref().unwrap_or("auto"));
+    let cmd = as_actor(&pending.user, ControlCommand::Generate { category: pending.category, topic: pending.topic, style });
+    match cmd_tx.send(cmd).await {
+        Ok(()) => format!("🚀 Dispatching Generate Request: {}\n✅ Request queued for Core.", summary),
+        Err(e) => format!("❌ Failed to send command to Core loop: {}", e),
+    }
+}
+
+/// `/generate` のスタイル選択メニュー (Discord の上限 25 件まで)
+fn style_menu(request: &str, styles: &[StyleOption]) -> CreateSelectMenu {
+    let options = styles.iter().take(25).map(|s| {
+        let label: String = s.name.chars().take(100).collect();
+        let option = CreateSelectMenuOption::new(label, &s.name);
+        if s.description.trim().is_empty() {
+            option
+        } else {
+            option.description(s.description.chars().take(100).collect::<String>())
+        }
+    }).collect();
+    CreateSelectMenu::new(format!("generate_style_{}", request), CreateSelectMenuKind::String { options })
+        .placeholder("Choose a style")
+}
+
+/// Re-render a past job (or workspace project), optionally resuming from a step
+#[poise::command(slash_command)]
+async fn remix(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID (or workspace project ID)"] job_id: String,
+    #[description = "Style Preset (default: the job's style)"] style: Option<String>,
+    #[description = "Resume from this step using the saved concept (e.g. voice, visual)"] step: Option<String>,
+) -> Result<(), Error> {
+    ctx.say(format!("🔁 Dispatching Remix Request: `{}`", job_id)).await?;
+    let cmd = as_actor(ctx.author(), ControlCommand::Remix { job_id, style, step, channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Queue a multi-part series (each part waits for the previous one)
+#[poise::command(slash_command)]
+async fn generate_series(
+    ctx: PoiseContext<'_>,
+    #[description = "Topics in order, separated by '|' (e.g. Part 1 | Part 2)"] topics: String,
     #[description = "Style Preset"] style: Option<String>,
+    #[description = "Channel (brand) from [channels]; default if omitted"] channel: Option<String>,
 ) -> Result<(), Error> {
-    ctx.say(format!("🚀 Dispatching Generate Request: **{}** ({})", topic, category)).await?;
-    let cmd = ControlCommand::Generate { category, topic, style };
+    let topics: Vec<String> = topics.split('|').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    if topics.is_empty() {
+        ctx.say("❌ Please provide at least one topic.").await?;
+        return Ok(());
+    }
+    ctx.say(format!("🚀 Dispatching Series Request: {} parts", topics.len())).await?;
+    let cmd = as_actor(ctx.author(), ControlCommand::GenerateSeries { topics, style, channel, channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Schedule jobs to run at a future time (e.g. a week of episodes aligned to release windows)
+#[poise::command(slash_command)]
+#[allow(clippy::too_many_arguments)]
+async fn schedule(
+    ctx: PoiseContext<'_>,
+    #[description = "First run time, e.g. 2025-01-03T07:00 (server local time) or with an offset"] at: String,
+    #[description = "Topic (inserted into the series topic template when a series is given)"] topic: Option<String>,
+    #[description = "Series name: each job becomes the next episode"] series: Option<String>,
+    #[description = "Style Preset"] style: Option<String>,
+    #[description = "Number of jobs (default 1)"] count: Option<u32>,
+    #[description = "Hours between jobs (default 24)"] every_hours: Option<i64>,
+    #[description = "Channel (brand) from [channels]; default if omitted"] channel: Option<String>,
+) -> Result<(), Error> {
+    if topic.is_none() && series.is_none() {
+        ctx.say("❌ Please provide a topic or a series.").await?;
+        return Ok(());
+    }
+    ctx.say(format!("🗓️ Scheduling from **{}**...", at)).await?;
+    let cmd = ControlCommand::Schedule {
+        at,
+        topic,
+        series,
+        style,
+        count: count.unwrap_or(1),
+        every_hours: every_hours.unwrap_or(24),
+        channel,
+        channel_id: ctx.channel_id().get(),
+    };
+    let cmd = as_actor(ctx.author(), cmd);
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Explain why a job exists (Samsara provenance)
+#[poise::command(slash_command)]
+async fn why(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID"] job_id: String,
+) -> Result<(), Error> {
+    ctx.say(format!("🔍 Looking into `{}`...", job_id)).await?;
+    let cmd = ControlCommand::Why { job_id, channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Pull a published video from the platform (private by default)
+#[poise::command(slash_command, owners_only)]
+async fn takedown(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID"] job_id: String,
+    #[description = "Delete the video instead of making it private"] delete: Option<bool>,
+) -> Result<(), Error> {
+    let delete = delete.unwrap_or(false);
+    ctx.say(format!("🚫 Requesting takedown of `{}` ({})...", job_id, if delete { "delete" } else { "private" })).await?;
+    let cmd = as_actor(ctx.author(), ControlCommand::Takedown { job_id, delete, channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// List recent failed jobs, or re-queue one by ID
+#[poise::command(slash_command, owners_only)]
+async fn retry(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID to re-queue (omit to list recent failures)"] job_id: Option<String>,
+) -> Result<(), Error> {
+    match &job_id {
+        Some(id) => ctx.say(format!("🔁 Re-queueing `{}`...", id)).await?,
+        None => ctx.say("📋 Fetching recent failures...").await?,
+    };
+    let cmd = as_actor(ctx.author(), ControlCommand::Retry { job_id, channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Jobs that are running or waiting in the queue
+#[poise::command(slash_command)]
+async fn queue(
+    ctx: PoiseContext<'_>,
+    #[description = "Page (default 1)"] page: Option<u32>,
+) -> Result<(), Error> {
+    ctx.say("📋 Fetching the job queue...").await?;
+    let cmd = ControlCommand::ListJobs { page: page.unwrap_or(1), channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Full detail of one job: stage, error and outputs
+#[poise::command(slash_command)]
+async fn job(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID"] job_id: String,
+) -> Result<(), Error> {
+    ctx.say(format!("🔎 Looking up `{}`...", job_id)).await?;
+    let cmd = ControlCommand::GetJob { job_id, channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Production digest: completions, failure rate, render time, karma and top videos
+#[poise::command(slash_command)]
+async fn report(
+    ctx: PoiseContext<'_>,
+    #[description = "Days to cover (default from [cron] production_report_days)"] days: Option<u32>,
+) -> Result<(), Error> {
+    ctx.say("📈 Compiling the production report...").await?;
+    let cmd = ControlCommand::Report { days, channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Stop picking up new jobs (the running job finishes)
+#[poise::command(slash_command)]
+async fn pause(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    ctx.say("⏸️ Pausing the autonomous engine...").await?;
+    let cmd = as_actor(ctx.author(), ControlCommand::Pause { channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Resume picking up queued jobs
+#[poise::command(slash_command)]
+async fn resume(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    ctx.say("▶️ Resuming the autonomous engine...").await?;
+    let cmd = as_actor(ctx.author(), ControlCommand::Resume { channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Recent runs of the scheduled jobs (Samsara, Sentinel, Oracle, ...)
+#[poise::command(slash_command)]
+async fn cron(
+    ctx: PoiseContext<'_>,
+    #[description = "Job name, e.g. samsara or sentinel (omit for all jobs)"] job: Option<String>,
+) -> Result<(), Error> {
+    ctx.say("⏰ Fetching cron history...").await?;
+    let cmd = ControlCommand::CronRuns { job, channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// What the videos cost: one job, or the last N days
+#[poise::command(slash_command)]
+async fn costs(
+    ctx: PoiseContext<'_>,
+    #[description = "Job ID (omit for a summary of recent days)"] job_id: Option<String>,
+    #[description = "Days to summarize (default 7)"] days: Option<u32>,
+) -> Result<(), Error> {
+    ctx.say("💸 Tallying costs...").await?;
+    let cmd = ControlCommand::Costs { job_id, days, channel_id: ctx.channel_id().get() };
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Forget her chat history and memory summary for this channel (asks for confirmation)
+#[poise::command(slash_command, owners_only)]
+async fn forget(ctx: PoiseContext<'_>) -> Result<(), Error> {
+    // ボタンは依頼者本人だけが押せるよう custom_id にユーザー ID を含める
+    let suffix = format!("{}_{}", ctx.channel_id().get(), ctx.author().id.get());
+    let reply = poise::CreateReply::default()
+        .content("🧽 **Forget this channel?**
+This deletes the chat history and memory summary for this channel. It cannot be undone.")
+        .components(vec![serenity::CreateActionRow::Buttons(vec![
+            CreateButton::new(format!("forget_confirm_{}", suffix)).label("🧽 Forget").style(serenity::ButtonStyle::Danger),
+            CreateButton::new(format!("forget_cancel_{}", suffix)).label("Cancel").style(serenity::ButtonStyle::Secondary),
+        ])]);
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Make a short from an attached picture (reference image) or audio clip (narration)
+#[poise::command(slash_command, owners_only)]
+async fn attach(
+    ctx: PoiseContext<'_>,
+    #[description = "Image (png/jpg/webp) or audio (wav/mp3/m4a)"] file: serenity::Attachment,
+    #[description = "What the short is about"] topic: String,
+    #[description = "Style Preset"] style: Option<String>,
+    #[description = "Narration language for audio (default ja)"] lang: Option<String>,
+) -> Result<(), Error> {
+    if file.size as usize > MAX_FILE_TRANSFER_BYTES {
+        ctx.say(format!("❌ `{}` is too large (max {} MB).", file.filename, MAX_FILE_TRANSFER_BYTES / 1_048_576)).await?;
+        return Ok(());
+    }
+    ctx.say(format!("📎 Sending `{}` to Core...", file.filename)).await?;
+    let data = file.download().await?;
+    let transfer_id = uuid::Uuid::new_v4();
+    for frame in encode_file_frames(transfer_id, &file.filename, &data) {
+        if let Err(e) = ctx.data().file_tx.send(frame).await {
+            ctx.say(format!("❌ Failed to send the file to Core: {}", e)).await?;
+            return Ok(());
+        }
+    }
+    let cmd = as_actor(ctx.author(), ControlCommand::GenerateFromAttachment { transfer_id, topic, style, lang, channel_id: ctx.channel_id().get() });
+    if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
+        ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
+    }
+    Ok(())
+}
+
+/// Turn a render dropped into the watch folder into a job (omit the ID to list pending renders)
+#[poise::command(slash_command, owners_only)]
+async fn ingest(
+    ctx: PoiseContext<'_>,
+    #[description = "Ingest ID from the watch folder notice"] id: Option<String>,
+    #[description = "Topic for narration and metadata"] topic: Option<String>,
+    #[description = "Style Preset"] style: Option<String>,
+) -> Result<(), Error> {
+    if id.is_some() && topic.is_none() {
+        ctx.say("❌ Please provide a topic for the render.").await?;
+        return Ok(());
+    }
+    match &id {
+        Some(id) => ctx.say(format!("🎞️ Ingesting `{}`...", id)).await?,
+        None => ctx.say("📋 Fetching pending renders...").await?,
+    };
+    let cmd = as_actor(ctx.author(), ControlCommand::Ingest { ingest_id: id, topic, style, channel_id: ctx.channel_id().get() });
     if let Err(e) = ctx.data().cmd_tx.send(cmd).await {
         ctx.say(format!("❌ Failed to send command to Core loop: {}", e)).await?;
-    } else {
-        ctx.say("✅ Request queued for Core.").await?;
     }
     Ok(())
 }
@@ -185,6 +524,7 @@ async fn main() -> anyhow::Result<()> {
     let latest_status = Arc::new(Mutex::new(None));
     let (event_tx, mut event_rx) = mpsc::channel::<CoreEvent>(100);
     let (cmd_tx, mut cmd_rx) = mpsc::channel::<ControlCommand>(100);
+    let (file_tx, mut file_rx) = mpsc::channel::<Vec<u8>>(16);
 
     // === W-1 & W-4: UDS Loop with Reconnection Visibility and Heartbeat Timeout ===
     let status_clone = latest_status.clone();
@@ -208,6 +548,8 @@ async fn main() -> anyhow::Result<()> {
                     let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
                     loop {
                         tokio::select! {
+                            // ファイルのチャンクを、それを参照するコマンドより先に送り切る
+                            biased;
                             // 1. Core -> Bot
                             msg = framed.next() => {
                                 match msg {
@@ -227,7 +569,13 @@ async fn main() -> anyhow::Result<()> {
                                     _ => break, // Reconnect
                                 }
                             }
-                            // 2. Bot -> Core
+                            // 2. Bot -> Core (file chunks first)
+                            Some(frame) = file_rx.recv() => {
+                                if let Err(e) = framed.send(Bytes::from(frame)).await {
+                                    error!("❌ UDS Write Error: {}", e);
+                                    break;
+                                }
+                            }
                             Some(cmd) = cmd_rx.recv() => {
                                 let json = serde_json::to_vec(&cmd).unwrap_or_default();
                                 if let Err(e) = framed.send(Bytes::from(json)).await {
@@ -291,8 +639,8 @@ async fn main() -> anyhow::Result<()> {
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![status(), nuke(), stats(), generate(), talk(), command()],
-            event_handler: |ctx, event, _framework, data| {
+            commands: vec![status(), nuke(), stats(), generate(), remix(), generate_series(), schedule(), why(), takedown(), retry(), queue(), job(), report(), pause(), resume(), cron(), costs(), forget(), attach(), ingest(), talk(), command()],
+            event_handler: |ctx, event, framework, data| {
                 Box::pin(async move {
                     // Handle normal messages in specific channels (Chat/Command routing)
                     if let serenity::FullEvent::Message { new_message } = event {
@@ -320,15 +668,118 @@ async fn main() -> anyhow::Result<()> {
                     // Handle approval buttons
                     if let serenity::FullEvent::InteractionCreate { interaction } = event {
                         if let Some(it) = interaction.as_message_component() {
-                            if it.data.custom_id.starts_with("approve_") || it.data.custom_id.starts_with("reject_") {
-                                let approved = it.data.custom_id.starts_with("approve_");
-                                let uuid_str = it.data.custom_id.split('_').nth(1).unwrap_or("");
-                                if let Ok(tid) = uuid::Uuid::parse_str(uuid_str) {
-                                    let cmd = ControlCommand::ApprovalResponse { transition_id: tid, approved };
+                            if let Some((tid, approved)) = parse_approval_action(&it.data.custom_id) {
+                                let cmd = as_actor(&it.user, ControlCommand::ApprovalResponse { transition_id: tid, approved });
+                                let _ = data.cmd_tx.send(cmd).await;
+                                let _ = it.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(
+                                    CreateInteractionResponseMessage::new()
+                                        .content(format!("{} **{}**", if approved { "✅ Approved" } else { "❌ Rejected" }, tid))
+                                        .components(vec![])
+                                )).await;
+                            }
+                        }
+                    }
+
+                    // Handle dead-letter retry buttons
+                    if let serenity::FullEvent::InteractionCreate { interaction } = event {
+                        if let Some(it) = interaction.as_message_component() {
+                            if let Some(job_id) = it.data.custom_id.strip_prefix("retry_") {
+                                // /retry と同じく owner だけが再投入できる
+                                let response = if !framework.options.owners.contains(&it.user.id) {
+                                    CreateInteractionResponseMessage::new()
+                                        .content("❌ Only bot owners can retry jobs.")
+                                        .ephemeral(true)
+                                } else {
+                                    let cmd = as_actor(&it.user, ControlCommand::Retry { job_id: Some(job_id.to_string()), channel_id: it.channel_id.get() });
                                     let _ = data.cmd_tx.send(cmd).await;
+                                    CreateInteractionResponseMessage::new()
+                                        .content(format!("🔁 Re-queueing `{}`...", job_id))
+                                };
+                                let _ = it.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await;
+                            }
+                        }
+                    }
+
+                    // Handle /queue page buttons
+                    if let serenity::FullEvent::InteractionCreate { interaction } = event {
+                        if let Some(it) = interaction.as_message_component() {
+                            if let Some(page) = it.data.custom_id.strip_prefix("queue_page_").and_then(|p| p.parse::<u32>().ok()) {
+                                let _ = data.cmd_tx.send(ControlCommand::ListJobs { page, channel_id: it.channel_id.get() }).await;
+                                let _ = it.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(
+                                    CreateInteractionResponseMessage::new().components(vec![])
+                                )).await;
+                            }
+                        }
+                    }
+
+                    // Handle /generate style menus
+                    if let serenity::FullEvent::InteractionCreate { interaction } = event {
+                        if let Some(it) = interaction.as_message_component() {
+                            if let Some(request) = it.data.custom_id.strip_prefix("generate_style_") {
+                                let style = match &it.data.kind {
+                                    serenity::ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+                                    _ => None,
+                                };
+                                let pending = {
+                                    let mut pending = data.pending_generates.lock().await;
+                                    match pending.get(request) {
+                                        Some(p) if p.expired() => {
+                                            pending.remove(request);
+                                            Ok(None)
+                                        }
+                                        Some(p) if p.user.id != it.user.id => Err(()),
+                                        Some(_) => Ok(pending.remove(request)),
+                                        None => Ok(None),
+                                    }
+                                };
+                                let response = match (pending, style) {
+                                    (Err(()), _) => CreateInteractionResponse::Message(
+                                        CreateInteractionResponseMessage::new()
+                                            .content("❌ Only the person who ran /generate can pick its style.")
+                                            .ephemeral(true)
+                                    ),
+                                    (Ok(Some(pending)), Some(style)) => CreateInteractionResponse::UpdateMessage(
+                                        CreateInteractionResponseMessage::new()
+                                            .content(dispatch_generate(&data.cmd_tx, pending, Some(style)).await)
+                                            .components(vec![])
+                                    ),
+                                    _ => CreateInteractionResponse::UpdateMessage(
+                                        CreateInteractionResponseMessage::new()
+                                            .content("⌛ This style picker has expired. Run /generate again.")
+                                            .components(vec![])
+                                    ),
+                                };
+                                let _ = it.create_response(&ctx.http, response).await;
+                            }
+                        }
+                    }
+
+                    // Handle /forget confirmation buttons
+                    if let serenity::FullEvent::InteractionCreate { interaction } = event {
+                        if let Some(it) = interaction.as_message_component() {
+                            let confirm = it.data.custom_id.strip_prefix("forget_confirm_");
+                            if let Some(rest) = confirm.or_else(|| it.data.custom_id.strip_prefix("forget_cancel_")) {
+                                let mut parts = rest.split('_');
+                                let channel_id = parts.next().and_then(|c| c.parse::<u64>().ok());
+                                let owner = parts.next().and_then(|u| u.parse::<u64>().ok());
+                                if owner != Some(it.user.id.get()) {
+                                    let _ = it.create_response(&ctx.http, CreateInteractionResponse::Message(
+                                        CreateInteractionResponseMessage::new()
+                                            .content("❌ Only the person who ran /forget can confirm it.")
+                                            .ephemeral(true)
+                                    )).await;
+                                } else if let (Some(channel_id), Some(_)) = (channel_id, confirm) {
+                                    let requested_by = format!("{} ({})", it.user.name, it.user.id);
+                                    let _ = data.cmd_tx.send(as_actor(&it.user, ControlCommand::ForgetMemory { channel_id, requested_by })).await;
                                     let _ = it.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(
                                         CreateInteractionResponseMessage::new()
-                                            .content(format!("{} **{}**", if approved { "✅ Approved" } else { "❌ Rejected" }, tid))
+                                            .content("🧽 Forgetting this channel...")
+                                            .components(vec![])
+                                    )).await;
+                                } else {
+                                    let _ = it.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(
+                                        CreateInteractionResponseMessage::new()
+                                            .content("↩️ Nothing was forgotten.")
                                             .components(vec![])
                                     )).await;
                                 }
@@ -353,7 +804,12 @@ async fn main() -> anyhow::Result<()> {
                                         // Extract job_id from the "Job ID" field
                                         if let Some(field) = embed.fields.iter().find(|f| f.name == "Job ID") {
                                             let job_id = field.value.clone();
-                                            let _ = data.cmd_tx.send(ControlCommand::SetCreativeRating { job_id: job_id.clone(), rating: r }).await;
+                                            let cmd = ControlCommand::SetCreativeRating { job_id: job_id.clone(), rating: r };
+                                            let cmd = match add_reaction.user(&ctx.http).await {
+                                                Ok(user) => as_actor(&user, cmd),
+                                                Err(_) => cmd,
+                                            };
+                                            let _ = data.cmd_tx.send(cmd).await;
                                             let _ = add_reaction.channel_id.say(&ctx.http, format!("🧘 **Karma Received**: Job {} rated {} by human.", job_id, if r > 0 { "🔥 (+1)" } else { "🗑️ (-1)" })).await;
                                         }
                                     }
@@ -369,13 +825,24 @@ async fn main() -> anyhow::Result<()> {
         })
         .setup(move |ctx, _ready, framework| {
             Box::pin(async move {
-                let cmd_tx_clone = cmd_tx.clone();
+                let discord = DiscordNotifier { http: ctx.http.clone(), channel: ChannelId::new(log_channel_id), cmd_tx: cmd_tx.clone() };
+                let notifiers: Vec<Arc<dyn Notifier>> = vec![Arc::new(discord)];
+                // ログチャンネルにピン留めしたライブのステータスボード (DISCORD_STATUS_BOARD=off で無効)
+                let board_enabled = !std::env::var("DISCORD_STATUS_BOARD").is_ok_and(|v| v.eq_ignore_ascii_case("off"));
+                if board_enabled && log_channel_id != 0 {
+                    tokio::spawn(status_board::run(ctx.http.clone(), ChannelId::new(log_channel_id), latest_status.clone()));
+                }
+                let pending_generates: PendingGenerates = Arc::new(Mutex::new(HashMap::new()));
+                let generate_tx = cmd_tx.clone();
+                let generate_pending = pending_generates.clone();
                 let data = Data { 
                     cmd_tx, 
+                    file_tx,
                     latest_status, 
                     log_channel_id: ChannelId::new(log_channel_id),
                     command_channel_id: ChannelId::new(command_channel_id),
                     chat_channel_id: ChannelId::new(chat_channel_id),
+                    pending_generates,
                 };
                 
                 // Event Forwarder with Throttling + System Alert Channel
@@ -394,49 +861,8 @@ async fn main() -> anyhow::Result<()> {
                                             flush_logs(&mut buffer, log_chan, &http).await;
                                         }
                                     }
-                                    CoreEvent::ApprovalRequest { transition_id, description } => {
-                                        let msg = CreateMessage::new()
-                                            .content(format!("🚨 **Approval Required**\n{}", description))
-                                            .button(CreateButton::new(format!("approve_{}", transition_id)).label("✅ Approve").style(serenity::ButtonStyle::Success))
-                                            .button(CreateButton::new(format!("reject_{}", transition_id)).label("❌ Reject").style(serenity::ButtonStyle::Danger));
-                                        let _ = log_chan.send_message(&http, msg).await;
-                                    }
-                                    CoreEvent::TaskCompleted { job_id, result, topic, style, .. } => {
-                                        // W-3: Rich embed notification for completed jobs
-                                        let is_success = result.to_lowercase().contains("success") || result.to_lowercase().contains("completed");
-                                        let embed = CreateEmbed::new()
-                                            .title(if is_success { "✅ Job Completed" } else { "❌ Job Failed" })
-                                            .field("Topic", &topic, true)
-                                            .field("Style", &style, true)
-                                            .field("Job ID", &job_id, false)
-                                            .field("Result", &result, false)
-                                            .color(if is_success { 0x00FF41 } else { 0xFF003C })
-                                            .footer(serenity::all::CreateEmbedFooter::new("React 🔥 = Best (+1) | 🗑️ = Trash (-1) | No reaction = Neutral (0) after 30min"));
-                                        let msg = CreateMessage::new().embed(embed);
-                                        if let Ok(sent) = log_chan.send_message(&http, msg).await {
-                                            // Add reaction buttons
-                                            let _ = sent.react(&http, ReactionType::Unicode("🔥".to_string())).await;
-                                            let _ = sent.react(&http, ReactionType::Unicode("🗑️".to_string())).await;
-
-                                            // Lazy Distillation: 30-minute timer for default positive
-                                            let cmd_tx_lazy = cmd_tx_clone.clone();
-                                            let job_id_lazy = job_id.clone();
-                                            let msg_id = sent.id;
-                                            let http_lazy = http.clone();
-                                            let chan_lazy = log_chan;
-                                            tokio::spawn(async move {
-                                                tokio::time::sleep(tokio::time::Duration::from_secs(30 * 60)).await;
-                                                // Check if human has reacted (fetch message, look for non-bot reactions)
-                                                if let Ok(msg) = chan_lazy.message(&http_lazy, msg_id).await {
-                                                    let has_human_reaction = msg.reactions.iter().any(|r| r.count > 1); // >1 means someone besides bot reacted
-                                                    if !has_human_reaction {
-                                                        // Default Positive: no reaction = neutral (0)
-                                                        let _ = cmd_tx_lazy.send(ControlCommand::SetCreativeRating { job_id: job_id_lazy, rating: 0 }).await;
-                                                        let _ = chan_lazy.say(&http_lazy, format!("🧘 **Lazy Distillation**: Job {} auto-rated 0 (neutral). No human feedback received.", msg_id)).await;
-                                                    }
-                                                }
-                                            });
-                                        }
+                                    event @ (CoreEvent::ApprovalRequest { .. } | CoreEvent::TaskCompleted { .. }) => {
+                                        notify_all(&notifiers, &event).await;
                                     }
                                     CoreEvent::ChatResponse { response, channel_id } => {
                                         let chan = ChannelId::new(channel_id);
@@ -451,6 +877,212 @@ async fn main() -> anyhow::Result<()> {
                                         };
                                         let _ = target_chan.say(&http, message).await;
                                     }
+                                    CoreEvent::SidecarAlert { name, message, flapping } => {
+                                        let icon = if flapping { "🔁" } else { "🩺" };
+                                        let _ = log_chan.say(&http, format!("{} **Sidecar `{}`**: {}", icon, name, message)).await;
+                                    }
+                                    CoreEvent::Backpressure { reason, waited_secs, cleared } => {
+                                        let text = if cleared {
+                                            format!("🚦 **Render resumed** after {}m {}s: {}", waited_secs / 60, waited_secs % 60, reason)
+                                        } else {
+                                            format!("🚦 **Render deferred** — the machine is overloaded ({}). The next ComfyUI render waits until it calms down.", reason)
+                                        };
+                                        let _ = log_chan.say(&http, text).await;
+                                    }
+                                    CoreEvent::SelfTestFailed { project_id, problems } => {
+                                        let _ = log_chan.say(&http, format!(
+                                            "🧪🚨 **Nightly self-test failed** (`{}`)\n{}\nFix the environment before the next Samsara run.",
+                                            project_id,
+                                            problems.iter().map(|p| format!("• {}", p)).collect::<Vec<_>>().join("\n")
+                                        )).await;
+                                    }
+                                    CoreEvent::TopicBlocked { origin, topic, reason } => {
+                                        let _ = log_chan.say(&http, format!(
+                                            "⛔ **Topic blocked by the editorial policy** ({})\n**{}** {}. Nothing was queued.",
+                                            origin, topic, reason
+                                        )).await;
+                                    }
+                                    CoreEvent::KarmaReviewRequest { karma_id, skill, lesson, replaced } => {
+                                        let mut sample = replaced.iter().take(5).map(|l| format!("• {}", l.chars().take(120).collect::<String>())).collect::<Vec<_>>().join("\n");
+                                        if replaced.len() > 5 {
+                                            sample.push_str(&format!("\n… and {} more", replaced.len() - 5));
+                                        }
+                                        let embed = CreateEmbed::new()
+                                            .title("🧬 Synthesized Karma Review")
+                                            .field("Skill", &skill, true)
+                                            .field("Replaces", format!("{} lesson(s)", replaced.len()), true)
+                                            .field("New lesson", lesson.chars().take(1000).collect::<String>(), false)
+                                            .field("Current lessons", if sample.is_empty() { "-".to_string() } else { sample }, false)
+                                            .color(0x9B59B6)
+                                            .footer(serenity::all::CreateEmbedFooter::new("Approve to replace them with the new lesson (weight 100). Reject keeps them."));
+                                        let msg = CreateMessage::new()
+                                            .embed(embed)
+                                            .button(CreateButton::new(approval_action_id(karma_id, true)).label("✅ Approve").style(serenity::ButtonStyle::Success))
+                                            .button(CreateButton::new(approval_action_id(karma_id, false)).label("❌ Reject").style(serenity::ButtonStyle::Danger));
+                                        let _ = log_chan.send_message(&http, msg).await;
+                                    }
+                                    CoreEvent::IngestPrompt { ingest_id, file_name, size_bytes } => {
+                                        let _ = log_chan.say(&http, format!(
+                                            "🎞️ **New render in the watch folder**: `{}` ({:.1} MB)\nGive it a topic with `/ingest id:{} topic:<topic> style:<style>`",
+                                            file_name, size_bytes as f64 / 1_048_576.0, ingest_id
+                                        )).await;
+                                    }
+                                    CoreEvent::FailedJobs { jobs, channel_id } => {
+                                        let chan = ChannelId::new(channel_id);
+                                        if jobs.is_empty() {
+                                            let _ = chan.say(&http, "✨ No failed jobs. The dead-letter queue is empty.").await;
+                                            continue;
+                                        }
+                                        let mut embed = CreateEmbed::new()
+                                            .title("🪦 Recent Failures")
+                                            .color(0xFF003C)
+                                            .footer(serenity::all::CreateEmbedFooter::new("Press a button or use /retry job_id:<id> to re-queue"));
+                                        let mut msg = CreateMessage::new();
+                                        for job in &jobs {
+                                            let error: String = job.error_message.as_deref().unwrap_or("(no error message)").chars().take(200).collect();
+                                            embed = embed.field(
+                                                format!("{} [{}]", job.topic, job.channel),
+                                                format!("`{}`\n{} · retried {}x\n{}", job.job_id, job.failed_at, job.requeue_count, error),
+                                                false,
+                                            );
+                                            let label: String = job.topic.chars().take(40).collect();
+                                            msg = msg.button(CreateButton::new(format!("retry_{}", job.job_id)).label(format!("🔁 {}", label)).style(serenity::ButtonStyle::Primary));
+                                        }
+                                        let _ = chan.send_message(&http, msg.embed(embed)).await;
+                                    }
+                                    CoreEvent::JobList { jobs, page, page_size, total, channel_id } => {
+                                        let chan = ChannelId::new(channel_id);
+                                        if total == 0 {
+                                            let _ = chan.say(&http, "✨ The queue is empty. Nothing is running or waiting.").await;
+                                            continue;
+                                        }
+                                        let pages = ((total as u64).div_ceil(page_size.max(1) as u64)).max(1) as u32;
+                                        let mut embed = CreateEmbed::new()
+                                            .title(format!("📋 Job Queue ({} active)", total))
+                                            .color(0x3498DB)
+                                            .footer(serenity::all::CreateEmbedFooter::new(format!("Page {}/{} · /job job_id:<id> for details", page, pages)));
+                                        if jobs.is_empty() {
+                                            embed = embed.description(format!("No jobs on page {}.", page));
+                                        }
+                                        for job in &jobs {
+                                            let icon = if job.status == "Processing" { "⚙️" } else { "⏳" };
+                                            let mut detail = format!("`{}`\n{} · {}", job.job_id, job.style, job.status);
+                                            if let Some(stage) = &job.stage {
+                                                detail.push_str(&format!(" · {}", stage));
+                                            }
+                                            if let Some(at) = &job.scheduled_for {
+                                                detail.push_str(&format!("\nscheduled for {}", at));
+                                            }
+                                            let topic: String = job.topic.chars().take(200).collect();
+                                            embed = embed.field(format!("{} {} [{}]", icon, topic, job.channel), detail, false);
+                                        }
+                                        let mut msg = CreateMessage::new().embed(embed);
+                                        if page > 1 {
+                                            msg = msg.button(CreateButton::new(format!("queue_page_{}", page - 1)).label("◀ Prev").style(serenity::ButtonStyle::Secondary));
+                                        }
+                                        if page < pages {
+                                            msg = msg.button(CreateButton::new(format!("queue_page_{}", page + 1)).label("Next ▶").style(serenity::ButtonStyle::Secondary));
+                                        }
+                                        let _ = chan.send_message(&http, msg).await;
+                                    }
+                                    CoreEvent::JobDetail { job_id, job, channel_id } => {
+                                        let chan = ChannelId::new(channel_id);
+                                        let Some(job) = job else {
+                                            let _ = chan.say(&http, format!("❓ Job `{}` not found.", job_id)).await;
+                                            continue;
+                                        };
+                                        let color = match job.status.as_str() {
+                                            "Completed" => 0x00FF41,
+                                            "Failed" => 0xFF003C,
+                                            _ => 0x3498DB,
+                                        };
+                                        let mut embed = CreateEmbed::new()
+                                            .title(format!("🔎 {}", job.topic.chars().take(200).collect::<String>()))
+                                            .field("Job ID", &job.job_id, false)
+                                            .field("Status", &job.status, true)
+                                            .field("Stage", job.stage.as_deref().unwrap_or("-"), true)
+                                            .field("Style", &job.style, true)
+                                            .field("Channel", &job.channel, true)
+                                            .color(color);
+                                        if let Some(at) = &job.started_at {
+                                            embed = embed.field("Started", at, true);
+                                        }
+                                        if let Some(at) = &job.scheduled_for {
+                                            embed = embed.field("Scheduled for", at, true);
+                                        }
+                                        if let Some(parent) = &job.depends_on {
+                                            embed = embed.field("Waits for", format!("`{}`", parent), true);
+                                        }
+                                        if let Some(error) = &job.error_message {
+                                            embed = embed.field("Error", error.chars().take(1000).collect::<String>(), false);
+                                        }
+                                        if !job.outputs.is_empty() {
+                                            let outputs: String = job.outputs.join("\n").chars().take(1000).collect();
+                                            embed = embed.field("Outputs", outputs, false);
+                                        }
+                                        if let Some(url) = &job.video_url {
+                                            embed = embed.url(url).field("Published", url, false);
+                                        }
+                                        let _ = chan.send_message(&http, CreateMessage::new().embed(embed)).await;
+                                    }
+                                    CoreEvent::StyleList { styles, channel_id, request } => {
+                                        let chan = ChannelId::new(channel_id);
+                                        let pending = match &request {
+                                            Some(request) => generate_pending.lock().await.remove(request),
+                                            None => None,
+                                        };
+                                        let Some(request) = request else {
+                                            let list = styles.iter().map(|s| format!("• `{}` {}", s.name, s.description)).collect::<Vec<_>>().join("\n");
+                                            let _ = chan.say(&http, format!("🎨 **Styles**\n{}", if list.is_empty() { "(none loaded)".to_string() } else { list })).await;
+                                            continue;
+                                        };
+                                        let Some(pending) = pending else {
+                                            continue;
+                                        };
+                                        // 入力どおりのスタイルがあればそのまま、スタイルが 1 つも無ければ Core の既定で送る
+                                        let matched = pending.style.as_deref().and_then(|s| match_style(&styles, s)).map(|s| s.name.clone());
+                                        if matched.is_some() || styles.is_empty() {
+                                            let _ = chan.say(&http, dispatch_generate(&generate_tx, pending, matched).await).await;
+                                            continue;
+                                        }
+                                        let mut content = match &pending.style {
+                                            Some(style) => format!("⚠️ `{}` is not in styles.toml. Pick a style for **{}**:", style, pending.topic),
+                                            None => format!("🎨 Pick a style for **{}**:", pending.topic),
+                                        };
+                                        if styles.len() > 25 {
+                                            content.push_str(&format!("\n(showing 25 of {} styles)", styles.len()));
+                                        }
+                                        let msg = CreateMessage::new().content(content).select_menu(style_menu(&request, &styles));
+                                        hold_pending(&generate_pending, request, pending).await;
+                                        let _ = chan.send_message(&http, msg).await;
+                                    }
+                                    CoreEvent::ProductionReport { report, channel_id } => {
+                                        let chan = if channel_id == 0 { data.command_channel_id } else { ChannelId::new(channel_id) };
+                                        let period = if report.days == 1 { "last 24h".to_string() } else { format!("last {} days", report.days) };
+                                        let failure_rate = report.failure_rate().map(|r| format!("{:.1}%", r * 100.0)).unwrap_or_else(|| "-".to_string());
+                                        let avg_render = report.avg_render_secs
+                                            .map(|secs| format!("{}m {:02}s", secs as u64 / 60, secs as u64 % 60))
+                                            .unwrap_or_else(|| "-".to_string());
+                                        let top = report.top_videos.iter().enumerate().map(|(i, v)| {
+                                            let topic: String = v.topic.chars().take(80).collect();
+                                            let title = match &v.video_url {
+                                                Some(url) => format!("[{}]({})", topic, url),
+                                                None => topic,
+                                            };
+                                            format!("{}. {} [{}] — {} views · {} likes", i + 1, title, v.channel, v.views, v.likes)
+                                        }).collect::<Vec<_>>().join("\n");
+                                        let embed = CreateEmbed::new()
+                                            .title(format!("📈 Production Report ({})", period))
+                                            .field("Completed", report.completed.to_string(), true)
+                                            .field("Failed", report.failed.to_string(), true)
+                                            .field("Failure rate", failure_rate, true)
+                                            .field("Avg render time", avg_render, true)
+                                            .field("Karma gained", report.karma_gained.to_string(), true)
+                                            .field("Top videos", if top.is_empty() { "No metrics recorded in this period.".to_string() } else { top.chars().take(1024).collect() }, false)
+                                            .color(if report.failed > report.completed { 0xFF003C } else { 0x00FF41 })
+                                            .footer(serenity::all::CreateEmbedFooter::new("/report days:<n> for another window · /queue for what is running now"));
+                                        let _ = chan.send_message(&http, CreateMessage::new().embed(embed)).await;
+                                    }
                                     _ => {}
                                 }
                             }
@@ -0,0 +1,127 @@
+//! ログチャンネルにピン留めしたステータスボード (Heartbeat で書き換えるライブダッシュボード)
+//!
+//! 起動時にボット自身がピン留めしたボードを探して使い回し、無ければ投稿してピン留めする。
+//! 中身が変わったときだけ編集するので、Discord のレート制限に触れない間隔で回す。
+
+use std::sync::Arc;
+
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage, MessageId};
+use shared::watchtower::SystemStatus;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// ボードの embed のタイトル (起動時に既存のボードを見分ける)
+const BOARD_TITLE: &str = "🏭 Factory Status";
+/// 書き換えの間隔 (Heartbeat は 5 秒ごとだが、編集は控えめにする)
+const REFRESH: std::time::Duration = std::time::Duration::from_secs(15);
+/// これを超えたら CPU を警告色にする (%)
+const CPU_WARN_PERCENT: f32 = 90.0;
+/// これを超えたら VRAM を警告色にする (ComfyUI の OOM が近い)
+const VRAM_WARN_RATIO: f64 = 0.9;
+
+/// 状態ごとの色とラベル
+fn indicator(status: Option<&SystemStatus>) -> (u32, &'static str) {
+    match status {
+        None => (0xFF003C, "🔴 Offline"),
+        Some(s) if s.paused => (0x95A5A6, "⏸️ Paused (finishing the running job, queue on hold)"),
+        Some(s) if s.breaker_open() => (0xFF8C00, "🟠 Degraded (circuit breaker open)"),
+        Some(s) if s.vram_ratio().is_some_and(|r| r >= VRAM_WARN_RATIO) => (0xFF8C00, "🟠 VRAM nearly full"),
+        Some(s) if s.cpu_usage >= CPU_WARN_PERCENT => (0xFF8C00, "🟠 Under heavy load"),
+        Some(s) if s.active_job_id.is_some() => (0x3498DB, "🔵 Producing"),
+        Some(_) => (0x00FF41, "🟢 Idle"),
+    }
+}
+
+/// ボードの embed (`None` は Heartbeat が途絶えている)
+pub fn status_embed(status: Option<&SystemStatus>) -> CreateEmbed {
+    let (color, label) = indicator(status);
+    let embed = CreateEmbed::new().title(BOARD_TITLE).description(label).color(color);
+    let Some(s) = status else {
+        return embed.footer(CreateEmbedFooter::new("No heartbeat from Core"));
+    };
+    let breaker = if s.api_failure_limit == 0 {
+        "-".to_string()
+    } else {
+        format!("{} ({} / {} failures)", if s.breaker_open() { "🔴 OPEN" } else { "🟢 closed" }, s.api_failures, s.api_failure_limit)
+    };
+    let vram = match s.vram_ratio() {
+        Some(ratio) => format!(
+            "{}{} / {} MB ({:.0}%){}",
+            if ratio >= VRAM_WARN_RATIO { "⚠️ " } else { "" },
+            s.vram_used_mb,
+            s.vram_total_mb,
+            ratio * 100.0,
+            s.gpu_utilization.map(|u| format!("\nGPU {:.0}%", u)).unwrap_or_default()
+        ),
+        None => "n/a".to_string(),
+    };
+    embed
+        .field("CPU", format!("{}{:.1}%", if s.cpu_usage >= CPU_WARN_PERCENT { "⚠️ " } else { "" }, s.cpu_usage), true)
+        .field("RAM", format!("{} MB", s.memory_used_mb), true)
+        .field("VRAM", vram, true)
+        .field("Current job", s.active_job_id.as_deref().map(|j| format!("`{}`", j)).unwrap_or_else(|| "-".to_string()), true)
+        .field("Stage", s.stage.as_deref().unwrap_or("-"), true)
+        .field("Queue", format!("{} waiting{}", s.queue_depth, if s.paused { " (on hold)" } else { "" }), true)
+        .field("Circuit breaker", breaker, false)
+        .footer(CreateEmbedFooter::new("Updated with each heartbeat · /queue for the full list"))
+        .timestamp(serenity::Timestamp::now())
+}
+
+/// ボット自身がピン留めしたボードを探す
+async fn find_board(http: &serenity::Http, channel: ChannelId) -> Option<MessageId> {
+    let me = http.get_current_user().await.ok()?.id;
+    let pins = channel.pins(http).await.ok()?;
+    pins.into_iter()
+        .find(|m| m.author.id == me && m.embeds.first().and_then(|e| e.title.as_deref()) == Some(BOARD_TITLE))
+        .map(|m| m.id)
+}
+
+/// 新しくボードを投稿してピン留めする
+async fn post_board(http: &serenity::Http, channel: ChannelId, status: Option<&SystemStatus>) -> Option<MessageId> {
+    match channel.send_message(http, CreateMessage::new().embed(status_embed(status))).await {
+        Ok(msg) => {
+            if let Err(e) = msg.pin(http).await {
+                warn!("⚠️ Status board: Could not pin the board (Manage Messages permission?): {}", e);
+            }
+            Some(msg.id)
+        }
+        Err(e) => {
+            warn!("⚠️ Status board: Failed to post the board: {}", e);
+            None
+        }
+    }
+}
+
+/// 最新の Heartbeat をボードへ反映し続ける
+pub async fn run(http: Arc<serenity::Http>, channel: ChannelId, latest_status: Arc<Mutex<Option<SystemStatus>>>) {
+    let mut board = find_board(&http, channel).await;
+    if board.is_some() {
+        info!("📌 Status board: Reusing the pinned board in {}", channel);
+    }
+    // 初回は必ず書き換える
+    let mut shown: Option<Option<SystemStatus>> = None;
+    let mut interval = tokio::time::interval(REFRESH);
+    loop {
+        interval.tick().await;
+        let status = latest_status.lock().await.clone();
+        if shown.as_ref() == Some(&status) {
+            continue;
+        }
+        let Some(id) = board else {
+            board = post_board(&http, channel, status.as_ref()).await;
+            if board.is_some() {
+                shown = Some(status);
+            }
+            continue;
+        };
+        match channel.edit_message(&http, id, EditMessage::new().embed(status_embed(status.as_ref()))).await {
+            Ok(_) => shown = Some(status),
+            Err(e) => {
+                // 消された・ピンを外されたなどで編集できなければ、次の周回で投稿し直す
+                warn!("⚠️ Status board: Failed to update the board, posting a new one: {}", e);
+                board = None;
+            }
+        }
+    }
+}
@@ -53,7 +53,7 @@ fn init_rust() -> Result<()> {
     fs::write(target_path, GUARDRAILS_TEMPLATE)?;
 
     println!("{} Generated '{}'", "✓".green().bold(), target_path);
-    println!("");
+    println!();
     println!("  {} Add 'regex = \"1.10\"' to your Cargo.toml", "Next steps:".cyan().bold());
     println!("  Then use it in your code: 'mod guardrails; use guardrails::validate_input;'");
 
@@ -75,7 +75,7 @@ fn init_python() -> Result<()> {
     fs::write(target_path, SECURE_REQUIREMENTS_TEMPLATE)?;
 
     println!("{} Generated '{}'", "✓".green().bold(), target_path);
-    println!("");
+    println!();
     println!("  {} Append to requirements.txt:", "Next steps:".cyan().bold());
     println!("  'cat secure_requirements.txt >> requirements.txt && pip install -r requirements.txt'");
 
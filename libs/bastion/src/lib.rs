@@ -7,6 +7,7 @@
 //! - `fs_guard`: File Jail (パス・トラバーサル / TOCTOU 防止)
 //! - `net_guard`: Net Shield (SSRF / DNS Rebinding 防止)
 //! - `text_guard`: Analyzer & Sanitizer (DoS / Bidi / インジェクション検知・防止)
+//! - `secrets`: API Key Store (ハッシュ保存・失効・定数時間検証)
 
 pub mod common;
 pub mod guardrails;
@@ -23,3 +24,6 @@ pub mod net_guard;
 
 #[cfg(feature = "text")]
 pub mod text_guard;
+
+#[cfg(feature = "secrets")]
+pub mod secrets;
@@ -21,6 +21,25 @@ enum Commands {
         #[arg(default_value = "auto")]
         language: String,
     },
+
+    /// HTTP API キーを管理する (発行・一覧・失効)
+    Keys {
+        /// キー保管庫のパス
+        #[arg(long, default_value = "secrets/api_keys.json")]
+        file: String,
+        #[command(subcommand)]
+        action: KeyAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum KeyAction {
+    /// 新しいキーを発行する (平文はこの一度しか表示されない)
+    Add { name: String },
+    /// 発行済みキーを一覧する
+    List,
+    /// キーを失効させる
+    Revoke { name: String },
 }
 
 fn main() -> Result<()> {
@@ -34,6 +53,30 @@ fn main() -> Result<()> {
         Some(Commands::Init { language }) => {
             bastion::init::run_init(&language)?;
         }
+        Some(Commands::Keys { file, action }) => {
+            let store = bastion::secrets::KeyStore::open(&file)?;
+            match action {
+                KeyAction::Add { name } => {
+                    let key = store.create_key(&name)?;
+                    println!("🔑 Issued key '{}' (store: {})", name, store.path().display());
+                    println!("{}", key);
+                    println!("Save it now; only its hash is stored.");
+                }
+                KeyAction::List => {
+                    for r in store.list() {
+                        let state = if r.revoked { "revoked" } else { "active" };
+                        println!("{:<20} {}…  {:<8} created_at={}", r.name, r.hint, state, r.created_at);
+                    }
+                }
+                KeyAction::Revoke { name } => {
+                    if store.revoke(&name)? {
+                        println!("🗝️ Revoked '{}'", name);
+                    } else {
+                        anyhow::bail!("No active key named '{}'", name);
+                    }
+                }
+            }
+        }
     }
 
     Ok(())
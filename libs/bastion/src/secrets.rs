@@ -0,0 +1,189 @@
+//! # secrets (API Key Store)
+//!
+//! HTTP API 用の API キーを発行・失効・検証する。
+//! 平文のキーは発行時に一度だけ返し、ファイルには SHA-256 ハッシュのみを 0600 で保存する。
+//! 別プロセス (`bastion keys ...`) がファイルを書き換えても、検証時に更新日時を見て読み直す。
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
+
+/// 発行するキーの接頭辞 (シークレットスキャナで検出しやすくする)
+pub const KEY_PREFIX: &str = "bk_";
+
+/// 保存されるキーの記録 (平文は持たない)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyRecord {
+    /// 利用者を識別する名前 (ログ・レート制限の単位)
+    pub name: String,
+    /// 平文キーの先頭 (一覧表示用)
+    pub hint: String,
+    /// 平文キーの SHA-256 (hex)
+    pub hash: String,
+    /// 発行時刻 (UNIX 秒)
+    pub created_at: u64,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+#[derive(Default)]
+struct Loaded {
+    records: Vec<ApiKeyRecord>,
+    modified: Option<SystemTime>,
+}
+
+/// ファイルに永続化された API キーの保管庫
+pub struct KeyStore {
+    path: PathBuf,
+    state: RwLock<Loaded>,
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl KeyStore {
+    /// 保管庫を開く。ファイルが無ければ空として扱い、最初の発行時に作成する。
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let store = Self { path: path.as_ref().to_path_buf(), state: RwLock::new(Loaded::default()) };
+        store.reload()?;
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn reload(&self) -> Result<()> {
+        let modified = modified_at(&self.path);
+        let records = match fs::read_to_string(&self.path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Key store {} is corrupted", self.path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read key store {}", self.path.display())),
+        };
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) = Loaded { records, modified };
+        Ok(())
+    }
+
+    /// 他プロセスによる変更があれば読み直す (失敗しても手元の内容で続行)
+    fn refresh(&self) {
+        let stale = self.state.read().map(|s| s.modified != modified_at(&self.path)).unwrap_or(true);
+        if stale {
+            let _ = self.reload();
+        }
+    }
+
+    /// 一時ファイルに書いてから rename し、途中で落ちても壊れたファイルを残さない
+    fn save(&self, records: &[ApiKeyRecord]) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(&tmp)?;
+        file.write_all(serde_json::to_string_pretty(records)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// 新しいキーを発行し、平文を返す (再表示はできない)
+    pub fn create_key(&self, name: &str) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Key name must not be empty");
+        }
+        self.reload()?;
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if state.records.iter().any(|r| r.name == name && !r.revoked) {
+            bail!("An active key named '{}' already exists", name);
+        }
+        let key = format!("{}{}", KEY_PREFIX, hex::encode(rand::random::<[u8; 24]>()));
+        let record = ApiKeyRecord {
+            name: name.to_string(),
+            hint: key[..KEY_PREFIX.len() + 6].to_string(),
+            hash: hash_key(&key),
+            created_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            revoked: false,
+        };
+        let mut records = state.records.clone();
+        records.push(record);
+        self.save(&records)?;
+        *state = Loaded { records, modified: modified_at(&self.path) };
+        Ok(key)
+    }
+
+    /// 名前で有効なキーを失効させる。該当が無ければ false
+    pub fn revoke(&self, name: &str) -> Result<bool> {
+        self.reload()?;
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        let mut records = state.records.clone();
+        let mut found = false;
+        for record in records.iter_mut().filter(|r| r.name == name && !r.revoked) {
+            record.revoked = true;
+            found = true;
+        }
+        if found {
+            self.save(&records)?;
+            *state = Loaded { records, modified: modified_at(&self.path) };
+        }
+        Ok(found)
+    }
+
+    pub fn list(&self) -> Vec<ApiKeyRecord> {
+        self.refresh();
+        self.state.read().map(|s| s.records.clone()).unwrap_or_default()
+    }
+
+    /// 平文キーを検証し、有効なら持ち主の名前を返す (ハッシュは定数時間で比較する)
+    pub fn verify(&self, key: &str) -> Option<String> {
+        self.refresh();
+        let hash = hash_key(key);
+        let state = self.state.read().ok()?;
+        state
+            .records
+            .iter()
+            .filter(|r| !r.revoked)
+            .find(|r| bool::from(r.hash.as_bytes().ct_eq(hash.as_bytes())))
+            .map(|r| r.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_create_verify_revoke_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys/api_keys.json");
+        let store = KeyStore::open(&path).unwrap();
+
+        let key = store.create_key("tauri").unwrap();
+        assert!(key.starts_with(KEY_PREFIX));
+        assert_eq!(store.verify(&key).as_deref(), Some("tauri"));
+        assert!(store.verify("bk_wrong").is_none());
+        assert!(store.create_key("tauri").is_err());
+
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains(&key));
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // A second handle (the CLI) revokes; the first one notices on the next check
+        assert!(KeyStore::open(&path).unwrap().revoke("tauri").unwrap());
+        assert!(store.verify(&key).is_none());
+        assert!(store.list()[0].revoked);
+    }
+}
@@ -0,0 +1,373 @@
+//! # Factory Client — Serve API (Command Center API) の型付きクライアント
+//!
+//! 要求・応答の型は `factory_core::api` をそのまま再エクスポートし、エンドポイントごとに
+//! 型付きのメソッドを持つ。Command Center (Tauri)・CLI の `--remote`・将来のリモートワーカーが使い、
+//! サーバーの構造体と手書きの reqwest 呼び出しがずれないようにする。
+//!
+//! 認証はサーバーと同じ `Authorization: Bearer <key>` (API キー / スコープ付きトークン)。
+//! 4xx / 5xx は `ErrorResponse` の本文を読んで `ClientError` にする。
+
+pub use factory_core::api::*;
+
+use reqwest::{Method, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+/// `FactoryClient::from_env` が読む接続先とキー
+pub const URL_ENV: &str = "FACTORY_URL";
+pub const API_KEY_ENV: &str = "FACTORY_API_KEY";
+/// `FACTORY_URL` が無いときに読む Serve の runtime ファイル (`RuntimeEndpoints`)
+pub const RUNTIME_FILE_ENV: &str = "FACTORY_RUNTIME_FILE";
+pub const DEFAULT_BASE_URL: &str = "http://127.0.0.1:3000";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Invalid URL: {0}")]
+    Url(String),
+    #[error("Core rejected the API key (401). Set FACTORY_API_KEY (issue one with `shorts-factory token create`).")]
+    Unauthorized,
+    #[error("The API key lacks the scope for this route (403): {0}")]
+    Forbidden(String),
+    #[error("Not found (404): {0}")]
+    NotFound(String),
+    #[error("System busy! Request rejected (429).")]
+    Busy,
+    #[error("Core returned status {status}: {message}")]
+    Status { status: u16, message: String },
+    #[error("Failed to parse response: {0}")]
+    Decode(String),
+}
+
+impl ClientError {
+    /// サーバーが受け付けなかった応答を分類する (本文が `ErrorResponse` ならその文言を使う)
+    fn from_response(status: StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<ErrorResponse>(body)
+            .map(|e| e.error)
+            .unwrap_or_else(|_| body.trim().to_string());
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS => Self::Busy,
+            _ => Self::Status { status: status.as_u16(), message },
+        }
+    }
+
+    /// サーバーに届かなかった (オフライン判定に使う)
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Network(_))
+    }
+}
+
+/// `GET /api/audit` の絞り込み
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuditFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// この時刻 (`YYYY-MM-DD HH:MM:SS` UTC) 以降
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+}
+
+/// `GET /api/karma` の絞り込み
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KarmaFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill: Option<String>,
+    /// `Technical` / `Creative` / `Synthesized`
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub karma_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_weight: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_weight: Option<i64>,
+}
+
+/// Serve が書いた runtime ファイルを読む (無い・壊れていれば None)
+pub fn read_runtime_file(path: &std::path::Path) -> Option<RuntimeEndpoints> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+#[derive(Debug, Clone)]
+pub struct FactoryClient {
+    base_url: Url,
+    api_key: Option<String>,
+    http: reqwest::Client,
+}
+
+impl FactoryClient {
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        let base_url = Url::parse(base_url.trim_end_matches('/')).map_err(|e| ClientError::Url(format!("{}: {}", base_url, e)))?;
+        if base_url.cannot_be_a_base() {
+            return Err(ClientError::Url(format!("{} cannot be a base URL", base_url)));
+        }
+        Ok(Self { base_url, api_key: None, http: Self::build_http(DEFAULT_TIMEOUT) })
+    }
+
+    /// `FACTORY_URL` と `FACTORY_API_KEY` から作る。URL が無ければ `FACTORY_RUNTIME_FILE` が指す
+    /// runtime ファイルの `api_url` (自動割り当てされたポート)、それも無ければ `DEFAULT_BASE_URL`
+    pub fn from_env() -> Result<Self, ClientError> {
+        let base_url = std::env::var(URL_ENV)
+            .ok()
+            .or_else(|| std::env::var(RUNTIME_FILE_ENV).ok().and_then(|path| read_runtime_file(std::path::Path::new(&path))).map(|r| r.api_url))
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let client = Self::new(&base_url)?;
+        Ok(match std::env::var(API_KEY_ENV) {
+            Ok(key) => client.with_api_key(&key),
+            Err(_) => client,
+        })
+    }
+
+    fn build_http(timeout: Duration) -> reqwest::Client {
+        reqwest::Client::builder().timeout(timeout).build().unwrap_or_default()
+    }
+
+    /// 空文字ならキー無し (認証無効のサーバー向け)
+    pub fn with_api_key(mut self, key: &str) -> Self {
+        self.api_key = Some(key.trim().to_string()).filter(|k| !k.is_empty());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http = Self::build_http(timeout);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str().trim_end_matches('/')
+    }
+
+    /// ライブテレメトリの WebSocket (`http://` -> `ws://`)
+    pub fn telemetry_url(&self) -> String {
+        let ws_base = self.base_url().replacen("https://", "wss://", 1).replacen("http://", "ws://", 1);
+        format!("{}/ws/telemetry", ws_base)
+    }
+
+    /// 納品物の URL (`/assets/{project}/{file}`)
+    pub fn asset_url(&self, project_id: &str, file: &str) -> Result<String, ClientError> {
+        self.url(&["assets", project_id, file]).map(String::from)
+    }
+
+    /// パスの各区間は URL エンコードする (ID・シリーズ名に `/` や空白が入っても壊れない)
+    fn url(&self, segments: &[&str]) -> Result<Url, ClientError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| ClientError::Url(self.base_url.to_string()))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn send<Q: Serialize + ?Sized, B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Q>,
+        body: Option<&B>,
+    ) -> Result<T, ClientError> {
+        let mut request = self.http.request(method, self.url(segments)?);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        if let Some(query) = query {
+            request = request.query(query);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await.map_err(|e| ClientError::Network(e.to_string()))?;
+        let status = response.status();
+        let text = response.text().await.map_err(|e| ClientError::Network(e.to_string()))?;
+        if !status.is_success() {
+            return Err(ClientError::from_response(status, &text));
+        }
+        serde_json::from_str(&text).map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, segments: &[&str]) -> Result<T, ClientError> {
+        self.send::<(), (), T>(Method::GET, segments, None, None).await
+    }
+
+    async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(&self, segments: &[&str], body: &B) -> Result<T, ClientError> {
+        self.send::<(), B, T>(Method::POST, segments, None, Some(body)).await
+    }
+
+    // ===== Jobs =====
+
+    /// `POST /api/remix` — ワークフローを今すぐ実行する
+    pub async fn remix(&self, request: &WorkflowRequest) -> Result<AcceptedJob, ClientError> {
+        self.post(&["api", "remix"], request).await
+    }
+
+    /// `POST /api/variants` — 1 つの台本から演出違いを N 本作る
+    pub async fn variants(&self, request: &VariantsRequest) -> Result<AcceptedJob, ClientError> {
+        self.post(&["api", "variants"], request).await
+    }
+
+    /// `GET /api/jobs` — 直近 100 件
+    pub async fn jobs(&self) -> Result<Vec<Job>, ClientError> {
+        self.get(&["api", "jobs"]).await
+    }
+
+    /// `GET /api/jobs/failed` — Dead-letter キュー
+    pub async fn failed_jobs(&self) -> Result<Vec<FailedJobSummary>, ClientError> {
+        self.get(&["api", "jobs", "failed"]).await
+    }
+
+    pub async fn job(&self, job_id: &str) -> Result<Job, ClientError> {
+        self.get(&["api", "jobs", job_id]).await
+    }
+
+    pub async fn retry_job(&self, job_id: &str) -> Result<RetryResponse, ClientError> {
+        self.post(&["api", "jobs", job_id, "retry"], &serde_json::json!({})).await
+    }
+
+    /// 待機中 (予約を含む) のジョブを取り消す。実行中・終了済みなら 409 の `ClientError::Status`
+    pub async fn cancel_job(&self, job_id: &str) -> Result<StatusResponse, ClientError> {
+        self.post(&["api", "jobs", job_id, "cancel"], &serde_json::json!({})).await
+    }
+
+    /// -1 = ボツ, 0 = 普通, 1 = 最高
+    pub async fn rate_job(&self, job_id: &str, rating: i64) -> Result<StatusResponse, ClientError> {
+        self.post(&["api", "jobs", job_id, "rate"], &RateRequest { rating: Some(rating) }).await
+    }
+
+    pub async fn job_why(&self, job_id: &str) -> Result<JobWhyResponse, ClientError> {
+        self.get(&["api", "jobs", job_id, "why"]).await
+    }
+
+    pub async fn job_timeline(&self, job_id: &str) -> Result<JobTimeline, ClientError> {
+        self.get(&["api", "jobs", job_id, "timeline"]).await
+    }
+
+    pub async fn job_costs(&self, job_id: &str) -> Result<JobCosts, ClientError> {
+        self.get(&["api", "jobs", job_id, "costs"]).await
+    }
+
+    /// `GET /api/karma` — 直近 200 件の教訓
+    pub async fn karma(&self) -> Result<Vec<serde_json::Value>, ClientError> {
+        self.get(&["api", "karma"]).await
+    }
+
+    /// `GET /api/karma` を絞り込む
+    pub async fn karma_filtered(&self, filter: &KarmaFilter) -> Result<Vec<serde_json::Value>, ClientError> {
+        self.send::<_, (), _>(Method::GET, &["api", "karma"], Some(filter), None).await
+    }
+
+    /// `POST /api/karma` — 教訓を手動で書き込む (admin スコープ)
+    pub async fn inject_karma(&self, karma: &NewKarma) -> Result<KarmaCreated, ClientError> {
+        self.post(&["api", "karma"], karma).await
+    }
+
+    /// `DELETE /api/karma/{id}` (admin スコープ)
+    pub async fn delete_karma(&self, karma_id: &str) -> Result<StatusResponse, ClientError> {
+        self.send::<(), (), _>(Method::DELETE, &["api", "karma", karma_id], None, None).await
+    }
+
+    // ===== Series =====
+
+    /// `POST /api/series` — トピックを親子ジョブの連鎖として積む
+    pub async fn enqueue_series(&self, request: &SeriesRequest) -> Result<SeriesResponse, ClientError> {
+        self.post(&["api", "series"], request).await
+    }
+
+    pub async fn series_list(&self) -> Result<Vec<Series>, ClientError> {
+        self.get(&["api", "series"]).await
+    }
+
+    /// 名前または ID で
+    pub async fn series(&self, name: &str) -> Result<SeriesDetail, ClientError> {
+        self.get(&["api", "series", name]).await
+    }
+
+    pub async fn create_series(&self, name: &str, series: &NewSeries) -> Result<Series, ClientError> {
+        self.send::<(), _, _>(Method::PUT, &["api", "series", name], None, Some(series)).await
+    }
+
+    /// `POST /api/series/{name}/episodes` — 次の話数を積む
+    pub async fn enqueue_episode(&self, name: &str, request: &EpisodeRequest) -> Result<EpisodeAccepted, ClientError> {
+        self.post(&["api", "series", name, "episodes"], request).await
+    }
+
+    // ===== Projects / Styles =====
+
+    pub async fn projects(&self) -> Result<Vec<ProjectSummary>, ClientError> {
+        self.get(&["api", "projects"]).await
+    }
+
+    /// `GET /api/projects/{id}` — 言語・画角ごとの納品物
+    pub async fn project(&self, project_id: &str) -> Result<ProjectDetail, ClientError> {
+        self.get(&["api", "projects", project_id]).await
+    }
+
+    pub async fn review_link(&self, project_id: &str, request: &ReviewLinkRequest) -> Result<ReviewLink, ClientError> {
+        self.post(&["api", "projects", project_id, "review-link"], request).await
+    }
+
+    pub async fn reviews(&self, project_id: &str) -> Result<Vec<ReviewRecord>, ClientError> {
+        self.get(&["api", "projects", project_id, "reviews"]).await
+    }
+
+    pub async fn styles(&self) -> Result<Vec<String>, ClientError> {
+        self.get(&["api", "styles"]).await
+    }
+
+    pub async fn reload_styles(&self) -> Result<StyleReloadResponse, ClientError> {
+        self.post(&["api", "styles", "reload"], &serde_json::json!({})).await
+    }
+
+    // ===== Infrastructure =====
+
+    /// `GET /api/audit` (admin スコープ)
+    pub async fn audit(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>, ClientError> {
+        self.send::<_, (), _>(Method::GET, &["api", "audit"], Some(filter), None).await
+    }
+
+    /// `GET /api/openapi.json` — サーバーが公開しているスキーマ
+    pub async fn openapi(&self) -> Result<serde_json::Value, ClientError> {
+        self.get(&["api", "openapi.json"]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_urls_encode_segments_and_keep_base_path() {
+        let client = FactoryClient::new("http://127.0.0.1:3000/").unwrap();
+        assert_eq!(client.base_url(), "http://127.0.0.1:3000");
+        assert_eq!(client.url(&["api", "series", "AI News/Weekly"]).unwrap().as_str(), "http://127.0.0.1:3000/api/series/AI%20News%2FWeekly");
+        assert_eq!(client.telemetry_url(), "ws://127.0.0.1:3000/ws/telemetry");
+
+        let proxied = FactoryClient::new("https://factory.example.com/core").unwrap();
+        assert_eq!(proxied.asset_url("job_1", "final_ja.mp4").unwrap(), "https://factory.example.com/core/assets/job_1/final_ja.mp4");
+        assert_eq!(proxied.telemetry_url(), "wss://factory.example.com/core/ws/telemetry");
+        assert!(FactoryClient::new("not a url").is_err());
+        assert!(FactoryClient::new("http://x").unwrap().with_api_key("  ").api_key.is_none());
+    }
+
+    #[test]
+    fn test_error_responses_are_classified() {
+        let body = r#"{"error":"Job not found"}"#;
+        assert!(matches!(ClientError::from_response(StatusCode::NOT_FOUND, body), ClientError::NotFound(m) if m == "Job not found"));
+        assert!(matches!(ClientError::from_response(StatusCode::UNAUTHORIZED, ""), ClientError::Unauthorized));
+        assert!(matches!(ClientError::from_response(StatusCode::TOO_MANY_REQUESTS, body), ClientError::Busy));
+        match ClientError::from_response(StatusCode::BAD_GATEWAY, "upstream down\n") {
+            ClientError::Status { status, message } => assert_eq!((status, message.as_str()), (502, "upstream down")),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(ClientError::Network("refused".into()).is_network());
+    }
+}
@@ -0,0 +1,486 @@
+//! # Serve API DTOs — HTTP API の公開契約
+//!
+//! `shorts-factory` の axum ルーターが返す/受け取る型。
+//! Command Center (Tauri) や外部ツールはここを参照し、構造体を手で複製しない。
+//! スキーマは `/api/openapi.json` で公開される。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use crate::contracts::{EpisodeLink, JobProvenance, WorkflowRequest};
+pub use crate::traits::{Job, JobStatus, NewSeries, ReviewRecord, ReviewVerdict, Series};
+pub use shared::watchtower::{FailedJobSummary, SystemStatus};
+
+/// エラー応答 (4xx / 5xx 共通)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+/// 非同期ジョブの受付 (`POST /api/remix` → 202)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AcceptedJob {
+    pub status: String,
+    pub job_id: String,
+    pub job_type: String,
+}
+
+/// 1 つの台本から演出違いを N 本作る (`POST /api/variants` → 202)
+///
+/// 結果は親プロジェクトの `variants.json` と `<export_dir>/<親プロジェクト>/` に出る。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VariantsRequest {
+    #[serde(flatten)]
+    pub workflow: WorkflowRequest,
+    /// 作る本数 (1〜6)
+    pub count: usize,
+}
+
+/// Warehouse のプロジェクト一覧の 1 件
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectSummary {
+    pub id: String,
+    pub title: String,
+    pub style: Option<String>,
+    pub created_at: String,
+    pub thumbnail_url: Option<String>,
+}
+
+/// プロジェクトの詳細 (`GET /api/projects/:id`): 一覧の項目と、画角・言語ごとの納品物
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectDetail {
+    #[serde(flatten)]
+    pub summary: ProjectSummary,
+    pub outputs: Vec<crate::contracts::OutputVideo>,
+}
+
+/// アップロードされた素材の保存先 (WorkflowRequest の voiceover / footage に渡す相対パス)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UploadResponse {
+    pub path: String,
+}
+
+/// シリーズ制作のリクエスト (`POST /api/series`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeriesRequest {
+    /// 各パートのトピック (この順に直列実行される)
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub style: Option<String>,
+    /// 既存ジョブの続編として繋げる場合の親ジョブ ID
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// 投稿先チャンネル (親ジョブがある場合は親のチャンネルを継承する)
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// SOUL プロファイル名 (`[souls]` のキー)
+    #[serde(default)]
+    pub soul: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeriesResponse {
+    pub job_ids: Vec<String>,
+}
+
+/// 連載シリーズと直近のエピソード (`GET /api/series/{name}`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeriesDetail {
+    pub series: Series,
+    /// 新しい話数から順に
+    pub episodes: Vec<EpisodeLink>,
+}
+
+/// 次話の投入 (`POST /api/series/{name}/episodes`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EpisodeRequest {
+    /// シリーズのトピック書式に差し込む今回の題材 (書式が無ければ必須)
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// シリーズにスタイルが無い場合に使うスタイル
+    #[serde(default)]
+    pub style: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EpisodeAccepted {
+    pub job_id: String,
+    pub series: String,
+    pub episode_number: i64,
+}
+
+/// レビューリンクの発行 (`POST /api/projects/{id}/review-link`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewLinkRequest {
+    /// 有効期限 (時間)。省略時は `[review] ttl_hours`
+    #[serde(default)]
+    pub ttl_hours: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewLink {
+    /// API キー無しで開けるレビューページの URL
+    pub url: String,
+    pub expires_at: String,
+}
+
+/// コンテンツカレンダーの項目の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarEntryKind {
+    /// Samsara が企画を積む予定の時刻
+    SamsaraSlot,
+    /// 時刻指定で予約されたジョブ (`schedule`)
+    Scheduled,
+    /// キュー待ち・制作中のジョブ (時刻未定)
+    Queued,
+    /// 公開済みの動画
+    Published,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarEntry {
+    pub kind: CalendarEntryKind,
+    /// RFC 3339 (UTC)。キュー待ちのジョブは None
+    pub at: Option<String>,
+    pub channel: String,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub series: Option<String>,
+    /// Samsara 枠では、その枠で積まれる見込みの話数
+    #[serde(default)]
+    pub episode_number: Option<i64>,
+    #[serde(default)]
+    pub video_url: Option<String>,
+}
+
+/// 共同作業者と共有するための制作計画 (`GET /api/calendar/export`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarExport {
+    pub generated_at: String,
+    /// 前後何日分を含むか
+    pub days: i64,
+    pub series: Vec<Series>,
+    /// 時刻順 (時刻未定のキュー待ちは末尾)
+    pub entries: Vec<CalendarEntry>,
+}
+
+/// 人間によるクリエイティブ評価 (`POST /api/jobs/{id}/rate`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RateRequest {
+    /// -1 = ボツ, 0 = 普通, 1 = 最高 (省略時 50 は旧 UI 互換)
+    #[serde(default)]
+    pub rating: Option<i64>,
+}
+
+/// 手動で書き込む教訓 (`POST /api/karma`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewKarma {
+    pub lesson: String,
+    /// 関連スキル (省略時 `global` = すべての生成に効く)
+    #[serde(default)]
+    pub skill_id: Option<String>,
+    /// `Technical` / `Creative` / `Synthesized` (省略時 `Synthesized`)
+    #[serde(default)]
+    pub karma_type: Option<String>,
+    /// 0-100 (省略時 100)
+    #[serde(default)]
+    pub weight: Option<i64>,
+    /// スタイル名やカテゴリ (省略時は全名前空間に効く共通の教訓)
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
+/// 書き込んだ教訓の ID (`POST /api/karma`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KarmaCreated {
+    pub id: String,
+}
+
+/// 処理結果だけを返す応答 (`{"status": "success"}` など)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StatusResponse {
+    pub status: String,
+}
+
+/// 自律エンジンの一時停止状態 (`POST /api/system/pause` / `POST /api/system/resume`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PauseResponse {
+    pub paused: bool,
+    /// 今回の要求で状態が変わったか (既にその状態なら false)
+    pub changed: bool,
+    /// 一時停止した時刻 (RFC 3339、停止中のみ)
+    pub paused_since: Option<String>,
+}
+
+/// 失敗ジョブの再投入結果 (`POST /api/jobs/{id}/retry`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetryResponse {
+    pub status: String,
+    pub job_id: String,
+    /// 一緒に戻った子孫を含む件数
+    pub requeued: u64,
+}
+
+/// ジョブの来歴とペルソナによる説明 (`GET /api/jobs/{id}/why`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobWhyResponse {
+    pub job_id: String,
+    pub explanation: String,
+    pub provenance: Option<JobProvenance>,
+}
+
+/// ジョブの工程ごとの実行区間 (`GET /api/jobs/{id}/timeline`)
+///
+/// Command Center がガントチャートとして描けるよう、各工程の開始位置と長さを秒で持つ。
+/// 同じ工程が複数回現れるのはやり直し (締め切り超過・一時的な失敗の再実行) の跡。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobTimeline {
+    pub job_id: String,
+    /// 最初の工程の開始時刻 (RFC 3339, UTC。まだ工程がなければ None)
+    pub started_at: Option<String>,
+    /// 最後の工程の終了時刻 (実行中なら None)
+    pub ended_at: Option<String>,
+    /// 最初の工程の開始から最後の工程の終了 (実行中なら現在) までの秒数
+    pub total_secs: f64,
+    pub stages: Vec<TimelineStage>,
+}
+
+/// タイムライン上の 1 工程
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TimelineStage {
+    /// 例: "concept", "voice", "forge", "awaiting_approval:concept"
+    pub stage: String,
+    pub started_at: String,
+    /// 実行中なら None
+    pub ended_at: Option<String>,
+    /// ジョブの開始からこの工程の開始までの秒数
+    pub offset_secs: f64,
+    /// 工程の長さ (実行中なら現在までの秒数)
+    pub duration_secs: f64,
+}
+
+/// コスト台帳の集計 1 行 (プロバイダー × 工程)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CostLine {
+    /// "gemini" / "brave" / "comfyui" (合計行は "total")
+    pub provider: String,
+    pub operation: String,
+    pub api_calls: i64,
+    /// 文字数からの概算
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub gpu_secs: f64,
+    /// 記録時の `[costs]` 単価による概算額
+    pub estimated_usd: f64,
+}
+
+impl CostLine {
+    /// 行を足し合わせた合計行
+    pub fn total(lines: &[CostLine]) -> Self {
+        lines.iter().fold(
+            CostLine { provider: "total".to_string(), ..Default::default() },
+            |mut acc, l| {
+                acc.api_calls += l.api_calls;
+                acc.input_tokens += l.input_tokens;
+                acc.output_tokens += l.output_tokens;
+                acc.gpu_secs += l.gpu_secs;
+                acc.estimated_usd += l.estimated_usd;
+                acc
+            },
+        )
+    }
+}
+
+/// ジョブ 1 本のコスト (`GET /api/jobs/{id}/costs`)。企画合成と公開後の Oracle 評価も含む
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobCosts {
+    pub job_id: String,
+    pub lines: Vec<CostLine>,
+    pub total: CostLine,
+}
+
+/// 期間内のコスト集計 (Watchtower `/costs`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CostSummary {
+    pub days: i64,
+    /// 期間内に完成したジョブ数
+    pub videos: i64,
+    pub lines: Vec<CostLine>,
+    pub total: CostLine,
+    /// 完成 1 本あたりの概算額 (完成 0 本なら None)
+    pub usd_per_video: Option<f64>,
+}
+
+/// API トークンの権限。上位のスコープは下位の操作をすべて含む (admin ⊃ operator ⊃ readonly)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    /// 参照系 (GET) のみ
+    Readonly,
+    /// ジョブ投入・アップロード・評価などの変更系
+    Operator,
+    /// スタイル編集など工場全体に効く操作
+    Admin,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Readonly => "readonly",
+            ApiScope::Operator => "operator",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    /// `required` の操作を許すか
+    pub fn allows(&self, required: ApiScope) -> bool {
+        *self >= required
+    }
+}
+
+impl std::str::FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "readonly" | "read" => Ok(ApiScope::Readonly),
+            "operator" => Ok(ApiScope::Operator),
+            "admin" => Ok(ApiScope::Admin),
+            other => Err(format!("unknown scope '{}' (expected admin, operator or readonly)", other)),
+        }
+    }
+}
+
+/// 発行済み API トークン (平文は持たない)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scope: ApiScope,
+    /// 平文トークンの先頭 (一覧表示用)
+    pub hint: String,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+/// 監査ログの 1 件 (`GET /api/audit`)。追記のみで、更新・削除はできない
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditRecord {
+    pub id: i64,
+    /// 操作の経路: api / discord / cli
+    pub source: String,
+    /// API トークン名・Discord ユーザー・OS ユーザーなど
+    pub actor: String,
+    /// enqueue / rating / style_edit / nuke など
+    pub action: String,
+    /// 対象 (ジョブ ID・スタイル名など)
+    pub target: Option<String>,
+    /// 補足 (リクエスト行や応答ステータス)
+    pub detail: Option<String>,
+    pub created_at: String,
+}
+
+/// 期間内の操作の集計 (夜間ダイジェスト)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AuditSummaryLine {
+    pub action: String,
+    pub actor: String,
+    pub count: i64,
+}
+
+/// cron ジョブ 1 回分の実行記録 (`GET /api/cron/runs`, Watchtower `/cron`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CronRun {
+    pub id: i64,
+    /// `[cron]` のキー (samsara / sentinel / oracle ...)
+    pub job: String,
+    /// 開始時刻 (RFC 3339)
+    pub started_at: String,
+    /// 実行中 (または途中でプロセスごと落ちた) なら None
+    pub finished_at: Option<String>,
+    /// running / ok / partial / skipped / failed
+    pub outcome: String,
+    /// 処理件数・見送りの理由・エラー
+    pub detail: Option<String>,
+}
+
+/// cron ジョブ 1 回の結末
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronOutcome {
+    Ok,
+    /// チャンネルなど一部の対象だけ失敗した (取りこぼし補完では実行済みとして扱う)
+    Partial,
+    /// サーキットブレーカー・一時停止などで見送った
+    Skipped,
+    Failed,
+}
+
+impl CronOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CronOutcome::Ok => "ok",
+            CronOutcome::Partial => "partial",
+            CronOutcome::Skipped => "skipped",
+            CronOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// styles.toml の再読み込み結果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StyleReloadResponse {
+    pub status: String,
+    pub styles: Vec<String>,
+}
+
+/// 稼働中の Serve が実際に使っているエンドポイント (`[ports] runtime_file`)
+///
+/// ポートを自動で割り当てた場合も、Command Center や CLI はこれを読めば接続先が分かる。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RuntimeEndpoints {
+    pub pid: u32,
+    pub api_url: String,
+    pub api_port: u16,
+    pub tts_url: String,
+    pub tts_port: u16,
+    pub started_at: String,
+}
+
+/// ffmpeg 1 回分の進捗 (`/ws/telemetry` の `ffmpeg_progress` フレーム)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FfmpegProgress {
+    /// 処理の種類 (final_assembly, resize, reframe, fit_clip)
+    pub operation: String,
+    /// 書き出し済みの長さ (秒)
+    pub out_secs: f64,
+    /// 出力の全長 (秒)。分からなければ None
+    pub total_secs: Option<f64>,
+    pub frame: u64,
+    /// 実時間に対する処理速度 (1.0 = 等速)
+    pub speed: Option<f64>,
+    pub percent: Option<f32>,
+    /// 残り時間の見込み (秒)
+    pub eta_secs: Option<u64>,
+    pub done: bool,
+}
+
+/// ログイベント (`/ws/telemetry` の `log` フレーム)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LogEvent {
+    pub level: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// 実行中ジョブの工程 (stage が None ならアイドル)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StageEvent {
+    pub project_id: Option<String>,
+    pub stage: Option<String>,
+    pub timestamp: String,
+}
@@ -2,6 +2,7 @@
 //!
 //! 憲法第2条に基づき、アクター間のやり取りを型安全に定義する。
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use crate::traits::TrendItem;
 
@@ -40,6 +41,30 @@ pub struct ConceptRequest {
     pub trend_items: Vec<TrendItem>,
     /// 利用可能な演出スタイルの一覧
     pub available_styles: Vec<String>,
+    /// シーンごとに選べる ComfyUI ワークフロー (`resources/workflows` のファイル名)
+    #[serde(default)]
+    pub available_workflows: Vec<String>,
+    /// シリーズ制作: 前編のコンセプト (続編として整合させる)
+    #[serde(default)]
+    pub previous_part: Option<ConceptResponse>,
+    /// 名前付き SOUL が選ばれた場合のペルソナ本文
+    #[serde(default)]
+    pub persona: Option<String>,
+    /// Samsara が勧めるフックの型 (`HOOK_STYLES` のいずれか)。A 案に使わせる
+    #[serde(default)]
+    pub hook_style: Option<String>,
+    /// BGM 目録 (`bgm_manifest.toml`) にあるムード。空なら選ばせない
+    #[serde(default)]
+    pub available_moods: Vec<String>,
+    /// 効果音ライブラリにある種類。空なら強調点を付けさせない
+    #[serde(default)]
+    pub available_sfx: Vec<String>,
+    /// 台本を用意する言語 (英語の原稿は常に作る)。空なら ja
+    #[serde(default)]
+    pub target_langs: Vec<String>,
+    /// Gemini の使用量の記録先 (ジョブ単位)
+    #[serde(skip)]
+    pub cost: crate::cost::CostTracker,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,7 +108,87 @@ pub struct ConceptResponse {
     pub style_profile: String,
     /// 各シーン固有の描写 (Action/Background) - 必ず3件
     pub visual_prompts: Vec<String>,
+    /// 各シーンの ComfyUI ワークフロー (visual_prompts と同順。空・欠けはスタイルの指定か既定)
+    #[serde(default)]
+    pub scene_workflows: Vec<String>,
     pub metadata: std::collections::HashMap<String, String>,
+    /// A/B テスト用のタイトル・フック案 (A は `title` と同じ)
+    #[serde(default)]
+    pub title_variants: Vec<TitleVariant>,
+    /// BGM のムード (`available_moods` のいずれか。空ならカテゴリで選曲)
+    #[serde(default)]
+    pub bgm_mood: String,
+    /// 効果音を重ねる強調点
+    #[serde(default)]
+    pub sfx_cues: Vec<SfxCue>,
+}
+
+/// フックの型。勝敗はこの単位で集計する
+pub const HOOK_STYLES: &[&str] = &["question", "shock_stat", "bold_claim", "curiosity_gap", "how_to"];
+
+/// 台本の強調点に重ねる効果音
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SfxCue {
+    /// 幕 (0 = intro, 1 = body, 2 = outro)
+    pub act: usize,
+    /// 幕の中の位置 (0.0 = 冒頭, 1.0 = 末尾)。言語によらず同じ位置に鳴らす
+    #[serde(default)]
+    pub at: f32,
+    /// 効果音の種類 (`available_sfx` のいずれか)
+    pub sfx: String,
+}
+
+/// タイトルと冒頭フックの 1 案
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TitleVariant {
+    /// "A" / "B" / "C"
+    #[serde(default)]
+    pub label: String,
+    pub title: String,
+    /// 冒頭の一文 (サムネ文字・概要欄の 1 行目に使う)
+    #[serde(default)]
+    pub hook: String,
+    /// `HOOK_STYLES` のいずれか (不明なら空)
+    #[serde(default)]
+    pub hook_style: String,
+}
+
+/// 同時に試すタイトル案の上限
+pub const MAX_TITLE_VARIANTS: usize = 3;
+
+/// フックの型ごとの勝敗 (公開した案の再生数がチャンネルの中央値を上回れば勝ち)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HookStat {
+    pub hook_style: String,
+    pub published: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub avg_views: f64,
+}
+
+impl ConceptResponse {
+    /// タイトル案を A/B/C に揃える: A は必ず `title`、最大 3 案、未知のフック型は空にする
+    pub fn normalize_title_variants(&mut self) {
+        let (primary, others): (Vec<TitleVariant>, Vec<TitleVariant>) = std::mem::take(&mut self.title_variants)
+            .into_iter()
+            .filter(|v| !v.title.trim().is_empty())
+            .partition(|v| v.title == self.title);
+        let primary = primary.into_iter().next().unwrap_or_else(|| TitleVariant {
+            title: self.title.clone(),
+            hook: self.display_intro.split(['.', '。', '?', '？', '!', '！']).next().unwrap_or_default().trim().to_string(),
+            ..Default::default()
+        });
+        let mut variants = vec![primary];
+        variants.extend(others);
+        variants.truncate(MAX_TITLE_VARIANTS);
+        for (i, v) in variants.iter_mut().enumerate() {
+            v.label = char::from(b'A' + i as u8).to_string();
+            if !HOOK_STYLES.contains(&v.hook_style.as_str()) {
+                v.hook_style.clear();
+            }
+        }
+        self.title_variants = variants;
+    }
 }
 
 // --- Video クラスター ---
@@ -93,12 +198,101 @@ pub struct VideoRequest {
     pub prompt: String,
     pub workflow_id: String,
     pub input_image: Option<String>,
+    /// 登場させる再登場キャラクター (見た目固定用の LoRA / 参照画像)
+    #[serde(default)]
+    pub characters: Vec<CharacterRef>,
+    /// スタイルが指定するチェックポイント・LoRA (ワークフロー JSON の既定を実行時に差し替える)
+    #[serde(default)]
+    pub models: ModelStack,
+    /// [API_SAMPLER] に入れるシード (None ならランダム)
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// チェックポイントと LoRA の組み合わせ (`styles.toml` の `[<style>.models]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelStack {
+    /// `CheckpointLoaderSimple` の ckpt_name を差し替える (None ならワークフローのまま)
+    #[serde(default)]
+    pub checkpoint: Option<String>,
+    /// チェックポイントの直後に順に重ねる LoRA
+    #[serde(default)]
+    pub loras: Vec<LoraSpec>,
+}
+
+impl ModelStack {
+    pub fn is_empty(&self) -> bool {
+        self.checkpoint.is_none() && self.loras.is_empty()
+    }
+}
+
+/// LoRA 1 枚 (ComfyUI の models/loras 内のファイル名と強度)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LoraSpec {
+    pub name: String,
+    #[serde(default = "default_lora_strength")]
+    pub strength: f32,
+}
+
+fn default_lora_strength() -> f32 {
+    1.0
+}
+
+/// 幕と幕のつなぎ目のトランジション (`styles.toml` の `[<style>.transition]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SceneTransition {
+    /// ffmpeg の xfade の種類 (fade / dissolve / wipeleft / slideup / circleopen など)
+    #[serde(default = "default_transition_kind")]
+    pub kind: String,
+    /// 重ねる秒数
+    #[serde(default = "default_transition_duration")]
+    pub duration: f32,
+}
+
+impl Default for SceneTransition {
+    fn default() -> Self {
+        Self { kind: default_transition_kind(), duration: default_transition_duration() }
+    }
+}
+
+fn default_transition_kind() -> String {
+    "fade".to_string()
+}
+
+fn default_transition_duration() -> f32 {
+    0.5
+}
+
+/// ワークフローに注入するキャラクター素材 (`[characters]` から解決済み)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CharacterRef {
+    pub name: String,
+    /// プロンプト先頭に足す固定タグ
+    pub prompt: String,
+    /// LoRA ファイル名と強度
+    pub lora: Option<(String, f32)>,
+    /// IPAdapter 用の参照画像 (絶対パス) と重み
+    pub reference_image: Option<(String, f32)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoResponse {
     pub output_path: String,
     pub job_id: String,
+    /// 安全性チェックで棄却して描き直した出力 (採用された出力は含まない)
+    #[serde(default)]
+    pub safety_rejections: Vec<SafetyRejection>,
+}
+
+/// 安全性チェックで棄却した ComfyUI 出力 1 回分
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SafetyRejection {
+    pub workflow_id: String,
+    pub prompt: String,
+    /// 0.0 (安全) - 1.0 (明らかに不適切)
+    pub score: f32,
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 // --- Voice クラスター ---
@@ -127,6 +321,12 @@ pub struct MediaRequest {
     pub audio_path: String,
     pub subtitle_path: Option<String>,
     pub force_style: Option<String>,
+    /// 二言語字幕の副言語行 (ASS)。主字幕と一緒に焼き込む
+    #[serde(default)]
+    pub secondary_subtitle_path: Option<String>,
+    /// 冒頭タイトル等の ASS オーバーレイ (字幕の上に重ねて焼き込む)
+    #[serde(default)]
+    pub overlay_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -136,7 +336,7 @@ pub struct MediaResponse {
 
 // --- Workflow クラスター (Phase 5) ---
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct CustomStyle {
     // --- 視覚演出 (Cameraman) ---
     pub zoom_speed: Option<f64>,
@@ -149,13 +349,33 @@ pub struct CustomStyle {
     pub fade_duration: Option<f32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 本編の画角
+pub const PRIMARY_ASPECT: &str = "9:16";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OutputVideo {
     pub lang: String,
     pub path: String,
+    /// ArtifactStore に保管した納品物の URL (リモートの指令センターから再生する)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 画角 (本編は "9:16"。`[export]` の追加プロファイルは "1:1" / "16:9" など)
+    #[serde(default = "default_output_aspect")]
+    pub aspect: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_output_aspect() -> String {
+    PRIMARY_ASPECT.to_string()
+}
+
+impl OutputVideo {
+    /// 本編 (9:16) か。レビュー・投稿はこちらを使う
+    pub fn is_primary(&self) -> bool {
+        self.aspect == PRIMARY_ASPECT
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct WorkflowRequest {
     pub category: String,
     pub topic: String,
@@ -171,9 +391,225 @@ pub struct WorkflowRequest {
     /// ユーザーによるカスタム調整 (None の場合はプリセット通り)
     pub custom_style: Option<CustomStyle>,
 
-    /// 生成対象言語 (例: ["ja", "en"])
+    /// 生成対象言語 (例: ["ja", "en", "es"])。映像は共通で、言語ごとにナレーション・字幕・ミックスを作って 1 本ずつ納品する
     #[serde(default)]
     pub target_langs: Vec<String>,
+
+    /// ユーザー持ち込みのナレーション (指定言語の TTS を置き換える)
+    #[serde(default)]
+    pub voiceover: Option<VoiceoverInput>,
+
+    /// 所属チャンネル (空なら既定チャンネル)。スタイルの候補と納品先が切り替わる
+    #[serde(default)]
+    pub channel: String,
+
+    /// SOUL プロファイル名 (`[souls]` のキー)。指定時はチャンネルの SOUL より優先する
+    #[serde(default)]
+    pub soul: Option<String>,
+
+    /// シリーズ制作: 前編のプロジェクト ID (コンセプトを引き継ぐ)
+    #[serde(default)]
+    pub series_parent: Option<String>,
+
+    /// ユーザー持ち込みの映像素材 (指定時は画像生成 + Ken Burns の代わりに使用)
+    #[serde(default)]
+    pub footage: Option<FootageInput>,
+
+    /// ユーザー持ち込みの参照画像 (プロジェクトからの相対パス)。全シーンの `[API_CHARACTER_REF]` に渡す
+    #[serde(default)]
+    pub reference_image: Option<String>,
+
+    /// 出力プロファイル (動画 or 音声のみのポッドキャスト)
+    #[serde(default)]
+    pub output_profile: OutputProfile,
+
+    /// 連載シリーズのエピソードとして作る場合の話数・既刊情報 (タイトルの通し番号と相互宣伝に使う)
+    #[serde(default)]
+    pub episode: Option<EpisodeContext>,
+
+    /// 台本を LLM でなく定型テンプレート (`templates.toml` のキー) で組む。チャンネルの指定より優先する
+    #[serde(default)]
+    pub script_template: Option<String>,
+
+    /// A 案に使うフックの型 (Samsara がフックの勝敗から選ぶ)
+    #[serde(default)]
+    pub hook_style: Option<String>,
+
+    /// シーン画像の ComfyUI ワークフロー (`resources/workflows` のファイル名)。None なら shorts_standard_v1
+    #[serde(default)]
+    pub workflow_id: Option<String>,
+
+    /// シーンのシードの元になる値。None ならジョブ ID (無ければプロジェクト ID) から導く (同じ値・同じプロンプトなら同じ絵になる)
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Remix 時、前回記録したシーンごとのシード (`seeds.json`) をそのまま使い、同じ絵を再現する
+    #[serde(default)]
+    pub reuse_seeds: bool,
+
+    /// 夜間セルフテストの実行 (承認待ち・納品・ArtifactStore を飛ばす)。API からは指定できない
+    #[serde(skip)]
+    pub self_test: bool,
+
+    /// バリエーション制作の 1 本 (`generate --variants`)。API からは `/api/variants` 経由で指定する
+    #[serde(skip)]
+    pub variant: Option<VariantSpec>,
+}
+
+/// ワークフロー ID として使える名前か (`resources/workflows/{id}.json` に結合するので英数字・`_`・`-` だけ)
+pub fn is_valid_workflow_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// 1 つの台本から作り分ける演出違いの 1 本
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantSpec {
+    /// 台本を共有する親プロジェクト。納品先は `<export_dir>/<set_id>/`
+    pub set_id: String,
+    /// 1 始まりの番号
+    pub index: usize,
+    /// ナレーションの話速 (None ならボイスの既定)
+    pub voice_speed: Option<f32>,
+}
+
+/// シーン 1 つの生成に使ったシード (プロジェクトの `seeds.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneSeed {
+    pub scene: usize,
+    pub seed: u64,
+    pub workflow_id: String,
+    /// 採用した生成のプロンプト (Vision QA の修正後を含む)
+    pub prompt: String,
+}
+
+/// バリエーション 1 本分の記録 (親プロジェクトの `variants.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantRecord {
+    pub index: usize,
+    /// 素材・中間ファイルを持つプロジェクト
+    pub project_id: String,
+    pub voice_speed: Option<f32>,
+    pub custom_style: CustomStyle,
+    #[serde(default)]
+    pub output_videos: Vec<OutputVideo>,
+    /// 失敗した場合のエラー
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// シリーズのタイトル書式の既定値 (`{series}` = シリーズ名, `{n}` = 話数, `{title}` = コンセプトのタイトル)
+pub const DEFAULT_EPISODE_TITLE: &str = "{series} #{n}: {title}";
+
+/// エピソード制作時に渡されるシリーズ情報
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct EpisodeContext {
+    pub series_id: String,
+    pub series_name: String,
+    pub episode_number: i64,
+    pub title_template: String,
+    /// 完了済みの既刊 (新しい順)
+    #[serde(default)]
+    pub previous_episodes: Vec<EpisodeLink>,
+}
+
+/// シリーズ内の 1 話 (相互宣伝用のリンク)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EpisodeLink {
+    pub episode_number: i64,
+    pub job_id: String,
+    pub topic: String,
+    pub status: String,
+    pub sns_platform: Option<String>,
+    pub sns_video_id: Option<String>,
+}
+
+impl EpisodeLink {
+    /// 公開済みなら視聴 URL
+    pub fn video_url(&self) -> Option<String> {
+        published_video_url(self.sns_platform.as_deref(), self.sns_video_id.as_deref())
+    }
+}
+
+/// 公開先と動画 ID から視聴 URL を作る (未公開・未知のプラットフォームは None)
+pub fn published_video_url(platform: Option<&str>, video_id: Option<&str>) -> Option<String> {
+    match (platform, video_id) {
+        (Some("youtube"), Some(id)) => Some(format!("https://youtube.com/shorts/{}", id)),
+        (Some("tiktok"), Some(id)) => Some(format!("https://www.tiktok.com/video/{}", id)),
+        _ => None,
+    }
+}
+
+impl EpisodeContext {
+    /// 通し番号付きのタイトル ("AI News Weekly #14: ...")
+    pub fn format_title(&self, title: &str) -> String {
+        let template = if self.title_template.trim().is_empty() { DEFAULT_EPISODE_TITLE } else { &self.title_template };
+        template
+            .replace("{series}", &self.series_name)
+            .replace("{n}", &self.episode_number.to_string())
+            .replace("{title}", title.trim())
+            .trim()
+            .trim_end_matches(':')
+            .trim()
+            .to_string()
+    }
+
+    /// 説明欄に足す相互宣伝の行 (公開済みの既刊のみ)
+    pub fn cross_promotion(&self) -> Vec<String> {
+        self.previous_episodes
+            .iter()
+            .filter_map(|ep| ep.video_url().map(|url| format!("▶ {} #{}: {} {}", self.series_name, ep.episode_number, ep.topic, url)))
+            .collect()
+    }
+}
+
+/// ユーザーが持ち込んだ映像素材 (横長なら 9:16 にリフレームされる)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FootageInput {
+    /// プロジェクトディレクトリからの相対パス (例: "uploads/footage.mp4")
+    pub path: String,
+    /// 被写体検出サイドカーでクロップ窓を追従させるか (false なら中央クロップ)
+    #[serde(default = "default_true")]
+    pub track_subject: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 成果物の形態
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputProfile {
+    /// 縦型ショート動画 (従来どおり)
+    #[default]
+    Video,
+    /// 映像トラックを生成せず、チャプター付き MP3 として書き出す
+    Podcast,
+}
+
+/// ポッドキャストのチャプター (幕の区切り)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioChapter {
+    pub title: String,
+    pub start_secs: f32,
+    pub end_secs: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputAudio {
+    pub lang: String,
+    pub path: String,
+    #[serde(default)]
+    pub chapters: Vec<AudioChapter>,
+}
+
+/// ユーザーが収録したナレーション音声
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VoiceoverInput {
+    /// プロジェクトディレクトリからの相対パス (例: "uploads/voiceover.wav")
+    pub path: String,
+    /// ナレーションの言語。この言語のみ VoiceActor をスキップし、強制アライメントで幕ごとに分割する
+    pub lang: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -183,6 +619,46 @@ pub struct WorkflowResponse {
     #[serde(default)]
     pub output_videos: Vec<OutputVideo>,
     pub concept: ConceptResponse,
+    /// 言語別の字幕読み速度 (CPS) 統計
+    #[serde(default)]
+    pub subtitle_stats: Vec<SubtitleCpsStats>,
+    /// ポッドキャスト出力 (OutputProfile::Podcast の場合のみ)
+    #[serde(default)]
+    pub output_audios: Vec<OutputAudio>,
+    /// シーン画像の美的スコア (美的ゲート有効時のみ)
+    #[serde(default)]
+    pub aesthetic_scores: Vec<AestheticScore>,
+    /// 安全性チェックで棄却したシーン出力 (安全性チェック有効時のみ)
+    #[serde(default)]
+    pub safety_rejections: Vec<SafetyRejection>,
+    /// 直近の動画と似すぎていたためプロンプト・シードをずらしたシーンの記録
+    #[serde(default)]
+    pub similarity_nudges: Vec<String>,
+}
+
+/// シーン 1 枚分の美的スコア履歴
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AestheticScore {
+    pub scene: usize,
+    /// 生成ごとのスコア (最後が採用された画像)
+    pub attempts: Vec<f32>,
+    /// 閾値に届かないまま採用されたか
+    pub below_threshold: bool,
+}
+
+/// 字幕 QA の結果 (Characters Per Second)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubtitleCpsStats {
+    pub lang: String,
+    /// 適用された閾値
+    pub threshold: f32,
+    pub max_cps: f32,
+    pub avg_cps: f32,
+    pub cue_count: usize,
+    /// 最終的に閾値を超えたままの字幕数
+    pub over_threshold: usize,
+    /// 圧縮リライトが適用された幕 (intro/body/outro) の数
+    pub compressed_acts: usize,
 }
 
 // --- Phase 10-F: The Absolute Contract v2 (最終確定・Rust構造体) ---
@@ -231,6 +707,10 @@ pub struct KarmaDirectives {
     #[serde(default)]
     pub execution_notes: String,
 
+    /// タイトル A 案に使うフックの型 (`HOOK_STYLES` のいずれか。空なら指定なし)
+    #[serde(default)]
+    pub hook_style: String,
+
     /// LLM 自身のこの生成に対する自信度 (0-100)。
     /// DB挿入前に必ず `.clamped()` を呼び出すこと。
     pub confidence_score: u8,
@@ -244,6 +724,34 @@ impl KarmaDirectives {
     }
 }
 
+/// Samsara がジョブを合成した経緯 (`/why` で説明に使う)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct JobProvenance {
+    /// 視点 (Entropy Injection で選ばれたアングル)
+    pub angle: String,
+    /// Sonar Ping が生成した検索クエリ
+    pub search_query: String,
+    /// 検索結果 (World Context) の冒頭
+    #[serde(default)]
+    pub world_context_excerpt: String,
+    /// 合成時に参照したカルマ
+    #[serde(default)]
+    pub karma_applied: Vec<String>,
+    /// フォールバックが発動した箇所 (検索失敗・JSON 解析失敗・スタイル差し替えなど)
+    #[serde(default)]
+    pub fallback_events: Vec<String>,
+    /// LLM が提案した元のスタイル (検証で差し替えられる前)
+    #[serde(default)]
+    pub proposed_style: String,
+    #[serde(default)]
+    pub confidence_score: u8,
+    #[serde(default)]
+    pub execution_notes: String,
+    /// 直近の動画と似すぎていたためプロンプト・シードをずらしたシーン (制作後に追記)
+    #[serde(default)]
+    pub similarity_nudges: Vec<String>,
+}
+
 // --- Phase 11: The Absolute Contract v3 (神託の契約) ---
 
 /// LLM（The Oracle）による動画の最終審判。
@@ -258,4 +766,13 @@ pub struct OracleVerdict {
     pub soul_score: f64,
     /// 次元分解に基づく分析とインサイト
     pub reasoning: String,
+    /// 審判に加わったモデル (アンサンブルなら複数。スコアはその平均)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub judges: Vec<String>,
+    /// アンサンブルの審判のスコアが `[oracle] disagreement_threshold` を超えて割れた
+    #[serde(default)]
+    pub disagreement: bool,
+    /// Gemini が失敗し、ローカルの審判だけで判定した
+    #[serde(default)]
+    pub cloud_failed: bool,
 }
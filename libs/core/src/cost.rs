@@ -0,0 +1,171 @@
+//! # Cost — ジョブ単位のコスト計測
+//!
+//! Gemini のトークン・Brave の呼び出し・ComfyUI の GPU 秒を `CostTracker` 経由で記録し、
+//! `CostLedger` (SQLite の `cost_ledger`) に積む。1 本の動画が実際にいくらかかったかを
+//! `/api/jobs/:id/costs` と Watchtower `/costs` で見られるようにする。
+//!
+//! rig の `prompt()` は使用量を返さないため、トークン数はプロンプトと応答の長さからの概算。
+
+use crate::error::FactoryError;
+use async_trait::async_trait;
+use shared::config::CostsConfig;
+use std::sync::{Arc, Mutex};
+
+/// 台帳の 1 行分
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CostEntry {
+    /// "gemini" / "brave" / "comfyui"
+    pub provider: String,
+    /// 例: "concept", "translate", "oracle", "samsara_synthesis", "render_scene"
+    pub operation: String,
+    pub api_calls: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub gpu_secs: f64,
+    /// 記録時の単価で出した概算額
+    pub estimated_usd: f64,
+}
+
+impl CostEntry {
+    /// LLM 呼び出し 1 回 (トークン数は文字数からの概算)
+    pub fn llm(provider: &str, operation: &str, prompt: &str, response: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            operation: operation.to_string(),
+            api_calls: 1,
+            input_tokens: estimate_tokens(prompt),
+            output_tokens: estimate_tokens(response),
+            ..Default::default()
+        }
+    }
+
+    /// 従量課金の API 呼び出し 1 回
+    pub fn call(provider: &str, operation: &str) -> Self {
+        Self { provider: provider.to_string(), operation: operation.to_string(), api_calls: 1, ..Default::default() }
+    }
+
+    /// ComfyUI の GPU 占有時間
+    pub fn gpu(operation: &str, secs: f64) -> Self {
+        Self { provider: "comfyui".to_string(), operation: operation.to_string(), api_calls: 1, gpu_secs: secs, ..Default::default() }
+    }
+}
+
+/// トークン数の概算: ASCII は 4 文字で 1 トークン、それ以外 (日本語など) は 1 文字 1 トークン
+pub fn estimate_tokens(text: &str) -> i64 {
+    let (ascii, other) = text.chars().fold((0i64, 0i64), |(a, o), c| if c.is_ascii() { (a + 1, o) } else { (a, o + 1) });
+    (ascii + 3) / 4 + other
+}
+
+/// 単価表で概算額を出す
+pub fn price(prices: &CostsConfig, entry: &CostEntry) -> f64 {
+    let mut usd = entry.gpu_secs / 3600.0 * prices.gpu_usd_per_hour;
+    match entry.provider.as_str() {
+        "gemini" => {
+            usd += entry.input_tokens as f64 / 1_000_000.0 * prices.gemini_input_usd_per_mtok;
+            usd += entry.output_tokens as f64 / 1_000_000.0 * prices.gemini_output_usd_per_mtok;
+        }
+        "brave" => usd += entry.api_calls as f64 * prices.brave_usd_per_call,
+        _ => {}
+    }
+    usd
+}
+
+/// コスト台帳
+#[async_trait]
+pub trait CostLedger: Send + Sync {
+    /// 1 件記録する。ジョブを持たない実行 (Remix など) は `job_id` なしでプロジェクトに付ける
+    async fn record_cost(&self, job_id: Option<&str>, project_id: Option<&str>, entry: &CostEntry) -> Result<(), FactoryError>;
+}
+
+/// コスト計測器
+///
+/// `Default` は何も記録しない。`for_job` で記録先のジョブ (またはプロジェクト) を決めたものを各工程に渡す。
+/// 記録先が決まる前 (cron の企画合成など) の記録は溜めておき、`attribute` でジョブに付け替える。
+#[derive(Clone, Default)]
+pub struct CostTracker {
+    ledger: Option<Arc<dyn CostLedger>>,
+    prices: Arc<CostsConfig>,
+    job_id: Option<String>,
+    project_id: Option<String>,
+    pending: Arc<Mutex<Vec<CostEntry>>>,
+}
+
+impl std::fmt::Debug for CostTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CostTracker")
+            .field("enabled", &self.ledger.is_some())
+            .field("job_id", &self.job_id)
+            .field("project_id", &self.project_id)
+            .finish()
+    }
+}
+
+impl CostTracker {
+    /// 記録先の決まっていない計測器 (`prices.enabled = false` なら何も記録しない)
+    pub fn new(ledger: Arc<dyn CostLedger>, prices: CostsConfig) -> Self {
+        Self {
+            ledger: prices.enabled.then_some(ledger),
+            prices: Arc::new(prices),
+            ..Default::default()
+        }
+    }
+
+    /// 記録先をジョブ (無ければプロジェクト) に決めた計測器
+    pub fn for_job(&self, job_id: Option<&str>, project_id: Option<&str>) -> Self {
+        Self {
+            ledger: self.ledger.clone(),
+            prices: self.prices.clone(),
+            job_id: job_id.map(str::to_string),
+            project_id: project_id.map(str::to_string),
+            pending: Arc::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ledger.is_some()
+    }
+
+    /// 単価を掛けて記録する (台帳への書き込み失敗は制作を止めない)
+    pub async fn record(&self, mut entry: CostEntry) {
+        let Some(ledger) = &self.ledger else { return };
+        entry.estimated_usd = price(&self.prices, &entry);
+        if self.job_id.is_none() && self.project_id.is_none() {
+            self.pending.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+            return;
+        }
+        let _ = ledger.record_cost(self.job_id.as_deref(), self.project_id.as_deref(), &entry).await;
+    }
+
+    /// 溜めておいた記録をジョブに付け替える
+    pub async fn attribute(&self, job_id: &str) {
+        let Some(ledger) = &self.ledger else { return };
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        for entry in pending {
+            let _ = ledger.record_cost(Some(job_id), None, &entry).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens_and_price() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("こんにちは"), 5);
+
+        let prices = CostsConfig {
+            gemini_input_usd_per_mtok: 1.0,
+            gemini_output_usd_per_mtok: 2.0,
+            brave_usd_per_call: 0.01,
+            gpu_usd_per_hour: 3.6,
+            ..CostsConfig::default()
+        };
+        let llm = CostEntry { provider: "gemini".into(), input_tokens: 500_000, output_tokens: 250_000, ..Default::default() };
+        assert!((price(&prices, &llm) - 1.0).abs() < 1e-9);
+        assert!((price(&prices, &CostEntry::call("brave", "trends")) - 0.01).abs() < 1e-9);
+        assert!((price(&prices, &CostEntry::gpu("render_scene", 100.0)) - 0.1).abs() < 1e-9);
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! `thiserror` を使い、すべてのドメインエラーに明確な型を付与する。
 //! Iron Principles: `unwrap()` / `expect()` は禁止。
+//!
+//! 各バリアントは一時的 (時間をおけば回復しうる) か恒久的かに分類され、
+//! Supervisor と JobWorker は `is_retryable()` が true の失敗だけを再試行する。
 
 use thiserror::Error;
 
@@ -29,6 +32,9 @@ pub enum FactoryError {
     #[error("ComfyUI ワークフロー実行失敗: {reason}")]
     ComfyWorkflowFailed { reason: String },
 
+    #[error("生成物が安全性チェックで棄却: {reason}")]
+    UnsafeOutput { reason: String },
+
     // === メディア編集 ===
     #[error("FFmpeg 実行エラー: {reason}")]
     FfmpegFailed { reason: String },
@@ -73,6 +79,9 @@ pub enum FactoryError {
     #[error("運用タイムアウト: {reason}")]
     OperationalTimeout { reason: String },
 
+    #[error("承認チェックポイントで却下: {checkpoint}")]
+    ApprovalRejected { checkpoint: String },
+
     #[error("OSエラー: {source}")]
     OsError {
         #[source]
@@ -82,9 +91,70 @@ pub enum FactoryError {
     #[error("インフラ構造エラー: {reason}")]
     Infrastructure { reason: String },
 
+    /// 今のジョブの状態ではその操作ができない (実行中のジョブの取り消しなど)
+    #[error("ジョブの状態と操作が合わない: {reason}")]
+    InvalidJobState { reason: String },
+
+    // === 外部サービス ===
+    #[error("ネットワークエラー ({service}): {reason}")]
+    Network { service: String, reason: String },
+
+    #[error("レート制限 ({service}): {reason}")]
+    RateLimited { service: String, reason: String },
+
     #[error("音声合成失敗 (TTS): {reason}")]
     TtsFailure { reason: String },
 
     #[error("セキュリティ法規違反: {reason}")]
     SecurityViolation { reason: String },
 }
+
+impl FactoryError {
+    /// 同じ入力で再試行すれば成功しうる失敗か
+    ///
+    /// 接続断・タイムアウト・混雑・LLM の揺らぎは一時的とみなす。
+    /// ワークフロー不正・素材欠落・検閲・承認却下などは何度やっても同じ結果になるため恒久的。
+    /// 分類の無い `Infrastructure` も恒久的として扱う (一時的なものは `Network` 等で返すこと)。
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::TrendFetch { .. }
+                | Self::ComfyConnection { .. }
+                | Self::ComfyTimeout { .. }
+                | Self::LlmResponse { .. }
+                | Self::InsufficientVram { .. }
+                | Self::OperationalTimeout { .. }
+                | Self::Network { .. }
+                | Self::RateLimited { .. }
+        )
+    }
+
+    /// 外部 API の非 2xx 応答を分類する (429 → RateLimited, 408 / 5xx → Network, それ以外 → Infrastructure)
+    pub fn from_http_status(service: &str, status: u16, body: &str) -> Self {
+        let reason = format!("HTTP {}: {}", status, body.trim());
+        match status {
+            429 => Self::RateLimited { service: service.to_string(), reason },
+            408 | 500..=599 => Self::Network { service: service.to_string(), reason },
+            _ => Self::Infrastructure { reason: format!("{} failed with {}", service, reason) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryability_classification() {
+        assert!(FactoryError::ComfyTimeout { timeout_secs: 180 }.is_retryable());
+        assert!(FactoryError::Network { service: "Brave".into(), reason: "reset".into() }.is_retryable());
+        assert!(!FactoryError::ComfyWorkflowFailed { reason: "Missing [API_PROMPT] node".into() }.is_retryable());
+        assert!(!FactoryError::SecurityViolation { reason: "escape".into() }.is_retryable());
+        assert!(!FactoryError::UnsafeOutput { reason: "nudity".into() }.is_retryable());
+        assert!(!FactoryError::Infrastructure { reason: "unclassified".into() }.is_retryable());
+
+        assert!(matches!(FactoryError::from_http_status("YouTube API", 429, "quota"), FactoryError::RateLimited { .. }));
+        assert!(FactoryError::from_http_status("TTS", 503, "").is_retryable());
+        assert!(!FactoryError::from_http_status("TTS", 400, "bad voice").is_retryable());
+    }
+}
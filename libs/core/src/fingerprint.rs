@@ -0,0 +1,95 @@
+//! # Fingerprint — シーン素材の指紋 (使い回しの検知)
+//!
+//! ビジュアルプロンプトの SimHash と、生成静止画の知覚ハッシュ (dHash) を 64 ビットで持つ。
+//! 直近の動画のシーンとハミング距離が近ければ、Orchestrator がプロンプトやシードをずらして描き直す。
+//! 指紋は `FingerprintStore` (SQLite の `visual_fingerprints`) に積む。
+
+use crate::error::FactoryError;
+use async_trait::async_trait;
+
+/// シーン 1 つ分の指紋
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VisualFingerprint {
+    pub project_id: String,
+    pub scene: usize,
+    /// ビジュアルプロンプトの SimHash
+    pub prompt_hash: u64,
+    /// 採用した静止画の dHash (測れなかった場合は None)
+    pub image_hash: Option<u64>,
+}
+
+/// 指紋の保存先
+#[async_trait]
+pub trait FingerprintStore: Send + Sync {
+    async fn record_fingerprint(&self, fingerprint: &VisualFingerprint) -> Result<(), FactoryError>;
+
+    /// 直近 `days` 日の指紋 (`exclude_project` 自身の分は除く)
+    async fn recent_fingerprints(&self, days: i64, exclude_project: &str) -> Result<Vec<VisualFingerprint>, FactoryError>;
+}
+
+/// FNV-1a (64 ビット)。実行ごとに変わらないハッシュが要るので std の Hasher は使わない
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| (h ^ *b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// プロンプトの SimHash。語順・重複・大文字小文字・記号の違いは無視する
+pub fn prompt_simhash(prompt: &str) -> u64 {
+    let lower = prompt.to_lowercase();
+    let mut tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+
+    let mut weights = [0i32; 64];
+    for token in tokens {
+        let h = fnv1a(token.as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if h >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights.iter().enumerate().fold(0u64, |acc, (bit, w)| if *w > 0 { acc | 1 << bit } else { acc })
+}
+
+/// 9x8 のグレースケール画素 (行優先) から dHash を作る: 各行で右隣より明るければ 1
+pub fn dhash(gray: &[u8]) -> Option<u64> {
+    if gray.len() != 72 {
+        return None;
+    }
+    let mut hash = 0u64;
+    for row in gray.chunks(9) {
+        for pair in row.windows(2) {
+            hash = hash << 1 | (pair[0] > pair[1]) as u64;
+        }
+    }
+    Some(hash)
+}
+
+/// 2 つの指紋のハミング距離 (0 = 同一)
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_simhash_ignores_order_and_case() {
+        let a = prompt_simhash("neon city at night, rain, cyberpunk");
+        assert_eq!(a, prompt_simhash("Cyberpunk, rain, NEON city at night"));
+        assert!(distance(a, prompt_simhash("neon city at night, rain, cyberpunk, umbrella")) < 16);
+        assert!(distance(a, prompt_simhash("quiet forest lake at dawn, mist, watercolor")) > 16);
+    }
+
+    #[test]
+    fn test_dhash_compares_horizontal_neighbours() {
+        let gradient: Vec<u8> = (0..8).flat_map(|_| (0..9).map(|x| 255 - x * 20)).collect();
+        assert_eq!(dhash(&gradient), Some(u64::MAX));
+        let flat = vec![128u8; 72];
+        assert_eq!(dhash(&flat), Some(0));
+        assert_eq!(distance(u64::MAX, 0), 64);
+        assert!(dhash(&[0u8; 10]).is_none());
+    }
+}
@@ -6,3 +6,6 @@
 pub mod error;
 pub mod traits;
 pub mod contracts;
+pub mod api;
+pub mod cost;
+pub mod fingerprint;
@@ -6,7 +6,7 @@
 use crate::error::FactoryError;
 use crate::contracts::OracleVerdict;
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// トレンド調査ツール (TrendSonar)
 ///
@@ -50,21 +50,31 @@ pub trait VideoGenerator: Send + Sync {
 /// FFmpeg を使って動画・音声・字幕を合成する。
 #[async_trait]
 pub trait MediaEditor: Send + Sync {
-    /// 動画、音声、字幕を合成して最終出力を生成
+    /// 動画、音声、字幕を合成して最終出力を生成 (`secondary_captions` は二言語字幕の副言語行の ASS)
     async fn combine_assets(
         &self,
-        video: &PathBuf,
-        audio: &PathBuf,
+        video: &Path,
+        audio: &Path,
         subtitle: Option<&PathBuf>,
         force_style: Option<String>,
+        secondary_captions: Option<&PathBuf>,
+        overlay: Option<&PathBuf>,
     ) -> Result<PathBuf, FactoryError>;
 
     /// 動画をショート用にリサイズ (9:16, 1080x1920)
-    async fn resize_for_shorts(&self, input: &PathBuf) -> Result<PathBuf, FactoryError>;
+    async fn resize_for_shorts(&self, input: &Path) -> Result<PathBuf, FactoryError>;
 
     /// 複数のメディアクリップを 1つのファイルに結合
     async fn concatenate_clips(&self, clips: Vec<String>, output_name: String) -> Result<String, FactoryError>;
 
+    /// クリップをトランジションでつないで結合する (再エンコード)。つなぎ目で尺は縮まず、合計は元のクリップの和のまま
+    async fn concatenate_with_transition(
+        &self,
+        clips: Vec<String>,
+        output_name: String,
+        transition: &crate::contracts::SceneTransition,
+    ) -> Result<String, FactoryError>;
+
     /// メディアファイルの尺長（秒）を取得する
     async fn get_duration(&self, path: &std::path::Path) -> Result<f32, FactoryError>;
 }
@@ -72,7 +82,7 @@ pub trait MediaEditor: Send + Sync {
 // --- Phase 10: The Automaton ---
 
 /// ジョブステータス
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub enum JobStatus {
     Pending,
     Processing,
@@ -80,14 +90,15 @@ pub enum JobStatus {
     Failed,
 }
 
-impl ToString for JobStatus {
-    fn to_string(&self) -> String {
-        match self {
-            JobStatus::Pending => "Pending".to_string(),
-            JobStatus::Processing => "Processing".to_string(),
-            JobStatus::Completed => "Completed".to_string(),
-            JobStatus::Failed => "Failed".to_string(),
-        }
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            JobStatus::Pending => "Pending",
+            JobStatus::Processing => "Processing",
+            JobStatus::Completed => "Completed",
+            JobStatus::Failed => "Failed",
+        };
+        f.write_str(s)
     }
 }
 
@@ -103,7 +114,7 @@ impl JobStatus {
 }
 
 /// 永続化ジョブ (The Immortal Schema)
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 pub struct Job {
     pub id: String,
     pub topic: String,
@@ -128,6 +139,80 @@ pub struct Job {
     pub published_at: Option<String>,
     /// 多言語出力された動画のリスト (JSON文字列)
     pub output_videos: Option<String>,
+    /// シリーズ制作: このジョブが完了を待つ親ジョブ ID
+    #[serde(default)]
+    pub depends_on: Option<String>,
+    /// 予約投入: この時刻 (RFC 3339, UTC) までは dequeue されない
+    #[serde(default)]
+    pub scheduled_for: Option<String>,
+    /// 持ち込み映像 (Watch folder で取り込んだ手動レンダーの絶対パス)。実行時にプロジェクトへ取り込まれる
+    #[serde(default)]
+    pub footage: Option<String>,
+    /// 所属チャンネル (ブランド)。魂・スタイル・納品先・公開資格情報の切り替えに使う
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    /// SOUL プロファイル名 (None ならチャンネルの SOUL)。カルマの soul_hash もこれに従う
+    #[serde(default)]
+    pub soul: Option<String>,
+}
+
+fn default_channel() -> String {
+    shared::config::DEFAULT_CHANNEL.to_string()
+}
+
+/// 連載シリーズ (番組)。エピソードはジョブとして投入され、話数が自動で振られる
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct Series {
+    pub id: String,
+    pub name: String,
+    /// エピソードのトピック書式 (`{n}` = 話数, `{date}` = 投入日, `{topic}` = 指定トピック)
+    pub topic_template: String,
+    /// 完成動画のタイトル書式 (`{series}`, `{n}`, `{title}`)
+    pub title_template: String,
+    /// 空ならエピソード投入時に指定されたスタイル
+    pub style: String,
+    pub channel: String,
+    pub soul: Option<String>,
+    /// 最後に割り当てた話数
+    pub episode_counter: i64,
+    pub created_at: String,
+}
+
+impl Series {
+    /// `n` 話目のトピック。書式が空なら指定トピックをそのまま使う
+    pub fn episode_topic(&self, n: i64, topic: Option<&str>, date: &str) -> String {
+        let topic = topic.map(str::trim).unwrap_or_default();
+        if self.topic_template.trim().is_empty() {
+            return topic.to_string();
+        }
+        let rendered = self.topic_template
+            .replace("{n}", &n.to_string())
+            .replace("{date}", date)
+            .replace("{topic}", topic);
+        if topic.is_empty() || self.topic_template.contains("{topic}") {
+            rendered
+        } else {
+            format!("{}: {}", rendered, topic)
+        }
+    }
+}
+
+/// シリーズ作成の入力
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct NewSeries {
+    pub name: String,
+    #[serde(default)]
+    pub topic_template: String,
+    /// 空なら `DEFAULT_EPISODE_TITLE`
+    #[serde(default)]
+    pub title_template: String,
+    #[serde(default)]
+    pub style: String,
+    /// 空なら既定チャンネル
+    #[serde(default)]
+    pub channel: String,
+    #[serde(default)]
+    pub soul: Option<String>,
 }
 
 /// ジョブキュー (The Persistent Memory & Samsara)
@@ -136,28 +221,63 @@ pub struct Job {
 /// The Immortal Schema に準拠。
 #[async_trait]
 pub trait JobQueue: Send + Sync {
-    /// 新規ジョブをキューに追加 (Pending)
+    /// 新規ジョブを既定チャンネルのキューに追加 (Pending)
     async fn enqueue(&self, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError>;
 
+    /// 指定チャンネル・SOUL プロファイルのジョブとしてキューに追加 (Pending)
+    async fn enqueue_for_channel(&self, channel: &str, soul: Option<&str>, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError>;
+
+    /// `scheduled_for` (RFC 3339, UTC) まで dequeue されないジョブとして追加する (None なら即時)
+    async fn enqueue_at(&self, channel: &str, soul: Option<&str>, topic: &str, style: &str, karma_directives: Option<&str>, scheduled_for: Option<&str>) -> Result<String, FactoryError>;
+
+    /// 持ち込み映像 (`footage` は絶対パス) を素材にするジョブを追加する
+    async fn enqueue_footage(&self, channel: &str, topic: &str, style: &str, footage: &str) -> Result<String, FactoryError>;
+
+    /// 親ジョブの完了後にのみ実行される子ジョブを追加 (シリーズ制作、チャンネルと SOUL は親を継承)
+    async fn enqueue_child(&self, parent_id: &str, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError>;
+
     /// 指定したIDのジョブを取得する
     async fn fetch_job(&self, job_id: &str) -> Result<Option<Job>, FactoryError>;
 
     /// 次に実行すべき Pending ジョブを 1件取得し、Processing に更新
+    /// (親ジョブが Completed でない子ジョブはスキップ)
     async fn dequeue(&self) -> Result<Option<Job>, FactoryError>;
 
     /// ジョブを完了状態にする
     async fn complete_job(&self, job_id: &str, output_videos: Option<&str>) -> Result<(), FactoryError>;
 
-    /// ジョブを失敗状態にする
+    /// ジョブを失敗状態にする (待機中の子孫ジョブも連鎖的に失敗させる)
     async fn fail_job(&self, job_id: &str, reason: &str) -> Result<(), FactoryError>;
 
+    /// 失敗ジョブを Pending に戻す (requeue_count を加算し、retry_count・カルマ・エラー履歴は残す)。
+    /// 親の失敗に巻き込まれた子孫 (DEPENDENCY_FAILED) も一緒に戻す。戻したジョブ数を返す。
+    async fn retry_job(&self, job_id: &str) -> Result<u64, FactoryError>;
+
+    /// 待機中 (予約を含む) のジョブを取り消す。Failed (`CANCELLED`) にし、待機中の子孫も連鎖的に失敗させる。
+    /// 実行中・終了済みのジョブはエラー。
+    async fn cancel_job(&self, job_id: &str) -> Result<(), FactoryError>;
+
+    /// 一時的な失敗で落ちたジョブを、指数バックオフ (`base_delay_secs` × 2^(n-1)) 後に実行されるよう Pending に戻す。
+    /// 戻したら (通算の失敗回数 n, 再実行時刻) を返す。n が `max_attempts` に達していれば戻さずに None
+    /// (呼び出し側で fail_job する)。
+    async fn defer_retry(&self, job_id: &str, reason: &str, max_attempts: i64, base_delay_secs: i64) -> Result<Option<(i64, String)>, FactoryError>;
+
+    /// 停止のために中断した Processing ジョブを Pending に戻す (失敗回数には数えない)。
+    /// 同じプロジェクト ID で再実行されるので、次回は生成済みの中間成果物から続きを作る。戻せたら true
+    async fn requeue_interrupted(&self, job_id: &str, reason: &str) -> Result<bool, FactoryError>;
+
+    /// Dead-letter: 直近の失敗ジョブを新しい順に取得する
+    async fn fetch_failed_jobs(&self, limit: i64) -> Result<Vec<shared::watchtower::FailedJobSummary>, FactoryError>;
+
     // --- Phase 10-A.5 The Samsara Protocol ---
     /// RAG-Driven Karma Injection: トピックとSkillIDに関連する過去の教訓を抽出する
-    async fn fetch_relevant_karma(&self, topic: &str, skill_id: &str, limit: i64, current_soul_hash: &str) -> Result<Vec<String>, FactoryError>;
+    /// `namespace` (スタイル名やカテゴリ) を渡すと、その名前空間と共通の教訓だけを返す (None なら全名前空間)
+    async fn fetch_relevant_karma(&self, topic: &str, skill_id: &str, namespace: Option<&str>, limit: i64, current_soul_hash: &str) -> Result<Vec<String>, FactoryError>;
 
     /// 抽出された教訓（Karma）を保存する
     /// `karma_type`: 'Technical', 'Creative', 'Synthesized'
-    async fn store_karma(&self, job_id: &str, skill_id: &str, lesson: &str, karma_type: &str, soul_hash: &str) -> Result<(), FactoryError>;
+    /// `namespace`: 教訓が得られたスタイルやカテゴリ (None / 空なら全名前空間に効く共通の教訓)
+    async fn store_karma(&self, job_id: &str, skill_id: &str, namespace: Option<&str>, lesson: &str, karma_type: &str, soul_hash: &str) -> Result<(), FactoryError>;
 
     /// The Zombie Hunter: 一定時間以上 Processing のまま放置されたジョブを Failed に強制移行する
     /// Heartbeat 版: last_heartbeat が timeout 分以上途絶えているものを回収
@@ -166,6 +286,13 @@ pub trait JobQueue: Send + Sync {
     /// クリエイティブ評価 (人間からの非同期フィードバック) を設定する
     async fn set_creative_rating(&self, job_id: &str, rating: i32) -> Result<(), FactoryError>;
 
+    /// レビューを記録し、ジョブが完了済みなら判定を creative_rating にも写す。
+    /// rating を更新できたかを返す
+    async fn record_review(&self, project_id: &str, job_id: Option<&str>, verdict: ReviewVerdict, comment: &str, reviewer: &str) -> Result<bool, FactoryError>;
+
+    /// プロジェクトのレビュー (新しい順)
+    async fn fetch_reviews(&self, project_id: &str) -> Result<Vec<ReviewRecord>, FactoryError>;
+
     /// The Heartbeat Pulse: 長時間処理中のワーカーが生存を証明する
     async fn heartbeat_pulse(&self, job_id: &str) -> Result<(), FactoryError>;
 
@@ -186,14 +313,19 @@ pub trait JobQueue: Send + Sync {
     /// SNS動画IDをジョブに紐付ける (Phase 11: The Anchor Link)
     async fn link_sns_data(&self, job_id: &str, platform: &str, video_id: &str) -> Result<(), FactoryError>;
 
+    /// 取り下げ操作をジョブに記録する。記録済みのジョブは Sentinel の評価対象から外れる。
+    async fn record_takedown(&self, job_id: &str, action: TakedownAction) -> Result<(), FactoryError>;
+
     /// 評価マイルストーンに到達した未評価のジョブを取得する (Phase 11: The Catch-up Logic)
     async fn fetch_jobs_for_evaluation(&self, milestone_days: i64, limit: i64) -> Result<Vec<Job>, FactoryError>;
 
     /// 取得したSNSメトリクスを台帳に記録する (Phase 11: The Metrics Ledger)
     #[allow(clippy::too_many_arguments)]
+    /// `platform` は正規化済みの名前 ("youtube" / "tiktok" / "instagram")
     async fn record_sns_metrics(
         &self,
         job_id: &str,
+        platform: &str,
         milestone_days: i64,
         views: i64,
         likes: i64,
@@ -216,6 +348,25 @@ pub trait JobQueue: Send + Sync {
     /// 最近のジョブをN件取得する
     async fn fetch_recent_jobs(&self, limit: i64) -> Result<Vec<Job>, FactoryError>;
 
+    // --- Series / Episodes ---
+    /// シリーズを作成する (同名があればエラー)
+    async fn create_series(&self, series: &NewSeries) -> Result<Series, FactoryError>;
+
+    /// ID または名前でシリーズを取得する
+    async fn fetch_series(&self, name_or_id: &str) -> Result<Option<Series>, FactoryError>;
+
+    async fn list_series(&self) -> Result<Vec<Series>, FactoryError>;
+
+    /// 次の話数を割り当ててエピソードのジョブを投入する。`(job_id, 話数)` を返す。
+    /// シリーズにスタイルがあればそれを、無ければ `style` を使う。`scheduled_for` は `enqueue_at` と同じ
+    async fn enqueue_episode(&self, series_id: &str, topic: Option<&str>, style: Option<&str>, karma_directives: Option<&str>, scheduled_for: Option<&str>) -> Result<(String, i64), FactoryError>;
+
+    /// シリーズのエピソード (新しい順)
+    async fn fetch_series_episodes(&self, series_id: &str, limit: i64) -> Result<Vec<crate::contracts::EpisodeLink>, FactoryError>;
+
+    /// ジョブがエピソードならシリーズ情報と完了済みの既刊を返す
+    async fn fetch_episode_context(&self, job_id: &str) -> Result<Option<crate::contracts::EpisodeContext>, FactoryError>;
+
     // --- Phase 12: The Agent Evolution (Project Ani) ---
     /// 育成ステータを取得
     async fn get_agent_stats(&self) -> Result<shared::watchtower::AgentStats, FactoryError>;
@@ -227,11 +378,61 @@ pub trait JobQueue: Send + Sync {
     async fn add_intimacy(&self, amount: i32) -> Result<(), FactoryError>;
 }
 
+/// レビューリンクからの判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewVerdict {
+    Approve,
+    Revise,
+}
+
+impl ReviewVerdict {
+    /// ジョブの creative_rating に写す値 (承認 = 最高, 差し戻し = ボツ)
+    pub fn creative_rating(self) -> i32 {
+        match self {
+            ReviewVerdict::Approve => 1,
+            ReviewVerdict::Revise => -1,
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReviewVerdict::Approve => "approve",
+            ReviewVerdict::Revise => "revise",
+        })
+    }
+}
+
+/// 共同作業者によるレビューの記録
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct ReviewRecord {
+    pub project_id: String,
+    pub job_id: Option<String>,
+    pub verdict: ReviewVerdict,
+    pub comment: String,
+    pub reviewer: String,
+    pub created_at: String,
+}
+
+/// ジョブの工程の記録 (`job_stages` テーブルの 1 行)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct JobStageSpan {
+    pub stage: String,
+    /// RFC 3339 (UTC)
+    pub started_at: String,
+    /// 実行中なら None
+    pub ended_at: Option<String>,
+}
+
 /// 評価台帳（sns_metrics_history）のレコード構造体
 #[derive(Debug, Clone)]
 pub struct SnsMetricsRecord {
     pub id: i64,
     pub job_id: String,
+    /// 計測元プラットフォーム (列追加前の行はジョブの sns_platform から補完)
+    pub platform: Option<String>,
     pub milestone_days: i64,
     pub views: i64,
     pub likes: i64,
@@ -240,13 +441,89 @@ pub struct SnsMetricsRecord {
 }
 
 
+/// 公開済み動画への取り下げ操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TakedownAction {
+    /// 非公開化 (動画と統計は残る)
+    SetPrivate,
+    /// プラットフォームから削除
+    Unpublish,
+}
+
+impl TakedownAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TakedownAction::SetPrivate => "set_private",
+            TakedownAction::Unpublish => "unpublish",
+        }
+    }
+}
+
+impl std::fmt::Display for TakedownAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 投稿先プラットフォーム (YouTube 等)
+///
+/// 自律投稿された動画に問題があった場合、同じ認証情報で取り下げるための操作を提供する。
+#[async_trait]
+pub trait Publisher: Send + Sync {
+    /// `Job::sns_platform` と照合されるプラットフォーム名 (例: "youtube")
+    fn platform(&self) -> &str;
+
+    /// 動画を非公開にする
+    async fn set_private(&self, video_id: &str) -> Result<(), FactoryError>;
+
+    /// 動画をプラットフォームから削除する
+    async fn unpublish(&self, video_id: &str) -> Result<(), FactoryError>;
+
+    /// 字幕トラック (WebVTT) を動画に添付する。`lang` は BCP-47 の言語コード
+    async fn upload_captions(&self, video_id: &str, lang: &str, vtt_path: &std::path::Path) -> Result<(), FactoryError> {
+        let _ = (video_id, lang, vtt_path);
+        Err(FactoryError::Infrastructure { reason: format!("{} does not support caption uploads", self.platform()) })
+    }
+
+    /// アクションに応じて `set_private` / `unpublish` を呼び分ける
+    async fn take_down(&self, video_id: &str, action: TakedownAction) -> Result<(), FactoryError> {
+        match action {
+            TakedownAction::SetPrivate => self.set_private(video_id).await,
+            TakedownAction::Unpublish => self.unpublish(video_id).await,
+        }
+    }
+}
+
+/// 納品物の保管先 (ローカルの納品ディレクトリ・S3 / MinIO 等)
+///
+/// 納品ディレクトリに置かれた完成動画を受け取り、リモートから取得できる URL を返す。
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// バックエンド名 (ログ用。例: "local", "s3")
+    fn backend(&self) -> &str;
+
+    /// `local_path` のファイルを `key` (例: `{project_id}/{file_name}`) で保管し、取得用 URL を返す
+    async fn put(&self, key: &str, local_path: &Path) -> Result<String, FactoryError>;
+}
+
+/// テキスト埋め込みモデル (Karma の意味検索用)
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// 埋め込みを識別する名前 (モデルが変わったら古いベクトルは比較できない)
+    fn model_id(&self) -> &str;
+
+    /// テキストをベクトルに変換する
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, FactoryError>;
+}
+
 /// ログ・通知ツール (FactoryLog)
 ///
 /// 稼働ログをSQLiteに記録し、必要に応じてSlack/Discordに通知する。
 #[async_trait]
 pub trait FactoryLogger: Send + Sync {
     /// 動画生成成功をログに記録
-    async fn log_success(&self, video_id: &str, output_path: &PathBuf) -> Result<(), FactoryError>;
+    async fn log_success(&self, video_id: &str, output_path: &Path) -> Result<(), FactoryError>;
 
     /// エラーをログに記録
     async fn log_error(&self, reason: &str) -> Result<(), FactoryError>;
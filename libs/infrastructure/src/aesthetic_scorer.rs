@@ -0,0 +1,125 @@
+//! # AestheticScorer — 生成静止画の美的スコア
+//!
+//! ComfyUI の出力をアニメーション (Ken Burns) に回す前に 1-10 のスコアを付ける。
+//! ローカルの採点サイドカー (LAION aesthetic predictor 等のラッパー) があればそれを使い、
+//! 無ければ軽量な Vision LLM に採点させる。
+
+use crate::vision_judge::generate_with_images;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// スコアの下限・上限 (LAION aesthetic predictor と同じ尺度)
+pub const MIN_AESTHETIC_SCORE: f32 = 1.0;
+pub const MAX_AESTHETIC_SCORE: f32 = 10.0;
+
+enum Backend {
+    /// `POST {url}/score` に画像パスを渡す
+    Sidecar { url: String },
+    Gemini { api_key: String, model: String },
+}
+
+pub struct AestheticScorer {
+    backend: Backend,
+    client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct ScoreRequest<'a> {
+    path: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ScoreResponse {
+    score: f32,
+}
+
+impl ScoreResponse {
+    /// サイドカー / LLM の応答からスコアを取り出し、尺度内に収める
+    fn parse(text: &str) -> Result<f32, FactoryError> {
+        let start = text.find('{');
+        let end = text.rfind('}');
+        let json = match (start, end) {
+            (Some(s), Some(e)) if s < e => &text[s..=e],
+            _ => return Err(FactoryError::Infrastructure { reason: format!("Aesthetic scorer returned no JSON: {}", text) }),
+        };
+        let res: Self = serde_json::from_str(json)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse aesthetic score: {}", e) })?;
+        if !res.score.is_finite() {
+            return Err(FactoryError::Infrastructure { reason: "Aesthetic score is not a number".to_string() });
+        }
+        Ok(res.score.clamp(MIN_AESTHETIC_SCORE, MAX_AESTHETIC_SCORE))
+    }
+}
+
+impl AestheticScorer {
+    /// `scorer_url` が空なら Gemini (`model`) で採点する
+    pub fn new(scorer_url: &str, gemini_api_key: &str, model: &str, timeout: Duration) -> Result<Self, FactoryError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build AestheticScorer HTTP client: {}", e) })?;
+        let backend = if scorer_url.trim().is_empty() {
+            Backend::Gemini { api_key: gemini_api_key.to_string(), model: model.to_string() }
+        } else {
+            Backend::Sidecar { url: scorer_url.trim_end_matches('/').to_string() }
+        };
+        Ok(Self { backend, client })
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            Backend::Sidecar { .. } => "sidecar",
+            Backend::Gemini { .. } => "gemini",
+        }
+    }
+
+    /// 静止画 1 枚を採点する (1.0 - 10.0)
+    pub async fn score(&self, image: &Path) -> Result<f32, FactoryError> {
+        let score = match &self.backend {
+            Backend::Sidecar { url } => {
+                let path = image.to_string_lossy();
+                let res = self.client
+                    .post(format!("{}/score", url))
+                    .json(&ScoreRequest { path: &path })
+                    .send()
+                    .await
+                    .map_err(|e| FactoryError::Network { service: "Aesthetic scorer".into(), reason: e.to_string() })?;
+                if !res.status().is_success() {
+                    return Err(FactoryError::from_http_status("Aesthetic scorer", res.status().as_u16(), ""));
+                }
+                let text = res.text().await
+                    .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid aesthetic scorer response: {}", e) })?;
+                ScoreResponse::parse(&text)?
+            }
+            Backend::Gemini { api_key, model } => {
+                if api_key.is_empty() {
+                    return Err(FactoryError::Infrastructure { reason: "Gemini API Key is missing".to_string() });
+                }
+                let prompt = "You are an art director rating AI-generated stills for a short-video channel.\n\
+                    Rate the attached image's aesthetic quality only (composition, lighting, color, detail, absence of artifacts), \
+                    ignoring its subject matter.\n\
+                    Respond with JSON only: {\"score\": <1.0-10.0>}";
+                let text = generate_with_images(&self.client, api_key, model, prompt, &[PathBuf::from(image)]).await?;
+                ScoreResponse::parse(&text)?
+            }
+        };
+        info!("🎨 AestheticScorer ({}): {:.2} for {}", self.backend_name(), score, image.display());
+        Ok(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_score_clamps_and_rejects_garbage() {
+        assert_eq!(ScoreResponse::parse("{\"score\": 6.5}").unwrap(), 6.5);
+        assert_eq!(ScoreResponse::parse("```json\n{\"score\": 14}\n```").unwrap(), MAX_AESTHETIC_SCORE);
+        assert_eq!(ScoreResponse::parse("{\"score\": -3}").unwrap(), MIN_AESTHETIC_SCORE);
+        assert!(ScoreResponse::parse("beautiful").is_err());
+    }
+}
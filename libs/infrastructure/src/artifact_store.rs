@@ -0,0 +1,368 @@
+//! # ArtifactStore — 納品物の保管先
+//!
+//! 納品ディレクトリに置かれた完成動画を保管し、リモートの指令センターから再生できる URL を返す。
+//! - `local`: 納品ディレクトリのファイルをそのまま指す (配信 URL 未設定なら file:// URL)
+//! - `s3`: AWS S3 / MinIO 等の S3 互換ストアへ SigV4 署名付き PUT でアップロードする
+
+use async_trait::async_trait;
+use chrono::Utc;
+use factory_core::error::FactoryError;
+use factory_core::traits::ArtifactStore;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use shared::config::{FactoryConfig, S3Config};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 設定から保管先を組み立てる (不明なバックエンドは local に退避)
+pub fn build_artifact_store(config: &FactoryConfig) -> Result<Arc<dyn ArtifactStore>, FactoryError> {
+    let cfg = &config.storage;
+    Ok(match cfg.backend.as_str() {
+        "s3" if !cfg.s3.bucket.is_empty() => Arc::new(S3ArtifactStore::new(cfg.s3.clone())?),
+        "s3" => {
+            warn!("⚠️ storage.backend = \"s3\" but storage.s3.bucket is empty. Falling back to local.");
+            Arc::new(LocalArtifactStore::from_config(config))
+        }
+        "local" => Arc::new(LocalArtifactStore::from_config(config)),
+        other => {
+            warn!("⚠️ Unknown storage.backend '{}'. Falling back to local.", other);
+            Arc::new(LocalArtifactStore::from_config(config))
+        }
+    })
+}
+
+/// 納品ディレクトリをそのまま保管先とする
+pub struct LocalArtifactStore {
+    /// (納品ディレクトリ, 配信 URL)。深いディレクトリから順に照合する
+    roots: Vec<(PathBuf, String)>,
+}
+
+impl LocalArtifactStore {
+    pub fn new(export_dir: &str, public_base_url: &str) -> Self {
+        Self { roots: Vec::new() }.with_root(export_dir, public_base_url)
+    }
+
+    /// グローバルの納品ディレクトリに、配信 URL を持つチャンネル別の納品ディレクトリを加える
+    pub fn from_config(config: &FactoryConfig) -> Self {
+        config.channels.keys().fold(Self::new(&config.export_dir, &config.storage.public_base_url), |store, name| {
+            let profile = config.resolve_channel(name);
+            store.with_root(&profile.export_dir, &profile.public_base_url)
+        })
+    }
+
+    /// 納品ディレクトリと配信 URL の組を足す (配信 URL が空なら何もしない)
+    pub fn with_root(mut self, export_dir: &str, public_base_url: &str) -> Self {
+        let public_base_url = public_base_url.trim_end_matches('/');
+        if !public_base_url.is_empty() {
+            self.roots.push((PathBuf::from(export_dir), public_base_url.to_string()));
+            self.roots.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+        }
+        self
+    }
+
+    /// 納品ディレクトリ配下なら配信 URL、それ以外 (または配信 URL 未設定) なら file:// URL
+    fn url_for(&self, local_path: &Path) -> String {
+        for (root, public_base_url) in &self.roots {
+            if let Ok(rel) = local_path.strip_prefix(root) {
+                let rel = rel.to_string_lossy().replace('\\', "/");
+                return format!("{}/{}", public_base_url, uri_encode(&rel, false));
+            }
+        }
+        let abs = std::fs::canonicalize(local_path).unwrap_or_else(|_| local_path.to_path_buf());
+        format!("file://{}", uri_encode(&abs.to_string_lossy(), false))
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for LocalArtifactStore {
+    fn backend(&self) -> &str {
+        "local"
+    }
+
+    async fn put(&self, _key: &str, local_path: &Path) -> Result<String, FactoryError> {
+        if !local_path.exists() {
+            return Err(FactoryError::Infrastructure {
+                reason: format!("Artifact not found: {}", local_path.display()),
+            });
+        }
+        Ok(self.url_for(local_path))
+    }
+}
+
+/// S3 互換オブジェクトストア (AWS S3 / MinIO)
+pub struct S3ArtifactStore {
+    cfg: S3Config,
+    access_key: String,
+    secret_key: String,
+    client: reqwest::Client,
+}
+
+impl S3ArtifactStore {
+    pub fn new(cfg: S3Config) -> Result<Self, FactoryError> {
+        let access_key = non_empty_or_env(&cfg.access_key, "AWS_ACCESS_KEY_ID");
+        let secret_key = non_empty_or_env(&cfg.secret_key, "AWS_SECRET_ACCESS_KEY");
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build S3 HTTP client: {}", e) })?;
+        Ok(Self { cfg, access_key, secret_key, client })
+    }
+
+    /// 接頭辞を付けたオブジェクトキー
+    fn object_key(&self, key: &str) -> String {
+        let prefix = self.cfg.prefix.trim_matches('/');
+        let key = key.trim_start_matches('/');
+        if prefix.is_empty() { key.to_string() } else { format!("{}/{}", prefix, key) }
+    }
+
+    /// アップロード先の URL と Host ヘッダー値
+    fn object_url(&self, object_key: &str) -> Result<(String, String), FactoryError> {
+        let endpoint = reqwest::Url::parse(&self.cfg.endpoint).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Invalid storage.s3.endpoint '{}': {}", self.cfg.endpoint, e),
+        })?;
+        let host = endpoint.host_str().unwrap_or_default();
+        let host = match endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let base_path = endpoint.path().trim_end_matches('/');
+        let encoded_key = uri_encode(object_key, false);
+        if self.cfg.path_style {
+            let url = format!("{}://{}{}/{}/{}", endpoint.scheme(), host, base_path, self.cfg.bucket, encoded_key);
+            Ok((url, host))
+        } else {
+            let host = format!("{}.{}", self.cfg.bucket, host);
+            let url = format!("{}://{}{}/{}", endpoint.scheme(), host, base_path, encoded_key);
+            Ok((url, host))
+        }
+    }
+
+    /// 呼び出し側へ返す URL (public_base_url があればそちらを優先)
+    fn public_url(&self, object_key: &str, upload_url: &str) -> String {
+        if self.cfg.public_base_url.is_empty() {
+            upload_url.to_string()
+        } else {
+            format!("{}/{}", self.cfg.public_base_url.trim_end_matches('/'), uri_encode(object_key, false))
+        }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for S3ArtifactStore {
+    fn backend(&self) -> &str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, local_path: &Path) -> Result<String, FactoryError> {
+        if self.access_key.is_empty() || self.secret_key.is_empty() {
+            return Err(FactoryError::Infrastructure { reason: "S3 credentials are missing".to_string() });
+        }
+        let body = tokio::fs::read(local_path).await.map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to read artifact {}: {}", local_path.display(), e),
+        })?;
+
+        let object_key = self.object_key(key);
+        let (url, host) = self.object_url(&object_key)?;
+        let canonical_uri = reqwest::Url::parse(&url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_else(|_| "/".to_string());
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let content_type = content_type_for(local_path);
+
+        let signed = sign_put(&SigningInput {
+            access_key: &self.access_key,
+            secret_key: &self.secret_key,
+            region: &self.cfg.region,
+            host: &host,
+            canonical_uri: &canonical_uri,
+            content_type,
+            payload_hash: &payload_hash,
+            amz_date: &amz_date,
+            date: &date,
+        })?;
+
+        info!("☁️ ArtifactStore: Uploading {} ({} bytes) -> {}", local_path.display(), body.len(), url);
+        let res = self.client
+            .put(&url)
+            .header("content-type", content_type)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &amz_date)
+            .header("authorization", signed)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "S3".into(), reason: e.to_string() })?;
+        if !res.status().is_success() {
+            let status = res.status().as_u16();
+            let text = res.text().await.unwrap_or_default();
+            return Err(FactoryError::from_http_status("S3", status, &text));
+        }
+        Ok(self.public_url(&object_key, &url))
+    }
+}
+
+fn non_empty_or_env(value: &str, env: &str) -> String {
+    if value.is_empty() { std::env::var(env).unwrap_or_default() } else { value.to_string() }
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        Some("webm") => "video/webm",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// RFC 3986 の非予約文字以外をパーセントエンコードする (`encode_slash = false` なら `/` は残す)
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(input.len());
+    for b in input.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(b as char),
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+struct SigningInput<'a> {
+    access_key: &'a str,
+    secret_key: &'a str,
+    region: &'a str,
+    host: &'a str,
+    canonical_uri: &'a str,
+    content_type: &'a str,
+    payload_hash: &'a str,
+    amz_date: &'a str,
+    date: &'a str,
+}
+
+fn hmac(key: &[u8], data: &str) -> Result<Vec<u8>, FactoryError> {
+    let mut mac = HmacSha256::new_from_slice(key)
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid HMAC key: {}", e) })?;
+    mac.update(data.as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// SigV4 の署名鍵 (日付 → リージョン → サービス → aws4_request の順に HMAC を連鎖)
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Result<Vec<u8>, FactoryError> {
+    let k_date = hmac(format!("AWS4{}", secret_key).as_bytes(), date)?;
+    let k_region = hmac(&k_date, region)?;
+    let k_service = hmac(&k_region, service)?;
+    hmac(&k_service, "aws4_request")
+}
+
+/// PUT Object 用の Authorization ヘッダー値 (AWS Signature Version 4)
+fn sign_put(input: &SigningInput<'_>) -> Result<String, FactoryError> {
+    let signed_headers = "content-type;host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        input.canonical_uri, input.content_type, input.host, input.payload_hash, input.amz_date, signed_headers, input.payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", input.date, input.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        input.amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = signing_key(input.secret_key, input.date, input.region, "s3")?;
+    let signature = hex::encode(hmac(&key, &string_to_sign)?);
+    Ok(format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        input.access_key, scope, signed_headers, signature
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key_matches_aws_reference() {
+        // AWS ドキュメントの導出例 (IAM / us-east-1 / 20120215)
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam").unwrap();
+        assert_eq!(hex::encode(key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[test]
+    fn test_object_url_path_and_virtual_host_style() {
+        let mut cfg = S3Config {
+            endpoint: "http://minio.local:9000".to_string(),
+            bucket: "shorts".to_string(),
+            access_key: "ak".to_string(),
+            secret_key: "sk".to_string(),
+            ..S3Config::default()
+        };
+        let store = S3ArtifactStore::new(cfg.clone()).unwrap();
+        let key = store.object_key("proj 1/final.mp4");
+        assert_eq!(key, "deliveries/proj 1/final.mp4");
+        let (url, host) = store.object_url(&key).unwrap();
+        assert_eq!(url, "http://minio.local:9000/shorts/deliveries/proj%201/final.mp4");
+        assert_eq!(host, "minio.local:9000");
+
+        cfg.endpoint = "https://s3.ap-northeast-1.amazonaws.com".to_string();
+        cfg.path_style = false;
+        cfg.public_base_url = "https://cdn.example.com/".to_string();
+        let store = S3ArtifactStore::new(cfg).unwrap();
+        let (url, host) = store.object_url(&key).unwrap();
+        assert_eq!(url, "https://shorts.s3.ap-northeast-1.amazonaws.com/deliveries/proj%201/final.mp4");
+        assert_eq!(host, "shorts.s3.ap-northeast-1.amazonaws.com");
+        assert_eq!(store.public_url(&key, &url), "https://cdn.example.com/deliveries/proj%201/final.mp4");
+    }
+
+    #[tokio::test]
+    async fn test_local_store_points_at_export_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("2026_job_final.mp4");
+        std::fs::write(&file, b"video").unwrap();
+
+        let store = LocalArtifactStore::new(dir.path().to_str().unwrap(), "https://cdn.example.com/exports/");
+        let url = store.put("job/2026_job_final.mp4", &file).await.unwrap();
+        assert_eq!(url, "https://cdn.example.com/exports/2026_job_final.mp4");
+
+        let store = LocalArtifactStore::new(dir.path().to_str().unwrap(), "");
+        let url = store.put("job/2026_job_final.mp4", &file).await.unwrap();
+        assert!(url.starts_with("file://") && url.ends_with("/2026_job_final.mp4"));
+
+        assert!(store.put("job/missing.mp4", &dir.path().join("missing.mp4")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_store_serves_channel_export_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let calm_dir = dir.path().join("calm");
+        std::fs::create_dir_all(&calm_dir).unwrap();
+        let file = calm_dir.join("2026_job_final.mp4");
+        std::fs::write(&file, b"video").unwrap();
+
+        let mut config = FactoryConfig { export_dir: dir.path().to_string_lossy().to_string(), ..FactoryConfig::default() };
+        config.storage.public_base_url = "https://cdn.example.com/exports".to_string();
+        let store = LocalArtifactStore::from_config(&config);
+        let url = store.put("job/2026_job_final.mp4", &file).await.unwrap();
+        assert_eq!(url, "https://cdn.example.com/exports/calm/2026_job_final.mp4");
+
+        config.channels.insert("calm".to_string(), shared::config::ChannelProfile {
+            export_dir: calm_dir.to_string_lossy().to_string(),
+            public_base_url: "https://calm.example.com/".to_string(),
+            ..Default::default()
+        });
+        let store = LocalArtifactStore::from_config(&config);
+        let url = store.put("job/2026_job_final.mp4", &file).await.unwrap();
+        assert_eq!(url, "https://calm.example.com/2026_job_final.mp4");
+    }
+}
@@ -0,0 +1,178 @@
+//! # BGM Manifest — BGM ライブラリの目録
+//!
+//! `resources/bgm/bgm_manifest.toml` に曲ごとのムード・BPM・ライセンス・ループ可否を書く。
+//! コンセプト段階が選んだムードで `SoundMixer` が曲を選び、目録に無い・合う曲が無い場合は
+//! 従来どおり `{category}.mp3` → `default.mp3` に落ちる。
+//!
+//! ```toml
+//! [[track]]
+//! file = "calm_piano.mp3"
+//! moods = ["calm", "hopeful"]
+//! bpm = 72
+//! license = "CC BY 4.0 — Jane Doe"
+//! loopable = true
+//! categories = ["tech"]
+//! ```
+//!
+//! 曲の追加は `shorts-factory bgm import`、目録との突き合わせは `shorts-factory bgm scan`。
+
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "bgm_manifest.toml";
+/// ライブラリとして扱う拡張子
+pub(crate) const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "m4a", "flac"];
+
+/// 1 曲分のメタデータ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BgmTrack {
+    /// ライブラリ内のファイル名
+    pub file: String,
+    /// ムードタグ (小文字で比較する)
+    #[serde(default)]
+    pub moods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<u32>,
+    #[serde(default)]
+    pub license: String,
+    /// 継ぎ目なくループできる曲か (false なら尺が足りる場合だけ選び、末尾はフェードで切る)
+    #[serde(default)]
+    pub loopable: bool,
+    /// 優先するカテゴリ (空ならどのカテゴリでも)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
+impl BgmTrack {
+    pub fn has_mood(&self, mood: &str) -> bool {
+        self.moods.iter().any(|m| m.eq_ignore_ascii_case(mood.trim()))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BgmManifest {
+    #[serde(default, rename = "track")]
+    pub tracks: Vec<BgmTrack>,
+}
+
+impl BgmManifest {
+    /// `dir/bgm_manifest.toml` を読む (無ければ空の目録)
+    pub fn load(dir: &Path) -> Result<Self, FactoryError> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to read {}: {}", path.display(), e),
+        })?;
+        toml::from_str(&content).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to parse {}: {}", path.display(), e),
+        })
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), FactoryError> {
+        let path = dir.join(MANIFEST_FILE);
+        let content = toml::to_string_pretty(self).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to serialize {}: {}", path.display(), e),
+        })?;
+        std::fs::write(&path, content).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write {}: {}", path.display(), e),
+        })
+    }
+
+    /// 目録にあるムードの一覧 (コンセプト段階に選ばせる候補)
+    pub fn moods(&self) -> Vec<String> {
+        let mut moods: Vec<String> = self.tracks.iter().flat_map(|t| t.moods.iter().map(|m| m.to_lowercase())).collect();
+        moods.sort();
+        moods.dedup();
+        moods
+    }
+
+    /// ムードが合う曲を優先順に返す (カテゴリも合う曲が先。同順位は目録の順)
+    pub fn candidates(&self, mood: &str, category: &str) -> Vec<&BgmTrack> {
+        let mut matched: Vec<&BgmTrack> = self.tracks.iter().filter(|t| t.has_mood(mood)).collect();
+        matched.sort_by_key(|t| !t.categories.iter().any(|c| c.eq_ignore_ascii_case(category)));
+        matched
+    }
+
+    /// 同じファイル名の曲があれば差し替え、無ければ追加する
+    pub fn upsert(&mut self, track: BgmTrack) {
+        match self.tracks.iter_mut().find(|t| t.file == track.file) {
+            Some(existing) => *existing = track,
+            None => self.tracks.push(track),
+        }
+    }
+
+    /// (目録に無い音声ファイル, 目録にあるがファイルが無い曲)
+    pub fn scan(&self, dir: &Path) -> (Vec<PathBuf>, Vec<String>) {
+        let mut untracked: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().and_then(|e| e.to_str()).is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str())))
+                    .filter(|p| {
+                        let name = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        !self.tracks.iter().any(|t| t.file == name)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        untracked.sort();
+        let missing = self.tracks.iter().filter(|t| !dir.join(&t.file).is_file()).map(|t| t.file.clone()).collect();
+        (untracked, missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[[track]]
+file = "calm_piano.mp3"
+moods = ["Calm", "hopeful"]
+bpm = 72
+license = "CC BY 4.0"
+loopable = true
+
+[[track]]
+file = "tech_pulse.mp3"
+moods = ["calm", "energetic"]
+license = "Royalty free"
+categories = ["tech"]
+"#;
+
+    #[test]
+    fn test_candidates_prefer_category_and_moods_are_listed() {
+        let manifest: BgmManifest = toml::from_str(MANIFEST).unwrap();
+        assert_eq!(manifest.moods(), vec!["calm", "energetic", "hopeful"]);
+        let files = |mood, category| manifest.candidates(mood, category).iter().map(|t| t.file.as_str()).collect::<Vec<_>>();
+        assert_eq!(files("calm", "tech"), vec!["tech_pulse.mp3", "calm_piano.mp3"]);
+        assert_eq!(files("CALM ", "news"), vec!["calm_piano.mp3", "tech_pulse.mp3"]);
+        assert!(files("dark", "tech").is_empty());
+        assert!(!manifest.tracks[1].loopable);
+    }
+
+    #[test]
+    fn test_scan_and_upsert_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("calm_piano.mp3"), b"").unwrap();
+        std::fs::write(dir.path().join("new_track.WAV"), b"").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"").unwrap();
+        let mut manifest: BgmManifest = toml::from_str(MANIFEST).unwrap();
+        let (untracked, missing) = manifest.scan(dir.path());
+        assert_eq!(untracked, vec![dir.path().join("new_track.WAV")]);
+        assert_eq!(missing, vec!["tech_pulse.mp3"]);
+
+        manifest.upsert(BgmTrack { file: "new_track.WAV".into(), moods: vec!["dark".into()], ..Default::default() });
+        manifest.upsert(BgmTrack { file: "calm_piano.mp3".into(), moods: vec!["calm".into()], loopable: true, ..Default::default() });
+        manifest.save(dir.path()).unwrap();
+        let reloaded = BgmManifest::load(dir.path()).unwrap();
+        assert_eq!(reloaded.tracks.len(), 3);
+        assert_eq!(reloaded.tracks[0].moods, vec!["calm"]);
+        assert_eq!(reloaded.candidates("dark", "tech")[0].file, "new_track.WAV");
+        assert!(BgmManifest::load(&dir.path().join("missing")).unwrap().tracks.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+//! # BitrateSelector — 最終エンコードの適応ビットレート
+//!
+//! 固定 6000 kbps だと、静かなスライドショーは無駄に重く、動きの多い映像はブロックノイズが出る。
+//! 解像度・尺・素材の複雑さ (素材のビット/画素) から目標を決め、容量上限で頭打ちにする。
+//! VMAF のスポットチェック結果は倍率として次回以降の目標に反映する。
+
+use shared::config::EncodingConfig;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 1080x1920 の画素数 (reference_kbps の基準)
+const REFERENCE_PIXELS: f64 = 1080.0 * 1920.0;
+/// 「平均的な複雑さ」とみなす素材のビット/画素/フレーム
+const REFERENCE_BPP: f64 = 0.08;
+/// 容量上限に対する安全率 (コンテナのオーバーヘッド・VBR の揺れ)
+const SIZE_HEADROOM: f64 = 0.92;
+/// 保持するエンコード記録の件数
+const HISTORY_LEN: usize = 50;
+
+/// ffprobe で測った素材の性質
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceStats {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+    pub duration_secs: f64,
+    pub bitrate_kbps: f64,
+}
+
+impl SourceStats {
+    /// 素材の複雑さ (1.0 = 平均)。素材が重く符号化されているほど細部・動きが多いとみなす
+    pub fn complexity(&self) -> f64 {
+        let pixels_per_sec = self.width as f64 * self.height as f64 * self.fps;
+        if pixels_per_sec <= 0.0 || self.bitrate_kbps <= 0.0 {
+            return 1.0;
+        }
+        (self.bitrate_kbps * 1000.0 / pixels_per_sec / REFERENCE_BPP).clamp(0.6, 1.6)
+    }
+}
+
+/// 1 回のエンコード結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeSample {
+    pub kbps: u32,
+    pub complexity: f64,
+    pub duration_secs: f64,
+    pub size_mb: f64,
+    pub vmaf: Option<f64>,
+}
+
+/// 目標ビットレートの決定と結果のフィードバック
+///
+/// 倍率・記録は書きかけの状態にならないため、ロックが poisoned でも中身をそのまま使う
+pub struct BitrateSelector {
+    cfg: EncodingConfig,
+    /// VMAF から学んだ補正倍率 (0.7〜1.5)
+    multiplier: Mutex<f64>,
+    history: Mutex<VecDeque<EncodeSample>>,
+}
+
+impl BitrateSelector {
+    pub fn new(cfg: EncodingConfig) -> Self {
+        Self { cfg, multiplier: Mutex::new(1.0), history: Mutex::new(VecDeque::new()) }
+    }
+
+    pub fn config(&self) -> &EncodingConfig {
+        &self.cfg
+    }
+
+    /// 尺に対して容量上限に収まる映像ビットレートの上限 (kbps)
+    pub fn size_cap_kbps(&self, duration_secs: f64) -> u32 {
+        if duration_secs <= 0.0 || self.cfg.max_file_mb <= 0.0 {
+            return u32::MAX;
+        }
+        let total_kbps = self.cfg.max_file_mb * 8.0 * 1024.0 * SIZE_HEADROOM / duration_secs;
+        (total_kbps - self.cfg.audio_kbps as f64).max(300.0) as u32
+    }
+
+    /// 出力解像度と素材から目標ビットレート (kbps) を決める
+    pub fn select(&self, out_width: u32, out_height: u32, source: &SourceStats) -> u32 {
+        let multiplier = *self.multiplier.lock().unwrap_or_else(|e| e.into_inner());
+        let scale = out_width as f64 * out_height as f64 / REFERENCE_PIXELS;
+        let target = self.cfg.reference_kbps as f64 * scale * source.complexity() * multiplier;
+        let target = (target.round() as u32).clamp(self.cfg.min_kbps, self.cfg.max_kbps.max(self.cfg.min_kbps));
+        // 容量上限はプラットフォーム側の拒否に直結するため min_kbps より優先する
+        target.min(self.size_cap_kbps(source.duration_secs))
+    }
+
+    /// 結果を記録し、VMAF があれば補正倍率を更新する。更新後の倍率を返す
+    pub fn record(&self, sample: EncodeSample) -> f64 {
+        let mut multiplier = self.multiplier.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(vmaf) = sample.vmaf {
+            if vmaf < self.cfg.vmaf_min {
+                *multiplier *= 1.15;
+            } else if vmaf > self.cfg.vmaf_max {
+                *multiplier *= 0.95;
+            }
+            *multiplier = multiplier.clamp(0.7, 1.5);
+        }
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        history.push_back(sample);
+        while history.len() > HISTORY_LEN {
+            history.pop_front();
+        }
+        *multiplier
+    }
+
+    /// 直近のエンコード記録 (古い順)
+    pub fn recent(&self) -> Vec<EncodeSample> {
+        self.history.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+}
+
+/// ffmpeg libvmaf の出力 (`VMAF score: 93.42`) からスコアを取り出す
+pub fn parse_vmaf_score(stderr: &str) -> Option<f64> {
+    stderr
+        .lines()
+        .rev()
+        .find_map(|line| line.split("VMAF score:").nth(1))
+        .and_then(|s| s.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(bitrate_kbps: f64, duration_secs: f64) -> SourceStats {
+        SourceStats { width: 1080, height: 1920, fps: 30.0, duration_secs, bitrate_kbps }
+    }
+
+    #[test]
+    fn test_target_follows_resolution_complexity_and_size_cap() {
+        let selector = BitrateSelector::new(EncodingConfig::default());
+        // 平均的な素材 (0.08 bpp ≒ 4977 kbps) なら基準どおり
+        let average = source(REFERENCE_PIXELS * 30.0 * REFERENCE_BPP / 1000.0, 30.0);
+        assert_eq!(selector.select(1080, 1920, &average), 6000);
+        assert_eq!(selector.select(720, 1280, &average), 2667);
+        // 重い素材は上げ、軽い素材は下げる (補正は 0.6〜1.6 倍で頭打ち)
+        assert_eq!(selector.select(1080, 1920, &source(20000.0, 30.0)), 9600);
+        assert_eq!(selector.select(1080, 1920, &source(500.0, 30.0)), 3600);
+        // 長尺では容量上限が min_kbps より優先される: 250MB / 900 秒
+        let cap = selector.size_cap_kbps(900.0);
+        assert_eq!(cap, 1965);
+        assert_eq!(selector.select(1080, 1920, &source(20000.0, 900.0)), cap);
+    }
+
+    #[test]
+    fn test_vmaf_feedback_adjusts_later_targets() {
+        let selector = BitrateSelector::new(EncodingConfig::default());
+        let average = source(REFERENCE_PIXELS * 30.0 * REFERENCE_BPP / 1000.0, 30.0);
+        let sample = |vmaf| EncodeSample { kbps: 6000, complexity: 1.0, duration_secs: 30.0, size_mb: 22.0, vmaf };
+        assert_eq!(selector.record(sample(Some(85.0))), 1.15);
+        assert_eq!(selector.select(1080, 1920, &average), 6900);
+        selector.record(sample(Some(99.0)));
+        selector.record(sample(None));
+        assert!(selector.select(1080, 1920, &average) < 6900);
+        assert_eq!(selector.recent().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_vmaf_score() {
+        let stderr = "[Parsed_libvmaf_1 @ 0x1] VMAF score: 93.412345\n";
+        assert_eq!(parse_vmaf_score(stderr), Some(93.412345));
+        assert_eq!(parse_vmaf_score("no score"), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! # Chaos — 回復機構を確かめるための障害注入
+//!
+//! サーキットブレーカー・Poison Pill・ゾンビ回収・途中再開は、本番で障害が起きるまで動いたか分からない。
+//! `chaos` feature 付きでビルドすると、ComfyUI のタイムアウト・Gemini や SNS API の 500・ディスクフル・
+//! Watchtower との UDS 切断を設定した確率で起こせる (設定は Serve の `/api/chaos`、admin 限定)。
+//! feature なしのビルドでは `inject` は常に `Ok(())` で、規則を置く場所も持たない。
+
+use factory_core::error::FactoryError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `chaos` feature 付きでビルドされているか
+pub const ENABLED: bool = cfg!(feature = "chaos");
+
+/// 注入できる障害
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Fault {
+    /// ComfyUI の実行がタイムアウトした (ワークフローは投入しない)
+    ComfyTimeout,
+    /// Gemini が 500 を返した (Concept / 字幕圧縮 / Oracle)
+    GeminiError,
+    /// SNS のメトリクス API が 500 を返した (Sentinel)
+    SnsError,
+    /// 納品・プロジェクト作成・Heartbeat の書き込みで ENOSPC
+    DiskFull,
+    /// Watchtower への送信中に UDS が切れた
+    UdsDisconnect,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 5] = [Fault::ComfyTimeout, Fault::GeminiError, Fault::SnsError, Fault::DiskFull, Fault::UdsDisconnect];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ComfyTimeout => "comfy_timeout",
+            Self::GeminiError => "gemini_error",
+            Self::SnsError => "sns_error",
+            Self::DiskFull => "disk_full",
+            Self::UdsDisconnect => "uds_disconnect",
+        }
+    }
+
+    /// 本物の障害と同じ種類のエラー (再試行の分類も本物どおり)
+    pub fn error(&self) -> FactoryError {
+        match self {
+            Self::ComfyTimeout => FactoryError::ComfyTimeout { timeout_secs: 0 },
+            Self::GeminiError => FactoryError::from_http_status("Gemini", 500, "injected by chaos"),
+            Self::SnsError => FactoryError::from_http_status("SNS API", 500, "injected by chaos"),
+            Self::DiskFull => FactoryError::OsError { source: std::io::Error::from_raw_os_error(28).into() },
+            Self::UdsDisconnect => FactoryError::Network { service: "Watchtower UDS".into(), reason: "disconnect injected by chaos".into() },
+        }
+    }
+}
+
+impl std::str::FromStr for Fault {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.as_str() == s.trim())
+            .ok_or_else(|| format!("unknown fault '{}' (comfy_timeout, gemini_error, sns_error, disk_full, uds_disconnect)", s))
+    }
+}
+
+/// 1 種類の障害の起こし方
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FaultRule {
+    /// 呼び出しごとに障害を起こす確率 (0.0 - 1.0)
+    pub probability: f64,
+    /// あと何回起こすか (None なら解除するまで)
+    #[serde(default)]
+    pub remaining: Option<u32>,
+}
+
+impl FaultRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(format!("probability = {} is out of range (0 - 1)", self.probability));
+        }
+        Ok(())
+    }
+
+    /// `roll` (0.0 以上 1.0 未満) で障害を起こすか決め、起こすなら残り回数を減らす
+    #[cfg(any(feature = "chaos", test))]
+    fn fires(&mut self, roll: f64) -> bool {
+        if self.remaining == Some(0) || roll >= self.probability {
+            return false;
+        }
+        if let Some(n) = self.remaining.as_mut() {
+            *n -= 1;
+        }
+        true
+    }
+}
+
+#[cfg(feature = "chaos")]
+static RULES: std::sync::Mutex<std::collections::BTreeMap<Fault, FaultRule>> = std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+/// 障害の注入点。規則に当たれば `fault` のエラーを返す
+pub fn inject(fault: Fault) -> Result<(), FactoryError> {
+    #[cfg(feature = "chaos")]
+    {
+        let mut rules = RULES.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rule) = rules.get_mut(&fault) {
+            if rule.fires(rand::random::<f64>()) {
+                if rule.remaining == Some(0) {
+                    rules.remove(&fault);
+                }
+                tracing::warn!("🐒 Chaos: Injecting {}", fault.as_str());
+                return Err(fault.error());
+            }
+        }
+    }
+    #[cfg(not(feature = "chaos"))]
+    let _ = fault;
+    Ok(())
+}
+
+/// 規則を置き換える
+#[cfg(feature = "chaos")]
+pub fn set_rule(fault: Fault, rule: FaultRule) -> Result<(), String> {
+    rule.validate()?;
+    tracing::warn!("🐒 Chaos: {} armed (p={}, remaining={:?})", fault.as_str(), rule.probability, rule.remaining);
+    RULES.lock().unwrap_or_else(|e| e.into_inner()).insert(fault, rule);
+    Ok(())
+}
+
+/// 規則を外す (None なら全部)
+#[cfg(feature = "chaos")]
+pub fn clear(fault: Option<Fault>) {
+    let mut rules = RULES.lock().unwrap_or_else(|e| e.into_inner());
+    match fault {
+        Some(fault) => {
+            rules.remove(&fault);
+        }
+        None => rules.clear(),
+    }
+}
+
+/// 有効な規則の一覧
+#[cfg(feature = "chaos")]
+pub fn rules() -> std::collections::BTreeMap<Fault, FaultRule> {
+    RULES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_fires_by_probability_and_counts_down() {
+        let mut rule = FaultRule { probability: 0.5, remaining: Some(2) };
+        assert!(!rule.fires(0.7));
+        assert!(rule.fires(0.1));
+        assert!(rule.fires(0.49));
+        assert_eq!(rule.remaining, Some(0));
+        assert!(!rule.fires(0.0));
+
+        let mut always = FaultRule { probability: 1.0, remaining: None };
+        assert!((0..10).all(|_| always.fires(0.99)));
+        assert!(FaultRule { probability: 1.5, remaining: None }.validate().is_err());
+    }
+
+    #[test]
+    fn test_faults_parse_and_keep_real_retry_classes() {
+        assert_eq!("disk_full".parse::<Fault>(), Ok(Fault::DiskFull));
+        assert!("meteor".parse::<Fault>().is_err());
+        assert!(Fault::ComfyTimeout.error().is_retryable());
+        assert!(Fault::GeminiError.error().is_retryable());
+        assert!(!Fault::DiskFull.error().is_retryable());
+        assert!(Fault::DiskFull.error().to_string().contains("No space left"));
+    }
+}
@@ -5,18 +5,209 @@
 
 use async_trait::async_trait;
 use bastion::net_guard::ShieldClient;
-use factory_core::contracts::{VideoRequest, VideoResponse};
+use factory_core::contracts::{CharacterRef, ModelStack, SafetyRejection, VideoRequest, VideoResponse};
 use factory_core::error::FactoryError;
 use factory_core::traits::{AgentAct, VideoGenerator};
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
+use crate::safety_classifier::SafetyClassifier;
+use crate::video_encoder::EncoderProfile;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::process::Stdio;
 use tokio::process::Command;
 
+/// ファクトリーが投入したプロンプトの client_id 接頭辞 (手動投入のワークフローと区別する)
+pub const FACTORY_CLIENT_PREFIX: &str = "aiome-";
+/// 動画クリップとして扱う出力の拡張子 (VHS_VideoCombine / SaveAnimatedWEBM 等)
+pub const VIDEO_OUTPUT_EXTS: &[&str] = &["mp4", "webm", "mov", "mkv", "gif"];
+
+/// ComfyUI キュー上の 1 件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ComfyQueueItem {
+    pub prompt_id: String,
+    /// キュー投入順の番号
+    pub number: i64,
+    pub client_id: Option<String>,
+    /// ファクトリーが投入したものか (false なら手動投入など外部のワークフロー)
+    pub from_factory: bool,
+}
+
+/// ComfyUI の実行履歴の要約
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ComfyHistoryItem {
+    pub prompt_id: String,
+    pub from_factory: bool,
+    /// "success" / "error" など (ComfyUI の status_str)
+    pub status: String,
+    /// 実行時間 (execution_start から終了メッセージまで)
+    pub duration_secs: Option<f64>,
+}
+
+/// `/queue` と `/history` の要約
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ComfyQueueSnapshot {
+    pub running: Vec<ComfyQueueItem>,
+    pub pending: Vec<ComfyQueueItem>,
+    pub recent: Vec<ComfyHistoryItem>,
+}
+
+/// ComfyUI にインストールされているモデル (`/object_info` のローダーの選択肢)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ComfyModels {
+    pub checkpoints: Vec<String>,
+    pub loras: Vec<String>,
+    /// スタイルが指定しているのに ComfyUI に無いモデル (スタイル名 → ファイル名)
+    #[serde(default)]
+    pub missing_by_style: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+impl ComfyModels {
+    pub fn from_object_info(object_info: &serde_json::Value) -> Self {
+        let choices = |class: &str, field: &str| -> Vec<String> {
+            object_info[class]["input"]["required"][field][0]
+                .as_array()
+                .map(|names| names.iter().filter_map(|n| n.as_str().map(String::from)).collect())
+                .unwrap_or_default()
+        };
+        Self {
+            checkpoints: choices("CheckpointLoaderSimple", "ckpt_name"),
+            loras: choices("LoraLoader", "lora_name"),
+            missing_by_style: Default::default(),
+        }
+    }
+
+    /// スタイルの指定のうち ComfyUI に無いもの
+    pub fn missing(&self, stack: &ModelStack) -> Vec<String> {
+        let mut missing: Vec<String> = stack.checkpoint.iter().filter(|c| !self.checkpoints.contains(c)).cloned().collect();
+        missing.extend(stack.loras.iter().filter(|l| !self.loras.contains(&l.name)).map(|l| l.name.clone()));
+        missing
+    }
+}
+
+/// 実行中のプロンプトの進捗 (WebSocket の `executing` / `progress` メッセージ)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ComfyProgress {
+    pub prompt_id: String,
+    pub node_id: String,
+    /// ノードのタイトル (`_meta.title`) か class_type
+    pub node: String,
+    /// サンプラー等のステップ (ノード開始時は 0/0)
+    pub step: u32,
+    pub total: u32,
+}
+
+impl ComfyProgress {
+    /// `executing` / `progress` メッセージを解析する。`current_node` は直近に開始したノード
+    ///
+    /// 他のプロンプトのメッセージと、`executing` の node=null (完了) は None。
+    pub fn from_event(
+        event: &serde_json::Value,
+        prompt_id: &str,
+        node_names: &std::collections::HashMap<String, String>,
+        current_node: &mut Option<String>,
+    ) -> Option<Self> {
+        let data = event.get("data")?;
+        if data.get("prompt_id").and_then(|v| v.as_str()).is_some_and(|p| p != prompt_id) {
+            return None;
+        }
+        let (node_id, step, total) = match event.get("type")?.as_str()? {
+            "executing" => {
+                let node_id = data.get("node")?.as_str()?.to_string();
+                *current_node = Some(node_id.clone());
+                (node_id, 0, 0)
+            }
+            "progress" => {
+                let node_id = data.get("node").and_then(|v| v.as_str()).map(str::to_string).or_else(|| current_node.clone())?;
+                (node_id, data.get("value")?.as_u64()? as u32, data.get("max")?.as_u64()? as u32)
+            }
+            _ => return None,
+        };
+        let node = node_names.get(&node_id).cloned().unwrap_or_else(|| node_id.clone());
+        Some(Self { prompt_id: prompt_id.to_string(), node_id, node, step, total })
+    }
+
+    /// ワークフローのノード ID → 表示名 (`_meta.title`、無ければ class_type)
+    pub fn node_names(workflow: &serde_json::Value) -> std::collections::HashMap<String, String> {
+        workflow
+            .as_object()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter_map(|(id, node)| {
+                        let name = node.pointer("/_meta/title").and_then(|t| t.as_str())
+                            .or_else(|| node.get("class_type").and_then(|c| c.as_str()))?;
+                        Some((id.clone(), name.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl ComfyQueueSnapshot {
+    /// `/queue` のレスポンス (`queue_running` / `queue_pending`) を解析する
+    pub fn parse_queue(queue: &serde_json::Value) -> (Vec<ComfyQueueItem>, Vec<ComfyQueueItem>) {
+        let items = |key: &str| -> Vec<ComfyQueueItem> {
+            queue.get(key)
+                .and_then(|v| v.as_array())
+                .map(|arr| arr.iter().filter_map(parse_queue_entry).collect())
+                .unwrap_or_default()
+        };
+        let mut pending = items("queue_pending");
+        pending.sort_by_key(|i| i.number);
+        (items("queue_running"), pending)
+    }
+
+    /// `/history` のレスポンスを新しい順に最大 `limit` 件解析する
+    pub fn parse_history(history: &serde_json::Value, limit: usize) -> Vec<ComfyHistoryItem> {
+        let Some(map) = history.as_object() else { return Vec::new() };
+        let mut entries: Vec<(i64, ComfyHistoryItem)> = map
+            .iter()
+            .map(|(prompt_id, entry)| {
+                let queued = entry.get("prompt").and_then(parse_queue_entry);
+                let status = entry.get("status");
+                let timestamps: Vec<f64> = status
+                    .and_then(|s| s.get("messages"))
+                    .and_then(|m| m.as_array())
+                    .map(|msgs| msgs.iter().filter_map(|m| m.get(1)?.get("timestamp")?.as_f64()).collect())
+                    .unwrap_or_default();
+                let duration_secs = match (timestamps.first(), timestamps.last()) {
+                    (Some(start), Some(end)) if timestamps.len() > 1 => Some((end - start) / 1000.0),
+                    _ => None,
+                };
+                let item = ComfyHistoryItem {
+                    prompt_id: prompt_id.clone(),
+                    from_factory: queued.as_ref().is_some_and(|q| q.from_factory),
+                    status: status
+                        .and_then(|s| s.get("status_str"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    duration_secs,
+                };
+                (queued.map(|q| q.number).unwrap_or(0), item)
+            })
+            .collect();
+        entries.sort_by_key(|(number, _)| std::cmp::Reverse(*number));
+        entries.into_iter().take(limit).map(|(_, item)| item).collect()
+    }
+}
+
+/// キューの 1 要素 `[number, prompt_id, prompt, extra_data, outputs]` を解析する
+fn parse_queue_entry(entry: &serde_json::Value) -> Option<ComfyQueueItem> {
+    let number = entry.get(0)?.as_i64()?;
+    let prompt_id = entry.get(1)?.as_str()?.to_string();
+    let client_id = entry.get(3)
+        .and_then(|extra| extra.get("client_id"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let from_factory = client_id.as_deref().is_some_and(|c| c.starts_with(FACTORY_CLIENT_PREFIX));
+    Some(ComfyQueueItem { prompt_id, number, client_id, from_factory })
+}
+
 /// ComfyUI API クライアント
 #[derive(Clone)]
 pub struct ComfyBridgeClient {
@@ -28,6 +219,12 @@ pub struct ComfyBridgeClient {
     pub base_dir: PathBuf,
     /// タイムアウト（秒）
     pub timeout_secs: u64,
+    /// 実行中の進捗の配信先 (None なら捨てる)
+    pub progress: Option<tokio::sync::broadcast::Sender<ComfyProgress>>,
+    /// 出力画像の NSFW / 暴力表現チェック (None なら検査しない)
+    pub safety: Option<Arc<SafetyClassifier>>,
+    /// Ken Burns で使う映像エンコーダー (既定は videotoolbox)
+    pub encoder: Arc<EncoderProfile>,
 }
 
 impl ComfyBridgeClient {
@@ -37,9 +234,30 @@ impl ComfyBridgeClient {
             api_url: api_url.into(),
             base_dir: base_dir.into(),
             timeout_secs,
+            progress: None,
+            safety: None,
+            encoder: Arc::new(EncoderProfile::default()),
         }
     }
 
+    /// 起動時に検出したエンコーダーを使う
+    pub fn with_encoder(mut self, encoder: Arc<EncoderProfile>) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// 出力画像を `classifier` で検査し、不適切なものは棄却して描き直す
+    pub fn with_safety(mut self, classifier: SafetyClassifier) -> Self {
+        self.safety = Some(Arc::new(classifier));
+        self
+    }
+
+    /// ノードの開始・ステップ進捗を `tx` へ流す (テレメトリ・Discord 向け)
+    pub fn with_progress(mut self, tx: tokio::sync::broadcast::Sender<ComfyProgress>) -> Self {
+        self.progress = Some(tx);
+        self
+    }
+
     /// Zero-Copy: 指定された入力素材を ComfyUI の `input/` フォルダに直接コピーし、一意なファイル名を返す
     pub async fn inject_input_file(&self, src_path: &std::path::Path, tracking_id: &str) -> Result<String, FactoryError> {
         let file_name = src_path.file_name()
@@ -88,6 +306,15 @@ impl ComfyBridgeClient {
         }
     }
 
+    /// CLIPTextEncode ノードのテキストを取り出す (他のノード種別なら None)
+    fn clip_text(workflow: &serde_json::Value, node_id: &str) -> Option<String> {
+        let node = workflow.get(node_id)?;
+        if node.get("class_type").and_then(|v| v.as_str()) != Some("CLIPTextEncode") {
+            return None;
+        }
+        node.get("inputs")?.get("text")?.as_str().map(str::to_string)
+    }
+
     /// KSampler ノードの positive/negative 入力に繋がっている CLIPTextEncode ノードを特定し、
     /// Pony V6 XL 専用の品質タグ (score_9...) と 拒絶呪文 (uncanny, nsfw...) を強制挿入する。
     pub fn enforce_pony_quality_and_safety(workflow: &mut serde_json::Value) -> Result<(), FactoryError> {
@@ -101,6 +328,7 @@ impl ComfyBridgeClient {
         
         let mut negative_node_ids = std::collections::HashSet::new();
         let mut positive_node_ids = std::collections::HashSet::new();
+        let mut sampler_pairs = Vec::new();
         
         if let Some(nodes) = workflow.as_object() {
             for (_, node) in nodes {
@@ -108,16 +336,19 @@ impl ComfyBridgeClient {
                     if class_type == "KSampler" || class_type == "KSamplerAdvanced" {
                         if let Some(inputs) = node.get("inputs") {
                             // Negative
-                            if let Some(negative) = inputs.get("negative").and_then(|v| v.as_array()) {
-                                if let Some(neg_id) = negative.first().and_then(|v| v.as_str()) {
-                                    negative_node_ids.insert(neg_id.to_string());
-                                }
+                            let neg_id = inputs.get("negative").and_then(|v| v.as_array())
+                                .and_then(|a| a.first()).and_then(|v| v.as_str());
+                            if let Some(neg_id) = neg_id {
+                                negative_node_ids.insert(neg_id.to_string());
                             }
                             // Positive
-                            if let Some(positive) = inputs.get("positive").and_then(|v| v.as_array()) {
-                                if let Some(pos_id) = positive.first().and_then(|v| v.as_str()) {
-                                    positive_node_ids.insert(pos_id.to_string());
-                                }
+                            let pos_id = inputs.get("positive").and_then(|v| v.as_array())
+                                .and_then(|a| a.first()).and_then(|v| v.as_str());
+                            if let Some(pos_id) = pos_id {
+                                positive_node_ids.insert(pos_id.to_string());
+                            }
+                            if let (Some(p), Some(n)) = (pos_id, neg_id) {
+                                sampler_pairs.push((p.to_string(), n.to_string()));
                             }
                         }
                     }
@@ -165,9 +396,156 @@ impl ComfyBridgeClient {
             }
         }
         
+        // Prompt Lint: 後付けしたタグとの重複・矛盾を解消し、CLIP のトークン上限に収める
+        let protected: Vec<String> = crate::prompt_linter::tokenize(neg_curse);
+        let protected: Vec<&str> = protected.iter().map(String::as_str).collect();
+        for (pos_id, neg_id) in sampler_pairs {
+            if pos_id == neg_id {
+                continue;
+            }
+            let (Some(pos_text), Some(neg_text)) = (Self::clip_text(workflow, &pos_id), Self::clip_text(workflow, &neg_id)) else {
+                continue;
+            };
+            let (pos, neg, report) = crate::prompt_linter::lint_pair(&pos_text, &neg_text, &protected);
+            if !report.is_clean() {
+                info!(
+                    "🧹 Prompt lint (nodes {}/{}): {} duplicates, {} conflicts, {} truncated",
+                    pos_id, neg_id, report.duplicates_removed, report.conflicts_resolved.len(), report.truncated.len()
+                );
+            }
+            Self::inject_node_value(workflow, &pos_id, "text", serde_json::Value::String(pos))?;
+            Self::inject_node_value(workflow, &neg_id, "text", serde_json::Value::String(neg))?;
+        }
+
         Ok(())
     }
 
+    /// キャラクター素材をワークフローに注入する (プロンプト注入後・品質タグ強制前に呼ぶ)
+    ///
+    /// - 固定タグを `[API_PROMPT]` の先頭へ
+    /// - LoRA は Checkpoint の直後に LoraLoader を直列に差し込み、model/clip の参照を付け替える
+    /// - 参照画像は `[API_CHARACTER_REF]` (LoadImage) へ、重みは `[API_IPADAPTER]` へ。ノードが無いワークフローでは LoRA とタグのみ
+    pub fn apply_characters(workflow: &mut serde_json::Value, characters: &[CharacterRef], reference_input: Option<&str>) -> Result<(), FactoryError> {
+        if characters.is_empty() {
+            return Ok(());
+        }
+
+        let tags: Vec<&str> = characters.iter().map(|c| c.prompt.trim()).filter(|p| !p.is_empty()).collect();
+        if !tags.is_empty() {
+            if let Some(prompt_node) = Self::find_node_id_by_title(workflow, "[API_PROMPT]") {
+                let text = Self::clip_text(workflow, &prompt_node).unwrap_or_default();
+                Self::inject_node_value(workflow, &prompt_node, "text", serde_json::Value::String(format!("{}, {}", tags.join(", "), text)))?;
+            }
+        }
+
+        let loras: Vec<(String, &str, f32)> = characters
+            .iter()
+            .filter_map(|c| c.lora.as_ref().map(|(lora, strength)| (format!("[CHARACTER_LORA] {}", c.name), lora.as_str(), *strength)))
+            .collect();
+        if !loras.is_empty() && !Self::splice_loras(workflow, &loras) {
+            warn!("🧍 ComfyBridge: No CheckpointLoaderSimple node. Character LoRAs skipped.");
+        }
+
+        if let (Some(image), Some((_, weight))) = (reference_input, characters.iter().find_map(|c| c.reference_image.as_ref())) {
+            match Self::find_node_id_by_title(workflow, "[API_CHARACTER_REF]") {
+                Some(ref_node) => {
+                    Self::inject_node_value(workflow, &ref_node, "image", serde_json::Value::String(image.to_string()))?;
+                    if let Some(ip_node) = Self::find_node_id_by_title(workflow, "[API_IPADAPTER]") {
+                        Self::inject_node_value(workflow, &ip_node, "weight", serde_json::json!(weight))?;
+                    }
+                }
+                None => warn!("🧍 ComfyBridge: Workflow has no [API_CHARACTER_REF] node. Reference image ignored."),
+            }
+        }
+        Ok(())
+    }
+
+    /// スタイルのモデル構成を注入する (キャラクターより前に呼び、スタイルの LoRA をチェックポイント側に置く)
+    pub fn apply_model_stack(workflow: &mut serde_json::Value, models: &ModelStack) -> Result<(), FactoryError> {
+        if models.is_empty() {
+            return Ok(());
+        }
+        let Some(ckpt) = Self::checkpoint_node(workflow) else {
+            warn!("🎛️ ComfyBridge: No CheckpointLoaderSimple node. Style model stack skipped.");
+            return Ok(());
+        };
+        if let Some(checkpoint) = &models.checkpoint {
+            Self::inject_node_value(workflow, &ckpt, "ckpt_name", serde_json::Value::String(checkpoint.clone()))?;
+        }
+        let loras: Vec<(String, &str, f32)> = models
+            .loras
+            .iter()
+            .map(|l| (format!("[STYLE_LORA] {}", l.name), l.name.as_str(), l.strength))
+            .collect();
+        Self::splice_loras(workflow, &loras);
+        Ok(())
+    }
+
+    fn checkpoint_node(workflow: &serde_json::Value) -> Option<String> {
+        workflow.as_object().and_then(|nodes| {
+            nodes.iter()
+                .find(|(_, n)| n.get("class_type").and_then(|v| v.as_str()) == Some("CheckpointLoaderSimple"))
+                .map(|(id, _)| id.clone())
+        })
+    }
+
+    /// LoraLoader を (title, lora_name, strength) の順に、現在 model/clip を供給しているノードの直後へ直列に差し込む。
+    /// 既に LoRA が差し込まれていればその後ろに続ける。Checkpoint が無ければ false
+    fn splice_loras(workflow: &mut serde_json::Value, loras: &[(String, &str, f32)]) -> bool {
+        let Some(ckpt) = Self::checkpoint_node(workflow) else { return false };
+        // 既存の LoRA チェーンの末尾を探す (チェックポイントの model 出力を受ける LoraLoader をたどる)
+        let mut tail = ckpt;
+        while let Some(next) = workflow.as_object().and_then(|nodes| {
+            nodes.iter()
+                .find(|(_, n)| {
+                    n.get("class_type").and_then(|v| v.as_str()) == Some("LoraLoader")
+                        && n["inputs"]["model"] == serde_json::json!([tail, 0])
+                })
+                .map(|(id, _)| id.clone())
+        }) {
+            tail = next;
+        }
+        let mut model_src = serde_json::json!([tail, 0]);
+        let mut clip_src = serde_json::json!([tail, 1]);
+        for (title, lora_name, strength) in loras {
+            let id = Self::next_node_id(workflow);
+            let nodes = workflow.as_object_mut().expect("workflow with a checkpoint is an object");
+            for node in nodes.values_mut() {
+                if let Some(inputs) = node.get_mut("inputs").and_then(|v| v.as_object_mut()) {
+                    for value in inputs.values_mut() {
+                        if *value == model_src {
+                            *value = serde_json::json!([id, 0]);
+                        } else if *value == clip_src {
+                            *value = serde_json::json!([id, 1]);
+                        }
+                    }
+                }
+            }
+            nodes.insert(id.clone(), serde_json::json!({
+                "class_type": "LoraLoader",
+                "inputs": {
+                    "lora_name": lora_name,
+                    "strength_model": strength,
+                    "strength_clip": strength,
+                    "model": model_src,
+                    "clip": clip_src,
+                },
+                "_meta": { "title": title }
+            }));
+            model_src = serde_json::json!([id, 0]);
+            clip_src = serde_json::json!([id, 1]);
+        }
+        true
+    }
+
+    /// 既存の数値ノード ID の最大値 + 1
+    fn next_node_id(workflow: &serde_json::Value) -> String {
+        let max = workflow.as_object()
+            .map(|nodes| nodes.keys().filter_map(|k| k.parse::<u64>().ok()).max().unwrap_or(0))
+            .unwrap_or(0);
+        (max + 1).to_string()
+    }
+
     pub async fn clear_comfy_queue(&self) -> Result<(), FactoryError> {
         let http_base = self.api_url.replace("ws://", "http://").replace("/ws", "");
         let url = format!("{}/queue", http_base);
@@ -176,8 +554,42 @@ impl ComfyBridgeClient {
         match self.shield.post(&url, &payload).await {
             Ok(res) if res.status().is_success() => Ok(()),
             Ok(res) => Err(FactoryError::ComfyConnection { url, source: anyhow::anyhow!("Failed to clear queue: HTTP {}", res.status()) }),
-            Err(e) => Err(FactoryError::ComfyConnection { url, source: e.into() }),
+            Err(e) => Err(FactoryError::ComfyConnection { url, source: e }),
+        }
+    }
+
+    /// ComfyUI 自身のキュー状態と直近の実行履歴を取得する
+    pub async fn fetch_queue_snapshot(&self, history_limit: usize) -> Result<ComfyQueueSnapshot, FactoryError> {
+        let http_base = self.api_url.replace("ws://", "http://").replace("/ws", "");
+        let queue = self.get_json(&format!("{}/queue", http_base)).await?;
+        let history = self.get_json(&format!("{}/history?max_items={}", http_base, history_limit)).await?;
+        let (running, pending) = ComfyQueueSnapshot::parse_queue(&queue);
+        Ok(ComfyQueueSnapshot {
+            running,
+            pending,
+            recent: ComfyQueueSnapshot::parse_history(&history, history_limit),
+        })
+    }
+
+    /// 稼働中の ComfyUI が知っているノード定義 (`/object_info`) を取得する
+    pub async fn fetch_object_info(&self) -> Result<serde_json::Value, FactoryError> {
+        let http_base = self.api_url.replace("ws://", "http://").replace("/ws", "");
+        self.get_json(&format!("{}/object_info", http_base)).await
+    }
+
+    /// ComfyUI にあるチェックポイントと LoRA の一覧
+    pub async fn list_models(&self) -> Result<ComfyModels, FactoryError> {
+        Ok(ComfyModels::from_object_info(&self.fetch_object_info().await?))
+    }
+
+    async fn get_json(&self, url: &str) -> Result<serde_json::Value, FactoryError> {
+        let res = self.shield.get(url).await
+            .map_err(|e| FactoryError::ComfyConnection { url: url.to_string(), source: e })?;
+        if !res.status().is_success() {
+            return Err(FactoryError::ComfyConnection { url: url.to_string(), source: anyhow::anyhow!("HTTP {}", res.status()) });
         }
+        res.json().await
+            .map_err(|e| FactoryError::ComfyConnection { url: url.to_string(), source: e.into() })
     }
 
     /// ComfyUI の output ディレクトリにある、指定した接頭辞 (job_id) を持つすべてのファイルを削除する
@@ -200,14 +612,85 @@ impl ComfyBridgeClient {
     }
 }
 
-#[async_trait]
-impl VideoGenerator for ComfyBridgeClient {
-    async fn generate_video(
+impl ComfyBridgeClient {
+    /// ワークフローを実行する。`characters` が空でなければ見た目固定の素材を注入する
+    ///
+    /// 安全性チェックが有効なら出力 (動画はフレーム) を検査し、棄却した出力は消してシードを変えて描き直す。
+    /// 上限まで描き直しても通らなければ `UnsafeOutput` で失敗する。判定できなかった出力も採用しない。
+    pub async fn run_workflow(
         &self,
         prompt: &str,
         workflow_id: &str,
         input_image: Option<&std::path::Path>,
+        characters: &[CharacterRef],
+        models: &ModelStack,
+        seed: Option<u64>,
     ) -> Result<VideoResponse, FactoryError> {
+        let mut rejections: Vec<SafetyRejection> = Vec::new();
+        loop {
+            // 描き直しはシードをずらす (シード指定があればそこから決定的に)
+            let attempt_seed = seed.map(|s| s.wrapping_add(rejections.len() as u64));
+            let mut res = self.render_workflow(prompt, workflow_id, input_image, characters, models, attempt_seed).await?;
+            let output = std::path::Path::new(&res.output_path);
+            let Some(classifier) = self.safety.as_ref() else {
+                res.safety_rejections = rejections;
+                return Ok(res);
+            };
+            let verdict = if is_video_output(output) { classifier.classify_video(output).await } else { classifier.classify(output).await };
+            let verdict = match verdict {
+                Ok(verdict) => verdict,
+                Err(e) => {
+                    // 検査していない出力は納品に回さない
+                    warn!("🛡️ ComfyBridge: Safety check failed, discarding output: {}", e);
+                    self.delete_output_debris(&res.job_id);
+                    return Err(e);
+                }
+            };
+            if !classifier.is_flagged(&verdict) {
+                res.safety_rejections = rejections;
+                return Ok(res);
+            }
+
+            self.delete_output_debris(&res.job_id);
+            rejections.push(SafetyRejection {
+                workflow_id: workflow_id.to_string(),
+                prompt: prompt.to_string(),
+                score: verdict.score,
+                labels: verdict.labels,
+            });
+            let attempts = rejections.len() as u32;
+            if attempts > classifier.max_regenerations {
+                let labels: Vec<&str> = rejections.iter().flat_map(|r| r.labels.iter().map(String::as_str)).collect();
+                return Err(FactoryError::UnsafeOutput {
+                    reason: format!("workflow '{}' produced flagged output {} time(s) [{}] for prompt: {}", workflow_id, attempts, labels.join(", "), prompt),
+                });
+            }
+            warn!("🛡️ ComfyBridge: Output flagged as unsafe ({:.2}). Regenerating ({}/{})...", verdict.score, attempts, classifier.max_regenerations);
+        }
+    }
+
+    /// 静止画を `[API_IMAGE_INPUT]` に渡してアップスケール用ワークフローを実行する
+    /// (元画像は検査済みなので安全性チェックは通さない)
+    pub async fn upscale_image(&self, input: &std::path::Path, workflow_id: &str) -> Result<VideoResponse, FactoryError> {
+        self.render_workflow("", workflow_id, Some(input), &[], &ModelStack::default(), None).await
+    }
+
+    /// ワークフローを 1 回実行して出力を取り出す
+    async fn render_workflow(
+        &self,
+        prompt: &str,
+        workflow_id: &str,
+        input_image: Option<&std::path::Path>,
+        characters: &[CharacterRef],
+        models: &ModelStack,
+        seed: Option<u64>,
+    ) -> Result<VideoResponse, FactoryError> {
+        crate::chaos::inject(crate::chaos::Fault::ComfyTimeout)?;
+        // ID はそのままファイル名になるので、パスとして使えないものはキューに触る前に断る
+        if !factory_core::contracts::is_valid_workflow_id(workflow_id) {
+            return Err(FactoryError::ComfyWorkflowFailed { reason: format!("Invalid workflow id '{}'", workflow_id) });
+        }
+
         // 1. The Zombie Queue 排除 (Pre-flight Queue Purge)
         self.clear_comfy_queue().await?;
 
@@ -223,14 +706,17 @@ impl VideoGenerator for ComfyBridgeClient {
                 .map_err(|e| FactoryError::ComfyWorkflowFailed { reason: format!("Invalid JSON: {}", e) })?
         };
 
-        // 3. ランダムな追跡用ジョブIDとシードの発行
+        // 3. 追跡用ジョブIDとシードの発行 (シード未指定ならランダム)
         let job_id = uuid::Uuid::new_v4().to_string();
-        let seed: u64 = rand::random();
+        let seed: u64 = seed.unwrap_or_else(rand::random);
 
         // 4. The Trinity Injection (3点動的注入)
-        let prompt_node = Self::find_node_id_by_title(&workflow, "[API_PROMPT]")
-            .ok_or_else(|| FactoryError::ComfyWorkflowFailed { reason: "Missing [API_PROMPT] node".into() })?;
-        Self::inject_node_value(&mut workflow, &prompt_node, "text", serde_json::Value::String(prompt.to_string()))?;
+        // プロンプトを取らない後処理ワークフロー (アップスケール等) は空のプロンプトで呼ぶ
+        match Self::find_node_id_by_title(&workflow, "[API_PROMPT]") {
+            Some(prompt_node) => Self::inject_node_value(&mut workflow, &prompt_node, "text", serde_json::Value::String(prompt.to_string()))?,
+            None if prompt.is_empty() => {}
+            None => return Err(FactoryError::ComfyWorkflowFailed { reason: "Missing [API_PROMPT] node".into() }),
+        }
 
         if let Some(sampler_node) = Self::find_node_id_by_title(&workflow, "[API_SAMPLER]") {
             Self::inject_node_value(&mut workflow, &sampler_node, "seed", serde_json::Value::Number(seed.into()))?;
@@ -241,6 +727,19 @@ impl VideoGenerator for ComfyBridgeClient {
             Self::inject_node_value(&mut workflow, &save_node, "filename_prefix", serde_json::Value::String(job_id.clone()))?;
         }
 
+        // 4.1 Style Models: チェックポイントの差し替えとスタイルの LoRA
+        Self::apply_model_stack(&mut workflow, models)?;
+
+        // 4.2 Character Consistency: 固定タグ・LoRA・参照画像 (品質タグの強制より前に入れ、Lint の対象にする)
+        let mut injected_reference_name = None;
+        if let Some((reference, _)) = characters.iter().find_map(|c| c.reference_image.as_ref()) {
+            match self.inject_input_file(std::path::Path::new(reference), &job_id).await {
+                Ok(name) => injected_reference_name = Some(name),
+                Err(e) => warn!("🧍 ComfyBridge: Failed to stage character reference {}: {}", reference, e),
+            }
+        }
+        Self::apply_characters(&mut workflow, characters, injected_reference_name.as_deref())?;
+
         // 4.5 TOS Guillotine: 物理的な NSFW/Gore 遮断 & 品質タグ強制 (プロンプト注入後に適用)
         Self::enforce_pony_quality_and_safety(&mut workflow)?;
 
@@ -255,7 +754,8 @@ impl VideoGenerator for ComfyBridgeClient {
         }
 
         // 6. WebSocket 接続確立 (The Blind Submission 回避)
-        let ws_url = format!("{}?clientId={}", self.api_url, job_id);
+        let client_id = format!("{}{}", FACTORY_CLIENT_PREFIX, job_id);
+        let ws_url = format!("{}?clientId={}", self.api_url, client_id);
         let (mut ws_stream, _) = tokio_tungstenite::connect_async(&ws_url)
             .await.map_err(|e| FactoryError::ComfyConnection { url: ws_url.clone(), source: e.into() })?;
 
@@ -264,13 +764,17 @@ impl VideoGenerator for ComfyBridgeClient {
         let prompt_url = format!("{}/prompt", http_base);
         let payload = serde_json::json!({
             "prompt": workflow,
-            "client_id": job_id
+            "client_id": client_id
         });
         
         let post_res = self.shield.post(&prompt_url, &payload).await
-            .map_err(|e| FactoryError::ComfyConnection { url: prompt_url.clone(), source: e.into() })?;
+            .map_err(|e| FactoryError::ComfyConnection { url: prompt_url.clone(), source: e })?;
             
+        if post_res.status().is_server_error() {
+            return Err(FactoryError::ComfyConnection { url: prompt_url, source: anyhow::anyhow!("POST /prompt failed: {}", post_res.status()) });
+        }
         if !post_res.status().is_success() {
+            // 400 はノード検証エラー (ワークフロー不正) なので再試行しても通らない
             return Err(FactoryError::ComfyWorkflowFailed { reason: format!("POST /prompt failed: {}", post_res.status()) });
         }
         
@@ -286,12 +790,14 @@ impl VideoGenerator for ComfyBridgeClient {
         use futures_util::StreamExt;
         let timeout_duration = std::time::Duration::from_secs(self.timeout_secs);
         let mut final_filename = None;
+        let node_names = ComfyProgress::node_names(&workflow);
+        let mut current_node = None;
         
         let ws_loop = async {
             while let Some(msg) = ws_stream.next().await {
                 let msg = match msg {
                     Ok(m) => m,
-                    Err(e) => return Err(FactoryError::ComfyWorkflowFailed { reason: format!("WS Error: {}", e) }),
+                    Err(e) => return Err(FactoryError::ComfyConnection { url: ws_url.clone(), source: e.into() }),
                 };
                 
                 if let tokio_tungstenite::tungstenite::Message::Text(text) = msg {
@@ -302,24 +808,26 @@ impl VideoGenerator for ComfyBridgeClient {
                         if msg_type == Some("execution_error") {
                             return Err(FactoryError::ComfyWorkflowFailed { reason: format!("ComfyUI reported execution_error: {:?}", data) });
                         }
+
+                        if let Some(tx) = &self.progress {
+                            if let Some(progress) = ComfyProgress::from_event(&event, &prompt_id, &node_names, &mut current_node) {
+                                // 誰も聞いていなければ無視
+                                let _ = tx.send(progress);
+                            }
+                        }
                         
-                        if msg_type == Some("executed") && data.and_then(|d| d.get("prompt_id")).and_then(|v| v.as_str()) == Some(&prompt_id) {
-                            if let Some(d) = data {
-                                // 9. The Output Divergence: 画像、GIF、動画の全フォールバック解析
-                                if let Some(output) = d.get("output") {
-                                    for key in ["images", "gifs", "videos"] {
-                                        if let Some(arr) = output.get(key).and_then(|v| v.as_array()) {
-                                            if let Some(first) = arr.first() {
-                                                if let Some(fname) = first.get("filename").and_then(|v| v.as_str()) {
-                                                    final_filename = Some(fname.to_string());
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
+                        let ours = data.and_then(|d| d.get("prompt_id")).and_then(|v| v.as_str()) == Some(&prompt_id);
+                        if msg_type == Some("executed") && ours {
+                            // 9. The Output Divergence: 動画 (AnimateDiff 等) を優先し、静止画は最後の出力として控えておく
+                            if let Some((fname, is_video)) = data.and_then(|d| d.get("output")).and_then(output_filename) {
+                                final_filename = Some(fname);
+                                if is_video {
+                                    break; // 処理完了
                                 }
                             }
-                            break; // 処理完了
+                        }
+                        if msg_type == Some("executing") && ours && data.is_some_and(|d| d.get("node").is_some_and(|n| n.is_null())) {
+                            break; // プロンプト全体の実行完了
                         }
                     }
                 }
@@ -329,11 +837,12 @@ impl VideoGenerator for ComfyBridgeClient {
 
         // タイムアウト監視を実行
         let res = tokio::time::timeout(timeout_duration, ws_loop).await
-            .map_err(|_| FactoryError::ComfyWorkflowFailed { reason: "WebSocket Timeout while waiting for 'executed'".into() })?;
+            .map_err(|_| FactoryError::ComfyTimeout { timeout_secs: self.timeout_secs })
+            .and_then(|r| r);
             
         // 10. The Input Debris (Input Garbage Collection)
         // タイムアウトや直前のエラー等に関わらず、Inputが作られていた場合は確実に清掃する
-        if let Some(injected_name) = injected_input_name {
+        for injected_name in injected_input_name.into_iter().chain(injected_reference_name) {
             let input_file_path = self.base_dir.join("input").join(&injected_name);
             if input_file_path.exists() {
                 if let Err(e) = std::fs::remove_file(&input_file_path) {
@@ -354,9 +863,23 @@ impl VideoGenerator for ComfyBridgeClient {
         Ok(VideoResponse {
             output_path: out_path.to_string_lossy().to_string(),
             job_id,
+            safety_rejections: Vec::new(),
         })
     }
 
+}
+
+#[async_trait]
+impl VideoGenerator for ComfyBridgeClient {
+    async fn generate_video(
+        &self,
+        prompt: &str,
+        workflow_id: &str,
+        input_image: Option<&std::path::Path>,
+    ) -> Result<VideoResponse, FactoryError> {
+        self.run_workflow(prompt, workflow_id, input_image, &[], &ModelStack::default(), None).await
+    }
+
     async fn health_check(&self) -> Result<bool, FactoryError> {
         // ws://127.0.0.1:8188/ws などの末尾の /ws を削って http に直すための簡易処理
         // ただし、今の `health_check` で `/system_stats` を叩くには REST HTTP が必要。
@@ -367,7 +890,7 @@ impl VideoGenerator for ComfyBridgeClient {
             Ok(res) => Ok(res.status().is_success()),
             Err(e) => Err(FactoryError::ComfyConnection {
                 url: http_base,
-                source: e.into(),
+                source: e,
             }),
         }
     }
@@ -398,7 +921,7 @@ impl AgentAct for ComfyBridgeClient {
         _jail: &bastion::fs_guard::Jail,
     ) -> Result<Self::Output, FactoryError> {
         let input_path = input.input_image.as_deref().map(std::path::Path::new);
-        self.generate_video(&input.prompt, &input.workflow_id, input_path).await
+        self.run_workflow(&input.prompt, &input.workflow_id, input_path, &input.characters, &input.models, input.seed).await
     }
 }
 
@@ -424,6 +947,21 @@ impl Tool for ComfyBridgeClient {
     }
 }
 
+/// 出力ファイルが動画 (GIF・WebM 等を含む) かどうか
+pub fn is_video_output(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| VIDEO_OUTPUT_EXTS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// `executed` イベントの output から保存ファイル名と動画かどうかを取り出す (動画を静止画より優先)
+fn output_filename(output: &serde_json::Value) -> Option<(String, bool)> {
+    ["videos", "gifs", "images"].iter().find_map(|key| {
+        let fname = output.get(*key)?.as_array()?.first()?.get("filename")?.as_str()?;
+        Some((fname.to_string(), is_video_output(std::path::Path::new(fname))))
+    })
+}
+
 impl ComfyBridgeClient {
     /// 静止画に対して Ken Burns エフェクト (Pan & Zoom) を適用し、滑らかな動画クリップを生成する
     /// VE-01: 数学的なイージング関数による脱カクつき実装
@@ -445,25 +983,25 @@ impl ComfyBridgeClient {
         let total_frames = (30.0 * duration_secs) as usize;
         let zoom_expr = format!("1+{}*sin(on/{}*3.14159/2)", style.zoom_speed * 100.0, total_frames); 
         
-        // M4 Pro Optimization: Hardware acceleration + Proper Vertical Handling
+        // Proper Vertical Handling
         // First scale the image to a reasonable size (2K height) to allow zoom without extreme overhead.
         // 8K scale was causing massive slowdowns in the software zoompan filter.
         let filter = format!(
             "scale=-1:2160,zoompan=z='{}':d={}:s=1080x1920:fps=30,format=yuv420p",
             zoom_expr, total_frames
         );
+        let filter = self.encoder.video_filter(Some(filter)).unwrap_or_default();
         
-        info!("MediaForge: Applying hardware-accelerated Ken Burns (M4 Pro)...");
+        info!("MediaForge: Applying Ken Burns ({})...", self.encoder.encoder.codec());
 
         let status = Command::new("ffmpeg")
             .arg("-y")
+            .args(self.encoder.input_args())
             .arg("-loop").arg("1")
             .arg("-i").arg(image_path)
             .arg("-vf").arg(filter)
-            .arg("-c:v").arg("h264_videotoolbox") // M4 Pro Hardware Accel
-            .arg("-b:v").arg("8000k")
+            .args(self.encoder.output_args(self.encoder.intermediate_kbps()))
             .arg("-t").arg(duration_secs.to_string())
-            .arg("-pix_fmt").arg("yuv420p")
             .arg(&output_path)
             .stdin(Stdio::null()) // Avoid SIGTTIN on background execution
             .status()
@@ -478,4 +1016,136 @@ impl ComfyBridgeClient {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_workflow_ids_that_escape_the_workflow_dir_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shield = Arc::new(ShieldClient::builder().build().unwrap());
+        // 断るのはキューの掃除より前なので、ComfyUI は立っていなくてよい
+        let comfy = ComfyBridgeClient::new(shield, "http://127.0.0.1:9", tmp.path(), 5);
+        for id in ["../../etc/passwd", "a/b", "", "shorts v1"] {
+            let err = comfy.generate_video("prompt", id, None).await.unwrap_err();
+            assert!(matches!(err, FactoryError::ComfyWorkflowFailed { .. }), "{:?} -> {}", id, err);
+        }
+        assert!(factory_core::contracts::is_valid_workflow_id("shorts_standard_v1"));
+    }
+
+    #[test]
+    fn test_queue_snapshot_distinguishes_factory_prompts() {
+        let queue = serde_json::json!({
+            "queue_running": [[7, "p-run", {}, {"client_id": "aiome-1234"}, []]],
+            "queue_pending": [[9, "p-late", {}, {}, []], [8, "p-manual", {}, {"client_id": "browser"}, []]],
+        });
+        let (running, pending) = ComfyQueueSnapshot::parse_queue(&queue);
+        assert!(running[0].from_factory);
+        assert_eq!(pending.iter().map(|i| i.prompt_id.as_str()).collect::<Vec<_>>(), vec!["p-manual", "p-late"]);
+        assert!(pending.iter().all(|i| !i.from_factory));
 
+        let history = serde_json::json!({
+            "old": {"prompt": [1, "old", {}, {"client_id": "aiome-x"}, []], "status": {"status_str": "success",
+                "messages": [["execution_start", {"timestamp": 1000}], ["execution_success", {"timestamp": 4500}]]}},
+            "new": {"prompt": [2, "new", {}, {}, []], "status": {"status_str": "error", "messages": []}},
+        });
+        let recent = ComfyQueueSnapshot::parse_history(&history, 10);
+        assert_eq!(recent[0].prompt_id, "new");
+        assert_eq!(recent[1].duration_secs, Some(3.5));
+        assert!(recent[1].from_factory);
+    }
+
+    #[test]
+    fn test_video_outputs_win_over_preview_images() {
+        let combined = serde_json::json!({
+            "images": [{"filename": "preview_00001_.png"}],
+            "gifs": [{"filename": "AnimateDiff_00001.mp4", "format": "video/h264-mp4"}],
+        });
+        assert_eq!(output_filename(&combined), Some(("AnimateDiff_00001.mp4".to_string(), true)));
+        let still = serde_json::json!({"images": [{"filename": "ComfyUI_00001_.png"}]});
+        assert_eq!(output_filename(&still), Some(("ComfyUI_00001_.png".to_string(), false)));
+        assert_eq!(output_filename(&serde_json::json!({})), None);
+        assert!(is_video_output(std::path::Path::new("clip.GIF")));
+        assert!(!is_video_output(std::path::Path::new("scene_0.png")));
+    }
+
+    #[test]
+    fn test_progress_events_name_nodes_and_ignore_other_prompts() {
+        let workflow = serde_json::json!({
+            "3": {"class_type": "KSampler", "inputs": {}},
+            "9": {"class_type": "SaveImage", "_meta": {"title": "[API_SAVE]"}, "inputs": {}},
+        });
+        let names = ComfyProgress::node_names(&workflow);
+        let mut current = None;
+        let started = serde_json::json!({"type": "executing", "data": {"node": "3", "prompt_id": "p1"}});
+        let step = serde_json::json!({"type": "progress", "data": {"value": 14, "max": 30, "prompt_id": "p1"}});
+        let other = serde_json::json!({"type": "progress", "data": {"value": 1, "max": 20, "node": "3", "prompt_id": "p2"}});
+        let done = serde_json::json!({"type": "executing", "data": {"node": null, "prompt_id": "p1"}});
+
+        let p = ComfyProgress::from_event(&started, "p1", &names, &mut current).unwrap();
+        assert_eq!((p.node.as_str(), p.step, p.total), ("KSampler", 0, 0));
+        let p = ComfyProgress::from_event(&step, "p1", &names, &mut current).unwrap();
+        assert_eq!((p.node_id.as_str(), p.node.as_str(), p.step, p.total), ("3", "KSampler", 14, 30));
+        assert!(ComfyProgress::from_event(&other, "p1", &names, &mut current).is_none());
+        assert!(ComfyProgress::from_event(&done, "p1", &names, &mut current).is_none());
+        assert_eq!(names["9"], "[API_SAVE]");
+    }
+
+    #[test]
+    fn test_apply_characters_splices_lora_chain_and_reference() {
+        let mut workflow = serde_json::json!({
+            "3": {"class_type": "KSampler", "inputs": {"model": ["4", 0], "positive": ["6", 0], "negative": ["7", 0]}},
+            "4": {"class_type": "CheckpointLoaderSimple", "inputs": {"ckpt_name": "pony.safetensors"}},
+            "6": {"class_type": "CLIPTextEncode", "inputs": {"text": "a cat on the moon", "clip": ["4", 1]}, "_meta": {"title": "[API_PROMPT]"}},
+            "7": {"class_type": "CLIPTextEncode", "inputs": {"text": "lowres", "clip": ["4", 1]}},
+            "10": {"class_type": "LoadImage", "inputs": {"image": "placeholder.png"}, "_meta": {"title": "[API_CHARACTER_REF]"}},
+            "11": {"class_type": "IPAdapterAdvanced", "inputs": {"weight": 1.0}, "_meta": {"title": "[API_IPADAPTER]"}},
+        });
+        let characters = vec![
+            CharacterRef { name: "mika".into(), prompt: "mika, pink hair".into(), lora: Some(("mika.safetensors".into(), 0.8)), reference_image: Some(("/refs/mika.png".into(), 0.6)) },
+            CharacterRef { name: "bot".into(), prompt: String::new(), lora: Some(("bot.safetensors".into(), 0.5)), reference_image: None },
+        ];
+        ComfyBridgeClient::apply_characters(&mut workflow, &characters, Some("job_mika.png")).unwrap();
+
+        assert_eq!(workflow["6"]["inputs"]["text"], "mika, pink hair, a cat on the moon");
+        // Checkpoint -> 12 (mika) -> 13 (bot) -> consumers
+        assert_eq!(workflow["12"]["inputs"]["model"], serde_json::json!(["4", 0]));
+        assert_eq!(workflow["13"]["inputs"]["clip"], serde_json::json!(["12", 1]));
+        assert_eq!(workflow["3"]["inputs"]["model"], serde_json::json!(["13", 0]));
+        assert_eq!(workflow["7"]["inputs"]["clip"], serde_json::json!(["13", 1]));
+        assert_eq!(workflow["10"]["inputs"]["image"], "job_mika.png");
+        assert_eq!(workflow["11"]["inputs"]["weight"], serde_json::json!(0.6f32));
+    }
+
+    #[test]
+    fn test_style_model_stack_swaps_checkpoint_and_precedes_character_loras() {
+        use factory_core::contracts::LoraSpec;
+        let mut workflow = serde_json::json!({
+            "3": {"class_type": "KSampler", "inputs": {"model": ["4", 0]}},
+            "4": {"class_type": "CheckpointLoaderSimple", "inputs": {"ckpt_name": "pony.safetensors"}},
+            "6": {"class_type": "CLIPTextEncode", "inputs": {"text": "a cat", "clip": ["4", 1]}, "_meta": {"title": "[API_PROMPT]"}},
+        });
+        let models = ModelStack {
+            checkpoint: Some("sdxl_anime.safetensors".into()),
+            loras: vec![LoraSpec { name: "flat_color.safetensors".into(), strength: 0.7 }],
+        };
+        ComfyBridgeClient::apply_model_stack(&mut workflow, &models).unwrap();
+        let characters = vec![CharacterRef { name: "mika".into(), prompt: String::new(), lora: Some(("mika.safetensors".into(), 0.8)), reference_image: None }];
+        ComfyBridgeClient::apply_characters(&mut workflow, &characters, None).unwrap();
+
+        assert_eq!(workflow["4"]["inputs"]["ckpt_name"], "sdxl_anime.safetensors");
+        // Checkpoint -> 7 (style) -> 8 (character) -> consumers
+        assert_eq!(workflow["7"]["inputs"]["lora_name"], "flat_color.safetensors");
+        assert_eq!(workflow["8"]["inputs"]["model"], serde_json::json!(["7", 0]));
+        assert_eq!(workflow["3"]["inputs"]["model"], serde_json::json!(["8", 0]));
+        assert_eq!(workflow["6"]["inputs"]["clip"], serde_json::json!(["8", 1]));
+
+        let object_info = serde_json::json!({
+            "CheckpointLoaderSimple": {"input": {"required": {"ckpt_name": [["pony.safetensors", "sdxl_anime.safetensors"]]}}},
+            "LoraLoader": {"input": {"required": {"lora_name": [["mika.safetensors"]], "strength_model": ["FLOAT", {}]}}},
+        });
+        let installed = ComfyModels::from_object_info(&object_info);
+        assert_eq!(installed.checkpoints.len(), 2);
+        assert_eq!(installed.missing(&models), vec!["flat_color.safetensors".to_string()]);
+    }
+}
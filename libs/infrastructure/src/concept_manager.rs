@@ -1,11 +1,12 @@
-use factory_core::contracts::{ConceptRequest, ConceptResponse};
+use factory_core::contracts::{ConceptRequest, ConceptResponse, LocalizedScript, HOOK_STYLES};
 use factory_core::traits::AgentAct;
 use factory_core::error::FactoryError;
+use factory_core::cost::{CostEntry, CostTracker};
 use async_trait::async_trait;
 use rig::providers::gemini;
 use rig::prelude::*;
 use rig::completion::Prompt;
-use tracing::{info, error};
+use tracing::{info, warn};
 
 /// 動画コンセプト生成機 (Director)
 /// 
@@ -25,6 +26,7 @@ impl ConceptManager {
     }
 
     fn get_client(&self) -> Result<gemini::Client, FactoryError> {
+        crate::chaos::inject(crate::chaos::Fault::GeminiError)?;
         gemini::Client::new(&self.api_key)
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Gemini Client error: {}", e) })
     }
@@ -44,35 +46,40 @@ impl AgentAct for ConceptManager {
 
         // Stage 1: Generate English base concept and visual prompts
         let mut concept = self.generate_english_concept(&input).await?;
+        concept.normalize_title_variants();
         
-        // Stage 2: Translate and localize to Japanese (in parallel or sequentially)
-        // Note: For now we do ja localization. Future can expand to other langs.
-        let ja_script = self.translate_to_japanese(&concept).await?;
-
-        // Construct LocalizedScript list
-        concept.scripts = vec![
-            factory_core::contracts::LocalizedScript {
-                lang: "en".to_string(),
-                display_intro: concept.display_intro.clone(),
-                display_body: concept.display_body.clone(),
-                display_outro: concept.display_outro.clone(),
-                script_intro: concept.script_intro.clone(),
-                script_body: concept.script_body.clone(),
-                script_outro: concept.script_outro.clone(),
-            },
-            ja_script.clone(),
-        ];
+        // Stage 2: 英語の原稿を要求された言語ごとに翻訳する (最初の言語は必須、以降は失敗しても残りを続ける)
+        let english = LocalizedScript {
+            lang: "en".to_string(),
+            display_intro: concept.display_intro.clone(),
+            display_body: concept.display_body.clone(),
+            display_outro: concept.display_outro.clone(),
+            script_intro: concept.script_intro.clone(),
+            script_body: concept.script_body.clone(),
+            script_outro: concept.script_outro.clone(),
+        };
+        let mut scripts = vec![english.clone()];
+        for (i, lang) in localization_targets(&input.target_langs).into_iter().enumerate() {
+            match self.localize(&concept.title, &english, &lang, &input.cost).await {
+                Ok(script) => scripts.push(script),
+                Err(e) if i == 0 => return Err(e),
+                Err(e) => warn!("⚠️ ConceptManager: Localization to '{}' failed, skipping that language: {}", lang, e),
+            }
+        }
+        concept.scripts = scripts;
 
         // Maintain backward compatibility for single-language consumers
         // (Defaulting to Japanese for the legacy fields)
-        concept.display_intro = ja_script.display_intro;
-        concept.display_body = ja_script.display_body;
-        concept.display_outro = ja_script.display_outro;
-        concept.script_intro = ja_script.script_intro;
-        concept.script_body = ja_script.script_body;
-        concept.script_outro = ja_script.script_outro;
-
-        info!("✅ ConceptManager: Multilingual concept finalized: '{}' (Langs: [en, ja])", concept.title);
+        let primary = primary_script(&concept.scripts).clone();
+        concept.display_intro = primary.display_intro;
+        concept.display_body = primary.display_body;
+        concept.display_outro = primary.display_outro;
+        concept.script_intro = primary.script_intro;
+        concept.script_body = primary.script_body;
+        concept.script_outro = primary.script_outro;
+
+        let langs: Vec<&str> = concept.scripts.iter().map(|s| s.lang.as_str()).collect();
+        info!("✅ ConceptManager: Multilingual concept finalized: '{}' (Langs: [{}])", concept.title, langs.join(", "));
         Ok(concept)
     }
 }
@@ -108,6 +115,11 @@ impl ConceptManager {
             - Short sentences (approx 15-20 words max) for rhythm.
             - No ellipses (...). Use periods.
 
+            [TITLE A/B TEST]
+            Propose 2-3 title + hook pairs in title_variants. The first one must use exactly the same title as \"title\".
+            Each hook is the opening line a viewer sees first. Label each with one hook_style from: {}.
+            Make the variants differ in hook_style, not just wording.
+
             [VISUAL PROMPTS]
             Detailed, specific English descriptions for intro, body, and outro.
             - Use cinematic lighting, specific camera angles (e.g., dynamic low angle), and high-quality modifiers (hyper-detailed, 8k, masterpiece).
@@ -126,9 +138,14 @@ impl ConceptManager {
               \"common_style\": \"cinematic anime style, hyper-detailed, dramatic lighting, futuristic atmosphere\",
               \"style_profile\": \"{}\",
               \"visual_prompts\": [\"intro prompt\", \"body prompt\", \"outro prompt\"],
-              \"metadata\": {{ \"narrator_persona\": \"tech_visionary\" }}
+              \"metadata\": {{ \"narrator_persona\": \"tech_visionary\" }},
+              \"title_variants\": [
+                {{ \"title\": \"Title in English\", \"hook\": \"...\", \"hook_style\": \"question\" }},
+                {{ \"title\": \"Alternative title\", \"hook\": \"...\", \"hook_style\": \"shock_stat\" }}
+              ]
             }}
             ```",
+            HOOK_STYLES.join(", "),
             style_list
         );
 
@@ -136,31 +153,69 @@ impl ConceptManager {
         let trend_list = input.trend_items.iter()
             .map(|i| format!("- {} (Score: {})", i.keyword, i.score))
             .collect::<Vec<_>>().join("\n");
-        let user_prompt = format!("Current trends:\n{}\n\nSelect the most interesting topic and generate a top-tier video concept.", trend_list);
+        let mut user_prompt = format!("Current trends:\n{}\n\nSelect the most interesting topic and generate a top-tier video concept.", trend_list);
+        if let Some(prev) = &input.previous_part {
+            user_prompt.push_str(&format!(
+                "\n\n[SERIES CONTINUITY]\nThis video is the next part of a series. Topic for this part: {}\nPrevious part title: {}\nPrevious part summary: {} {}\nContinue the story without repeating it. Briefly reference the previous part in the intro. Keep the same common_style and style_profile.",
+                input.topic, prev.title, prev.display_intro, prev.display_outro
+            ));
+        }
+        if let Some(hook) = input.hook_style.as_deref().filter(|h| HOOK_STYLES.contains(h)) {
+            user_prompt.push_str(&format!(
+                "\n\n[HOOK STYLE]\nOur channel's A/B results favor '{}' hooks. Use that hook_style for the first title variant.",
+                hook
+            ));
+        }
+        if !input.available_workflows.is_empty() {
+            user_prompt.push_str(&format!(
+                "\n\n[SCENE WORKFLOWS]\nEach scene can be rendered with a different ComfyUI workflow. Optionally add \"scene_workflows\": [\"intro\", \"body\", \"outro\"] (same order as visual_prompts) using only these IDs: {}. Use \"\" or omit the field for the default.",
+                input.available_workflows.join(", ")
+            ));
+        }
+        if !input.available_moods.is_empty() {
+            user_prompt.push_str(&format!(
+                "\n\n[BGM MOOD]\nAdd \"bgm_mood\": the background music mood that best fits the script, chosen from: {}.",
+                input.available_moods.join(", ")
+            ));
+        }
+        if !input.available_sfx.is_empty() {
+            user_prompt.push_str(&format!(
+                "\n\n[SOUND EFFECTS]\nOptionally mark up to 3 emphasis points (a reveal, a surprising number) with \"sfx_cues\": [{{\"act\": 1, \"at\": 0.4, \"sfx\": \"pop\"}}]. act is 0 = intro, 1 = body, 2 = outro; at is the position within the act from 0.0 to 1.0. Use only these sfx: {}. Act transitions already get a sound, so do not mark them.",
+                input.available_sfx.join(", ")
+            ));
+        }
+        if let Some(persona) = &input.persona {
+            user_prompt.push_str(&format!(
+                "\n\n[PERSONA]\nWrite the concept in the voice and values of this persona:\n{}",
+                persona
+            ));
+        }
 
-        let response: String = agent.prompt(user_prompt).await.map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        let sent = format!("{}\n{}", preamble, user_prompt);
+        let response: String = agent.prompt(user_prompt).await.map_err(|e| FactoryError::LlmResponse { source: e.into() })?;
+        input.cost.record(CostEntry::llm("gemini", "concept", &sent, &response)).await;
         let json_text = extract_json(&response)?;
-        serde_json::from_str(&json_text).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })
+        serde_json::from_str(&json_text).map_err(|e| FactoryError::LlmResponse { source: e.into() })
     }
 
-    /// Stage 2: Translate English concept to Japanese, focusing on natural narration
-    async fn translate_to_japanese(&self, en_concept: &ConceptResponse) -> Result<factory_core::contracts::LocalizedScript, FactoryError> {
-        info!("  [Stage 2] Localizing to Japanese...");
+    /// Stage 2: 英語の台本を `lang` に翻訳する (字幕用の display_* と TTS 用の script_*)。
+    /// 後から言語を足したプロジェクトの再開でも、保存済みの英語台本から呼ぶ
+    pub async fn localize(&self, title: &str, english: &LocalizedScript, lang: &str, cost: &CostTracker) -> Result<LocalizedScript, FactoryError> {
+        let language = language_name(lang);
+        info!("  [Stage 2] Localizing to {}...", language);
         let client = self.get_client()?;
 
-        let preamble = "You are an expert Japanese translator and script editor for AI narration.
-            Translate the given English video script into engaging, natural Japanese.
+        let preamble = format!(
+            "You are an expert {language} translator and script editor for AI narration.
+            Translate the given English video script into engaging, natural {language}.
 
             [RULES]
-            - Tone: '知的だが親しみやすい'. Use '〜なんです' or '〜ですよね'.
-            - display_*: Keep technical terms or company names in English if they look better in subtitles (e.g., 'OpenAI', 'AI').
-            - script_*: !!CRITICAL!! This is for TTS. Use only Kanji, Hiragana, and Katakana. Convert ALL English terms and numbers to Katakana/Hiragana pronunciation (e.g., 'OpenAI' -> 'オープンエーアイ', 'AI' -> 'エイアイ'). No symbols like % or $.
-            - Ensure the rhythm is fast-paced for Shorts (short sentences).
+            {rules}
 
             [OUTPUT FORMAT (JSON only)]
             ```json
             {{
-              \"lang\": \"ja\",
+              \"lang\": \"{lang}\",
               \"display_intro\": \"...\",
               \"display_body\": \"...\",
               \"display_outro\": \"...\",
@@ -168,17 +223,116 @@ impl ConceptManager {
               \"script_body\": \"...\",
               \"script_outro\": \"...\"
             }}
-            ```";
+            ```",
+            language = language,
+            rules = localization_rules(lang),
+            lang = lang,
+        );
 
-        let agent = client.agent(&self.model).preamble(preamble).temperature(0.3).build();
+        let agent = client.agent(&self.model).preamble(&preamble).temperature(0.3).build();
         let user_prompt = format!(
-            "Title: {}\nIntro: {}\nBody: {}\nOutro: {}\n\nTranslate these into Japanese for the display_* and script_* fields.",
-            en_concept.title, en_concept.display_intro, en_concept.display_body, en_concept.display_outro
+            "Title: {}\nIntro: {}\nBody: {}\nOutro: {}\n\nTranslate these into {} for the display_* and script_* fields.",
+            title, english.display_intro, english.display_body, english.display_outro, language
         );
 
-        let response: String = agent.prompt(user_prompt).await.map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        let sent = format!("{}\n{}", preamble, user_prompt);
+        let response: String = agent.prompt(user_prompt).await.map_err(|e| FactoryError::LlmResponse { source: e.into() })?;
+        cost.record(CostEntry::llm("gemini", "translate", &sent, &response)).await;
         let json_text = extract_json(&response)?;
-        serde_json::from_str(&json_text).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })
+        let mut script: LocalizedScript = serde_json::from_str(&json_text).map_err(|e| FactoryError::LlmResponse { source: e.into() })?;
+        // 後段は言語コードで台本を引くので、LLM の返した表記は信用しない
+        script.lang = lang.to_string();
+        Ok(script)
+    }
+}
+
+impl ConceptManager {
+    /// 字幕 QA: 読み速度を超過した表示テキストを `max_chars` 文字以内に圧縮する
+    ///
+    /// TTS 用の script_* には触れないため、音声の再生成は不要。
+    pub async fn compress_subtitle(&self, text: &str, lang: &str, max_chars: usize, cost: &CostTracker) -> Result<String, FactoryError> {
+        info!("✂️ ConceptManager: Compressing subtitle ({}) to <= {} chars...", lang, max_chars);
+        let client = self.get_client()?;
+
+        let preamble = "You are a subtitle editor for YouTube Shorts.
+            Rewrite the given subtitle text so that it fits within the character limit while keeping its meaning, facts and numbers.
+
+            [RULES]
+            - Keep the same language as the input.
+            - Keep sentence boundaries (periods / 。) so the text can still be split into lines.
+            - Never exceed the character limit (whitespace excluded).
+            - Output only the rewritten text. No quotes, no explanations.";
+
+        let agent = client.agent(&self.model).preamble(preamble).temperature(0.2).build();
+        let user_prompt = format!("Language: {}\nCharacter limit: {}\n\n{}", lang, max_chars, text);
+
+        let sent = format!("{}\n{}", preamble, user_prompt);
+        let response: String = agent.prompt(user_prompt).await.map_err(|e| FactoryError::LlmResponse { source: e.into() })?;
+        cost.record(CostEntry::llm("gemini", "compress_subtitle", &sent, &response)).await;
+        Ok(response.trim().trim_matches('"').trim().to_string())
+    }
+}
+
+/// 言語コードを小文字にそろえ、空と重複を除く (要求順)
+pub fn normalize_langs(target_langs: &[String]) -> Vec<String> {
+    let mut langs: Vec<String> = Vec::new();
+    for lang in target_langs.iter().map(|l| l.trim().to_lowercase()) {
+        if !lang.is_empty() && !langs.contains(&lang) {
+            langs.push(lang);
+        }
+    }
+    langs
+}
+
+/// 翻訳する言語 (重複と英語を除いた要求順。指定なしなら従来どおり ja)
+pub fn localization_targets(target_langs: &[String]) -> Vec<String> {
+    if target_langs.is_empty() {
+        return vec!["ja".to_string()];
+    }
+    normalize_langs(target_langs).into_iter().filter(|l| l != "en").collect()
+}
+
+/// 従来の単一言語フィールドに入れる台本 (ja、無ければ最初の翻訳、それも無ければ英語)
+fn primary_script(scripts: &[LocalizedScript]) -> &LocalizedScript {
+    scripts
+        .iter()
+        .find(|s| s.lang == "ja")
+        .or_else(|| scripts.iter().find(|s| s.lang != "en"))
+        .unwrap_or(&scripts[0])
+}
+
+/// プロンプトに書く言語名 (知らないコードはそのまま渡す)
+fn language_name(lang: &str) -> &str {
+    match lang.split(['-', '_']).next().unwrap_or(lang) {
+        "ja" => "Japanese",
+        "en" => "English",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "fr" => "French",
+        "de" => "German",
+        "it" => "Italian",
+        "ko" => "Korean",
+        "zh" => "Chinese",
+        "id" => "Indonesian",
+        "hi" => "Hindi",
+        _ => lang,
+    }
+}
+
+/// 言語ごとの翻訳ルール (TTS 用の読みの指定を含む)
+fn localization_rules(lang: &str) -> String {
+    match lang {
+        "ja" => "- Tone: '知的だが親しみやすい'. Use '〜なんです' or '〜ですよね'.
+            - display_*: Keep technical terms or company names in English if they look better in subtitles (e.g., 'OpenAI', 'AI').
+            - script_*: !!CRITICAL!! This is for TTS. Use only Kanji, Hiragana, and Katakana. Convert ALL English terms and numbers to Katakana/Hiragana pronunciation (e.g., 'OpenAI' -> 'オープンエーアイ', 'AI' -> 'エイアイ'). No symbols like % or $.
+            - Ensure the rhythm is fast-paced for Shorts (short sentences).".to_string(),
+        _ => format!(
+            "- Tone: smart but friendly, as a native {language} creator would say it on camera.
+            - display_*: Keep technical terms or company names in their original spelling if that reads better in subtitles.
+            - script_*: !!CRITICAL!! This is for TTS. Spell out numbers, symbols and abbreviations exactly as they are spoken in {language}. No symbols like % or $.
+            - Ensure the rhythm is fast-paced for Shorts (short sentences).",
+            language = language_name(lang)
+        ),
     }
 }
 
@@ -217,7 +371,7 @@ fn extract_json(text: &str) -> Result<String, FactoryError> {
 
         Ok(json_str)
     } else {
-        Err(FactoryError::Infrastructure { reason: "LLM response did not contain JSON".into() })
+        Err(FactoryError::LlmResponse { source: anyhow::anyhow!("LLM response did not contain JSON") })
     }
 }
 #[cfg(test)]
@@ -231,6 +385,29 @@ mod tests {
         assert_eq!(result, "{\"title\": \"test\"}");
     }
 
+    #[test]
+    fn test_localization_targets_and_primary_script() {
+        assert_eq!(localization_targets(&[]), vec!["ja"]);
+        let langs: Vec<String> = ["en", "es", "JA", "es"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(localization_targets(&langs), vec!["es", "ja"]);
+        assert!(localization_targets(&["en".to_string()]).is_empty());
+        assert_eq!(normalize_langs(&langs), vec!["en", "es", "ja"]);
+
+        let script = |lang: &str| LocalizedScript {
+            lang: lang.to_string(),
+            display_intro: String::new(),
+            display_body: String::new(),
+            display_outro: String::new(),
+            script_intro: String::new(),
+            script_body: String::new(),
+            script_outro: String::new(),
+        };
+        assert_eq!(primary_script(&[script("en"), script("es"), script("ja")]).lang, "ja");
+        assert_eq!(primary_script(&[script("en"), script("es")]).lang, "es");
+        assert_eq!(primary_script(&[script("en")]).lang, "en");
+        assert!(localization_rules("pt-BR").contains("Portuguese"));
+    }
+
     #[test]
     fn test_extract_json_no_block() {
         let text = "There is no json here";
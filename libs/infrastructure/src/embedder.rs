@@ -0,0 +1,144 @@
+//! # Embedder — Karma 検索用のテキスト埋め込み
+//!
+//! Gemini Embedding API またはローカル Ollama (OpenAI 互換 `/v1/embeddings`) で
+//! 教訓と検索トピックをベクトル化する。ベクトルは SQLite に f32 の BLOB として保存し、
+//! コサイン類似度は Rust 側で計算する。
+
+use async_trait::async_trait;
+use factory_core::error::FactoryError;
+use factory_core::traits::Embedder;
+use std::time::Duration;
+
+fn http_client() -> Result<reqwest::Client, FactoryError> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build embedder HTTP client: {}", e) })
+}
+
+pub struct GeminiEmbedder {
+    api_key: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl GeminiEmbedder {
+    pub fn new(api_key: &str, model: &str) -> Result<Self, FactoryError> {
+        Ok(Self { api_key: api_key.to_string(), model: model.to_string(), client: http_client()? })
+    }
+}
+
+#[async_trait]
+impl Embedder for GeminiEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, FactoryError> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:embedContent?key={}",
+            self.model, self.api_key
+        );
+        let res = self.client
+            .post(&url)
+            .json(&serde_json::json!({
+                "model": format!("models/{}", self.model),
+                "content": { "parts": [{ "text": text }] }
+            }))
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "Gemini Embedding".into(), reason: e.to_string() })?;
+        if !res.status().is_success() {
+            return Err(FactoryError::from_http_status("Gemini Embedding", res.status().as_u16(), ""));
+        }
+        let body: serde_json::Value = res.json().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid Gemini Embedding response: {}", e) })?;
+        parse_vector(body.pointer("/embedding/values"))
+    }
+}
+
+pub struct OllamaEmbedder {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OllamaEmbedder {
+    /// `base_url` は OpenAI 互換エンドポイント (例: `http://localhost:11434/v1`)
+    pub fn new(base_url: &str, model: &str) -> Result<Self, FactoryError> {
+        Ok(Self { base_url: base_url.trim_end_matches('/').to_string(), model: model.to_string(), client: http_client()? })
+    }
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, FactoryError> {
+        let res = self.client
+            .post(format!("{}/embeddings", self.base_url))
+            .json(&serde_json::json!({ "model": self.model, "input": text }))
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "Ollama Embedding".into(), reason: e.to_string() })?;
+        if !res.status().is_success() {
+            return Err(FactoryError::from_http_status("Ollama Embedding", res.status().as_u16(), ""));
+        }
+        let body: serde_json::Value = res.json().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid Ollama Embedding response: {}", e) })?;
+        parse_vector(body.pointer("/data/0/embedding"))
+    }
+}
+
+fn parse_vector(value: Option<&serde_json::Value>) -> Result<Vec<f32>, FactoryError> {
+    let vector: Vec<f32> = value
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|x| x.as_f64()).map(|x| x as f32).collect())
+        .unwrap_or_default();
+    if vector.is_empty() {
+        return Err(FactoryError::Infrastructure { reason: "Embedding response has no vector".to_string() });
+    }
+    Ok(vector)
+}
+
+/// SQLite 保存用: little-endian f32 の連結
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes.chunks_exact(4).map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect()
+}
+
+/// コサイン類似度 (次元が違う・ゼロベクトルなら 0.0)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut na, mut nb) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        na += x * x;
+        nb += y * y;
+    }
+    if na == 0.0 || nb == 0.0 {
+        return 0.0;
+    }
+    dot / (na.sqrt() * nb.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_round_trip_and_cosine() {
+        let v = vec![0.5f32, -1.25, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&v)), v);
+        assert!((cosine_similarity(&v, &v) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), 0.0);
+    }
+}
@@ -0,0 +1,432 @@
+//! # Evolution Scenario — Samsara の回帰試験用シナリオとレポート
+//!
+//! `shorts-factory simulate-evolution --scenario <file>` が読む TOML。
+//! 初期の教訓・合成したジョブの結果 (評価・SNS メトリクス・Oracle の判定)・検索の期待値を並べ、
+//! 使い捨ての DB で流した結果を [`EvolutionReport`] として `workspace/simulations/` に書き出す。
+//!
+//! ```toml
+//! name = "style-leak"
+//!
+//! [[karma]]
+//! skill = "script"
+//! namespace = "horror"
+//! lesson = "Linger on the jump scare"
+//! weight = 80
+//! age_days = 10
+//!
+//! [[jobs]]
+//! topic = "Robot toys explained"
+//! style = "kids_tech"
+//! rating = 5
+//! lesson = "Explain with toys"
+//! metrics = { views = 120000, likes = 9000, comments = "So cute!" }
+//! verdict = { topic_score = 0.8, visual_score = 0.6, soul_score = 0.9, reasoning = "Warm and clear" }
+//!
+//! [[probes]]
+//! topic = "Robot toys"
+//! skill = "script"
+//! namespace = "kids_tech"
+//! expect = ["Explain with toys"]
+//! forbid = ["jump scare"]
+//! ```
+//!
+//! `verdict` を省くと Oracle に実際に問い合わせる。書いておけば LLM なしで毎回同じ結果になる。
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use factory_core::contracts::OracleVerdict;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+
+use crate::karma_archive::KarmaRecord;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EvolutionScenario {
+    pub name: String,
+    /// 教訓と判定に付ける Soul ハッシュ
+    #[serde(default = "default_soul_hash")]
+    pub soul_hash: String,
+    #[serde(default)]
+    pub karma: Vec<SeedKarma>,
+    #[serde(default)]
+    pub jobs: Vec<SyntheticJob>,
+    #[serde(default)]
+    pub probes: Vec<RetrievalProbe>,
+}
+
+fn default_soul_hash() -> String {
+    "simulation".to_string()
+}
+
+/// 初期状態の教訓
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SeedKarma {
+    #[serde(default = "default_skill")]
+    pub skill: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    pub lesson: String,
+    #[serde(default = "default_karma_type")]
+    pub karma_type: String,
+    #[serde(default = "default_weight")]
+    pub weight: i64,
+    /// 何日前に書かれた教訓とするか (時間減衰の確認用)
+    #[serde(default)]
+    pub age_days: i64,
+}
+
+fn default_skill() -> String {
+    "global".to_string()
+}
+
+fn default_karma_type() -> String {
+    "Technical".to_string()
+}
+
+fn default_weight() -> i64 {
+    100
+}
+
+/// 合成したジョブの結果 (シナリオの順に流す)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SyntheticJob {
+    pub topic: String,
+    #[serde(default)]
+    pub style: String,
+    /// false なら失敗したジョブとして扱う
+    #[serde(default = "default_true")]
+    pub succeeded: bool,
+    /// 人間の評価 (1-5)
+    #[serde(default)]
+    pub rating: Option<i32>,
+    /// Distiller が抽出したことにする教訓 (スタイルの名前空間に入る)
+    #[serde(default)]
+    pub lesson: Option<String>,
+    #[serde(default)]
+    pub metrics: Option<SyntheticMetrics>,
+    /// 固定の判定 (省略時は Oracle に問い合わせる)
+    #[serde(default)]
+    pub verdict: Option<SyntheticVerdict>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SyntheticMetrics {
+    /// 30 日目なら Final Verdict として教訓が残る
+    #[serde(default = "default_milestone")]
+    pub milestone_days: i64,
+    pub views: i64,
+    pub likes: i64,
+    #[serde(default)]
+    pub comments: String,
+}
+
+fn default_milestone() -> i64 {
+    30
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SyntheticVerdict {
+    pub topic_score: f64,
+    pub visual_score: f64,
+    pub soul_score: f64,
+    #[serde(default)]
+    pub reasoning: String,
+}
+
+impl From<SyntheticVerdict> for OracleVerdict {
+    fn from(v: SyntheticVerdict) -> Self {
+        OracleVerdict {
+            topic_score: v.topic_score,
+            visual_score: v.visual_score,
+            soul_score: v.soul_score,
+            reasoning: v.reasoning,
+            judges: vec!["scenario".to_string()],
+            disagreement: false,
+            cloud_failed: false,
+        }
+    }
+}
+
+/// Samsara の Karma 検索に対する期待値
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RetrievalProbe {
+    pub topic: String,
+    #[serde(default = "default_skill")]
+    pub skill: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default = "default_probe_limit")]
+    pub limit: i64,
+    /// 取り出されるべき教訓 (部分一致)
+    #[serde(default)]
+    pub expect: Vec<String>,
+    /// 取り出されてはいけない教訓 (部分一致)
+    #[serde(default)]
+    pub forbid: Vec<String>,
+}
+
+fn default_probe_limit() -> i64 {
+    3
+}
+
+impl EvolutionScenario {
+    pub fn load(path: &Path) -> Result<Self, FactoryError> {
+        let text = std::fs::read_to_string(path).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read {}: {}", path.display(), e) })?;
+        let scenario: Self = toml::from_str(&text).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse {}: {}", path.display(), e) })?;
+        if scenario.name.trim().is_empty() {
+            return Err(FactoryError::Infrastructure { reason: format!("{}: scenario name must not be empty", path.display()) });
+        }
+        Ok(scenario)
+    }
+}
+
+/// ある時点の教訓の集計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KarmaSnapshot {
+    pub total: usize,
+    /// weight > 0 の教訓
+    pub active: usize,
+    /// 有効な教訓の平均重み
+    pub mean_weight: f64,
+    /// 名前空間ごとの有効な教訓の数 (共通の教訓は "shared")
+    pub by_namespace: BTreeMap<String, usize>,
+}
+
+impl KarmaSnapshot {
+    pub fn of(records: &[KarmaRecord]) -> Self {
+        let active: Vec<&KarmaRecord> = records.iter().filter(|r| r.weight > 0).collect();
+        let mut by_namespace = BTreeMap::new();
+        for r in &active {
+            *by_namespace.entry(r.namespace.clone().unwrap_or_else(|| "shared".to_string())).or_insert(0) += 1;
+        }
+        let mean_weight = if active.is_empty() { 0.0 } else { active.iter().map(|r| r.weight as f64).sum::<f64>() / active.len() as f64 };
+        Self { total: records.len(), active: active.len(), mean_weight, by_namespace }
+    }
+}
+
+/// 重みが変わった (または増えた・消えた) 教訓
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightDrift {
+    pub id: String,
+    pub lesson: String,
+    /// シナリオ前の重み (新しく増えた教訓は None)
+    pub before: Option<i64>,
+    /// シナリオ後の重み (消えた教訓は None)
+    pub after: Option<i64>,
+}
+
+/// 前後の教訓を ID で突き合わせ、重みが変わったものだけを返す
+pub fn weight_drift(before: &[KarmaRecord], after: &[KarmaRecord]) -> Vec<WeightDrift> {
+    let old: HashMap<&str, &KarmaRecord> = before.iter().map(|r| (r.id.as_str(), r)).collect();
+    let new: HashMap<&str, &KarmaRecord> = after.iter().map(|r| (r.id.as_str(), r)).collect();
+    let mut drift: Vec<WeightDrift> = after
+        .iter()
+        .filter(|r| old.get(r.id.as_str()).map(|o| o.weight) != Some(r.weight))
+        .map(|r| WeightDrift { id: r.id.clone(), lesson: r.lesson.clone(), before: old.get(r.id.as_str()).map(|o| o.weight), after: Some(r.weight) })
+        .collect();
+    drift.extend(
+        before
+            .iter()
+            .filter(|r| !new.contains_key(r.id.as_str()))
+            .map(|r| WeightDrift { id: r.id.clone(), lesson: r.lesson.clone(), before: Some(r.weight), after: None }),
+    );
+    drift
+}
+
+/// 1 つの検索の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbeResult {
+    pub topic: String,
+    pub skill: String,
+    pub namespace: Option<String>,
+    pub retrieved: Vec<String>,
+    /// 取り出せた期待値
+    pub hits: Vec<String>,
+    /// 取り出せなかった期待値
+    pub misses: Vec<String>,
+    /// 取り出してしまった禁止の教訓
+    pub leaks: Vec<String>,
+}
+
+impl ProbeResult {
+    pub fn evaluate(probe: &RetrievalProbe, retrieved: Vec<String>) -> Self {
+        let found = |needle: &String| retrieved.iter().any(|lesson| lesson.contains(needle.as_str()));
+        let (hits, misses) = probe.expect.iter().cloned().partition(|e| found(e));
+        let leaks = probe.forbid.iter().filter(|f| found(f)).cloned().collect();
+        Self { topic: probe.topic.clone(), skill: probe.skill.clone(), namespace: probe.namespace.clone(), retrieved, hits, misses, leaks }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.misses.is_empty() && self.leaks.is_empty()
+    }
+}
+
+/// 検索の期待値の集計
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetrievalStats {
+    pub probes: Vec<ProbeResult>,
+    /// 期待値のうち取り出せた割合 (期待値が無ければ 1.0)
+    pub hit_rate: f64,
+    pub leaks: usize,
+}
+
+impl RetrievalStats {
+    pub fn of(probes: Vec<ProbeResult>) -> Self {
+        let expected: usize = probes.iter().map(|p| p.hits.len() + p.misses.len()).sum();
+        let hits: usize = probes.iter().map(|p| p.hits.len()).sum();
+        let hit_rate = if expected == 0 { 1.0 } else { hits as f64 / expected as f64 };
+        let leaks = probes.iter().map(|p| p.leaks.len()).sum();
+        Self { probes, hit_rate, leaks }
+    }
+}
+
+/// 合成ジョブ 1 件の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobOutcome {
+    pub job_id: String,
+    pub topic: String,
+    pub style: String,
+    pub succeeded: bool,
+    #[serde(default)]
+    pub verdict: Option<OracleVerdict>,
+    /// 判定を適用できなかった理由
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// `workspace/simulations/<name>-<時刻>.json` に書き出す結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvolutionReport {
+    pub scenario: String,
+    pub ran_at: String,
+    pub karma_before: KarmaSnapshot,
+    pub karma_after: KarmaSnapshot,
+    pub weight_drift: Vec<WeightDrift>,
+    pub jobs: Vec<JobOutcome>,
+    /// 初期の教訓だけのときの検索結果
+    pub retrieval_before: RetrievalStats,
+    /// ジョブを流した後の検索結果
+    pub retrieval_after: RetrievalStats,
+}
+
+impl EvolutionReport {
+    pub fn save(&self, path: &Path) -> Result<(), FactoryError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to serialize report: {}", e) })?;
+        std::fs::write(path, json).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write {}: {}", path.display(), e) })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FactoryError> {
+        let text = std::fs::read_to_string(path).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read {}: {}", path.display(), e) })?;
+        serde_json::from_str(&text).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse {}: {}", path.display(), e) })
+    }
+
+    /// 以前のレポートより悪くなった点 (ヒット率の低下・禁止した教訓の混入・通らなくなった検索)
+    pub fn regressions(&self, baseline: &EvolutionReport) -> Vec<String> {
+        let (now, then) = (&self.retrieval_after, &baseline.retrieval_after);
+        let mut found = Vec::new();
+        if now.hit_rate + 1e-9 < then.hit_rate {
+            found.push(format!("retrieval hit-rate fell from {:.0}% to {:.0}%", then.hit_rate * 100.0, now.hit_rate * 100.0));
+        }
+        if now.leaks > then.leaks {
+            found.push(format!("forbidden lessons retrieved: {} (was {})", now.leaks, then.leaks));
+        }
+        for probe in now.probes.iter().filter(|p| !p.passed()) {
+            let passed_before = then
+                .probes
+                .iter()
+                .any(|b| b.topic == probe.topic && b.skill == probe.skill && b.namespace == probe.namespace && b.passed());
+            if passed_before {
+                found.push(format!("probe '{}' ({}) no longer passes: missing {:?}, leaked {:?}", probe.topic, probe.skill, probe.misses, probe.leaks));
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, namespace: Option<&str>, weight: i64) -> KarmaRecord {
+        KarmaRecord {
+            id: id.into(),
+            job_id: None,
+            karma_type: "Technical".into(),
+            related_skill: "script".into(),
+            namespace: namespace.map(String::from),
+            lesson: format!("lesson {}", id),
+            weight,
+            soul_version_hash: None,
+            created_at: String::new(),
+            last_applied_at: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_scenario_defaults() {
+        let scenario: EvolutionScenario = toml::from_str(
+            r#"
+            name = "leak"
+            [[karma]]
+            lesson = "Keep it short"
+            [[jobs]]
+            topic = "Robots"
+            metrics = { views = 10, likes = 1 }
+            [[probes]]
+            topic = "Robots"
+            expect = ["short"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(scenario.soul_hash, "simulation");
+        assert_eq!((scenario.karma[0].skill.as_str(), scenario.karma[0].weight), ("global", 100));
+        assert!(scenario.jobs[0].succeeded);
+        assert_eq!(scenario.jobs[0].metrics.as_ref().unwrap().milestone_days, 30);
+        assert_eq!(scenario.probes[0].limit, 3);
+    }
+
+    #[test]
+    fn test_report_drift_and_regressions() {
+        let before = vec![record("a", None, 80), record("b", Some("horror"), 60)];
+        let after = vec![record("a", None, 80), record("c", Some("kids"), 90)];
+        let drift = weight_drift(&before, &after);
+        assert_eq!(drift.iter().map(|d| (d.id.as_str(), d.before, d.after)).collect::<Vec<_>>(), vec![("c", None, Some(90)), ("b", Some(60), None)]);
+        let snapshot = KarmaSnapshot::of(&after);
+        assert_eq!((snapshot.active, snapshot.mean_weight), (2, 85.0));
+        assert_eq!(snapshot.by_namespace.get("shared"), Some(&1));
+
+        let probe = RetrievalProbe {
+            topic: "Robots".into(),
+            skill: "script".into(),
+            namespace: Some("kids".into()),
+            limit: 3,
+            expect: vec!["toys".into(), "short".into()],
+            forbid: vec!["scare".into()],
+        };
+        let good = ProbeResult::evaluate(&probe, vec!["Explain with toys".into(), "Keep it short".into()]);
+        let bad = ProbeResult::evaluate(&probe, vec!["Explain with toys".into(), "[LEGACY KARMA]\nLinger on the scare".into()]);
+        assert!(good.passed());
+        assert_eq!((bad.misses.clone(), bad.leaks.clone()), (vec!["short".to_string()], vec!["scare".to_string()]));
+
+        let report = |stats: RetrievalStats| EvolutionReport {
+            scenario: "leak".into(),
+            ran_at: String::new(),
+            karma_before: KarmaSnapshot::default(),
+            karma_after: snapshot.clone(),
+            weight_drift: Vec::new(),
+            jobs: Vec::new(),
+            retrieval_before: RetrievalStats::default(),
+            retrieval_after: stats,
+        };
+        let baseline = report(RetrievalStats::of(vec![good]));
+        let current = report(RetrievalStats::of(vec![bad]));
+        assert_eq!(current.retrieval_after.hit_rate, 0.5);
+        assert_eq!(current.regressions(&baseline).len(), 3);
+        assert!(baseline.regressions(&current).is_empty());
+    }
+}
@@ -6,7 +6,7 @@ use async_trait::async_trait;
 use factory_core::error::FactoryError;
 use factory_core::traits::FactoryLogger;
 use sqlx::sqlite::SqlitePool;
-use std::path::PathBuf;
+use std::path::Path;
 
 /// SQLite をバックエンドとするロガークライアント
 pub struct FactoryLogClient {
@@ -44,7 +44,7 @@ impl FactoryLogClient {
 
 #[async_trait]
 impl FactoryLogger for FactoryLogClient {
-    async fn log_success(&self, video_id: &str, output_path: &PathBuf) -> Result<(), FactoryError> {
+    async fn log_success(&self, video_id: &str, output_path: &Path) -> Result<(), FactoryError> {
         sqlx::query::<sqlx::Sqlite>("INSERT INTO logs (event_type, video_id, output_path) VALUES (?, ?, ?)")
             .bind("SUCCESS")
             .bind(video_id)
@@ -0,0 +1,152 @@
+//! # FfmpegProgress — ffmpeg の進捗 (`-progress pipe:1`) の解析と配信
+//!
+//! 以前の ffmpeg 呼び出しは終わるまで何も言わず、長い最終合成は固まったように見えた。
+//! `progress_command()` で作ったコマンドを `run_with_progress` で走らせると、標準出力に流れる
+//! `key=value` のブロックを読んで書き出し済みの秒数・フレーム数・速度から残り時間を見積もり、
+//! 送信口 (TelemetryHub) へ流す。
+
+pub use factory_core::api::FfmpegProgress;
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::broadcast;
+
+/// `-progress pipe:1` 付きの ffmpeg コマンド (統計行は stderr に出さない)
+pub fn progress_command() -> Command {
+    let mut cmd = Command::new("ffmpeg");
+    cmd.arg("-progress").arg("pipe:1").arg("-nostats");
+    cmd
+}
+
+/// `progress=` 行で閉じる 1 ブロック分の値
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgressBlock {
+    pub out_secs: f64,
+    pub frame: u64,
+    pub speed: Option<f64>,
+    /// `progress=end` (エンコード終了)
+    pub end: bool,
+}
+
+#[derive(Default)]
+pub struct ProgressParser {
+    current: ProgressBlock,
+}
+
+impl ProgressParser {
+    /// 1 行ずつ渡す。ブロックが閉じたらその値を返す (`N/A` の項目は前の値のまま)
+    pub fn feed(&mut self, line: &str) -> Option<ProgressBlock> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim();
+        match key {
+            "frame" => {
+                if let Ok(frame) = value.parse() {
+                    self.current.frame = frame;
+                }
+            }
+            // out_time_ms も中身はマイクロ秒 (ffmpeg の歴史的経緯)
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(us) = value.parse::<i64>() {
+                    self.current.out_secs = us.max(0) as f64 / 1_000_000.0;
+                }
+            }
+            "speed" => self.current.speed = value.trim_end_matches('x').trim().parse().ok().filter(|s: &f64| *s > 0.0),
+            "progress" => {
+                self.current.end = value == "end";
+                return Some(self.current.clone());
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// ブロックから進捗率と残り時間を見積もる。速度が分かればそれで、無ければ経過時間の比で
+pub fn estimate(operation: &str, block: &ProgressBlock, total_secs: Option<f64>, elapsed: Duration) -> FfmpegProgress {
+    let total = total_secs.filter(|t| *t > 0.0);
+    let fraction = if block.end { Some(1.0) } else { total.map(|t| (block.out_secs / t).clamp(0.0, 1.0)) };
+    let eta_secs = match (block.end, total, block.speed, fraction) {
+        (true, ..) => Some(0),
+        (false, Some(t), Some(speed), _) => Some(((t - block.out_secs).max(0.0) / speed).round() as u64),
+        (false, _, None, Some(f)) if f > 0.0 => Some((elapsed.as_secs_f64() * (1.0 - f) / f).round() as u64),
+        _ => None,
+    };
+    FfmpegProgress {
+        operation: operation.to_string(),
+        out_secs: block.out_secs,
+        total_secs: total,
+        frame: block.frame,
+        speed: block.speed,
+        percent: fraction.map(|f| (f * 100.0) as f32),
+        eta_secs,
+        done: block.end,
+    }
+}
+
+/// `progress_command()` で作ったコマンドを実行し、進捗を `tx` に流す。戻り値の stdout は空
+pub async fn run_with_progress(
+    mut cmd: Command,
+    operation: &str,
+    total_secs: Option<f64>,
+    tx: Option<&broadcast::Sender<FfmpegProgress>>,
+) -> std::io::Result<Output> {
+    let started = Instant::now();
+    let mut child = cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+    // stderr を並行して吸い出さないと、パイプが詰まって ffmpeg が止まる
+    let mut stderr = child.stderr.take();
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_end(&mut buf).await;
+        }
+        buf
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut parser = ProgressParser::default();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let (Some(block), Some(tx)) = (parser.feed(&line), tx) {
+                // 誰も聞いていなければ捨てる
+                let _ = tx.send(estimate(operation, &block, total_secs, started.elapsed()));
+            }
+        }
+    }
+
+    let status = child.wait().await?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    Ok(Output { status, stdout: Vec::new(), stderr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_emits_block_per_progress_line() {
+        let mut parser = ProgressParser::default();
+        let output = "frame=120\nfps=30.0\nout_time_us=4000000\nout_time=00:00:04.000000\nspeed=2.0x\nprogress=continue\n\
+                      frame=240\nout_time_us=N/A\nspeed=N/A\nprogress=end\n";
+        let blocks: Vec<ProgressBlock> = output.lines().filter_map(|l| parser.feed(l)).collect();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0], ProgressBlock { out_secs: 4.0, frame: 120, speed: Some(2.0), end: false });
+        assert_eq!(blocks[1], ProgressBlock { out_secs: 4.0, frame: 240, speed: None, end: true });
+    }
+
+    #[test]
+    fn test_estimate_prefers_speed_then_elapsed_ratio() {
+        let block = ProgressBlock { out_secs: 15.0, frame: 450, speed: Some(0.5), end: false };
+        let p = estimate("final_assembly", &block, Some(60.0), Duration::from_secs(30));
+        assert_eq!((p.percent, p.eta_secs), (Some(25.0), Some(90)));
+
+        let no_speed = ProgressBlock { speed: None, ..block.clone() };
+        assert_eq!(estimate("resize", &no_speed, Some(60.0), Duration::from_secs(30)).eta_secs, Some(90));
+        let unknown = estimate("resize", &no_speed, None, Duration::from_secs(30));
+        assert_eq!((unknown.percent, unknown.eta_secs), (None, None));
+
+        let done = estimate("resize", &ProgressBlock { end: true, ..block }, None, Duration::from_secs(30));
+        assert_eq!((done.percent, done.eta_secs, done.done), (Some(100.0), Some(0), true));
+    }
+}
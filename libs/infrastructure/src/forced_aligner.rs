@@ -0,0 +1,179 @@
+//! # ForcedAligner — 持ち込みナレーションのタイミング合わせ
+//!
+//! ユーザーが収録した 1 本のナレーション音声を、台本の幕 (intro/body/outro) に合わせて分割する。
+//! 台本の文字数比から期待される境界を求め、FFmpeg `silencedetect` で検出した無音区間へ吸着させる。
+
+use factory_core::error::FactoryError;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::info;
+
+/// 無音とみなす音量 (dB)
+const SILENCE_NOISE_DB: f32 = -35.0;
+/// 無音とみなす最短時間 (秒)
+const SILENCE_MIN_SECS: f32 = 0.25;
+/// 期待境界から吸着を許す距離 (全長に対する比率)
+const SNAP_TOLERANCE_RATIO: f32 = 0.15;
+
+pub struct ForcedAligner;
+
+impl ForcedAligner {
+    /// ナレーションを台本の区切りに合わせて分割し、各区間の長さ (秒) を返す
+    ///
+    /// `texts` と `out_paths` は同じ長さであること。
+    pub async fn align_and_split(
+        audio: &Path,
+        total_duration: f32,
+        texts: &[&str],
+        out_paths: &[PathBuf],
+    ) -> Result<Vec<f32>, FactoryError> {
+        if texts.len() != out_paths.len() || texts.is_empty() {
+            return Err(FactoryError::Infrastructure {
+                reason: format!("ForcedAligner: {} texts but {} output paths", texts.len(), out_paths.len()),
+            });
+        }
+
+        let silences = Self::detect_silences(audio).await?;
+        let weights: Vec<usize> = texts.iter().map(|t| t.chars().filter(|c| !c.is_whitespace()).count()).collect();
+        let cuts = Self::align_boundaries(total_duration, &weights, &silences);
+        info!("🎚️ ForcedAligner: {} silence(s) detected, cuts at {:?}", silences.len(), cuts);
+
+        let mut bounds = Vec::with_capacity(cuts.len() + 2);
+        bounds.push(0.0);
+        bounds.extend(cuts.iter().cloned());
+        bounds.push(total_duration);
+
+        let mut durations = Vec::with_capacity(out_paths.len());
+        for (i, out) in out_paths.iter().enumerate() {
+            let (start, end) = (bounds[i], bounds[i + 1]);
+            Self::extract_segment(audio, start, end, out).await?;
+            durations.push(end - start);
+        }
+        Ok(durations)
+    }
+
+    /// 文字数比で求めた期待境界を、許容範囲内で最も近い無音区間の中央へ吸着させる
+    ///
+    /// 戻り値は `weights.len() - 1` 個の単調増加する切れ目 (秒)。
+    pub fn align_boundaries(total_duration: f32, weights: &[usize], silences: &[(f32, f32)]) -> Vec<f32> {
+        let total_weight: usize = weights.iter().sum::<usize>().max(1);
+        let tolerance = total_duration * SNAP_TOLERANCE_RATIO;
+        let midpoints: Vec<f32> = silences.iter().map(|(s, e)| (s + e) / 2.0).collect();
+
+        let mut cuts = Vec::new();
+        let mut accumulated = 0usize;
+        let mut prev = 0.0f32;
+        for w in &weights[..weights.len().saturating_sub(1)] {
+            accumulated += w;
+            let expected = total_duration * accumulated as f32 / total_weight as f32;
+            let snapped = midpoints
+                .iter()
+                .filter(|&&m| m > prev && (m - expected).abs() <= tolerance)
+                .min_by(|a, b| (*a - expected).abs().total_cmp(&(*b - expected).abs()))
+                .cloned()
+                .unwrap_or(expected);
+            let cut = snapped.max(prev).min(total_duration);
+            cuts.push(cut);
+            prev = cut;
+        }
+        cuts
+    }
+
+    /// FFmpeg `silencedetect` で無音区間 (start, end) を列挙する
+    async fn detect_silences(audio: &Path) -> Result<Vec<(f32, f32)>, FactoryError> {
+        let output = Command::new("ffmpeg")
+            .arg("-hide_banner")
+            .arg("-i").arg(audio)
+            .arg("-af").arg(format!("silencedetect=noise={}dB:d={}", SILENCE_NOISE_DB, SILENCE_MIN_SECS))
+            .arg("-f").arg("null")
+            .arg("-")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("silencedetect spawn failed: {}", e) })?;
+
+        if !output.status.success() {
+            return Err(FactoryError::FfmpegFailed {
+                reason: format!("silencedetect failed: {}", String::from_utf8_lossy(&output.stderr)),
+            });
+        }
+        Ok(Self::parse_silences(&String::from_utf8_lossy(&output.stderr)))
+    }
+
+    fn parse_silences(log: &str) -> Vec<(f32, f32)> {
+        let value_after = |line: &str, key: &str| -> Option<f32> {
+            let rest = &line[line.find(key)? + key.len()..];
+            rest.split_whitespace().next()?.parse().ok()
+        };
+
+        let mut silences = Vec::new();
+        let mut open: Option<f32> = None;
+        for line in log.lines() {
+            if let Some(start) = value_after(line, "silence_start: ") {
+                open = Some(start.max(0.0));
+            } else if let Some(end) = value_after(line, "silence_end: ") {
+                if let Some(start) = open.take() {
+                    silences.push((start, end));
+                }
+            }
+        }
+        silences
+    }
+
+    async fn extract_segment(audio: &Path, start: f32, end: f32, out: &Path) -> Result<(), FactoryError> {
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(audio)
+            .arg("-ss").arg(format!("{:.3}", start))
+            .arg("-to").arg(format!("{:.3}", end))
+            .arg("-c:a").arg("pcm_s16le")
+            .arg(out)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("segment spawn failed: {}", e) })?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Failed to extract {:.2}s-{:.2}s from {}", start, end, audio.display()) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries_snap_to_nearby_silence() {
+        // 期待境界: 10s と 20s (文字数 1:1:1, 全長 30s)
+        let silences = vec![(9.0, 9.6), (21.0, 21.4), (2.0, 2.5)];
+        let cuts = ForcedAligner::align_boundaries(30.0, &[10, 10, 10], &silences);
+        assert_eq!(cuts.len(), 2);
+        assert!((cuts[0] - 9.3).abs() < 0.01);
+        assert!((cuts[1] - 21.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_boundaries_fall_back_to_text_ratio() {
+        // 許容範囲外の無音しか無い場合は文字数比のまま
+        let cuts = ForcedAligner::align_boundaries(30.0, &[1, 2], &[(0.5, 1.0)]);
+        assert_eq!(cuts, vec![10.0]);
+    }
+
+    #[test]
+    fn test_parse_silencedetect_log() {
+        let log = "[silencedetect @ 0x1] silence_start: 4.5\n\
+                   [silencedetect @ 0x1] silence_end: 5.25 | silence_duration: 0.75\n\
+                   [silencedetect @ 0x1] silence_start: -0.01\n\
+                   [silencedetect @ 0x1] silence_end: 0.3 | silence_duration: 0.31\n";
+        let silences = ForcedAligner::parse_silences(log);
+        assert_eq!(silences, vec![(4.5, 5.25), (0.0, 0.3)]);
+    }
+}
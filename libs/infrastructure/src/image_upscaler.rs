@@ -0,0 +1,155 @@
+//! # ImageUpscaler — `upscale` 工程の拡大方式
+//!
+//! 以前の `upscale` 工程は ffmpeg の lanczos で引き伸ばすだけで、Ken Burns のズームで粗が残った。
+//! `[upscale] backend` で ComfyUI の ESRGAN ワークフローか Real-ESRGAN サイドカーを選べる。
+//! どちらかが失敗したシーンは lanczos に落とし、工程全体は止めない。
+
+use crate::comfy_bridge::ComfyBridgeClient;
+use crate::media_forge::MediaForgeClient;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use shared::config::UpscaleConfig;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Backend {
+    Ffmpeg,
+    /// `resources/workflows/{workflow}.json` を ComfyUI で実行する
+    Comfy { workflow: String },
+    /// `POST {url}/upscale` に入出力パスを渡す
+    Sidecar { url: String },
+}
+
+impl Backend {
+    fn from_config(cfg: &UpscaleConfig) -> Self {
+        match cfg.backend.trim().to_lowercase().as_str() {
+            "ffmpeg" => Self::Ffmpeg,
+            "comfy" if !cfg.comfy_workflow.trim().is_empty() => Self::Comfy { workflow: cfg.comfy_workflow.trim().to_string() },
+            "realesrgan" if !cfg.realesrgan_url.trim().is_empty() => Self::Sidecar { url: cfg.realesrgan_url.trim().trim_end_matches('/').to_string() },
+            other => {
+                warn!("⚠️ Upscale: backend '{}' is unknown or not configured, using ffmpeg", other);
+                Self::Ffmpeg
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UpscaleRequest<'a> {
+    path: &'a str,
+    output: &'a str,
+    scale: u32,
+}
+
+#[derive(Deserialize)]
+struct UpscaleResponse {
+    /// 書き出したパス (省略時は `output` のとおり)
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// 拡大した結果
+pub struct Upscaled {
+    pub path: PathBuf,
+    /// GPU の方式で拡大できたか (lanczos に落ちたら false)
+    pub on_gpu: bool,
+}
+
+pub struct ImageUpscaler {
+    backend: Backend,
+    factor: u32,
+    client: reqwest::Client,
+}
+
+impl Default for ImageUpscaler {
+    fn default() -> Self {
+        Self { backend: Backend::Ffmpeg, factor: UpscaleConfig::default().factor.max(1), client: reqwest::Client::new() }
+    }
+}
+
+impl ImageUpscaler {
+    pub fn new(cfg: &UpscaleConfig) -> Result<Self, FactoryError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(cfg.timeout_secs))
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build upscaler HTTP client: {}", e) })?;
+        Ok(Self { backend: Backend::from_config(cfg), factor: cfg.factor.max(1), client })
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            Backend::Ffmpeg => "ffmpeg",
+            Backend::Comfy { .. } => "comfy",
+            Backend::Sidecar { .. } => "realesrgan",
+        }
+    }
+
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    /// `input` を拡大して `output` に置く。GPU の方式が失敗したら lanczos で拡大する
+    pub async fn upscale(&self, input: &Path, output: &Path, forge: &MediaForgeClient, comfy: &ComfyBridgeClient) -> Result<Upscaled, FactoryError> {
+        let res = match &self.backend {
+            Backend::Ffmpeg => Err(None),
+            Backend::Comfy { workflow } => self.upscale_comfy(input, output, workflow, comfy).await.map_err(Some),
+            Backend::Sidecar { url } => self.upscale_sidecar(input, output, url).await.map_err(Some),
+        };
+        match res {
+            Ok(path) => Ok(Upscaled { path, on_gpu: true }),
+            Err(e) => {
+                if let Some(e) = e {
+                    warn!("⚠️ Upscale ({}): {} failed, falling back to ffmpeg: {}", self.backend_name(), input.display(), e);
+                }
+                let path = forge.upscale_image(input, self.factor, output).await?;
+                Ok(Upscaled { path, on_gpu: false })
+            }
+        }
+    }
+
+    async fn upscale_comfy(&self, input: &Path, output: &Path, workflow: &str, comfy: &ComfyBridgeClient) -> Result<PathBuf, FactoryError> {
+        let res = comfy.upscale_image(input, workflow).await?;
+        let copied = tokio::fs::copy(&res.output_path, output).await;
+        comfy.delete_output_debris(&res.job_id);
+        copied.map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to copy upscaled image {}: {}", res.output_path, e) })?;
+        Ok(output.to_path_buf())
+    }
+
+    async fn upscale_sidecar(&self, input: &Path, output: &Path, url: &str) -> Result<PathBuf, FactoryError> {
+        let (path, out) = (input.to_string_lossy(), output.to_string_lossy());
+        let res = self.client
+            .post(format!("{}/upscale", url))
+            .json(&UpscaleRequest { path: &path, output: &out, scale: self.factor })
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "Real-ESRGAN".into(), reason: e.to_string() })?;
+        if !res.status().is_success() {
+            return Err(FactoryError::from_http_status("Real-ESRGAN", res.status().as_u16(), ""));
+        }
+        let body: UpscaleResponse = res.json().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid Real-ESRGAN response: {}", e) })?;
+        let written = body.path.map(PathBuf::from).unwrap_or_else(|| output.to_path_buf());
+        if !written.exists() {
+            return Err(FactoryError::Infrastructure { reason: format!("Real-ESRGAN did not write {}", written.display()) });
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_falls_back_to_ffmpeg_when_unconfigured() {
+        let cfg = |backend: &str, url: &str| UpscaleConfig { backend: backend.into(), realesrgan_url: url.into(), ..Default::default() };
+        assert_eq!(Backend::from_config(&cfg("ffmpeg", "")), Backend::Ffmpeg);
+        assert_eq!(Backend::from_config(&cfg("Comfy", "")), Backend::Comfy { workflow: "upscale_esrgan_v1".into() });
+        assert_eq!(Backend::from_config(&cfg("realesrgan", "http://127.0.0.1:5070/")), Backend::Sidecar { url: "http://127.0.0.1:5070".into() });
+        assert_eq!(Backend::from_config(&cfg("realesrgan", "")), Backend::Ffmpeg);
+        assert_eq!(Backend::from_config(&cfg("waifu2x", "")), Backend::Ffmpeg);
+        assert_eq!(ImageUpscaler::default().backend_name(), "ffmpeg");
+    }
+}
@@ -1,18 +1,29 @@
 use async_trait::async_trait;
-use factory_core::traits::{Job, JobQueue, JobStatus, SnsMetricsRecord};
-use factory_core::contracts::OracleVerdict;
+use factory_core::traits::{Embedder, Job, JobQueue, JobStageSpan, JobStatus, NewSeries, ReviewRecord, ReviewVerdict, Series, SnsMetricsRecord, TakedownAction};
+use factory_core::contracts::{EpisodeContext, EpisodeLink, HookStat, JobProvenance, OracleVerdict, TitleVariant, DEFAULT_EPISODE_TITLE};
 use factory_core::error::FactoryError;
+use factory_core::cost::{CostEntry, CostLedger};
+use factory_core::fingerprint::{FingerprintStore, VisualFingerprint};
+use factory_core::api::{ApiScope, ApiTokenInfo, AuditRecord, AuditSummaryLine, CostLine, CostSummary, CronOutcome, CronRun, JobCosts};
 use sqlx::{SqlitePool, Row};
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 use chrono::Utc;
+use shared::config::DEFAULT_CHANNEL;
+use crate::karma_archive::{KarmaConflict, KarmaImportReport, KarmaRecord};
+use crate::soul_history::SoulVersion;
 
 /// Job Queue that utilizes SQLite in WAL Mode to allow multi-threaded queue operations.
 /// Implements **The Immortal Samsara Schema** — crash-resistant, self-healing, and eternal.
 #[derive(Clone)]
 pub struct SqliteJobQueue {
     pool: SqlitePool,
+    /// Karma の意味検索用 (None なら LIKE 検索)
+    embedder: Option<Arc<dyn Embedder>>,
+    /// 関連スキル外の Karma を採用する最低類似度
+    min_karma_similarity: f32,
 }
 
 impl SqliteJobQueue {
@@ -31,11 +42,18 @@ impl SqliteJobQueue {
             .await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to connect to SQLite: {}", e) })?;
 
-        let queue = Self { pool };
+        let queue = Self { pool, embedder: None, min_karma_similarity: 0.0 };
         queue.init_db().await?;
         Ok(queue)
     }
 
+    /// Karma の保存・検索に埋め込みを使う
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>, min_similarity: f32) -> Self {
+        self.embedder = Some(embedder);
+        self.min_karma_similarity = min_similarity;
+        self
+    }
+
     /// Read-only reference to the connection pool (for advanced queries).
     pub fn pool_ref(&self) -> &SqlitePool {
         &self.pool
@@ -86,6 +104,26 @@ impl SqliteJobQueue {
             "ALTER TABLE jobs ADD COLUMN published_at TEXT",
             "ALTER TABLE jobs ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0",
             "ALTER TABLE jobs ADD COLUMN output_videos TEXT",
+            "ALTER TABLE jobs ADD COLUMN depends_on TEXT",
+            "ALTER TABLE jobs ADD COLUMN channel TEXT NOT NULL DEFAULT 'default'",
+            "ALTER TABLE jobs ADD COLUMN soul TEXT",
+            "ALTER TABLE jobs ADD COLUMN provenance TEXT",
+            "ALTER TABLE jobs ADD COLUMN takedown_action TEXT",
+            "ALTER TABLE jobs ADD COLUMN takedown_at TEXT",
+            // 手動リトライ回数 (retry_count は Distillation の Poison Pill 用なので分ける)
+            "ALTER TABLE jobs ADD COLUMN requeue_count INTEGER NOT NULL DEFAULT 0",
+            // Series / Episodes
+            "ALTER TABLE jobs ADD COLUMN series_id TEXT",
+            "ALTER TABLE jobs ADD COLUMN episode_number INTEGER",
+            // Scheduled publishing
+            "ALTER TABLE jobs ADD COLUMN scheduled_for TEXT",
+            // Watch folder: 取り込んだ手動レンダーの絶対パス
+            "ALTER TABLE jobs ADD COLUMN footage TEXT",
+            // 一時的な失敗による自動再試行の回数 (手動の requeue_count とは別)
+            "ALTER TABLE jobs ADD COLUMN transient_failures INTEGER NOT NULL DEFAULT 0",
+            // タイトル・フックの A/B 案 (JSON) と、実際に公開した案のラベル
+            "ALTER TABLE jobs ADD COLUMN title_variants TEXT",
+            "ALTER TABLE jobs ADD COLUMN published_variant TEXT",
         ] {
             let _ = sqlx::query(migration).execute(&self.pool).await;
         }
@@ -110,6 +148,8 @@ impl SqliteJobQueue {
         // Indices for optimal performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_jobs_status_started ON jobs(status, started_at);")
             .execute(&self.pool).await.ok();
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_jobs_depends_on ON jobs(depends_on);")
+            .execute(&self.pool).await.ok();
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_karma_logs_skill_weight ON karma_logs(related_skill, weight DESC);")
             .execute(&self.pool).await.ok();
         
@@ -144,10 +184,31 @@ impl SqliteJobQueue {
             "ALTER TABLE sns_metrics_history ADD COLUMN raw_comments_json TEXT",
             "ALTER TABLE sns_metrics_history ADD COLUMN is_finalized INTEGER NOT NULL DEFAULT 0",
             "ALTER TABLE sns_metrics_history ADD COLUMN retry_count INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE sns_metrics_history ADD COLUMN platform TEXT",
+            // 計測時点で公開されていたタイトル案 (A/B 案の無いジョブは NULL)
+            "ALTER TABLE sns_metrics_history ADD COLUMN title_variant TEXT",
+            // Oracle のアンサンブル: 審判したモデル (カンマ区切り)、スコアが割れたか
+            "ALTER TABLE sns_metrics_history ADD COLUMN oracle_judges TEXT",
+            "ALTER TABLE sns_metrics_history ADD COLUMN oracle_disagreement INTEGER NOT NULL DEFAULT 0",
             "ALTER TABLE karma_logs ADD COLUMN soul_version_hash TEXT",
+            // Semantic Karma: f32 LE の BLOB と、それを生成したモデル名
+            "ALTER TABLE karma_logs ADD COLUMN embedding BLOB",
+            "ALTER TABLE karma_logs ADD COLUMN embedding_model TEXT",
+            // 蒸留した教訓の人間レビュー: 'pending' の間は weight 0 で注入されず、置き換える教訓の ID (JSON 配列) を持つ
+            "ALTER TABLE karma_logs ADD COLUMN review_status TEXT",
+            "ALTER TABLE karma_logs ADD COLUMN supersedes TEXT",
+            // 教訓の名前空間 (スタイル名やカテゴリ)。NULL はどの名前空間にも注入する共通の教訓
+            "ALTER TABLE karma_logs ADD COLUMN namespace TEXT",
         ] {
             let _ = sqlx::query(migration).execute(&self.pool).await;
         }
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_karma_logs_namespace ON karma_logs(namespace, related_skill, weight DESC);")
+            .execute(&self.pool).await.ok();
+        // platform 列より前の記録は、計測対象ジョブの投稿先で埋める
+        sqlx::query(
+            "UPDATE sns_metrics_history SET platform = (SELECT lower(sns_platform) FROM jobs WHERE jobs.id = sns_metrics_history.job_id)
+             WHERE platform IS NULL"
+        ).execute(&self.pool).await.ok();
         
         // --- Phase 12: Project Ani Foundation ---
         sqlx::query(
@@ -181,6 +242,91 @@ impl SqliteJobQueue {
         .execute(&self.pool)
         .await
         .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create system_state table: {}", e) })?;
+        // 一時停止の切り替えは UPSERT の変更行数で判定するので、行を先に用意しておく (`set_paused`)
+        sqlx::query("INSERT OR IGNORE INTO system_state (key, value) VALUES ('paused', '0')")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to seed system_state: {}", e) })?;
+
+        // 連載シリーズ: 話数カウンタはエピソード投入と同じトランザクションで進める
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS series (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                topic_template TEXT NOT NULL DEFAULT '',
+                title_template TEXT NOT NULL DEFAULT '',
+                style TEXT NOT NULL DEFAULT '',
+                channel TEXT NOT NULL DEFAULT 'default',
+                soul TEXT,
+                episode_counter INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT DEFAULT (datetime('now'))
+            );"
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create series table: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_jobs_series ON jobs(series_id, episode_number);")
+            .execute(&self.pool).await.ok();
+
+        // レビューリンク経由の判定 (ジョブを持たない Remix プロジェクトも記録する)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS reviews (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                job_id TEXT,
+                verdict TEXT NOT NULL,
+                comment TEXT NOT NULL DEFAULT '',
+                reviewer TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL
+            );"
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create reviews table: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_reviews_project ON reviews(project_id, created_at);")
+            .execute(&self.pool).await.ok();
+
+        // ジョブ内の工程ごとの実行区間 (タイムライン表示用)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS job_stages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_id TEXT NOT NULL,
+                stage TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                ended_at TEXT,
+                FOREIGN KEY(job_id) REFERENCES jobs(id) ON DELETE CASCADE
+            );"
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create job_stages table: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_job_stages_job ON job_stages(job_id, id);")
+            .execute(&self.pool).await.ok();
+
+        // コスト台帳 (ジョブが消えても集計用に残す)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS cost_ledger (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_id TEXT,
+                project_id TEXT,
+                provider TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                api_calls INTEGER NOT NULL DEFAULT 0,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                gpu_secs REAL NOT NULL DEFAULT 0,
+                estimated_usd REAL NOT NULL DEFAULT 0,
+                recorded_at TEXT NOT NULL,
+                FOREIGN KEY(job_id) REFERENCES jobs(id) ON DELETE SET NULL
+            );"
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create cost_ledger table: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_cost_ledger_job ON cost_ledger(job_id);")
+            .execute(&self.pool).await.ok();
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_cost_ledger_recorded ON cost_ledger(recorded_at);")
+            .execute(&self.pool).await.ok();
 
         // --- Watchtower Memory Distillation Tables ---
         sqlx::query(
@@ -211,26 +357,208 @@ impl SqliteJobQueue {
         .execute(&self.pool).await
         .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create chat_memory_summaries: {}", e) })?;
 
+        // /forget の監査記録 (消した内容そのものは残さない)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS chat_memory_wipes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id TEXT NOT NULL,
+                requested_by TEXT NOT NULL,
+                messages_deleted INTEGER NOT NULL,
+                summary_deleted INTEGER NOT NULL,
+                wiped_at TEXT DEFAULT (datetime('now'))
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create chat_memory_wipes: {}", e) })?;
+
+        // Serve API のスコープ付きトークン (平文は発行時に一度だけ返し、SHA-256 のみ保存)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS api_tokens (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                scope TEXT NOT NULL,
+                hint TEXT NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                created_at TEXT DEFAULT (datetime('now')),
+                revoked_at TEXT,
+                last_used_at TEXT
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create api_tokens: {}", e) })?;
+
+        // 制御系の操作の監査ログ (追記のみ: 更新・削除はトリガーで拒否する)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                action TEXT NOT NULL,
+                target TEXT,
+                detail TEXT,
+                created_at TEXT DEFAULT (datetime('now'))
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create audit_log: {}", e) })?;
+        for (name, event) in [("audit_log_no_update", "UPDATE"), ("audit_log_no_delete", "DELETE")] {
+            sqlx::query(&format!(
+                "CREATE TRIGGER IF NOT EXISTS {} BEFORE {} ON audit_log BEGIN SELECT RAISE(ABORT, 'audit_log is append-only'); END;",
+                name, event
+            ))
+            .execute(&self.pool).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create {}: {}", name, e) })?;
+        }
+
+        // cron ジョブの実行履歴 (開始時に running で積み、終わったら結末を書き込む)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS cron_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                outcome TEXT NOT NULL DEFAULT 'running',
+                detail TEXT
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create cron_runs: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_cron_runs_job ON cron_runs (job, id);")
+            .execute(&self.pool).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create idx_cron_runs_job: {}", e) })?;
+
+        // シーン素材の指紋 (プロンプトの SimHash と静止画の dHash。u64 はビットそのままで i64 に入れる)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS visual_fingerprints (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                project_id TEXT NOT NULL,
+                scene INTEGER NOT NULL,
+                prompt_hash INTEGER NOT NULL,
+                image_hash INTEGER,
+                created_at TEXT DEFAULT (datetime('now'))
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create visual_fingerprints: {}", e) })?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_visual_fingerprints_created ON visual_fingerprints(created_at)")
+            .execute(&self.pool).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to index visual_fingerprints: {}", e) })?;
+
+        // SOUL の版 (本文ごと残し、Karma の soul_version_hash から引けるようにする)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS soul_versions (
+                source TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                content TEXT NOT NULL,
+                recorded_at TEXT NOT NULL,
+                PRIMARY KEY (source, hash)
+            );"
+        )
+        .execute(&self.pool).await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create soul_versions: {}", e) })?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FingerprintStore for SqliteJobQueue {
+    async fn record_fingerprint(&self, fingerprint: &VisualFingerprint) -> Result<(), FactoryError> {
+        // 描き直したシーンは古い指紋を置き換える
+        sqlx::query("DELETE FROM visual_fingerprints WHERE project_id = ? AND scene = ?")
+            .bind(&fingerprint.project_id)
+            .bind(fingerprint.scene as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to replace fingerprint: {}", e) })?;
+        sqlx::query("INSERT INTO visual_fingerprints (project_id, scene, prompt_hash, image_hash) VALUES (?, ?, ?, ?)")
+            .bind(&fingerprint.project_id)
+            .bind(fingerprint.scene as i64)
+            .bind(fingerprint.prompt_hash as i64)
+            .bind(fingerprint.image_hash.map(|h| h as i64))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record fingerprint: {}", e) })?;
+        Ok(())
+    }
+
+    async fn recent_fingerprints(&self, days: i64, exclude_project: &str) -> Result<Vec<VisualFingerprint>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT project_id, scene, prompt_hash, image_hash FROM visual_fingerprints
+             WHERE created_at >= datetime('now', ?) AND project_id != ?
+             ORDER BY id DESC"
+        )
+        .bind(format!("-{} days", days))
+        .bind(exclude_project)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch fingerprints: {}", e) })?;
+        Ok(rows
+            .iter()
+            .map(|row| VisualFingerprint {
+                project_id: row.get("project_id"),
+                scene: row.get::<i64, _>("scene") as usize,
+                prompt_hash: row.get::<i64, _>("prompt_hash") as u64,
+                image_hash: row.get::<Option<i64>, _>("image_hash").map(|h| h as u64),
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl CostLedger for SqliteJobQueue {
+    async fn record_cost(&self, job_id: Option<&str>, project_id: Option<&str>, entry: &CostEntry) -> Result<(), FactoryError> {
+        sqlx::query(
+            "INSERT INTO cost_ledger (job_id, project_id, provider, operation, api_calls, input_tokens, output_tokens, gpu_secs, estimated_usd, recorded_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(job_id)
+        .bind(project_id)
+        .bind(&entry.provider)
+        .bind(&entry.operation)
+        .bind(entry.api_calls)
+        .bind(entry.input_tokens)
+        .bind(entry.output_tokens)
+        .bind(entry.gpu_secs)
+        .bind(entry.estimated_usd)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record cost: {}", e) })?;
         Ok(())
     }
 }
 
+/// 取り消したジョブの error_message
+pub const CANCELLED_REASON: &str = "CANCELLED: by operator";
+
 #[async_trait]
 impl JobQueue for SqliteJobQueue {
     async fn enqueue(&self, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError> {
+        self.enqueue_for_channel(DEFAULT_CHANNEL, None, topic, style, karma_directives).await
+    }
+
+    async fn enqueue_for_channel(&self, channel: &str, soul: Option<&str>, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError> {
+        self.enqueue_at(channel, soul, topic, style, karma_directives, None).await
+    }
+
+    async fn enqueue_at(&self, channel: &str, soul: Option<&str>, topic: &str, style: &str, karma_directives: Option<&str>, scheduled_for: Option<&str>) -> Result<String, FactoryError> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
         // Default to empty JSON object if None, satisfying CHECK(json_valid(...))
         let directives = karma_directives.unwrap_or("{}");
 
         sqlx::query(
-            "INSERT INTO jobs (id, topic, style_name, karma_directives, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+            "INSERT INTO jobs (id, topic, style_name, karma_directives, status, channel, soul, scheduled_for, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(&id)
         .bind(topic)
         .bind(style)
         .bind(directives)
         .bind(JobStatus::Pending.to_string())
+        .bind(channel)
+        .bind(soul)
+        .bind(scheduled_for)
         .bind(&now)
         .bind(&now)
         .execute(&self.pool)
@@ -240,9 +568,52 @@ impl JobQueue for SqliteJobQueue {
         Ok(id)
     }
 
+    async fn enqueue_footage(&self, channel: &str, topic: &str, style: &str, footage: &str) -> Result<String, FactoryError> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            "INSERT INTO jobs (id, topic, style_name, karma_directives, status, channel, footage, created_at, updated_at) VALUES (?, ?, ?, '{}', ?, ?, ?, ?, ?)"
+        )
+        .bind(&id)
+        .bind(topic)
+        .bind(style)
+        .bind(JobStatus::Pending.to_string())
+        .bind(channel)
+        .bind(footage)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to enqueue footage job: {}", e) })?;
+
+        Ok(id)
+    }
+
+    async fn enqueue_child(&self, parent_id: &str, topic: &str, style: &str, karma_directives: Option<&str>) -> Result<String, FactoryError> {
+        let parent: Option<(String, String, Option<String>)> = sqlx::query_as("SELECT status, channel, soul FROM jobs WHERE id = ?")
+            .bind(parent_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up parent job {}: {}", parent_id, e) })?;
+        let (parent_channel, parent_soul) = match parent {
+            None => return Err(FactoryError::Infrastructure { reason: format!("Parent job {} not found", parent_id) }),
+            Some((status, _, _)) if status == "Failed" => return Err(FactoryError::Infrastructure { reason: format!("Parent job {} has already failed", parent_id) }),
+            Some((_, channel, soul)) => (channel, soul),
+        };
+
+        let id = self.enqueue_for_channel(&parent_channel, parent_soul.as_deref(), topic, style, karma_directives).await?;
+        sqlx::query("UPDATE jobs SET depends_on = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(&id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to link job {} to parent {}: {}", id, parent_id, e) })?;
+        Ok(id)
+    }
+
     async fn fetch_job(&self, job_id: &str) -> Result<Option<Job>, FactoryError> {
         let row = sqlx::query(
-            "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, tech_karma_extracted, creative_rating, execution_log, error_message, sns_platform, sns_video_id, published_at, output_videos FROM jobs WHERE id = ?"
+            "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, tech_karma_extracted, creative_rating, execution_log, error_message, sns_platform, sns_video_id, published_at, output_videos, depends_on, channel, soul, scheduled_for, footage FROM jobs WHERE id = ?"
         )
         .bind(job_id)
         .fetch_optional(&self.pool)
@@ -262,6 +633,9 @@ impl JobQueue for SqliteJobQueue {
             let sns_video_id: Option<String> = try_get_optional_string(&r, "sns_video_id");
             let published_at: Option<String> = try_get_optional_string(&r, "published_at");
             let output_videos: Option<String> = try_get_optional_string(&r, "output_videos");
+            let depends_on: Option<String> = try_get_optional_string(&r, "depends_on");
+            let channel = read_channel(&r);
+            let soul: Option<String> = try_get_optional_string(&r, "soul");
             let status_str: String = r.get("status");
             let status = JobStatus::from_string(&status_str);
 
@@ -281,6 +655,11 @@ impl JobQueue for SqliteJobQueue {
                 sns_video_id,
                 published_at,
                 output_videos,
+                depends_on,
+                channel,
+                soul,
+                scheduled_for: try_get_optional_string(&r, "scheduled_for"),
+                footage: try_get_optional_string(&r, "footage"),
             }))
         } else {
             Ok(None)
@@ -292,9 +671,15 @@ impl JobQueue for SqliteJobQueue {
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
 
         let row = sqlx::query(
-            "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, tech_karma_extracted, creative_rating, execution_log, error_message, sns_platform, sns_video_id, published_at, output_videos FROM jobs WHERE status = ? ORDER BY created_at ASC LIMIT 1"
+            "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, tech_karma_extracted, creative_rating, execution_log, error_message, sns_platform, sns_video_id, published_at, output_videos, depends_on, channel, soul, scheduled_for, footage FROM jobs
+             WHERE status = ?
+             AND NOT EXISTS (SELECT 1 FROM jobs parent WHERE parent.id = jobs.depends_on AND parent.status != ?)
+             AND (scheduled_for IS NULL OR scheduled_for <= ?)
+             ORDER BY COALESCE(scheduled_for, created_at) ASC LIMIT 1"
         )
         .bind(JobStatus::Pending.to_string())
+        .bind(JobStatus::Completed.to_string())
+        .bind(Utc::now().to_rfc3339())
         .fetch_optional(&mut *tx)
         .await
         .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch pending job: {}", e) })?;
@@ -312,6 +697,9 @@ impl JobQueue for SqliteJobQueue {
             let sns_video_id: Option<String> = try_get_optional_string(&r, "sns_video_id");
             let published_at: Option<String> = try_get_optional_string(&r, "published_at");
             let output_videos: Option<String> = try_get_optional_string(&r, "output_videos");
+            let depends_on: Option<String> = try_get_optional_string(&r, "depends_on");
+            let channel = read_channel(&r);
+            let soul: Option<String> = try_get_optional_string(&r, "soul");
 
             let now = Utc::now().to_rfc3339();
             // Set status to Processing, record started_at AND first heartbeat
@@ -344,6 +732,11 @@ impl JobQueue for SqliteJobQueue {
                 sns_video_id,
                 published_at,
                 output_videos,
+                depends_on,
+                channel,
+                soul,
+                scheduled_for: try_get_optional_string(&r, "scheduled_for"),
+                footage: try_get_optional_string(&r, "footage"),
             }))
         } else {
             Ok(None)
@@ -365,133 +758,322 @@ impl JobQueue for SqliteJobQueue {
 
     async fn fail_job(&self, job_id: &str, reason: &str) -> Result<(), FactoryError> {
         let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
+
         sqlx::query("UPDATE jobs SET status = ?, error_message = ?, updated_at = ? WHERE id = ?")
             .bind(JobStatus::Failed.to_string())
             .bind(reason)
             .bind(&now)
             .bind(job_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fail job {}: {}", job_id, e) })?;
+        Self::cascade_dependency_failure(&mut tx, job_id, &now).await?;
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
         Ok(())
     }
 
-    async fn fetch_relevant_karma(&self, topic: &str, skill_id: &str, limit: i64, current_soul_hash: &str) -> Result<Vec<String>, FactoryError> {
-        // Boltzmann RAG: Time-Decay Karma Injection
-        // - effective_weight = max(0, weight - days_since_creation * 0.5)
-        // - Older karma naturally fades, preventing the Success Trap
-        // - Fresh insights are always prioritized
-        let topic_pattern = format!("%{}%", topic);
+    async fn retry_job(&self, job_id: &str) -> Result<u64, FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
 
-        let rows = sqlx::query(
-            "SELECT id, lesson, soul_version_hash,
-              max(0, weight - (julianday('now') - julianday(created_at)) * 0.5) AS effective_weight
-             FROM karma_logs 
-             WHERE weight > 0 AND (related_skill = ? OR related_skill = 'global' OR lesson LIKE ?) 
-             ORDER BY effective_weight DESC, created_at DESC LIMIT ?"
+        let row: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT j.status, p.status FROM jobs j LEFT JOIN jobs p ON p.id = j.depends_on WHERE j.id = ?"
         )
-        .bind(skill_id)
-        .bind(&topic_pattern)
-        .bind(limit)
-        .fetch_all(&self.pool)
+        .bind(job_id)
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch relevant karma: {}", e) })?;
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up job {}: {}", job_id, e) })?;
 
-        let mut karma = Vec::new();
-        for row in &rows {
-            let lesson: String = row.get("lesson");
-            let karma_hash: Option<String> = try_get_optional_string(row, "soul_version_hash");
-            
-            let mut processed_lesson = lesson;
-            if let Some(h) = karma_hash {
-                // The Cognitive Dissonance Trap Fix: Warn LLM if this karma is from a different era
-                if h != current_soul_hash {
-                    processed_lesson = format!("[LEGACY KARMA - from an older Soul version]\n{}", processed_lesson);
-                }
+        match row {
+            None => return Err(FactoryError::Infrastructure { reason: format!("Job {} not found", job_id) }),
+            Some((status, _)) if status != JobStatus::Failed.to_string() => {
+                return Err(FactoryError::Infrastructure { reason: format!("Job {} is {}, only Failed jobs can be retried", job_id, status) });
             }
-            karma.push(processed_lesson);
-        }
-
-        // Update last_applied_at for applied karma entries (Usage Tracking for TTL Decay)
-        let now = Utc::now().to_rfc3339();
-        for row in &rows {
-            let karma_id: String = row.get("id");
-            let _ = sqlx::query("UPDATE karma_logs SET last_applied_at = ? WHERE id = ?")
-                .bind(&now)
-                .bind(&karma_id)
-                .execute(&self.pool)
-                .await;
+            Some((_, Some(parent_status))) if parent_status == JobStatus::Failed.to_string() => {
+                return Err(FactoryError::Infrastructure { reason: format!("Job {} depends on a failed parent; retry the parent instead", job_id) });
+            }
+            _ => {}
         }
 
-        Ok(karma)
-    }
-
-    async fn store_karma(&self, job_id: &str, skill_id: &str, lesson: &str, karma_type: &str, soul_hash: &str) -> Result<(), FactoryError> {
-        let id = Uuid::new_v4().to_string();
+        // 本体 + 本体の失敗に巻き込まれた子孫 (error_message は次の失敗まで履歴として残す)
         let now = Utc::now().to_rfc3339();
-        sqlx::query(
-            "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, lesson, soul_version_hash, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)"
+        let result = sqlx::query(
+            "WITH RECURSIVE cascade(id) AS (
+                SELECT ?
+                UNION SELECT j.id FROM jobs j JOIN cascade c ON j.depends_on = c.id
+                WHERE j.status = ? AND j.error_message = ?
+             )
+             UPDATE jobs SET status = ?, requeue_count = requeue_count + 1, transient_failures = 0, started_at = NULL, last_heartbeat = NULL, updated_at = ?
+             WHERE id IN cascade"
         )
-        .bind(&id)
         .bind(job_id)
-        .bind(karma_type)
-        .bind(skill_id)
-        .bind(lesson)
-        .bind(soul_hash)
+        .bind(JobStatus::Failed.to_string())
+        .bind(format!("DEPENDENCY_FAILED: {}", job_id))
+        .bind(JobStatus::Pending.to_string())
         .bind(&now)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to store karma for job {}: {}", job_id, e) })?;
-        Ok(())
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to retry job {}: {}", job_id, e) })?;
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
+        Ok(result.rows_affected())
     }
 
-    /// The Zombie Hunter (Heartbeat Edition): Reclaims jobs whose heartbeat has gone silent.
-    /// Uses `last_heartbeat` instead of `started_at`, preventing false kills on long-running jobs.
-    async fn reclaim_zombie_jobs(&self, timeout_minutes: i64) -> Result<u64, FactoryError> {
+    async fn cancel_job(&self, job_id: &str) -> Result<(), FactoryError> {
+        // dequeue と競合しないよう、Pending のときだけ書き換える
         let now = Utc::now().to_rfc3339();
-        let result = sqlx::query(
-            "UPDATE jobs SET status = 'Failed', error_message = 'Zombie reclaimed: heartbeat timeout exceeded', updated_at = ? 
-             WHERE status = 'Processing' 
-             AND last_heartbeat IS NOT NULL 
-             AND (julianday('now') - julianday(last_heartbeat)) * 24 * 60 > ?"
+        let result = sqlx::query("UPDATE jobs SET status = ?, error_message = ?, updated_at = ? WHERE id = ? AND status = ?")
+            .bind(JobStatus::Failed.to_string())
+            .bind(CANCELLED_REASON)
+            .bind(&now)
+            .bind(job_id)
+            .bind(JobStatus::Pending.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to cancel job {}: {}", job_id, e) })?;
+        if result.rows_affected() == 0 {
+            return match self.fetch_job(job_id).await? {
+                None => Err(FactoryError::Infrastructure { reason: format!("Job {} not found", job_id) }),
+                Some(job) => Err(FactoryError::InvalidJobState { reason: format!("Job {} is {}, only Pending jobs can be cancelled", job_id, job.status) }),
+            };
+        }
+        // 子孫の連鎖失敗は fail_job と同じ
+        self.fail_job(job_id, CANCELLED_REASON).await
+    }
+
+    async fn defer_retry(&self, job_id: &str, reason: &str, max_attempts: i64, base_delay_secs: i64) -> Result<Option<(i64, String)>, FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
+
+        let failures: i64 = sqlx::query_scalar("SELECT transient_failures FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up job {}: {}", job_id, e) })?
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("Job {} not found", job_id) })?;
+        let attempt = failures + 1;
+        // 上限に達したら何も書き換えない (status は呼び出し側の fail_job に任せる)
+        if attempt >= max_attempts {
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        let delay = base_delay_secs.max(0).saturating_mul(1 << (attempt - 1).min(16));
+        let retry_at = (now + chrono::Duration::seconds(delay)).to_rfc3339();
+        sqlx::query(
+            "UPDATE jobs SET status = ?, transient_failures = ?, error_message = ?, scheduled_for = ?,
+                    started_at = NULL, last_heartbeat = NULL, updated_at = ?
+             WHERE id = ?"
         )
-        .bind(&now)
-        .bind(timeout_minutes)
-        .execute(&self.pool)
+        .bind(JobStatus::Pending.to_string())
+        .bind(attempt)
+        .bind(reason)
+        .bind(&retry_at)
+        .bind(now.to_rfc3339())
+        .bind(job_id)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to reclaim zombie jobs: {}", e) })?;
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to defer job {}: {}", job_id, e) })?;
 
-        let count = result.rows_affected();
-        if count > 0 {
-            tracing::warn!("🧟 Zombie Hunter: Reclaimed {} ghost job(s)", count);
-        }
-        Ok(count)
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
+        Ok(Some((attempt, retry_at)))
     }
 
-    /// Sets the creative rating for a completed job (Human-in-the-Loop, Asynchronous Karma).
-    /// Atomic Guard: Only Completed or Processing jobs can receive ratings.
-    async fn set_creative_rating(&self, job_id: &str, rating: i32) -> Result<(), FactoryError> {
+    async fn requeue_interrupted(&self, job_id: &str, reason: &str) -> Result<bool, FactoryError> {
         let now = Utc::now().to_rfc3339();
         let result = sqlx::query(
-            "UPDATE jobs SET creative_rating = ?, updated_at = ? WHERE id = ? AND status IN ('Completed', 'Processing')"
+            "UPDATE jobs SET status = ?, error_message = ?, started_at = NULL, last_heartbeat = NULL, updated_at = ?
+             WHERE id = ? AND status = ?"
         )
-        .bind(rating)
+        .bind(JobStatus::Pending.to_string())
+        .bind(reason)
         .bind(&now)
         .bind(job_id)
+        .bind(JobStatus::Processing.to_string())
         .execute(&self.pool)
         .await
-        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to set creative rating for job {}: {}", job_id, e) })?;
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to requeue interrupted job {}: {}", job_id, e) })?;
+        Ok(result.rows_affected() > 0)
+    }
 
-        if result.rows_affected() == 0 {
-            return Err(FactoryError::Infrastructure {
-                reason: format!("Atomic Guard: Job '{}' is not in Completed/Processing state, rating rejected", job_id),
-            });
-        }
-        Ok(())
+    async fn fetch_failed_jobs(&self, limit: i64) -> Result<Vec<shared::watchtower::FailedJobSummary>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, topic, channel, error_message, updated_at, requeue_count FROM jobs
+             WHERE status = ? ORDER BY updated_at DESC LIMIT ?"
+        )
+        .bind(JobStatus::Failed.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch failed jobs: {}", e) })?;
+
+        Ok(rows.iter().map(|r| shared::watchtower::FailedJobSummary {
+            job_id: r.get("id"),
+            topic: r.get("topic"),
+            channel: read_channel(r),
+            error_message: try_get_optional_string(r, "error_message"),
+            failed_at: try_get_optional_string(r, "updated_at").unwrap_or_default(),
+            requeue_count: r.get("requeue_count"),
+        }).collect())
+    }
+
+    async fn fetch_relevant_karma(&self, topic: &str, skill_id: &str, namespace: Option<&str>, limit: i64, current_soul_hash: &str) -> Result<Vec<String>, FactoryError> {
+        // Boltzmann RAG: Time-Decay Karma Injection
+        // - effective_weight = max(0, weight - days_since_creation * 0.5)
+        // - Older karma naturally fades, preventing the Success Trap
+        // - Fresh insights are always prioritized
+        let namespace = normalize_namespace(namespace);
+        if let Some(embedder) = &self.embedder {
+            match embedder.embed(topic).await {
+                Ok(query) => return self.fetch_semantic_karma(embedder.model_id(), &query, topic, skill_id, namespace, limit, current_soul_hash).await,
+                Err(e) => tracing::warn!("⚠️ Karma: Topic embedding failed, falling back to LIKE matching: {}", e),
+            }
+        }
+
+        let topic_pattern = format!("%{}%", topic);
+
+        let rows = sqlx::query(
+            "SELECT id, lesson, soul_version_hash,
+              max(0, weight - (julianday('now') - julianday(created_at)) * 0.5) AS effective_weight
+             FROM karma_logs 
+             WHERE weight > 0 AND (related_skill = ?1 OR related_skill = 'global' OR lesson LIKE ?2)
+               AND (?3 IS NULL OR namespace IS NULL OR namespace = ?3)
+             ORDER BY effective_weight DESC, created_at DESC LIMIT ?4"
+        )
+        .bind(skill_id)
+        .bind(&topic_pattern)
+        .bind(namespace)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch relevant karma: {}", e) })?;
+
+        let applied = rows.iter()
+            .map(|row| (row.get("id"), row.get("lesson"), try_get_optional_string(row, "soul_version_hash")))
+            .collect();
+        Ok(self.apply_karma(applied, current_soul_hash).await)
+    }
+
+    async fn store_karma(&self, job_id: &str, skill_id: &str, namespace: Option<&str>, lesson: &str, karma_type: &str, soul_hash: &str) -> Result<(), FactoryError> {
+        self.insert_karma(Some(job_id), skill_id, namespace, lesson, karma_type, 100, Some(soul_hash)).await.map(|_| ())
+    }
+
+    /// The Zombie Hunter (Heartbeat Edition): Reclaims jobs whose heartbeat has gone silent.
+    /// Uses `last_heartbeat` instead of `started_at`, preventing false kills on long-running jobs.
+    async fn reclaim_zombie_jobs(&self, timeout_minutes: i64) -> Result<u64, FactoryError> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
+
+        let reclaimed: Vec<(String,)> = sqlx::query_as(
+            "UPDATE jobs SET status = 'Failed', error_message = 'Zombie reclaimed: heartbeat timeout exceeded', updated_at = ? 
+             WHERE status = 'Processing' 
+             AND last_heartbeat IS NOT NULL 
+             AND (julianday('now') - julianday(last_heartbeat)) * 24 * 60 > ?
+             RETURNING id"
+        )
+        .bind(&now)
+        .bind(timeout_minutes)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to reclaim zombie jobs: {}", e) })?;
+        for (id,) in &reclaimed {
+            Self::cascade_dependency_failure(&mut tx, id, &now).await?;
+        }
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
+
+        let count = reclaimed.len() as u64;
+        if count > 0 {
+            tracing::warn!("🧟 Zombie Hunter: Reclaimed {} ghost job(s)", count);
+        }
+        Ok(count)
+    }
+
+    /// Sets the creative rating for a completed job (Human-in-the-Loop, Asynchronous Karma).
+    /// Atomic Guard: Only Completed or Processing jobs can receive ratings.
+    async fn set_creative_rating(&self, job_id: &str, rating: i32) -> Result<(), FactoryError> {
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query(
+            "UPDATE jobs SET creative_rating = ?, updated_at = ? WHERE id = ? AND status IN ('Completed', 'Processing')"
+        )
+        .bind(rating)
+        .bind(&now)
+        .bind(job_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to set creative rating for job {}: {}", job_id, e) })?;
+
+        if result.rows_affected() == 0 {
+            return Err(FactoryError::Infrastructure {
+                reason: format!("Atomic Guard: Job '{}' is not in Completed/Processing state, rating rejected", job_id),
+            });
+        }
+        Ok(())
+    }
+
+    async fn record_review(&self, project_id: &str, job_id: Option<&str>, verdict: ReviewVerdict, comment: &str, reviewer: &str) -> Result<bool, FactoryError> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to begin transaction: {}", e) })?;
+
+        sqlx::query("INSERT INTO reviews (project_id, job_id, verdict, comment, reviewer, created_at) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(project_id)
+            .bind(job_id)
+            .bind(verdict.to_string())
+            .bind(comment)
+            .bind(reviewer)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record review for {}: {}", project_id, e) })?;
+
+        let mut rated = false;
+        if let Some(job_id) = job_id {
+            let result = sqlx::query("UPDATE jobs SET creative_rating = ?, updated_at = ? WHERE id = ? AND status = 'Completed'")
+                .bind(verdict.creative_rating())
+                .bind(&now)
+                .bind(job_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to set creative rating for job {}: {}", job_id, e) })?;
+            rated = result.rows_affected() > 0;
+        }
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit review: {}", e) })?;
+        Ok(rated)
+    }
+
+    async fn fetch_reviews(&self, project_id: &str) -> Result<Vec<ReviewRecord>, FactoryError> {
+        let rows = sqlx::query("SELECT project_id, job_id, verdict, comment, reviewer, created_at FROM reviews WHERE project_id = ? ORDER BY id DESC")
+            .bind(project_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch reviews for {}: {}", project_id, e) })?;
+        Ok(rows
+            .iter()
+            .map(|r| ReviewRecord {
+                project_id: r.get("project_id"),
+                job_id: try_get_optional_string(r, "job_id"),
+                verdict: if r.get::<String, _>("verdict") == "approve" { ReviewVerdict::Approve } else { ReviewVerdict::Revise },
+                comment: r.get("comment"),
+                reviewer: r.get("reviewer"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
     }
 
     /// The Heartbeat Pulse: Worker calls this periodically to prove it's alive.
     async fn heartbeat_pulse(&self, job_id: &str) -> Result<(), FactoryError> {
+        crate::chaos::inject(crate::chaos::Fault::DiskFull)?;
         let now = Utc::now().to_rfc3339();
         sqlx::query("UPDATE jobs SET last_heartbeat = ?, updated_at = ? WHERE id = ?")
             .bind(&now)
@@ -521,7 +1103,7 @@ impl JobQueue for SqliteJobQueue {
         let rows = sqlx::query(
             "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, 
                      tech_karma_extracted, creative_rating, execution_log, error_message,
-                     sns_platform, sns_video_id, published_at, output_videos 
+                     sns_platform, sns_video_id, published_at, output_videos, depends_on, channel, soul, scheduled_for, footage
               FROM jobs 
               WHERE execution_log IS NOT NULL 
               AND tech_karma_extracted = 0 
@@ -556,6 +1138,11 @@ impl JobQueue for SqliteJobQueue {
                 sns_video_id: try_get_optional_string(&r, "sns_video_id"),
                 published_at: try_get_optional_string(&r, "published_at"),
                 output_videos: try_get_optional_string(&r, "output_videos"),
+                depends_on: try_get_optional_string(&r, "depends_on"),
+                channel: read_channel(&r),
+                soul: try_get_optional_string(&r, "soul"),
+                scheduled_for: try_get_optional_string(&r, "scheduled_for"),
+                footage: try_get_optional_string(&r, "footage"),
             });
         }
         Ok(jobs)
@@ -607,17 +1194,34 @@ impl JobQueue for SqliteJobQueue {
         Ok(())
     }
 
+    async fn record_takedown(&self, job_id: &str, action: TakedownAction) -> Result<(), FactoryError> {
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query("UPDATE jobs SET takedown_action = ?, takedown_at = ?, updated_at = ? WHERE id = ? AND sns_video_id IS NOT NULL")
+            .bind(action.as_str())
+            .bind(&now)
+            .bind(&now)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record takedown for job {}: {}", job_id, e) })?;
+        if result.rows_affected() == 0 {
+            return Err(FactoryError::Infrastructure { reason: format!("Job {} not found or not linked to an SNS video", job_id) });
+        }
+        Ok(())
+    }
+
     async fn fetch_jobs_for_evaluation(&self, milestone_days: i64, limit: i64) -> Result<Vec<Job>, FactoryError> {
         // The Catch-up Logic: State-based query that finds jobs past their milestone without a record.
         let rows = sqlx::query(
             "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, 
                      tech_karma_extracted, creative_rating, execution_log, error_message,
-                     sns_platform, sns_video_id, published_at, output_videos 
+                     sns_platform, sns_video_id, published_at, output_videos, depends_on, channel, soul, scheduled_for, footage
               FROM jobs 
               WHERE sns_platform IS NOT NULL 
               AND sns_video_id IS NOT NULL 
               AND published_at IS NOT NULL
               AND published_at <= datetime('now', ? || ' days')
+              AND takedown_action IS NULL
               AND id NOT IN (SELECT job_id FROM sns_metrics_history WHERE milestone_days = ?)
               ORDER BY published_at ASC LIMIT ?"
         )
@@ -651,6 +1255,11 @@ impl JobQueue for SqliteJobQueue {
                 sns_video_id: try_get_optional_string(&r, "sns_video_id"),
                 published_at: try_get_optional_string(&r, "published_at"),
                 output_videos: try_get_optional_string(&r, "output_videos"),
+                depends_on: try_get_optional_string(&r, "depends_on"),
+                channel: read_channel(&r),
+                soul: try_get_optional_string(&r, "soul"),
+                scheduled_for: try_get_optional_string(&r, "scheduled_for"),
+                footage: try_get_optional_string(&r, "footage"),
             });
         }
         Ok(jobs)
@@ -660,6 +1269,7 @@ impl JobQueue for SqliteJobQueue {
     async fn record_sns_metrics(
         &self,
         job_id: &str,
+        platform: &str,
         milestone_days: i64,
         views: i64,
         likes: i64,
@@ -667,15 +1277,18 @@ impl JobQueue for SqliteJobQueue {
         raw_comments: Option<&str>,
     ) -> Result<(), FactoryError> {
         sqlx::query(
-            "INSERT INTO sns_metrics_history (job_id, milestone_days, views, likes, comments_count, raw_comments_json)
-             VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO sns_metrics_history (job_id, platform, milestone_days, views, likes, comments_count, raw_comments_json, title_variant)
+             VALUES (?, ?, ?, ?, ?, ?, ?,
+                     (SELECT CASE WHEN title_variants IS NULL THEN NULL ELSE COALESCE(published_variant, 'A') END FROM jobs WHERE id = ?))"
         )
         .bind(job_id)
+        .bind(platform)
         .bind(milestone_days)
         .bind(views)
         .bind(likes)
         .bind(comments_count)
         .bind(raw_comments)
+        .bind(job_id)
         .execute(&self.pool)
         .await
         .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record SNS metrics: {}", e) })?;
@@ -683,7 +1296,7 @@ impl JobQueue for SqliteJobQueue {
     }
     async fn fetch_pending_evaluations(&self, limit: i64) -> Result<Vec<SnsMetricsRecord>, FactoryError> {
         let rows = sqlx::query(
-            "SELECT id, job_id, milestone_days, views, likes, comments_count, raw_comments_json
+            "SELECT id, job_id, platform, milestone_days, views, likes, comments_count, raw_comments_json
              FROM sns_metrics_history
              WHERE is_finalized = 0
              LIMIT ?"
@@ -698,6 +1311,7 @@ impl JobQueue for SqliteJobQueue {
             out.push(SnsMetricsRecord {
                 id: row.get("id"),
                 job_id: row.get("job_id"),
+                platform: row.get("platform"),
                 milestone_days: row.get("milestone_days"),
                 views: row.get("views"),
                 likes: row.get("likes"),
@@ -720,13 +1334,16 @@ impl JobQueue for SqliteJobQueue {
         // 1. Update the Metrics Ledger (The Proof)
         sqlx::query(
             "UPDATE sns_metrics_history 
-             SET oracle_score_topic = ?, oracle_score_visual = ?, oracle_score_soul = ?, oracle_reason = ?, is_finalized = 1
+             SET oracle_score_topic = ?, oracle_score_visual = ?, oracle_score_soul = ?, oracle_reason = ?,
+                 oracle_judges = ?, oracle_disagreement = ?, is_finalized = 1
              WHERE id = ?"
         )
         .bind(verdict.topic_score)
         .bind(verdict.visual_score)
         .bind(verdict.soul_score)
         .bind(&verdict.reasoning)
+        .bind((!verdict.judges.is_empty()).then(|| verdict.judges.join(",")))
+        .bind(verdict.disagreement)
         .bind(record_id)
         .execute(&mut *tx)
         .await
@@ -759,8 +1376,8 @@ impl JobQueue for SqliteJobQueue {
                 let lesson = format!("SOUL VIOLATION / 魂の汚染: {}", verdict.reasoning);
                 
                 sqlx::query(
-                    "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, lesson, weight, soul_version_hash)
-                     VALUES (?, ?, ?, ?, ?, ?, ?)"
+                    "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, lesson, weight, soul_version_hash, namespace)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
                 )
                 .bind(&karma_id)
                 .bind(&job_id)
@@ -769,6 +1386,7 @@ impl JobQueue for SqliteJobQueue {
                 .bind(&lesson)
                 .bind(100) // 絶対的な掟として RAG のトップに固定
                 .bind(soul_hash)
+                .bind(normalize_namespace(Some(&style_name)))
                 .execute(&mut *tx)
                 .await
                 .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to inject Semantic Refinement: {}", e) })?;
@@ -778,13 +1396,14 @@ impl JobQueue for SqliteJobQueue {
             let weight = calculated_weight.clamp(0, 100);
 
             sqlx::query(
-                "INSERT INTO karma_logs (job_id, topic, style_name, lesson, weight, soul_version_hash)
-                 VALUES (?, ?, ?, ?, ?, ?)"
+                "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, namespace, lesson, weight, soul_version_hash)
+                 VALUES (?, ?, 'Creative', ?, ?, ?, ?, ?)"
             )
+            .bind(Uuid::new_v4().to_string())
             .bind(&job_id)
-            .bind(&topic)
             .bind(&style_name)
-            .bind(&verdict.reasoning)
+            .bind(normalize_namespace(Some(&style_name)))
+            .bind(format!("{} (topic: {})", verdict.reasoning, topic))
             .bind(weight)
             .bind(soul_hash)
             .execute(&mut *tx)
@@ -802,7 +1421,7 @@ impl JobQueue for SqliteJobQueue {
         let rows = sqlx::query(
             "SELECT id, topic, style_name, karma_directives, status, started_at, last_heartbeat, 
                      tech_karma_extracted, creative_rating, execution_log, error_message,
-                     sns_platform, sns_video_id, published_at, output_videos 
+                     sns_platform, sns_video_id, published_at, output_videos, depends_on, channel, soul, scheduled_for, footage
               FROM jobs 
               ORDER BY created_at DESC LIMIT ?"
         )
@@ -830,11 +1449,158 @@ impl JobQueue for SqliteJobQueue {
                 sns_video_id: try_get_optional_string(&r, "sns_video_id"),
                 published_at: try_get_optional_string(&r, "published_at"),
                 output_videos: try_get_optional_string(&r, "output_videos"),
+                depends_on: try_get_optional_string(&r, "depends_on"),
+                channel: read_channel(&r),
+                soul: try_get_optional_string(&r, "soul"),
+                scheduled_for: try_get_optional_string(&r, "scheduled_for"),
+                footage: try_get_optional_string(&r, "footage"),
             });
         }
         Ok(jobs)
     }
 
+    async fn create_series(&self, series: &NewSeries) -> Result<Series, FactoryError> {
+        let name = series.name.trim();
+        if name.is_empty() {
+            return Err(FactoryError::Infrastructure { reason: "Series name must not be empty".to_string() });
+        }
+        let id = Uuid::new_v4().to_string();
+        let title_template = if series.title_template.trim().is_empty() { DEFAULT_EPISODE_TITLE } else { series.title_template.trim() };
+        let channel = if series.channel.trim().is_empty() { DEFAULT_CHANNEL } else { series.channel.trim() };
+        sqlx::query(
+            "INSERT INTO series (id, name, topic_template, title_template, style, channel, soul, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(series.topic_template.trim())
+        .bind(title_template)
+        .bind(series.style.trim())
+        .bind(channel)
+        .bind(series.soul.as_deref().filter(|s| !s.is_empty()))
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => FactoryError::Infrastructure { reason: format!("Series '{}' already exists", name) },
+            e => FactoryError::Infrastructure { reason: format!("Failed to create series '{}': {}", name, e) },
+        })?;
+
+        self.fetch_series(&id).await?
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("Series '{}' vanished after insert", name) })
+    }
+
+    async fn fetch_series(&self, name_or_id: &str) -> Result<Option<Series>, FactoryError> {
+        let row = sqlx::query("SELECT * FROM series WHERE id = ? OR name = ?")
+            .bind(name_or_id)
+            .bind(name_or_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch series {}: {}", name_or_id, e) })?;
+        Ok(row.as_ref().map(read_series))
+    }
+
+    async fn list_series(&self) -> Result<Vec<Series>, FactoryError> {
+        let rows = sqlx::query("SELECT * FROM series ORDER BY name")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to list series: {}", e) })?;
+        Ok(rows.iter().map(read_series).collect())
+    }
+
+    async fn enqueue_episode(&self, series_id: &str, topic: Option<&str>, style: Option<&str>, karma_directives: Option<&str>, scheduled_for: Option<&str>) -> Result<(String, i64), FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to begin transaction: {}", e) })?;
+
+        let row = sqlx::query("UPDATE series SET episode_counter = episode_counter + 1 WHERE id = ? OR name = ? RETURNING *")
+            .bind(series_id)
+            .bind(series_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to allocate episode for series {}: {}", series_id, e) })?
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("Series {} not found", series_id) })?;
+        let series = read_series(&row);
+        let number = series.episode_counter;
+
+        let topic = series.episode_topic(number, topic, &Utc::now().format("%Y-%m-%d").to_string());
+        if topic.is_empty() {
+            return Err(FactoryError::Infrastructure { reason: format!("Series '{}' has no topic template; a topic is required", series.name) });
+        }
+        let style = Some(series.style.as_str()).filter(|s| !s.is_empty())
+            .or(style.filter(|s| !s.is_empty()))
+            .unwrap_or("tech_news_v1");
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            "INSERT INTO jobs (id, topic, style_name, karma_directives, status, channel, soul, series_id, episode_number, scheduled_for, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&id)
+        .bind(&topic)
+        .bind(style)
+        .bind(karma_directives.unwrap_or("{}"))
+        .bind(JobStatus::Pending.to_string())
+        .bind(&series.channel)
+        .bind(&series.soul)
+        .bind(&series.id)
+        .bind(number)
+        .bind(scheduled_for)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to enqueue episode: {}", e) })?;
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit episode: {}", e) })?;
+        Ok((id, number))
+    }
+
+    async fn fetch_series_episodes(&self, series_id: &str, limit: i64) -> Result<Vec<EpisodeLink>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, topic, status, episode_number, sns_platform, sns_video_id FROM jobs
+             WHERE series_id = ? ORDER BY episode_number DESC LIMIT ?"
+        )
+        .bind(series_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch episodes of series {}: {}", series_id, e) })?;
+        Ok(rows.iter().map(read_episode).collect())
+    }
+
+    async fn fetch_episode_context(&self, job_id: &str) -> Result<Option<EpisodeContext>, FactoryError> {
+        let row: Option<(Option<String>, Option<i64>)> = sqlx::query_as("SELECT series_id, episode_number FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up episode of job {}: {}", job_id, e) })?;
+        let Some((Some(series_id), Some(number))) = row else {
+            return Ok(None);
+        };
+        let Some(series) = self.fetch_series(&series_id).await? else {
+            return Ok(None);
+        };
+
+        let rows = sqlx::query(
+            "SELECT id, topic, status, episode_number, sns_platform, sns_video_id FROM jobs
+             WHERE series_id = ? AND episode_number < ? AND status = 'Completed'
+             ORDER BY episode_number DESC LIMIT 3"
+        )
+        .bind(&series.id)
+        .bind(number)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch previous episodes: {}", e) })?;
+
+        Ok(Some(EpisodeContext {
+            series_id: series.id,
+            series_name: series.name,
+            episode_number: number,
+            title_template: series.title_template,
+            previous_episodes: rows.iter().map(read_episode).collect(),
+        }))
+    }
+
     async fn get_agent_stats(&self) -> Result<shared::watchtower::AgentStats, FactoryError> {
         let row = sqlx::query("SELECT level, exp, affection, intimacy, fatigue FROM agent_stats WHERE id = 1")
             .fetch_one(&self.pool)
@@ -881,9 +1647,15 @@ impl JobQueue for SqliteJobQueue {
 
 impl SqliteJobQueue {
     // --- Ultimate Production Audit: Karma Distillation ---
-    pub async fn fetch_skills_for_distillation(&self, threshold: i64) -> Result<Vec<String>, FactoryError> {
+    /// 有効な教訓が `threshold` 件を超えたスキルと名前空間の組 (レビュー待ちの蒸留がある組と、引退した教訓は除く)。
+    /// 名前空間をまたいで蒸留すると、あるスタイルの教訓が別のスタイルに漏れるので分けて数える
+    pub async fn fetch_skills_for_distillation(&self, threshold: i64) -> Result<Vec<(String, Option<String>)>, FactoryError> {
         let rows = sqlx::query(
-            "SELECT related_skill FROM karma_logs GROUP BY related_skill HAVING COUNT(id) > ?"
+            "SELECT related_skill, namespace FROM karma_logs k
+             WHERE review_status IS NULL AND weight > 0
+               AND NOT EXISTS (SELECT 1 FROM karma_logs p WHERE p.review_status = 'pending'
+                                 AND p.related_skill = k.related_skill AND p.namespace IS k.namespace)
+             GROUP BY related_skill, namespace HAVING COUNT(id) > ?"
         )
         .bind(threshold)
         .fetch_all(&self.pool)
@@ -892,30 +1664,31 @@ impl SqliteJobQueue {
 
         let mut skills = Vec::new();
         for r in rows {
-            skills.push(r.try_get("related_skill").unwrap_or_else(|_| "".to_string()));
+            skills.push((r.try_get("related_skill").unwrap_or_else(|_| "".to_string()), try_get_optional_string(&r, "namespace")));
         }
         Ok(skills)
     }
 
-    pub async fn fetch_raw_karma_for_skill(&self, skill: &str) -> Result<Vec<(String, String)>, FactoryError> {
+    pub async fn fetch_raw_karma_for_skill(&self, skill: &str, namespace: Option<&str>) -> Result<Vec<(String, String)>, FactoryError> {
         let rows = sqlx::query(
-            "SELECT id, lesson FROM karma_logs WHERE related_skill = ?"
+            "SELECT id, lesson FROM karma_logs WHERE related_skill = ? AND namespace IS ? AND review_status IS NULL AND weight > 0"
         )
         .bind(skill)
+        .bind(namespace)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch raw karma for skill: {}", e) })?;
 
         let mut karma = Vec::new();
         for r in rows {
-            let id: String = try_get_optional_string(&r, "id").unwrap_or_else(|| "".to_string());
-            let lesson: String = try_get_optional_string(&r, "lesson").unwrap_or_else(|| "".to_string());
+            let id: String = try_get_optional_string(&r, "id").unwrap_or_default();
+            let lesson: String = try_get_optional_string(&r, "lesson").unwrap_or_default();
             karma.push((id, lesson));
         }
         Ok(karma)
     }
 
-    pub async fn apply_distilled_karma(&self, skill: &str, distilled_lesson: &str, old_karma_ids: &[String], soul_hash: &str) -> Result<(), FactoryError> {
+    pub async fn apply_distilled_karma(&self, skill: &str, namespace: Option<&str>, distilled_lesson: &str, old_karma_ids: &[String], soul_hash: &str) -> Result<(), FactoryError> {
         let mut tx = self.pool.begin().await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start tx for distillation: {}", e) })?;
 
@@ -926,13 +1699,14 @@ impl SqliteJobQueue {
 
         let new_id = uuid::Uuid::new_v4().to_string();
         sqlx::query(
-            "INSERT INTO karma_logs (id, karma_type, related_skill, lesson, weight, soul_version_hash)
-             VALUES (?, 'Synthesized', ?, ?, 100, ?)"
+            "INSERT INTO karma_logs (id, karma_type, related_skill, lesson, weight, soul_version_hash, namespace)
+             VALUES (?, 'Synthesized', ?, ?, 100, ?, ?)"
         )
             .bind(&new_id)
             .bind(skill)
             .bind(distilled_lesson)
             .bind(soul_hash)
+            .bind(namespace)
             .execute(&mut *tx)
             .await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to insert synthesized karma: {}", e) })?;
@@ -943,23 +1717,107 @@ impl SqliteJobQueue {
         Ok(())
     }
 
+    /// 蒸留した教訓をレビュー待ちで置く (weight 0 なので承認まで注入されず、元の教訓もそのまま効く)。ID を返す
+    pub async fn propose_distilled_karma(&self, skill: &str, namespace: Option<&str>, distilled_lesson: &str, old_karma_ids: &[String], soul_hash: &str) -> Result<uuid::Uuid, FactoryError> {
+        let id = uuid::Uuid::new_v4();
+        let supersedes = serde_json::to_string(old_karma_ids).unwrap_or_else(|_| "[]".to_string());
+        sqlx::query(
+            "INSERT INTO karma_logs (id, karma_type, related_skill, lesson, weight, soul_version_hash, review_status, supersedes, namespace)
+             VALUES (?, 'Synthesized', ?, ?, 0, ?, 'pending', ?, ?)"
+        )
+        .bind(id.to_string())
+        .bind(skill)
+        .bind(distilled_lesson)
+        .bind(soul_hash)
+        .bind(&supersedes)
+        .bind(namespace)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to propose synthesized karma: {}", e) })?;
+        Ok(id)
+    }
+
+    /// レビュー待ちの蒸留を決着させる。承認なら元の教訓を消して weight 100 で有効にし、却下なら蒸留を捨てる。
+    /// レビュー待ちでなければ false (別の承認ボタンの応答)
+    pub async fn resolve_karma_review(&self, id: &str, approved: bool) -> Result<bool, FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start tx for karma review: {}", e) })?;
+        let supersedes: Option<Option<String>> = sqlx::query_scalar("SELECT supersedes FROM karma_logs WHERE id = ? AND review_status = 'pending'")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up karma review {}: {}", id, e) })?;
+        let Some(supersedes) = supersedes else { return Ok(false) };
+
+        if approved {
+            let old_ids: Vec<String> = supersedes.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default();
+            for old_id in &old_ids {
+                sqlx::query("DELETE FROM karma_logs WHERE id = ?").bind(old_id).execute(&mut *tx).await
+                    .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to delete old karma {}: {}", old_id, e) })?;
+            }
+            // 時間減衰はレビュー待ちの期間ではなく有効になった時点から数える
+            sqlx::query("UPDATE karma_logs SET weight = 100, review_status = NULL, supersedes = NULL, created_at = ? WHERE id = ?")
+                .bind(Utc::now().to_rfc3339())
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to activate karma {}: {}", id, e) })?;
+        } else {
+            sqlx::query("DELETE FROM karma_logs WHERE id = ?").bind(id).execute(&mut *tx).await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to discard karma {}: {}", id, e) })?;
+        }
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit karma review: {}", e) })?;
+        Ok(true)
+    }
+
+    /// Series Cascade: 親が失敗した以上、待機中の子孫は永遠に実行されないため同じトランザクションで失敗させる
+    async fn cascade_dependency_failure(conn: &mut sqlx::SqliteConnection, job_id: &str, now: &str) -> Result<(), FactoryError> {
+        sqlx::query(
+            "WITH RECURSIVE descendants(id) AS (
+                SELECT id FROM jobs WHERE depends_on = ?
+                UNION SELECT j.id FROM jobs j JOIN descendants d ON j.depends_on = d.id
+             )
+             UPDATE jobs SET status = ?, error_message = ?, updated_at = ?
+             WHERE id IN descendants AND status = ?"
+        )
+        .bind(job_id)
+        .bind(JobStatus::Failed.to_string())
+        .bind(format!("DEPENDENCY_FAILED: {}", job_id))
+        .bind(now)
+        .bind(JobStatus::Pending.to_string())
+        .execute(conn)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to cascade failure of job {}: {}", job_id, e) })?;
+        Ok(())
+    }
+
     // --- Ultimate Production Audit: Poison Pill (Infinite Billing Loop Defense) ---
     pub async fn increment_job_retry_count(&self, job_id: &str) -> Result<bool, FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
         let row = sqlx::query("UPDATE jobs SET retry_count = retry_count + 1 WHERE id = ? RETURNING retry_count")
             .bind(job_id)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to increment job retry count: {}", e) })?;
-            
+
         let count: i64 = row.get("retry_count");
-        if count >= 3 {
-            sqlx::query("UPDATE jobs SET status = 'Failed', error_message = 'Poison Pill Activated: API continually fails.' WHERE id = ?")
+        let activated = count >= 3;
+        if activated {
+            let now = Utc::now().to_rfc3339();
+            sqlx::query("UPDATE jobs SET status = 'Failed', error_message = 'Poison Pill Activated: API continually fails.', updated_at = ? WHERE id = ?")
+                .bind(&now)
                 .bind(job_id)
-                .execute(&self.pool).await.ok();
-            Ok(true) // Poison pill activated
-        } else {
-            Ok(false)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fail job {}: {}", job_id, e) })?;
+            Self::cascade_dependency_failure(&mut tx, job_id, &now).await?;
         }
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
+        Ok(activated) // true: Poison pill activated
     }
 
     pub async fn increment_oracle_retry_count(&self, record_id: i64) -> Result<bool, FactoryError> {
@@ -981,6 +1839,9 @@ impl SqliteJobQueue {
     }
 
     // --- The Final Wire: Global Circuit Breaker (Mass Extinction Defense) ---
+    /// この回数以上 API が連続で失敗したら Sentinel / Oracle は実行を見送る
+    pub const GLOBAL_API_FAILURE_LIMIT: i64 = 5;
+
     pub async fn get_global_api_failures(&self) -> Result<i64, FactoryError> {
         let row = sqlx::query("SELECT value FROM system_state WHERE key = 'consecutive_api_failures'")
             .fetch_optional(&self.pool)
@@ -1024,25 +1885,71 @@ impl SqliteJobQueue {
         
         Ok(())
     }
+
+    // --- Pause Switch: 実行中のジョブは最後まで作り、新しいジョブは取り出さない ---
+    /// 一時停止した時刻 (RFC 3339)。動いていれば None
+    pub async fn paused_since(&self) -> Result<Option<String>, FactoryError> {
+        let row = sqlx::query("SELECT value, updated_at FROM system_state WHERE key = 'paused'")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read system_state: {}", e) })?;
+        Ok(row
+            .filter(|r| r.try_get::<String, _>("value").is_ok_and(|v| v == "1"))
+            .and_then(|r| try_get_optional_string(&r, "updated_at")))
+    }
+
+    /// 一時停止・再開を切り替える。状態が変わったら true (既にその状態なら false)。
+    /// 読んでから書くと同時の切り替えが両方 true になるので、1 文の UPSERT で判定する
+    pub async fn set_paused(&self, paused: bool) -> Result<bool, FactoryError> {
+        let result = sqlx::query(
+            "INSERT INTO system_state (key, value, updated_at)
+             VALUES ('paused', ?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+             WHERE system_state.value <> excluded.value"
+        )
+        .bind(if paused { "1" } else { "0" })
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to update system_state: {}", e) })?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 impl SqliteJobQueue {
     pub async fn fetch_all_karma(&self, limit: i64) -> Result<Vec<serde_json::Value>, FactoryError> {
-        // (Existing fetch_all_karma code omitted for brevity; this block replaces the whole method)
+        self.fetch_karma(limit, None, None, None, None).await
+    }
+
+    /// 教訓を新しい順に絞り込んで取得する (重みは `min_weight..=max_weight`)
+    pub async fn fetch_karma(
+        &self,
+        limit: i64,
+        skill_id: Option<&str>,
+        karma_type: Option<&str>,
+        min_weight: Option<i64>,
+        max_weight: Option<i64>,
+    ) -> Result<Vec<serde_json::Value>, FactoryError> {
         let rows = sqlx::query(
-            "SELECT * FROM karma_logs ORDER BY created_at DESC LIMIT ?"
+            "SELECT * FROM karma_logs
+             WHERE (?1 IS NULL OR related_skill = ?1) AND (?2 IS NULL OR karma_type = ?2)
+               AND (?3 IS NULL OR weight >= ?3) AND (?4 IS NULL OR weight <= ?4)
+             ORDER BY created_at DESC LIMIT ?5"
         )
+        .bind(skill_id)
+        .bind(karma_type)
+        .bind(min_weight)
+        .bind(max_weight)
         .bind(limit)
         .fetch_all(&self.pool)
         .await
-        .map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch karma: {}", e) })?;
 
         let mut karmas = Vec::new();
         for row in rows {
-            use sqlx::Row;
             karmas.push(serde_json::json!({
                 "id": row.try_get::<String, _>("id").unwrap_or_default(),
-                "job_id": row.try_get::<String, _>("job_id").unwrap_or_default(),
+                "job_id": row.try_get::<Option<String>, _>("job_id").unwrap_or_default().unwrap_or_default(),
                 "skill_id": row.try_get::<String, _>("related_skill").unwrap_or_default(),
                 "lesson": row.try_get::<String, _>("lesson").unwrap_or_default(),
                 "karma_type": row.try_get::<String, _>("karma_type").unwrap_or_default(),
@@ -1050,29 +1957,583 @@ impl SqliteJobQueue {
                 "created_at": row.try_get::<String, _>("created_at").unwrap_or_default(),
                 "last_applied_at": row.try_get::<Option<String>, _>("last_applied_at").unwrap_or_default(),
                 "soul_version_hash": row.try_get::<Option<String>, _>("soul_version_hash").unwrap_or_default(),
+                "review_status": try_get_optional_string(&row, "review_status"),
+                "namespace": try_get_optional_string(&row, "namespace"),
             }));
         }
         Ok(karmas)
     }
 
-    // --- Watchtower Memory Distillation Methods ---
+    /// 教訓を 1 件書き込み、ID を返す。手動で入れる教訓はジョブも Soul も持たない (job_id / soul_hash = None)。
+    /// `namespace` が空なら共通の教訓になる。
+    /// 埋め込みに失敗しても教訓は失わない (後で backfill_karma_embeddings が埋める)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_karma(
+        &self,
+        job_id: Option<&str>,
+        skill_id: &str,
+        namespace: Option<&str>,
+        lesson: &str,
+        karma_type: &str,
+        weight: i64,
+        soul_hash: Option<&str>,
+    ) -> Result<String, FactoryError> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        let (embedding, embedding_model) = match &self.embedder {
+            Some(embedder) => match embedder.embed(lesson).await {
+                Ok(v) => (Some(crate::embedder::encode_vector(&v)), Some(embedder.model_id().to_string())),
+                Err(e) => {
+                    tracing::warn!("⚠️ Karma: Lesson embedding failed for {}: {}", job_id.unwrap_or("a manual lesson"), e);
+                    (None, None)
+                }
+            },
+            None => (None, None),
+        };
+        sqlx::query(
+            "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, namespace, lesson, weight, soul_version_hash, embedding, embedding_model, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&id)
+        .bind(job_id)
+        .bind(karma_type)
+        .bind(skill_id)
+        .bind(normalize_namespace(namespace))
+        .bind(lesson)
+        .bind(weight)
+        .bind(soul_hash)
+        .bind(embedding)
+        .bind(embedding_model)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to store karma for {}: {}", job_id.unwrap_or("a manual lesson"), e) })?;
+        Ok(id)
+    }
 
-    pub async fn insert_chat_message(&self, channel_id: &str, role: &str, content: &str) -> Result<(), FactoryError> {
-        sqlx::query("INSERT INTO chat_history (channel_id, role, content) VALUES (?, ?, ?)")
-            .bind(channel_id)
-            .bind(role)
-            .bind(content)
-            .execute(&self.pool)
+    /// 全教訓を作成順に書き出す (`karma export`)
+    pub async fn export_karma(&self) -> Result<Vec<KarmaRecord>, FactoryError> {
+        let rows = sqlx::query("SELECT * FROM karma_logs ORDER BY created_at ASC, id ASC")
+            .fetch_all(&self.pool)
             .await
-            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to insert chat history: {}", e) })?;
-        Ok(())
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to export karma: {}", e) })?;
+        rows.iter().map(read_karma_record).collect()
     }
 
-    pub async fn fetch_chat_history(&self, channel_id: &str, limit: i64) -> Result<Vec<serde_json::Value>, FactoryError> {
-        // Fetch the newest `limit` messages, but we need them in chronological order
-        // So we order by id DESC, limit, and then reverse the result in memory.
-        let rows = sqlx::query(
-            "SELECT role, content FROM chat_history WHERE channel_id = ? ORDER BY id DESC LIMIT ?"
+    /// 書き出した教訓を 1 トランザクションで取り込む (`karma import`)。
+    /// 埋め込みは付けない (本文が変わらない Merge / Skip は既存の埋め込みを残す)
+    pub async fn import_karma(&self, records: &[KarmaRecord], conflict: KarmaConflict) -> Result<KarmaImportReport, FactoryError> {
+        let mut report = KarmaImportReport::default();
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
+        for record in records {
+            let weight = record.weight.clamp(0, 100);
+            let exists = sqlx::query("SELECT 1 FROM karma_logs WHERE id = ?")
+                .bind(&record.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to look up karma {}: {}", record.id, e) })?
+                .is_some();
+            let query = match (exists, conflict) {
+                (true, KarmaConflict::Skip) => {
+                    report.skipped += 1;
+                    continue;
+                }
+                (true, KarmaConflict::Merge) => {
+                    report.merged += 1;
+                    sqlx::query(
+                        "UPDATE karma_logs SET weight = max(weight, ?),
+                           last_applied_at = CASE WHEN last_applied_at IS NULL OR ? > last_applied_at THEN ? ELSE last_applied_at END
+                         WHERE id = ?"
+                    )
+                    .bind(weight)
+                    .bind(&record.last_applied_at)
+                    .bind(&record.last_applied_at)
+                    .bind(&record.id)
+                }
+                (true, KarmaConflict::Replace) => {
+                    report.replaced += 1;
+                    sqlx::query(
+                        "UPDATE karma_logs SET job_id = (SELECT id FROM jobs WHERE id = ?), karma_type = ?, related_skill = ?, namespace = ?,
+                           embedding = CASE WHEN lesson = ? THEN embedding ELSE NULL END,
+                           embedding_model = CASE WHEN lesson = ? THEN embedding_model ELSE NULL END,
+                           lesson = ?, weight = ?, soul_version_hash = ?, created_at = ?, last_applied_at = ?
+                         WHERE id = ?"
+                    )
+                    .bind(&record.job_id)
+                    .bind(&record.karma_type)
+                    .bind(&record.related_skill)
+                    .bind(normalize_namespace(record.namespace.as_deref()))
+                    .bind(&record.lesson)
+                    .bind(&record.lesson)
+                    .bind(&record.lesson)
+                    .bind(weight)
+                    .bind(&record.soul_version_hash)
+                    .bind(&record.created_at)
+                    .bind(&record.last_applied_at)
+                    .bind(&record.id)
+                }
+                (false, _) => {
+                    report.inserted += 1;
+                    sqlx::query(
+                        "INSERT INTO karma_logs (id, job_id, karma_type, related_skill, namespace, lesson, weight, soul_version_hash, created_at, last_applied_at)
+                         VALUES (?, (SELECT id FROM jobs WHERE id = ?), ?, ?, ?, ?, ?, ?, ?, ?)"
+                    )
+                    .bind(&record.id)
+                    .bind(&record.job_id)
+                    .bind(&record.karma_type)
+                    .bind(&record.related_skill)
+                    .bind(normalize_namespace(record.namespace.as_deref()))
+                    .bind(&record.lesson)
+                    .bind(weight)
+                    .bind(&record.soul_version_hash)
+                    .bind(&record.created_at)
+                    .bind(&record.last_applied_at)
+                }
+            };
+            query
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to import karma {}: {}", record.id, e) })?;
+        }
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit karma import: {}", e) })?;
+        Ok(report)
+    }
+
+    /// SOUL の今の本文を記録する。前回記録した版から変わっていれば、その版のハッシュを返す
+    /// (初回・変化なしは None)。以前の版に戻した場合も、その版を改めて最新として記録する
+    pub async fn record_soul_version(&self, source: &str, content: &str) -> Result<Option<String>, FactoryError> {
+        let hash = crate::soul_history::soul_hash(content);
+        let latest: Option<String> = sqlx::query_scalar("SELECT hash FROM soul_versions WHERE source = ? ORDER BY recorded_at DESC LIMIT 1")
+            .bind(source)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read soul versions: {}", e) })?;
+        if latest.as_deref() == Some(hash.as_str()) {
+            return Ok(None);
+        }
+        sqlx::query(
+            "INSERT INTO soul_versions (source, hash, content, recorded_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(source, hash) DO UPDATE SET recorded_at = excluded.recorded_at"
+        )
+        .bind(source)
+        .bind(&hash)
+        .bind(content)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record soul version: {}", e) })?;
+        Ok(latest)
+    }
+
+    /// SOUL の版を新しい順に (source を指定すればそのファイルだけ)
+    pub async fn fetch_soul_versions(&self, source: Option<&str>, limit: i64) -> Result<Vec<SoulVersion>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT v.*, (SELECT COUNT(*) FROM karma_logs k WHERE k.soul_version_hash = v.hash AND k.weight > 0) AS karma_count
+             FROM soul_versions v WHERE (?1 IS NULL OR v.source = ?1)
+             ORDER BY v.recorded_at DESC LIMIT ?2"
+        )
+        .bind(source)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch soul versions: {}", e) })?;
+        Ok(rows.iter().map(read_soul_version).collect())
+    }
+
+    /// ハッシュ (先頭一致) で版を引く。複数の SOUL に同じ版があれば新しい方
+    pub async fn fetch_soul_version(&self, hash_prefix: &str) -> Result<Option<SoulVersion>, FactoryError> {
+        let row = sqlx::query(
+            "SELECT v.*, (SELECT COUNT(*) FROM karma_logs k WHERE k.soul_version_hash = v.hash AND k.weight > 0) AS karma_count
+             FROM soul_versions v WHERE ltrim(v.hash) LIKE ? || '%'
+             ORDER BY v.recorded_at DESC LIMIT 1"
+        )
+        .bind(hash_prefix.trim())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch soul version: {}", e) })?;
+        Ok(row.as_ref().map(read_soul_version))
+    }
+
+    /// `source` の旧版の下で書かれ、まだ効いている教訓 (重い順)。
+    /// 版の記録より前の教訓 (どの版にも無いハッシュ) も旧版として扱う
+    pub async fn fetch_legacy_karma(&self, source: &str, current_hash: &str, limit: i64) -> Result<Vec<KarmaRecord>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT * FROM karma_logs
+             WHERE weight > 0 AND soul_version_hash IS NOT NULL AND soul_version_hash != ?1
+               AND (soul_version_hash IN (SELECT hash FROM soul_versions WHERE source = ?2)
+                    OR soul_version_hash NOT IN (SELECT hash FROM soul_versions))
+             ORDER BY weight DESC, created_at DESC LIMIT ?3"
+        )
+        .bind(current_hash)
+        .bind(source)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch legacy karma: {}", e) })?;
+        rows.iter().map(read_karma_record).collect()
+    }
+
+    /// 旧版の教訓を今の版に引き継ぐ。`lesson` があれば書き直す (埋め込みは作り直させる)
+    pub async fn rebase_karma(&self, id: &str, lesson: Option<&str>, soul_hash: &str) -> Result<(), FactoryError> {
+        sqlx::query(
+            "UPDATE karma_logs SET soul_version_hash = ?1,
+               embedding = CASE WHEN ?2 IS NULL THEN embedding ELSE NULL END,
+               embedding_model = CASE WHEN ?2 IS NULL THEN embedding_model ELSE NULL END,
+               lesson = COALESCE(?2, lesson)
+             WHERE id = ?3"
+        )
+        .bind(soul_hash)
+        .bind(lesson)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to rebase karma {}: {}", id, e) })?;
+        Ok(())
+    }
+
+    /// 今の SOUL に当てはまらなくなった教訓を退役させる (weight = 0 なので注入されない。行は残す)
+    pub async fn retire_karma(&self, id: &str) -> Result<(), FactoryError> {
+        sqlx::query("UPDATE karma_logs SET weight = 0 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to retire karma {}: {}", id, e) })?;
+        Ok(())
+    }
+
+    /// 教訓を削除する。無ければ false
+    pub async fn delete_karma(&self, id: &str) -> Result<bool, FactoryError> {
+        let result = sqlx::query("DELETE FROM karma_logs WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to delete karma {}: {}", id, e) })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 意味検索版の Karma 取得。
+    /// スコア = コサイン類似度 × 時間減衰後の重み。関連スキル / global の教訓は類似度が低くても候補に残す。
+    /// 埋め込みが無い (または別モデルの) 教訓は従来通りトピックの部分一致を類似度 1.0 として扱う。
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_semantic_karma(
+        &self,
+        model_id: &str,
+        query: &[f32],
+        topic: &str,
+        skill_id: &str,
+        namespace: Option<&str>,
+        limit: i64,
+        current_soul_hash: &str,
+    ) -> Result<Vec<String>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, lesson, soul_version_hash, related_skill, embedding, embedding_model, created_at,
+              max(0, weight - (julianday('now') - julianday(created_at)) * 0.5) AS effective_weight
+             FROM karma_logs WHERE weight > 0 AND (?1 IS NULL OR namespace IS NULL OR namespace = ?1)"
+        )
+        .bind(namespace)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch relevant karma: {}", e) })?;
+
+        let topic_lower = topic.to_lowercase();
+        let mut scored: Vec<(f64, f32, String, &sqlx::sqlite::SqliteRow)> = rows.iter().filter_map(|row| {
+            let lesson: String = row.get("lesson");
+            let embedding: Option<Vec<u8>> = row.try_get("embedding").ok().flatten();
+            let same_model = try_get_optional_string(row, "embedding_model").as_deref() == Some(model_id);
+            let similarity = match embedding {
+                Some(bytes) if same_model => crate::embedder::cosine_similarity(query, &crate::embedder::decode_vector(&bytes)),
+                _ if lesson.to_lowercase().contains(&topic_lower) => 1.0,
+                _ => 0.0,
+            };
+            let skill: String = row.get("related_skill");
+            if skill != skill_id && skill != "global" && similarity < self.min_karma_similarity {
+                return None;
+            }
+            let effective_weight: f64 = row.try_get("effective_weight").unwrap_or(0.0);
+            let created_at: String = row.get("created_at");
+            Some((effective_weight * similarity.max(0.0) as f64, similarity, created_at, row))
+        }).collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1)).then_with(|| b.2.cmp(&a.2)));
+
+        let applied = scored.into_iter()
+            .take(limit.max(0) as usize)
+            .map(|(_, _, _, row)| (row.get("id"), row.get("lesson"), try_get_optional_string(row, "soul_version_hash")))
+            .collect();
+        Ok(self.apply_karma(applied, current_soul_hash).await)
+    }
+
+    /// 注入する Karma を整形し、適用日時 (TTL 減衰用) を記録する。`(id, lesson, soul_version_hash)`
+    async fn apply_karma(&self, rows: Vec<(String, String, Option<String>)>, current_soul_hash: &str) -> Vec<String> {
+        let now = Utc::now().to_rfc3339();
+        let mut karma = Vec::new();
+        for (karma_id, lesson, karma_hash) in rows {
+            // The Cognitive Dissonance Trap Fix: Warn LLM if this karma is from a different era
+            let processed_lesson = match karma_hash {
+                Some(h) if h != current_soul_hash => format!("[LEGACY KARMA - from an older Soul version]\n{}", lesson),
+                _ => lesson,
+            };
+            karma.push(processed_lesson);
+
+            // Update last_applied_at for applied karma entries (Usage Tracking for TTL Decay)
+            let _ = sqlx::query("UPDATE karma_logs SET last_applied_at = ? WHERE id = ?")
+                .bind(&now)
+                .bind(&karma_id)
+                .execute(&self.pool)
+                .await;
+        }
+        karma
+    }
+
+    /// 埋め込みが無い (または古いモデルの) 教訓をベクトル化する。処理した件数を返す
+    pub async fn backfill_karma_embeddings(&self, batch: i64) -> Result<usize, FactoryError> {
+        let Some(embedder) = &self.embedder else { return Ok(0) };
+        let rows = sqlx::query(
+            "SELECT id, lesson FROM karma_logs
+             WHERE weight > 0 AND (embedding IS NULL OR embedding_model IS NULL OR embedding_model != ?)
+             ORDER BY created_at DESC LIMIT ?"
+        )
+        .bind(embedder.model_id())
+        .bind(batch)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch karma for embedding: {}", e) })?;
+
+        let mut done = 0;
+        for row in &rows {
+            let id: String = row.get("id");
+            let lesson: String = row.get("lesson");
+            let vector = embedder.embed(&lesson).await?;
+            sqlx::query("UPDATE karma_logs SET embedding = ?, embedding_model = ? WHERE id = ?")
+                .bind(crate::embedder::encode_vector(&vector))
+                .bind(embedder.model_id())
+                .bind(&id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to store karma embedding: {}", e) })?;
+            done += 1;
+        }
+        Ok(done)
+    }
+
+    /// Samsara の合成経緯を記録する
+    pub async fn store_provenance(&self, job_id: &str, provenance: &JobProvenance) -> Result<(), FactoryError> {
+        let json = serde_json::to_string(provenance)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to serialize provenance: {}", e) })?;
+        sqlx::query("UPDATE jobs SET provenance = ? WHERE id = ?")
+            .bind(json)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to store provenance: {}", e) })?;
+        Ok(())
+    }
+
+    /// 合成経緯を取得する (手動投入のジョブは None)
+    pub async fn fetch_provenance(&self, job_id: &str) -> Result<Option<JobProvenance>, FactoryError> {
+        let row: Option<(Option<String>,)> = sqlx::query_as("SELECT provenance FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch provenance: {}", e) })?;
+        Ok(row
+            .and_then(|(json,)| json)
+            .and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// 記録済みの取り下げ操作 (action, 実行時刻) を取得する
+    pub async fn fetch_takedown(&self, job_id: &str) -> Result<Option<(String, String)>, FactoryError> {
+        let row: Option<(Option<String>, Option<String>)> = sqlx::query_as("SELECT takedown_action, takedown_at FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch takedown: {}", e) })?;
+        Ok(row.and_then(|(action, at)| Some((action?, at.unwrap_or_default()))))
+    }
+
+    /// 工程の開始を記録する (開いたままの前の工程はこの時刻で閉じる)
+    pub async fn begin_job_stage(&self, job_id: &str, stage: &str) -> Result<(), FactoryError> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to begin stage transaction: {}", e) })?;
+        sqlx::query("UPDATE job_stages SET ended_at = ? WHERE job_id = ? AND ended_at IS NULL")
+            .bind(&now)
+            .bind(job_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to close previous stage: {}", e) })?;
+        sqlx::query("INSERT INTO job_stages (job_id, stage, started_at) VALUES (?, ?, ?)")
+            .bind(job_id)
+            .bind(stage)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record stage: {}", e) })?;
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit stage: {}", e) })?;
+        Ok(())
+    }
+
+    /// 開いたままの工程を閉じる (ジョブの終了・中断時)
+    pub async fn end_job_stages(&self, job_id: &str) -> Result<(), FactoryError> {
+        sqlx::query("UPDATE job_stages SET ended_at = ? WHERE job_id = ? AND ended_at IS NULL")
+            .bind(Utc::now().to_rfc3339())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to close stages: {}", e) })?;
+        Ok(())
+    }
+
+    /// 記録済みの工程 (古い順)
+    pub async fn fetch_job_stages(&self, job_id: &str) -> Result<Vec<JobStageSpan>, FactoryError> {
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT stage, started_at, ended_at FROM job_stages WHERE job_id = ? ORDER BY id ASC"
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch stages: {}", e) })?;
+        Ok(rows
+            .into_iter()
+            .map(|(stage, started_at, ended_at)| JobStageSpan { stage, started_at, ended_at })
+            .collect())
+    }
+
+    /// ジョブ 1 本のコスト (プロバイダー × 工程ごと)
+    pub async fn fetch_job_costs(&self, job_id: &str) -> Result<JobCosts, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT provider, operation, SUM(api_calls) AS api_calls, SUM(input_tokens) AS input_tokens,
+                    SUM(output_tokens) AS output_tokens, SUM(gpu_secs) AS gpu_secs, SUM(estimated_usd) AS estimated_usd
+             FROM cost_ledger WHERE job_id = ?
+             GROUP BY provider, operation ORDER BY MIN(id) ASC"
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch job costs: {}", e) })?;
+        let lines: Vec<CostLine> = rows.iter().map(read_cost_line).collect();
+        Ok(JobCosts { job_id: job_id.to_string(), total: CostLine::total(&lines), lines })
+    }
+
+    /// 直近 `days` 日のコスト集計と、完成 1 本あたりの概算額
+    pub async fn fetch_cost_summary(&self, days: i64) -> Result<CostSummary, FactoryError> {
+        let since = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        let rows = sqlx::query(
+            "SELECT provider, operation, SUM(api_calls) AS api_calls, SUM(input_tokens) AS input_tokens,
+                    SUM(output_tokens) AS output_tokens, SUM(gpu_secs) AS gpu_secs, SUM(estimated_usd) AS estimated_usd
+             FROM cost_ledger WHERE recorded_at >= ?
+             GROUP BY provider, operation ORDER BY SUM(estimated_usd) DESC, provider, operation"
+        )
+        .bind(&since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch cost summary: {}", e) })?;
+        let lines: Vec<CostLine> = rows.iter().map(read_cost_line).collect();
+
+        let (videos,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM jobs WHERE status = 'Completed' AND updated_at >= ?")
+            .bind(&since)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to count completed jobs: {}", e) })?;
+
+        let total = CostLine::total(&lines);
+        let usd_per_video = (videos > 0).then(|| total.estimated_usd / videos as f64);
+        Ok(CostSummary { days, videos, lines, total, usd_per_video })
+    }
+
+    /// 完成したジョブのタイトル・フック案を記録する
+    pub async fn record_title_variants(&self, job_id: &str, variants: &[TitleVariant]) -> Result<(), FactoryError> {
+        if variants.is_empty() {
+            return Ok(());
+        }
+        let json = serde_json::to_string(variants)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to serialize title variants: {}", e) })?;
+        sqlx::query("UPDATE jobs SET title_variants = ? WHERE id = ?")
+            .bind(json)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to store title variants: {}", e) })?;
+        Ok(())
+    }
+
+    /// ジョブのタイトル案 (記録が無ければ空)
+    pub async fn fetch_title_variants(&self, job_id: &str) -> Result<Vec<TitleVariant>, FactoryError> {
+        let row: Option<(Option<String>,)> = sqlx::query_as("SELECT title_variants FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch title variants: {}", e) })?;
+        Ok(row
+            .and_then(|(json,)| json)
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    /// 実際に公開したタイトル案を記録する (未記録なら A 案を公開したものとみなす)
+    pub async fn set_published_variant(&self, job_id: &str, label: &str) -> Result<TitleVariant, FactoryError> {
+        let label = label.trim().to_uppercase();
+        let variant = self.fetch_title_variants(job_id).await?
+            .into_iter()
+            .find(|v| v.label == label)
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("Job {} has no title variant '{}'", job_id, label) })?;
+        sqlx::query("UPDATE jobs SET published_variant = ?, updated_at = ? WHERE id = ?")
+            .bind(&label)
+            .bind(Utc::now().to_rfc3339())
+            .bind(job_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to set published variant: {}", e) })?;
+        Ok(variant)
+    }
+
+    /// 公開中のタイトル案 (A/B 案の無いジョブは None)
+    pub async fn fetch_published_variant(&self, job_id: &str) -> Result<Option<TitleVariant>, FactoryError> {
+        let label: Option<(Option<String>,)> = sqlx::query_as("SELECT published_variant FROM jobs WHERE id = ?")
+            .bind(job_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch published variant: {}", e) })?;
+        let label = label.and_then(|(l,)| l).unwrap_or_else(|| "A".to_string());
+        Ok(self.fetch_title_variants(job_id).await?.into_iter().find(|v| v.label == label))
+    }
+
+    /// チャンネルのフックの型ごとの勝敗 (`milestone_days` 時点の再生数で比べる)
+    pub async fn fetch_hook_stats(&self, channel: &str, milestone_days: i64) -> Result<Vec<HookStat>, FactoryError> {
+        let rows: Vec<(Option<String>, i64)> = sqlx::query_as(
+            "SELECT json_extract(v.value, '$.hook_style'), h.views
+             FROM sns_metrics_history h
+             JOIN jobs j ON j.id = h.job_id, json_each(j.title_variants) v
+             WHERE j.channel = ? AND h.milestone_days = ? AND h.title_variant IS NOT NULL
+               AND json_extract(v.value, '$.label') = h.title_variant"
+        )
+        .bind(channel)
+        .bind(milestone_days)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch hook stats: {}", e) })?;
+        Ok(summarize_hook_results(rows.into_iter().map(|(style, views)| (style.unwrap_or_default(), views)).collect()))
+    }
+
+    // --- Watchtower Memory Distillation Methods ---
+
+    pub async fn insert_chat_message(&self, channel_id: &str, role: &str, content: &str) -> Result<(), FactoryError> {
+        sqlx::query("INSERT INTO chat_history (channel_id, role, content) VALUES (?, ?, ?)")
+            .bind(channel_id)
+            .bind(role)
+            .bind(content)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to insert chat history: {}", e) })?;
+        Ok(())
+    }
+
+    pub async fn fetch_chat_history(&self, channel_id: &str, limit: i64) -> Result<Vec<serde_json::Value>, FactoryError> {
+        // Fetch the newest `limit` messages, but we need them in chronological order
+        // So we order by id DESC, limit, and then reverse the result in memory.
+        let rows = sqlx::query(
+            "SELECT role, content FROM chat_history WHERE channel_id = ? ORDER BY id DESC LIMIT ?"
         )
         .bind(channel_id)
         .bind(limit)
@@ -1168,11 +2629,589 @@ impl SqliteJobQueue {
 
         Ok(result.rows_affected())
     }
+
+    /// チャンネルの会話履歴とサマリーを消し、監査記録を残す (/forget)。
+    /// Returns (deleted message count, whether a summary existed)
+    pub async fn forget_chat_memory(&self, channel_id: &str, requested_by: &str) -> Result<(u64, bool), FactoryError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to start transaction: {}", e) })?;
+
+        let messages = sqlx::query("DELETE FROM chat_history WHERE channel_id = ?")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to delete chat history: {}", e) })?
+            .rows_affected();
+        let summary = sqlx::query("DELETE FROM chat_memory_summaries WHERE channel_id = ?")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to delete chat memory summary: {}", e) })?
+            .rows_affected() > 0;
+
+        sqlx::query("INSERT INTO chat_memory_wipes (channel_id, requested_by, messages_deleted, summary_deleted) VALUES (?, ?, ?, ?)")
+            .bind(channel_id)
+            .bind(requested_by)
+            .bind(messages as i64)
+            .bind(summary)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record chat memory wipe: {}", e) })?;
+
+        tx.commit().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to commit transaction: {}", e) })?;
+        Ok((messages, summary))
+    }
+
+    /// チャンネルの /forget 監査記録 (新しい順)
+    pub async fn fetch_chat_memory_wipes(&self, channel_id: &str) -> Result<Vec<serde_json::Value>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT requested_by, messages_deleted, summary_deleted, wiped_at FROM chat_memory_wipes WHERE channel_id = ? ORDER BY id DESC"
+        )
+        .bind(channel_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch chat memory wipes: {}", e) })?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                serde_json::json!({
+                    "requested_by": row.get::<String, _>("requested_by"),
+                    "messages_deleted": row.get::<i64, _>("messages_deleted"),
+                    "summary_deleted": row.get::<bool, _>("summary_deleted"),
+                    "wiped_at": try_get_optional_string(&row, "wiped_at"),
+                })
+            })
+            .collect())
+    }
+
+    /// スコープ付き API トークンを発行し、(記録, 平文) を返す。平文は再表示できない
+    pub async fn create_api_token(&self, name: &str, scope: ApiScope) -> Result<(ApiTokenInfo, String), FactoryError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(FactoryError::Infrastructure { reason: "Token name must not be empty".into() });
+        }
+        let active: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_tokens WHERE name = ? AND revoked_at IS NULL")
+            .bind(name)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to check api tokens: {}", e) })?;
+        if active > 0 {
+            return Err(FactoryError::Infrastructure { reason: format!("An active token named '{}' already exists", name) });
+        }
+
+        let token = format!("{}{}", API_TOKEN_PREFIX, hex::encode(rand::random::<[u8; 24]>()));
+        let id = Uuid::new_v4().to_string();
+        let hint = token[..API_TOKEN_PREFIX.len() + 6].to_string();
+        sqlx::query("INSERT INTO api_tokens (id, name, scope, hint, token_hash) VALUES (?, ?, ?, ?, ?)")
+            .bind(&id)
+            .bind(name)
+            .bind(scope.as_str())
+            .bind(&hint)
+            .bind(hash_api_token(&token))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to create api token: {}", e) })?;
+
+        let info = self.list_api_tokens().await?
+            .into_iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| FactoryError::Infrastructure { reason: "Created api token vanished".into() })?;
+        Ok((info, token))
+    }
+
+    /// 名前 (または ID) で有効なトークンを失効させる。該当が無ければ false
+    pub async fn revoke_api_token(&self, name_or_id: &str) -> Result<bool, FactoryError> {
+        let revoked = sqlx::query("UPDATE api_tokens SET revoked_at = datetime('now') WHERE (name = ? OR id = ?) AND revoked_at IS NULL")
+            .bind(name_or_id)
+            .bind(name_or_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to revoke api token: {}", e) })?
+            .rows_affected();
+        Ok(revoked > 0)
+    }
+
+    /// 発行済みトークンの一覧 (失効済みを含む・新しい順)
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiTokenInfo>, FactoryError> {
+        let rows = sqlx::query("SELECT * FROM api_tokens ORDER BY created_at DESC, rowid DESC")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to list api tokens: {}", e) })?;
+        Ok(rows.iter().filter_map(read_api_token).collect())
+    }
+
+    /// 平文トークンを検証し、有効なら記録を返す (最終使用時刻も更新する)
+    pub async fn verify_api_token(&self, token: &str) -> Result<Option<ApiTokenInfo>, FactoryError> {
+        if !token.starts_with(API_TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let row = sqlx::query("SELECT * FROM api_tokens WHERE token_hash = ? AND revoked_at IS NULL")
+            .bind(hash_api_token(token))
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to verify api token: {}", e) })?;
+        let Some(info) = row.as_ref().and_then(read_api_token) else {
+            return Ok(None);
+        };
+        sqlx::query("UPDATE api_tokens SET last_used_at = datetime('now') WHERE id = ?")
+            .bind(&info.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to touch api token: {}", e) })?;
+        Ok(Some(info))
+    }
+}
+
+impl SqliteJobQueue {
+    /// 制御系の操作を監査ログに追記する
+    pub async fn record_audit(&self, source: &str, actor: &str, action: &str, target: Option<&str>, detail: Option<&str>) -> Result<(), FactoryError> {
+        sqlx::query("INSERT INTO audit_log (source, actor, action, target, detail) VALUES (?, ?, ?, ?, ?)")
+            .bind(source)
+            .bind(actor)
+            .bind(action)
+            .bind(target)
+            .bind(detail)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record audit entry: {}", e) })?;
+        Ok(())
+    }
+
+    /// 監査ログ (新しい順)。actor・action・since (`YYYY-MM-DD HH:MM:SS` UTC) で絞り込める
+    pub async fn fetch_audit_log(&self, limit: i64, actor: Option<&str>, action: Option<&str>, since: Option<&str>) -> Result<Vec<AuditRecord>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT * FROM audit_log
+             WHERE (?1 IS NULL OR actor = ?1) AND (?2 IS NULL OR action = ?2) AND (?3 IS NULL OR created_at >= ?3)
+             ORDER BY id DESC LIMIT ?4"
+        )
+        .bind(actor)
+        .bind(action)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch audit log: {}", e) })?;
+        Ok(rows
+            .iter()
+            .map(|row| AuditRecord {
+                id: row.get("id"),
+                source: row.get("source"),
+                actor: row.get("actor"),
+                action: row.get("action"),
+                target: try_get_optional_string(row, "target"),
+                detail: try_get_optional_string(row, "detail"),
+                created_at: try_get_optional_string(row, "created_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// cron ジョブの実行開始を記録し、実行記録の ID を返す
+    pub async fn begin_cron_run(&self, job: &str) -> Result<i64, FactoryError> {
+        let result = sqlx::query("INSERT INTO cron_runs (job, started_at) VALUES (?, ?)")
+            .bind(job)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to record cron run for {}: {}", job, e) })?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// cron ジョブの結末を書き込む
+    pub async fn finish_cron_run(&self, run_id: i64, outcome: CronOutcome, detail: Option<&str>) -> Result<(), FactoryError> {
+        sqlx::query("UPDATE cron_runs SET finished_at = ?, outcome = ?, detail = ? WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(outcome.as_str())
+            .bind(detail)
+            .bind(run_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to finish cron run {}: {}", run_id, e) })?;
+        Ok(())
+    }
+
+    /// cron ジョブの実行履歴 (新しい順)。`job` で 1 種類に絞れる
+    pub async fn fetch_cron_runs(&self, job: Option<&str>, limit: i64) -> Result<Vec<CronRun>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, job, started_at, finished_at, outcome, detail FROM cron_runs
+             WHERE (?1 IS NULL OR job = ?1)
+             ORDER BY id DESC LIMIT ?2"
+        )
+        .bind(job)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch cron runs: {}", e) })?;
+        Ok(rows
+            .iter()
+            .map(|row| CronRun {
+                id: row.get("id"),
+                job: row.get("job"),
+                started_at: row.get("started_at"),
+                finished_at: try_get_optional_string(row, "finished_at"),
+                outcome: row.get("outcome"),
+                detail: try_get_optional_string(row, "detail"),
+            })
+            .collect())
+    }
+
+    /// そのジョブが最後に成功した実行の開始時刻 (起動時の取りこぼし判定用)
+    pub async fn last_cron_success(&self, job: &str) -> Result<Option<String>, FactoryError> {
+        sqlx::query_scalar("SELECT started_at FROM cron_runs WHERE job = ? AND outcome IN ('ok', 'partial') ORDER BY id DESC LIMIT 1")
+            .bind(job)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch the last cron success: {}", e) })
+    }
+
+    /// `days` 日より古い cron の実行履歴を消す
+    pub async fn purge_old_cron_runs(&self, days: i64) -> Result<u64, FactoryError> {
+        let result = sqlx::query("DELETE FROM cron_runs WHERE julianday(started_at) < julianday('now', ? || ' days')")
+            .bind(format!("-{}", days))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to purge old cron runs: {}", e) })?;
+        Ok(result.rows_affected())
+    }
+
+    /// 直近 `hours` 時間の操作を (操作, 操作者) ごとに数える (件数の多い順)
+    pub async fn summarize_audit(&self, hours: i64) -> Result<Vec<AuditSummaryLine>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT action, actor, COUNT(*) AS count FROM audit_log
+             WHERE created_at >= datetime('now', ?)
+             GROUP BY action, actor ORDER BY count DESC, action, actor"
+        )
+        .bind(format!("-{} hours", hours))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to summarize audit log: {}", e) })?;
+        Ok(rows
+            .iter()
+            .map(|row| AuditSummaryLine { action: row.get("action"), actor: row.get("actor"), count: row.get("count") })
+            .collect())
+    }
+}
+
+impl SqliteJobQueue {
+    /// 実行待ちのジョブ (dequeue される順: 予約時刻が来たものから投入順)
+    pub async fn fetch_queued_jobs(&self, limit: i64) -> Result<Vec<shared::watchtower::QueuedJobSummary>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, topic, style_name, channel, scheduled_for, depends_on FROM jobs
+             WHERE status = ? ORDER BY COALESCE(scheduled_for, created_at) ASC, created_at ASC LIMIT ?"
+        )
+        .bind(JobStatus::Pending.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch queued jobs: {}", e) })?;
+
+        Ok(rows.iter().map(|r| shared::watchtower::QueuedJobSummary {
+            job_id: r.get("id"),
+            topic: r.get("topic"),
+            style: r.get("style_name"),
+            channel: read_channel(r),
+            scheduled_for: try_get_optional_string(r, "scheduled_for"),
+            depends_on: try_get_optional_string(r, "depends_on"),
+        }).collect())
+    }
+
+    /// 実行を待っているジョブの数
+    pub async fn count_pending_jobs(&self) -> Result<i64, FactoryError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE status = ?")
+            .bind(JobStatus::Pending.to_string())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to count pending jobs: {}", e) })
+    }
+
+    /// 実行中・待機中のジョブ (実行中が先、待機中は実行予定順) の 1 ページと全件数
+    pub async fn fetch_active_jobs(&self, limit: i64, offset: i64) -> Result<(Vec<shared::watchtower::ActiveJobSummary>, i64), FactoryError> {
+        let processing = JobStatus::Processing.to_string();
+        let pending = JobStatus::Pending.to_string();
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE status IN (?, ?)")
+            .bind(&processing)
+            .bind(&pending)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to count active jobs: {}", e) })?;
+        let rows = sqlx::query(
+            "SELECT id, topic, style_name, channel, status, scheduled_for,
+                    (SELECT stage FROM job_stages WHERE job_stages.job_id = jobs.id ORDER BY job_stages.id DESC LIMIT 1) AS stage
+             FROM jobs WHERE status IN (?1, ?2)
+             ORDER BY status = ?1 DESC, COALESCE(scheduled_for, created_at) ASC, created_at ASC LIMIT ?3 OFFSET ?4"
+        )
+        .bind(&processing)
+        .bind(&pending)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch active jobs: {}", e) })?;
+
+        let jobs = rows.iter().map(|r| shared::watchtower::ActiveJobSummary {
+            job_id: r.get("id"),
+            topic: r.get("topic"),
+            style: r.get("style_name"),
+            channel: read_channel(r),
+            status: r.get("status"),
+            stage: try_get_optional_string(r, "stage"),
+            scheduled_for: try_get_optional_string(r, "scheduled_for"),
+        }).collect();
+        Ok((jobs, total))
+    }
+
+    /// 直近 `days` 日の制作レポート (上位動画は期間中に計測した再生数の多い順に `top` 件)
+    pub async fn fetch_production_report(&self, days: u32, top: i64) -> Result<shared::watchtower::ProductionReport, FactoryError> {
+        let since = (Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339();
+        // 日時は RFC 3339 と datetime('now') の形式が混在するので julianday で比べる
+        let (completed, failed, avg_render_secs): (i64, i64, Option<f64>) = sqlx::query_as(
+            "SELECT COALESCE(SUM(status = ?1), 0),
+                    COALESCE(SUM(status = ?2 AND COALESCE(error_message, '') != ?3), 0),
+                    AVG(CASE WHEN status = ?1 AND started_at IS NOT NULL
+                             THEN (julianday(updated_at) - julianday(started_at)) * 86400 END)
+             FROM jobs WHERE status IN (?1, ?2) AND julianday(updated_at) >= julianday(?4)"
+        )
+        .bind(JobStatus::Completed.to_string())
+        .bind(JobStatus::Failed.to_string())
+        .bind(CANCELLED_REASON)
+        .bind(&since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to summarize jobs: {}", e) })?;
+
+        let karma_gained: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM karma_logs
+             WHERE julianday(created_at) >= julianday(?) AND COALESCE(review_status, '') != 'pending'"
+        )
+        .bind(&since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to count karma: {}", e) })?;
+
+        let rows = sqlx::query(
+            "SELECT m.job_id, j.topic, j.channel, j.sns_platform, j.sns_video_id, MAX(m.views) AS views, MAX(m.likes) AS likes
+             FROM sns_metrics_history m JOIN jobs j ON j.id = m.job_id
+             WHERE julianday(m.recorded_at) >= julianday(?)
+             GROUP BY m.job_id ORDER BY views DESC, likes DESC LIMIT ?"
+        )
+        .bind(&since)
+        .bind(top)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch top videos: {}", e) })?;
+        let top_videos = rows.iter().map(|r| shared::watchtower::TopVideo {
+            job_id: r.get("job_id"),
+            topic: r.get("topic"),
+            channel: read_channel(r),
+            views: r.get("views"),
+            likes: r.get("likes"),
+            video_url: factory_core::contracts::published_video_url(
+                try_get_optional_string(r, "sns_platform").as_deref(),
+                try_get_optional_string(r, "sns_video_id").as_deref(),
+            ),
+        }).collect();
+
+        Ok(shared::watchtower::ProductionReport { days, completed, failed, avg_render_secs, karma_gained, top_videos })
+    }
+
+    /// 最近完了したジョブ (新しい順)
+    pub async fn fetch_recent_completions(&self, limit: i64) -> Result<Vec<shared::watchtower::CompletedJobSummary>, FactoryError> {
+        let rows = sqlx::query(
+            "SELECT id, topic, channel, updated_at, creative_rating FROM jobs
+             WHERE status = ? ORDER BY updated_at DESC LIMIT ?"
+        )
+        .bind(JobStatus::Completed.to_string())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch completed jobs: {}", e) })?;
+
+        Ok(rows.iter().map(|r| shared::watchtower::CompletedJobSummary {
+            job_id: r.get("id"),
+            topic: r.get("topic"),
+            channel: read_channel(r),
+            completed_at: try_get_optional_string(r, "updated_at").unwrap_or_default(),
+            creative_rating: r.try_get::<Option<i32>, _>("creative_rating").ok().flatten(),
+        }).collect())
+    }
+
+    /// チャンネルで直近 `days` 日に積んだジョブの題材 (新しい順。取り消したものは除く)
+    pub async fn fetch_recent_topics(&self, channel: &str, days: i64, limit: i64) -> Result<Vec<String>, FactoryError> {
+        let since = (Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        sqlx::query_scalar(
+            "SELECT topic FROM jobs
+             WHERE channel = ? AND created_at >= ? AND NOT (status = ? AND COALESCE(error_message, '') = ?)
+             ORDER BY created_at DESC LIMIT ?"
+        )
+        .bind(channel)
+        .bind(since)
+        .bind(JobStatus::Failed.to_string())
+        .bind(CANCELLED_REASON)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to fetch recent topics: {}", e) })
+    }
+}
+
+/// 発行するトークンの接頭辞 (Bastion の `bk_` キーと見分ける)
+pub const API_TOKEN_PREFIX: &str = "sft_";
+
+fn hash_api_token(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// api_tokens の 1 行 (スコープが読めない行は無視する)
+fn read_api_token(row: &sqlx::sqlite::SqliteRow) -> Option<ApiTokenInfo> {
+    Some(ApiTokenInfo {
+        id: row.try_get("id").ok()?,
+        name: row.try_get("name").ok()?,
+        scope: row.try_get::<String, _>("scope").ok()?.parse().ok()?,
+        hint: row.try_get("hint").unwrap_or_default(),
+        created_at: try_get_optional_string(row, "created_at").unwrap_or_default(),
+        revoked_at: try_get_optional_string(row, "revoked_at"),
+        last_used_at: try_get_optional_string(row, "last_used_at"),
+    })
+}
+
+fn read_cost_line(row: &sqlx::sqlite::SqliteRow) -> CostLine {
+    CostLine {
+        provider: row.get("provider"),
+        operation: row.get("operation"),
+        api_calls: row.try_get("api_calls").unwrap_or(0),
+        input_tokens: row.try_get("input_tokens").unwrap_or(0),
+        output_tokens: row.try_get("output_tokens").unwrap_or(0),
+        gpu_secs: row.try_get("gpu_secs").unwrap_or(0.0),
+        estimated_usd: row.try_get("estimated_usd").unwrap_or(0.0),
+    }
+}
+
+/// 題材の近さ (0-1)。空白・記号を除いた文字 bigram の Jaccard 係数なので、分かち書きしない日本語の題材も比べられる
+pub fn topic_similarity(a: &str, b: &str) -> f64 {
+    let bigrams = |s: &str| -> std::collections::HashSet<(char, char)> {
+        let chars: Vec<char> = s.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+        match chars.as_slice() {
+            [only] => [(*only, ' ')].into_iter().collect(),
+            _ => chars.windows(2).map(|w| (w[0], w[1])).collect(),
+        }
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count() as f64;
+    shared / ((a.len() + b.len()) as f64 - shared)
+}
+
+/// `recent` のうち `topic` に最も近く、近さが `threshold` 以上の題材
+pub fn find_similar_topic<'a>(topic: &str, recent: &'a [String], threshold: f64) -> Option<(&'a str, f64)> {
+    recent
+        .iter()
+        .map(|r| (r.as_str(), topic_similarity(topic, r)))
+        .filter(|(_, score)| *score >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// 公開した案の再生数を全体の中央値と比べ、フックの型ごとに勝敗を数える (勝率の高い順)
+pub fn summarize_hook_results(rows: Vec<(String, i64)>) -> Vec<HookStat> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    let mut views: Vec<i64> = rows.iter().map(|(_, v)| *v).collect();
+    views.sort_unstable();
+    let median = views[(views.len() - 1) / 2];
+    let mut stats: Vec<HookStat> = Vec::new();
+    for (hook_style, v) in rows {
+        let hook_style = if hook_style.is_empty() { "unlabeled".to_string() } else { hook_style };
+        let idx = match stats.iter().position(|s| s.hook_style == hook_style) {
+            Some(i) => i,
+            None => {
+                stats.push(HookStat { hook_style, ..Default::default() });
+                stats.len() - 1
+            }
+        };
+        let stat = &mut stats[idx];
+        stat.avg_views += (v as f64 - stat.avg_views) / (stat.published + 1) as f64;
+        stat.published += 1;
+        if v > median {
+            stat.wins += 1;
+        } else {
+            stat.losses += 1;
+        }
+    }
+    let rate = |s: &HookStat| s.wins as f64 / s.published as f64;
+    stats.sort_by(|a, b| rate(b).total_cmp(&rate(a)).then(b.published.cmp(&a.published)));
+    stats
 }
 
 // Helper function because `get` on Option panics if type is unexpected, 
 // using try_get is safer if column can be NULL.
 fn try_get_optional_string(row: &sqlx::sqlite::SqliteRow, col: &str) -> Option<String> {
     use sqlx::Row;
-    row.try_get(col).ok()
+    // NULL を `String` としてデコードすると空文字列になるため、Option で受けて区別する
+    row.try_get::<Option<String>, _>(col).ok().flatten()
+}
+
+fn read_series(row: &sqlx::sqlite::SqliteRow) -> Series {
+    Series {
+        id: row.get("id"),
+        name: row.get("name"),
+        topic_template: row.get("topic_template"),
+        title_template: row.get("title_template"),
+        style: row.get("style"),
+        channel: row.get("channel"),
+        soul: try_get_optional_string(row, "soul"),
+        episode_counter: row.get("episode_counter"),
+        created_at: try_get_optional_string(row, "created_at").unwrap_or_default(),
+    }
+}
+
+fn read_episode(row: &sqlx::sqlite::SqliteRow) -> EpisodeLink {
+    EpisodeLink {
+        episode_number: row.get("episode_number"),
+        job_id: row.get("id"),
+        topic: row.get("topic"),
+        status: row.get("status"),
+        sns_platform: try_get_optional_string(row, "sns_platform"),
+        sns_video_id: try_get_optional_string(row, "sns_video_id"),
+    }
+}
+
+/// チャンネル列を読む (旧スキーマ・NULL は既定チャンネル)
+fn read_channel(row: &sqlx::sqlite::SqliteRow) -> String {
+    try_get_optional_string(row, "channel").unwrap_or_else(|| DEFAULT_CHANNEL.to_string())
+}
+
+/// 空の名前空間は共通 (NULL) として扱う
+fn normalize_namespace(namespace: Option<&str>) -> Option<&str> {
+    namespace.map(str::trim).filter(|n| !n.is_empty())
+}
+
+/// 教訓の行を読む (必須の列が NULL・型違いならエラー)
+fn read_karma_record(row: &sqlx::sqlite::SqliteRow) -> Result<KarmaRecord, FactoryError> {
+    let malformed = |e: sqlx::Error| FactoryError::Infrastructure { reason: format!("Malformed karma row: {}", e) };
+    Ok(KarmaRecord {
+        id: row.try_get("id").map_err(malformed)?,
+        job_id: try_get_optional_string(row, "job_id"),
+        karma_type: row.try_get("karma_type").map_err(malformed)?,
+        related_skill: row.try_get("related_skill").map_err(malformed)?,
+        namespace: try_get_optional_string(row, "namespace"),
+        lesson: row.try_get("lesson").map_err(malformed)?,
+        weight: row.try_get("weight").map_err(malformed)?,
+        soul_version_hash: try_get_optional_string(row, "soul_version_hash"),
+        created_at: row.try_get("created_at").map_err(malformed)?,
+        last_applied_at: try_get_optional_string(row, "last_applied_at"),
+    })
+}
+
+fn read_soul_version(row: &sqlx::sqlite::SqliteRow) -> SoulVersion {
+    SoulVersion {
+        hash: row.get("hash"),
+        source: row.get("source"),
+        content: row.get("content"),
+        recorded_at: row.get("recorded_at"),
+        karma_count: row.get("karma_count"),
+    }
 }
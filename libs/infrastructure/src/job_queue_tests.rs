@@ -6,7 +6,7 @@
 #[cfg(test)]
 mod tests {
     use crate::job_queue::SqliteJobQueue;
-    use factory_core::traits::{JobQueue, JobStatus};
+    use factory_core::traits::{JobQueue, JobStatus, TakedownAction};
 
     /// テスト用のユニーク一時ファイル JobQueue を作成
     /// 各テストが独自のDBファイルを持ち、ロック競合を回避する
@@ -185,9 +185,9 @@ mod tests {
 
         let id = jq.enqueue("Karma Test", "karma", Some("{}")).await.unwrap();
         let hash = "test_hash";
-        jq.store_karma(&id, "comfy_bridge", "Use CFG 7.5 for anime", "Technical", hash).await.unwrap();
+        jq.store_karma(&id, "comfy_bridge", None, "Use CFG 7.5 for anime", "Technical", hash).await.unwrap();
 
-        let results = jq.fetch_relevant_karma("Karma Test", "comfy_bridge", 10, hash).await.unwrap();
+        let results = jq.fetch_relevant_karma("Karma Test", "comfy_bridge", None, 10, hash).await.unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].contains("CFG 7.5"));
     }
@@ -307,16 +307,1062 @@ mod tests {
         let soul_v2 = "hash_v2";
 
         // Store karma under Soul v1
-        jq.store_karma(&id, "soul_skill", "Use this workflow", "Technical", soul_v1).await.unwrap();
+        jq.store_karma(&id, "soul_skill", None, "Use this workflow", "Technical", soul_v1).await.unwrap();
 
         // Fetch karma using Soul v1
-        let karma_v1 = jq.fetch_relevant_karma("Soul Test", "soul_skill", 10, soul_v1).await.unwrap();
+        let karma_v1 = jq.fetch_relevant_karma("Soul Test", "soul_skill", None, 10, soul_v1).await.unwrap();
         assert_eq!(karma_v1.len(), 1);
         assert!(!karma_v1[0].contains("[LEGACY KARMA"));
 
         // Fetch karma using Soul v2 (Simulating a Soul evolution / Cognitive Dissonance)
-        let karma_v2 = jq.fetch_relevant_karma("Soul Test", "soul_skill", 10, soul_v2).await.unwrap();
+        let karma_v2 = jq.fetch_relevant_karma("Soul Test", "soul_skill", None, 10, soul_v2).await.unwrap();
         assert_eq!(karma_v2.len(), 1);
         assert!(karma_v2[0].contains("[LEGACY KARMA"));
     }
+
+    // ===== 11. Series DAG: depends_on =====
+    #[tokio::test]
+    async fn test_child_waits_for_parent_completion() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let part1 = jq.enqueue("Series Part 1", "cinematic", Some("{}")).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Series Part 2", "cinematic", Some("{}")).await.unwrap();
+
+        let job = jq.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, part1);
+
+        // Part 1 is still Processing: Part 2 must not be dequeued
+        assert!(jq.dequeue().await.unwrap().is_none());
+
+        jq.complete_job(&part1, None).await.unwrap();
+        let job = jq.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, part2);
+        assert_eq!(job.depends_on.as_deref(), Some(part1.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_parent_failure_cascades_to_descendants() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let part1 = jq.enqueue("Series Part 1", "cinematic", Some("{}")).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Series Part 2", "cinematic", Some("{}")).await.unwrap();
+        let part3 = jq.enqueue_child(&part2, "Series Part 3", "cinematic", Some("{}")).await.unwrap();
+
+        let _ = jq.dequeue().await.unwrap();
+        jq.fail_job(&part1, "boom").await.unwrap();
+
+        for id in [&part2, &part3] {
+            let job = jq.fetch_job(id).await.unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Failed);
+            assert!(job.error_message.unwrap().starts_with("DEPENDENCY_FAILED"));
+        }
+        assert!(jq.dequeue().await.unwrap().is_none());
+
+        // Cannot attach new children to a failed or missing parent
+        assert!(jq.enqueue_child(&part1, "Late Part", "cinematic", None).await.is_err());
+        assert!(jq.enqueue_child("missing", "Orphan", "cinematic", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zombie_reclaim_cascades_to_descendants() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let part1 = jq.enqueue("Series Part 1", "cinematic", Some("{}")).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Series Part 2", "cinematic", Some("{}")).await.unwrap();
+        let part3 = jq.enqueue_child(&part2, "Series Part 3", "cinematic", Some("{}")).await.unwrap();
+
+        let _ = jq.dequeue().await.unwrap();
+        sqlx::query("UPDATE jobs SET last_heartbeat = datetime('now', '-20 minutes') WHERE id = ?")
+            .bind(&part1)
+            .execute(jq.pool_ref())
+            .await
+            .unwrap();
+        assert_eq!(jq.reclaim_zombie_jobs(15).await.unwrap(), 1);
+
+        let expected = format!("DEPENDENCY_FAILED: {}", part1);
+        for id in [&part2, &part3] {
+            let job = jq.fetch_job(id).await.unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Failed);
+            assert_eq!(job.error_message.as_deref(), Some(expected.as_str()));
+        }
+
+        // Retrying the reclaimed parent brings the whole series back
+        assert_eq!(jq.retry_job(&part1).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_poison_pill_cascades_to_descendants() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let part1 = jq.enqueue("Series Part 1", "cinematic", Some("{}")).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Series Part 2", "cinematic", Some("{}")).await.unwrap();
+
+        assert!(!jq.increment_job_retry_count(&part1).await.unwrap());
+        assert!(!jq.increment_job_retry_count(&part1).await.unwrap());
+        assert_eq!(jq.fetch_job(&part2).await.unwrap().unwrap().status, JobStatus::Pending);
+
+        assert!(jq.increment_job_retry_count(&part1).await.unwrap());
+        assert_eq!(jq.fetch_job(&part1).await.unwrap().unwrap().status, JobStatus::Failed);
+        let child = jq.fetch_job(&part2).await.unwrap().unwrap();
+        assert_eq!(child.status, JobStatus::Failed);
+        assert_eq!(child.error_message, Some(format!("DEPENDENCY_FAILED: {}", part1)));
+    }
+
+    // ===== 12. Channel Routing & SOUL Profiles =====
+    #[tokio::test]
+    async fn test_channel_and_soul_are_stored_and_inherited() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let plain = jq.enqueue("Default Topic", "cinematic", None).await.unwrap();
+        let part1 = jq.enqueue_for_channel("gaming", Some("comedy"), "Gaming Part 1", "cinematic", None).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Gaming Part 2", "cinematic", None).await.unwrap();
+
+        assert_eq!(jq.fetch_job(&plain).await.unwrap().unwrap().channel, shared::config::DEFAULT_CHANNEL);
+        assert_eq!(jq.fetch_job(&part1).await.unwrap().unwrap().channel, "gaming");
+        assert_eq!(jq.fetch_job(&part2).await.unwrap().unwrap().channel, "gaming");
+        assert_eq!(jq.fetch_job(&plain).await.unwrap().unwrap().soul, None);
+        assert_eq!(jq.fetch_job(&part2).await.unwrap().unwrap().soul.as_deref(), Some("comedy"));
+    }
+
+    // ===== 13. Samsara Provenance =====
+    #[tokio::test]
+    async fn test_provenance_round_trip() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let manual = jq.enqueue("Manual Topic", "cinematic", None).await.unwrap();
+        let auto = jq.enqueue("Samsara Topic", "cinematic", None).await.unwrap();
+        let provenance = factory_core::contracts::JobProvenance {
+            angle: "奇妙なミーム".to_string(),
+            search_query: "AI meme".to_string(),
+            fallback_events: vec!["world_context_fallback".to_string()],
+            ..Default::default()
+        };
+        jq.store_provenance(&auto, &provenance).await.unwrap();
+
+        assert!(jq.fetch_provenance(&manual).await.unwrap().is_none());
+        let stored = jq.fetch_provenance(&auto).await.unwrap().unwrap();
+        assert_eq!(stored.search_query, "AI meme");
+        assert_eq!(stored.fallback_events, vec!["world_context_fallback".to_string()]);
+    }
+
+    // ===== 14. Takedown =====
+    #[tokio::test]
+    async fn test_takedown_is_recorded_and_excluded_from_evaluation() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let unlinked = jq.enqueue("Unlinked", "cinematic", None).await.unwrap();
+        assert!(jq.record_takedown(&unlinked, TakedownAction::Unpublish).await.is_err());
+
+        let id = jq.enqueue("Published", "cinematic", None).await.unwrap();
+        jq.link_sns_data(&id, "youtube", "vid123").await.unwrap();
+        sqlx::query("UPDATE jobs SET published_at = datetime('now', '-10 days') WHERE id = ?")
+            .bind(&id)
+            .execute(jq.pool_ref())
+            .await
+            .unwrap();
+        assert_eq!(jq.fetch_jobs_for_evaluation(7, 10).await.unwrap().len(), 1);
+
+        jq.record_takedown(&id, TakedownAction::SetPrivate).await.unwrap();
+        let (action, _) = jq.fetch_takedown(&id).await.unwrap().unwrap();
+        assert_eq!(action, "set_private");
+        assert!(jq.fetch_takedown(&unlinked).await.unwrap().is_none());
+        assert!(jq.fetch_jobs_for_evaluation(7, 10).await.unwrap().is_empty());
+    }
+
+    // ===== 15. Dead-letter Retry =====
+    #[tokio::test]
+    async fn test_retry_requeues_failed_job_and_cascaded_descendants() {
+        let (jq, _tmp) = create_test_queue().await;
+
+        let part1 = jq.enqueue("Series Part 1", "cinematic", Some("{}")).await.unwrap();
+        let part2 = jq.enqueue_child(&part1, "Series Part 2", "cinematic", Some("{}")).await.unwrap();
+        let part3 = jq.enqueue_child(&part2, "Series Part 3", "cinematic", Some("{}")).await.unwrap();
+
+        // Only Failed jobs can be retried
+        assert!(jq.retry_job(&part1).await.is_err());
+        assert!(jq.retry_job("missing").await.is_err());
+
+        let _ = jq.dequeue().await.unwrap();
+        jq.fail_job(&part1, "boom").await.unwrap();
+
+        let failed = jq.fetch_failed_jobs(10).await.unwrap();
+        assert_eq!(failed.len(), 3);
+        assert!(failed.iter().any(|f| f.job_id == part1 && f.error_message.as_deref() == Some("boom")));
+
+        // Descendants must wait for the parent to be retried
+        assert!(jq.retry_job(&part2).await.is_err());
+        assert_eq!(jq.retry_job(&part1).await.unwrap(), 3);
+        assert!(jq.fetch_failed_jobs(10).await.unwrap().is_empty());
+
+        let job = jq.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, part1);
+        // Error history is kept until the next outcome
+        assert_eq!(job.error_message.as_deref(), Some("boom"));
+        assert!(jq.dequeue().await.unwrap().is_none());
+
+        jq.fail_job(&part1, "boom again").await.unwrap();
+        let failed = jq.fetch_failed_jobs(10).await.unwrap();
+        let summary = failed.iter().find(|f| f.job_id == part1).unwrap();
+        assert_eq!(summary.requeue_count, 1);
+        assert_eq!(jq.fetch_job(&part3).await.unwrap().unwrap().status, JobStatus::Failed);
+    }
+
+    // ===== 16. Semantic Karma =====
+
+    /// 概念グループごとのキーワード出現数をベクトルにするテスト用 Embedder
+    struct KeywordEmbedder;
+
+    #[async_trait::async_trait]
+    impl factory_core::traits::Embedder for KeywordEmbedder {
+        fn model_id(&self) -> &str {
+            "keyword-test"
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>, factory_core::error::FactoryError> {
+            let text = text.to_lowercase();
+            let groups: [&[&str]; 2] = [&["gpu", "vram", "memory", "oom"], &["audio", "voice", "tts"]];
+            Ok(groups.iter().map(|g| g.iter().filter(|k| text.contains(*k)).count() as f32).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_karma_matches_related_lessons_and_backfills() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Karma Source", "cinematic", Some("{}")).await.unwrap();
+        let hash = "test_hash";
+        jq.store_karma(&id, "comfy_bridge", None, "Lower the batch size when VRAM runs out", "Technical", hash).await.unwrap();
+        jq.store_karma(&id, "voice_actor", None, "Voice clipping: normalize TTS output", "Technical", hash).await.unwrap();
+        jq.store_karma(&id, "misc", None, "GPU memory tip: restart ComfyUI nightly", "Technical", hash).await.unwrap();
+
+        let semantic = jq.clone().with_embedder(std::sync::Arc::new(KeywordEmbedder), 0.55);
+
+        // Lessons stored before the embedder existed fall back to substring matching
+        let before = semantic.fetch_relevant_karma("GPU memory", "unrelated_skill", None, 10, hash).await.unwrap();
+        assert_eq!(before.len(), 1);
+        assert!(before[0].contains("restart ComfyUI"));
+
+        assert_eq!(semantic.backfill_karma_embeddings(10).await.unwrap(), 3);
+        assert_eq!(semantic.backfill_karma_embeddings(10).await.unwrap(), 0);
+
+        // "VRAM" is found without sharing a substring with the topic; the voice lesson is not
+        let after = semantic.fetch_relevant_karma("GPU memory", "unrelated_skill", None, 10, hash).await.unwrap();
+        assert_eq!(after.len(), 2);
+        assert!(after.iter().any(|k| k.contains("VRAM")));
+        assert!(!after.iter().any(|k| k.contains("Voice clipping")));
+
+        // Lessons stored with an embedder get their vector immediately
+        semantic.store_karma(&id, "misc", None, "OOM again: reduce resolution", "Technical", hash).await.unwrap();
+        assert_eq!(semantic.backfill_karma_embeddings(10).await.unwrap(), 0);
+    }
+
+    // ===== 17. Series =====
+
+    #[tokio::test]
+    async fn test_series_numbers_episodes_and_links_previous_ones() {
+        let (jq, _tmp) = create_test_queue().await;
+        let series = jq.create_series(&factory_core::traits::NewSeries {
+            name: "AI News Weekly".into(),
+            topic_template: "AI News Weekly {date}".into(),
+            style: "tech_news_v1".into(),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(series.channel, "default");
+        assert!(jq.create_series(&factory_core::traits::NewSeries { name: "AI News Weekly".into(), ..Default::default() }).await.is_err());
+
+        let (first, n1) = jq.enqueue_episode("AI News Weekly", Some("GPT-5 rumours"), Some("cinematic"), None, None).await.unwrap();
+        let (second, n2) = jq.enqueue_episode(&series.id, None, None, None, None).await.unwrap();
+        assert_eq!((n1, n2), (1, 2));
+        let job = jq.fetch_job(&first).await.unwrap().unwrap();
+        assert!(job.topic.starts_with("AI News Weekly ") && job.topic.ends_with(": GPT-5 rumours"));
+        assert_eq!(job.style, "tech_news_v1"); // the series style wins
+
+        jq.complete_job(&first, None).await.unwrap();
+        jq.link_sns_data(&first, "youtube", "abc123").await.unwrap();
+
+        let ctx = jq.fetch_episode_context(&second).await.unwrap().unwrap();
+        assert_eq!(ctx.episode_number, 2);
+        assert_eq!(ctx.format_title("Robots everywhere"), "AI News Weekly #2: Robots everywhere");
+        assert_eq!(ctx.previous_episodes.len(), 1);
+        assert_eq!(ctx.previous_episodes[0].job_id, first);
+        assert_eq!(ctx.cross_promotion().len(), 1);
+
+        assert!(jq.fetch_episode_context(&jq.enqueue("Solo", "cinematic", None).await.unwrap()).await.unwrap().is_none());
+        assert_eq!(jq.fetch_series_episodes(&series.id, 10).await.unwrap().len(), 2);
+        assert_eq!(jq.fetch_series("AI News Weekly").await.unwrap().unwrap().episode_counter, 2);
+        assert!(jq.enqueue_episode("missing", Some("x"), None, None, None).await.is_err());
+    }
+
+    // ===== 18. Scheduled Jobs =====
+
+    #[tokio::test]
+    async fn test_dequeue_skips_jobs_scheduled_in_the_future() {
+        let (jq, _tmp) = create_test_queue().await;
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        let past = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+
+        let later = jq.enqueue_at("default", None, "Release window", "cinematic", None, Some(&future)).await.unwrap();
+        let now = jq.enqueue("Right now", "cinematic", None).await.unwrap();
+        // A job whose time has come runs before jobs queued after its slot
+        let due = jq.enqueue_at("default", None, "Due", "cinematic", None, Some(&past)).await.unwrap();
+
+        assert_eq!(jq.dequeue().await.unwrap().unwrap().id, due);
+        assert_eq!(jq.dequeue().await.unwrap().unwrap().id, now);
+        assert!(jq.dequeue().await.unwrap().is_none());
+
+        let job = jq.fetch_job(&later).await.unwrap().unwrap();
+        assert_eq!(job.scheduled_for.as_deref(), Some(future.as_str()));
+    }
+
+    // ===== 19. Collaborator Reviews =====
+
+    #[tokio::test]
+    async fn test_review_is_recorded_and_mirrored_to_creative_rating() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Review me", "cinematic", None).await.unwrap();
+        let project = format!("job_{}", id);
+
+        // Not finished yet: the review is kept but the rating is not touched
+        assert!(!jq.record_review(&project, Some(&id), factory_core::traits::ReviewVerdict::Approve, "", "mika").await.unwrap());
+
+        jq.dequeue().await.unwrap();
+        jq.complete_job(&id, None).await.unwrap();
+        assert!(jq.record_review(&project, Some(&id), factory_core::traits::ReviewVerdict::Revise, "Intro too long", "mika").await.unwrap());
+        assert_eq!(jq.fetch_job(&id).await.unwrap().unwrap().creative_rating, Some(-1));
+
+        // Projects without a job (remixes) are still recorded
+        assert!(!jq.record_review("tech_20250101_000000", None, factory_core::traits::ReviewVerdict::Approve, "ok", "").await.unwrap());
+
+        let reviews = jq.fetch_reviews(&project).await.unwrap();
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0].verdict, factory_core::traits::ReviewVerdict::Revise);
+        assert_eq!(reviews[0].comment, "Intro too long");
+    }
+
+    // ===== 20. Cross-Platform Metrics =====
+
+    #[tokio::test]
+    async fn test_sns_metrics_record_their_platform() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Cross-posted", "cinematic", None).await.unwrap();
+        jq.link_sns_data(&id, "TikTok", "7231").await.unwrap();
+
+        jq.record_sns_metrics(&id, "tiktok", 1, 5000, 310, 12, Some("[]")).await.unwrap();
+        jq.record_sns_metrics(&id, "instagram", 1, 900, 40, 3, None).await.unwrap();
+
+        let records = jq.fetch_pending_evaluations(10).await.unwrap();
+        let platforms: Vec<_> = records.iter().map(|r| r.platform.as_deref()).collect();
+        assert_eq!(platforms, vec![Some("tiktok"), Some("instagram")]);
+        assert_eq!(records[1].views, 900);
+    }
+
+    // ===== 21. Transient Failure Backoff =====
+
+    #[tokio::test]
+    async fn test_defer_retry_backs_off_then_gives_up() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Flaky network", "cinematic", None).await.unwrap();
+        jq.dequeue().await.unwrap();
+
+        let (attempt, retry_at) = jq.defer_retry(&id, "ComfyUI reset", 3, 60).await.unwrap().unwrap();
+        assert_eq!(attempt, 1);
+        let job = jq.fetch_job(&id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Pending);
+        assert_eq!(job.scheduled_for.as_deref(), Some(retry_at.as_str()));
+        // Not due yet
+        assert!(jq.dequeue().await.unwrap().is_none());
+
+        let (attempt, _) = jq.defer_retry(&id, "ComfyUI reset", 3, 60).await.unwrap().unwrap();
+        assert_eq!(attempt, 2);
+        assert!(jq.defer_retry(&id, "ComfyUI reset", 3, 60).await.unwrap().is_none());
+
+        // A manual retry starts a fresh budget
+        jq.fail_job(&id, "gave up").await.unwrap();
+        jq.retry_job(&id).await.unwrap();
+        assert_eq!(jq.defer_retry(&id, "again", 3, 60).await.unwrap().unwrap().0, 1);
+    }
+
+    // ===== 22. Watch Folder Footage Jobs =====
+
+    #[tokio::test]
+    async fn test_footage_job_carries_its_render_path() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue_footage("tech", "Manual render", "cinematic", "/inbox/.claimed/ab12__render.mp4").await.unwrap();
+
+        let job = jq.dequeue().await.unwrap().unwrap();
+        assert_eq!(job.id, id);
+        assert_eq!(job.channel, "tech");
+        assert_eq!(job.footage.as_deref(), Some("/inbox/.claimed/ab12__render.mp4"));
+        assert!(jq.fetch_job(&jq.enqueue("Plain", "cinematic", None).await.unwrap()).await.unwrap().unwrap().footage.is_none());
+    }
+
+    // ===== 23. Shutdown Checkpoint =====
+
+    #[tokio::test]
+    async fn test_requeue_interrupted_only_touches_processing_jobs() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Long render", "cinematic", None).await.unwrap();
+        jq.dequeue().await.unwrap();
+
+        assert!(jq.requeue_interrupted(&id, "Interrupted by shutdown").await.unwrap());
+        let job = jq.fetch_job(&id).await.unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Pending);
+        assert!(job.started_at.is_none());
+        // Picked up again right away, and it does not count against the transient budget
+        assert_eq!(jq.dequeue().await.unwrap().unwrap().id, id);
+        assert_eq!(jq.defer_retry(&id, "blip", 3, 60).await.unwrap().unwrap().0, 1);
+
+        // A job that already settled stays as it is
+        let done = jq.enqueue("Done", "cinematic", None).await.unwrap();
+        jq.complete_job(&done, None).await.unwrap();
+        assert!(!jq.requeue_interrupted(&done, "Interrupted by shutdown").await.unwrap());
+        assert_eq!(jq.fetch_job(&done).await.unwrap().unwrap().status, JobStatus::Completed);
+    }
+
+    // ===== 24. Stage Timeline =====
+
+    #[tokio::test]
+    async fn test_job_stages_close_previous_and_purge_with_job() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Timeline", "cinematic", None).await.unwrap();
+        jq.dequeue().await.unwrap();
+
+        jq.begin_job_stage(&id, "concept").await.unwrap();
+        jq.begin_job_stage(&id, "voice").await.unwrap();
+        let spans = jq.fetch_job_stages(&id).await.unwrap();
+        assert_eq!(spans.iter().map(|s| s.stage.as_str()).collect::<Vec<_>>(), vec!["concept", "voice"]);
+        assert!(spans[0].ended_at.is_some());
+        assert!(spans[1].ended_at.is_none());
+
+        jq.end_job_stages(&id).await.unwrap();
+        assert!(jq.fetch_job_stages(&id).await.unwrap().iter().all(|s| s.ended_at.is_some()));
+
+        // Stages go away with their job
+        jq.complete_job(&id, None).await.unwrap();
+        sqlx::query("UPDATE jobs SET created_at = datetime('now', '-90 days') WHERE id = ?")
+            .bind(&id).execute(jq.pool_ref()).await.unwrap();
+        assert_eq!(jq.purge_old_jobs(60).await.unwrap(), 1);
+        assert!(jq.fetch_job_stages(&id).await.unwrap().is_empty());
+    }
+
+    // ===== 25. Cost Ledger =====
+
+    #[tokio::test]
+    async fn test_cost_ledger_per_job_and_summary() {
+        use factory_core::cost::{CostEntry, CostTracker};
+        use shared::config::CostsConfig;
+        use std::sync::Arc;
+
+        let (jq, _tmp) = create_test_queue().await;
+        let jq = Arc::new(jq);
+        let id = jq.enqueue("Costs", "cinematic", None).await.unwrap();
+        jq.dequeue().await.unwrap();
+
+        let prices = CostsConfig { brave_usd_per_call: 0.01, gpu_usd_per_hour: 3.6, ..CostsConfig::default() };
+        let tracker = CostTracker::new(jq.clone(), prices);
+
+        // Entries recorded before the job exists are attributed afterwards
+        let unscoped = tracker.for_job(None, None);
+        unscoped.record(CostEntry::call("brave", "samsara_search")).await;
+        assert!(jq.fetch_job_costs(&id).await.unwrap().lines.is_empty());
+        unscoped.attribute(&id).await;
+
+        let scoped = tracker.for_job(Some(&id), Some("job_x"));
+        scoped.record(CostEntry::call("brave", "samsara_search")).await;
+        scoped.record(CostEntry::gpu("render_scene", 100.0)).await;
+
+        let costs = jq.fetch_job_costs(&id).await.unwrap();
+        assert_eq!(costs.lines.len(), 2);
+        assert_eq!(costs.lines[0].operation, "samsara_search");
+        assert_eq!(costs.lines[0].api_calls, 2);
+        assert!((costs.total.estimated_usd - 0.12).abs() < 1e-9);
+
+        // A disabled tracker records nothing
+        let disabled = CostTracker::new(jq.clone(), CostsConfig { enabled: false, ..CostsConfig::default() });
+        disabled.for_job(Some(&id), None).record(CostEntry::call("brave", "trends")).await;
+        tracker.for_job(None, Some("remix_1")).record(CostEntry::call("brave", "trends")).await;
+
+        jq.complete_job(&id, None).await.unwrap();
+        let summary = jq.fetch_cost_summary(7).await.unwrap();
+        assert_eq!(summary.videos, 1);
+        // Two Brave calls and one GPU render for the job, one Brave call for remix_1
+        assert_eq!(summary.total.api_calls, 4);
+        assert!(summary.usd_per_video.unwrap() > 0.12);
+    }
+
+    // ===== 26. Title / Hook A/B =====
+
+    #[tokio::test]
+    async fn test_title_variants_track_the_published_hook() {
+        use crate::job_queue::summarize_hook_results;
+        use factory_core::contracts::TitleVariant;
+
+        let (jq, _tmp) = create_test_queue().await;
+        let variant = |label: &str, hook_style: &str| TitleVariant {
+            label: label.into(),
+            title: format!("Title {}", label),
+            hook: String::new(),
+            hook_style: hook_style.into(),
+        };
+        let first = jq.enqueue("Hooks 1", "cinematic", None).await.unwrap();
+        let second = jq.enqueue("Hooks 2", "cinematic", None).await.unwrap();
+        let plain = jq.enqueue("No variants", "cinematic", None).await.unwrap();
+        for id in [&first, &second] {
+            jq.record_title_variants(id, &[variant("A", "question"), variant("B", "shock_stat")]).await.unwrap();
+        }
+
+        // Unpublished label is rejected, A is assumed until told otherwise
+        assert!(jq.set_published_variant(&first, "C").await.is_err());
+        assert_eq!(jq.fetch_published_variant(&first).await.unwrap().unwrap().label, "A");
+        assert_eq!(jq.set_published_variant(&second, "b").await.unwrap().hook_style, "shock_stat");
+        assert!(jq.fetch_published_variant(&plain).await.unwrap().is_none());
+
+        jq.record_sns_metrics(&first, "youtube", 7, 100, 1, 0, None).await.unwrap();
+        jq.record_sns_metrics(&second, "youtube", 7, 900, 9, 0, None).await.unwrap();
+        jq.record_sns_metrics(&plain, "youtube", 7, 5000, 50, 0, None).await.unwrap();
+        let labels: Vec<(String, Option<String>)> = sqlx::query_as("SELECT job_id, title_variant FROM sns_metrics_history ORDER BY id")
+            .fetch_all(jq.pool_ref()).await.unwrap();
+        assert_eq!(labels[0].1.as_deref(), Some("A"));
+        assert_eq!(labels[1].1.as_deref(), Some("B"));
+        assert_eq!(labels[2].1, None);
+
+        let stats = jq.fetch_hook_stats("default", 7).await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].hook_style.as_str(), stats[0].wins), ("shock_stat", 1));
+        assert_eq!((stats[1].hook_style.as_str(), stats[1].losses), ("question", 1));
+        assert!(jq.fetch_hook_stats("default", 1).await.unwrap().is_empty());
+
+        // Wins are counted against the median; averages are per hook style
+        let summary = summarize_hook_results(vec![
+            ("question".into(), 10), ("question".into(), 30), ("how_to".into(), 20), (String::new(), 40),
+        ]);
+        assert_eq!(summary[0].hook_style, "unlabeled");
+        let question = summary.iter().find(|s| s.hook_style == "question").unwrap();
+        assert_eq!((question.published, question.wins, question.losses), (2, 1, 1));
+        assert!((question.avg_views - 20.0).abs() < 1e-9);
+    }
+
+    // ===== 27. Chat Memory Forget =====
+
+    #[tokio::test]
+    async fn test_forget_chat_memory_wipes_one_channel_and_audits() {
+        let (jq, _tmp) = create_test_queue().await;
+        jq.insert_chat_message("111", "user", "hello").await.unwrap();
+        jq.insert_chat_message("111", "assistant", "hi").await.unwrap();
+        jq.insert_chat_message("222", "user", "other channel").await.unwrap();
+        jq.update_chat_memory_summary("111", "likes cats").await.unwrap();
+
+        assert_eq!(jq.forget_chat_memory("111", "owner#1").await.unwrap(), (2, true));
+        assert!(jq.fetch_chat_history("111", 20).await.unwrap().is_empty());
+        assert!(jq.get_chat_memory_summary("111").await.unwrap().is_none());
+        assert_eq!(jq.fetch_chat_history("222", 20).await.unwrap().len(), 1);
+
+        // Forgetting an empty channel is still audited
+        assert_eq!(jq.forget_chat_memory("111", "owner#1").await.unwrap(), (0, false));
+        let wipes = jq.fetch_chat_memory_wipes("111").await.unwrap();
+        assert_eq!(wipes.len(), 2);
+        assert_eq!(wipes[1]["messages_deleted"], 2);
+        assert_eq!(wipes[1]["summary_deleted"], true);
+        assert_eq!(wipes[0]["requested_by"], "owner#1");
+        assert!(jq.fetch_chat_memory_wipes("222").await.unwrap().is_empty());
+    }
+
+    // ===== 28. Scoped API Tokens =====
+
+    #[tokio::test]
+    async fn test_api_tokens_are_hashed_scoped_and_revocable() {
+        use factory_core::api::ApiScope;
+        let (jq, _tmp) = create_test_queue().await;
+        let (info, token) = jq.create_api_token("dashboard", ApiScope::Readonly).await.unwrap();
+        assert!(token.starts_with(crate::job_queue::API_TOKEN_PREFIX));
+        assert!(token.starts_with(&info.hint));
+
+        // Only the hash is stored
+        let stored: Vec<String> = sqlx::query_scalar("SELECT token_hash FROM api_tokens").fetch_all(jq.pool_ref()).await.unwrap();
+        assert!(stored.iter().all(|h| h != &token));
+
+        let verified = jq.verify_api_token(&token).await.unwrap().unwrap();
+        assert_eq!((verified.name.as_str(), verified.scope), ("dashboard", ApiScope::Readonly));
+        assert!(jq.list_api_tokens().await.unwrap()[0].last_used_at.is_some());
+        assert!(jq.verify_api_token("sft_nope").await.unwrap().is_none());
+        assert!(jq.create_api_token("dashboard", ApiScope::Admin).await.is_err());
+
+        assert!(jq.revoke_api_token("dashboard").await.unwrap());
+        assert!(!jq.revoke_api_token("dashboard").await.unwrap());
+        assert!(jq.verify_api_token(&token).await.unwrap().is_none());
+        // The name is free again once revoked
+        let (_, ops) = jq.create_api_token("dashboard", ApiScope::Operator).await.unwrap();
+        assert_eq!(jq.verify_api_token(&ops).await.unwrap().unwrap().scope, ApiScope::Operator);
+        assert_eq!(jq.list_api_tokens().await.unwrap().len(), 2);
+    }
+
+    // ===== 29. Audit Log =====
+
+    #[tokio::test]
+    async fn test_audit_log_is_append_only_and_filterable() {
+        let (jq, _tmp) = create_test_queue().await;
+        jq.record_audit("api", "dashboard", "enqueue", Some("job-1"), Some("POST /api/remix -> 202")).await.unwrap();
+        jq.record_audit("discord", "alice (42)", "rating", Some("job-1"), None).await.unwrap();
+        jq.record_audit("discord", "alice (42)", "rating", Some("job-2"), None).await.unwrap();
+
+        let all = jq.fetch_audit_log(10, None, None, None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].target.as_deref(), Some("job-2"));
+        assert_eq!(jq.fetch_audit_log(10, Some("dashboard"), None, None).await.unwrap()[0].action, "enqueue");
+        assert_eq!(jq.fetch_audit_log(10, None, Some("rating"), None).await.unwrap().len(), 2);
+        assert!(jq.fetch_audit_log(10, None, None, Some("2999-01-01 00:00:00")).await.unwrap().is_empty());
+
+        // Rows cannot be edited or removed
+        assert!(sqlx::query("UPDATE audit_log SET actor = 'mallory'").execute(jq.pool_ref()).await.is_err());
+        assert!(sqlx::query("DELETE FROM audit_log").execute(jq.pool_ref()).await.is_err());
+
+        let summary = jq.summarize_audit(24).await.unwrap();
+        assert_eq!((summary[0].action.as_str(), summary[0].actor.as_str(), summary[0].count), ("rating", "alice (42)", 2));
+        assert_eq!(summary.len(), 2);
+    }
+
+    // ===== 30. Visual Fingerprints =====
+
+    #[tokio::test]
+    async fn test_visual_fingerprints_round_trip_u64_and_replace_per_scene() {
+        use factory_core::fingerprint::{FingerprintStore, VisualFingerprint};
+        let (jq, _tmp) = create_test_queue().await;
+        let fp = |project: &str, scene, image_hash| VisualFingerprint { project_id: project.into(), scene, prompt_hash: u64::MAX - 1, image_hash };
+        jq.record_fingerprint(&fp("job_a", 0, Some(0xF0F0_0000_0000_0001))).await.unwrap();
+        jq.record_fingerprint(&fp("job_a", 1, None)).await.unwrap();
+        // A re-render of the same scene replaces its fingerprint
+        jq.record_fingerprint(&fp("job_a", 0, Some(7))).await.unwrap();
+        jq.record_fingerprint(&fp("job_b", 0, Some(9))).await.unwrap();
+
+        let recent = jq.recent_fingerprints(28, "job_b").await.unwrap();
+        assert_eq!(recent.len(), 2);
+        assert!(recent.iter().all(|f| f.project_id == "job_a" && f.prompt_hash == u64::MAX - 1));
+        assert_eq!(recent.iter().find(|f| f.scene == 0).unwrap().image_hash, Some(7));
+        assert_eq!(recent.iter().find(|f| f.scene == 1).unwrap().image_hash, None);
+        assert_eq!(jq.recent_fingerprints(28, "nobody").await.unwrap().len(), 3);
+    }
+
+    // ===== 31. Cancellation =====
+
+    #[tokio::test]
+    async fn test_cancel_only_pending_and_cascades_to_children() {
+        let (jq, _tmp) = create_test_queue().await;
+        let running = jq.enqueue("Running", "cinematic", None).await.unwrap();
+        let _ = jq.dequeue().await.unwrap();
+        let parent = jq.enqueue("Part 1", "cinematic", None).await.unwrap();
+        let child = jq.enqueue_child(&parent, "Part 2", "cinematic", None).await.unwrap();
+
+        assert!(matches!(jq.cancel_job(&running).await, Err(factory_core::error::FactoryError::InvalidJobState { .. })));
+        assert!(jq.cancel_job("missing").await.is_err());
+        jq.cancel_job(&parent).await.unwrap();
+
+        let parent_job = jq.fetch_job(&parent).await.unwrap().unwrap();
+        assert_eq!(parent_job.status, JobStatus::Failed);
+        assert_eq!(parent_job.error_message.as_deref(), Some(crate::job_queue::CANCELLED_REASON));
+        assert_eq!(jq.fetch_job(&child).await.unwrap().unwrap().status, JobStatus::Failed);
+        assert!(jq.cancel_job(&parent).await.is_err());
+        assert!(jq.dequeue().await.unwrap().is_none());
+        // A cancelled job can still be retried like any failed one
+        assert_eq!(jq.retry_job(&parent).await.unwrap(), 2);
+    }
+
+    // ===== 32. Status Page =====
+
+    #[tokio::test]
+    async fn test_queued_and_completed_summaries() {
+        let (jq, _tmp) = create_test_queue().await;
+        let done = jq.enqueue("Done", "cinematic", None).await.unwrap();
+        let _ = jq.dequeue().await.unwrap();
+        jq.complete_job(&done, None).await.unwrap();
+        jq.set_creative_rating(&done, 1).await.unwrap();
+        let first = jq.enqueue("First", "hype", None).await.unwrap();
+        let child = jq.enqueue_child(&first, "Second", "hype", None).await.unwrap();
+
+        let queued = jq.fetch_queued_jobs(10).await.unwrap();
+        assert_eq!(queued.iter().map(|q| q.job_id.as_str()).collect::<Vec<_>>(), vec![first.as_str(), child.as_str()]);
+        assert_eq!(queued[1].depends_on.as_deref(), Some(first.as_str()));
+        assert_eq!(queued[0].style, "hype");
+
+        let completed = jq.fetch_recent_completions(10).await.unwrap();
+        assert_eq!(completed.len(), 1);
+        assert_eq!((completed[0].topic.as_str(), completed[0].creative_rating), ("Done", Some(1)));
+        assert!(!completed[0].completed_at.is_empty());
+    }
+
+    // ===== 33. Topic Dedupe =====
+
+    #[tokio::test]
+    async fn test_recent_topics_and_similarity() {
+        use crate::job_queue::{find_similar_topic, topic_similarity};
+        let (jq, _tmp) = create_test_queue().await;
+        jq.enqueue_for_channel("main", None, "AI最新ニュースまとめ", "tech_news_v1", None).await.unwrap();
+        jq.enqueue_for_channel("other", None, "Other channel", "tech_news_v1", None).await.unwrap();
+        let cancelled = jq.enqueue_for_channel("main", None, "Cancelled topic", "tech_news_v1", None).await.unwrap();
+        jq.cancel_job(&cancelled).await.unwrap();
+        let old = jq.enqueue_for_channel("main", None, "Old topic", "tech_news_v1", None).await.unwrap();
+        let ten_days_ago = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+        sqlx::query("UPDATE jobs SET created_at = ? WHERE id = ?").bind(&ten_days_ago).bind(&old).execute(jq.pool_ref()).await.unwrap();
+
+        let recent = jq.fetch_recent_topics("main", 7, 50).await.unwrap();
+        assert_eq!(recent, vec!["AI最新ニュースまとめ".to_string()]);
+
+        assert_eq!(topic_similarity("Same Topic!", "same topic"), 1.0);
+        assert!(topic_similarity("AI最新ニュースまとめ", "最新AIニュースまとめ") > 0.6);
+        assert!(topic_similarity("AI最新ニュースまとめ", "量子コンピュータ入門") < 0.2);
+        assert_eq!(find_similar_topic("最新AIニュースまとめ", &recent, 0.6).map(|(t, _)| t), Some("AI最新ニュースまとめ"));
+        assert!(find_similar_topic("量子コンピュータ入門", &recent, 0.6).is_none());
+    }
+
+    // ===== 34. Karma Curation =====
+
+    #[tokio::test]
+    async fn test_manual_karma_filter_and_delete() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Karma Topic", "style", None).await.unwrap();
+        jq.store_karma(&id, "comfy_bridge", None, "Use CFG 7.5 for anime", "Technical", "hash").await.unwrap();
+        let manual = jq.insert_karma(None, "global", None, "Never open with a greeting", "Creative", 40, None).await.unwrap();
+
+        let all = jq.fetch_all_karma(10).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let creative = jq.fetch_karma(10, None, Some("Creative"), None, None).await.unwrap();
+        assert_eq!(creative.len(), 1);
+        assert_eq!(creative[0]["id"], manual.as_str());
+        assert_eq!(creative[0]["job_id"], "");
+        assert_eq!(creative[0]["weight"], 40);
+        assert_eq!(jq.fetch_karma(10, Some("comfy_bridge"), None, None, None).await.unwrap().len(), 1);
+        assert_eq!(jq.fetch_karma(10, None, None, Some(50), None).await.unwrap().len(), 1);
+        assert_eq!(jq.fetch_karma(10, None, None, None, Some(50)).await.unwrap()[0]["id"], manual.as_str());
+
+        // 手動の教訓は Soul を持たないので LEGACY 扱いにならない
+        let injected = jq.fetch_relevant_karma("anything", "other_skill", None, 5, "new_hash").await.unwrap();
+        assert!(injected.contains(&"Never open with a greeting".to_string()));
+
+        assert!(jq.insert_karma(None, "global", None, "Bad type", "Mystic", 50, None).await.is_err());
+        assert!(jq.delete_karma(&manual).await.unwrap());
+        assert!(!jq.delete_karma(&manual).await.unwrap());
+        assert_eq!(jq.fetch_all_karma(10).await.unwrap().len(), 1);
+    }
+
+    // ===== 35. Karma Export / Import =====
+
+    #[tokio::test]
+    async fn test_karma_export_import_round_trip_with_conflicts() {
+        use crate::karma_archive::{KarmaConflict, KarmaImportReport};
+        let (source, _tmp_a) = create_test_queue().await;
+        let id = source.enqueue("Karma Topic", "style", None).await.unwrap();
+        source.store_karma(&id, "comfy_bridge", None, "Use CFG 7.5 for anime", "Technical", "soul-a").await.unwrap();
+        let manual = source.insert_karma(None, "global", None, "Never open with a greeting", "Creative", 40, None).await.unwrap();
+        let exported = source.export_karma().await.unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].soul_version_hash.as_deref(), Some("soul-a"));
+        assert_eq!(exported[0].job_id.as_deref(), Some(id.as_str()));
+
+        // 移行先には元のジョブが無いので job_id は外れる
+        let (target, _tmp_b) = create_test_queue().await;
+        let report = target.import_karma(&exported, KarmaConflict::Skip).await.unwrap();
+        assert_eq!(report, KarmaImportReport { inserted: 2, ..Default::default() });
+        let imported = target.export_karma().await.unwrap();
+        assert_eq!(imported[0].job_id, None);
+        assert_eq!((imported[0].created_at.as_str(), imported[0].weight), (exported[0].created_at.as_str(), 100));
+        assert_eq!(imported[1].lesson, "Never open with a greeting");
+
+        let mut changed = exported.clone();
+        changed[1].weight = 90;
+        changed[1].lesson = "Open with the payoff".to_string();
+        assert_eq!(target.import_karma(&changed, KarmaConflict::Skip).await.unwrap().skipped, 2);
+        assert_eq!(target.import_karma(&changed, KarmaConflict::Merge).await.unwrap().merged, 2);
+        let merged = target.export_karma().await.unwrap();
+        assert_eq!((merged[1].weight, merged[1].lesson.as_str()), (90, "Never open with a greeting"));
+        assert_eq!(target.import_karma(&changed, KarmaConflict::Replace).await.unwrap().replaced, 2);
+        let replaced = target.export_karma().await.unwrap();
+        assert_eq!(replaced.iter().find(|r| r.id == manual).unwrap().lesson, "Open with the payoff");
+
+        // 型の合わない行はパニックせずエラーになる
+        sqlx::query("UPDATE karma_logs SET weight = 50.5 WHERE id = ?").bind(&manual).execute(target.pool_ref()).await.unwrap();
+        assert!(matches!(target.export_karma().await, Err(factory_core::error::FactoryError::Infrastructure { .. })));
+    }
+
+    // ===== 36. Soul Versions =====
+
+    #[tokio::test]
+    async fn test_soul_versions_and_legacy_karma_rebase() {
+        use crate::soul_history::soul_hash;
+        let (jq, _tmp) = create_test_queue().await;
+        let (v1, v2) = ("# Soul\n- Be creative.", "# Soul\n- Be bold.");
+        assert_eq!(jq.record_soul_version("SOUL.md", v1).await.unwrap(), None);
+        assert_eq!(jq.record_soul_version("SOUL.md", v1).await.unwrap(), None);
+        jq.insert_karma(None, "global", None, "Try wild ideas", "Creative", 80, Some(soul_hash(v1).as_str())).await.unwrap();
+        let stale = jq.insert_karma(None, "global", None, "Be polite", "Creative", 60, Some("predates history")).await.unwrap();
+        jq.record_soul_version("OTHER.md", "# Other").await.unwrap();
+        jq.insert_karma(None, "global", None, "Other channel lesson", "Creative", 70, Some(soul_hash("# Other").as_str())).await.unwrap();
+
+        assert_eq!(jq.record_soul_version("SOUL.md", v2).await.unwrap(), Some(soul_hash(v1)));
+        let history = jq.fetch_soul_versions(Some("SOUL.md"), 10).await.unwrap();
+        assert_eq!(history.iter().map(|v| v.content.as_str()).collect::<Vec<_>>(), vec![v2, v1]);
+        assert_eq!(history[1].karma_count, 1);
+        let found = jq.fetch_soul_version(&soul_hash(v1).trim()[..6]).await.unwrap().unwrap();
+        assert_eq!(found.content, v1);
+
+        // 他の SOUL の教訓は含まず、版の記録より前の教訓は含む
+        let legacy = jq.fetch_legacy_karma("SOUL.md", &soul_hash(v2), 10).await.unwrap();
+        assert_eq!(legacy.iter().map(|k| k.lesson.as_str()).collect::<Vec<_>>(), vec!["Try wild ideas", "Be polite"]);
+
+        jq.rebase_karma(&legacy[0].id, Some("Try bold ideas"), &soul_hash(v2)).await.unwrap();
+        jq.retire_karma(&stale).await.unwrap();
+        assert!(jq.fetch_legacy_karma("SOUL.md", &soul_hash(v2), 10).await.unwrap().is_empty());
+        let injected = jq.fetch_relevant_karma("topic", "global", None, 10, &soul_hash(v2)).await.unwrap();
+        assert!(injected.contains(&"Try bold ideas".to_string()));
+        assert!(!injected.iter().any(|k| k.contains("Be polite")));
+        // 引退した教訓は蒸留し直さない
+        let raw = jq.fetch_raw_karma_for_skill("global", None).await.unwrap();
+        assert_eq!(raw.len(), 2);
+        assert!(!raw.iter().any(|(id, _)| id == &stale));
+        assert!(jq.fetch_skills_for_distillation(2).await.unwrap().is_empty());
+        assert_eq!(jq.fetch_skills_for_distillation(1).await.unwrap(), vec![("global".to_string(), None)]);
+
+        // 以前の版に戻すと、その版が改めて最新になる
+        assert_eq!(jq.record_soul_version("SOUL.md", v1).await.unwrap(), Some(soul_hash(v2)));
+        assert_eq!(jq.fetch_soul_versions(Some("SOUL.md"), 1).await.unwrap()[0].content, v1);
+    }
+
+    // ===== 37. Karma Review =====
+
+    #[tokio::test]
+    async fn test_distilled_karma_waits_for_review() {
+        let (jq, _tmp) = create_test_queue().await;
+        let mut ids = Vec::new();
+        for lesson in ["Hook early", "Hook in one second", "Open with the payoff"] {
+            ids.push(jq.insert_karma(None, "script", None, lesson, "Technical", 100, Some("h")).await.unwrap());
+        }
+        assert_eq!(jq.fetch_skills_for_distillation(2).await.unwrap(), vec![("script".to_string(), None)]);
+
+        // レビュー待ちの間は元の教訓が効き続け、同じスキルを蒸留し直さない
+        let pending = jq.propose_distilled_karma("script", None, "Deliver the payoff within a second", &ids, "h").await.unwrap().to_string();
+        assert!(jq.fetch_skills_for_distillation(2).await.unwrap().is_empty());
+        assert_eq!(jq.fetch_raw_karma_for_skill("script", None).await.unwrap().len(), 3);
+        let injected = jq.fetch_relevant_karma("topic", "script", None, 10, "h").await.unwrap();
+        assert!(injected.contains(&"Hook early".to_string()));
+        assert!(!injected.iter().any(|k| k.contains("Deliver the payoff")));
+
+        // 承認すると元の教訓と入れ替わる
+        assert!(jq.resolve_karma_review(&pending, true).await.unwrap());
+        assert!(!jq.resolve_karma_review(&pending, true).await.unwrap());
+        let active = jq.fetch_raw_karma_for_skill("script", None).await.unwrap();
+        assert_eq!(active, vec![(pending.clone(), "Deliver the payoff within a second".to_string())]);
+        let injected = jq.fetch_relevant_karma("topic", "script", None, 10, "h").await.unwrap();
+        assert_eq!(injected, vec!["Deliver the payoff within a second".to_string()]);
+
+        // 却下すると蒸留だけが消える
+        let rejected = jq.propose_distilled_karma("script", None, "Never hook", std::slice::from_ref(&pending), "h").await.unwrap().to_string();
+        assert!(jq.resolve_karma_review(&rejected, false).await.unwrap());
+        assert_eq!(jq.fetch_raw_karma_for_skill("script", None).await.unwrap().len(), 1);
+        assert!(!jq.delete_karma(&rejected).await.unwrap());
+    }
+
+    // ===== 38. Karma Namespaces =====
+
+    #[tokio::test]
+    async fn test_karma_namespaces_keep_styles_apart() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Namespace Test", "horror", None).await.unwrap();
+        jq.store_karma(&id, "script", Some("horror"), "Linger on the jump scare", "Technical", "h").await.unwrap();
+        jq.store_karma(&id, "script", Some("kids_tech"), "Explain with toys", "Technical", "h").await.unwrap();
+        jq.store_karma(&id, "script", Some(" "), "Keep it under a minute", "Technical", "h").await.unwrap();
+
+        // 名前空間を渡すと、別の名前空間の教訓は混ざらない (共通の教訓は混ざる)
+        let kids = jq.fetch_relevant_karma("topic", "script", Some("kids_tech"), 10, "h").await.unwrap();
+        assert_eq!(kids.len(), 2);
+        assert!(kids.contains(&"Explain with toys".to_string()));
+        assert!(kids.contains(&"Keep it under a minute".to_string()));
+        assert_eq!(jq.fetch_relevant_karma("topic", "script", None, 10, "h").await.unwrap().len(), 3);
+
+        // 蒸留は名前空間ごとに数える
+        for lesson in ["Slow reveal", "Silence before the scare"] {
+            jq.store_karma(&id, "script", Some("horror"), lesson, "Technical", "h").await.unwrap();
+        }
+        assert_eq!(jq.fetch_skills_for_distillation(2).await.unwrap(), vec![("script".to_string(), Some("horror".to_string()))]);
+        assert_eq!(jq.fetch_raw_karma_for_skill("script", Some("horror")).await.unwrap().len(), 3);
+        assert_eq!(jq.fetch_raw_karma_for_skill("script", None).await.unwrap().len(), 1);
+
+        let exported = jq.export_karma().await.unwrap();
+        assert_eq!(exported.iter().filter(|k| k.namespace.as_deref() == Some("horror")).count(), 3);
+        assert_eq!(exported.iter().filter(|k| k.namespace.is_none()).count(), 1);
+    }
+
+    // ===== 39. Final Verdict Karma =====
+
+    #[tokio::test]
+    async fn test_final_verdict_writes_style_karma() {
+        let (jq, _tmp) = create_test_queue().await;
+        let id = jq.enqueue("Haunted lighthouse", "horror", None).await.unwrap();
+        jq.record_sns_metrics(&id, "youtube", 30, 1000, 100, 3, Some("boo")).await.unwrap();
+        let record = jq.fetch_pending_evaluations(10).await.unwrap().remove(0);
+        let verdict = factory_core::contracts::OracleVerdict {
+            topic_score: 0.8,
+            visual_score: 0.4,
+            soul_score: 0.4,
+            reasoning: "Clickbait scares".to_string(),
+            judges: Vec::new(),
+            disagreement: false,
+            cloud_failed: false,
+        };
+        jq.apply_final_verdict(record.id, verdict, "h").await.unwrap();
+        assert!(jq.fetch_pending_evaluations(10).await.unwrap().is_empty());
+
+        // 魂の汚染の戒めと、エンゲージメントから重みを決めた教訓の 2 件がスタイルの名前空間に入る
+        let karma = jq.export_karma().await.unwrap();
+        assert_eq!(karma.len(), 2);
+        assert!(karma.iter().all(|k| k.namespace.as_deref() == Some("horror") && k.related_skill == "horror"));
+        let learned = karma.iter().find(|k| k.karma_type == "Creative").unwrap();
+        assert_eq!(learned.weight, 62);
+        assert!(learned.lesson.contains("Haunted lighthouse"));
+        assert!(jq.fetch_relevant_karma("kids", "script", Some("kids_tech"), 10, "h").await.unwrap().is_empty());
+    }
+
+    // ===== 40. Active Job Listing =====
+
+    #[tokio::test]
+    async fn test_active_jobs_page_with_stage() {
+        let (jq, _tmp) = create_test_queue().await;
+        let running = jq.enqueue("Running", "hype", None).await.unwrap();
+        let _ = jq.dequeue().await.unwrap();
+        jq.begin_job_stage(&running, "concept").await.unwrap();
+        jq.begin_job_stage(&running, "voice").await.unwrap();
+        let second = jq.enqueue("Second", "hype", None).await.unwrap();
+        let third = jq.enqueue("Third", "cinematic", None).await.unwrap();
+        let failed = jq.enqueue("Broken", "hype", None).await.unwrap();
+        jq.fail_job(&failed, "boom").await.unwrap();
+
+        // 実行中が先頭、失敗ジョブは数えない
+        let (page, total) = jq.fetch_active_jobs(2, 0).await.unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(jq.count_pending_jobs().await.unwrap(), 2);
+        assert_eq!(page.iter().map(|j| j.job_id.as_str()).collect::<Vec<_>>(), vec![running.as_str(), second.as_str()]);
+        assert_eq!((page[0].status.as_str(), page[0].stage.as_deref()), ("Processing", Some("voice")));
+        assert_eq!((page[1].status.as_str(), page[1].stage.as_deref()), ("Pending", None));
+
+        let (page, _) = jq.fetch_active_jobs(2, 2).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!((page[0].job_id.as_str(), page[0].style.as_str()), (third.as_str(), "cinematic"));
+    }
+
+    // ===== 41. Production Report =====
+
+    #[tokio::test]
+    async fn test_production_report_counts_and_top_videos() {
+        let (jq, _tmp) = create_test_queue().await;
+        let empty = jq.fetch_production_report(1, 3).await.unwrap();
+        assert_eq!((empty.completed, empty.failed, empty.failure_rate()), (0, 0, None));
+        assert!(empty.avg_render_secs.is_none() && empty.top_videos.is_empty());
+
+        let hit = jq.enqueue("Hit", "hype", None).await.unwrap();
+        let _ = jq.dequeue().await.unwrap();
+        jq.complete_job(&hit, None).await.unwrap();
+        let quiet = jq.enqueue("Quiet", "hype", None).await.unwrap();
+        let _ = jq.dequeue().await.unwrap();
+        jq.complete_job(&quiet, None).await.unwrap();
+        let broken = jq.enqueue("Broken", "hype", None).await.unwrap();
+        jq.fail_job(&broken, "boom").await.unwrap();
+        let cancelled = jq.enqueue("Cancelled", "hype", None).await.unwrap();
+        jq.cancel_job(&cancelled).await.unwrap();
+        jq.store_karma(&hit, "hype", None, "Cut the intro", "Technical", "h").await.unwrap();
+
+        jq.link_sns_data(&hit, "youtube", "abc").await.unwrap();
+        jq.record_sns_metrics(&hit, "youtube", 1, 500, 40, 2, None).await.unwrap();
+        jq.record_sns_metrics(&hit, "youtube", 7, 1500, 90, 5, None).await.unwrap();
+        jq.record_sns_metrics(&quiet, "youtube", 1, 20, 1, 0, None).await.unwrap();
+
+        // 取り消しは失敗に数えない。上位動画は期間中の最大値で並ぶ
+        let report = jq.fetch_production_report(1, 3).await.unwrap();
+        assert_eq!((report.days, report.completed, report.failed, report.karma_gained), (1, 2, 1, 1));
+        assert!((report.failure_rate().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert!(report.avg_render_secs.unwrap() >= 0.0);
+        assert_eq!(report.top_videos.iter().map(|v| v.job_id.as_str()).collect::<Vec<_>>(), vec![hit.as_str(), quiet.as_str()]);
+        assert_eq!((report.top_videos[0].views, report.top_videos[0].likes), (1500, 90));
+        assert_eq!(report.top_videos[0].video_url.as_deref(), Some("https://youtube.com/shorts/abc"));
+        assert_eq!(report.top_videos[1].video_url, None);
+        assert_eq!(jq.fetch_production_report(1, 1).await.unwrap().top_videos.len(), 1);
+    }
+
+    // ===== 42. Pause Switch =====
+
+    #[tokio::test]
+    async fn test_pause_switch_reports_state_changes() {
+        let (jq, _tmp) = create_test_queue().await;
+        assert_eq!(jq.paused_since().await.unwrap(), None);
+        assert!(!jq.set_paused(false).await.unwrap());
+
+        assert!(jq.set_paused(true).await.unwrap());
+        let since = jq.paused_since().await.unwrap().expect("paused");
+        assert!(chrono::DateTime::parse_from_rfc3339(&since).is_ok());
+        // 二度目の停止は何も変えない (停止時刻も動かない)
+        assert!(!jq.set_paused(true).await.unwrap());
+        assert_eq!(jq.paused_since().await.unwrap(), Some(since));
+
+        assert!(jq.set_paused(false).await.unwrap());
+        assert_eq!(jq.paused_since().await.unwrap(), None);
+
+        // 同時の停止はどちらか一方だけが切り替えたことになる
+        let (a, b) = tokio::join!(jq.set_paused(true), jq.set_paused(true));
+        assert!(a.unwrap() ^ b.unwrap());
+    }
+
+    // ===== 43. Cron Run History =====
+
+    #[tokio::test]
+    async fn test_cron_runs_record_outcomes_newest_first() {
+        use factory_core::api::CronOutcome;
+        let (jq, _tmp) = create_test_queue().await;
+        let samsara = jq.begin_cron_run("samsara").await.unwrap();
+        jq.finish_cron_run(samsara, CronOutcome::Ok, Some("1 job(s) enqueued")).await.unwrap();
+        let sentinel = jq.begin_cron_run("sentinel").await.unwrap();
+        jq.finish_cron_run(sentinel, CronOutcome::Skipped, Some("circuit breaker open")).await.unwrap();
+        let running = jq.begin_cron_run("samsara").await.unwrap();
+
+        let all = jq.fetch_cron_runs(None, 10).await.unwrap();
+        assert_eq!(all.iter().map(|r| r.id).collect::<Vec<_>>(), vec![running, sentinel, samsara]);
+        assert_eq!((all[0].outcome.as_str(), all[0].finished_at.as_deref()), ("running", None));
+        assert_eq!((all[1].outcome.as_str(), all[1].detail.as_deref()), ("skipped", Some("circuit breaker open")));
+        assert!(all[2].finished_at.is_some());
+
+        let samsara_runs = jq.fetch_cron_runs(Some("samsara"), 1).await.unwrap();
+        assert_eq!(samsara_runs.len(), 1);
+        assert_eq!(samsara_runs[0].id, running);
+
+        // 古い履歴だけを消す
+        let forty_days_ago = (chrono::Utc::now() - chrono::Duration::days(40)).to_rfc3339();
+        sqlx::query("UPDATE cron_runs SET started_at = ? WHERE id = ?")
+            .bind(&forty_days_ago).bind(samsara).execute(jq.pool_ref()).await.unwrap();
+        assert_eq!(jq.purge_old_cron_runs(30).await.unwrap(), 1);
+        assert_eq!(jq.fetch_cron_runs(None, 10).await.unwrap().len(), 2);
+    }
+
+    // ===== 44. Cron Catch-up =====
+
+    #[tokio::test]
+    async fn test_last_cron_success_ignores_skipped_failed_and_running() {
+        use factory_core::api::CronOutcome;
+        let (jq, _tmp) = create_test_queue().await;
+        assert_eq!(jq.last_cron_success("samsara").await.unwrap(), None);
+
+        let ok = jq.begin_cron_run("samsara").await.unwrap();
+        jq.finish_cron_run(ok, CronOutcome::Ok, None).await.unwrap();
+        let skipped = jq.begin_cron_run("samsara").await.unwrap();
+        jq.finish_cron_run(skipped, CronOutcome::Skipped, Some("paused")).await.unwrap();
+        jq.begin_cron_run("samsara").await.unwrap();
+        let other = jq.begin_cron_run("oracle").await.unwrap();
+        jq.finish_cron_run(other, CronOutcome::Ok, None).await.unwrap();
+
+        let started = jq.fetch_cron_runs(Some("samsara"), 10).await.unwrap()
+            .into_iter().find(|r| r.id == ok).unwrap().started_at;
+        assert_eq!(jq.last_cron_success("samsara").await.unwrap(), Some(started));
+
+        // 一部のチャンネルだけ失敗した回も実行済みとして扱う
+        let partial = jq.begin_cron_run("samsara").await.unwrap();
+        jq.finish_cron_run(partial, CronOutcome::Partial, Some("1 job(s) enqueued; kids: quota")).await.unwrap();
+        let failed = jq.begin_cron_run("samsara").await.unwrap();
+        jq.finish_cron_run(failed, CronOutcome::Failed, Some("0 job(s) enqueued")).await.unwrap();
+        let started = jq.fetch_cron_runs(Some("samsara"), 10).await.unwrap()
+            .into_iter().find(|r| r.id == partial).unwrap().started_at;
+        assert_eq!(jq.last_cron_success("samsara").await.unwrap(), Some(started));
+    }
 }
@@ -0,0 +1,125 @@
+//! # Karma Archive — 教訓のマシン間移行
+//!
+//! `shorts-factory karma export` が karma_logs を JSON に書き出し、`karma import` が別マシンの DB に戻す。
+//! 重み・Soul ハッシュ・作成 / 適用時刻はそのまま運ぶ。埋め込みはモデル依存なので運ばず、
+//! 取り込んだ側の `backfill_karma_embeddings` が作り直す。
+//! 元のジョブは移行先に無いことが多いので、`job_id` は移行先に同じジョブがあるときだけ残す。
+
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 書き出す形式の版 (読めない版は取り込まない)
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// karma_logs の 1 行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KarmaRecord {
+    pub id: String,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    pub karma_type: String,
+    pub related_skill: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    pub lesson: String,
+    pub weight: i64,
+    #[serde(default)]
+    pub soul_version_hash: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_applied_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KarmaArchive {
+    pub version: u32,
+    pub exported_at: String,
+    pub entries: Vec<KarmaRecord>,
+}
+
+impl KarmaArchive {
+    pub fn new(entries: Vec<KarmaRecord>) -> Self {
+        Self { version: ARCHIVE_VERSION, exported_at: chrono::Utc::now().to_rfc3339(), entries }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FactoryError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to serialize karma: {}", e) })?;
+        std::fs::write(path, json).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write {}: {}", path.display(), e) })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FactoryError> {
+        let text = std::fs::read_to_string(path).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read {}: {}", path.display(), e) })?;
+        let archive: Self = serde_json::from_str(&text).map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse {}: {}", path.display(), e) })?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(FactoryError::Infrastructure {
+                reason: format!("{} is a version {} karma archive; this build reads up to version {}", path.display(), archive.version, ARCHIVE_VERSION),
+            });
+        }
+        Ok(archive)
+    }
+}
+
+/// 取り込み先に同じ ID の教訓があったときの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KarmaConflict {
+    /// 取り込み先を残す
+    #[default]
+    Skip,
+    /// 書き出した内容で置き換える
+    Replace,
+    /// 取り込み先の本文を残し、重みは大きい方・最終適用は新しい方を採る
+    Merge,
+}
+
+impl std::str::FromStr for KarmaConflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "replace" => Ok(Self::Replace),
+            "merge" => Ok(Self::Merge),
+            other => Err(format!("Unknown conflict mode '{}' (expected skip, replace or merge)", other)),
+        }
+    }
+}
+
+/// 取り込みの結果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KarmaImportReport {
+    pub inserted: usize,
+    pub replaced: usize,
+    pub merged: usize,
+    pub skipped: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip_and_version_guard() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("karma.json");
+        let record = KarmaRecord {
+            id: "k-1".into(),
+            job_id: None,
+            karma_type: "Creative".into(),
+            related_skill: "global".into(),
+            namespace: Some("horror_v1".into()),
+            lesson: "Hook in the first second".into(),
+            weight: 80,
+            soul_version_hash: Some("abc".into()),
+            created_at: "2025-01-03T07:00:00+00:00".into(),
+            last_applied_at: None,
+        };
+        KarmaArchive::new(vec![record.clone()]).save(&path).unwrap();
+        assert_eq!(KarmaArchive::load(&path).unwrap().entries, vec![record]);
+
+        std::fs::write(&path, r#"{"version": 99, "exported_at": "", "entries": []}"#).unwrap();
+        assert!(KarmaArchive::load(&path).is_err());
+        assert_eq!("Merge".parse::<KarmaConflict>(), Ok(KarmaConflict::Merge));
+        assert!("overwrite".parse::<KarmaConflict>().is_err());
+    }
+}
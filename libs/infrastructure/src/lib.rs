@@ -7,12 +7,35 @@ pub mod comfy_bridge;
 pub mod concept_manager;
 pub mod factory_log;
 pub mod media_forge;
+pub mod bitrate_selector;
+pub mod video_encoder;
+pub mod ffmpeg_progress;
+pub mod subject_tracker;
 pub mod trend_sonar;
+pub mod trend_sources;
 pub mod voice_actor;
+pub mod forced_aligner;
 pub mod sound_mixer;
+pub mod bgm_manifest;
 pub mod job_queue;
 mod job_queue_tests;
 pub mod workspace_manager;
 mod workspace_manager_tests;
 pub mod sns_watcher;
 pub mod oracle;
+pub mod youtube_publisher;
+pub mod vision_judge;
+pub mod prompt_linter;
+pub mod aesthetic_scorer;
+pub mod image_upscaler;
+pub mod safety_classifier;
+pub mod embedder;
+pub mod watch_folder;
+pub mod script_template;
+pub mod artifact_store;
+pub mod workflow_doctor;
+pub mod chaos;
+pub mod topic_policy;
+pub mod karma_archive;
+pub mod soul_history;
+pub mod evolution_scenario;
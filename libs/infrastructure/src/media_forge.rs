@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use bastion::fs_guard::Jail;
-use factory_core::contracts::{MediaRequest, MediaResponse};
+use factory_core::contracts::{AudioChapter, MediaRequest, MediaResponse, SceneTransition};
 use factory_core::error::FactoryError;
 use factory_core::traits::{AgentAct, MediaEditor};
 use rig::tool::Tool;
@@ -10,79 +10,1072 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::Command;
-use tracing::info;
+use tracing::{info, warn};
+use crate::bitrate_selector::{parse_vmaf_score, BitrateSelector, EncodeSample, SourceStats};
+use crate::video_encoder::EncoderProfile;
+use crate::ffmpeg_progress::{progress_command, run_with_progress, FfmpegProgress};
+use shared::config::{EncodingConfig, ExportFit, ExportProfile};
+use tuning::{CaptionPosition, DualCaptionLayout, SubtitlePosition, SubtitleTheme};
+
+/// SRT 字幕 (libass の既定 PlayResY=288) の座標を 1080x1920 の ASS 座標へ換算する倍率
+const SRT_TO_ASS_SCALE: f32 = 1920.0 / 288.0;
+/// 字幕 1 行の高さ / 文字サイズ
+const CAPTION_LINE_HEIGHT: f32 = 1.25;
+/// プレビューの音声ビットレート (kbps)
+const PREVIEW_AUDIO_KBPS: u32 = 64;
+/// これを下回る映像ビットレートのプレビューは見るに堪えないので作らない (kbps)
+const PREVIEW_MIN_VIDEO_KBPS: u32 = 200;
 
 /// FFmpeg を使用した動画編集クライアント
 #[derive(Clone)]
 pub struct MediaForgeClient {
     /// 作業用の Jail
     pub jail: Arc<Jail>,
+    /// 最終エンコードの適応ビットレート (None なら固定 6000 kbps)
+    pub bitrate: Option<Arc<BitrateSelector>>,
+    /// 映像エンコーダー (既定は videotoolbox)
+    pub encoder: Arc<EncoderProfile>,
+    /// ffmpeg の進捗の配信先 (None なら捨てる)
+    pub progress: Option<tokio::sync::broadcast::Sender<FfmpegProgress>>,
 }
 
 impl MediaForgeClient {
     pub fn new(jail: Arc<Jail>) -> Self {
-        Self { jail }
+        Self { jail, bitrate: None, encoder: Arc::new(EncoderProfile::default()), progress: None }
+    }
+
+    /// 長い ffmpeg 処理の進捗と残り時間を `tx` へ流す (テレメトリ向け)
+    pub fn with_progress(mut self, tx: tokio::sync::broadcast::Sender<FfmpegProgress>) -> Self {
+        self.progress = Some(tx);
+        self
+    }
+
+    /// 進捗付きで ffmpeg を走らせる (`cmd` は `progress_command()` で作る)
+    async fn run_ffmpeg(&self, cmd: Command, operation: &str, total_secs: Option<f64>) -> std::io::Result<std::process::Output> {
+        run_with_progress(cmd, operation, total_secs, self.progress.as_ref()).await
+    }
+
+    /// 起動時に検出したエンコーダーを使う
+    pub fn with_encoder(mut self, encoder: Arc<EncoderProfile>) -> Self {
+        self.encoder = encoder;
+        self
+    }
+
+    /// `[encoding]` に従って最終エンコードのビットレートを決める
+    pub fn with_encoding(mut self, cfg: EncodingConfig) -> Self {
+        self.bitrate = cfg.adaptive.then(|| Arc::new(BitrateSelector::new(cfg)));
+        self
+    }
+
+    /// fonts.toml の `subtitle_size` (SRT 座標) を 1080x1920 の px に換算する
+    pub fn subtitle_px(subtitle_size: i32) -> u32 {
+        (subtitle_size.max(1) as f32 * SRT_TO_ASS_SCALE).round() as u32
+    }
+
+    /// 二言語字幕の配置を決める: (副字幕の文字サイズ px, 副字幕のマージン px, 主字幕のマージン px)
+    ///
+    /// 主字幕は 1 行を想定し、副字幕をその上か下に `gap` 空けて置く。画面端に近い側の行が `base_margin` に入り、
+    /// もう一方はその分だけ端から離す (上寄せのテーマでは上下が入れ替わる)。
+    pub fn dual_caption_placement(primary_px: u32, base_margin: u32, from_top: bool, layout: &DualCaptionLayout) -> (u32, u32, u32) {
+        let secondary_px = primary_px as f32 * layout.secondary_scale;
+        let primary_first = (layout.position == CaptionPosition::Above) != from_top;
+        if primary_first {
+            let margin = base_margin as f32 + primary_px as f32 * CAPTION_LINE_HEIGHT + layout.gap as f32;
+            (secondary_px.round() as u32, margin.round() as u32, base_margin)
+        } else {
+            let margin = base_margin as f32 + secondary_px * CAPTION_LINE_HEIGHT + layout.gap as f32;
+            (secondary_px.round() as u32, base_margin, margin.round() as u32)
+        }
+    }
+
+    /// 字幕テーマのスタイルで主字幕を書き出す (ASS)。`cues` は (開始秒, 終了秒, テキスト)
+    ///
+    /// `font` / `font_size` はテーマが書体・サイズを指定していないときに使う言語別の値。
+    pub fn build_caption_ass(cues: &[(f32, f32, String)], theme: &SubtitleTheme, font: &str, font_size: u32, margin_v: u32) -> String {
+        let mut ass = format!(
+            "[Script Info]\n\
+             ScriptType: v4.00+\n\
+             PlayResX: 1080\n\
+             PlayResY: 1920\n\
+             ScaledBorderAndShadow: yes\n\
+             WrapStyle: 0\n\
+             \n\
+             [V4+ Styles]\n\
+             Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+             {style}\n\
+             \n\
+             [Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+            style = theme.style_line("Caption", font, font_size, margin_v),
+        );
+        for (start, end, text) in cues {
+            ass.push_str(&format!(
+                "Dialogue: 0,{},{},Caption,,0,0,0,,{}{}\n",
+                format_ass_time(*start),
+                format_ass_time(*end),
+                theme.animation_tags(),
+                escape_ass_text(text)
+            ));
+        }
+        ass
+    }
+
+    /// 二言語字幕の副言語行 (ASS)。`cues` は (開始秒, 終了秒, テキスト)。`position` は主字幕のテーマに揃える
+    pub fn build_secondary_caption_ass(cues: &[(f32, f32, String)], font: &str, font_size: u32, margin_v: u32, position: SubtitlePosition) -> String {
+        let mut ass = format!(
+            "[Script Info]\n\
+             ScriptType: v4.00+\n\
+             PlayResX: 1080\n\
+             PlayResY: 1920\n\
+             WrapStyle: 0\n\
+             \n\
+             [V4+ Styles]\n\
+             Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+             Style: Secondary,{font},{size},&H00E6E6E6,&H00FFFFFF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,4,2,{align},60,60,{margin},1\n\
+             \n\
+             [Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+            font = font,
+            size = font_size,
+            align = if position == SubtitlePosition::Top { 8 } else { 2 },
+            margin = margin_v,
+        );
+        for (start, end, text) in cues {
+            ass.push_str(&format!(
+                "Dialogue: 0,{},{},Secondary,,0,0,0,,{}\n",
+                format_ass_time(*start),
+                format_ass_time(*end),
+                escape_ass_text(text)
+            ));
+        }
+        ass
+    }
+
+    /// オープニング・フック用の ASS 字幕を生成する (0 〜 `duration_secs` 秒)
+    ///
+    /// 1080x1920 座標系の上部 1/3 に配置し、フェードイン + 軽いスケールアップで視線を掴む。
+    pub fn build_title_overlay_ass(title: &str, font: &str, font_size: u32, duration_secs: f32) -> String {
+        let text = escape_ass_text(title);
+        let end = format_ass_time(duration_secs);
+        let fade_out_ms = 300.min((duration_secs * 1000.0) as u32 / 2);
+
+        format!(
+            "[Script Info]\n\
+             ScriptType: v4.00+\n\
+             PlayResX: 1080\n\
+             PlayResY: 1920\n\
+             WrapStyle: 0\n\
+             \n\
+             [V4+ Styles]\n\
+             Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+             Style: Title,{font},{size},&H00FFFFFF,&H00FFFFFF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,6,3,8,60,60,420,1\n\
+             \n\
+             [Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+             Dialogue: 0,0:00:00.00,{end},Title,,0,0,0,,{{\\fad(150,{fade_out})\\fscx85\\fscy85\\t(0,250,\\fscx100\\fscy100)}}{text}\n",
+            font = font,
+            size = font_size,
+            end = end,
+            fade_out = fade_out_ms,
+            text = text,
+        )
+    }
+
+    /// サムネイル用の静止タイトル (ASS)。アニメーションなしで画面上部に大きく置く
+    pub fn build_thumbnail_title_ass(title: &str, font: &str, font_size: u32) -> String {
+        let text = escape_ass_text(title);
+        format!(
+            "[Script Info]\n\
+             ScriptType: v4.00+\n\
+             PlayResX: 1080\n\
+             PlayResY: 1920\n\
+             WrapStyle: 0\n\
+             \n\
+             [V4+ Styles]\n\
+             Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+             Style: Thumb,{font},{size},&H0000F0FF,&H00FFFFFF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,10,4,8,50,50,260,1\n\
+             \n\
+             [Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+             Dialogue: 0,0:00:00.00,9:59:59.99,Thumb,,0,0,0,,{text}\n",
+            font = font,
+            size = font_size,
+            text = text,
+        )
+    }
+
+    /// サムネイル用フィルタグラフ
+    ///
+    /// 全ソースを 1080x1920 に揃えて連結し、FFmpeg の `thumbnail` フィルタで最も代表的な 1 枚を選ぶ。
+    /// 静止画は 1 枚 = 1 フレーム、動画は先頭から `video_window` フレームが候補になる。
+    pub fn build_thumbnail_filter(source_count: usize, video_window: usize, title_ass: Option<&std::path::Path>) -> String {
+        let mut graph = String::new();
+        for i in 0..source_count {
+            graph.push_str(&format!(
+                "[{i}:v]scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920,setsar=1,format=yuv420p[s{i}];"
+            ));
+        }
+        for i in 0..source_count {
+            graph.push_str(&format!("[s{}]", i));
+        }
+        let candidates = if source_count > 1 { source_count } else { video_window.max(1) };
+        graph.push_str(&format!("concat=n={}:v=1:a=0,thumbnail={}", source_count, candidates));
+        if let Some(ass) = title_ass {
+            graph.push_str(&format!(",ass=filename='{}'", escape_filter_path(ass)));
+        }
+        graph.push_str("[thumb]");
+        graph
+    }
+
+    /// 候補素材からベストフレームを選び、タイトルを重ねた JPEG サムネイルを書き出す
+    pub async fn render_thumbnail(
+        &self,
+        sources: &[PathBuf],
+        title_ass: Option<&std::path::Path>,
+        output: &std::path::Path,
+    ) -> Result<PathBuf, FactoryError> {
+        if sources.is_empty() {
+            return Err(FactoryError::MediaNotFound { path: "thumbnail source".to_string() });
+        }
+        info!("🖼️ MediaForge: Selecting thumbnail frame from {} source(s)", sources.len());
+
+        let mut cmd = Command::new("ffmpeg");
+        cmd.arg("-y");
+        for src in sources {
+            cmd.arg("-i").arg(src);
+        }
+        let status = cmd
+            .arg("-filter_complex").arg(Self::build_thumbnail_filter(sources.len(), THUMBNAIL_VIDEO_WINDOW, title_ass))
+            .arg("-map").arg("[thumb]")
+            .arg("-frames:v").arg("1")
+            .arg("-q:v").arg("2")
+            .arg(output)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Thumbnail spawn failed: {}", e) })?;
+
+        if status.success() {
+            Ok(output.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Thumbnail rendering failed for {}", output.display()) })
+        }
+    }
+
+    /// FFMETADATA1 形式のチャプター定義を生成する (TIMEBASE=1/1000)
+    pub fn build_chapter_metadata(title: &str, chapters: &[AudioChapter]) -> String {
+        let mut meta = format!(";FFMETADATA1\ntitle={}\n", escape_ffmetadata(title));
+        for ch in chapters {
+            meta.push_str(&format!(
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+                (ch.start_secs.max(0.0) * 1000.0).round() as u64,
+                (ch.end_secs.max(0.0) * 1000.0).round() as u64,
+                escape_ffmetadata(&ch.title),
+            ));
+        }
+        meta
     }
+
+    /// 完パケ音声をポッドキャスト向けにマスタリングし、チャプター付き MP3 として書き出す
+    ///
+    /// ラウドネスは配信プラットフォームの推奨値 (-16 LUFS) に揃える。
+    pub async fn export_podcast_mp3(
+        &self,
+        audio_path: &std::path::Path,
+        title: &str,
+        chapters: &[AudioChapter],
+        output_path: &std::path::Path,
+    ) -> Result<PathBuf, FactoryError> {
+        info!("🎧 MediaForge: Exporting podcast MP3 ({} chapters) -> {}", chapters.len(), output_path.display());
+
+        let meta_path = output_path.with_extension("ffmeta");
+        std::fs::write(&meta_path, Self::build_chapter_metadata(title, chapters)).map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Failed to write chapter metadata: {}", e),
+        })?;
+
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(audio_path)
+            .arg("-i").arg(&meta_path)
+            .arg("-map").arg("0:a")
+            .arg("-map_metadata").arg("1")
+            .arg("-map_chapters").arg("1")
+            .arg("-af").arg("loudnorm=I=-16:LRA=11:TP=-1.5")
+            .arg("-c:a").arg("libmp3lame")
+            .arg("-b:a").arg("192k")
+            .arg("-id3v2_version").arg("3")
+            .arg(output_path)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Podcast export spawn failed: {}", e) })?;
+
+        std::fs::remove_file(&meta_path).ok();
+
+        if status.success() {
+            Ok(output_path.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: "Podcast MP3 export failed".into() })
+        }
+    }
+}
+
+/// 被写体トラッキングのキーフレーム (`cx` はフレーム幅に対する 0.0〜1.0 の中心位置)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CropKeyframe {
+    pub t: f32,
+    pub cx: f32,
+}
+
+/// クロップ位置の式に埋め込むキーフレーム数の上限 (FFmpeg 式のネスト深度対策)
+const MAX_CROP_KEYFRAMES: usize = 48;
+
+/// 動画ソースからサムネイル候補として見るフレーム数 (30fps で約 10 秒)
+const THUMBNAIL_VIDEO_WINDOW: usize = 300;
+
+impl MediaForgeClient {
+    /// 横長素材を 9:16 に切り出すフィルタを生成する
+    ///
+    /// キーフレームが無ければ中央クロップ。ある場合は指数平滑化した中心位置を
+    /// 区分線形に補間し、カメラワークのようにクロップ窓を追従させる。
+    pub fn build_reframe_filter(keyframes: &[CropKeyframe], smoothing: f32) -> String {
+        let cx_expr = Self::build_cx_expr(keyframes, smoothing);
+        format!(
+            "crop=w='min(iw,ih*9/16)':h='min(ih,iw*16/9)':x='clip(({})*iw-ow/2,0,iw-ow)':y='(ih-oh)/2',scale=1080:1920,setsar=1,fps=30,format=yuv420p",
+            cx_expr
+        )
+    }
+
+    fn build_cx_expr(keyframes: &[CropKeyframe], smoothing: f32) -> String {
+        if keyframes.is_empty() {
+            return "0.5".to_string();
+        }
+
+        // 間引き → 平滑化 (急なパンで酔わないように)
+        let step = keyframes.len().div_ceil(MAX_CROP_KEYFRAMES);
+        let alpha = (1.0 - smoothing).clamp(0.05, 1.0);
+        let mut smoothed: Vec<CropKeyframe> = Vec::new();
+        for kf in keyframes.iter().step_by(step) {
+            let cx = kf.cx.clamp(0.0, 1.0);
+            let cx = match smoothed.last() {
+                Some(prev) => prev.cx + alpha * (cx - prev.cx),
+                None => cx,
+            };
+            smoothed.push(CropKeyframe { t: kf.t.max(0.0), cx });
+        }
+
+        // 末尾から if(lt(t,T), 補間, 残り) を組み立てる
+        let last = smoothed[smoothed.len() - 1];
+        let mut expr = format!("{:.4}", last.cx);
+        for pair in smoothed.windows(2).rev() {
+            let (a, b) = (pair[0], pair[1]);
+            let span = (b.t - a.t).max(0.001);
+            expr = format!(
+                "if(lt(t,{:.3}),{:.4}+({:.4})*(t-{:.3})/{:.3},{})",
+                b.t, a.cx, b.cx - a.cx, a.t, span, expr
+            );
+        }
+        if smoothed.len() > 1 {
+            expr = format!("if(lt(t,{:.3}),{:.4},{})", smoothed[0].t, smoothed[0].cx, expr);
+        }
+        expr
+    }
+
+    /// クリップ (2 本以上) を xfade / acrossfade でつなぐ filter_complex を生成する。出力は `[vout]` と (音声があれば) `[aout]`
+    ///
+    /// 最後以外のクリップは末尾を `duration` 秒延長 (映像は最終フレームの静止、音声は無音) してから重ねるので、
+    /// 各つなぎ目は元の境界から始まり、合計の尺も境界の位置も変わらない (ナレーション・字幕とずれない)。
+    /// 音声の無いクリップは無音で埋め、音声を持つクリップが 1 本も無ければ音声は作らない。
+    pub fn build_transition_filter(durations: &[f32], has_audio: &[bool], transition: &SceneTransition) -> String {
+        let n = durations.len();
+        // 一番短いクリップより長くは重ねられない
+        let d = transition.duration.min(durations.iter().copied().fold(f32::MAX, f32::min)).max(0.0);
+        let len = |i: usize| if i + 1 < n { durations[i] + d } else { durations[i] };
+        let mut parts = Vec::new();
+
+        for i in 0..n {
+            let pad = if i + 1 < n { format!(",tpad=stop_mode=clone:stop_duration={:.3}", d) } else { String::new() };
+            parts.push(format!("[{}:v]settb=AVTB,fps=30,format=yuv420p{}[v{}]", i, pad, i));
+        }
+        let mut offset = 0.0;
+        for k in 1..n {
+            offset += durations[k - 1];
+            let prev = if k == 1 { "v0".to_string() } else { format!("vx{}", k - 1) };
+            let out = if k + 1 == n { "vout".to_string() } else { format!("vx{}", k) };
+            parts.push(format!("[{}][v{}]xfade=transition={}:duration={:.3}:offset={:.3}[{}]", prev, k, transition.kind, d, offset, out));
+        }
+
+        if has_audio.iter().any(|a| *a) {
+            for i in 0..n {
+                let source = if has_audio.get(i).copied().unwrap_or(false) {
+                    format!("[{}:a]aformat=sample_rates=48000:channel_layouts=stereo,apad=whole_dur={:.3}", i, len(i))
+                } else {
+                    "anullsrc=r=48000:cl=stereo".to_string()
+                };
+                parts.push(format!("{},atrim=duration={:.3}[a{}]", source, len(i), i));
+            }
+            for k in 1..n {
+                let prev = if k == 1 { "a0".to_string() } else { format!("ax{}", k - 1) };
+                let out = if k + 1 == n { "aout".to_string() } else { format!("ax{}", k) };
+                parts.push(format!("[{}][a{}]acrossfade=d={:.3}[{}]", prev, k, d, out));
+            }
+        }
+        parts.join(";")
+    }
+
+    /// 音声ストリームを持つか
+    pub async fn probe_has_audio(&self, path: &std::path::Path) -> bool {
+        Command::new("ffprobe")
+            .arg("-v").arg("error")
+            .arg("-select_streams").arg("a")
+            .arg("-show_entries").arg("stream=index")
+            .arg("-of").arg("csv=p=0")
+            .arg(path)
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .map(|out| !String::from_utf8_lossy(&out.stdout).trim().is_empty())
+            .unwrap_or(false)
+    }
+
+    /// 9:16 の映像を各画角へ分ける filter_complex を生成する (出力は `[out{i}]`)
+    ///
+    /// crop は画面を埋めるように拡大して `focus_y` の位置で切り出し、pad は全体を収めて
+    /// 余白を同じ映像のぼかしで埋める。字幕は画角を決めてから焼くので、どの画角でも切れない。
+    pub fn build_aspect_filter(profiles: &[ExportProfile], burn: &[String]) -> String {
+        let sources: String = (0..profiles.len()).map(|i| format!("[src{}]", i)).collect();
+        let mut parts = vec![format!("[0:v]split={}{}", profiles.len(), sources)];
+        let tail: String = burn.iter().map(|f| format!(",{}", f)).collect();
+        for (i, p) in profiles.iter().enumerate() {
+            let (w, h) = (p.width, p.height);
+            match p.fit {
+                ExportFit::Crop => parts.push(format!(
+                    "[src{}]scale={}:{}:force_original_aspect_ratio=increase,crop={}:{}:(iw-ow)/2:(ih-oh)*{:.3},setsar=1{}[out{}]",
+                    i, w, h, w, h, p.focus_y.clamp(0.0, 1.0), tail, i
+                )),
+                ExportFit::Pad => {
+                    parts.push(format!("[src{}]split[bg{}][fg{}]", i, i, i));
+                    parts.push(format!("[bg{}]scale={}:{}:force_original_aspect_ratio=increase,crop={}:{},boxblur=20:2[blur{}]", i, w, h, w, h, i));
+                    parts.push(format!("[fg{}]scale={}:{}:force_original_aspect_ratio=decrease[fit{}]", i, w, h, i));
+                    parts.push(format!("[blur{}][fit{}]overlay=(W-w)/2:(H-h)/2,setsar=1{}[out{}]", i, i, tail, i));
+                }
+            }
+        }
+        parts.join(";")
+    }
+
+    /// 動画の解像度 (幅, 高さ) を取得する
+    pub async fn probe_dimensions(&self, path: &std::path::Path) -> Result<(u32, u32), FactoryError> {
+        let output = Command::new("ffprobe")
+            .arg("-v").arg("error")
+            .arg("-select_streams").arg("v:0")
+            .arg("-show_entries").arg("stream=width,height")
+            .arg("-of").arg("csv=s=x:p=0")
+            .arg(path)
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("ffprobe spawn failed: {}", e) })?;
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let dims = text.trim().split_once('x').and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+        dims.ok_or_else(|| FactoryError::FfmpegFailed { reason: format!("Could not read dimensions of {}: '{}'", path.display(), text.trim()) })
+    }
+
+    /// 持ち込み素材を 1080x1920 の縦型に変換する (引き伸ばしではなくクロップ)
+    pub async fn reframe_vertical(
+        &self,
+        input: &std::path::Path,
+        keyframes: &[CropKeyframe],
+        smoothing: f32,
+        output: &std::path::Path,
+    ) -> Result<PathBuf, FactoryError> {
+        info!("📐 MediaForge: Reframing {} to 9:16 ({})", input.display(),
+            if keyframes.is_empty() { "center crop".to_string() } else { format!("tracking {} keyframes", keyframes.len()) });
+
+        let mut cmd = progress_command();
+        cmd.arg("-y")
+            .arg("-i").arg(input)
+            .arg("-vf").arg(Self::build_reframe_filter(keyframes, smoothing))
+            .arg("-an")
+            .arg("-c:v").arg("libx264")
+            .arg("-preset").arg("veryfast")
+            .arg(output);
+        let total = self.get_duration(input).await.ok().map(f64::from);
+        let result = self.run_ffmpeg(cmd, "reframe", total)
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Reframe spawn failed: {}", e) })?;
+
+        if result.status.success() {
+            Ok(output.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Reframe failed for {}", input.display()) })
+        }
+    }
+
+    /// 縦型素材から `offset` 秒目以降を `duration` 秒切り出す (素材が短ければループ)
+    pub async fn cut_segment(
+        &self,
+        input: &std::path::Path,
+        offset: f32,
+        duration: f32,
+        output: &std::path::Path,
+    ) -> Result<PathBuf, FactoryError> {
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-stream_loop").arg("-1")
+            .arg("-ss").arg(format!("{:.3}", offset.max(0.0)))
+            .arg("-i").arg(input)
+            .arg("-t").arg(format!("{:.3}", duration))
+            .arg("-an")
+            .arg("-c:v").arg("libx264")
+            .arg("-pix_fmt").arg("yuv420p")
+            .arg(output)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Segment spawn failed: {}", e) })?;
+
+        if status.success() {
+            Ok(output.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Failed to cut {:.2}s+{:.2}s from {}", offset, duration, input.display()) })
+        }
+    }
+
+    /// 生成された短い動画クリップ (AnimateDiff 等) を 1080x1920・30fps に揃え、`duration` 秒に合わせる
+    /// (短ければループ、長ければ切り詰め)
+    pub async fn fit_clip(
+        &self,
+        input: &std::path::Path,
+        duration: f32,
+        output: &std::path::Path,
+    ) -> Result<PathBuf, FactoryError> {
+        info!("🎞️ MediaForge: Fitting generated clip {} to {:.2}s", input.display(), duration);
+        let mut cmd = progress_command();
+        cmd.arg("-y")
+            .arg("-stream_loop").arg("-1")
+            .arg("-i").arg(input)
+            .arg("-t").arg(format!("{:.3}", duration))
+            .arg("-vf").arg("scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920,setsar=1,fps=30,format=yuv420p")
+            .arg("-an")
+            .arg("-c:v").arg("libx264")
+            .arg("-pix_fmt").arg("yuv420p")
+            .arg(output);
+        let result = self.run_ffmpeg(cmd, "fit_clip", Some(duration as f64))
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Clip fit spawn failed: {}", e) })?;
+
+        if result.status.success() {
+            Ok(output.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Failed to fit {} to {:.2}s", input.display(), duration) })
+        }
+    }
+}
+
+impl MediaForgeClient {
+    /// 静止画を `factor` 倍に拡大する (lanczos)。Ken Burns のズームで粗が目立たないように
+    pub async fn upscale_image(&self, input: &std::path::Path, factor: u32, output: &std::path::Path) -> Result<PathBuf, FactoryError> {
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(input)
+            .arg("-vf").arg(format!("scale=iw*{0}:ih*{0}:flags=lanczos", factor))
+            .arg("-frames:v").arg("1")
+            .arg(output)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Upscale spawn failed: {}", e) })?;
+
+        if status.success() {
+            Ok(output.to_path_buf())
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("Failed to upscale {}", input.display()) })
+        }
+    }
+
+    /// `duration_secs` 秒のプレビューを `max_bytes` に収める映像ビットレート (kbps)。
+    /// コンテナ分の 1 割を残し、下限を割る長さなら None
+    pub fn preview_video_kbps(duration_secs: f32, max_bytes: u64) -> Option<u32> {
+        if duration_secs <= 0.0 {
+            return None;
+        }
+        let budget_kbps = (max_bytes as f64 * 8.0 / 1000.0 * 0.9 / duration_secs as f64) as u32;
+        let video_kbps = budget_kbps.saturating_sub(PREVIEW_AUDIO_KBPS);
+        (video_kbps >= PREVIEW_MIN_VIDEO_KBPS).then_some(video_kbps)
+    }
+
+    /// Discord に添付できる大きさ (`max_bytes` 以下) の 540 幅プレビューを書き出す
+    pub async fn render_preview(&self, input: &std::path::Path, max_bytes: u64, output: &std::path::Path) -> Result<PathBuf, FactoryError> {
+        let duration = self.get_duration(input).await?;
+        let kbps = Self::preview_video_kbps(duration, max_bytes).ok_or_else(|| FactoryError::FfmpegFailed {
+            reason: format!("{:.0}s is too long for a {} MB preview", duration, max_bytes / 1_048_576),
+        })?;
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(input)
+            .arg("-vf").arg("scale=540:-2")
+            .arg("-c:v").arg("libx264")
+            .arg("-preset").arg("veryfast")
+            .arg("-b:v").arg(format!("{}k", kbps))
+            .arg("-maxrate").arg(format!("{}k", kbps))
+            .arg("-bufsize").arg(format!("{}k", kbps * 2))
+            .arg("-pix_fmt").arg("yuv420p")
+            .arg("-c:a").arg("aac")
+            .arg("-b:a").arg(format!("{}k", PREVIEW_AUDIO_KBPS))
+            .arg("-movflags").arg("+faststart")
+            .arg(output)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Preview spawn failed: {}", e) })?;
+
+        if !status.success() {
+            return Err(FactoryError::FfmpegFailed { reason: format!("Failed to render a preview of {}", input.display()) });
+        }
+        let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(u64::MAX);
+        if size > max_bytes {
+            return Err(FactoryError::FfmpegFailed { reason: format!("Preview came out at {} bytes (limit {})", size, max_bytes) });
+        }
+        Ok(output.to_path_buf())
+    }
+
+    /// 採点用フレームのタイムスタンプ (先頭・末尾を避けて等間隔)
+    pub fn frame_timestamps(duration_secs: f32, count: usize) -> Vec<f32> {
+        if count == 0 || duration_secs <= 0.0 {
+            return Vec::new();
+        }
+        (0..count)
+            .map(|i| duration_secs * (i as f32 + 1.0) / (count as f32 + 1.0))
+            .collect()
+    }
+
+    /// 動画から `count` 枚の JPEG フレームを切り出す (静止画はそのまま 1 枚として返す)
+    pub async fn extract_frames(
+        &self,
+        input: &std::path::Path,
+        count: usize,
+        out_dir: &std::path::Path,
+    ) -> Result<Vec<PathBuf>, FactoryError> {
+        let is_still = input.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "webp"));
+        if is_still {
+            return Ok(vec![input.to_path_buf()]);
+        }
+
+        std::fs::create_dir_all(out_dir).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+        let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+        let duration = self.get_duration(input).await?;
+        let mut frames = Vec::new();
+        for (i, t) in Self::frame_timestamps(duration, count).into_iter().enumerate() {
+            let frame = out_dir.join(format!("{}_frame_{}.jpg", stem, i));
+            let status = Command::new("ffmpeg")
+                .arg("-y")
+                .arg("-ss").arg(format!("{:.3}", t))
+                .arg("-i").arg(input)
+                .arg("-frames:v").arg("1")
+                .arg("-q:v").arg("3")
+                .arg(&frame)
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await
+                .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Frame extraction spawn failed: {}", e) })?;
+            if !status.success() {
+                return Err(FactoryError::FfmpegFailed { reason: format!("Failed to extract frame at {:.2}s from {}", t, input.display()) });
+            }
+            frames.push(frame);
+        }
+        Ok(frames)
+    }
+
+    /// 静止画 (動画なら先頭フレーム) の知覚ハッシュ (dHash)。9x8 のグレースケールに縮めて ffmpeg から受け取る
+    pub async fn perceptual_hash(&self, input: &std::path::Path) -> Result<u64, FactoryError> {
+        let output = Command::new("ffmpeg")
+            .arg("-v").arg("error")
+            .arg("-i").arg(input)
+            .arg("-frames:v").arg("1")
+            .arg("-vf").arg("scale=9:8:flags=area,format=gray")
+            .arg("-f").arg("rawvideo")
+            .arg("-")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Perceptual hash spawn failed: {}", e) })?;
+        if !output.status.success() {
+            return Err(FactoryError::FfmpegFailed { reason: format!("Failed to downscale {}: {}", input.display(), String::from_utf8_lossy(&output.stderr)) });
+        }
+        factory_core::fingerprint::dhash(&output.stdout).ok_or_else(|| FactoryError::FfmpegFailed {
+            reason: format!("Unexpected thumbnail size for {}: {} bytes", input.display(), output.stdout.len()),
+        })
+    }
+}
+
+/// FFMETADATA の特殊文字 (=, ;, #, \, 改行) をエスケープする
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '=' | ';' | '#' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\\n"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// ASS のオーバーライドタグとして解釈される文字を無害化する
+fn escape_ass_text(text: &str) -> String {
+    text.replace('\\', "＼")
+        .replace('{', "(")
+        .replace('}', ")")
+        .replace('\n', "\\N")
+}
+
+/// ASS 形式のタイムスタンプ (H:MM:SS.cc)
+fn format_ass_time(secs: f32) -> String {
+    let total_cs = (secs.max(0.0) * 100.0).round() as u32;
+    let cs = total_cs % 100;
+    let total_secs = total_cs / 100;
+    format!("{}:{:02}:{:02}.{:02}", total_secs / 3600, (total_secs % 3600) / 60, total_secs % 60, cs)
+}
+
+/// 焼き込み字幕の `subtitles` フィルタ (`force_style` は既定のスタイルに後から足して上書きする)
+///
+/// ASS は字幕テーマのスタイルを持っているので force_style を付けない。
+fn subtitle_filter(sub: &std::path::Path, force_style: Option<&str>) -> String {
+    if sub.extension().is_some_and(|e| e.eq_ignore_ascii_case("ass")) {
+        return format!("subtitles=filename='{}'", escape_filter_path(sub));
+    }
+    // デフォルトスタイル。FontSize=18, MarginV=30 (M4 Pro & Libass coordinate system optimization)
+    // 書体は通常 force_style (言語別の fonts.toml) で上書きされる。ここは文字体系を問わない汎用フォント
+    let default_style = "FontName=Noto Sans,FontSize=18,PrimaryColour=&H00FFFFFF,OutlineColour=&H00000000,BorderStyle=1,Outline=2.0,Shadow=1.0,Alignment=2,MarginV=30";
+    let active_style = match force_style {
+        Some(fs) => format!("{},{}", default_style, fs),
+        None => default_style.to_string(),
+    };
+    format!("subtitles=filename='{}':force_style='{}'", escape_filter_path(sub), active_style)
+}
+
+/// FFmpeg フィルタ引数内のパスをエスケープする
+fn escape_filter_path(path: &std::path::Path) -> String {
+    path.to_string_lossy()
+        .replace("'", "'\\''")
+        .replace(":", "\\:")
+}
+
+impl MediaForgeClient {
+    /// 字幕なしの映像と音声を HLS (`video.m3u8` + `seg_NNN.ts`) に変換する。映像は再エンコードしない
+    ///
+    /// WebVTT と同期させるため、タイムスタンプは 0 起点 (`X-TIMESTAMP-MAP=MPEGTS:0`) に揃える。
+    pub async fn transmux_hls(
+        &self,
+        video: &std::path::Path,
+        audio: &std::path::Path,
+        out_dir: &std::path::Path,
+        segment_secs: u32,
+    ) -> Result<PathBuf, FactoryError> {
+        let playlist = out_dir.join("video.m3u8");
+        let output = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(video)
+            .arg("-i").arg(audio)
+            .arg("-map").arg("0:v:0")
+            .arg("-map").arg("1:a:0")
+            .arg("-c:v").arg("copy")
+            .arg("-c:a").arg("aac")
+            .arg("-b:a").arg("128k")
+            .arg("-shortest")
+            .arg("-muxdelay").arg("0")
+            .arg("-muxpreload").arg("0")
+            .arg("-f").arg("hls")
+            .arg("-hls_time").arg(segment_secs.to_string())
+            .arg("-hls_playlist_type").arg("vod")
+            .arg("-hls_segment_filename").arg(out_dir.join("seg_%03d.ts"))
+            .arg(&playlist)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("HLS spawn failed: {}", e) })?;
+
+        if output.status.success() {
+            Ok(playlist)
+        } else {
+            Err(FactoryError::FfmpegFailed { reason: format!("HLS transmux failed: {}", String::from_utf8_lossy(&output.stderr)) })
+        }
+    }
+
+    /// SRT を HLS 用の WebVTT に変換する (タイムスタンプの `,` を `.` に)
+    pub fn srt_to_vtt(srt: &str) -> String {
+        Self::vtt_with_header(srt, "WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:0,LOCAL:00:00:00.000\n\n")
+    }
+
+    /// SRT を納品・投稿先に添付する素の WebVTT に変換する (HLS のタイムスタンプ対応付けを持たない)
+    pub fn srt_to_webvtt(srt: &str) -> String {
+        Self::vtt_with_header(srt, "WEBVTT\n\n")
+    }
+
+    fn vtt_with_header(srt: &str, header: &str) -> String {
+        let mut vtt = String::from(header);
+        for line in srt.replace("\r\n", "\n").lines() {
+            if line.contains("-->") {
+                vtt.push_str(&line.replace(',', "."));
+            } else {
+                vtt.push_str(line);
+            }
+            vtt.push('\n');
+        }
+        vtt
+    }
+}
+
+/// 適応ビットレート無効時・素材を測れなかったときの映像ビットレート (kbps)
+const FIXED_VIDEO_KBPS: u32 = 6000;
+/// VMAF スポットチェックで比べる長さ (秒)
+const VMAF_SPOT_SECS: f64 = 3.0;
+
+impl MediaForgeClient {
+    /// 素材の解像度・フレームレート・尺・ビットレートを測る
+    pub async fn probe_source_stats(&self, path: &std::path::Path) -> Result<SourceStats, FactoryError> {
+        let output = Command::new("ffprobe")
+            .arg("-v").arg("error")
+            .arg("-select_streams").arg("v:0")
+            .arg("-show_entries").arg("stream=width,height,r_frame_rate,bit_rate:format=duration,bit_rate")
+            .arg("-of").arg("json")
+            .arg(path)
+            .stderr(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("ffprobe spawn failed: {}", e) })?;
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| FactoryError::FfmpegFailed { reason: format!("ffprobe returned invalid JSON for {}: {}", path.display(), e) })?;
+        parse_source_stats(&json)
+            .ok_or_else(|| FactoryError::FfmpegFailed { reason: format!("Could not read stream info of {}", path.display()) })
+    }
+
+    /// 最終エンコードの映像ビットレートを決める (測れなければ固定値)
+    async fn choose_bitrate(&self, video: &std::path::Path) -> (u32, Option<SourceStats>) {
+        let Some(selector) = &self.bitrate else { return (FIXED_VIDEO_KBPS, None) };
+        match self.probe_source_stats(video).await {
+            Ok(stats) => {
+                let kbps = selector.select(stats.width, stats.height, &stats);
+                info!("🎚️ MediaForge: {}x{} {:.1}s complexity {:.2} -> {} kbps", stats.width, stats.height, stats.duration_secs, stats.complexity(), kbps);
+                (kbps, Some(stats))
+            }
+            Err(e) => {
+                warn!("⚠️ MediaForge: adaptive bitrate unavailable ({}), using {} kbps", e, FIXED_VIDEO_KBPS);
+                (FIXED_VIDEO_KBPS, None)
+            }
+        }
+    }
+
+    /// 字幕なしマスターから `[export]` の画角を 1 回の ffmpeg でまとめて書き出す (字幕・タイトルは画角ごとに焼き直す)。
+    /// 戻り値は `targets` と同じ順の出力パス
+    pub async fn render_aspect_variants(
+        &self,
+        video: &std::path::Path,
+        audio: &std::path::Path,
+        subtitle: Option<&std::path::Path>,
+        force_style: Option<&str>,
+        overlay: Option<&std::path::Path>,
+        targets: &[(ExportProfile, PathBuf)],
+    ) -> Result<Vec<PathBuf>, FactoryError> {
+        if targets.is_empty() {
+            return Ok(Vec::new());
+        }
+        info!("🖼️ MediaForge: Rendering {} aspect variant(s): {}", targets.len(),
+            targets.iter().map(|(p, _)| format!("{} {}", p.name, p.aspect())).collect::<Vec<_>>().join(", "));
+
+        let mut burn = Vec::new();
+        if let Some(sub) = subtitle {
+            burn.push(subtitle_filter(sub, force_style));
+        }
+        if let Some(ov) = overlay {
+            burn.push(format!("ass=filename='{}'", escape_filter_path(ov)));
+        }
+        burn.extend(self.encoder.video_filter(None));
+        let profiles: Vec<ExportProfile> = targets.iter().map(|(p, _)| p.clone()).collect();
+
+        let mut cmd = progress_command();
+        cmd.arg("-y")
+           .args(self.encoder.input_args())
+           .arg("-i").arg(video)
+           .arg("-i").arg(audio)
+           .arg("-filter_complex").arg(Self::build_aspect_filter(&profiles, &burn));
+        // 適応ビットレートは画角ごとの解像度で選び直す
+        let stats = match &self.bitrate {
+            Some(_) => self.probe_source_stats(video).await.ok(),
+            None => None,
+        };
+        for (i, (profile, output)) in targets.iter().enumerate() {
+            let kbps = match (&self.bitrate, &stats) {
+                (Some(selector), Some(stats)) => selector.select(profile.width, profile.height, stats),
+                _ => ((FIXED_VIDEO_KBPS as f64 * profile.width as f64 * profile.height as f64 / (1080.0 * 1920.0)).round() as u32).max(1000),
+            };
+            cmd.arg("-map").arg(format!("[out{}]", i))
+               .arg("-map").arg("1:a")
+               .args(self.encoder.output_args(self.encoder.final_kbps(kbps)))
+               .arg("-c:a").arg("aac")
+               .arg("-shortest")
+               .arg(output);
+        }
+
+        let total = self.get_duration(video).await.ok().map(f64::from);
+        let result = self.run_ffmpeg(cmd, "aspect_variants", total)
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to spawn ffmpeg: {}", e) })?;
+
+        if result.status.success() {
+            Ok(targets.iter().map(|(_, output)| output.clone()).collect())
+        } else {
+            let err = String::from_utf8_lossy(&result.stderr);
+            Err(FactoryError::Infrastructure { reason: format!("FFmpeg aspect variants failed: {}", err) })
+        }
+    }
+
+    /// 完成動画の中央 数秒を素材と比べた VMAF (libvmaf が無ければ None)
+    pub async fn vmaf_spot_check(&self, output: &std::path::Path, reference: &std::path::Path, duration_secs: f64) -> Option<f64> {
+        let start = ((duration_secs - VMAF_SPOT_SECS) / 2.0).max(0.0);
+        let result = Command::new("ffmpeg")
+            .arg("-ss").arg(format!("{:.2}", start)).arg("-t").arg(VMAF_SPOT_SECS.to_string())
+            .arg("-i").arg(output)
+            .arg("-ss").arg(format!("{:.2}", start)).arg("-t").arg(VMAF_SPOT_SECS.to_string())
+            .arg("-i").arg(reference)
+            .arg("-lavfi").arg("[0:v][1:v]scale2ref=flags=bicubic[dist][ref];[dist][ref]libvmaf")
+            .arg("-f").arg("null").arg("-")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .ok()?;
+        parse_vmaf_score(&String::from_utf8_lossy(&result.stderr))
+    }
+
+    /// 完成動画の容量と (有効なら) VMAF を記録し、次回以降の目標に反映する
+    async fn record_encode(&self, output: &std::path::Path, reference: &std::path::Path, kbps: u32, source: SourceStats) {
+        let Some(selector) = &self.bitrate else { return };
+        let size_mb = std::fs::metadata(output).map(|m| m.len() as f64 / (1024.0 * 1024.0)).unwrap_or(0.0);
+        let cfg = selector.config();
+        if cfg.max_file_mb > 0.0 && size_mb > cfg.max_file_mb {
+            warn!("⚠️ MediaForge: {} is {:.1} MB, over the {:.0} MB platform cap", output.display(), size_mb, cfg.max_file_mb);
+        }
+        let vmaf = if cfg.vmaf_spot_check {
+            self.vmaf_spot_check(output, reference, source.duration_secs).await
+        } else {
+            None
+        };
+        let multiplier = selector.record(EncodeSample {
+            kbps,
+            complexity: source.complexity(),
+            duration_secs: source.duration_secs,
+            size_mb,
+            vmaf,
+        });
+        info!("🎚️ MediaForge: encoded at {} kbps -> {:.1} MB{} (bitrate multiplier {:.2})", kbps, size_mb,
+            vmaf.map(|v| format!(", VMAF {:.1}", v)).unwrap_or_default(), multiplier);
+    }
+}
+
+/// ffprobe の JSON (`-show_entries stream=...:format=...`) から素材の性質を取り出す
+fn parse_source_stats(json: &serde_json::Value) -> Option<SourceStats> {
+    let stream = json.get("streams")?.get(0)?;
+    let format = json.get("format");
+    let number = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok());
+    let width = stream.get("width")?.as_u64()? as u32;
+    let height = stream.get("height")?.as_u64()? as u32;
+    let fps = stream
+        .get("r_frame_rate")
+        .and_then(|v| v.as_str())
+        .and_then(|r| {
+            let (n, d) = r.split_once('/')?;
+            let (n, d) = (n.parse::<f64>().ok()?, d.parse::<f64>().ok()?);
+            (d > 0.0).then(|| n / d)
+        })
+        .unwrap_or(30.0);
+    let duration_secs = number(format.and_then(|f| f.get("duration"))).unwrap_or(0.0);
+    // 連結直後の mp4 はストリーム側の bit_rate が無いことがあるため、コンテナ全体で代用する
+    let bitrate_kbps = number(stream.get("bit_rate"))
+        .or_else(|| number(format.and_then(|f| f.get("bit_rate"))))
+        .unwrap_or(0.0)
+        / 1000.0;
+    Some(SourceStats { width, height, fps, duration_secs, bitrate_kbps })
 }
 
 #[async_trait]
 impl MediaEditor for MediaForgeClient {
     async fn combine_assets(
         &self,
-        video: &std::path::PathBuf,
-        audio: &std::path::PathBuf,
+        video: &std::path::Path,
+        audio: &std::path::Path,
         subtitle: Option<&std::path::PathBuf>,
         force_style: Option<String>,
+        secondary_captions: Option<&std::path::PathBuf>,
+        overlay: Option<&std::path::PathBuf>,
     ) -> Result<std::path::PathBuf, FactoryError> {
         let output = self.jail.root().join("final_output.mp4");
         
-        let mut cmd = Command::new("ffmpeg");
+        let mut cmd = progress_command();
         cmd.arg("-y")
+           .args(self.encoder.input_args())
            .arg("-i").arg(video)
            .arg("-i").arg(audio);
         
+        let mut filters = Vec::new();
+
         // 字幕の焼き込み (Hard-burn) - Grade S Design
         if let Some(sub) = subtitle {
-            let sub_path = sub.to_string_lossy()
-                .replace("'", "'\\''")
-                .replace(":", "\\:");
-            
-            // デフォルトスタイル。FontSize=18, MarginV=30 (M4 Pro & Libass coordinate system optimization)
-            let default_style = "FontName=Hiragino Sans,FontSize=18,PrimaryColour=&H00FFFFFF,OutlineColour=&H00000000,BorderStyle=1,Outline=2.0,Shadow=1.0,Alignment=2,MarginV=30";
-            let active_style = if let Some(fs) = force_style {
-                 // force_style が指定されている場合、デフォルトとマージ or 単体使用
-                 // 今回は簡易的に、FontName等を上書きするためにそのまま使うか、連結するか
-                 format!("{},{}", default_style, fs)
-            } else {
-                 default_style.to_string()
-            };
+            filters.push(subtitle_filter(sub, force_style.as_deref()));
+        }
 
-            let filter = format!(
-                "subtitles=filename='{}':force_style='{}'",
-                sub_path, active_style
-            );
-            cmd.arg("-vf").arg(filter);
+        // 二言語字幕の副言語行 (位置・サイズは ASS 側で決まっている)
+        if let Some(secondary) = secondary_captions {
+            filters.push(format!("ass=filename='{}'", escape_filter_path(secondary)));
+        }
+
+        // オープニング・タイトル (字幕より上のレイヤーに重ねる)
+        if let Some(ov) = overlay {
+            filters.push(format!("ass=filename='{}'", escape_filter_path(ov)));
         }
 
-        // M4 Pro 最適化: Hardware Encoder (h264_videotoolbox) 強制
-        // 再エンコードが必要なため、CPU負荷を下げ速度を数倍に引き上げる
-        cmd.arg("-c:v").arg("h264_videotoolbox")
-           .arg("-b:v").arg("6000k") // ショート動画向けの高ビットレート
-           .arg("-pix_fmt").arg("yuv420p")
+        if let Some(vf) = self.encoder.video_filter((!filters.is_empty()).then(|| filters.join(","))) {
+            cmd.arg("-vf").arg(vf);
+        }
+
+        // 検出したエンコーダー (videotoolbox / NVENC / VAAPI / libx264) で再エンコードする
+        // 解像度・尺・素材の複雑さに応じた適応ビットレートを、エンコーダーごとの係数で補正する
+        let (kbps, source) = self.choose_bitrate(video).await;
+        cmd.args(self.encoder.output_args(self.encoder.final_kbps(kbps)))
            .arg("-c:a").arg("aac")
            .arg("-shortest")
-           .stdin(Stdio::null())
            .arg(&output);
 
-        tracing::info!("MediaForge: Running hardware-accelerated FFmpeg (M4 Pro) with Grade S subtitles...");
-        
-        let output_res = cmd.output()
+        tracing::info!("MediaForge: Running FFmpeg ({}) with Grade S subtitles...", self.encoder.encoder.codec());
+
+        // -shortest なので短い方が出力の長さ
+        let total = match (self.get_duration(video).await, self.get_duration(audio).await) {
+            (Ok(v), Ok(a)) => Some(v.min(a) as f64),
+            (Ok(d), Err(_)) | (Err(_), Ok(d)) => Some(d as f64),
+            _ => None,
+        };
+        let output_res = self.run_ffmpeg(cmd, "final_assembly", total)
            .await
            .map_err(|e| FactoryError::Infrastructure {
             reason: format!("Failed to spawn ffmpeg: {}", e),
         })?;
 
         if output_res.status.success() {
+            if let Some(source) = source {
+                self.record_encode(&output, video, kbps, source).await;
+            }
             Ok(output)
         } else {
             let err = String::from_utf8_lossy(&output_res.stderr);
@@ -92,22 +1085,22 @@ impl MediaEditor for MediaForgeClient {
         }
     }
 
-    async fn resize_for_shorts(&self, input: &std::path::PathBuf) -> Result<std::path::PathBuf, FactoryError> {
+    async fn resize_for_shorts(&self, input: &std::path::Path) -> Result<std::path::PathBuf, FactoryError> {
         let output = self.jail.root().join("resized_shorts.mp4");
         
-        let mut cmd = Command::new("ffmpeg");
+        let filter = self.encoder.video_filter(Some("scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920".to_string()));
+        let mut cmd = progress_command();
         cmd.arg("-y")
+           .args(self.encoder.input_args())
            .arg("-i").arg(input)
-           .arg("-vf").arg("scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920")
-           .arg("-c:v").arg("h264_videotoolbox") // M4 Pro 最適化
-           .arg("-b:v").arg("8000k")
-           .arg("-pix_fmt").arg("yuv420p")
+           .arg("-vf").arg(filter.unwrap_or_default())
+           .args(self.encoder.output_args(self.encoder.intermediate_kbps()))
            .arg("-c:a").arg("copy")
-           .stdin(Stdio::null())
            .arg(&output);
 
-        tracing::info!("MediaForge: Resizing video (Hardware Accelerated)...");
-        let output_res = cmd.output()
+        tracing::info!("MediaForge: Resizing video ({})...", self.encoder.encoder.codec());
+        let total = self.get_duration(input).await.ok().map(f64::from);
+        let output_res = self.run_ffmpeg(cmd, "resize", total)
            .await
            .map_err(|e| FactoryError::Infrastructure {
             reason: format!("Failed to spawn ffmpeg: {}", e),
@@ -158,6 +1151,59 @@ impl MediaEditor for MediaForgeClient {
         }
     }
 
+    /// クリップを xfade / acrossfade でつないで再エンコードする (1 本以下なら通常の結合)
+    async fn concatenate_with_transition(
+        &self,
+        clips: Vec<String>,
+        output_name: String,
+        transition: &SceneTransition,
+    ) -> Result<String, FactoryError> {
+        if clips.len() < 2 {
+            return self.concatenate_clips(clips, output_name).await;
+        }
+        let output = self.jail.root().join(&output_name);
+        info!("🎬 MediaForge: Joining {} clips with '{}' transitions ({:.2}s) -> {}", clips.len(), transition.kind, transition.duration, output.display());
+
+        let mut durations = Vec::new();
+        let mut has_audio = Vec::new();
+        for clip in &clips {
+            let path = std::path::Path::new(clip);
+            durations.push(self.get_duration(path).await?);
+            has_audio.push(self.probe_has_audio(path).await);
+        }
+        let mut graph = Self::build_transition_filter(&durations, &has_audio, transition);
+        let mut video_out = "[vout]";
+        if let Some(upload) = self.encoder.video_filter(None) {
+            graph.push_str(&format!(";[vout]{}[venc]", upload));
+            video_out = "[venc]";
+        }
+
+        let mut cmd = progress_command();
+        cmd.arg("-y").args(self.encoder.input_args());
+        for clip in &clips {
+            cmd.arg("-i").arg(clip);
+        }
+        cmd.arg("-filter_complex").arg(&graph)
+            .arg("-map").arg(video_out);
+        if has_audio.iter().any(|a| *a) {
+            cmd.arg("-map").arg("[aout]").arg("-c:a").arg("aac").arg("-b:a").arg("192k");
+        }
+        cmd.args(self.encoder.output_args(self.encoder.intermediate_kbps()))
+            .arg(&output);
+
+        let total: f32 = durations.iter().sum();
+        let result = self.run_ffmpeg(cmd, "transitions", Some(total as f64))
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("FFmpeg transition join failed: {}", e) })?;
+
+        if result.status.success() {
+            Ok(output.to_string_lossy().to_string())
+        } else {
+            let err = String::from_utf8_lossy(&result.stderr);
+            Err(FactoryError::Infrastructure { reason: format!("FFmpeg transition join failed: {}", err) })
+        }
+    }
+
     async fn get_duration(&self, path: &std::path::Path) -> Result<f32, FactoryError> {
         let output = Command::new("ffprobe")
             .arg("-v").arg("error")
@@ -210,6 +1256,8 @@ impl AgentAct for MediaForgeClient {
             &PathBuf::from(input.audio_path),
             input.subtitle_path.as_ref().map(PathBuf::from).as_ref(),
             input.force_style,
+            input.secondary_subtitle_path.as_ref().map(PathBuf::from).as_ref(),
+            input.overlay_path.as_ref().map(PathBuf::from).as_ref(),
         ).await?;
         Ok(MediaResponse {
             final_path: path.to_string_lossy().to_string(),
@@ -239,6 +1287,8 @@ impl Tool for MediaForgeClient {
                     &PathBuf::from(audio_path),
                     subtitle_path.as_ref().map(PathBuf::from).as_ref(),
                     force_style,
+                    None,
+                    None,
                 ).await?
             }
             MediaForgeArgs::Resize { input_path } => {
@@ -251,3 +1301,174 @@ impl Tool for MediaForgeClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_bitrate_fits_the_limit() {
+        let max = 10 * 1_048_576;
+        let kbps = MediaForgeClient::preview_video_kbps(60.0, max).unwrap();
+        let bytes = (kbps + PREVIEW_AUDIO_KBPS) as f64 * 1000.0 / 8.0 * 60.0;
+        assert!(bytes < max as f64);
+        assert!(kbps > 1000);
+        // 長すぎる・長さ不明ならプレビューを作らない
+        assert_eq!(MediaForgeClient::preview_video_kbps(3600.0, max), None);
+        assert_eq!(MediaForgeClient::preview_video_kbps(0.0, max), None);
+    }
+
+    #[test]
+    fn test_title_overlay_timing_and_escape() {
+        let ass = MediaForgeClient::build_title_overlay_ass("AI {override} Wars", "Inter Bold", 84, 2.0);
+        assert!(ass.contains("PlayResY: 1920"));
+        assert!(ass.contains("Style: Title,Inter Bold,84,"));
+        assert!(ass.contains("Dialogue: 0,0:00:00.00,0:00:02.00,Title"));
+        // ユーザー由来の波括弧はオーバーライドタグとして解釈させない
+        assert!(ass.contains("AI (override) Wars"));
+    }
+
+    #[test]
+    fn test_dual_caption_placement_and_secondary_ass() {
+        let layout = DualCaptionLayout { secondary_lang: "en".into(), secondary_scale: 0.5, position: CaptionPosition::Above, gap: 10 };
+        // 主字幕 18 (SRT) = 120px、下マージン 200px。副字幕 60px をその上に置く
+        assert_eq!(MediaForgeClient::subtitle_px(18), 120);
+        assert_eq!(MediaForgeClient::dual_caption_placement(120, 200, false, &layout), (60, 360, 200));
+        let below = DualCaptionLayout { position: CaptionPosition::Below, ..layout.clone() };
+        // 副字幕が既定の位置に入り、主字幕は 60 * 1.25 + 10 = 85px 持ち上がる
+        assert_eq!(MediaForgeClient::dual_caption_placement(120, 200, false, &below), (60, 200, 285));
+        // 上寄せのテーマでは「上」の副字幕が画面端側に入る
+        assert_eq!(MediaForgeClient::dual_caption_placement(120, 200, true, &layout), (60, 200, 285));
+
+        let cues = vec![(0.0, 1.5, "Hello {world}".to_string()), (1.5, 3.0, "Bye".to_string())];
+        let ass = MediaForgeClient::build_secondary_caption_ass(&cues, "Inter Bold", 60, 360, SubtitlePosition::Bottom);
+        assert!(ass.contains("Style: Secondary,Inter Bold,60,"));
+        assert!(ass.contains(",2,60,60,360,1\n"));
+        assert!(ass.contains("Dialogue: 0,0:00:00.00,0:00:01.50,Secondary,,0,0,0,,Hello (world)\n"));
+        assert!(ass.contains("Dialogue: 0,0:00:01.50,0:00:03.00,Secondary,,0,0,0,,Bye\n"));
+    }
+
+    #[test]
+    fn test_caption_ass_uses_theme_and_skips_force_style() {
+        let theme = SubtitleTheme { animation: tuning::SubtitleAnimation::Fade, ..SubtitleTheme::default() };
+        let cues = vec![(0.0, 1.5, "Hello {world}".to_string())];
+        let ass = MediaForgeClient::build_caption_ass(&cues, &theme, "Inter Bold", 80, 200);
+        assert!(ass.contains("ScaledBorderAndShadow: yes\n"));
+        assert!(ass.contains("Style: Caption,Inter Bold,80,&H00FFFFFF,&H00FFFFFF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,13,7,2,60,60,200,1\n"));
+        assert!(ass.contains("Dialogue: 0,0:00:00.00,0:00:01.50,Caption,,0,0,0,,{\\fad(120,80)}Hello (world)\n"));
+
+        assert_eq!(subtitle_filter(std::path::Path::new("/w/captions.ass"), Some("FontSize=12")), "subtitles=filename='/w/captions.ass'");
+        assert!(subtitle_filter(std::path::Path::new("/w/subtitles.srt"), Some("FontSize=12")).ends_with(",FontSize=12'"));
+    }
+
+    #[test]
+    fn test_parse_source_stats_falls_back_to_container_bitrate() {
+        let json = serde_json::json!({
+            "streams": [{"width": 1080, "height": 1920, "r_frame_rate": "30000/1001"}],
+            "format": {"duration": "31.5", "bit_rate": "5200000"}
+        });
+        let stats = parse_source_stats(&json).unwrap();
+        assert_eq!((stats.width, stats.height), (1080, 1920));
+        assert!((stats.fps - 29.97).abs() < 0.01);
+        assert_eq!(stats.duration_secs, 31.5);
+        assert_eq!(stats.bitrate_kbps, 5200.0);
+        assert!(parse_source_stats(&serde_json::json!({"streams": []})).is_none());
+    }
+
+    #[test]
+    fn test_srt_to_vtt() {
+        let srt = "1\r\n00:00:00,000 --> 00:00:02,500\r\nHello, world\r\n\r\n2\n00:00:02,500 --> 00:00:04,000\nBye\n";
+        let vtt = MediaForgeClient::srt_to_vtt(srt);
+        assert!(vtt.starts_with("WEBVTT\nX-TIMESTAMP-MAP=MPEGTS:0,LOCAL:00:00:00.000\n\n1\n"));
+        assert!(vtt.contains("00:00:00.000 --> 00:00:02.500\nHello, world\n\n2\n00:00:02.500 --> 00:00:04.000\nBye\n"));
+        assert!(MediaForgeClient::srt_to_webvtt(srt).starts_with("WEBVTT\n\n1\n00:00:00.000 --> 00:00:02.500\n"));
+    }
+
+    #[test]
+    fn test_format_ass_time() {
+        assert_eq!(format_ass_time(2.0), "0:00:02.00");
+        assert_eq!(format_ass_time(61.255), "0:01:01.26");
+    }
+
+    #[test]
+    fn test_chapter_metadata() {
+        let chapters = vec![
+            AudioChapter { title: "Intro".into(), start_secs: 0.0, end_secs: 4.25 },
+            AudioChapter { title: "Q=A; #1".into(), start_secs: 4.25, end_secs: 30.0 },
+        ];
+        let meta = MediaForgeClient::build_chapter_metadata("AI Wars", &chapters);
+        assert!(meta.starts_with(";FFMETADATA1\ntitle=AI Wars\n"));
+        assert_eq!(meta.matches("[CHAPTER]").count(), 2);
+        assert!(meta.contains("START=4250\nEND=30000\ntitle=Q\\=A\\; \\#1\n"));
+    }
+
+    #[test]
+    fn test_thumbnail_filter_concats_stills_and_overlays_title() {
+        let graph = MediaForgeClient::build_thumbnail_filter(3, 300, Some(std::path::Path::new("/tmp/p/thumb.ass")));
+        assert!(graph.starts_with("[0:v]scale=1080:1920:force_original_aspect_ratio=increase"));
+        assert!(graph.contains("[s0][s1][s2]concat=n=3:v=1:a=0,thumbnail=3,ass=filename='/tmp/p/thumb.ass'[thumb]"));
+
+        let video = MediaForgeClient::build_thumbnail_filter(1, 300, None);
+        assert!(video.ends_with("concat=n=1:v=1:a=0,thumbnail=300[thumb]"));
+    }
+
+    #[test]
+    fn test_frame_timestamps_avoid_edges() {
+        assert_eq!(MediaForgeClient::frame_timestamps(8.0, 3), vec![2.0, 4.0, 6.0]);
+        assert!(MediaForgeClient::frame_timestamps(0.0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_transition_filter_keeps_boundaries_and_total() {
+        let t = SceneTransition { kind: "dissolve".into(), duration: 0.5 };
+        let graph = MediaForgeClient::build_transition_filter(&[3.0, 4.0, 2.0], &[false, false, false], &t);
+        assert!(graph.contains("[0:v]settb=AVTB,fps=30,format=yuv420p,tpad=stop_mode=clone:stop_duration=0.500[v0]"));
+        assert!(graph.contains("[2:v]settb=AVTB,fps=30,format=yuv420p[v2]"));
+        assert!(graph.contains("[v0][v1]xfade=transition=dissolve:duration=0.500:offset=3.000[vx1]"));
+        assert!(graph.contains("[vx1][v2]xfade=transition=dissolve:duration=0.500:offset=7.000[vout]"));
+        assert!(!graph.contains("acrossfade"));
+
+        // 音声の無いクリップは無音で埋める。重ねる秒数は一番短いクリップまで
+        let graph = MediaForgeClient::build_transition_filter(&[2.0, 0.3], &[true, false], &t);
+        assert!(graph.contains("[0:a]aformat=sample_rates=48000:channel_layouts=stereo,apad=whole_dur=2.300,atrim=duration=2.300[a0]"));
+        assert!(graph.contains("anullsrc=r=48000:cl=stereo,atrim=duration=0.300[a1]"));
+        assert!(graph.contains("[a0][a1]acrossfade=d=0.300[aout]"));
+        assert!(graph.contains("xfade=transition=dissolve:duration=0.300:offset=2.000[vout]"));
+    }
+
+    #[test]
+    fn test_aspect_filter_crops_and_pads_before_burning_captions() {
+        let square = ExportProfile::default();
+        let landscape = ExportProfile { name: "landscape".into(), width: 1920, height: 1080, fit: ExportFit::Pad, focus_y: 0.5 };
+        assert_eq!((square.aspect(), landscape.aspect()), ("1:1".to_string(), "16:9".to_string()));
+
+        let graph = MediaForgeClient::build_aspect_filter(&[square, landscape], &["subtitles=filename='s.srt'".to_string()]);
+        assert!(graph.starts_with("[0:v]split=2[src0][src1];"));
+        assert!(graph.contains("[src0]scale=1080:1080:force_original_aspect_ratio=increase,crop=1080:1080:(iw-ow)/2:(ih-oh)*0.400,setsar=1,subtitles=filename='s.srt'[out0]"));
+        assert!(graph.contains("[fg1]scale=1920:1080:force_original_aspect_ratio=decrease[fit1]"));
+        assert!(graph.contains("[blur1][fit1]overlay=(W-w)/2:(H-h)/2,setsar=1,subtitles=filename='s.srt'[out1]"));
+    }
+
+    #[test]
+    fn test_reframe_filter_center_crop() {
+        let vf = MediaForgeClient::build_reframe_filter(&[], 0.5);
+        assert!(vf.starts_with("crop=w='min(iw,ih*9/16)'"));
+        assert!(vf.contains("x='clip((0.5)*iw-ow/2,0,iw-ow)'"));
+        assert!(vf.contains("scale=1080:1920"));
+    }
+
+    #[test]
+    fn test_reframe_filter_tracking_interpolates() {
+        let kfs = vec![
+            CropKeyframe { t: 0.0, cx: 0.2 },
+            CropKeyframe { t: 2.0, cx: 0.8 },
+        ];
+        // smoothing 0.0 → 生の値をそのまま補間
+        let expr = MediaForgeClient::build_cx_expr(&kfs, 0.0);
+        assert_eq!(expr, "if(lt(t,0.000),0.2000,if(lt(t,2.000),0.2000+(0.6000)*(t-0.000)/2.000,0.8000))");
+
+        // 平滑化すると追従が緩やかになる
+        let smoothed = MediaForgeClient::build_cx_expr(&kfs, 0.5);
+        assert!(smoothed.ends_with(",0.5000))"));
+    }
+}
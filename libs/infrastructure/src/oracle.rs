@@ -1,9 +1,80 @@
 use factory_core::contracts::OracleVerdict;
 use factory_core::error::FactoryError;
+use factory_core::cost::{CostEntry, CostTracker};
 use rig::providers::gemini;
 use rig::client::CompletionClient;
 use rig::completion::Prompt;
-use tracing::info;
+use shared::config::OracleConfig;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 審判の方式 (`[oracle] mode`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleMode {
+    /// Gemini のみ (従来どおり)
+    Gemini,
+    /// Gemini とローカル審判のスコアを平均する
+    Ensemble,
+    /// ローカル審判のみ (クラウドを使わない)
+    Local,
+}
+
+impl std::str::FromStr for OracleMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "gemini" | "" => Ok(Self::Gemini),
+            "ensemble" => Ok(Self::Ensemble),
+            "local" => Ok(Self::Local),
+            other => Err(format!("unknown oracle mode '{}' (gemini, ensemble, local)", other)),
+        }
+    }
+}
+
+/// Ollama (OpenAI 互換 `/v1/chat/completions`) で審判するローカルモデル
+pub struct LocalJudge {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl LocalJudge {
+    /// `base_url` は OpenAI 互換エンドポイント (例: `http://localhost:11434/v1`)
+    pub fn new(base_url: &str, model: &str, timeout: Duration) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            client: reqwest::Client::builder().timeout(timeout).build().unwrap_or_else(|_| reqwest::Client::new()),
+        }
+    }
+
+    async fn prompt(&self, system_prompt: &str, user_prompt: &str) -> Result<String, FactoryError> {
+        let res = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&serde_json::json!({
+                "model": self.model,
+                "messages": [
+                    { "role": "system", "content": system_prompt },
+                    { "role": "user", "content": user_prompt },
+                ],
+                "response_format": { "type": "json_object" },
+                "stream": false,
+            }))
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "Ollama Oracle".into(), reason: e.to_string() })?;
+        if !res.status().is_success() {
+            return Err(FactoryError::from_http_status("Ollama Oracle", res.status().as_u16(), ""));
+        }
+        let body: serde_json::Value = res.json().await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid Ollama Oracle response: {}", e) })?;
+        body.pointer("/choices/0/message/content")
+            .and_then(|c| c.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| FactoryError::Infrastructure { reason: "Ollama Oracle response has no message content".into() })
+    }
+}
 
 /// The Oracle (神託): 
 /// SNSの反響とSoul.mdの美学を天秤にかけ、Aiomeの進化を司る評価エンジン。
@@ -12,6 +83,12 @@ pub struct Oracle {
     api_key: String,
     model_name: String,
     soul_md: String,
+    cost: CostTracker,
+    mode: OracleMode,
+    local: Option<LocalJudge>,
+    /// Gemini の失敗時にローカル審判だけで判定する
+    local_fallback: bool,
+    disagreement_threshold: f64,
 }
 
 impl Oracle {
@@ -19,8 +96,50 @@ impl Oracle {
         Self { 
             api_key: api_key.to_string(), 
             model_name: model_name.to_string(), 
-            soul_md 
+            soul_md,
+            cost: CostTracker::default(),
+            mode: OracleMode::Gemini,
+            local: None,
+            local_fallback: false,
+            disagreement_threshold: 0.5,
+        }
+    }
+
+    /// `[oracle]` のアンサンブル・ローカル審判の設定を反映する (Gemini のモデル名も設定に従う)
+    pub fn with_ensemble(mut self, config: &OracleConfig, ollama_url: &str) -> Self {
+        self.mode = config.mode.parse().unwrap_or_else(|e| {
+            warn!("⚠️ [Oracle] {}. Using Gemini only.", e);
+            OracleMode::Gemini
+        });
+        self.model_name = config.gemini_model.clone();
+        self.local_fallback = config.local_fallback;
+        self.disagreement_threshold = config.disagreement_threshold;
+        if self.mode != OracleMode::Gemini || config.local_fallback {
+            self.local = Some(LocalJudge::new(ollama_url, &config.local_model, Duration::from_secs(config.local_timeout_secs)));
         }
+        self
+    }
+
+    /// 方式を差し替える (サーキットブレーカー作動中にローカルだけで続けるときなど)
+    pub fn with_mode(mut self, mode: OracleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Gemini に問い合わせる方式か
+    pub fn uses_cloud(&self) -> bool {
+        self.mode != OracleMode::Local
+    }
+
+    /// クラウドが使えなくてもローカルで判定できるか
+    pub fn can_judge_locally(&self) -> bool {
+        self.local.is_some() && (self.local_fallback || self.mode != OracleMode::Gemini)
+    }
+
+    /// 評価 1 回分の Gemini 使用量を記録する
+    pub fn with_cost_tracker(mut self, cost: CostTracker) -> Self {
+        self.cost = cost;
+        self
     }
 
     /// 動画の反響を評価し、最終審判（Verdict）を下す。
@@ -34,7 +153,7 @@ impl Oracle {
         likes: i64,
         comments_json: &str,
     ) -> Result<OracleVerdict, FactoryError> {
-        info!("🔮 [Oracle] Evaluating Job ({}d): topic='{}', style='{}' via Gemini-OpenAI Agent", milestone_days, topic, style);
+        info!("🔮 [Oracle] Evaluating Job ({}d): topic='{}', style='{}' ({:?})", milestone_days, topic, style, self.mode);
 
         let system_prompt = format!(
             "あなたは映像制作AI 'Aiome' のための「神託（The Oracle）」です。\n\
@@ -75,30 +194,156 @@ impl Oracle {
             milestone_days, topic, style, views, likes, comments_json
         );
 
+        match self.mode {
+            OracleMode::Gemini => match self.ask_gemini(&system_prompt, &user_prompt).await {
+                Ok(verdict) => Ok(verdict),
+                Err(e) if self.local_fallback && self.local.is_some() => {
+                    warn!("⚠️ [Oracle] Gemini failed ({}). Falling back to the local judge.", e);
+                    let mut verdict = self.ask_local(&system_prompt, &user_prompt).await?;
+                    verdict.cloud_failed = true;
+                    Ok(verdict)
+                }
+                Err(e) => Err(e),
+            },
+            OracleMode::Local => self.ask_local(&system_prompt, &user_prompt).await,
+            OracleMode::Ensemble => {
+                let (cloud, local) = tokio::join!(self.ask_gemini(&system_prompt, &user_prompt), self.ask_local(&system_prompt, &user_prompt));
+                match (cloud, local) {
+                    (Ok(cloud), Ok(local)) => {
+                        let verdict = combine_verdicts(vec![cloud, local], self.disagreement_threshold);
+                        if verdict.disagreement {
+                            warn!("⚖️ [Oracle] Judges disagree on '{}' ({}). Scores were averaged.", topic, verdict.judges.join(" vs "));
+                        }
+                        Ok(verdict)
+                    }
+                    (Ok(cloud), Err(e)) => {
+                        warn!("⚠️ [Oracle] Local judge failed ({}). Using the Gemini verdict alone.", e);
+                        Ok(cloud)
+                    }
+                    (Err(e), Ok(mut local)) => {
+                        warn!("⚠️ [Oracle] Gemini failed ({}). Using the local verdict alone.", e);
+                        local.cloud_failed = true;
+                        Ok(local)
+                    }
+                    (Err(e), Err(_)) => Err(e),
+                }
+            }
+        }
+    }
+
+    async fn ask_gemini(&self, system_prompt: &str, user_prompt: &str) -> Result<OracleVerdict, FactoryError> {
+        crate::chaos::inject(crate::chaos::Fault::GeminiError)?;
         let client: gemini::Client = gemini::Client::new(&self.api_key)
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build Gemini client: {}", e) })?;
 
         // Use Agent pattern: needs CompletionClient trait to be in scope for .agent()
         let agent = client.agent(&self.model_name)
-            .preamble(&system_prompt)
+            .preamble(system_prompt)
             .build();
         
         // Structured Output Contract
+        let sent = format!("{}\n{}", system_prompt, user_prompt);
         let response: String = agent.prompt(user_prompt).await
             .map_err(|e| FactoryError::Infrastructure { reason: format!("Gemini Oracle call failed: {}", e) })?;
+        self.cost.record(CostEntry::llm("gemini", "oracle", &sent, &response)).await;
 
-        // Extract JSON from response
-        let json_str = if let (Some(start), Some(end)) = (response.find('{'), response.rfind('}')) {
-            &response[start..=end]
-        } else {
-            &response
-        };
-
-        let verdict: OracleVerdict = serde_json::from_str(json_str)
-            .map_err(|e| FactoryError::Infrastructure { 
-                reason: format!("Failed to parse OracleVerdict JSON: {}. Raw response: {}", e, response) 
-            })?;
+        let mut verdict = parse_verdict(&response)?;
+        verdict.judges = vec![self.model_name.clone()];
+        Ok(verdict)
+    }
 
+    async fn ask_local(&self, system_prompt: &str, user_prompt: &str) -> Result<OracleVerdict, FactoryError> {
+        let judge = self.local.as_ref().ok_or_else(|| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("No local Oracle judge is configured ([oracle] mode / local_fallback)"),
+        })?;
+        let response = judge.prompt(system_prompt, user_prompt).await?;
+        self.cost.record(CostEntry::llm("ollama", "oracle", &format!("{}\n{}", system_prompt, user_prompt), &response)).await;
+        let mut verdict = parse_verdict(&response)?;
+        verdict.judges = vec![judge.model.clone()];
         Ok(verdict)
     }
 }
+
+/// 応答から JSON 部分を取り出して審判として読む
+fn parse_verdict(response: &str) -> Result<OracleVerdict, FactoryError> {
+    let json_str = if let (Some(start), Some(end)) = (response.find('{'), response.rfind('}')) {
+        &response[start..=end]
+    } else {
+        response
+    };
+    serde_json::from_str(json_str)
+        .map_err(|e| FactoryError::Infrastructure { 
+            reason: format!("Failed to parse OracleVerdict JSON: {}. Raw response: {}", e, response) 
+        })
+}
+
+/// 複数の審判のスコアを平均し、どれかのスコア差が `threshold` を超えていれば割れた印を付ける
+pub fn combine_verdicts(verdicts: Vec<OracleVerdict>, threshold: f64) -> OracleVerdict {
+    let n = verdicts.len().max(1) as f64;
+    let spread = |score: fn(&OracleVerdict) -> f64| {
+        let (min, max) = verdicts.iter().map(score).fold((f64::MAX, f64::MIN), |(lo, hi), x| (lo.min(x), hi.max(x)));
+        max - min
+    };
+    let max_spread = [spread(|v| v.topic_score), spread(|v| v.visual_score), spread(|v| v.soul_score)]
+        .into_iter()
+        .fold(0.0, f64::max);
+    let disagreement = verdicts.len() > 1 && max_spread > threshold;
+    let judges: Vec<String> = verdicts.iter().flat_map(|v| v.judges.clone()).collect();
+    let mut reasoning: Vec<String> = verdicts
+        .iter()
+        .map(|v| format!("[{}] {}", v.judges.join("+"), v.reasoning))
+        .collect();
+    if disagreement {
+        reasoning.insert(0, format!("⚠️ Judges disagree (max score gap {:.2}).", max_spread));
+    }
+    OracleVerdict {
+        topic_score: verdicts.iter().map(|v| v.topic_score).sum::<f64>() / n,
+        visual_score: verdicts.iter().map(|v| v.visual_score).sum::<f64>() / n,
+        soul_score: verdicts.iter().map(|v| v.soul_score).sum::<f64>() / n,
+        reasoning: reasoning.join("\n\n"),
+        judges,
+        disagreement,
+        cloud_failed: verdicts.iter().any(|v| v.cloud_failed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verdict(judge: &str, topic: f64, visual: f64, soul: f64) -> OracleVerdict {
+        let mut v = parse_verdict(&format!(
+            "```json\n{{\"topic_score\": {}, \"visual_score\": {}, \"soul_score\": {}, \"reasoning\": \"{} says so\"}}\n```",
+            topic, visual, soul, judge
+        ))
+        .unwrap();
+        v.judges = vec![judge.to_string()];
+        v
+    }
+
+    #[test]
+    fn test_ensemble_averages_and_flags_disagreement() {
+        let agreed = combine_verdicts(vec![verdict("gemini", 0.6, 0.4, 0.8), verdict("qwen", 0.4, 0.2, 0.6)], 0.5);
+        assert!((agreed.topic_score - 0.5).abs() < 1e-9);
+        assert!((agreed.soul_score - 0.7).abs() < 1e-9);
+        assert!(!agreed.disagreement);
+        assert_eq!(agreed.judges, vec!["gemini", "qwen"]);
+        assert!(agreed.reasoning.contains("[qwen] qwen says so"));
+
+        let split = combine_verdicts(vec![verdict("gemini", 0.9, 0.5, 0.9), verdict("qwen", -0.3, 0.5, 0.8)], 0.5);
+        assert!(split.disagreement);
+        assert!(split.reasoning.starts_with("⚠️ Judges disagree (max score gap 1.20)"));
+    }
+
+    #[test]
+    fn test_modes_parse_and_local_fallback_needs_a_judge() {
+        assert_eq!("Ensemble".parse::<OracleMode>(), Ok(OracleMode::Ensemble));
+        assert!("majority".parse::<OracleMode>().is_err());
+
+        let cloud_only = Oracle::new("key", "gemini-2.5-flash", String::new());
+        assert!(cloud_only.uses_cloud() && !cloud_only.can_judge_locally());
+        let config = OracleConfig { mode: "local".into(), ..Default::default() };
+        let local = Oracle::new("key", "gemini-2.5-flash", String::new()).with_ensemble(&config, "http://localhost:11434/v1");
+        assert!(!local.uses_cloud() && local.can_judge_locally());
+    }
+}
@@ -0,0 +1,203 @@
+//! # PromptLinter — レンダリング前のプロンプト整形
+//!
+//! ConceptManager が生成したプロンプトに品質タグ・拒絶呪文を後付けすると、
+//! タグの重複や矛盾 ("photo" と "anime" の同居など) が起きる。
+//! ComfyUI へ投入する直前にタグ単位で重複除去・矛盾解消・トークン数の上限適用を行う。
+
+use std::collections::HashSet;
+
+/// CLIP の 1 チャンクあたりのトークン数 (77 - BOS/EOS)
+pub const CLIP_CHUNK_TOKENS: usize = 75;
+/// ComfyUI が連結するチャンク数の上限。これを超えると後半のタグはほぼ効かなくなる
+pub const MAX_CLIP_CHUNKS: usize = 3;
+
+/// 同じプロンプト内で共存できないタグの組。先に現れた側を残し、もう一方を捨てる
+const CONFLICT_GROUPS: &[(&[&str], &[&str])] = &[
+    (
+        &["anime", "source anime", "source cartoon", "cartoon", "illustration", "2d", "cel shading"],
+        &["photo", "photorealistic", "realistic", "3d", "source real", "raw photo"],
+    ),
+    (
+        &["rating safe", "sfw"],
+        &["nsfw", "explicit", "rating explicit", "rating questionable"],
+    ),
+    (&["day", "daytime"], &["night", "nighttime"]),
+    (&["simple background"], &["detailed background"]),
+    (&["monochrome", "greyscale"], &["colorful", "vivid colors"]),
+];
+
+/// ポジティブ側に現れても必ずネガティブ側に残す安全系タグ
+const SAFETY_TAGS: &[&str] = &["nsfw", "explicit", "rating explicit", "rating questionable", "gore"];
+
+/// 整形で何が変わったか (ログ用)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintReport {
+    pub duplicates_removed: usize,
+    /// (捨てたタグ, 優先されたタグ)
+    pub conflicts_resolved: Vec<(String, String)>,
+    /// トークン上限で切り捨てたタグ
+    pub truncated: Vec<String>,
+}
+
+impl LintReport {
+    pub fn is_clean(&self) -> bool {
+        self.duplicates_removed == 0 && self.conflicts_resolved.is_empty() && self.truncated.is_empty()
+    }
+}
+
+/// 比較用のタグ正規化: 重み構文 `(tag:1.2)` を外し、小文字化して `_` を空白に寄せる
+fn normalize(tag: &str) -> String {
+    let mut t = tag.trim().trim_start_matches(['(', '[']).trim_end_matches([')', ']']).trim();
+    if let Some((head, weight)) = t.rsplit_once(':') {
+        if weight.trim().parse::<f32>().is_ok() {
+            t = head.trim();
+        }
+    }
+    t.to_lowercase().replace('_', " ")
+}
+
+/// カンマ区切りのタグ列に分解する (空要素は捨てる)
+pub fn tokenize(prompt: &str) -> Vec<String> {
+    prompt
+        .split([',', '\n'])
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// CLIP BPE トークン数の保守的な見積もり。
+/// 英数字の連なりは 7 文字ごとに 1 トークン、記号は 1 文字 1 トークン、区切りのカンマで 1 トークン。
+pub fn estimate_tokens(tag: &str) -> usize {
+    let mut tokens = 1;
+    let mut run = 0usize;
+    for c in tag.chars() {
+        if c.is_alphanumeric() {
+            run += 1;
+        } else {
+            tokens += run.div_ceil(7);
+            run = 0;
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+    }
+    tokens + run.div_ceil(7)
+}
+
+fn conflict_side(key: &str) -> Option<(usize, bool)> {
+    CONFLICT_GROUPS.iter().enumerate().find_map(|(i, (a, b))| {
+        if a.contains(&key) {
+            Some((i, false))
+        } else if b.contains(&key) {
+            Some((i, true))
+        } else {
+            None
+        }
+    })
+}
+
+/// 1 本のプロンプトを整形する。
+/// `protected` のタグ (正規化後で比較) はトークン上限でも切り捨てない。
+/// 矛盾解消はポジティブ側のみ (ネガティブは "anime" と "photo" を両方拒絶してよい)。
+pub fn lint_prompt(prompt: &str, protected: &[&str], resolve_conflicts: bool, max_tokens: usize, report: &mut LintReport) -> Vec<String> {
+    let mut seen = HashSet::new();
+    // 矛盾グループごとに先に採用された側とそのタグ
+    let mut winners: Vec<Option<(bool, String)>> = vec![None; CONFLICT_GROUPS.len()];
+    let mut tags = Vec::new();
+
+    for tag in tokenize(prompt) {
+        let key = normalize(&tag);
+        if !seen.insert(key.clone()) {
+            report.duplicates_removed += 1;
+            continue;
+        }
+        if let Some((group, side)) = conflict_side(&key).filter(|_| resolve_conflicts) {
+            match &winners[group] {
+                Some((won, winner)) if *won != side => {
+                    report.conflicts_resolved.push((tag, winner.clone()));
+                    continue;
+                }
+                Some(_) => {}
+                None => winners[group] = Some((side, tag.clone())),
+            }
+        }
+        tags.push(tag);
+    }
+
+    let protected: HashSet<String> = protected.iter().map(|t| normalize(t)).collect();
+    let mut total: usize = tags.iter().map(|t| estimate_tokens(t)).sum();
+    // 後ろ (優先度の低い側) から削る
+    let mut i = tags.len();
+    while total > max_tokens && i > 0 {
+        i -= 1;
+        if protected.contains(&normalize(&tags[i])) {
+            continue;
+        }
+        let dropped = tags.remove(i);
+        total -= estimate_tokens(&dropped);
+        report.truncated.push(dropped);
+    }
+    tags
+}
+
+/// KSampler の positive/negative の組を整形する。
+/// 両側に現れたタグは、安全系ならポジティブから、それ以外はネガティブから取り除く。
+pub fn lint_pair(positive: &str, negative: &str, protected_negative: &[&str]) -> (String, String, LintReport) {
+    let max_tokens = CLIP_CHUNK_TOKENS * MAX_CLIP_CHUNKS;
+    let mut report = LintReport::default();
+    let mut pos = lint_prompt(positive, &[], true, max_tokens, &mut report);
+    let mut neg = lint_prompt(negative, protected_negative, false, max_tokens, &mut report);
+
+    let neg_keys: HashSet<String> = neg.iter().map(|t| normalize(t)).collect();
+    let safety: HashSet<String> = SAFETY_TAGS.iter().map(|t| normalize(t)).collect();
+    let mut overlapping = HashSet::new();
+    pos.retain(|t| {
+        let key = normalize(t);
+        if !neg_keys.contains(&key) {
+            return true;
+        }
+        if safety.contains(&key) {
+            report.conflicts_resolved.push((t.clone(), format!("negative: {}", t)));
+            false
+        } else {
+            overlapping.insert(key);
+            true
+        }
+    });
+    neg.retain(|t| {
+        let keep = !overlapping.contains(&normalize(t));
+        if !keep {
+            report.conflicts_resolved.push((t.clone(), format!("positive: {}", t)));
+        }
+        keep
+    });
+
+    (pos.join(", "), neg.join(", "), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_pair_dedupes_resolves_conflicts_and_caps_tokens() {
+        let positive = "score_9, source_anime, masterpiece, 1girl, photo, (masterpiece:1.2), Masterpiece, night, day, simple background, nsfw";
+        let negative = "lowres, score_6, simple background, nsfw, photo, anime, lowres";
+        let (pos, neg, report) = lint_pair(positive, negative, &["nsfw"]);
+
+        assert_eq!(pos, "score_9, source_anime, masterpiece, 1girl, night, simple background");
+        assert_eq!(neg, "lowres, score_6, nsfw, photo, anime");
+        assert_eq!(report.duplicates_removed, 3);
+        assert!(report.conflicts_resolved.iter().any(|(dropped, kept)| dropped == "photo" && kept == "source_anime"));
+        assert!(report.conflicts_resolved.iter().any(|(dropped, _)| dropped == "day"));
+
+        // Token cap drops trailing tags but keeps protected ones
+        let long: Vec<String> = (0..120).map(|i| format!("tag{}", i)).collect();
+        let negative = format!("{}, nsfw", long.join(", "));
+        let (_, neg, report) = lint_pair("1girl", &negative, &["nsfw"]);
+        assert!(neg.ends_with("nsfw"));
+        assert!(!report.truncated.is_empty());
+        let total: usize = tokenize(&neg).iter().map(|t| estimate_tokens(t)).sum();
+        assert!(total <= CLIP_CHUNK_TOKENS * MAX_CLIP_CHUNKS);
+    }
+}
@@ -0,0 +1,194 @@
+//! # SafetyClassifier — ComfyUI 出力の NSFW / 暴力表現チェック
+//!
+//! `enforce_pony_quality_and_safety` はプロンプト側の遮断でしかないため、描かれた画像そのものを検査する。
+//! ローカルの分類サイドカー (CLIP ベースの NSFW 分類器等のラッパー) があればそれを使い、
+//! 無ければ Vision LLM にモデレーションさせる。`ComfyBridgeClient` が閾値以上の出力を棄却して描き直す。
+//! 動画出力 (AnimateDiff 等) は 1 秒ごとにフレームを切り出し、最も悪い判定を採る。
+
+use crate::vision_judge::generate_with_images;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::info;
+
+/// 動画出力から切り出して判定するフレームの上限
+const VIDEO_SAMPLE_FRAMES: usize = 3;
+
+enum Backend {
+    /// `POST {url}/classify` に画像パスを渡す
+    Sidecar { url: String },
+    Gemini { api_key: String, model: String },
+}
+
+/// 判定結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SafetyVerdict {
+    /// 0.0 (安全) - 1.0 (明らかに不適切)
+    pub score: f32,
+    /// 検出された種別 (nudity, gore など)
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+impl SafetyVerdict {
+    /// サイドカー / LLM の応答 (コードフェンス付きでも可) から判定を取り出す
+    fn parse(text: &str) -> Result<Self, FactoryError> {
+        let start = text.find('{');
+        let end = text.rfind('}');
+        let json = match (start, end) {
+            (Some(s), Some(e)) if s < e => &text[s..=e],
+            _ => return Err(FactoryError::Infrastructure { reason: format!("Safety classifier returned no JSON: {}", text) }),
+        };
+        let mut verdict: Self = serde_json::from_str(json)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse safety verdict: {}", e) })?;
+        if !verdict.score.is_finite() {
+            return Err(FactoryError::Infrastructure { reason: "Safety score is not a number".to_string() });
+        }
+        verdict.score = verdict.score.clamp(0.0, 1.0);
+        Ok(verdict)
+    }
+}
+
+#[derive(Serialize)]
+struct ClassifyRequest<'a> {
+    path: &'a str,
+}
+
+pub struct SafetyClassifier {
+    backend: Backend,
+    client: reqwest::Client,
+    /// この値以上を不適切として棄却する
+    pub threshold: f32,
+    /// 1 回の生成で描き直す上限 (超えたら生成失敗)
+    pub max_regenerations: u32,
+}
+
+impl SafetyClassifier {
+    /// `classifier_url` が空なら Gemini (`model`) で判定する
+    pub fn new(classifier_url: &str, gemini_api_key: &str, model: &str, threshold: f32, max_regenerations: u32, timeout: Duration) -> Result<Self, FactoryError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build safety classifier HTTP client: {}", e) })?;
+        let backend = if classifier_url.trim().is_empty() {
+            Backend::Gemini { api_key: gemini_api_key.to_string(), model: model.to_string() }
+        } else {
+            Backend::Sidecar { url: classifier_url.trim_end_matches('/').to_string() }
+        };
+        Ok(Self { backend, client, threshold, max_regenerations })
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            Backend::Sidecar { .. } => "sidecar",
+            Backend::Gemini { .. } => "gemini",
+        }
+    }
+
+    /// 判定が棄却ラインを超えているか
+    pub fn is_flagged(&self, verdict: &SafetyVerdict) -> bool {
+        verdict.score >= self.threshold
+    }
+
+    /// 静止画 1 枚を判定する
+    pub async fn classify(&self, image: &Path) -> Result<SafetyVerdict, FactoryError> {
+        let verdict = match &self.backend {
+            Backend::Sidecar { url } => {
+                let path = image.to_string_lossy();
+                let res = self.client
+                    .post(format!("{}/classify", url))
+                    .json(&ClassifyRequest { path: &path })
+                    .send()
+                    .await
+                    .map_err(|e| FactoryError::Network { service: "Safety classifier".into(), reason: e.to_string() })?;
+                if !res.status().is_success() {
+                    return Err(FactoryError::from_http_status("Safety classifier", res.status().as_u16(), ""));
+                }
+                let text = res.text().await
+                    .map_err(|e| FactoryError::Infrastructure { reason: format!("Invalid safety classifier response: {}", e) })?;
+                SafetyVerdict::parse(&text)?
+            }
+            Backend::Gemini { api_key, model } => {
+                if api_key.is_empty() {
+                    return Err(FactoryError::Infrastructure { reason: "Gemini API Key is missing".to_string() });
+                }
+                let prompt = "You are a content moderator for a general-audience short-video channel.\n\
+                    Check the attached AI-generated image for nudity, sexual content, gore, graphic violence or self-harm.\n\
+                    Respond with JSON only: {\"score\": <0.0 safe - 1.0 clearly unsafe>, \"labels\": [\"nudity\" | \"sexual\" | \"gore\" | \"violence\" | \"self_harm\", ...]}";
+                let text = generate_with_images(&self.client, api_key, model, prompt, &[PathBuf::from(image)]).await?;
+                SafetyVerdict::parse(&text)?
+            }
+        };
+        info!("🛡️ SafetyClassifier ({}): {:.2} {:?} for {}", self.backend_name(), verdict.score, verdict.labels, image.display());
+        Ok(verdict)
+    }
+
+    /// 動画から切り出したフレームを 1 枚ずつ判定し、最も高いスコアと全ラベルを返す
+    pub async fn classify_video(&self, video: &Path) -> Result<SafetyVerdict, FactoryError> {
+        let stem = video.file_stem().and_then(|s| s.to_str()).unwrap_or("output");
+        let out_dir = std::env::temp_dir().join(format!("safety_{}_{}", stem, std::process::id()));
+        let verdict = self.classify_frames(video, &out_dir).await;
+        let _ = std::fs::remove_dir_all(&out_dir);
+        verdict
+    }
+
+    async fn classify_frames(&self, video: &Path, out_dir: &Path) -> Result<SafetyVerdict, FactoryError> {
+        let mut worst = SafetyVerdict::default();
+        for frame in sample_frames(video, out_dir).await? {
+            let verdict = self.classify(&frame).await?;
+            worst.score = worst.score.max(verdict.score);
+            for label in verdict.labels {
+                if !worst.labels.contains(&label) {
+                    worst.labels.push(label);
+                }
+            }
+        }
+        Ok(worst)
+    }
+}
+
+/// 動画から 1 秒ごとに最大 `VIDEO_SAMPLE_FRAMES` 枚の JPEG を切り出す
+async fn sample_frames(video: &Path, out_dir: &Path) -> Result<Vec<PathBuf>, FactoryError> {
+    std::fs::create_dir_all(out_dir).map_err(|e| FactoryError::Infrastructure { reason: e.to_string() })?;
+    let status = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i").arg(video)
+        .arg("-vf").arg("fps=1")
+        .arg("-frames:v").arg(VIDEO_SAMPLE_FRAMES.to_string())
+        .arg("-q:v").arg("3")
+        .arg(out_dir.join("frame_%d.jpg"))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map_err(|e| FactoryError::FfmpegFailed { reason: format!("Frame extraction spawn failed: {}", e) })?;
+    let frames: Vec<PathBuf> = (1..=VIDEO_SAMPLE_FRAMES)
+        .map(|i| out_dir.join(format!("frame_{}.jpg", i)))
+        .filter(|f| f.exists())
+        .collect();
+    if !status.success() || frames.is_empty() {
+        return Err(FactoryError::FfmpegFailed { reason: format!("Failed to extract frames for the safety check from {}", video.display()) });
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verdict_and_threshold() {
+        let verdict = SafetyVerdict::parse("```json\n{\"score\": 1.7, \"labels\": [\"gore\"]}\n```").unwrap();
+        assert_eq!(verdict, SafetyVerdict { score: 1.0, labels: vec!["gore".into()] });
+        assert_eq!(SafetyVerdict::parse("{\"score\": 0.1}").unwrap().labels, Vec::<String>::new());
+        assert!(SafetyVerdict::parse("I cannot help with that").is_err());
+
+        let classifier = SafetyClassifier::new("http://127.0.0.1:5061/", "", "", 0.7, 2, Duration::from_secs(5)).unwrap();
+        assert_eq!(classifier.backend_name(), "sidecar");
+        assert!(classifier.is_flagged(&SafetyVerdict { score: 0.7, labels: vec![] }));
+        assert!(!classifier.is_flagged(&SafetyVerdict { score: 0.69, labels: vec![] }));
+    }
+}
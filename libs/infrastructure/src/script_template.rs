@@ -0,0 +1,360 @@
+//! # Script Templates — LLM を使わない定型台本
+//!
+//! 「今日のトップ 3 ヘッドライン」のような決まった型の動画は、`templates.toml` に書いた
+//! 手書きの雛形にトレンド項目を差し込むだけで台本を組み立てる。Gemini を呼ばないので
+//! 速く・安く・毎回同じ形になり、センシティブな話題が並ぶ日の安全な逃げ道にもなる。
+//!
+//! ```toml
+//! [top3_headlines]
+//! style = "documentary"
+//! items = 3
+//! common_style = "clean broadcast studio, soft key light"
+//! bgm_mood = "upbeat"
+//! visual_prompts = ["newsroom wall of screens about {topic}", "...", "..."]
+//!
+//! [top3_headlines.lang.en]
+//! title = "Top {count} {topic} headlines ({date})"
+//! intro = "Here are today's top {count} {topic} headlines."
+//! item = "Number {rank}. {keyword}."
+//! outro = "That's the roundup. Follow for tomorrow's list."
+//! ```
+//!
+//! 差し込み枠: `{topic}` `{category}` `{date}` `{count}`、項目ごとに `{rank}` `{keyword}` `{source}`。
+//! `common_style` と `visual_prompts` ではタイトル `{title}` も使える。
+//! `script_*` を省略した言語は表示用テキストをそのまま読み上げる。
+//! 差し込むトレンド項目が無いとき (`items = 0` や項目の無い日) は、本編に固定文の `body` を使う。
+
+use factory_core::contracts::{ConceptRequest, ConceptResponse, LocalizedScript};
+use factory_core::error::FactoryError;
+use factory_core::traits::TrendItem;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// The Ethical Circuit Breaker (Samsara のプロンプト) と同じ区分の、現実の悲劇を示す語。
+/// `[templates] sensitive_keywords` に加えて常に照らす
+pub const ETHICAL_CIRCUIT_BREAKER_KEYWORDS: &[&str] = &[
+    "earthquake", "tsunami", "hurricane", "typhoon", "wildfire", "flooding", "eruption",
+    "fatal", "killed", "casualties", "massacre", "shooting", "terror attack", "bombing", "airstrike", "invasion",
+    "pandemic", "outbreak",
+    "地震", "津波", "台風", "豪雨", "洪水", "噴火", "死亡", "死者", "犠牲", "殺人", "テロ", "戦争", "空爆", "侵攻", "感染拡大",
+];
+use std::path::Path;
+
+/// 1 言語ぶんの雛形
+#[derive(Debug, Clone, Deserialize)]
+pub struct LangTemplate {
+    pub title: String,
+    pub intro: String,
+    /// トレンド項目 1 件ぶん (本編は項目数だけ繰り返して連結する)
+    #[serde(default)]
+    pub item: String,
+    /// 差し込む項目が無いときの本編
+    #[serde(default)]
+    pub body: String,
+    pub outro: String,
+    #[serde(default)]
+    pub script_intro: Option<String>,
+    #[serde(default)]
+    pub script_item: Option<String>,
+    #[serde(default)]
+    pub script_body: Option<String>,
+    #[serde(default)]
+    pub script_outro: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptTemplate {
+    /// 使う演出スタイル (styles.toml のキー)
+    pub style: String,
+    /// 差し込むトレンド項目の数
+    #[serde(default = "default_items")]
+    pub items: usize,
+    #[serde(default)]
+    pub common_style: String,
+    /// BGM のムード (`bgm_manifest.toml` のタグ。空ならカテゴリで選曲)
+    #[serde(default)]
+    pub bgm_mood: String,
+    /// Intro / Body / Outro の 3 シーン分
+    pub visual_prompts: Vec<String>,
+    /// 言語コード -> 雛形 (先頭の言語が互換フィールドに入る。`ja` があれば ja を優先)
+    pub lang: BTreeMap<String, LangTemplate>,
+}
+
+fn default_items() -> usize {
+    3
+}
+
+/// 名前付きテンプレートの集合
+#[derive(Debug, Clone, Default)]
+pub struct ScriptTemplates {
+    templates: BTreeMap<String, ScriptTemplate>,
+}
+
+impl ScriptTemplates {
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, FactoryError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to read {}: {}", path.display(), e),
+        })?;
+        Self::parse(&content).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to parse {}: {}", path.display(), e),
+        })
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let templates: BTreeMap<String, ScriptTemplate> = toml::from_str(content).map_err(|e| e.to_string())?;
+        for (name, t) in &templates {
+            if t.lang.is_empty() {
+                return Err(format!("template '{}' has no [lang.*] section", name));
+            }
+            if t.visual_prompts.len() != 3 {
+                return Err(format!("template '{}' needs exactly 3 visual_prompts (intro, body, outro), got {}", name, t.visual_prompts.len()));
+            }
+            if let Some(lang) = t.lang.iter().find(|(_, l)| l.item.trim().is_empty() && l.body.trim().is_empty()).map(|(k, _)| k) {
+                return Err(format!("template '{}' [lang.{}] needs an item or a body", name, lang));
+            }
+            if t.items > 0 {
+                if let Some(lang) = t.lang.iter().find(|(_, l)| l.item.trim().is_empty()).map(|(k, _)| k) {
+                    return Err(format!("template '{}' [lang.{}] needs an item for its {} trend item(s)", name, lang, t.items));
+                }
+            }
+        }
+        Ok(Self { templates })
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.templates.keys().cloned().collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
+    /// トレンド項目を差し込んでコンセプトを組み立てる (`date` は `{date}` に入る文字列)
+    pub fn render(&self, name: &str, req: &ConceptRequest, date: &str) -> Result<ConceptResponse, FactoryError> {
+        let template = self.templates.get(name).ok_or_else(|| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Unknown script template '{}' (available: {})", name, self.names().join(", ")),
+        })?;
+
+        let items: Vec<&TrendItem> = req.trend_items.iter().take(template.items).collect();
+        let mut vars = BTreeMap::from([
+            ("topic", req.topic.clone()),
+            ("category", req.category.clone()),
+            ("date", date.to_string()),
+            ("count", items.len().to_string()),
+        ]);
+
+        let scripts: Vec<LocalizedScript> = template
+            .lang
+            .iter()
+            .map(|(lang, t)| {
+                let body = |item_template: &str, fixed: &str| {
+                    if items.is_empty() {
+                        return fill(fixed, &vars);
+                    }
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            let mut item_vars = vars.clone();
+                            item_vars.insert("rank", (i + 1).to_string());
+                            item_vars.insert("keyword", item.keyword.clone());
+                            item_vars.insert("source", item.source.clone());
+                            fill(item_template, &item_vars)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                LocalizedScript {
+                    lang: lang.clone(),
+                    display_intro: fill(&t.intro, &vars),
+                    display_body: body(&t.item, &t.body),
+                    display_outro: fill(&t.outro, &vars),
+                    script_intro: fill(t.script_intro.as_deref().unwrap_or(&t.intro), &vars),
+                    script_body: body(t.script_item.as_deref().unwrap_or(&t.item), t.script_body.as_deref().unwrap_or(&t.body)),
+                    script_outro: fill(t.script_outro.as_deref().unwrap_or(&t.outro), &vars),
+                }
+            })
+            .collect();
+        // 本編が空だと読み上げで失敗するので、ここで止める
+        if let Some(script) = scripts.iter().find(|s| s.script_body.trim().is_empty()) {
+            return Err(FactoryError::ConfigLoad {
+                source: anyhow::anyhow!("Script template '{}' rendered an empty body for '{}' (no trend items and no `body`)", name, script.lang),
+            });
+        }
+
+        // 互換フィールド (単一言語の利用側) は ConceptManager と同じく ja を優先する
+        let primary_lang = if template.lang.contains_key("ja") { "ja" } else { scripts[0].lang.as_str() };
+        let primary = scripts.iter().find(|s| s.lang == primary_lang).cloned().unwrap_or_else(|| scripts[0].clone());
+        let title = fill(&template.lang[primary_lang].title, &vars);
+        vars.insert("title", title.clone());
+
+        let mut metadata = HashMap::new();
+        metadata.insert("script_template".to_string(), name.to_string());
+
+        Ok(ConceptResponse {
+            title,
+            display_intro: primary.display_intro,
+            display_body: primary.display_body,
+            display_outro: primary.display_outro,
+            script_intro: primary.script_intro,
+            script_body: primary.script_body,
+            script_outro: primary.script_outro,
+            scripts,
+            common_style: fill(&template.common_style, &vars),
+            style_profile: template.style.clone(),
+            visual_prompts: template.visual_prompts.iter().map(|p| fill(p, &vars)).collect(),
+            scene_workflows: Vec::new(),
+            metadata,
+            // 定型台本はタイトル案を作らない (A/B の集計対象外)
+            title_variants: Vec::new(),
+            bgm_mood: template.bgm_mood.clone(),
+            sfx_cues: Vec::new(),
+        })
+    }
+}
+
+/// トレンド項目にセンシティブなキーワード (`ETHICAL_CIRCUIT_BREAKER_KEYWORDS` と `keywords`) が含まれていれば、
+/// 最初に見つかったものを返す
+pub fn find_sensitive<'a>(items: &[TrendItem], keywords: &'a [String]) -> Option<&'a str> {
+    ETHICAL_CIRCUIT_BREAKER_KEYWORDS
+        .iter()
+        .copied()
+        .chain(keywords.iter().map(String::as_str))
+        .filter(|k| !k.trim().is_empty())
+        .find(|k| {
+            let needle = k.to_lowercase();
+            items.iter().any(|item| item.keyword.to_lowercase().contains(&needle))
+        })
+}
+
+/// `{name}` を 1 回の走査で置き換える (未知の枠はそのまま残し、差し込んだ値の中の `{...}` は置き換えない)
+fn fill(template: &str, vars: &BTreeMap<&str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        match tail.find('}').and_then(|end| vars.get(&tail[1..end]).map(|value| (end, value))) {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"
+[top3_headlines]
+style = "documentary"
+items = 2
+common_style = "clean broadcast studio"
+visual_prompts = ["newsroom screens about {topic}", "ticker showing {count} stories", "anchor desk at dusk"]
+
+[top3_headlines.lang.en]
+title = "Top {count} {topic} headlines ({date})"
+intro = "Today's top {count} {topic} stories."
+item = "#{rank}: {keyword} ({source})."
+outro = "That's the roundup."
+script_item = "Number {rank}. {keyword}."
+
+[top3_headlines.lang.ja]
+title = "{date} の{topic}トップ{count}"
+intro = "今日の{topic}ニュース、トップ{count}です。"
+item = "第{rank}位、{keyword}。"
+outro = "以上です。"
+"##;
+
+    fn request(keywords: &[&str]) -> ConceptRequest {
+        ConceptRequest {
+            topic: "AI".to_string(),
+            category: "tech".to_string(),
+            trend_items: keywords
+                .iter()
+                .map(|k| TrendItem { keyword: k.to_string(), source: "Brave".to_string(), score: 1.0 })
+                .collect(),
+            available_styles: vec![],
+            available_workflows: vec![],
+            previous_part: None,
+            persona: None,
+            hook_style: None,
+            available_moods: vec![],
+            available_sfx: vec![],
+            target_langs: vec![],
+            cost: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_render_slots_trend_items_per_language() {
+        let templates = ScriptTemplates::parse(SAMPLE).unwrap();
+        let concept = templates.render("top3_headlines", &request(&["GPT-5 ships", "Chip export rules", "ignored"]), "2026-10-16").unwrap();
+
+        assert_eq!(concept.title, "2026-10-16 のAIトップ2");
+        assert_eq!(concept.style_profile, "documentary");
+        assert_eq!(concept.visual_prompts[1], "ticker showing 2 stories");
+        assert_eq!(concept.metadata.get("script_template").map(String::as_str), Some("top3_headlines"));
+
+        let en = concept.scripts.iter().find(|s| s.lang == "en").unwrap();
+        assert_eq!(en.display_body, "#1: GPT-5 ships (Brave). #2: Chip export rules (Brave).");
+        assert_eq!(en.script_body, "Number 1. GPT-5 ships. Number 2. Chip export rules.");
+        assert_eq!(en.script_intro, "Today's top 2 AI stories.");
+        // Legacy fields carry the Japanese script
+        assert_eq!(concept.display_body, "第1位、GPT-5 ships。 第2位、Chip export rules。");
+
+        assert!(templates.render("missing", &request(&[]), "today").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_incomplete_templates_and_flags_sensitive_days() {
+        assert!(ScriptTemplates::parse("[t]\nstyle = \"x\"\nvisual_prompts = [\"a\", \"b\", \"c\"]\nlang = {}\n").is_err());
+        assert!(ScriptTemplates::parse("[t]\nstyle = \"x\"\nvisual_prompts = [\"a\"]\n[t.lang.en]\ntitle = \"\"\nintro = \"\"\nitem = \"\"\noutro = \"\"\n").is_err());
+
+        let keywords = vec!["earthquake".to_string(), " ".to_string()];
+        assert_eq!(find_sensitive(&request(&["Major EARTHQUAKE hits coast"]).trend_items, &keywords), Some("earthquake"));
+        assert_eq!(find_sensitive(&request(&["New GPU launch"]).trend_items, &keywords), None);
+        // The Ethical Circuit Breaker's categories apply even without configured keywords
+        assert_eq!(find_sensitive(&request(&["東北で地震、死者多数"]).trend_items, &[]), Some("地震"));
+        // items > 0 needs an item; items = 0 needs a body
+        assert!(ScriptTemplates::parse("[t]\nstyle = \"x\"\nitems = 0\nvisual_prompts = [\"a\", \"b\", \"c\"]\n[t.lang.en]\ntitle = \"\"\nintro = \"\"\noutro = \"\"\n").is_err());
+        assert!(ScriptTemplates::parse("[t]\nstyle = \"x\"\nvisual_prompts = [\"a\", \"b\", \"c\"]\n[t.lang.en]\ntitle = \"\"\nintro = \"\"\nbody = \"b\"\noutro = \"\"\n").is_err());
+    }
+
+    #[test]
+    fn test_fill_does_not_rescan_inserted_values() {
+        let templates = ScriptTemplates::parse(SAMPLE).unwrap();
+        let concept = templates.render("top3_headlines", &request(&["{date} {rank} leak", "{unknown"]), "2026-10-16").unwrap();
+        let en = concept.scripts.iter().find(|s| s.lang == "en").unwrap();
+        assert_eq!(en.script_body, "Number 1. {date} {rank} leak. Number 2. {unknown.");
+
+        // Without trend items and without a body there is nothing to read
+        assert!(templates.render("top3_headlines", &request(&[]), "2026-10-16").is_err());
+    }
+
+    #[test]
+    fn test_shipped_templates_render_a_spoken_body() {
+        let raw = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../templates.toml")).unwrap();
+        let templates = ScriptTemplates::parse(&raw).unwrap();
+        for name in templates.names() {
+            for req in [request(&[]), request(&["Major earthquake hits coast", "GPU launch"])] {
+                let concept = templates.render(&name, &req, "2026-10-16").unwrap();
+                for script in &concept.scripts {
+                    assert!(!script.script_body.trim().is_empty(), "{} [{}] has an empty body", name, script.lang);
+                    assert!(!script.script_body.contains('{'), "{} [{}] left a slot unfilled", name, script.lang);
+                }
+            }
+        }
+        let calm = templates.render("calm_tech_digest", &request(&["Major earthquake hits coast"]), "2026-10-16").unwrap();
+        assert!(calm.scripts.iter().all(|s| !s.script_body.contains("earthquake")));
+    }
+}
@@ -1,6 +1,10 @@
+use async_trait::async_trait;
 use factory_core::error::FactoryError;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use serde_json::Value;
+use shared::config::ChannelProfile;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
 
 /// SNSから取得されるメトリクス情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,89 +15,165 @@ pub struct SnsMetrics {
     pub comments: Vec<String>,
 }
 
+/// 1 プラットフォーム分のメトリクス取得アダプタ
+#[async_trait]
+pub trait MetricsProvider: Send + Sync {
+    /// 正規化済みのプラットフォーム名 (`sns_metrics_history.platform` に記録される)
+    fn platform(&self) -> &'static str;
+
+    async fn fetch(&self, client: &reqwest::Client, video_id: &str) -> Result<SnsMetrics, FactoryError>;
+}
+
+const MAX_COMMENTS_TO_FETCH: i64 = 100; // Ultimate Production Audit: Top-K Truncation
+
+const INSTAGRAM_GRAPH_URL: &str = "https://graph.facebook.com/v21.0";
+
+/// 投稿時に記録されたプラットフォーム名の揺れ ("YouTube", "reels", "ig" ...) を正規名に揃える
+pub fn canonical_platform(name: &str) -> Option<&'static str> {
+    match name.trim().to_lowercase().as_str() {
+        "youtube" | "yt" | "youtube_shorts" | "shorts" => Some("youtube"),
+        "tiktok" | "tt" => Some("tiktok"),
+        "instagram" | "ig" | "reels" | "instagram_reels" => Some("instagram"),
+        _ => None,
+    }
+}
+
 /// SNSプラットフォームの観測を担当する
+///
+/// 資格情報が設定されたプラットフォームだけアダプタを登録する。
 pub struct SnsWatcher {
-    youtube_api_key: String,
+    client: reqwest::Client,
+    providers: BTreeMap<&'static str, Box<dyn MetricsProvider>>,
 }
 
-const MAX_COMMENTS_TO_FETCH: i64 = 100; // Ultimate Production Audit: Top-K Truncation
+impl Default for SnsWatcher {
+    fn default() -> Self {
+        Self { client: reqwest::Client::new(), providers: BTreeMap::new() }
+    }
+}
 
 impl SnsWatcher {
+    /// YouTube のみを観測する (従来の挙動)
     pub fn new(youtube_api_key: String) -> Self {
-        Self { youtube_api_key }
+        Self::default().with_provider(YouTubeMetrics::new(youtube_api_key))
     }
 
-    /// 動画のメトリクスとコメントを取得する (現在はモック実装、YouTube API等に差し替え可能)
+    /// チャンネル (解決済みプロファイル) の資格情報で観測できるプラットフォームを全て登録する
+    pub fn for_channel(profile: &ChannelProfile) -> Self {
+        let mut watcher = Self::default();
+        if !profile.youtube_api_key.is_empty() {
+            watcher = watcher.with_provider(YouTubeMetrics::new(profile.youtube_api_key.clone()));
+        }
+        if !profile.tiktok_access_token.is_empty() {
+            watcher = watcher.with_provider(TikTokMetrics::new(profile.tiktok_access_token.clone()));
+        }
+        if !profile.instagram_access_token.is_empty() {
+            watcher = watcher.with_provider(InstagramMetrics::new(profile.instagram_access_token.clone()));
+        }
+        watcher
+    }
+
+    pub fn with_provider(mut self, provider: impl MetricsProvider + 'static) -> Self {
+        self.providers.insert(provider.platform(), Box::new(provider));
+        self
+    }
+
+    /// このプラットフォームのメトリクスを取得できるか (未知の名前・資格情報なしは false)
+    pub fn supports(&self, platform: &str) -> bool {
+        canonical_platform(platform).is_some_and(|p| self.providers.contains_key(p))
+    }
+
+    /// 動画のメトリクスとコメントを取得する
     /// Soft-Fail Resilience: 個別の取得失敗は呼び出し側でハンドルする
     pub async fn fetch_metrics(&self, platform: &str, video_id: &str) -> Result<SnsMetrics, FactoryError> {
-        if self.youtube_api_key.is_empty() {
-             return Err(FactoryError::Infrastructure { 
-                 reason: "YouTube API Key is missing".to_string() 
-             });
-        }
+        let canonical = canonical_platform(platform).ok_or_else(|| FactoryError::Infrastructure {
+            reason: format!("Unsupported platform: {}", platform),
+        })?;
+        let provider = self.providers.get(canonical).ok_or_else(|| FactoryError::Infrastructure {
+            reason: format!("No {} credentials configured for metrics", canonical),
+        })?;
+        crate::chaos::inject(crate::chaos::Fault::SnsError)?;
+        provider.fetch(&self.client, video_id).await
+    }
+}
 
-        match platform.to_lowercase().as_str() {
-            "youtube" => self.fetch_youtube_metrics(video_id).await,
-            _ => Err(FactoryError::Infrastructure { 
-                reason: format!("Unsupported platform: {}", platform) 
-            }),
-        }
+/// 非 2xx をエラー本文込みの FactoryError に変換する
+async fn expect_success(resp: reqwest::Response, api: &str) -> Result<Value, FactoryError> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(FactoryError::from_http_status(api, status.as_u16(), &body));
     }
+    resp.json().await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse {} JSON: {}", api, e) })
+}
 
-    async fn fetch_youtube_metrics(&self, video_id: &str) -> Result<SnsMetrics, FactoryError> {
-        info!("📺 [SnsWatcher] Fetching YouTube metrics for {}", video_id);
-        
-        let client = reqwest::Client::new();
+/// 数値・文字列どちらで返ってきても i64 として読む (YouTube は文字列で返す)
+fn count(value: Option<&Value>) -> i64 {
+    match value {
+        Some(Value::Number(n)) => n.as_i64().unwrap_or(0),
+        Some(Value::String(s)) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
 
-        // 1. Fetch Video Statistics
-        let video_url = format!(
-            "https://www.googleapis.com/youtube/v3/videos?part=statistics&id={}&key={}",
-            video_id, self.youtube_api_key
-        );
+// --- YouTube Data API v3 ---
 
-        let vid_resp = client.get(&video_url).send().await
-            .map_err(|e| FactoryError::Infrastructure { reason: format!("YouTube API Error: {}", e) })?;
-
-        if !vid_resp.status().is_success() {
-            let status = vid_resp.status();
-            let body = vid_resp.text().await.unwrap_or_default();
-            return Err(FactoryError::Infrastructure { 
-                reason: format!("YouTube API failed with status {}: {}", status, body) 
-            });
-        }
+pub struct YouTubeMetrics {
+    api_key: String,
+}
 
-        let vid_data: serde_json::Value = vid_resp.json().await
-            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse JSON: {}", e) })?;
+impl YouTubeMetrics {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
 
-        let items = vid_data.get("items")
-            .and_then(|i| i.as_array())
-            .ok_or_else(|| FactoryError::Infrastructure { reason: "Missing items in YouTube response".to_string() })?;
+/// `videos?part=statistics` の応答から (views, likes, comments_count)
+fn parse_youtube_statistics(data: &Value, video_id: &str) -> Result<(i64, i64, i64), FactoryError> {
+    let items = data.get("items")
+        .and_then(|i| i.as_array())
+        .ok_or_else(|| FactoryError::Infrastructure { reason: "Missing items in YouTube response".to_string() })?;
+    let stats = items.first()
+        .ok_or_else(|| FactoryError::Infrastructure { reason: format!("YouTube video {} not found", video_id) })?
+        .get("statistics")
+        .ok_or_else(|| FactoryError::Infrastructure { reason: "Missing statistics in video data".to_string() })?;
+    Ok((count(stats.get("viewCount")), count(stats.get("likeCount")), count(stats.get("commentCount"))))
+}
 
-        if items.is_empty() {
-            return Err(FactoryError::Infrastructure { reason: format!("YouTube video {} not found", video_id) });
-        }
+#[async_trait]
+impl MetricsProvider for YouTubeMetrics {
+    fn platform(&self) -> &'static str {
+        "youtube"
+    }
 
-        let stats = items[0].get("statistics")
-            .ok_or_else(|| FactoryError::Infrastructure { reason: "Missing statistics in video data".to_string() })?;
+    async fn fetch(&self, client: &reqwest::Client, video_id: &str) -> Result<SnsMetrics, FactoryError> {
+        info!("📺 [SnsWatcher] Fetching YouTube metrics for {}", video_id);
 
-        let views = stats.get("viewCount").and_then(|v| v.as_str()).unwrap_or("0").parse::<i64>().unwrap_or(0);
-        let likes = stats.get("likeCount").and_then(|v| v.as_str()).unwrap_or("0").parse::<i64>().unwrap_or(0);
-        let comments_count = stats.get("commentCount").and_then(|v| v.as_str()).unwrap_or("0").parse::<i64>().unwrap_or(0);
+        // 1. Fetch Video Statistics
+        let video_url = format!(
+            "https://www.googleapis.com/youtube/v3/videos?part=statistics&id={}&key={}",
+            video_id, self.api_key
+        );
+        let vid_resp = client.get(&video_url).send().await
+            .map_err(|e| FactoryError::Network { service: "YouTube API".into(), reason: e.to_string() })?;
+        let vid_data = expect_success(vid_resp, "YouTube API").await?;
+        let (views, likes, comments_count) = parse_youtube_statistics(&vid_data, video_id)?;
 
         // 2. Fetch Comment Threads (The Pagination Abyss: Top-K Truncation implementation)
         // Fetches top MAX_COMMENTS_TO_FETCH by relevance, ignoring nextPageToken entirely.
         let comments_url = format!(
             "https://www.googleapis.com/youtube/v3/commentThreads?part=snippet&videoId={}&maxResults={}&order=relevance&key={}",
-            video_id, MAX_COMMENTS_TO_FETCH, self.youtube_api_key
+            video_id, MAX_COMMENTS_TO_FETCH, self.api_key
         );
 
         let mut comments = Vec::new();
 
         let comm_resp = client.get(&comments_url).send().await
-            .map_err(|e| FactoryError::Infrastructure { reason: format!("YouTube Comment API Error: {}", e) })?;
+            .map_err(|e| FactoryError::Network { service: "YouTube Comment API".into(), reason: e.to_string() })?;
 
         if comm_resp.status().is_success() {
-            if let Ok(comm_data) = comm_resp.json::<serde_json::Value>().await {
+            if let Ok(comm_data) = comm_resp.json::<Value>().await {
                 if let Some(c_items) = comm_data.get("items").and_then(|i| i.as_array()) {
                     for item in c_items {
                         if let Some(text) = item.pointer("/snippet/topLevelComment/snippet/textOriginal").and_then(|t| t.as_str()) {
@@ -104,10 +184,10 @@ impl SnsWatcher {
             }
         } else if comm_resp.status() == 403 {
              // 403 means comments disabled or quota exceeded for the day
-             tracing::warn!("⚠️ [SnsWatcher] Comments disabled or Quota Exceeded for video {}", video_id);
+             warn!("⚠️ [SnsWatcher] Comments disabled or Quota Exceeded for video {}", video_id);
              // We do not fail the whole metric fetch just because comments are disabled, the watcher proceeds with views/likes.
         } else {
-             tracing::warn!("⚠️ [SnsWatcher] Failed to fetch comments: status {}", comm_resp.status());
+             warn!("⚠️ [SnsWatcher] Failed to fetch comments: status {}", comm_resp.status());
         }
 
         info!("✅ [SnsWatcher] Fetched for {}: {} views, {} likes, {} comments extracted.", video_id, views, likes, comments.len());
@@ -120,3 +200,178 @@ impl SnsWatcher {
         })
     }
 }
+
+// --- TikTok Display API v2 ---
+
+/// 投稿者アカウントの user access token (`video.list` スコープ) で自分の動画を照会する。
+/// Display API はコメント本文を返さないため、comments は常に空になる。
+pub struct TikTokMetrics {
+    access_token: String,
+}
+
+impl TikTokMetrics {
+    pub fn new(access_token: String) -> Self {
+        Self { access_token }
+    }
+}
+
+/// `video/query` の応答から該当動画の (views, likes, comments_count)
+fn parse_tiktok_video(data: &Value, video_id: &str) -> Result<(i64, i64, i64), FactoryError> {
+    if let Some(code) = data.pointer("/error/code").and_then(|c| c.as_str()).filter(|c| *c != "ok") {
+        let message = data.pointer("/error/message").and_then(|m| m.as_str()).unwrap_or_default();
+        return Err(FactoryError::Infrastructure { reason: format!("TikTok API error {}: {}", code, message) });
+    }
+    let video = data.pointer("/data/videos")
+        .and_then(|v| v.as_array())
+        .and_then(|videos| videos.iter().find(|v| v.get("id").and_then(|id| id.as_str()) == Some(video_id)))
+        .ok_or_else(|| FactoryError::Infrastructure { reason: format!("TikTok video {} not found", video_id) })?;
+    Ok((count(video.get("view_count")), count(video.get("like_count")), count(video.get("comment_count"))))
+}
+
+#[async_trait]
+impl MetricsProvider for TikTokMetrics {
+    fn platform(&self) -> &'static str {
+        "tiktok"
+    }
+
+    async fn fetch(&self, client: &reqwest::Client, video_id: &str) -> Result<SnsMetrics, FactoryError> {
+        info!("🎵 [SnsWatcher] Fetching TikTok metrics for {}", video_id);
+
+        let resp = client
+            .post("https://open.tiktokapis.com/v2/video/query/?fields=id,view_count,like_count,comment_count")
+            .bearer_auth(&self.access_token)
+            .json(&serde_json::json!({ "filters": { "video_ids": [video_id] } }))
+            .send().await
+            .map_err(|e| FactoryError::Network { service: "TikTok API".into(), reason: e.to_string() })?;
+        let data = expect_success(resp, "TikTok API").await?;
+        let (views, likes, comments_count) = parse_tiktok_video(&data, video_id)?;
+
+        info!("✅ [SnsWatcher] Fetched for {}: {} views, {} likes (TikTok).", video_id, views, likes);
+        Ok(SnsMetrics { views, likes, comments_count, comments: Vec::new() })
+    }
+}
+
+// --- Instagram Graph API (Reels) ---
+
+/// ビジネス/クリエイターアカウントのアクセストークンで Reels のメディア ID を照会する
+pub struct InstagramMetrics {
+    access_token: String,
+}
+
+impl InstagramMetrics {
+    pub fn new(access_token: String) -> Self {
+        Self { access_token }
+    }
+}
+
+/// `/{media-id}/insights?metric=views` の応答から再生数
+fn parse_instagram_views(data: &Value) -> i64 {
+    data.get("data")
+        .and_then(|d| d.as_array())
+        .and_then(|metrics| metrics.iter().find(|m| m.get("name").and_then(|n| n.as_str()) == Some("views")))
+        .map(|m| count(m.pointer("/values/0/value").or_else(|| m.pointer("/total_value/value"))))
+        .unwrap_or(0)
+}
+
+#[async_trait]
+impl MetricsProvider for InstagramMetrics {
+    fn platform(&self) -> &'static str {
+        "instagram"
+    }
+
+    async fn fetch(&self, client: &reqwest::Client, video_id: &str) -> Result<SnsMetrics, FactoryError> {
+        info!("📸 [SnsWatcher] Fetching Instagram metrics for {}", video_id);
+
+        // 1. いいね数・コメント数はメディア本体のフィールド
+        let resp = client
+            .get(format!("{}/{}", INSTAGRAM_GRAPH_URL, video_id))
+            .query(&[("fields", "like_count,comments_count"), ("access_token", self.access_token.as_str())])
+            .send().await
+            .map_err(|e| FactoryError::Network { service: "Instagram API".into(), reason: e.to_string() })?;
+        let media = expect_success(resp, "Instagram API").await?;
+        let likes = count(media.get("like_count"));
+        let comments_count = count(media.get("comments_count"));
+
+        // 2. 再生数は Insights (取得できなくてもいいね・コメントで続行する)
+        let views = match client
+            .get(format!("{}/{}/insights", INSTAGRAM_GRAPH_URL, video_id))
+            .query(&[("metric", "views"), ("access_token", self.access_token.as_str())])
+            .send().await
+        {
+            Ok(resp) => match expect_success(resp, "Instagram Insights").await {
+                Ok(data) => parse_instagram_views(&data),
+                Err(e) => {
+                    warn!("⚠️ [SnsWatcher] {}", e);
+                    0
+                }
+            },
+            Err(e) => {
+                warn!("⚠️ [SnsWatcher] Instagram Insights Error: {}", e);
+                0
+            }
+        };
+
+        // 3. コメント本文 (先頭 MAX_COMMENTS_TO_FETCH 件のみ)
+        let mut comments = Vec::new();
+        let limit = MAX_COMMENTS_TO_FETCH.to_string();
+        match client
+            .get(format!("{}/{}/comments", INSTAGRAM_GRAPH_URL, video_id))
+            .query(&[("fields", "text"), ("limit", limit.as_str()), ("access_token", self.access_token.as_str())])
+            .send().await
+        {
+            Ok(resp) if resp.status().is_success() => {
+                if let Ok(data) = resp.json::<Value>().await {
+                    if let Some(items) = data.get("data").and_then(|d| d.as_array()) {
+                        comments.extend(items.iter().filter_map(|c| c.get("text").and_then(|t| t.as_str())).map(String::from));
+                    }
+                }
+            }
+            Ok(resp) => warn!("⚠️ [SnsWatcher] Failed to fetch Instagram comments: status {}", resp.status()),
+            Err(e) => warn!("⚠️ [SnsWatcher] Instagram Comment API Error: {}", e),
+        }
+
+        info!("✅ [SnsWatcher] Fetched for {}: {} views, {} likes, {} comments extracted (Instagram).", video_id, views, likes, comments.len());
+        Ok(SnsMetrics { views, likes, comments_count, comments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_platform_aliases_and_provider_registration() {
+        assert_eq!(canonical_platform(" YouTube "), Some("youtube"));
+        assert_eq!(canonical_platform("reels"), Some("instagram"));
+        assert_eq!(canonical_platform("TT"), Some("tiktok"));
+        assert_eq!(canonical_platform("vimeo"), None);
+
+        let profile = ChannelProfile { tiktok_access_token: "act.x".into(), ..Default::default() };
+        let watcher = SnsWatcher::for_channel(&profile);
+        assert!(watcher.supports("TikTok"));
+        assert!(!watcher.supports("youtube"));
+        assert!(!watcher.supports("ig"));
+    }
+
+    #[test]
+    fn test_parse_platform_responses() {
+        let yt = json!({"items": [{"statistics": {"viewCount": "1200", "likeCount": "80", "commentCount": "7"}}]});
+        assert_eq!(parse_youtube_statistics(&yt, "abc").unwrap(), (1200, 80, 7));
+        assert!(parse_youtube_statistics(&json!({"items": []}), "abc").is_err());
+
+        let tt = json!({
+            "data": {"videos": [{"id": "other", "view_count": 1}, {"id": "723", "view_count": 5000, "like_count": 310, "comment_count": 12}]},
+            "error": {"code": "ok", "message": ""}
+        });
+        assert_eq!(parse_tiktok_video(&tt, "723").unwrap(), (5000, 310, 12));
+        assert!(parse_tiktok_video(&tt, "999").is_err());
+        let denied = json!({"data": {}, "error": {"code": "access_token_invalid", "message": "expired"}});
+        assert!(parse_tiktok_video(&denied, "723").unwrap_err().to_string().contains("access_token_invalid"));
+
+        let ig = json!({"data": [{"name": "views", "period": "lifetime", "values": [{"value": 4321}]}]});
+        assert_eq!(parse_instagram_views(&ig), 4321);
+        assert_eq!(parse_instagram_views(&json!({"data": [{"name": "views", "total_value": {"value": 9}}]})), 9);
+        assert_eq!(parse_instagram_views(&json!({"data": []})), 0);
+    }
+}
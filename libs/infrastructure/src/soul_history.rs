@@ -0,0 +1,143 @@
+//! # Soul History — SOUL の版と、版をまたいだ Karma の引き継ぎ
+//!
+//! 教訓 (Karma) は書かれた時点の SOUL のハッシュを持ち、SOUL を書き換えると
+//! 古い教訓は `[LEGACY KARMA]` として注入される。どの版がいつ使われていたかを `soul_versions` に本文ごと残し、
+//! `shorts-factory soul history / diff` で追えるようにする。
+//! `soul reevaluate` は旧版との差分を LLM に見せ、古い教訓を今の SOUL の下で残す・書き直す・退役させる。
+
+/// SOUL 本文のハッシュ (karma_logs.soul_version_hash と同じ値)
+pub fn soul_hash(content: &str) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:16x}", hasher.finish())
+}
+
+/// 記録済みの SOUL の版
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoulVersion {
+    pub hash: String,
+    /// SOUL ファイルのパス (チャンネル / `[souls]` のプロファイルが参照するもの)
+    pub source: String,
+    pub content: String,
+    /// この版が有効になった時刻
+    pub recorded_at: String,
+    /// この版の下で書かれ、まだ効いている (weight > 0) 教訓の数
+    pub karma_count: i64,
+}
+
+/// 行単位の差分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+/// 最長共通部分列による行単位の差分
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // lcs[i][j] = old[i..] と new[j..] の共通部分列の長さ
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            out.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    out.extend(old[i..].iter().copied().map(DiffLine::Removed));
+    out.extend(new[j..].iter().copied().map(DiffLine::Added));
+    out
+}
+
+/// `- ` / `+ ` 付きの差分。`changes_only` なら変わった行だけ (LLM に見せる用)
+pub fn render_diff(lines: &[DiffLine<'_>], changes_only: bool) -> String {
+    lines
+        .iter()
+        .filter_map(|line| match line {
+            DiffLine::Same(l) => (!changes_only).then(|| format!("  {}", l)),
+            DiffLine::Added(l) => Some(format!("+ {}", l)),
+            DiffLine::Removed(l) => Some(format!("- {}", l)),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 古い教訓を今の SOUL の下でどう扱うか
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KarmaDecision {
+    /// そのまま有効 (今の版の教訓として引き継ぐ)
+    Keep,
+    /// 書き直して引き継ぐ
+    Revise(String),
+    /// もう当てはまらない (weight = 0 にする)
+    Retire,
+}
+
+/// LLM の応答 `{"decision": "keep" | "revise" | "retire", "lesson": "..."}` を読む
+pub fn parse_decision(text: &str) -> Option<KarmaDecision> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(text.get(start..=end)?).ok()?;
+    match value.get("decision")?.as_str()?.trim().to_lowercase().as_str() {
+        "keep" => Some(KarmaDecision::Keep),
+        "retire" => Some(KarmaDecision::Retire),
+        "revise" => {
+            let lesson = value.get("lesson")?.as_str()?.trim();
+            (!lesson.is_empty()).then(|| KarmaDecision::Revise(lesson.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let old = "# Soul\n- Be creative.\n- Stay calm.";
+        let new = "# Soul\n- Be bold.\n- Stay calm.\n- Never clickbait.";
+        let diff = diff_lines(old, new);
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("# Soul"),
+                DiffLine::Removed("- Be creative."),
+                DiffLine::Added("- Be bold."),
+                DiffLine::Same("- Stay calm."),
+                DiffLine::Added("- Never clickbait."),
+            ]
+        );
+        assert_eq!(render_diff(&diff, true), "- - Be creative.\n+ - Be bold.\n+ - Never clickbait.");
+        assert_eq!(soul_hash(old), soul_hash(old));
+        assert_ne!(soul_hash(old), soul_hash(new));
+    }
+
+    #[test]
+    fn test_parse_decision() {
+        assert_eq!(parse_decision("```json\n{\"decision\": \"Keep\"}\n```"), Some(KarmaDecision::Keep));
+        assert_eq!(parse_decision(r#"{"decision": "retire", "reason": "contradicts"}"#), Some(KarmaDecision::Retire));
+        assert_eq!(
+            parse_decision(r#"{"decision": "revise", "lesson": "Open with the payoff"}"#),
+            Some(KarmaDecision::Revise("Open with the payoff".to_string()))
+        );
+        assert_eq!(parse_decision(r#"{"decision": "revise", "lesson": " "}"#), None);
+        assert_eq!(parse_decision("no idea"), None);
+    }
+}
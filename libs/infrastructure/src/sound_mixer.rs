@@ -1,17 +1,169 @@
+use crate::bgm_manifest::{BgmManifest, AUDIO_EXTENSIONS};
+use factory_core::contracts::SfxCue;
 use factory_core::error::FactoryError;
+use shared::config::SfxConfig;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
-use tracing::info;
+use tracing::{info, warn};
 use tokio::process::Command;
 use std::process::Stdio;
 
+/// BGM の末尾のフェードアウト (秒)
+const BGM_FADE_OUT_SECS: f32 = 1.5;
+
+/// 効果音 1 つ分の配置 (ナレーション先頭からの秒)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfxPlacement {
+    pub at: f32,
+    pub kind: String,
+}
+
+/// `{種類}.wav` / `{種類}_2.wav` … を種類ごとにまとめる
+fn scan_sfx_library(dir: &Path) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut library: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in std::fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path()) {
+        let is_audio = path.extension().and_then(|e| e.to_str()).is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()).filter(|_| is_audio) else { continue };
+        let kind = match stem.rsplit_once('_') {
+            Some((kind, n)) if !kind.is_empty() && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => kind,
+            _ => stem,
+        };
+        library.entry(kind.to_lowercase()).or_default().push(path.clone());
+    }
+    library.values_mut().for_each(|variants| variants.sort());
+    library
+}
+
+/// 入力 0 がナレーション、入力 1.. が効果音 (`delays_ms` と同順) の filter_complex
+fn sfx_filter(delays_ms: &[u64], gain_db: f32) -> String {
+    let mut filter = String::new();
+    let mut mix = String::from("[0:a]");
+    for (k, delay) in delays_ms.iter().enumerate() {
+        filter.push_str(&format!("[{}:a]volume={}dB,adelay={}:all=1[sfx{}]; ", k + 1, gain_db, delay, k));
+        mix.push_str(&format!("[sfx{}]", k));
+    }
+    format!("{}{}amix=inputs={}:duration=first:normalize=0[out]", filter, mix, delays_ms.len() + 1)
+}
+
+/// 入力 0 がナレーション、入力 1 が BGM。BGM をナレーションでダッキングし、`duration` 秒の終わりでフェードアウトする
+fn bgm_mix_filter(looped: bool, duration: f32, style: &tuning::StyleProfile) -> String {
+    let fade_start = (duration - BGM_FADE_OUT_SECS).max(0.0);
+    format!(
+        "[1:a]{}afade=t=out:st={}:d={}[bgm]; \
+         [bgm][0:a]sidechaincompress=threshold={}:ratio=20:attack=10:release=200[bgm_ducked]; \
+         [0:a][bgm_ducked]amix=inputs=2:weights=1.0 {}:duration=first:normalize=0[out]; \
+         [out]loudnorm=I=-14:LRA=11:TP=-1.5[final]",
+        if looped { "aloop=loop=-1:size=2e+09," } else { "" },
+        fade_start,
+        BGM_FADE_OUT_SECS,
+        style.ducking_threshold,
+        style.ducking_ratio,
+    )
+}
+
 /// プロフェッショナル・オーディオ合成機 ("The Sound Mixer")
 pub struct SoundMixer {
     bgm_library_path: PathBuf,
+    /// `bgm_manifest.toml` (読めなければ空 = カテゴリ名での選曲のみ)
+    manifest: BgmManifest,
+    sfx: SfxConfig,
+    /// 種類 -> 効果音ファイル (無効時は空)
+    sfx_library: BTreeMap<String, Vec<PathBuf>>,
 }
 
 impl SoundMixer {
     pub fn new(bgm_library_path: PathBuf) -> Self {
-        Self { bgm_library_path }
+        let manifest = BgmManifest::load(&bgm_library_path).unwrap_or_else(|e| {
+            warn!("⚠️ SoundMixer: {}. Falling back to category-named BGM.", e);
+            BgmManifest::default()
+        });
+        if !manifest.tracks.is_empty() {
+            info!("🎶 SoundMixer: {} BGM track(s) in manifest (moods: {})", manifest.tracks.len(), manifest.moods().join(", "));
+        }
+        Self { bgm_library_path, manifest, sfx: SfxConfig::default(), sfx_library: BTreeMap::new() }
+    }
+
+    /// 効果音の挿入を設定する (enabled = false なら何もしない)
+    pub fn with_sfx(mut self, cfg: SfxConfig) -> Self {
+        if cfg.enabled {
+            self.sfx_library = scan_sfx_library(Path::new(&cfg.library_dir));
+            if self.sfx_library.is_empty() {
+                warn!("⚠️ SoundMixer: No SFX found in {}. Sound effects are skipped.", cfg.library_dir);
+            } else {
+                info!("🔊 SoundMixer: SFX library: {}", self.sfx_kinds().join(", "));
+            }
+        }
+        self.sfx = cfg;
+        self
+    }
+
+    /// コンセプト段階に選ばせるムードの候補
+    pub fn moods(&self) -> Vec<String> {
+        self.manifest.moods()
+    }
+
+    /// コンセプト段階に選ばせる効果音の種類 (幕の切り替わり用は除く)
+    pub fn sfx_kinds(&self) -> Vec<String> {
+        self.sfx_library.keys().filter(|k| **k != self.sfx.transition).cloned().collect()
+    }
+
+    /// 幕の区間 (開始秒, 尺) と強調点から効果音の配置を決める。
+    /// 切り替わりの効果音が先、強調点は時刻順に `max_cues` まで
+    pub fn place_sfx(&self, acts: &[(f32, f32)], cues: &[SfxCue]) -> Vec<SfxPlacement> {
+        let mut placements = Vec::new();
+        if self.sfx_library.contains_key(&self.sfx.transition) {
+            for (start, _) in acts.iter().skip(1) {
+                placements.push(SfxPlacement { at: (start - self.sfx.transition_lead_secs).max(0.0), kind: self.sfx.transition.clone() });
+            }
+        }
+        let mut emphasis: Vec<SfxPlacement> = cues
+            .iter()
+            .filter_map(|cue| {
+                let kind = cue.sfx.trim().to_lowercase();
+                let (start, duration) = acts.get(cue.act)?;
+                self.sfx_library.contains_key(&kind).then(|| SfxPlacement { at: start + duration * cue.at.clamp(0.0, 1.0), kind })
+            })
+            .collect();
+        emphasis.sort_by(|a, b| a.at.total_cmp(&b.at));
+        placements.extend(emphasis);
+        placements.truncate(self.sfx.max_cues);
+        placements.sort_by(|a, b| a.at.total_cmp(&b.at));
+        placements
+    }
+
+    /// ナレーションに効果音を重ねる (BGM とのミックスの前段)。同じ種類はバリエーションを順に使う
+    pub async fn overlay_sfx(&self, narration_path: &Path, placements: &[SfxPlacement], output_path: &Path) -> Result<PathBuf, FactoryError> {
+        let mut cmd = Command::new("ffmpeg");
+        cmd.arg("-y").arg("-i").arg(narration_path);
+        let mut uses: HashMap<&str, usize> = HashMap::new();
+        let mut delays = Vec::new();
+        for placement in placements {
+            let Some(variants) = self.sfx_library.get(&placement.kind) else { continue };
+            let n = uses.entry(placement.kind.as_str()).or_default();
+            cmd.arg("-i").arg(&variants[*n % variants.len()]);
+            *n += 1;
+            delays.push((placement.at * 1000.0).round() as u64);
+        }
+        if delays.is_empty() {
+            return Ok(narration_path.to_path_buf());
+        }
+
+        let status = cmd
+            .arg("-filter_complex").arg(sfx_filter(&delays, self.sfx.gain_db))
+            .arg("-map").arg("[out]")
+            .arg(output_path)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("FFmpeg SFX overlay failed to spawn: {}", e) })?;
+
+        if status.success() {
+            info!("🔊 SoundMixer: Overlaid {} SFX onto {}", delays.len(), narration_path.display());
+            Ok(output_path.to_path_buf())
+        } else {
+            Err(FactoryError::Infrastructure { reason: "FFmpeg SFX overlay execution failed".into() })
+        }
     }
 
     /// ナレーション、BGM、効果音をミキシングし、完パケ音声を生成する
@@ -19,27 +171,48 @@ impl SoundMixer {
         &self,
         narration_path: &Path,
         category: &str,
+        mood: Option<&str>,
         output_path: &Path,
         style: &tuning::StyleProfile,
     ) -> Result<PathBuf, FactoryError> {
         info!("🎶 SoundMixer: Mixing narration with BGM (Style: {})...", style.name);
+        self.mix(narration_path, category, mood, output_path, style, None).await
+    }
+
+    /// ダッキングの調整用: ナレーションの先頭 `max_secs` 秒だけを本番と同じフィルタで BGM と混ぜる
+    pub async fn mix_preview(
+        &self,
+        narration_path: &Path,
+        category: &str,
+        mood: Option<&str>,
+        output_path: &Path,
+        style: &tuning::StyleProfile,
+        max_secs: f32,
+    ) -> Result<PathBuf, FactoryError> {
+        info!("🎶 SoundMixer: Rendering a {:.0}s BGM preview (Style: {})...", max_secs, style.name);
+        self.mix(narration_path, category, mood, output_path, style, Some(max_secs)).await
+    }
+
+    async fn mix(
+        &self,
+        narration_path: &Path,
+        category: &str,
+        mood: Option<&str>,
+        output_path: &Path,
+        style: &tuning::StyleProfile,
+        max_secs: Option<f32>,
+    ) -> Result<PathBuf, FactoryError> {
         let output = output_path.to_path_buf();
 
-        // 1. BGM 選択
-        let bgm_path = self.select_bgm(category).await?;
-        
         // ナレーションの長さを取得 (秒)
-        let duration = self.get_audio_duration(narration_path).await?;
-        
-        // 2. FFmpeg Complex Filter の構築
-        let filter = format!(
-            "[1:a]aloop=loop=-1:size=2e+09[bgm]; \
-             [bgm][0:a]sidechaincompress=threshold={}:ratio=20:attack=10:release=200[bgm_ducked]; \
-             [0:a][bgm_ducked]amix=inputs=2:weights=1.0 {}:duration=first:normalize=0[out]; \
-             [out]loudnorm=I=-14:LRA=11:TP=-1.5[final]",
-            style.ducking_threshold,
-            style.ducking_ratio,
-        );
+        let full = self.get_audio_duration(narration_path).await?;
+        let duration = max_secs.map_or(full, |max| full.min(max));
+
+        // 1. BGM 選択
+        let (bgm_path, looped) = self.select_bgm(category, mood, duration).await?;
+
+        // 2. FFmpeg Complex Filter の構築 (ループできない曲は尺が足りるものだけ選ばれている)
+        let filter = bgm_mix_filter(looped, duration, style);
 
         let status = Command::new("ffmpeg")
             .arg("-y")
@@ -63,7 +236,60 @@ impl SoundMixer {
         }
     }
 
-    async fn select_bgm(&self, category: &str) -> Result<PathBuf, FactoryError> {
+    /// BGM を使わない工程向け: ナレーションの音量を mix_and_finalize と同じ基準に揃えるだけ
+    pub async fn normalize_only(&self, narration_path: &Path, output_path: &Path) -> Result<PathBuf, FactoryError> {
+        info!("🎶 SoundMixer: Normalizing narration without BGM...");
+        let status = Command::new("ffmpeg")
+            .arg("-y")
+            .arg("-i").arg(narration_path)
+            .arg("-af").arg("loudnorm=I=-14:LRA=11:TP=-1.5")
+            .arg(output_path)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("FFmpeg mixer failed to spawn: {}", e) })?;
+
+        if status.success() {
+            Ok(output_path.to_path_buf())
+        } else {
+            Err(FactoryError::Infrastructure { reason: "FFmpeg loudness normalization failed".into() })
+        }
+    }
+
+    /// (BGM, ループするか)。ムードに合う曲のうち、ループ可能か尺が足りる曲を目録の優先順で選ぶ。
+    /// 尺の足りない曲しか無ければループさせて使い、ムードに合う曲が無ければカテゴリ名の曲に落ちる
+    async fn select_bgm(&self, category: &str, mood: Option<&str>, duration: f32) -> Result<(PathBuf, bool), FactoryError> {
+        if let Some(mood) = mood.map(str::trim).filter(|m| !m.is_empty()) {
+            let candidates: Vec<_> = self.manifest.candidates(mood, category)
+                .into_iter()
+                .filter(|t| self.bgm_library_path.join(&t.file).is_file())
+                .collect();
+            for track in &candidates {
+                let path = self.bgm_library_path.join(&track.file);
+                if track.loopable {
+                    info!("🎵 SoundMixer: '{}' for mood '{}' (looped)", track.file, mood);
+                    return Ok((path, true));
+                }
+                match self.get_audio_duration(&path).await {
+                    Ok(len) if len >= duration => {
+                        info!("🎵 SoundMixer: '{}' for mood '{}' ({:.0}s, trimmed to {:.0}s)", track.file, mood, len, duration);
+                        return Ok((path, false));
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("⚠️ SoundMixer: Could not probe '{}': {}", track.file, e),
+                }
+            }
+            if let Some(track) = candidates.first() {
+                warn!("⚠️ SoundMixer: No loopable or long-enough track for mood '{}'. Looping '{}'.", mood, track.file);
+                return Ok((self.bgm_library_path.join(&track.file), true));
+            }
+            warn!("⚠️ SoundMixer: No BGM tagged '{}' in manifest. Falling back to category '{}'.", mood, category);
+        }
+        self.select_category_bgm(category).map(|path| (path, true))
+    }
+
+    fn select_category_bgm(&self, category: &str) -> Result<PathBuf, FactoryError> {
         let category_bgm = self.bgm_library_path.join(format!("{}.mp3", category));
         if category_bgm.exists() {
             Ok(category_bgm)
@@ -93,3 +319,51 @@ impl SoundMixer {
         dur_str.parse::<f32>().map_err(|_| FactoryError::Infrastructure { reason: "Failed to parse duration".into() })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mixer(dir: &Path) -> SoundMixer {
+        for name in ["whoosh.wav", "whoosh_2.wav", "pop.mp3", "hit_big.wav", "readme.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let cfg = SfxConfig { enabled: true, library_dir: dir.to_string_lossy().to_string(), max_cues: 4, ..Default::default() };
+        SoundMixer::new(dir.join("bgm")).with_sfx(cfg)
+    }
+
+    #[test]
+    fn test_sfx_library_groups_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let mixer = mixer(dir.path());
+        assert_eq!(mixer.sfx_library["whoosh"].len(), 2);
+        assert_eq!(mixer.sfx_kinds(), vec!["hit_big", "pop"]);
+    }
+
+    #[test]
+    fn test_place_sfx_on_transitions_and_cues() {
+        let dir = tempfile::tempdir().unwrap();
+        let mixer = mixer(dir.path());
+        let acts = [(0.0, 4.0), (4.0, 20.0), (24.0, 6.0)];
+        let cue = |act, at: f32, sfx: &str| SfxCue { act, at, sfx: sfx.to_string() };
+        let placements = mixer.place_sfx(&acts, &[cue(1, 0.5, "POP"), cue(2, 2.0, "hit_big"), cue(1, 0.1, "boom"), cue(5, 0.0, "pop"), cue(0, 0.0, "pop")]);
+        let got: Vec<(f32, &str)> = placements.iter().map(|p| (p.at, p.kind.as_str())).collect();
+        // 上限 4: 切り替わり 2 つ + 早い順の強調点 2 つ (未知の種類・存在しない幕は無視)
+        assert_eq!(got, vec![(0.0, "pop"), (3.75, "whoosh"), (14.0, "pop"), (23.75, "whoosh")]);
+
+        assert_eq!(
+            sfx_filter(&[3750, 14000], -8.0),
+            "[1:a]volume=-8dB,adelay=3750:all=1[sfx0]; [2:a]volume=-8dB,adelay=14000:all=1[sfx1]; [0:a][sfx0][sfx1]amix=inputs=3:duration=first:normalize=0[out]"
+        );
+        assert!(SoundMixer::new(dir.path().join("bgm")).place_sfx(&acts, &[cue(1, 0.5, "pop")]).is_empty());
+    }
+
+    #[test]
+    fn test_bgm_filter_fades_at_preview_end_with_style_ducking() {
+        let style = tuning::StyleProfile { ducking_threshold: 0.05, ducking_ratio: 0.3, ..Default::default() };
+        let filter = bgm_mix_filter(true, 12.0, &style);
+        assert!(filter.starts_with("[1:a]aloop=loop=-1:size=2e+09,afade=t=out:st=10.5:d=1.5[bgm]"));
+        assert!(filter.contains("sidechaincompress=threshold=0.05:") && filter.contains("weights=1.0 0.3:"));
+        assert!(bgm_mix_filter(false, 1.0, &style).starts_with("[1:a]afade=t=out:st=0:"));
+    }
+}
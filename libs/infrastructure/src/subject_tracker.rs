@@ -0,0 +1,72 @@
+//! # SubjectTracker — 縦型リフレーム用の被写体検出クライアント
+//!
+//! 軽量な検出サイドカー (例: YOLO / MediaPipe のラッパー) に動画パスを渡し、
+//! 一定間隔ごとの主被写体の水平位置を受け取る。結果は `MediaForgeClient::reframe_vertical` に渡す。
+
+use crate::media_forge::CropKeyframe;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+pub struct SubjectTracker {
+    detector_url: String,
+    sample_interval_secs: f32,
+    client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct TrackRequest<'a> {
+    path: &'a str,
+    interval: f32,
+}
+
+#[derive(Deserialize)]
+struct TrackResponse {
+    #[serde(default)]
+    keyframes: Vec<CropKeyframe>,
+}
+
+impl SubjectTracker {
+    pub fn new(detector_url: &str, sample_interval_secs: f32, timeout: Duration) -> Result<Self, FactoryError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build SubjectTracker HTTP client: {}", e) })?;
+
+        Ok(Self {
+            detector_url: detector_url.trim_end_matches('/').to_string(),
+            sample_interval_secs,
+            client,
+        })
+    }
+
+    /// `POST {detector_url}/track` で被写体の軌跡を取得する
+    ///
+    /// 被写体が検出されなかった区間はサイドカー側で省略してよい (空なら中央クロップ)。
+    pub async fn track(&self, video: &Path) -> Result<Vec<CropKeyframe>, FactoryError> {
+        let path = video.to_string_lossy();
+        let res = self.client
+            .post(format!("{}/track", self.detector_url))
+            .json(&TrackRequest { path: &path, interval: self.sample_interval_secs })
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "Subject detector".into(), reason: e.to_string() })?;
+
+        if !res.status().is_success() {
+            return Err(FactoryError::from_http_status("Subject detector", res.status().as_u16(), ""));
+        }
+
+        let body: TrackResponse = res.json().await.map_err(|e| FactoryError::Infrastructure {
+            reason: format!("Invalid detector response: {}", e),
+        })?;
+
+        let mut keyframes: Vec<CropKeyframe> = body.keyframes.into_iter()
+            .filter(|k| k.t.is_finite() && k.cx.is_finite())
+            .collect();
+        keyframes.sort_by(|a, b| a.t.total_cmp(&b.t));
+        info!("🎯 SubjectTracker: {} keyframes for {}", keyframes.len(), video.display());
+        Ok(keyframes)
+    }
+}
@@ -0,0 +1,135 @@
+//! # Topic Policy — 編集方針による題材の禁止・必須テーマ
+//!
+//! Samsara の Ethical Circuit Breaker は災害・事故などの一般的な悲劇しか避けない。
+//! チャンネルとして扱わないと決めた題材は `[topic_policy] file` (既定 `workspace/config/topic_policy.toml`) に書き、
+//! Samsara の企画と REST / Discord の生成要求をジョブに積む前にここで照らす。
+//!
+//! ```toml
+//! # 1 つでも含む題材は却下 (大文字小文字・全角半角は区別しない)
+//! banned_keywords = ["カジノ", "crypto airdrop"]
+//! # 空でなければ、題材はどれか 1 つを含まなければならない
+//! required_themes = ["AI", "テクノロジー", "アート"]
+//! ```
+//!
+//! ファイルは照合のたびに読むので、書き換えは再起動なしで効く。無ければ何も制限しない。
+
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicPolicy {
+    /// 題材に含まれていれば却下するキーワード
+    #[serde(default)]
+    pub banned_keywords: Vec<String>,
+    /// 題材が少なくとも 1 つ含むべきテーマ (空なら制限なし)
+    #[serde(default)]
+    pub required_themes: Vec<String>,
+}
+
+/// 方針に反した理由
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopicViolation {
+    /// 禁止キーワードを含む
+    Banned { keyword: String },
+    /// 必須テーマをどれも含まない
+    MissingTheme { themes: Vec<String> },
+}
+
+impl std::fmt::Display for TopicViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Banned { keyword } => write!(f, "contains the banned keyword '{}'", keyword),
+            Self::MissingTheme { themes } => write!(f, "matches none of the required themes ({})", themes.join(", ")),
+        }
+    }
+}
+
+impl TopicPolicy {
+    /// 方針ファイルを読む (無ければ制限なし)
+    pub fn load(path: &Path) -> Result<Self, FactoryError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to read {}: {}", path.display(), e),
+        })?;
+        toml::from_str(&content).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to parse {}: {}", path.display(), e),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.banned_keywords.iter().all(|k| k.trim().is_empty()) && self.required_themes.iter().all(|t| t.trim().is_empty())
+    }
+
+    /// 題材を方針と照らす
+    pub fn check(&self, topic: &str) -> Result<(), TopicViolation> {
+        let topic = normalize(topic);
+        if let Some(keyword) = self.banned_keywords.iter().find(|k| !k.trim().is_empty() && topic.contains(&normalize(k))) {
+            return Err(TopicViolation::Banned { keyword: keyword.trim().to_string() });
+        }
+        let themes: Vec<&String> = self.required_themes.iter().filter(|t| !t.trim().is_empty()).collect();
+        if !themes.is_empty() && !themes.iter().any(|t| topic.contains(&normalize(t))) {
+            return Err(TopicViolation::MissingTheme { themes: themes.iter().map(|t| t.trim().to_string()).collect() });
+        }
+        Ok(())
+    }
+
+    /// 企画プロンプトに添える方針の説明 (制限が無ければ空)
+    pub fn prompt_section(&self) -> String {
+        let mut lines = Vec::new();
+        let banned: Vec<&str> = self.banned_keywords.iter().map(|k| k.trim()).filter(|k| !k.is_empty()).collect();
+        if !banned.is_empty() {
+            lines.push(format!("- 次の語を含む題材は禁止: {}", banned.join(" / ")));
+        }
+        let themes: Vec<&str> = self.required_themes.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+        if !themes.is_empty() {
+            lines.push(format!("- 題材 (topic) には次のテーマのどれかを必ず含める: {}", themes.join(" / ")));
+        }
+        lines.join("\n")
+    }
+}
+
+/// NFKC + 小文字化 (全角英数と半角英数を同一視する)
+fn normalize(s: &str) -> String {
+    s.trim().nfkc().collect::<String>().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banned_keywords_and_required_themes() {
+        let policy: TopicPolicy = toml::from_str(
+            r#"
+            banned_keywords = ["カジノ", "Crypto Airdrop"]
+            required_themes = ["AI", "アート"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.check("ＡＩが描くアートの最前線"), Ok(()));
+        assert_eq!(
+            policy.check("AIで勝てるオンラインカジノ"),
+            Err(TopicViolation::Banned { keyword: "カジノ".to_string() })
+        );
+        assert!(matches!(policy.check("AI and the next crypto airdrop"), Err(TopicViolation::Banned { .. })));
+        assert!(matches!(policy.check("今日の天気"), Err(TopicViolation::MissingTheme { .. })));
+        assert!(policy.prompt_section().contains("カジノ / Crypto Airdrop"));
+    }
+
+    #[test]
+    fn test_missing_file_allows_everything() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let policy = TopicPolicy::load(&tmp.path().join("topic_policy.toml")).unwrap();
+        assert!(policy.is_empty());
+        assert_eq!(policy.check("なんでも"), Ok(()));
+        assert_eq!(policy.prompt_section(), "");
+
+        std::fs::write(tmp.path().join("broken.toml"), "banned_keywords = [").unwrap();
+        assert!(TopicPolicy::load(&tmp.path().join("broken.toml")).is_err());
+    }
+}
@@ -46,7 +46,7 @@ impl BraveTrendSonar {
     }
 
     /// Context Sanitization: strips HTML tags, excessive whitespace, and URLs
-    fn sanitize_snippet(snippet: &str) -> String {
+    pub(crate) fn sanitize_snippet(snippet: &str) -> String {
         let mut text = snippet.to_string();
         
         // Strip URLs
@@ -85,13 +85,13 @@ impl TrendSource for BraveTrendSonar {
             .header("Accept", "application/json")
             .send()
             .await
-            .map_err(|e| FactoryError::Infrastructure { reason: format!("Brave API request failed: {}", e) })?;
+            .map_err(|e| FactoryError::Network { service: "Brave API".into(), reason: e.to_string() })?;
 
         if !res.status().is_success() {
             let status = res.status();
             let body = res.text().await.unwrap_or_default();
             tracing::error!("Brave API Error [{}]: {}", status, body);
-            return Err(FactoryError::Infrastructure { reason: format!("Brave API error [{}]: {}", status, body) });
+            return Err(FactoryError::from_http_status("Brave API", status.as_u16(), &body));
         }
 
         let search_res: BraveSearchResponse = res.json().await
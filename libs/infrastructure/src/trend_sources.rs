@@ -0,0 +1,429 @@
+//! # Trend Sources — Brave 以外のトレンド情報源と、その統合
+//!
+//! Samsara の世界観 (world context) と台本のトレンド材料を Brave Search だけに頼ると、
+//! クォータ切れや障害のたびに汎用テーマへ落ちる。Reddit・Google Trends・YouTube 急上昇・RSS を足し、
+//! `CompositeTrendSource` が `[trends]` の重みで各情報源のスコアを混ぜて並べ直す。
+//! 一部の情報源が失敗しても残りで続行し、全滅したときだけエラーにする。
+//!
+//! Brave 以外はクエリを取らない「今の話題」の一覧なので、クエリの語を含む話題だけ少し持ち上げる。
+
+use async_trait::async_trait;
+use factory_core::contracts::{TrendRequest, TrendResponse};
+use factory_core::error::FactoryError;
+use factory_core::traits::{AgentAct, TrendItem, TrendSource};
+use regex::Regex;
+use shared::config::TrendsConfig;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::trend_sonar::BraveTrendSonar;
+
+/// 1 情報源あたりに読む件数
+const ITEMS_PER_SOURCE: usize = 10;
+/// クエリの語を含む話題のスコア倍率
+const QUERY_MATCH_BOOST: f64 = 1.5;
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .user_agent(concat!("aiome-shorts-factory/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+async fn fetch_text(client: &reqwest::Client, service: &str, url: &str) -> Result<String, FactoryError> {
+    let res = client.get(url).send().await
+        .map_err(|e| FactoryError::Network { service: service.into(), reason: e.to_string() })?;
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(FactoryError::from_http_status(service, status.as_u16(), &body));
+    }
+    Ok(body)
+}
+
+/// RSS / Atom の `<item>` / `<entry>` の中身を順に取り出す
+fn feed_entries(xml: &str) -> Vec<&str> {
+    let re = Regex::new(r"(?s)<(item|entry)\b[^>]*>(.*?)</(item|entry)>").unwrap();
+    re.captures_iter(xml).filter_map(|c| c.get(2).map(|m| m.as_str())).collect()
+}
+
+/// 要素 `tag` の本文 (CDATA を外し、タグ・実体参照を検疫した文字列)
+fn element_text(entry: &str, tag: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?s)<{}\b[^>]*>(.*?)</{}>", regex::escape(tag), regex::escape(tag))).unwrap();
+    let raw = re.captures(entry)?.get(1)?.as_str();
+    let raw = raw.trim().trim_start_matches("<![CDATA[").trim_end_matches("]]>");
+    let text = BraveTrendSonar::sanitize_snippet(raw);
+    (!text.is_empty()).then_some(text)
+}
+
+/// Google Trends の `ht:approx_traffic` ("20,000+" など) を数値にする
+fn approx_traffic(text: &str) -> f64 {
+    text.chars().filter(|c| c.is_ascii_digit()).collect::<String>().parse().unwrap_or(0.0)
+}
+
+/// Reddit のリスティング JSON (`/r/<subs>/top.json`) を話題にする (スコアは upvote 数)
+pub fn parse_reddit_listing(json: &serde_json::Value) -> Vec<TrendItem> {
+    json["data"]["children"]
+        .as_array()
+        .map(|children| {
+            children
+                .iter()
+                .filter(|c| !c["data"]["stickied"].as_bool().unwrap_or(false))
+                .filter_map(|c| {
+                    let title = BraveTrendSonar::sanitize_snippet(c["data"]["title"].as_str()?);
+                    (!title.is_empty()).then(|| TrendItem {
+                        keyword: title,
+                        source: "Reddit".to_string(),
+                        score: c["data"]["ups"].as_f64().unwrap_or(0.0).max(1.0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Google Trends の急上昇 RSS を話題にする (スコアは検索数の概算)
+pub fn parse_google_trends(xml: &str) -> Vec<TrendItem> {
+    feed_entries(xml)
+        .into_iter()
+        .filter_map(|entry| {
+            Some(TrendItem {
+                keyword: element_text(entry, "title")?,
+                source: "GoogleTrends".to_string(),
+                score: element_text(entry, "ht:approx_traffic").map(|t| approx_traffic(&t)).unwrap_or(0.0).max(1.0),
+            })
+        })
+        .collect()
+}
+
+/// YouTube Data API `videos?chart=mostPopular` の応答を話題にする (スコアは再生数)
+pub fn parse_youtube_trending(json: &serde_json::Value) -> Vec<TrendItem> {
+    json["items"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let title = BraveTrendSonar::sanitize_snippet(item["snippet"]["title"].as_str()?);
+                    let views = item["statistics"]["viewCount"].as_str().and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                    (!title.is_empty()).then(|| TrendItem { keyword: title, source: "YouTubeTrending".to_string(), score: views.max(1.0) })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// RSS / Atom フィードの見出しを話題にする (スコアは掲載順: 新しいものほど高い)
+pub fn parse_feed(xml: &str) -> Vec<TrendItem> {
+    let titles: Vec<String> = feed_entries(xml).into_iter().filter_map(|entry| element_text(entry, "title")).take(ITEMS_PER_SOURCE).collect();
+    let n = titles.len() as f64;
+    titles
+        .into_iter()
+        .enumerate()
+        .map(|(i, keyword)| TrendItem { keyword, source: "RSS".to_string(), score: n - i as f64 })
+        .collect()
+}
+
+/// Reddit の指定サブレディットの直近 24 時間の上位投稿
+pub struct RedditTrendSource {
+    subreddits: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl RedditTrendSource {
+    pub fn new(subreddits: Vec<String>) -> Self {
+        Self { subreddits, client: http_client() }
+    }
+}
+
+#[async_trait]
+impl TrendSource for RedditTrendSource {
+    async fn get_trends(&self, _category: &str) -> Result<Vec<TrendItem>, FactoryError> {
+        let url = format!("https://www.reddit.com/r/{}/top.json?t=day&limit={}", self.subreddits.join("+"), ITEMS_PER_SOURCE);
+        let body = fetch_text(&self.client, "Reddit", &url).await?;
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse Reddit listing: {}", e) })?;
+        Ok(parse_reddit_listing(&json))
+    }
+}
+
+/// Google Trends の地域別急上昇ワード (公開 RSS。キー不要)
+pub struct GoogleTrendsSource {
+    geo: String,
+    client: reqwest::Client,
+}
+
+impl GoogleTrendsSource {
+    pub fn new(geo: String) -> Self {
+        Self { geo, client: http_client() }
+    }
+}
+
+#[async_trait]
+impl TrendSource for GoogleTrendsSource {
+    async fn get_trends(&self, _category: &str) -> Result<Vec<TrendItem>, FactoryError> {
+        let url = format!("https://trends.google.com/trending/rss?geo={}", self.geo);
+        let body = fetch_text(&self.client, "Google Trends", &url).await?;
+        Ok(parse_google_trends(&body).into_iter().take(ITEMS_PER_SOURCE).collect())
+    }
+}
+
+/// YouTube の地域別急上昇動画
+pub struct YouTubeTrendingSource {
+    api_key: String,
+    region: String,
+    client: reqwest::Client,
+}
+
+impl YouTubeTrendingSource {
+    pub fn new(api_key: String, region: String) -> Self {
+        Self { api_key, region, client: http_client() }
+    }
+}
+
+#[async_trait]
+impl TrendSource for YouTubeTrendingSource {
+    async fn get_trends(&self, _category: &str) -> Result<Vec<TrendItem>, FactoryError> {
+        let max_results = ITEMS_PER_SOURCE.to_string();
+        let res = self.client.get("https://www.googleapis.com/youtube/v3/videos")
+            .query(&[
+                ("part", "snippet,statistics"),
+                ("chart", "mostPopular"),
+                ("regionCode", self.region.as_str()),
+                ("maxResults", max_results.as_str()),
+                ("key", self.api_key.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| FactoryError::Network { service: "YouTube Data API".into(), reason: e.to_string() })?;
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(FactoryError::from_http_status("YouTube Data API", status.as_u16(), &body));
+        }
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse YouTube trending response: {}", e) })?;
+        Ok(parse_youtube_trending(&json))
+    }
+}
+
+/// 任意の RSS / Atom フィードの最新見出し
+pub struct RssTrendSource {
+    feeds: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl RssTrendSource {
+    pub fn new(feeds: Vec<String>) -> Self {
+        Self { feeds, client: http_client() }
+    }
+}
+
+#[async_trait]
+impl TrendSource for RssTrendSource {
+    async fn get_trends(&self, _category: &str) -> Result<Vec<TrendItem>, FactoryError> {
+        let mut items = Vec::new();
+        let mut last_error = None;
+        for feed in &self.feeds {
+            match fetch_text(&self.client, "RSS", feed).await {
+                Ok(body) => items.extend(parse_feed(&body)),
+                Err(e) => {
+                    warn!("⚠️ TrendSources: RSS feed {} failed: {}", feed, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if items.is_empty() => Err(e),
+            _ => Ok(items),
+        }
+    }
+}
+
+/// 重み付きで複数の情報源をまとめる `TrendSource`
+pub struct CompositeTrendSource {
+    sources: Vec<(String, f64, Arc<dyn TrendSource>)>,
+    max_items: usize,
+}
+
+impl CompositeTrendSource {
+    pub fn new(max_items: usize) -> Self {
+        Self { sources: Vec::new(), max_items }
+    }
+
+    /// 情報源を足す (重みが 0 以下なら足さない)
+    pub fn with_source(mut self, name: &str, weight: f64, source: Arc<dyn TrendSource>) -> Self {
+        if weight > 0.0 {
+            self.sources.push((name.to_string(), weight, source));
+        }
+        self
+    }
+
+    /// `[trends]` の設定から組み立てる (Brave は `brave_api_key` が空なら使わない)
+    pub fn from_config(cfg: &TrendsConfig, brave_api_key: &str, youtube_api_key: &str) -> Self {
+        let mut composite = Self::new(cfg.max_items);
+        if !brave_api_key.is_empty() {
+            composite = composite.with_source("brave", cfg.brave_weight, Arc::new(BraveTrendSonar::new(brave_api_key.to_string())));
+        }
+        if !cfg.reddit_subreddits.is_empty() {
+            composite = composite.with_source("reddit", cfg.reddit_weight, Arc::new(RedditTrendSource::new(cfg.reddit_subreddits.clone())));
+        }
+        if !cfg.google_trends_geo.is_empty() {
+            composite = composite.with_source("google_trends", cfg.google_trends_weight, Arc::new(GoogleTrendsSource::new(cfg.google_trends_geo.clone())));
+        }
+        if !cfg.youtube_region.is_empty() && !youtube_api_key.is_empty() {
+            composite = composite.with_source("youtube", cfg.youtube_weight, Arc::new(YouTubeTrendingSource::new(youtube_api_key.to_string(), cfg.youtube_region.clone())));
+        }
+        if !cfg.rss_feeds.is_empty() {
+            composite = composite.with_source("rss", cfg.rss_weight, Arc::new(RssTrendSource::new(cfg.rss_feeds.clone())));
+        }
+        composite
+    }
+
+    /// 使う情報源の名前
+    pub fn source_names(&self) -> Vec<&str> {
+        self.sources.iter().map(|(name, _, _)| name.as_str()).collect()
+    }
+}
+
+/// 見出しの同一判定用キー (大小文字・空白・記号の違いを無視)
+fn normalize_keyword(keyword: &str) -> String {
+    keyword.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// 情報源ごとに最大値で 0-1 に正規化して重みを掛け、クエリに触れる話題を持ち上げ、
+/// 同じ話題は合算 (出典を `+` で連結) してスコア順に `max_items` 件返す
+pub fn merge_trends(batches: Vec<(f64, Vec<TrendItem>)>, query: &str, max_items: usize) -> Vec<TrendItem> {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).filter(|t| t.chars().count() >= 2).collect();
+    let mut merged: Vec<(String, TrendItem)> = Vec::new();
+    for (weight, items) in batches {
+        let top = items.iter().map(|t| t.score).fold(0.0_f64, f64::max);
+        if top <= 0.0 || weight <= 0.0 {
+            continue;
+        }
+        for item in items {
+            let key = normalize_keyword(&item.keyword);
+            if key.is_empty() {
+                continue;
+            }
+            let mut score = weight * item.score / top;
+            let lower = item.keyword.to_lowercase();
+            if terms.iter().any(|t| lower.contains(t.as_str())) {
+                score *= QUERY_MATCH_BOOST;
+            }
+            match merged.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, existing)) => {
+                    existing.score += score;
+                    if !existing.source.split('+').any(|s| s == item.source) {
+                        existing.source = format!("{}+{}", existing.source, item.source);
+                    }
+                }
+                None => merged.push((key, TrendItem { score, ..item })),
+            }
+        }
+    }
+    let mut items: Vec<TrendItem> = merged.into_iter().map(|(_, item)| item).collect();
+    items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    items.truncate(max_items);
+    items
+}
+
+#[async_trait]
+impl TrendSource for CompositeTrendSource {
+    async fn get_trends(&self, category: &str) -> Result<Vec<TrendItem>, FactoryError> {
+        let results = futures_util::future::join_all(self.sources.iter().map(|(_, _, source)| source.get_trends(category))).await;
+        let mut batches = Vec::new();
+        let mut last_error = None;
+        for ((name, weight, _), result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(items) => {
+                    info!("📡 TrendSources: {} returned {} item(s)", name, items.len());
+                    batches.push((*weight, items));
+                }
+                Err(e) => {
+                    warn!("⚠️ TrendSources: {} failed, continuing without it: {}", name, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if batches.is_empty() => Err(e),
+            _ => Ok(merge_trends(batches, category, self.max_items)),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentAct for CompositeTrendSource {
+    type Input = TrendRequest;
+    type Output = TrendResponse;
+
+    async fn execute(
+        &self,
+        input: Self::Input,
+        _jail: &bastion::fs_guard::Jail,
+    ) -> Result<Self::Output, FactoryError> {
+        let trends = self.get_trends(&input.category).await?;
+        Ok(TrendResponse { items: trends })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(keyword: &str, source: &str, score: f64) -> TrendItem {
+        TrendItem { keyword: keyword.to_string(), source: source.to_string(), score }
+    }
+
+    #[test]
+    fn test_parsers_read_each_source_format() {
+        let reddit = serde_json::json!({"data": {"children": [
+            {"data": {"title": "Pinned rules", "ups": 9999, "stickied": true}},
+            {"data": {"title": "New <b>open</b> model beats GPT", "ups": 4200}},
+        ]}});
+        let items = parse_reddit_listing(&reddit);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].keyword, "New open model beats GPT");
+        assert_eq!(items[0].score, 4200.0);
+
+        let trends = r#"<rss><channel><title>Daily Search Trends</title>
+            <item><title>大谷翔平</title><ht:approx_traffic>200,000+</ht:approx_traffic></item>
+            <item><title><![CDATA[AI 規制]]></title><ht:approx_traffic>5,000+</ht:approx_traffic></item>
+            </channel></rss>"#;
+        let items = parse_google_trends(trends);
+        assert_eq!(items.iter().map(|t| t.keyword.as_str()).collect::<Vec<_>>(), vec!["大谷翔平", "AI 規制"]);
+        assert_eq!(items[0].score, 200000.0);
+
+        let youtube = serde_json::json!({"items": [{"snippet": {"title": "Trailer"}, "statistics": {"viewCount": "1500000"}}]});
+        assert_eq!(parse_youtube_trending(&youtube)[0].score, 1500000.0);
+
+        let atom = r#"<feed><title>Blog</title><entry><title type="html">First &amp; newest</title></entry><entry><title>Older</title></entry></feed>"#;
+        let items = parse_feed(atom);
+        assert_eq!(items[0].keyword, "First & newest");
+        assert!(items[0].score > items[1].score);
+    }
+
+    #[test]
+    fn test_merge_weights_dedupes_and_boosts_query_matches() {
+        let merged = merge_trends(
+            vec![
+                (1.0, vec![item("Robot dogs", "BraveSearch", 1.0)]),
+                (0.5, vec![item("robot dogs!", "Reddit", 300.0), item("Cat memes", "Reddit", 600.0)]),
+                (0.0, vec![item("Ignored", "RSS", 1.0)]),
+                (0.4, vec![item("AI chips shortage", "RSS", 2.0)]),
+            ],
+            "AI",
+            3,
+        );
+        // Brave 1.0 + Reddit 0.5 * 300/600 で合算、出典は連結
+        assert_eq!(merged[0].keyword, "Robot dogs");
+        assert_eq!(merged[0].source, "BraveSearch+Reddit");
+        assert!((merged[0].score - 1.25).abs() < 1e-9);
+        // RSS 0.4 はクエリ一致で 0.6 になり、Reddit の 0.5 を上回る
+        assert_eq!(merged[1].keyword, "AI chips shortage");
+        assert_eq!(merged[2].keyword, "Cat memes");
+        assert_eq!(merged.len(), 3);
+    }
+}
@@ -0,0 +1,216 @@
+//! # VideoEncoder — 映像エンコーダーの選択
+//!
+//! 最終エンコード・リサイズ・Ken Burns は以前 `h264_videotoolbox` 決め打ちで、macOS 以外では動かなかった。
+//! 起動時に videotoolbox → NVENC → VAAPI → libx264 の順で短いテストエンコードを試し、通ったものを使う。
+//! ビットレートの係数や追加引数は `[encoder]` のエンコーダーごとのプリセットで持つ。
+
+use shared::config::{EncoderConfig, EncoderPreset};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoEncoder {
+    VideoToolbox,
+    Nvenc,
+    Vaapi,
+    Libx264,
+}
+
+impl VideoEncoder {
+    /// 自動検出で試す順
+    pub const DETECTION_ORDER: [Self; 4] = [Self::VideoToolbox, Self::Nvenc, Self::Vaapi, Self::Libx264];
+
+    /// `[encoder] preferred` での名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::VideoToolbox => "videotoolbox",
+            Self::Nvenc => "nvenc",
+            Self::Vaapi => "vaapi",
+            Self::Libx264 => "libx264",
+        }
+    }
+
+    /// ffmpeg の `-c:v` に渡すコーデック名
+    pub fn codec(&self) -> &'static str {
+        match self {
+            Self::VideoToolbox => "h264_videotoolbox",
+            Self::Nvenc => "h264_nvenc",
+            Self::Vaapi => "h264_vaapi",
+            Self::Libx264 => "libx264",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::DETECTION_ORDER.into_iter().find(|e| e.name() == name || e.codec() == name)
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        *self != Self::Libx264
+    }
+}
+
+/// 選ばれたエンコーダーとそのプリセット。ffmpeg の引数はここで組み立てる
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncoderProfile {
+    pub encoder: VideoEncoder,
+    pub preset: EncoderPreset,
+    vaapi_device: String,
+}
+
+impl Default for EncoderProfile {
+    /// 検出しない場合は従来どおり videotoolbox
+    fn default() -> Self {
+        Self::new(VideoEncoder::VideoToolbox, &EncoderConfig::default())
+    }
+}
+
+impl EncoderProfile {
+    pub fn new(encoder: VideoEncoder, cfg: &EncoderConfig) -> Self {
+        let preset = match encoder {
+            VideoEncoder::VideoToolbox => &cfg.videotoolbox,
+            VideoEncoder::Nvenc => &cfg.nvenc,
+            VideoEncoder::Vaapi => &cfg.vaapi,
+            VideoEncoder::Libx264 => &cfg.libx264,
+        };
+        Self { encoder, preset: preset.clone(), vaapi_device: cfg.vaapi_device.clone() }
+    }
+
+    /// 入力より前に置く引数 (VAAPI のデバイス指定)
+    pub fn input_args(&self) -> Vec<String> {
+        match self.encoder {
+            VideoEncoder::Vaapi => vec!["-vaapi_device".into(), self.vaapi_device.clone()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// `-vf` の末尾に足すフィルタを付けた最終的なフィルタ列 (VAAPI は GPU へのアップロードが要る)
+    pub fn video_filter(&self, filters: Option<String>) -> Option<String> {
+        let upload = (self.encoder == VideoEncoder::Vaapi).then_some("format=nv12,hwupload");
+        match (filters.filter(|f| !f.is_empty()), upload) {
+            (Some(f), Some(u)) => Some(format!("{},{}", f, u)),
+            (Some(f), None) => Some(f),
+            (None, u) => u.map(str::to_string),
+        }
+    }
+
+    /// `-c:v` 以降の映像出力引数。VAAPI はアップロード時に画素形式が決まるので `-pix_fmt` を付けない
+    pub fn output_args(&self, kbps: u32) -> Vec<String> {
+        let mut args = vec!["-c:v".to_string(), self.encoder.codec().to_string()];
+        args.extend(self.preset.extra_args.iter().cloned());
+        args.extend(["-b:v".to_string(), format!("{}k", kbps)]);
+        if self.encoder != VideoEncoder::Vaapi {
+            args.extend(["-pix_fmt".to_string(), "yuv420p".to_string()]);
+        }
+        args
+    }
+
+    /// 最終エンコードの目標ビットレートをエンコーダーの係数で補正する
+    pub fn final_kbps(&self, kbps: u32) -> u32 {
+        ((kbps as f64 * self.preset.bitrate_scale).round() as u32).max(1)
+    }
+
+    /// 中間ファイル (リサイズ・Ken Burns) のビットレート
+    pub fn intermediate_kbps(&self) -> u32 {
+        self.preset.intermediate_kbps
+    }
+
+    /// `preferred` が名前ならそれを使い、"auto" なら使えるものを順に探す (何も通らなければ libx264)
+    pub async fn detect(cfg: &EncoderConfig) -> Self {
+        if !cfg.preferred.trim().eq_ignore_ascii_case("auto") {
+            match VideoEncoder::from_name(&cfg.preferred) {
+                Some(encoder) => {
+                    info!("🎞️ VideoEncoder: using {} (configured)", encoder.codec());
+                    return Self::new(encoder, cfg);
+                }
+                None => warn!("⚠️ VideoEncoder: unknown encoder '{}', detecting instead", cfg.preferred),
+            }
+        }
+        let listed = match Command::new("ffmpeg").arg("-hide_banner").arg("-encoders").stdin(Stdio::null()).output().await {
+            Ok(out) => String::from_utf8_lossy(&out.stdout).to_string(),
+            Err(e) => {
+                warn!("⚠️ VideoEncoder: ffmpeg not runnable ({}), assuming libx264", e);
+                return Self::new(VideoEncoder::Libx264, cfg);
+            }
+        };
+        let available = parse_encoder_list(&listed);
+        for encoder in VideoEncoder::DETECTION_ORDER {
+            if !available.iter().any(|c| c == encoder.codec()) {
+                continue;
+            }
+            let profile = Self::new(encoder, cfg);
+            if profile.test_encode().await {
+                info!("🎞️ VideoEncoder: using {}", encoder.codec());
+                return profile;
+            }
+            info!("🎞️ VideoEncoder: {} is listed but failed a test encode, skipping", encoder.codec());
+        }
+        warn!("⚠️ VideoEncoder: no encoder passed a test encode, falling back to libx264");
+        Self::new(VideoEncoder::Libx264, cfg)
+    }
+
+    /// 黒画面 1 フレームを捨て先にエンコードしてみる (ドライバやデバイスが無ければここで落ちる)
+    async fn test_encode(&self) -> bool {
+        let mut cmd = Command::new("ffmpeg");
+        cmd.arg("-hide_banner").arg("-loglevel").arg("error").arg("-y");
+        cmd.args(self.input_args());
+        cmd.arg("-f").arg("lavfi").arg("-i").arg("color=c=black:s=256x256:d=0.1");
+        if let Some(filter) = self.video_filter(None) {
+            cmd.arg("-vf").arg(filter);
+        }
+        cmd.args(self.output_args(1000));
+        cmd.arg("-frames:v").arg("1").arg("-f").arg("null").arg("-").stdin(Stdio::null());
+        cmd.output().await.map(|out| out.status.success()).unwrap_or(false)
+    }
+}
+
+/// `ffmpeg -encoders` の出力から映像エンコーダー名を取り出す (` V....D h264_nvenc  NVIDIA ...` の形式)。
+/// 先頭の凡例 (` V..... = Video`) は `------` の区切りより前なので読み飛ばす
+fn parse_encoder_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let flags = parts.next()?;
+            let name = parts.next()?;
+            (flags.len() == 6 && flags.starts_with('V')).then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_encoder_list_keeps_video_encoders() {
+        let listed = "Encoders:\n V..... = Video\n ------\n V....D libx264              libx264 H.264 / AVC\n V....D h264_nvenc           NVIDIA NVENC H.264 encoder\n V....D h264_vaapi           H.264/AVC (VAAPI)\n A....D aac                  AAC (Advanced Audio Coding)\n";
+        assert_eq!(parse_encoder_list(listed), vec!["libx264", "h264_nvenc", "h264_vaapi"]);
+    }
+
+    #[test]
+    fn test_profile_args_per_encoder() {
+        let cfg = EncoderConfig::default();
+        let vt = EncoderProfile::default();
+        assert_eq!(vt.output_args(6000), vec!["-c:v", "h264_videotoolbox", "-b:v", "6000k", "-pix_fmt", "yuv420p"]);
+        assert!(vt.input_args().is_empty());
+        assert_eq!(vt.video_filter(None), None);
+
+        let nvenc = EncoderProfile::new(VideoEncoder::Nvenc, &cfg);
+        assert_eq!(&nvenc.output_args(8000)[..4], ["-c:v", "h264_nvenc", "-preset", "p5"]);
+
+        let vaapi = EncoderProfile::new(VideoEncoder::Vaapi, &cfg);
+        assert_eq!(vaapi.input_args(), vec!["-vaapi_device", "/dev/dri/renderD128"]);
+        assert_eq!(vaapi.video_filter(Some("scale=1080:1920".into())).unwrap(), "scale=1080:1920,format=nv12,hwupload");
+        assert_eq!(vaapi.video_filter(None).unwrap(), "format=nv12,hwupload");
+        assert!(!vaapi.output_args(8000).contains(&"-pix_fmt".to_string()));
+
+        let x264 = EncoderProfile::new(VideoEncoder::Libx264, &cfg);
+        assert_eq!(x264.final_kbps(6000), 5100);
+        assert_eq!(x264.intermediate_kbps(), 6000);
+        assert_eq!(VideoEncoder::from_name("H264_NVENC"), Some(VideoEncoder::Nvenc));
+        assert_eq!(VideoEncoder::from_name("qsv"), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! # VisionJudge — 生成シーンの画像品質ゲート
+//!
+//! シーンのフレームを Gemini Vision に渡し、ビジュアルプロンプトとの一致度と
+//! 破綻 (崩れた手・文字化け・無関係な被写体など) を採点させる。
+
+use base64::Engine as _;
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// 採点結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct VisionVerdict {
+    /// 0.0 (全く一致しない / 破綻) - 1.0 (完全に一致)
+    pub score: f32,
+    /// 検出された問題 (再生成時のプロンプト補正に使う)
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+impl VisionVerdict {
+    /// LLM の応答 (コードフェンス付きでも可) から採点結果を取り出す
+    pub fn parse(text: &str) -> Result<Self, FactoryError> {
+        let start = text.find('{');
+        let end = text.rfind('}');
+        let json = match (start, end) {
+            (Some(s), Some(e)) if s < e => &text[s..=e],
+            _ => return Err(FactoryError::Infrastructure { reason: format!("Vision judge returned no JSON: {}", text) }),
+        };
+        let mut verdict: Self = serde_json::from_str(json)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse vision verdict: {}", e) })?;
+        verdict.score = verdict.score.clamp(0.0, 1.0);
+        Ok(verdict)
+    }
+}
+
+pub struct VisionJudge {
+    api_key: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl VisionJudge {
+    pub fn new(api_key: &str, model: &str) -> Result<Self, FactoryError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to build VisionJudge HTTP client: {}", e) })?;
+        Ok(Self { api_key: api_key.to_string(), model: model.to_string(), client })
+    }
+
+    /// フレーム群をプロンプトに照らして採点する
+    pub async fn score(&self, frames: &[PathBuf], visual_prompt: &str) -> Result<VisionVerdict, FactoryError> {
+        if self.api_key.is_empty() {
+            return Err(FactoryError::Infrastructure { reason: "Gemini API Key is missing".to_string() });
+        }
+
+        let prompt = format!(
+            "You are a strict quality inspector for AI-generated short-video scenes.\n\
+             The scene was generated from this prompt:\n{}\n\n\
+             Look at the attached frame(s) and rate how well they match the prompt and whether they are free of \
+             generation defects (malformed hands or faces, extra limbs, garbled text, off-topic subjects).\n\
+             Respond with JSON only: {{\"score\": <0.0-1.0>, \"issues\": [\"short description\", ...]}}",
+            visual_prompt
+        );
+        let text = generate_with_images(&self.client, &self.api_key, &self.model, &prompt, frames).await?;
+        let verdict = VisionVerdict::parse(&text)?;
+        info!("👁️ VisionJudge: score {:.2} ({} frame(s), {} issue(s))", verdict.score, frames.len(), verdict.issues.len());
+        Ok(verdict)
+    }
+}
+
+/// Gemini に画像を添付してテキスト (JSON 指定) を生成させる
+pub(crate) async fn generate_with_images(
+    client: &reqwest::Client,
+    api_key: &str,
+    model: &str,
+    prompt: &str,
+    images: &[PathBuf],
+) -> Result<String, FactoryError> {
+    let mut parts = vec![serde_json::json!({ "text": prompt })];
+    for image in images {
+        let bytes = tokio::fs::read(image).await
+            .map_err(|_| FactoryError::MediaNotFound { path: image.display().to_string() })?;
+        let mime = match image.extension().and_then(|e| e.to_str()) {
+            Some("png") => "image/png",
+            Some("webp") => "image/webp",
+            _ => "image/jpeg",
+        };
+        parts.push(serde_json::json!({
+            "inline_data": { "mime_type": mime, "data": base64::engine::general_purpose::STANDARD.encode(bytes) }
+        }));
+    }
+
+    let url = format!(
+        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
+        model, api_key
+    );
+    let res = client
+        .post(&url)
+        .json(&serde_json::json!({
+            "contents": [{ "parts": parts }],
+            "generationConfig": { "responseMimeType": "application/json", "temperature": 0.0 }
+        }))
+        .send()
+        .await
+        .map_err(|e| FactoryError::Network { service: "Gemini Vision".into(), reason: e.to_string() })?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let body = res.text().await.unwrap_or_default();
+        return Err(FactoryError::from_http_status("Gemini Vision", status.as_u16(), &body));
+    }
+    let body: serde_json::Value = res.json().await
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to parse Gemini response: {}", e) })?;
+    body.pointer("/candidates/0/content/parts/0/text")
+        .and_then(|t| t.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| FactoryError::Infrastructure { reason: "Gemini Vision response has no text".to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verdict_tolerates_fences_and_clamps() {
+        let v = VisionVerdict::parse("```json\n{\"score\": 1.4, \"issues\": [\"six fingers\"]}\n```").unwrap();
+        assert_eq!(v.score, 1.0);
+        assert_eq!(v.issues, vec!["six fingers".to_string()]);
+        assert!(VisionVerdict::parse("no json here").is_err());
+    }
+}
@@ -74,7 +74,7 @@ impl VoiceActor {
     }
 
     /// テキストを文単位で分割する
-    fn split_into_sentences(text: &str) -> Vec<String> {
+    pub fn split_into_sentences(text: &str) -> Vec<String> {
         let mut sentences = Vec::new();
         let mut current = String::new();
 
@@ -162,14 +162,16 @@ impl AgentAct for VoiceActor {
         });
 
         let response = self.client.post(&url).json(&body).send().await
-            .map_err(|e| FactoryError::TtsFailure {
-                reason: format!("Failed to connect to TTS: {}", e),
-            })?;
+            .map_err(|e| FactoryError::Network { service: "TTS".into(), reason: e.to_string() })?;
 
         if !response.status().is_success() {
             let status = response.status();
             let err_body = response.text().await.unwrap_or_default();
             error!("TTS Server Error [{}]: {}", status, err_body);
+            // 混雑・再起動中は再試行に回し、入力が原因の拒否 (4xx) だけを TTS 破壊とみなす
+            if status.as_u16() == 429 || status.is_server_error() {
+                return Err(FactoryError::from_http_status("TTS", status.as_u16(), &err_body));
+            }
             return Err(FactoryError::TtsFailure {
                 reason: format!("TTS Server Error [{}]: {}", status, err_body),
             });
@@ -0,0 +1,179 @@
+//! # Watch Folder — 手動レンダーの取り込み口
+//!
+//! 監視ディレクトリ直下に置かれた動画を見つけ、書き込みが落ち着いたものから
+//! `.pending/` に退避して取り込み ID を振る。トピックとスタイルが決まったら `claim` で
+//! `.claimed/` に移し、そのパスを持ち込み映像としてジョブに渡す。
+//! 状態はファイル名だけで表すので、再起動しても取り込み待ちは失われない。
+
+use bastion::fs_guard::Jail;
+use factory_core::error::FactoryError;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// 取り込み対象の拡張子 (持ち込み映像として扱えるもの)
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "webm", "mkv"];
+
+const PENDING_DIR: &str = ".pending";
+const CLAIMED_DIR: &str = ".claimed";
+/// `.pending/{id}__{元のファイル名}`
+const ID_SEPARATOR: &str = "__";
+
+/// トピック待ちのレンダー
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRender {
+    pub id: String,
+    /// 置かれたときのファイル名
+    pub file_name: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+pub struct WatchFolder {
+    inbox: PathBuf,
+    settle: Duration,
+    /// `.claimed/` の檻 (ジョブが持ち込み映像を読むときはここを通す)
+    claimed: Jail,
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> FactoryError {
+    FactoryError::Infrastructure { reason: format!("Watch folder: failed to {} {}: {}", action, path.display(), e) }
+}
+
+impl WatchFolder {
+    /// 監視ディレクトリ (と作業用サブディレクトリ) を用意する
+    pub fn open(inbox: impl Into<PathBuf>, settle: Duration) -> Result<Self, FactoryError> {
+        let inbox = inbox.into();
+        for dir in [inbox.join(PENDING_DIR), inbox.join(CLAIMED_DIR)] {
+            std::fs::create_dir_all(&dir).map_err(|e| io_error("create", &dir, e))?;
+        }
+        let claimed_dir = inbox.join(CLAIMED_DIR);
+        let claimed = Jail::new(&claimed_dir).map_err(|e| io_error("jail", &claimed_dir, e))?;
+        Ok(Self { inbox, settle, claimed })
+    }
+
+    pub fn inbox(&self) -> &Path {
+        &self.inbox
+    }
+
+    /// `claim` したファイルを読むための檻
+    pub fn claimed_jail(&self) -> &Jail {
+        &self.claimed
+    }
+
+    /// 書き込みの終わった新着動画を `.pending/` に移し、取り込み待ちとして返す
+    ///
+    /// 隠しファイル・対象外の拡張子・`settle` 以内に更新されたファイルは触らない。
+    pub fn collect(&self, now: SystemTime) -> Result<Vec<PendingRender>, FactoryError> {
+        let entries = std::fs::read_dir(&self.inbox).map_err(|e| io_error("read", &self.inbox, e))?;
+        let mut found = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+                continue;
+            };
+            let is_video = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            if file_name.starts_with('.') || !is_video {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            if !meta.is_file() || meta.len() == 0 {
+                continue;
+            }
+            let settled = meta
+                .modified()
+                .ok()
+                .and_then(|m| now.duration_since(m).ok())
+                .is_some_and(|age| age >= self.settle);
+            if !settled {
+                continue;
+            }
+
+            let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+            let dest = self.inbox.join(PENDING_DIR).join(format!("{}{}{}", id, ID_SEPARATOR, file_name));
+            if let Err(e) = std::fs::rename(&path, &dest) {
+                warn!("⚠️ Watch folder: could not move {} aside: {}", path.display(), e);
+                continue;
+            }
+            info!("🎞️ Watch folder: picked up {} as {}", file_name, id);
+            found.push(PendingRender { id, file_name, path: dest, size_bytes: meta.len() });
+        }
+        Ok(found)
+    }
+
+    /// トピック待ちの一覧 (古い順)
+    pub fn pending(&self) -> Vec<PendingRender> {
+        let dir = self.inbox.join(PENDING_DIR);
+        let mut renders: Vec<(SystemTime, PendingRender)> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let name = path.file_name()?.to_str()?;
+                let (id, file_name) = name.split_once(ID_SEPARATOR)?;
+                let meta = entry.metadata().ok()?;
+                let render = PendingRender {
+                    id: id.to_string(),
+                    file_name: file_name.to_string(),
+                    path: path.clone(),
+                    size_bytes: meta.len(),
+                };
+                Some((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), render))
+            })
+            .collect();
+        renders.sort_by_key(|(modified, _)| *modified);
+        renders.into_iter().map(|(_, r)| r).collect()
+    }
+
+    /// 取り込み待ちを確定し、`.claimed/` に移したファイルの絶対パスを返す
+    /// (ジョブが持ち込み映像としてプロジェクトへコピーするまでここに残る)
+    pub fn claim(&self, id: &str) -> Result<PathBuf, FactoryError> {
+        let render = self
+            .pending()
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or_else(|| FactoryError::Infrastructure { reason: format!("No pending render with id '{}'", id) })?;
+        let dest = self.inbox.join(CLAIMED_DIR).join(render.path.file_name().unwrap_or_default());
+        std::fs::rename(&render.path, &dest).map_err(|e| io_error("claim", &render.path, e))?;
+        dest.canonicalize().map_err(|e| io_error("resolve", &dest, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_waits_for_settled_videos_then_claims() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = WatchFolder::open(dir.path(), Duration::from_secs(10)).unwrap();
+        std::fs::write(dir.path().join("comfy_00001.mp4"), b"video").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"ignored").unwrap();
+        std::fs::write(dir.path().join(".partial.mp4"), b"ignored").unwrap();
+
+        // Still being written
+        assert!(folder.collect(SystemTime::now()).unwrap().is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        let found = folder.collect(later).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].file_name, "comfy_00001.mp4");
+        assert!(!dir.path().join("comfy_00001.mp4").exists());
+        assert!(folder.collect(later).unwrap().is_empty());
+
+        // Survives a restart: the pending list is read back from the file names
+        let reopened = WatchFolder::open(dir.path(), Duration::from_secs(10)).unwrap();
+        assert_eq!(reopened.pending(), found);
+
+        let claimed = reopened.claim(&found[0].id).unwrap();
+        assert!(claimed.ends_with(format!(".claimed/{}__comfy_00001.mp4", found[0].id)));
+        assert!(reopened.pending().is_empty());
+        assert!(reopened.claim(&found[0].id).is_err());
+        assert!(reopened.claimed_jail().open_file(&claimed).is_ok());
+        assert!(reopened.claimed_jail().open_file(dir.path().join("notes.txt")).is_err());
+    }
+}
@@ -0,0 +1,494 @@
+//! # WorkflowDoctor — ワークフロー JSON の互換性チェック
+//!
+//! ComfyUI の更新でノードの入力名が変わると、`resources/workflows` の JSON は投入時に初めて落ちる。
+//! 稼働中の ComfyUI の `/object_info` とノードを突き合わせ、未知のノード・欠けた入力・消えた入力・
+//! 選択肢に無い値を洗い出す。消えた入力と欠けた必須入力が 1 つずつで型も合う場合は改名とみなし、
+//! 移行 (入力名の付け替え) を提案する。
+//!
+//! 結果は `resources/workflows/.registry.json` に記録し、Samsara は壊れたワークフローをスタイルに選ばない。
+//!
+//! ComfyUI に繋がなくてもできる構造チェック (`lint_workflow`: タイトル付きノード・ノード参照) は
+//! `validate-workflows` と起動時に走らせる。
+
+use factory_core::error::FactoryError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// チェック結果の記録先 (ワークフローと同じディレクトリに置く)
+pub const REGISTRY_FILE: &str = ".registry.json";
+
+/// `run_workflow` がプロンプトを差し込むのに必須のノード
+const REQUIRED_MARKERS: &[&str] = &["[API_PROMPT]"];
+
+/// 構造チェックで要求するタイトル付きノード (プロンプト・シード・出力名の注入先)
+const LINT_MARKERS: &[&str] = &["[API_PROMPT]", "[API_SAMPLER]", "[API_SAVE]"];
+
+/// 後処理ワークフロー (アップスケール等、画像を受けてサンプラーを持たない) で要求するノード
+const POST_PROCESS_MARKERS: &[&str] = &["[API_IMAGE_INPUT]", "[API_SAVE]"];
+
+/// `[API_IMAGE_INPUT]` があってサンプラーが無いワークフローはプロンプトを取らない後処理とみなす
+fn is_post_process(nodes: &serde_json::Map<String, Value>) -> bool {
+    let has_input = nodes.values().any(|n| n.pointer("/_meta/title").and_then(|t| t.as_str()) == Some("[API_IMAGE_INPUT]"));
+    let has_sampler = nodes.values().any(|n| n.get("class_type").and_then(|c| c.as_str()).is_some_and(|c| c.contains("Sampler")));
+    has_input && !has_sampler
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IssueKind {
+    /// ComfyUI が知らないノード (カスタムノード未導入・改名)
+    UnknownNode,
+    /// 必須入力が JSON に無い
+    MissingInput,
+    /// ノード定義に無い入力
+    UnknownInput,
+    /// 選択肢 (モデル名・サンプラー名など) に無い値
+    InvalidChoice,
+    /// ファクトリーが注入に使うタイトル付きノードが無い
+    MissingMarker,
+    /// JSON として読めない・ノードに class_type が無いなど
+    Malformed,
+    /// 入力のリンク `[node, slot]` が存在しないノードを指している
+    BrokenLink,
+}
+
+/// 入力名の付け替え
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Migration {
+    pub node_id: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowIssue {
+    pub node_id: String,
+    pub class_type: String,
+    pub kind: IssueKind,
+    pub message: String,
+}
+
+impl WorkflowIssue {
+    /// 人が読む 1 行 (ノードに紐づかない問題はメッセージのみ)
+    pub fn describe(&self) -> String {
+        if self.node_id.is_empty() {
+            self.message.clone()
+        } else if self.class_type.is_empty() {
+            format!("node {}: {}", self.node_id, self.message)
+        } else {
+            format!("node {} ({}): {}", self.node_id, self.class_type, self.message)
+        }
+    }
+}
+
+/// 1 本のワークフローの診断結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowReport {
+    pub issues: Vec<WorkflowIssue>,
+    /// `--fix` で自動適用できる移行
+    pub migrations: Vec<Migration>,
+}
+
+impl WorkflowReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 移行を当てた後も残る問題
+    pub fn unresolved(&self) -> Vec<&WorkflowIssue> {
+        self.issues
+            .iter()
+            .filter(|i| !self.migrations.iter().any(|m| {
+                m.node_id == i.node_id
+                    && matches!(i.kind, IssueKind::UnknownInput | IssueKind::MissingInput)
+                    && (i.message.contains(&format!("'{}'", m.from)) || i.message.contains(&format!("'{}'", m.to)))
+            }))
+            .collect()
+    }
+}
+
+/// ノード定義の入力仕様 `["INT", {...}]` / `[["a", "b"], {...}]` / `["COMBO", {"options": [...]}]` から選択肢を取り出す
+fn choices(spec: &Value) -> Option<Vec<&str>> {
+    let head = spec.get(0)?;
+    let list = match head {
+        Value::Array(list) => list,
+        Value::String(t) if t == "COMBO" => spec.get(1)?.get("options")?.as_array()?,
+        _ => return None,
+    };
+    Some(list.iter().filter_map(|v| v.as_str()).collect())
+}
+
+/// 入力値がその仕様の型に入りうるか (リンク `[node, slot]` はどの入力にも繋がる)
+fn fits(value: &Value, spec: &Value) -> bool {
+    let is_link = value.as_array().is_some_and(|a| a.len() == 2 && a[0].is_string() && a[1].is_u64());
+    if is_link {
+        return true;
+    }
+    if choices(spec).is_some() {
+        return value.is_string();
+    }
+    match spec.get(0).and_then(|t| t.as_str()) {
+        Some("INT") | Some("FLOAT") => value.is_number(),
+        Some("STRING") => value.is_string(),
+        Some("BOOLEAN") => value.is_boolean(),
+        _ => false,
+    }
+}
+
+/// 1 ノード分の入力仕様 (required と optional を合わせたもの)
+fn input_specs(def: &Value) -> (BTreeMap<&str, &Value>, BTreeMap<&str, &Value>) {
+    let section = move |name: &str| -> BTreeMap<&str, &Value> {
+        def.get("input")
+            .and_then(|i| i.get(name))
+            .and_then(|s| s.as_object())
+            .map(|m| m.iter().map(|(k, v)| (k.as_str(), v)).collect())
+            .unwrap_or_default()
+    };
+    let required = section("required");
+    let mut known = section("optional");
+    known.extend(section("hidden"));
+    known.extend(required.clone());
+    (required, known)
+}
+
+/// ワークフロー (API 形式) を `/object_info` と突き合わせる
+pub fn check_workflow(workflow: &Value, object_info: &Value) -> WorkflowReport {
+    let mut report = WorkflowReport::default();
+    let Some(nodes) = workflow.as_object() else {
+        report.issues.push(WorkflowIssue {
+            node_id: String::new(),
+            class_type: String::new(),
+            kind: IssueKind::UnknownNode,
+            message: "workflow is not an API-format JSON object".to_string(),
+        });
+        return report;
+    };
+
+    let required_markers = if is_post_process(nodes) { POST_PROCESS_MARKERS } else { REQUIRED_MARKERS };
+    for marker in required_markers {
+        let found = nodes.values().any(|n| n.pointer("/_meta/title").and_then(|t| t.as_str()) == Some(*marker));
+        if !found {
+            report.issues.push(WorkflowIssue {
+                node_id: String::new(),
+                class_type: String::new(),
+                kind: IssueKind::MissingMarker,
+                message: format!("no node titled {}", marker),
+            });
+        }
+    }
+
+    for (node_id, node) in nodes {
+        let Some(class_type) = node.get("class_type").and_then(|c| c.as_str()) else { continue };
+        let issue = |kind, message: String| WorkflowIssue {
+            node_id: node_id.clone(),
+            class_type: class_type.to_string(),
+            kind,
+            message,
+        };
+        let Some(def) = object_info.get(class_type) else {
+            let hint = closest_class(class_type, object_info)
+                .map(|c| format!(" (did you mean '{}'?)", c))
+                .unwrap_or_default();
+            report.issues.push(issue(IssueKind::UnknownNode, format!("node type '{}' is not installed{}", class_type, hint)));
+            continue;
+        };
+        let (required, known) = input_specs(def);
+        let inputs = node.get("inputs").and_then(|i| i.as_object()).cloned().unwrap_or_default();
+
+        let unknown: Vec<&String> = inputs.keys().filter(|k| !known.contains_key(k.as_str())).collect();
+        let missing: Vec<&str> = required.keys().copied().filter(|k| !inputs.contains_key(*k)).collect();
+        for name in &unknown {
+            report.issues.push(issue(IssueKind::UnknownInput, format!("input '{}' no longer exists", name)));
+        }
+        for name in &missing {
+            report.issues.push(issue(IssueKind::MissingInput, format!("required input '{}' is missing", name)));
+        }
+        // 消えた入力と欠けた入力が 1 つずつで、値が新しい入力の型に合えば改名とみなす
+        if let ([from], [to]) = (unknown.as_slice(), missing.as_slice()) {
+            if fits(&inputs[from.as_str()], required[to]) {
+                report.migrations.push(Migration { node_id: node_id.clone(), from: from.to_string(), to: to.to_string() });
+            }
+        }
+
+        for (name, value) in &inputs {
+            let Some(options) = known.get(name.as_str()).and_then(|spec| choices(spec)) else { continue };
+            if let Some(v) = value.as_str() {
+                if !options.contains(&v) {
+                    report.issues.push(issue(IssueKind::InvalidChoice, format!("'{}' is not a valid {} (not installed or renamed)", v, name)));
+                }
+            }
+        }
+    }
+    report
+}
+
+/// ComfyUI 無しでできる構造チェック: タイトル付きノードの有無・重複と、ノード参照の解決
+pub fn lint_workflow(workflow: &Value) -> WorkflowReport {
+    let mut report = WorkflowReport::default();
+    let issue = |node_id: &str, class_type: &str, kind, message: String| WorkflowIssue {
+        node_id: node_id.to_string(),
+        class_type: class_type.to_string(),
+        kind,
+        message,
+    };
+    let Some(nodes) = workflow.as_object() else {
+        report.issues.push(issue("", "", IssueKind::Malformed, "workflow is not an API-format JSON object".to_string()));
+        return report;
+    };
+
+    let lint_markers = if is_post_process(nodes) { POST_PROCESS_MARKERS } else { LINT_MARKERS };
+    for marker in lint_markers {
+        let count = nodes.values().filter(|n| n.pointer("/_meta/title").and_then(|t| t.as_str()) == Some(*marker)).count();
+        match count {
+            0 => report.issues.push(issue("", "", IssueKind::MissingMarker, format!("no node titled {}", marker))),
+            1 => {}
+            n => report.issues.push(issue("", "", IssueKind::Malformed, format!("{} nodes are titled {} (only the first one is used)", n, marker))),
+        }
+    }
+
+    for (node_id, node) in nodes {
+        let Some(class_type) = node.get("class_type").and_then(|c| c.as_str()) else {
+            report.issues.push(issue(node_id, "", IssueKind::Malformed, "node has no class_type".to_string()));
+            continue;
+        };
+        let Some(inputs) = node.get("inputs").and_then(|i| i.as_object()) else {
+            report.issues.push(issue(node_id, class_type, IssueKind::Malformed, "node has no inputs object".to_string()));
+            continue;
+        };
+        for (name, value) in inputs {
+            let Some([target, slot]) = value.as_array().map(|a| a.as_slice()) else { continue };
+            let Some(target) = target.as_str() else { continue };
+            if !slot.is_u64() {
+                continue;
+            }
+            if !nodes.contains_key(target) {
+                report.issues.push(issue(node_id, class_type, IssueKind::BrokenLink, format!("input '{}' links to missing node {}", name, target)));
+            } else if target == node_id {
+                report.issues.push(issue(node_id, class_type, IssueKind::BrokenLink, format!("input '{}' links to its own node", name)));
+            }
+        }
+    }
+    report
+}
+
+/// `dir` の全ワークフローを構造チェックする (読めない・JSON でないものも Malformed として返す)
+pub fn lint_dir(dir: &Path) -> Result<Vec<(String, WorkflowReport)>, FactoryError> {
+    let mut results = Vec::new();
+    for (id, path) in list_workflows(dir)? {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str::<Value>(&s).map_err(|e| format!("invalid JSON: {}", e)));
+        let report = match parsed {
+            Ok(workflow) => lint_workflow(&workflow),
+            Err(message) => WorkflowReport {
+                issues: vec![WorkflowIssue { node_id: String::new(), class_type: String::new(), kind: IssueKind::Malformed, message }],
+                migrations: Vec::new(),
+            },
+        };
+        results.push((id, report));
+    }
+    Ok(results)
+}
+
+/// 移行を当てる。当てた件数を返す
+pub fn apply_migrations(workflow: &mut Value, migrations: &[Migration]) -> usize {
+    let mut applied = 0;
+    for m in migrations {
+        let Some(inputs) = workflow.get_mut(&m.node_id).and_then(|n| n.get_mut("inputs")).and_then(|i| i.as_object_mut()) else {
+            continue;
+        };
+        if inputs.contains_key(&m.to) {
+            continue;
+        }
+        if let Some(value) = inputs.remove(&m.from) {
+            inputs.insert(m.to.clone(), value);
+            applied += 1;
+        }
+    }
+    applied
+}
+
+/// 大文字小文字を無視して最も近いノード名 (編集距離 3 以内)
+fn closest_class<'a>(class_type: &str, object_info: &'a Value) -> Option<&'a str> {
+    let target = class_type.to_lowercase();
+    object_info
+        .as_object()?
+        .keys()
+        .map(|k| (edit_distance(&target, &k.to_lowercase()), k.as_str()))
+        .filter(|(d, _)| *d <= 3)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// ワークフローごとの最新のチェック結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub ok: bool,
+    /// 未解決の問題 (人が読む用)
+    #[serde(default)]
+    pub problems: Vec<String>,
+}
+
+/// `resources/workflows/.registry.json`: どのワークフローが今の ComfyUI で動くか
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowRegistry {
+    pub checked_at: String,
+    pub workflows: BTreeMap<String, RegistryEntry>,
+}
+
+impl WorkflowRegistry {
+    /// 記録が無い・読めない場合は空 (= 全ワークフローを使える扱い)
+    pub fn load(dir: &Path) -> Self {
+        std::fs::read_to_string(dir.join(REGISTRY_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), FactoryError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to serialize workflow registry: {}", e) })?;
+        std::fs::write(dir.join(REGISTRY_FILE), json)
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to write workflow registry: {}", e) })
+    }
+
+    /// 直近のチェックで壊れていると分かったワークフローか
+    pub fn is_broken(&self, workflow_id: &str) -> bool {
+        self.workflows.get(workflow_id).is_some_and(|e| !e.ok)
+    }
+
+    pub fn record(&mut self, workflow_id: &str, report: &WorkflowReport) {
+        let problems: Vec<String> = report.issues.iter().map(WorkflowIssue::describe).collect();
+        self.workflows.insert(workflow_id.to_string(), RegistryEntry { ok: problems.is_empty(), problems });
+    }
+}
+
+/// `dir` 直下のワークフロー (`<id>.json`) を ID 順に列挙する (レジストリ自身は除く)
+pub fn list_workflows(dir: &Path) -> Result<Vec<(String, std::path::PathBuf)>, FactoryError> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read {}: {}", dir.display(), e) })?;
+    let mut list: Vec<(String, std::path::PathBuf)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter(|p| p.file_name().is_some_and(|n| n != REGISTRY_FILE))
+        .filter_map(|p| Some((p.file_stem()?.to_str()?.to_string(), p)))
+        .collect();
+    list.sort();
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object_info() -> Value {
+        json!({
+            "KSampler": {"input": {"required": {
+                "model": ["MODEL"],
+                "noise_seed": ["INT", {"default": 0}],
+                "steps": ["INT", {"default": 20}],
+                "sampler_name": [["euler", "euler_ancestral"]],
+            }}},
+            "CLIPTextEncode": {"input": {"required": {"text": ["STRING", {"multiline": true}], "clip": ["CLIP"]}}},
+            "CheckpointLoaderSimple": {"input": {"required": {"ckpt_name": ["COMBO", {"options": ["a.safetensors"]}]}}},
+        })
+    }
+
+    #[test]
+    fn test_check_finds_renames_and_broken_nodes() {
+        let mut workflow = json!({
+            "3": {"class_type": "KSampler", "inputs": {
+                "model": ["4", 0], "seed": 1, "steps": 25, "sampler_name": "euler_ancestral"
+            }},
+            "6": {"class_type": "CLIPTextEncode", "_meta": {"title": "[API_PROMPT]"}, "inputs": {"text": "x", "clip": ["4", 1]}},
+            "4": {"class_type": "CheckpointLoaderSimple", "inputs": {"ckpt_name": "missing.safetensors"}},
+            "8": {"class_type": "VAEDecod", "inputs": {}},
+        });
+        let info = object_info();
+        let report = check_workflow(&workflow, &info);
+        assert_eq!(report.migrations, vec![Migration { node_id: "3".into(), from: "seed".into(), to: "noise_seed".into() }]);
+        let kinds: Vec<IssueKind> = report.unresolved().iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec![IssueKind::InvalidChoice, IssueKind::UnknownNode]);
+
+        assert_eq!(apply_migrations(&mut workflow, &report.migrations), 1);
+        assert_eq!(workflow["3"]["inputs"]["noise_seed"], json!(1));
+        let after = check_workflow(&workflow, &info);
+        assert!(after.migrations.is_empty());
+        assert_eq!(after.issues.len(), 2);
+    }
+
+    #[test]
+    fn test_type_mismatch_is_not_a_rename_and_marker_is_required() {
+        let workflow = json!({
+            "3": {"class_type": "KSampler", "inputs": {"model": ["4", 0], "seed": "abc", "steps": 25, "sampler_name": "euler"}},
+        });
+        let report = check_workflow(&workflow, &object_info());
+        assert!(report.migrations.is_empty());
+        assert!(report.issues.iter().any(|i| i.kind == IssueKind::MissingMarker));
+
+        let mut registry = WorkflowRegistry::default();
+        registry.record("broken_v1", &report);
+        registry.record("fine_v1", &WorkflowReport::default());
+        assert!(registry.is_broken("broken_v1"));
+        assert!(!registry.is_broken("fine_v1"));
+        assert!(!registry.is_broken("unknown"));
+    }
+
+    #[test]
+    fn test_lint_checks_markers_and_links() {
+        let workflow = json!({
+            "3": {"class_type": "KSampler", "_meta": {"title": "[API_SAMPLER]"}, "inputs": {"model": ["4", 0], "positive": ["6", 0]}},
+            "6": {"class_type": "CLIPTextEncode", "_meta": {"title": "[API_PROMPT]"}, "inputs": {"text": "x", "clip": ["9", 1]}},
+            "4": {"class_type": "CheckpointLoaderSimple", "inputs": {"ckpt_name": "a.safetensors"}},
+            "7": {"inputs": {}},
+        });
+        let report = lint_workflow(&workflow);
+        let found: Vec<(IssueKind, &str)> = report.issues.iter().map(|i| (i.kind, i.node_id.as_str())).collect();
+        assert_eq!(found, vec![
+            (IssueKind::MissingMarker, ""),
+            (IssueKind::BrokenLink, "6"),
+            (IssueKind::Malformed, "7"),
+        ]);
+
+        let dir = std::env::temp_dir().join(format!("wf_lint_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("bad_v1.json"), "{ not json").unwrap();
+        let results = lint_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1.issues[0].kind, IssueKind::Malformed);
+    }
+
+    #[test]
+    fn test_post_process_workflow_needs_no_prompt() {
+        let workflow = json!({
+            "1": {"class_type": "LoadImage", "_meta": {"title": "[API_IMAGE_INPUT]"}, "inputs": {"image": "in.png"}},
+            "2": {"class_type": "UpscaleModelLoader", "inputs": {"model_name": "RealESRGAN_x2plus.pth"}},
+            "3": {"class_type": "ImageUpscaleWithModel", "inputs": {"upscale_model": ["2", 0], "image": ["1", 0]}},
+            "4": {"class_type": "SaveImage", "_meta": {"title": "[API_SAVE]"}, "inputs": {"images": ["3", 0], "filename_prefix": "up"}},
+        });
+        assert!(lint_workflow(&workflow).issues.is_empty());
+
+        let mut no_save = workflow.clone();
+        no_save.as_object_mut().unwrap().remove("4");
+        let found: Vec<String> = lint_workflow(&no_save).issues.iter().map(|i| i.message.clone()).collect();
+        assert_eq!(found, vec!["no node titled [API_SAVE]"]);
+    }
+}
@@ -28,6 +28,7 @@ impl WorkspaceManager {
         source_path: &Path,
         export_dir: &str,
     ) -> Result<PathBuf, FactoryError> {
+        crate::chaos::inject(crate::chaos::Fault::DiskFull)?;
         let export_path = PathBuf::from(export_dir);
         
         // 納品先ディレクトリの確保
@@ -0,0 +1,118 @@
+//! # YouTubePublisher — 公開済み動画の取り下げと字幕の添付
+//!
+//! YouTube Data API v3 を OAuth トークンで叩き、動画の非公開化・削除と字幕トラックの追加を行う。
+//! `dry_run` が有効な場合は API を呼ばず、実行予定の操作をログに残すだけにする。
+
+use async_trait::async_trait;
+use factory_core::error::FactoryError;
+use factory_core::traits::Publisher;
+use std::path::Path;
+use tracing::info;
+
+const VIDEOS_ENDPOINT: &str = "https://www.googleapis.com/youtube/v3/videos";
+const CAPTIONS_UPLOAD_ENDPOINT: &str = "https://www.googleapis.com/upload/youtube/v3/captions";
+const MULTIPART_BOUNDARY: &str = "factory_caption_boundary";
+
+pub struct YouTubePublisher {
+    client: reqwest::Client,
+    oauth_token: String,
+    dry_run: bool,
+}
+
+impl YouTubePublisher {
+    pub fn new(oauth_token: String, dry_run: bool) -> Self {
+        Self { client: reqwest::Client::new(), oauth_token, dry_run }
+    }
+
+    fn require_token(&self) -> Result<(), FactoryError> {
+        if self.oauth_token.is_empty() {
+            return Err(FactoryError::Infrastructure {
+                reason: "YouTube OAuth token is missing (publisher.youtube_oauth_token)".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn check(resp: reqwest::Response, op: &str, video_id: &str) -> Result<(), FactoryError> {
+        if resp.status().is_success() {
+            return Ok(());
+        }
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        Err(FactoryError::Infrastructure {
+            reason: format!("YouTube {} for {} failed with status {}: {}", op, video_id, status, body),
+        })
+    }
+}
+
+#[async_trait]
+impl Publisher for YouTubePublisher {
+    fn platform(&self) -> &str {
+        "youtube"
+    }
+
+    async fn set_private(&self, video_id: &str) -> Result<(), FactoryError> {
+        if self.dry_run {
+            info!("🧪 [YouTubePublisher] dry-run: would set {} to private", video_id);
+            return Ok(());
+        }
+        self.require_token()?;
+        info!("🔒 [YouTubePublisher] Setting {} to private", video_id);
+        let resp = self.client
+            .put(VIDEOS_ENDPOINT)
+            .query(&[("part", "status")])
+            .bearer_auth(&self.oauth_token)
+            .json(&serde_json::json!({ "id": video_id, "status": { "privacyStatus": "private" } }))
+            .send()
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("YouTube API Error: {}", e) })?;
+        Self::check(resp, "set_private", video_id).await
+    }
+
+    async fn unpublish(&self, video_id: &str) -> Result<(), FactoryError> {
+        if self.dry_run {
+            info!("🧪 [YouTubePublisher] dry-run: would delete {}", video_id);
+            return Ok(());
+        }
+        self.require_token()?;
+        info!("🗑️ [YouTubePublisher] Deleting {}", video_id);
+        let resp = self.client
+            .delete(VIDEOS_ENDPOINT)
+            .query(&[("id", video_id)])
+            .bearer_auth(&self.oauth_token)
+            .send()
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("YouTube API Error: {}", e) })?;
+        Self::check(resp, "unpublish", video_id).await
+    }
+
+    async fn upload_captions(&self, video_id: &str, lang: &str, vtt_path: &Path) -> Result<(), FactoryError> {
+        if self.dry_run {
+            info!("🧪 [YouTubePublisher] dry-run: would attach {} captions to {}", lang, video_id);
+            return Ok(());
+        }
+        self.require_token()?;
+        let vtt = tokio::fs::read(vtt_path).await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("Failed to read captions {}: {}", vtt_path.display(), e) })?;
+        info!("💬 [YouTubePublisher] Attaching {} captions to {}", lang, video_id);
+        // captions.insert はメタデータ (JSON) と字幕ファイルの multipart/related で受け取る
+        let snippet = serde_json::json!({ "snippet": { "videoId": video_id, "language": lang, "name": "", "isDraft": false } });
+        let mut body = format!(
+            "--{b}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{b}\r\nContent-Type: text/vtt\r\n\r\n",
+            snippet,
+            b = MULTIPART_BOUNDARY
+        ).into_bytes();
+        body.extend_from_slice(&vtt);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+        let resp = self.client
+            .post(CAPTIONS_UPLOAD_ENDPOINT)
+            .query(&[("part", "snippet"), ("uploadType", "multipart")])
+            .bearer_auth(&self.oauth_token)
+            .header(reqwest::header::CONTENT_TYPE, format!("multipart/related; boundary={}", MULTIPART_BOUNDARY))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| FactoryError::Infrastructure { reason: format!("YouTube API Error: {}", e) })?;
+        Self::check(resp, "upload_captions", video_id).await
+    }
+}
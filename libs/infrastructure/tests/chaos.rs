@@ -0,0 +1,128 @@
+//! # Chaos Tests — 障害注入下の回復機構
+//!
+//! `chaos` feature で本物の注入点 (Gemini クライアント・ComfyUI 実行・納品・Heartbeat) に障害を起こし、
+//! サーキットブレーカー・ゾンビ回収・途中再開がジョブキュー上で期待どおりに働くことを確かめる。
+//! Sentinel の Poison Pill は shorts-factory 側 (`server::cron` のテスト) で確かめる。
+//! 規則はプロセス全体で共有なので、テストは `CHAOS` ロックで 1 本ずつ流す。
+
+use std::sync::Arc;
+
+use bastion::net_guard::ShieldClient;
+use factory_core::cost::CostTracker;
+use factory_core::error::FactoryError;
+use factory_core::traits::{JobQueue, JobStatus};
+use infrastructure::chaos::{self, Fault, FaultRule};
+use infrastructure::comfy_bridge::ComfyBridgeClient;
+use infrastructure::concept_manager::ConceptManager;
+use infrastructure::job_queue::SqliteJobQueue;
+use infrastructure::workspace_manager::WorkspaceManager;
+
+static CHAOS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn create_test_queue() -> (SqliteJobQueue, tempfile::TempDir) {
+    let tmp_dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+    let db_path = tmp_dir.path().join("test.db");
+    let jq = SqliteJobQueue::new(db_path.to_str().expect("Invalid path")).await.expect("Failed to create test job queue");
+    (jq, tmp_dir)
+}
+
+fn arm(fault: Fault, remaining: Option<u32>) {
+    chaos::set_rule(fault, FaultRule { probability: 1.0, remaining }).unwrap();
+}
+
+#[tokio::test]
+async fn test_injected_gemini_errors_open_the_global_breaker() {
+    let _guard = CHAOS.lock().await;
+    chaos::clear(None);
+    let (jq, _tmp) = create_test_queue().await;
+    let concept = ConceptManager::new("test-key", "gemini-2.0-flash");
+
+    arm(Fault::GeminiError, None);
+    for _ in 0..SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT {
+        let err = concept.compress_subtitle("長すぎる字幕", "ja", 5, &CostTracker::default()).await.unwrap_err();
+        assert!(err.is_retryable(), "a Gemini 500 is transient: {}", err);
+        jq.record_global_api_failure().await.unwrap();
+    }
+    assert!(jq.get_global_api_failures().await.unwrap() >= SqliteJobQueue::GLOBAL_API_FAILURE_LIMIT);
+
+    // 障害が止めばブレーカーは 1 回の成功で閉じる
+    chaos::clear(Some(Fault::GeminiError));
+    jq.record_global_api_success().await.unwrap();
+    assert_eq!(jq.get_global_api_failures().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_worker_that_cannot_write_its_heartbeat_is_reclaimed_as_zombie() {
+    let _guard = CHAOS.lock().await;
+    chaos::clear(None);
+    let (jq, _tmp) = create_test_queue().await;
+    let healthy = jq.enqueue("Healthy Topic", "dark", Some("{}")).await.unwrap();
+    let stalled = jq.enqueue("Stalled Topic", "dark", Some("{}")).await.unwrap();
+    let _ = jq.dequeue().await.unwrap();
+    let _ = jq.dequeue().await.unwrap();
+    // 2 本とも 20 分前から走っている
+    sqlx::query("UPDATE jobs SET started_at = datetime('now', '-20 minutes'), last_heartbeat = datetime('now', '-20 minutes')")
+        .execute(jq.pool_ref())
+        .await
+        .unwrap();
+
+    jq.heartbeat_pulse(&healthy).await.unwrap();
+    // ディスクが埋まると Heartbeat の書き込みが失敗し、最後の鼓動は 20 分前のまま残る
+    arm(Fault::DiskFull, None);
+    let err = jq.heartbeat_pulse(&stalled).await.unwrap_err();
+    assert!(matches!(err, FactoryError::OsError { .. }));
+    chaos::clear(None);
+
+    assert_eq!(jq.reclaim_zombie_jobs(15).await.unwrap(), 1);
+    assert_eq!(jq.fetch_job(&healthy).await.unwrap().unwrap().status, JobStatus::Processing);
+    let job = jq.fetch_job(&stalled).await.unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Failed);
+    assert!(job.error_message.unwrap().contains("Zombie"));
+}
+
+#[tokio::test]
+async fn test_injected_comfy_timeout_is_deferred_for_retry() {
+    let _guard = CHAOS.lock().await;
+    chaos::clear(None);
+    let (jq, tmp) = create_test_queue().await;
+    let shield = Arc::new(ShieldClient::builder().build().unwrap());
+    // 注入はワークフロー投入より前なので、ComfyUI は立っていなくてよい
+    let comfy = ComfyBridgeClient::new(shield, "http://127.0.0.1:9", tmp.path(), 5);
+    let id = jq.enqueue("Slow GPU", "cinematic", Some("{}")).await.unwrap();
+    let _ = jq.dequeue().await.unwrap();
+
+    arm(Fault::ComfyTimeout, Some(1));
+    let err = comfy.upscale_image(&tmp.path().join("still.png"), "upscale_esrgan").await.unwrap_err();
+    assert!(matches!(err, FactoryError::ComfyTimeout { .. }));
+    assert!(err.is_retryable());
+
+    let (attempt, _retry_at) = jq.defer_retry(&id, &err.to_string(), 3, 60).await.unwrap().expect("first timeout is deferred");
+    assert_eq!(attempt, 1);
+    assert_eq!(jq.fetch_job(&id).await.unwrap().unwrap().status, JobStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_disk_full_on_delivery_resumes_from_the_rendered_output() {
+    let _guard = CHAOS.lock().await;
+    chaos::clear(None);
+    let (jq, tmp) = create_test_queue().await;
+    let export_dir = tmp.path().join("export");
+    let rendered = tmp.path().join("final.mp4");
+    tokio::fs::write(&rendered, "rendered video").await.unwrap();
+    let id = jq.enqueue("Full Disk", "cinematic", Some("{}")).await.unwrap();
+    let _ = jq.dequeue().await.unwrap();
+
+    arm(Fault::DiskFull, Some(1));
+    let err = WorkspaceManager::deliver_output(&id, &rendered, export_dir.to_str().unwrap()).await.unwrap_err();
+    assert!(!err.is_retryable());
+    assert!(rendered.exists(), "the rendered output is the checkpoint and must survive");
+
+    // 中断扱いで Pending に戻し、次の実行は生成済みの出力から納品だけやり直す
+    assert!(jq.requeue_interrupted(&id, "Disk full during delivery").await.unwrap());
+    let job = jq.dequeue().await.unwrap().expect("requeued job runs again");
+    assert_eq!(job.id, id);
+    let delivered = WorkspaceManager::deliver_output(&id, &rendered, export_dir.to_str().unwrap()).await.unwrap();
+    assert!(delivered.exists());
+    jq.complete_job(&id, Some(delivered.to_str().unwrap())).await.unwrap();
+    assert_eq!(jq.fetch_job(&id).await.unwrap().unwrap().status, JobStatus::Completed);
+}
@@ -1,4 +1,9 @@
 use serde::{Deserialize, Serialize};
+use croner::parser::{CronParser, Seconds};
+use std::collections::BTreeMap;
+
+/// チャンネル未指定のジョブが属するチャンネル名
+pub const DEFAULT_CHANNEL: &str = "default";
 
 /// ShortsFactory 全体の設定
 #[derive(Clone, Serialize, Deserialize)]
@@ -29,10 +34,1424 @@ pub struct FactoryConfig {
     pub youtube_api_key: String,
     /// Gemini API Key for The Oracle (Phase 11-D)
     pub gemini_api_key: String,
-    /// TikTok API Key for Phase 11 Sentinel (Placeholder)
+    /// TikTok Display API のアクセストークン (Phase 11 Sentinel)
     pub tiktok_api_key: String,
+    /// Instagram Graph API のアクセストークン (Phase 11 Sentinel: Reels)
+    #[serde(default)]
+    pub instagram_access_token: String,
     /// Unleashed Mode (Platinum Edition): Bypass all level requirements
     pub unleashed_mode: bool,
+    /// 定期ジョブのスケジュール (`[cron]` セクション)
+    #[serde(default)]
+    pub cron: CronConfig,
+    /// 字幕読み速度 QA (`[subtitle_qa]` セクション)
+    #[serde(default)]
+    pub subtitle_qa: SubtitleQaConfig,
+    /// 画像品質ゲート (`[vision_qa]` セクション)
+    #[serde(default)]
+    pub vision_qa: VisionQaConfig,
+    /// 静止画の美的スコアゲート (`[aesthetic]` セクション)
+    #[serde(default)]
+    pub aesthetic: AestheticConfig,
+    /// ComfyUI 出力の安全性チェック (`[safety]` セクション)
+    #[serde(default)]
+    pub safety: SafetyConfig,
+    /// シーン素材の使い回し検知 (`[fingerprint]` セクション)
+    #[serde(default)]
+    pub fingerprint: FingerprintConfig,
+    /// 効果音の挿入 (`[sfx]` セクション)
+    #[serde(default)]
+    pub sfx: SfxConfig,
+    /// Karma の意味検索 (`[karma_embedding]` セクション)
+    #[serde(default)]
+    pub karma_embedding: KarmaEmbeddingConfig,
+    /// サイドカー監視 (`[sidecar]` セクション)
+    #[serde(default)]
+    pub sidecar: SidecarConfig,
+    /// ポートの衝突検知と自動割り当て (`[ports]` セクション)
+    #[serde(default)]
+    pub ports: PortsConfig,
+    /// 持ち込み横長素材の縦型リフレーム (`[reframe]` セクション)
+    #[serde(default)]
+    pub reframe: ReframeConfig,
+    /// チャンネル (ブランド) 別プロファイル (`[channels.<name>]` セクション)
+    #[serde(default)]
+    pub channels: BTreeMap<String, ChannelProfile>,
+    /// 名前付き SOUL プロファイル (`[souls]` セクション: 名前 = ファイルパス)
+    #[serde(default)]
+    pub souls: BTreeMap<String, String>,
+    /// 再登場キャラクター (`[characters.<name>]` セクション)
+    #[serde(default)]
+    pub characters: BTreeMap<String, CharacterProfile>,
+    /// 人間による承認チェックポイント (`[approval]` セクション)
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+    /// 公開済み動画の取り下げ (`[publisher]` セクション)
+    #[serde(default)]
+    pub publisher: PublisherConfig,
+    /// Serve API の認証 (`[auth]` セクション)
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// 共同作業者向けレビューリンク (`[review]` セクション)
+    #[serde(default)]
+    pub review: ReviewConfig,
+    /// 手動レンダーの取り込みフォルダ (`[watch_folder]` セクション)
+    #[serde(default)]
+    pub watch_folder: WatchFolderConfig,
+    /// 停止時の後始末 (`[shutdown]` セクション)
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    /// LLM を使わない定型台本 (`[templates]` セクション)
+    #[serde(default)]
+    pub templates: TemplatesConfig,
+    /// ジョブ内の工程ごとの締め切り (`[watchdog]` セクション)
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    /// 納品物の保管先 (`[storage]` セクション)
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// API 呼び出し・GPU 時間の単価 (`[costs]` セクション)
+    #[serde(default)]
+    pub costs: CostsConfig,
+    /// 夜間セルフテストの題材 (`[self_test]` セクション)
+    #[serde(default)]
+    pub self_test: SelfTestConfig,
+    /// 最終エンコードのビットレート (`[encoding]` セクション)
+    #[serde(default)]
+    pub encoding: EncodingConfig,
+    /// 映像エンコーダーの選択とエンコーダーごとのプリセット (`[encoder]` セクション)
+    #[serde(default)]
+    pub encoder: EncoderConfig,
+    /// 9:16 以外の画角の書き出し (`[export]` セクション)
+    #[serde(default)]
+    pub export: ExportConfig,
+    /// `upscale` 工程の拡大方式 (`[upscale]` セクション)
+    #[serde(default)]
+    pub upscale: UpscaleConfig,
+    /// トレンドの情報源と重み (`[trends]` セクション)
+    #[serde(default)]
+    pub trends: TrendsConfig,
+    /// Watchtower 会話の記憶 (`[chat_memory]` セクション)
+    #[serde(default)]
+    pub chat_memory: ChatMemoryConfig,
+    /// 題材の編集方針 (`[topic_policy]` セクション)
+    #[serde(default)]
+    pub topic_policy: TopicPolicyConfig,
+    /// Oracle (SNS 反響の最終審判) のモデル構成 (`[oracle]` セクション)
+    #[serde(default)]
+    pub oracle: OracleConfig,
+    /// 完了通知に添える動画プレビュー (`[discord_preview]` セクション)
+    #[serde(default)]
+    pub discord_preview: DiscordPreviewConfig,
+    /// Discord 以外の通知先 (`[notifiers]` セクション)
+    #[serde(default)]
+    pub notifiers: NotifiersConfig,
+    /// ComfyUI レンダーの受け入れ制御 (`[admission]` セクション)
+    #[serde(default)]
+    pub admission: AdmissionConfig,
+    /// JobWorker の並列度 (`[worker]` セクション)
+    #[serde(default)]
+    pub worker: WorkerConfig,
+}
+
+/// チャンネル (ブランド) ごとの魂・演出・納品先・公開資格情報
+///
+/// 空の項目はグローバル設定 (SOUL.md / export_dir / 各 SNS の資格情報 / publisher) にフォールバックする。
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ChannelProfile {
+    /// SOUL ファイルのパス (作業ディレクトリ相対)
+    pub soul_file: String,
+    /// 使用を許可するスタイル名 (空なら全スタイル)
+    pub styles: Vec<String>,
+    /// 納品先ディレクトリ
+    pub export_dir: String,
+    /// 納品先ディレクトリを配信している URL (local 保管先用。空なら `[storage] public_base_url` の配下か file:// URL)
+    pub public_base_url: String,
+    /// 公開動画のメトリクス取得用 YouTube API Key
+    pub youtube_api_key: String,
+    /// 公開動画の取り下げ用 YouTube OAuth トークン
+    pub youtube_oauth_token: String,
+    /// TikTok に転載した動画のメトリクス取得用アクセストークン
+    pub tiktok_access_token: String,
+    /// Instagram Reels に転載した動画のメトリクス取得用アクセストークン
+    pub instagram_access_token: String,
+    /// Samsara による自律企画の対象にするか
+    pub samsara: bool,
+    /// Samsara の企画をこの連載シリーズの次話として積む (空なら単発)
+    pub series: String,
+    /// 台本を LLM でなく `templates.toml` の定型テンプレートで組む (空なら Gemini)
+    pub script_template: String,
+}
+
+impl Default for ChannelProfile {
+    fn default() -> Self {
+        Self {
+            soul_file: String::new(),
+            styles: Vec::new(),
+            export_dir: String::new(),
+            public_base_url: String::new(),
+            youtube_api_key: String::new(),
+            youtube_oauth_token: String::new(),
+            tiktok_access_token: String::new(),
+            instagram_access_token: String::new(),
+            samsara: true,
+            series: String::new(),
+            script_template: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ChannelProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelProfile")
+            .field("soul_file", &self.soul_file)
+            .field("styles", &self.styles)
+            .field("export_dir", &self.export_dir)
+            .field("public_base_url", &self.public_base_url)
+            .field("youtube_api_key", if self.youtube_api_key.is_empty() { &"" } else { &"***" })
+            .field("youtube_oauth_token", if self.youtube_oauth_token.is_empty() { &"" } else { &"***" })
+            .field("tiktok_access_token", if self.tiktok_access_token.is_empty() { &"" } else { &"***" })
+            .field("instagram_access_token", if self.instagram_access_token.is_empty() { &"" } else { &"***" })
+            .field("samsara", &self.samsara)
+            .field("series", &self.series)
+            .field("script_template", &self.script_template)
+            .finish()
+    }
+}
+
+impl ChannelProfile {
+    /// スタイルがこのチャンネルで許可されているか
+    pub fn allows_style(&self, style: &str) -> bool {
+        self.styles.is_empty() || self.styles.iter().any(|s| s == style)
+    }
+
+    /// Samsara が引く Karma の名前空間 (スタイルを 1 つに固定したチャンネルならそのスタイル)
+    pub fn karma_namespace(&self) -> Option<&str> {
+        match self.styles.as_slice() {
+            [style] => Some(style.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// 再登場キャラクター (マスコット等) の見た目を固定するための素材
+///
+/// 企画 (タイトル・トピック・シーン描写) が名前か別名に触れたとき、ComfyUI ワークフローへ注入される。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CharacterProfile {
+    /// 企画中での呼び名 (キー名に加えて一致判定に使う)
+    pub aliases: Vec<String>,
+    /// 見た目を固定するタグ (トリガーワード・髪色・衣装など)。プロンプトの先頭に入る
+    pub prompt: String,
+    /// ComfyUI の `models/loras` 内のファイル名 (空なら LoRA なし)
+    pub lora: String,
+    pub lora_strength: f32,
+    /// 参照画像 (作業ディレクトリ相対)。先頭の 1 枚を IPAdapter に渡す
+    pub reference_images: Vec<String>,
+    pub ipadapter_weight: f32,
+}
+
+impl Default for CharacterProfile {
+    fn default() -> Self {
+        Self {
+            aliases: Vec::new(),
+            prompt: String::new(),
+            lora: String::new(),
+            lora_strength: 0.8,
+            reference_images: Vec::new(),
+            ipadapter_weight: 0.7,
+        }
+    }
+}
+
+/// サイドカー (TTS サーバー) の監視設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SidecarConfig {
+    /// HTTP レディネスプローブの URL
+    pub tts_probe_url: String,
+    /// プローブ間隔 (秒)
+    pub probe_interval_secs: u64,
+    /// プローブのタイムアウト (秒)
+    pub probe_timeout_secs: u64,
+    /// 起動・再起動直後のプローブ猶予 (秒)
+    pub startup_grace_secs: u64,
+    /// 初回起動時にレディネスを待つ上限 (秒)
+    pub ready_timeout_secs: u64,
+    /// 再起動に至る連続プローブ失敗回数
+    pub failure_threshold: u32,
+    /// 再起動バックオフの初期値 / 上限 (秒)
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+    /// フラップ判定: `flap_window_secs` 内に `flap_threshold` 回以上の再起動
+    pub flap_window_secs: u64,
+    pub flap_threshold: usize,
+}
+
+impl Default for SidecarConfig {
+    fn default() -> Self {
+        Self {
+            tts_probe_url: "http://localhost:5001/health".to_string(),
+            probe_interval_secs: 15,
+            probe_timeout_secs: 5,
+            startup_grace_secs: 30,
+            ready_timeout_secs: 180,
+            failure_threshold: 3,
+            initial_backoff_secs: 2,
+            max_backoff_secs: 300,
+            flap_window_secs: 600,
+            flap_threshold: 3,
+        }
+    }
+}
+
+/// ポートの衝突検知と自動割り当て
+///
+/// 既定では使用中のポートがあれば起動を止める。`auto_allocate = true` なら次の空きポートを使い、
+/// 実際のポートを `runtime_file` に書く (Command Center は `FACTORY_RUNTIME_FILE` でこれを読む)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PortsConfig {
+    /// 使用中なら次の空きポートを探す
+    pub auto_allocate: bool,
+    /// 空きを探す個数 (希望ポートの次から)
+    pub search_range: u16,
+    /// TTS サイドカーの希望ポート (サイドカーには `TTS_PORT` で渡す)
+    pub tts_port: u16,
+    /// 実際のエンドポイントを書き出すファイル
+    pub runtime_file: String,
+}
+
+impl Default for PortsConfig {
+    fn default() -> Self {
+        Self {
+            auto_allocate: false,
+            search_range: 20,
+            tts_port: 5001,
+            runtime_file: "workspace/runtime.json".to_string(),
+        }
+    }
+}
+
+/// 横長素材 (16:9) の縦型リフレーム設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ReframeConfig {
+    /// 被写体検出サイドカーの URL (空なら常に中央クロップ)
+    pub detector_url: String,
+    /// 検出のサンプリング間隔 (秒)
+    pub sample_interval_secs: f32,
+    /// 検出リクエストのタイムアウト (秒)
+    pub detector_timeout_secs: u64,
+    /// クロップ窓の追従の鈍さ (0.0 = 即追従, 0.9 = ほぼ固定)
+    pub smoothing: f32,
+}
+
+impl Default for ReframeConfig {
+    fn default() -> Self {
+        Self {
+            detector_url: String::new(),
+            sample_interval_secs: 0.5,
+            detector_timeout_secs: 120,
+            smoothing: 0.6,
+        }
+    }
+}
+
+/// 承認待ちがタイムアウトしたときの扱い
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalTimeoutPolicy {
+    /// 承認扱いで先へ進む (無人運転向け)
+    Approve,
+    /// 却下扱いでジョブを止める
+    Reject,
+}
+
+/// 制作パイプラインの承認チェックポイント
+///
+/// 有効なチェックポイントでは Watchtower に ApprovalRequest を出し、応答が来るまで停止する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ApprovalConfig {
+    /// コンセプト (台本) 確定後、GPU を使う前に止める
+    pub after_concept: bool,
+    /// 最初の言語の動画が完成した時点で止める (残りの言語・納品の前)
+    pub after_first_render: bool,
+    /// 応答待ちの上限 (秒)
+    pub timeout_secs: u64,
+    pub on_timeout: ApprovalTimeoutPolicy,
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            after_concept: false,
+            after_first_render: false,
+            timeout_secs: 1800,
+            on_timeout: ApprovalTimeoutPolicy::Approve,
+        }
+    }
+}
+
+/// 投稿先プラットフォームの操作設定
+///
+/// 取り下げ (非公開化・削除) は動画の所有者権限が必要なため、閲覧用の API キーではなく OAuth トークンを使う。
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PublisherConfig {
+    /// true なら実際の API は呼ばず、実行予定の操作をログに出すだけ
+    pub dry_run: bool,
+    /// YouTube Data API の OAuth アクセストークン (youtube スコープ)
+    pub youtube_oauth_token: String,
+    /// 動画を紐付けたとき (`link-sns`) に言語ごとの字幕トラック (WebVTT) を添付する (既定は無効)
+    pub upload_captions: bool,
+}
+
+impl Default for PublisherConfig {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            youtube_oauth_token: std::env::var("YOUTUBE_OAUTH_TOKEN").unwrap_or_default(),
+            upload_captions: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for PublisherConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublisherConfig")
+            .field("dry_run", &self.dry_run)
+            .field("youtube_oauth_token", if self.youtube_oauth_token.is_empty() { &"" } else { &"***" })
+            .field("upload_captions", &self.upload_captions)
+            .finish()
+    }
+}
+
+/// Serve API の認証設定
+///
+/// 有効時、GET 以外のリクエストには `Authorization: Bearer <key>` か `X-API-Key` が必要になる。
+/// キーは `bastion keys add <name>` で発行して `keys_file` に保存するか、`static_keys` に直接書く。
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub enabled: bool,
+    /// `bastion keys` が管理するキー保管庫 (ハッシュのみ保存)
+    pub keys_file: String,
+    /// 設定ファイルに直接書く静的キー (名前 = キー)
+    pub static_keys: BTreeMap<String, String>,
+    /// キーごとの 1 分あたりの変更系リクエスト上限 (0 = 無制限)
+    pub rate_limit_per_minute: u32,
+    /// 参照系 (GET) にも readonly 以上のトークンを要求する
+    pub protect_reads: bool,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keys_file: "secrets/api_keys.json".to_string(),
+            static_keys: BTreeMap::new(),
+            rate_limit_per_minute: 30,
+            protect_reads: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for AuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("enabled", &self.enabled)
+            .field("keys_file", &self.keys_file)
+            .field("static_keys", &self.static_keys.keys().collect::<Vec<_>>())
+            .field("rate_limit_per_minute", &self.rate_limit_per_minute)
+            .field("protect_reads", &self.protect_reads)
+            .finish()
+    }
+}
+
+/// レビューリンク設定
+///
+/// `/api/projects/:id/review-link` が発行する期限付き URL は HMAC で署名され、
+/// API キーを持たない共同作業者でも動画の確認と承認/差し戻しができる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// 署名鍵のファイル (無ければ初回起動時に生成。消すと発行済みリンクは全て無効になる)
+    pub secret_file: String,
+    /// 既定の有効期限 (時間)
+    pub ttl_hours: u32,
+    /// 指定できる有効期限の上限 (時間)
+    pub max_ttl_hours: u32,
+    /// リンクに使う外部 URL (例: "https://factory.example.com")。空なら発行リクエストの Host
+    pub public_base_url: String,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            secret_file: "secrets/review_link.key".to_string(),
+            ttl_hours: 72,
+            max_ttl_hours: 24 * 30,
+            public_base_url: String::new(),
+        }
+    }
+}
+
+/// 納品物の保管先設定
+///
+/// 完成動画は従来どおり納品ディレクトリに置いたうえで、`backend` に保管して URL をジョブの
+/// `output_videos` に記録する。`local` は納品ディレクトリのファイルをそのまま指す。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// "local" または "s3" (MinIO など S3 互換も含む)
+    pub backend: String,
+    /// local: 納品ディレクトリを配信している URL (例: "https://cdn.example.com/exports")。空なら file:// URL
+    pub public_base_url: String,
+    pub s3: S3Config,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self { backend: "local".to_string(), public_base_url: String::new(), s3: S3Config::default() }
+    }
+}
+
+/// S3 / MinIO の接続設定 (`[storage.s3]`)
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct S3Config {
+    /// 例: "https://s3.ap-northeast-1.amazonaws.com", "http://minio.local:9000"
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// オブジェクトキーの接頭辞
+    pub prefix: String,
+    /// 空なら環境変数 AWS_ACCESS_KEY_ID
+    pub access_key: String,
+    /// 空なら環境変数 AWS_SECRET_ACCESS_KEY
+    pub secret_key: String,
+    /// `{endpoint}/{bucket}/{key}` 形式で送る (MinIO は true、AWS の仮想ホスト形式なら false)
+    pub path_style: bool,
+    /// 返す URL の接頭辞 (CDN 等)。空ならアップロード先の URL
+    pub public_base_url: String,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://s3.amazonaws.com".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            prefix: "deliveries".to_string(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            path_style: true,
+            public_base_url: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("access_key", if self.access_key.is_empty() { &"" } else { &"***" })
+            .field("secret_key", if self.secret_key.is_empty() { &"" } else { &"***" })
+            .field("path_style", &self.path_style)
+            .field("public_base_url", &self.public_base_url)
+            .finish()
+    }
+}
+
+/// コスト台帳の単価設定
+///
+/// 記録時にトークン数・呼び出し回数・GPU 秒へ掛けて概算額 (USD) を出す。
+/// 0 にした項目は数量だけ記録する (ローカル GPU の電気代などは各自の環境に合わせる)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CostsConfig {
+    /// false なら台帳に記録しない
+    pub enabled: bool,
+    /// Gemini の入力 100 万トークンあたり
+    pub gemini_input_usd_per_mtok: f64,
+    /// Gemini の出力 100 万トークンあたり
+    pub gemini_output_usd_per_mtok: f64,
+    /// Brave Search の 1 リクエストあたり
+    pub brave_usd_per_call: f64,
+    /// ComfyUI の GPU 1 時間あたり
+    pub gpu_usd_per_hour: f64,
+}
+
+impl Default for CostsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gemini_input_usd_per_mtok: 0.30,
+            gemini_output_usd_per_mtok: 2.50,
+            brave_usd_per_call: 0.005,
+            gpu_usd_per_hour: 0.0,
+        }
+    }
+}
+
+/// 夜間セルフテスト設定
+///
+/// `cron.self_test` の時刻に、固定の短い台本 (LLM・Brave を使わない) を安いワークフローで 1 本作り、
+/// ffmpeg / ComfyUI / TTS と字幕・美的ゲートが通ることを確かめる。失敗したら Discord に知らせる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SelfTestConfig {
+    /// ComfyUI ワークフロー (`resources/workflows/<name>.json`)
+    pub workflow: String,
+    /// ナレーション・字幕の言語
+    pub lang: String,
+    /// 完成動画の長さの許容範囲 (秒)。台本は 5 秒前後になるよう書いてある
+    pub min_duration_secs: f64,
+    pub max_duration_secs: f64,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            workflow: "shorts_standard_v1".to_string(),
+            lang: "en".to_string(),
+            min_duration_secs: 2.0,
+            max_duration_secs: 15.0,
+        }
+    }
+}
+
+/// 最終エンコード (字幕焼き込み) のビットレート設定
+///
+/// 解像度・尺・素材の複雑さから目標ビットレートを決め、プラットフォームの容量上限を超えないよう抑える。
+/// VMAF のスポットチェックを有効にすると、品質が低ければ次回から上げ、過剰なら下げる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncodingConfig {
+    /// false なら従来どおり固定 6000 kbps
+    pub adaptive: bool,
+    /// 1080x1920・平均的な複雑さのときの映像ビットレート (kbps)
+    pub reference_kbps: u32,
+    pub min_kbps: u32,
+    pub max_kbps: u32,
+    /// 納品ファイルの容量上限 (MB)。公開先のうち最も厳しいものに合わせる
+    pub max_file_mb: f64,
+    /// 容量計算で差し引く音声ビットレート (kbps)
+    pub audio_kbps: u32,
+    /// 完成動画の一部を素材と比べて VMAF を測る (ffmpeg に libvmaf が必要)
+    pub vmaf_spot_check: bool,
+    /// これを下回ったら次回からビットレートを上げる
+    pub vmaf_min: f64,
+    /// これを上回ったら過剰とみなして下げる
+    pub vmaf_max: f64,
+}
+
+impl Default for EncodingConfig {
+    fn default() -> Self {
+        Self {
+            adaptive: true,
+            reference_kbps: 6000,
+            min_kbps: 2500,
+            max_kbps: 12000,
+            max_file_mb: 250.0,
+            audio_kbps: 128,
+            vmaf_spot_check: false,
+            vmaf_min: 90.0,
+            vmaf_max: 97.0,
+        }
+    }
+}
+
+/// エンコーダー 1 つ分のプリセット
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncoderPreset {
+    /// 中間ファイル (リサイズ・Ken Burns) の映像ビットレート (kbps)
+    pub intermediate_kbps: u32,
+    /// 最終エンコードの目標ビットレートに掛ける係数 (圧縮効率の差を吸収する)
+    pub bitrate_scale: f64,
+    /// `-c:v` の後ろに足す引数 (`["-preset", "p5"]` など)
+    pub extra_args: Vec<String>,
+}
+
+impl Default for EncoderPreset {
+    fn default() -> Self {
+        Self { intermediate_kbps: 8000, bitrate_scale: 1.0, extra_args: Vec::new() }
+    }
+}
+
+/// 映像エンコーダー
+///
+/// `preferred = "auto"` なら起動時に videotoolbox → nvenc → vaapi → libx264 の順で
+/// 実際に短いテストエンコードが通るものを選ぶ。名前を書けば検出せずにそれを使う。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EncoderConfig {
+    /// "auto" | "videotoolbox" | "nvenc" | "vaapi" | "libx264"
+    pub preferred: String,
+    /// VAAPI で使う DRM デバイス
+    pub vaapi_device: String,
+    pub videotoolbox: EncoderPreset,
+    pub nvenc: EncoderPreset,
+    pub vaapi: EncoderPreset,
+    pub libx264: EncoderPreset,
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            preferred: "auto".to_string(),
+            vaapi_device: "/dev/dri/renderD128".to_string(),
+            videotoolbox: EncoderPreset::default(),
+            nvenc: EncoderPreset { extra_args: vec!["-preset".into(), "p5".into()], ..Default::default() },
+            vaapi: EncoderPreset::default(),
+            libx264: EncoderPreset {
+                intermediate_kbps: 6000,
+                bitrate_scale: 0.85,
+                extra_args: vec!["-preset".into(), "veryfast".into()],
+            },
+        }
+    }
+}
+
+/// 9:16 を別の画角に合わせる方法
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFit {
+    /// 画面を埋めるように拡大して切り出す (`focus_y` の位置を残す)
+    #[default]
+    Crop,
+    /// 全体を収め、余白はぼかした同じ映像で埋める
+    Pad,
+}
+
+/// 追加で書き出す画角 1 つ分 (`[[export.profiles]]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExportProfile {
+    /// ファイル名の接尾辞 (`{project}_{lang}_{name}.mp4`)
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub fit: ExportFit,
+    /// crop のときに残す縦位置 (0.0 = 上端, 0.5 = 中央, 1.0 = 下端)
+    pub focus_y: f32,
+}
+
+impl Default for ExportProfile {
+    fn default() -> Self {
+        Self { name: "square".to_string(), width: 1080, height: 1080, fit: ExportFit::default(), focus_y: 0.4 }
+    }
+}
+
+impl ExportProfile {
+    /// "1:1" / "16:9" のような比
+    pub fn aspect(&self) -> String {
+        fn gcd(a: u32, b: u32) -> u32 {
+            if b == 0 { a } else { gcd(b, a % b) }
+        }
+        let g = gcd(self.width, self.height).max(1);
+        format!("{}:{}", self.width / g, self.height / g)
+    }
+}
+
+/// 追加の画角の書き出し
+///
+/// 最終合成のあと、字幕なしマスターから全プロファイルを 1 回の ffmpeg でまとめて書き出し、
+/// 字幕・タイトルはそれぞれの画角で焼き直す。空なら 9:16 のみ。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct ExportConfig {
+    pub profiles: Vec<ExportProfile>,
+}
+
+/// `upscale` 工程の拡大方式
+///
+/// `backend` は "ffmpeg" (lanczos。GPU 不要)、"comfy" (`comfy_workflow` の ESRGAN ワークフロー)、
+/// "realesrgan" (`realesrgan_url` のサイドカー) のいずれか。GPU を使う方式の所要時間はジョブの GPU 時間に計上する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UpscaleConfig {
+    pub backend: String,
+    /// 拡大率 (comfy はワークフローのモデルで決まる)
+    pub factor: u32,
+    pub comfy_workflow: String,
+    pub realesrgan_url: String,
+    /// サイドカーへのリクエストのタイムアウト (秒)
+    pub timeout_secs: u64,
+}
+
+impl Default for UpscaleConfig {
+    fn default() -> Self {
+        Self {
+            backend: "ffmpeg".to_string(),
+            factor: 2,
+            comfy_workflow: "upscale_esrgan_v1".to_string(),
+            realesrgan_url: String::new(),
+            timeout_secs: 120,
+        }
+    }
+}
+
+/// トレンドの情報源と重み
+///
+/// Brave (`brave_api_key`) に加えて、`reddit_subreddits`・`google_trends_geo`・`youtube_region`
+/// (`youtube_api_key` も必要)・`rss_feeds` を設定した情報源を併用する。各情報源のスコアを 0-1 に揃えて重みを掛け、
+/// 複数の情報源に出た話題は合算して `max_items` 件に絞る。重みを 0 にした情報源は使わない。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TrendsConfig {
+    pub brave_weight: f64,
+    pub reddit_subreddits: Vec<String>,
+    pub reddit_weight: f64,
+    /// 国コード (例: "JP")
+    pub google_trends_geo: String,
+    pub google_trends_weight: f64,
+    /// 国コード (例: "JP")
+    pub youtube_region: String,
+    pub youtube_weight: f64,
+    pub rss_feeds: Vec<String>,
+    pub rss_weight: f64,
+    pub max_items: usize,
+}
+
+impl Default for TrendsConfig {
+    fn default() -> Self {
+        Self {
+            brave_weight: 1.0,
+            reddit_subreddits: Vec::new(),
+            reddit_weight: 0.8,
+            google_trends_geo: String::new(),
+            google_trends_weight: 1.0,
+            youtube_region: String::new(),
+            youtube_weight: 0.7,
+            rss_feeds: Vec::new(),
+            rss_weight: 0.5,
+            max_items: 10,
+        }
+    }
+}
+
+/// 題材の編集方針
+///
+/// `file` に禁止キーワードと必須テーマを書くと、Samsara の企画と REST / Discord の生成要求を積む前に照らし、
+/// 反したものは積まずに Watchtower へ知らせる (書式は `infrastructure::topic_policy`)。ファイルが無ければ制限しない。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TopicPolicyConfig {
+    pub file: String,
+}
+
+impl Default for TopicPolicyConfig {
+    fn default() -> Self {
+        Self { file: "workspace/config/topic_policy.toml".to_string() }
+    }
+}
+
+/// Oracle のモデル構成
+///
+/// `mode` は "gemini" (従来どおり Gemini のみ) / "ensemble" (Gemini と `ollama_url` のローカル審判の平均) /
+/// "local" (ローカルのみ)。審判のスコア差が `disagreement_threshold` を超えた評価には割れた印を付ける。
+/// `local_fallback` なら Gemini の失敗時やサーキットブレーカー作動中もローカルだけで判定を続ける。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OracleConfig {
+    pub mode: String,
+    pub gemini_model: String,
+    /// Ollama のモデル名
+    pub local_model: String,
+    pub local_fallback: bool,
+    /// topic / visual / soul のどれかでスコア差がこれを超えたら割れた評価とする
+    pub disagreement_threshold: f64,
+    /// ローカル審判の応答待ち (秒)
+    pub local_timeout_secs: u64,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            mode: "gemini".to_string(),
+            gemini_model: "gemini-2.5-flash".to_string(),
+            local_model: "qwen2.5:14b".to_string(),
+            local_fallback: false,
+            disagreement_threshold: 0.5,
+            local_timeout_secs: 180,
+        }
+    }
+}
+
+/// 完了通知の動画プレビュー
+///
+/// 本編 (最初の 9:16) が `max_mb` 以下ならそのまま、超えるなら縮小・再圧縮したプレビューを
+/// Watchtower の完了通知に添付する。再圧縮しても収まらない長さなら文字だけの通知になる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DiscordPreviewConfig {
+    pub enabled: bool,
+    /// 添付の上限 (MB)。サーバーのブーストで上限が上がっていれば合わせて上げる
+    pub max_mb: u64,
+}
+
+impl Default for DiscordPreviewConfig {
+    fn default() -> Self {
+        Self { enabled: true, max_mb: 10 }
+    }
+}
+
+/// Discord 以外の通知先 (Slack / Telegram)
+///
+/// トークンを設定した通知先に、完了通知と承認依頼 (承認・却下ボタン付き) を Discord と並べて送る。
+/// Slack のボタンは App の Interactivity Request URL を `/hooks/slack/actions` に向け、
+/// `slack_signing_secret` で署名を検証する。Telegram のボタンは Core が getUpdates で受け取る。
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotifiersConfig {
+    /// Bot User OAuth Token (xoxb-...)。空なら Slack には送らない
+    pub slack_bot_token: String,
+    /// 投稿先チャンネル (ID か #名前)
+    pub slack_channel: String,
+    pub slack_signing_secret: String,
+    /// BotFather のトークン。空なら Telegram には送らない
+    pub telegram_bot_token: String,
+    /// 投稿先のチャット ID (グループは負の数、公開チャンネルは @名前)
+    pub telegram_chat_id: String,
+}
+
+impl std::fmt::Debug for NotifiersConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NotifiersConfig")
+            .field("slack_bot_token", if self.slack_bot_token.is_empty() { &"" } else { &"***" })
+            .field("slack_channel", &self.slack_channel)
+            .field("slack_signing_secret", if self.slack_signing_secret.is_empty() { &"" } else { &"***" })
+            .field("telegram_bot_token", if self.telegram_bot_token.is_empty() { &"" } else { &"***" })
+            .field("telegram_chat_id", &self.telegram_chat_id)
+            .finish()
+    }
+}
+
+/// ComfyUI レンダーの受け入れ制御 (ResourceArbiter)
+///
+/// GPU を渡す前にマシン全体の空きメモリ・VRAM・CPU 負荷を確かめ、閾値を超えていれば
+/// 新しいレンダーを始めずに待つ (スワップで Mac ごと固まるのを防ぐ)。待っている間は
+/// Watchtower にバックプレッシャーを知らせ、`max_wait_mins` を過ぎたらそのまま始める。
+/// 既定では無効 (`enabled = true` で有効にする)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AdmissionConfig {
+    pub enabled: bool,
+    /// これより空きメモリ (MB) が少なければ待つ。0 なら見ない
+    pub min_free_ram_mb: u64,
+    /// VRAM の使用率がこれ以上なら待つ (0.0 - 1.0)。1.0 以上なら見ない
+    pub max_vram_ratio: f64,
+    /// マシン全体の CPU 使用率 (%) がこれ以上なら待つ。100 以上なら見ない
+    pub max_cpu_percent: f32,
+    /// 計り直すまでの間隔 (秒)
+    pub check_interval_secs: u64,
+    /// 待つ上限 (分)。0 なら空くまで待ち続ける
+    pub max_wait_mins: u64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_free_ram_mb: 4096,
+            max_vram_ratio: 0.9,
+            max_cpu_percent: 95.0,
+            check_interval_secs: 15,
+            max_wait_mins: 30,
+        }
+    }
+}
+
+/// JobWorker の並列度
+///
+/// `concurrency` 件までのジョブを同時に走らせる (Watchtower の即時実行も同じ枠を使う)。
+/// GPU (画像・音声) は ResourceArbiter が 1 件ずつ、FFmpeg は 2 件までに絞るので、重なるのは企画や合成の待ちの間。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WorkerConfig {
+    pub concurrency: usize,
+    /// 待ち行列を見に行く間隔 (秒)
+    pub poll_secs: u64,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self { concurrency: 1, poll_secs: 10 }
+    }
+}
+
+/// Watchtower 会話の記憶 (chat_history / 蒸留サマリー)
+///
+/// `persist = false` なら会話を一切保存せず、過去の履歴・サマリーも会話に使わない。
+/// 保存済みの記憶はチャンネルごとに `/forget` で消せる (消去の記録は残る)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ChatMemoryConfig {
+    pub persist: bool,
+}
+
+impl Default for ChatMemoryConfig {
+    fn default() -> Self {
+        Self { persist: true }
+    }
+}
+
+/// 手動レンダーの取り込み設定
+///
+/// `dir` に置かれた動画を検出し、Discord でトピックとスタイルを尋ねてから
+/// 持ち込み映像のジョブとして通常のパイプライン (ミックス・納品・公開・計測) に乗せる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WatchFolderConfig {
+    pub enabled: bool,
+    /// 監視するディレクトリ (取り込み待ちは `.pending/`、ジョブ化済みは `.claimed/` に移される)
+    pub dir: String,
+    /// 走査間隔 (秒)
+    pub poll_secs: u64,
+    /// 最終更新からこの秒数が経つまでは書き込み中とみなして触らない
+    pub settle_secs: u64,
+    /// 取り込んだジョブのチャンネル (空なら default)
+    pub channel: String,
+}
+
+impl Default for WatchFolderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "watch_inbox".to_string(),
+            poll_secs: 30,
+            settle_secs: 15,
+            channel: String::new(),
+        }
+    }
+}
+
+/// 定型台本の設定
+///
+/// テンプレート本体は `file` (既定 `templates.toml`) に書く。チャンネルの `script_template` か
+/// リクエストの指定があればそれを使い、トレンドに The Ethical Circuit Breaker の語か `sensitive_keywords` が
+/// 含まれる日は `sensitive_template` に切り替えて Gemini を呼ばない (未設定ならトレンド項目を差し込まず Gemini に任せる)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TemplatesConfig {
+    pub file: String,
+    /// 組み込みの語に加えて、トレンド項目にこれらが含まれていたら安全側に切り替える (大文字小文字を区別しない部分一致)
+    pub sensitive_keywords: Vec<String>,
+    /// センシティブな日に使うテンプレート名 (空なら切り替えない)
+    pub sensitive_template: String,
+}
+
+impl Default for TemplatesConfig {
+    fn default() -> Self {
+        Self {
+            file: "templates.toml".to_string(),
+            sensitive_keywords: Vec::new(),
+            sensitive_template: String::new(),
+        }
+    }
+}
+
+/// 工程ウォッチドッグ設定
+///
+/// JobWorker が工程 (concept / assets / voice / forge / thumbnail) の切り替わりごとに Heartbeat を打ち、
+/// 1 工程が締め切りを超えたらその実行を打ち切って、その工程から (生成済みの素材は再利用して) やり直す。
+/// 承認待ちのように表にない工程には締め切りを設けない。
+/// 既定では無効 (`enabled = true` で有効にする)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// 工程名 -> 締め切り (分)
+    pub stage_deadline_mins: BTreeMap<String, u64>,
+    /// 同じ工程をやり直す回数の上限 (超えたらジョブごと一時的な失敗として扱う)
+    pub max_stage_retries: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stage_deadline_mins: [("concept", 10), ("assets", 45), ("voice", 20), ("forge", 30), ("thumbnail", 5)]
+                .into_iter()
+                .map(|(stage, mins)| (stage.to_string(), mins))
+                .collect(),
+            max_stage_retries: 1,
+        }
+    }
+}
+
+/// 停止設定
+///
+/// `/stop`・SIGTERM を受けたら新規ジョブの取り出しをやめ、実行中のジョブを待ってから落ちる。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    /// 実行中のジョブを待つ上限 (秒)。超えたらジョブを Pending に戻して次回起動で続きから作る
+    pub drain_timeout_secs: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self { drain_timeout_secs: 300 }
+    }
+}
+
+/// 画像品質ゲート設定
+///
+/// 生成シーンのフレームを Gemini Vision に見せてビジュアルプロンプトとの一致度を採点させ、
+/// 閾値を下回ったシーンはプロンプトを補正して再生成する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VisionQaConfig {
+    pub enabled: bool,
+    pub model: String,
+    /// 合格ライン (0.0 - 1.0)
+    pub min_score: f32,
+    /// 1シーンあたりの再生成回数の上限
+    pub max_regenerations: u32,
+    /// 採点に使うフレーム数 (動画シーンの場合)
+    pub frames_per_scene: usize,
+}
+
+impl Default for VisionQaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: "gemini-2.5-flash".to_string(),
+            min_score: 0.6,
+            max_regenerations: 2,
+            frames_per_scene: 3,
+        }
+    }
+}
+
+/// 生成静止画の美的スコアゲート設定
+///
+/// アニメーション前に ComfyUI の出力を 1-10 で採点し、閾値未満なら新しいシードで再生成する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AestheticConfig {
+    pub enabled: bool,
+    /// 採点サイドカーの URL (空なら `model` の Vision LLM で採点)
+    pub scorer_url: String,
+    pub model: String,
+    /// 合格ライン (1.0 - 10.0)
+    pub min_score: f32,
+    /// 1シーンあたりの再生成回数の上限
+    pub max_regenerations: u32,
+    /// 採点リクエストのタイムアウト (秒)
+    pub timeout_secs: u64,
+}
+
+impl Default for AestheticConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scorer_url: String::new(),
+            model: "gemini-2.5-flash-lite".to_string(),
+            min_score: 5.0,
+            max_regenerations: 2,
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// ComfyUI 出力の安全性チェック設定
+///
+/// 描かれた画像 (動画はフレーム) を NSFW / 暴力表現の分類器にかけ、`threshold` 以上なら棄却してシードを変えて描き直す。
+/// 判定できなかった出力は採用しない。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SafetyConfig {
+    pub enabled: bool,
+    /// 分類サイドカーの URL (空なら `model` の Vision LLM でモデレーション)
+    pub classifier_url: String,
+    pub model: String,
+    /// 棄却ライン (0.0 - 1.0)
+    pub threshold: f32,
+    /// 1 回の生成で描き直す上限 (超えたらジョブを失敗にする)
+    pub max_regenerations: u32,
+    /// 判定リクエストのタイムアウト (秒)
+    pub timeout_secs: u64,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            classifier_url: String::new(),
+            model: "gemini-2.5-flash-lite".to_string(),
+            threshold: 0.7,
+            max_regenerations: 2,
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// シーン素材の使い回し検知設定
+///
+/// ビジュアルプロンプトの SimHash と静止画の dHash を直近の動画と比べ、近すぎればずらして描き直す。
+/// 距離は 64 ビット中の異なるビット数 (小さいほど似ている)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FingerprintConfig {
+    pub enabled: bool,
+    /// 比較対象にする直近の日数
+    pub lookback_days: i64,
+    /// これ以下ならプロンプトが同じとみなす
+    pub max_prompt_distance: u32,
+    /// これ以下なら画像が同じとみなす
+    pub max_image_distance: u32,
+    /// 1 シーンあたりのシードの引き直し上限
+    pub max_nudges: u32,
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookback_days: 28,
+            max_prompt_distance: 6,
+            max_image_distance: 10,
+            max_nudges: 2,
+        }
+    }
+}
+
+/// 効果音 (SFX) の挿入設定
+///
+/// 幕の切り替わりに `transition` の効果音を自動で置き、コンセプトが指定した強調点にも重ねる。
+/// 効果音は `library_dir` の `{種類}.wav` / `{種類}_2.wav` … (種類ごとに順に使い回す)。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SfxConfig {
+    pub enabled: bool,
+    pub library_dir: String,
+    /// 幕の切り替わりに置く効果音の種類 (空なら自動配置しない)
+    pub transition: String,
+    /// 切り替わりの何秒前から鳴らすか (ピークを切り替わりに合わせる)
+    pub transition_lead_secs: f32,
+    /// 効果音の音量 (dB, ナレーション基準)
+    pub gain_db: f32,
+    /// 1 本あたりの上限 (鳴らしすぎ防止)
+    pub max_cues: usize,
+}
+
+impl Default for SfxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            library_dir: "resources/sfx".to_string(),
+            transition: "whoosh".to_string(),
+            transition_lead_secs: 0.25,
+            gain_db: -8.0,
+            max_cues: 8,
+        }
+    }
+}
+
+/// Karma の意味検索 (埋め込み) 設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct KarmaEmbeddingConfig {
+    /// "gemini" | "ollama" | "none" (none なら従来の LIKE 検索)
+    pub provider: String,
+    /// 空ならプロバイダ既定 (gemini: text-embedding-004, ollama: nomic-embed-text)
+    pub model: String,
+    /// 関連スキル外の教訓を採用する最低コサイン類似度
+    pub min_similarity: f32,
+}
+
+impl Default for KarmaEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            provider: "gemini".to_string(),
+            model: String::new(),
+            min_similarity: 0.55,
+        }
+    }
+}
+
+impl KarmaEmbeddingConfig {
+    /// プロバイダ既定を反映したモデル名
+    pub fn resolved_model(&self) -> &str {
+        match (self.model.is_empty(), self.provider.as_str()) {
+            (false, _) => &self.model,
+            (true, "ollama") => "nomic-embed-text",
+            _ => "text-embedding-004",
+        }
+    }
+}
+
+/// 字幕の読み速度ゲート設定
+///
+/// CPS (1秒あたりの表示文字数) が閾値を超えた幕は、表示テキストを LLM で圧縮する。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SubtitleQaConfig {
+    pub enabled: bool,
+    /// 日本語・中国語・韓国語の上限 CPS
+    pub max_cps_cjk: f32,
+    /// それ以外 (英語等) の上限 CPS
+    pub max_cps_latin: f32,
+    /// 超過時に表示テキストの圧縮を依頼するか (false なら計測のみ)
+    pub compress_on_violation: bool,
+}
+
+impl Default for SubtitleQaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_cps_cjk: 8.0,
+            max_cps_latin: 20.0,
+            compress_on_violation: true,
+        }
+    }
+}
+
+impl SubtitleQaConfig {
+    pub fn max_cps_for_lang(&self, lang: &str) -> f32 {
+        match lang {
+            "ja" | "zh" | "ko" => self.max_cps_cjk,
+            _ => self.max_cps_latin,
+        }
+    }
+}
+
+/// 定期ジョブのスケジュール設定 (6フィールド cron 式: 秒 分 時 日 月 曜日)
+///
+/// 空文字列または `"off"` を指定したジョブは登録されない。
+/// 未指定のキーはデフォルト（従来のハードコード値）にフォールバックする。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CronConfig {
+    /// The Samsara Protocol (次ジョブの自律合成)
+    pub samsara: String,
+    /// The Zombie Hunter (ハートビート途絶ジョブの回収)
+    pub zombie_hunter: String,
+    /// Deferred Distillation (未抽出ログからのカルマ抽出)
+    pub deferred_distillation: String,
+    /// DB Scavenger (古いジョブ・チャットの削除)
+    pub db_scavenger: String,
+    /// Memory Distiller (対話履歴の要約)
+    pub memory_distiller: String,
+    /// スケジューラ生存確認ログ
+    pub health_check: String,
+    /// 朝の挨拶 (Proactive Talk)
+    pub morning_greeting: String,
+    /// File Scavenger (ワークスペース・ComfyUI temp の清掃)
+    pub file_scavenger: String,
+    /// The Sentinel (SNS マイルストーン監視)
+    pub sentinel: String,
+    /// The Oracle Evaluator (最終評価)
+    pub oracle: String,
+    /// The Karma Distiller (カルマ圧縮)
+    pub karma_distiller: String,
+    /// 蒸留した教訓を Watchtower で承認されるまで有効にしない (false なら従来どおり即座に置き換える)
+    pub karma_distiller_review: bool,
+    /// 夜間セルフテスト (朝の Samsara の前に制作環境の故障を見つける)
+    pub self_test: String,
+    /// 監査ログの夜間ダイジェスト (直近 24 時間の操作を Discord へ)
+    pub audit_digest: String,
+    /// 制作レポート (完了数・失敗率・平均レンダー時間・カルマ・上位動画を Discord へ)
+    pub production_report: String,
+    /// 制作レポートの集計期間 (日。週次にするなら 7 と週 1 回のスケジュールを組み合わせる)
+    pub production_report_days: u32,
+    /// Samsara が企画時に用いる SOUL プロファイル名 (`[souls]` のキー。空ならチャンネルの SOUL)
+    pub samsara_soul: String,
+    /// Samsara が直近この日数に積んだトピックと似た企画を避ける (0 で無効)
+    pub samsara_dedupe_days: i64,
+    /// 重複とみなすトピック類似度 (文字 bigram の Jaccard, 0.0 - 1.0)
+    pub samsara_dedupe_threshold: f64,
+    /// 各ジョブの発火を 0 - この秒数だけランダムに遅らせる (同時刻のジョブが API に殺到しないように。0 で無効)
+    pub jitter_secs: u64,
+    /// 起動時に取りこぼしを補うジョブ (最後の成功がスケジュールの周期より古ければ 1 回だけ実行する)
+    pub catch_up: Vec<String>,
+}
+
+impl Default for CronConfig {
+    fn default() -> Self {
+        Self {
+            samsara: "0 0 7,19 * * *".to_string(),
+            zombie_hunter: "0 */15 * * * *".to_string(),
+            deferred_distillation: "0 */5 * * * *".to_string(),
+            db_scavenger: "0 0 1 * * *".to_string(),
+            memory_distiller: "0 30 1 * * *".to_string(),
+            health_check: "0 */10 * * * *".to_string(),
+            morning_greeting: "0 0 9 * * *".to_string(),
+            file_scavenger: "0 0 2 * * *".to_string(),
+            sentinel: "0 0 */4 * * *".to_string(),
+            oracle: "0 0 * * * *".to_string(),
+            karma_distiller: "0 0 4 * * *".to_string(),
+            karma_distiller_review: true,
+            self_test: "0 30 5 * * *".to_string(),
+            audit_digest: "0 55 23 * * *".to_string(),
+            production_report: "0 0 8 * * *".to_string(),
+            production_report_days: 1,
+            samsara_soul: String::new(),
+            samsara_dedupe_days: 7,
+            samsara_dedupe_threshold: 0.6,
+            jitter_secs: 60,
+            catch_up: ["samsara", "sentinel", "oracle", "memory_distiller", "karma_distiller", "db_scavenger"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+impl CronConfig {
+    /// 空文字列 / "off" / "disabled" は無効化扱い
+    pub fn is_disabled(expr: &str) -> bool {
+        let e = expr.trim();
+        e.is_empty() || e.eq_ignore_ascii_case("off") || e.eq_ignore_ascii_case("disabled")
+    }
+
+    /// (ジョブ名, スケジュール) の一覧
+    pub fn entries(&self) -> [(&'static str, &str); 14] {
+        [
+            ("samsara", &self.samsara),
+            ("zombie_hunter", &self.zombie_hunter),
+            ("deferred_distillation", &self.deferred_distillation),
+            ("db_scavenger", &self.db_scavenger),
+            ("memory_distiller", &self.memory_distiller),
+            ("health_check", &self.health_check),
+            ("morning_greeting", &self.morning_greeting),
+            ("file_scavenger", &self.file_scavenger),
+            ("sentinel", &self.sentinel),
+            ("oracle", &self.oracle),
+            ("karma_distiller", &self.karma_distiller),
+            ("self_test", &self.self_test),
+            ("audit_digest", &self.audit_digest),
+            ("production_report", &self.production_report),
+        ]
+    }
+
+    /// 構文チェック: 有効なジョブはスケジューラと同じ解釈 (秒あり 6 または 7 フィールド) で読める cron 式でなければならない
+    pub fn validate(&self) -> Result<(), String> {
+        let parser = CronParser::builder().seconds(Seconds::Required).build();
+        for (name, expr) in self.entries() {
+            if Self::is_disabled(expr) {
+                continue;
+            }
+            if let Err(e) = parser.parse(expr.trim()) {
+                return Err(format!("cron.{}: invalid expression '{}' (sec min hour day month weekday): {}", name, expr, e));
+            }
+        }
+        if let Some(unknown) = self.catch_up.iter().find(|job| !self.entries().iter().any(|(name, _)| *name == job.as_str())) {
+            return Err(format!("cron.catch_up: unknown job '{}'", unknown));
+        }
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for FactoryConfig {
@@ -51,12 +1470,85 @@ impl std::fmt::Debug for FactoryConfig {
             .field("youtube_api_key", if self.youtube_api_key.is_empty() { &"" } else { &"***" })
             .field("gemini_api_key", if self.gemini_api_key.is_empty() { &"" } else { &"***" })
             .field("tiktok_api_key", if self.tiktok_api_key.is_empty() { &"" } else { &"***" })
+            .field("instagram_access_token", if self.instagram_access_token.is_empty() { &"" } else { &"***" })
             .field("unleashed_mode", &self.unleashed_mode)
+            .field("cron", &self.cron)
+            .field("subtitle_qa", &self.subtitle_qa)
+            .field("vision_qa", &self.vision_qa)
+            .field("aesthetic", &self.aesthetic)
+            .field("safety", &self.safety)
+            .field("fingerprint", &self.fingerprint)
+            .field("sfx", &self.sfx)
+            .field("karma_embedding", &self.karma_embedding)
+            .field("sidecar", &self.sidecar)
+            .field("ports", &self.ports)
+            .field("reframe", &self.reframe)
+            .field("export", &self.export)
+            .field("upscale", &self.upscale)
+            .field("trends", &self.trends)
+            .field("channels", &self.channels)
+            .field("souls", &self.souls)
+            .field("characters", &self.characters)
+            .field("approval", &self.approval)
+            .field("publisher", &self.publisher)
+            .field("auth", &self.auth)
+            .field("review", &self.review)
+            .field("watch_folder", &self.watch_folder)
+            .field("shutdown", &self.shutdown)
+            .field("templates", &self.templates)
+            .field("watchdog", &self.watchdog)
+            .field("storage", &self.storage)
+            .field("costs", &self.costs)
+            .field("self_test", &self.self_test)
+            .field("encoding", &self.encoding)
+            .field("encoder", &self.encoder)
+            .field("chat_memory", &self.chat_memory)
+            .field("topic_policy", &self.topic_policy)
+            .field("oracle", &self.oracle)
+            .field("discord_preview", &self.discord_preview)
+            .field("notifiers", &self.notifiers)
+            .field("admission", &self.admission)
+            .field("worker", &self.worker)
             .finish()
     }
 }
 
 impl FactoryConfig {
+    /// 定義済みチャンネル名の一覧 (未定義なら `default` のみ)
+    pub fn channel_names(&self) -> Vec<String> {
+        if self.channels.is_empty() {
+            vec![DEFAULT_CHANNEL.to_string()]
+        } else {
+            self.channels.keys().cloned().collect()
+        }
+    }
+
+    /// グローバル設定でフォールバックを埋めたチャンネルプロファイルを返す
+    ///
+    /// 未定義のチャンネル名はグローバル設定そのもの (`default` 相当) として扱う。
+    pub fn resolve_channel(&self, name: &str) -> ChannelProfile {
+        let mut profile = self.channels.get(name).cloned().unwrap_or_default();
+        if profile.soul_file.is_empty() {
+            profile.soul_file = "SOUL.md".to_string();
+        }
+        if profile.export_dir.is_empty() {
+            profile.export_dir = self.export_dir.clone();
+        }
+        if profile.youtube_api_key.is_empty() {
+            profile.youtube_api_key = self.youtube_api_key.clone();
+        }
+        if profile.youtube_oauth_token.is_empty() {
+            profile.youtube_oauth_token = self.publisher.youtube_oauth_token.clone();
+        }
+        if profile.tiktok_access_token.is_empty() {
+            profile.tiktok_access_token = self.tiktok_api_key.clone();
+        }
+        if profile.instagram_access_token.is_empty() {
+            profile.instagram_access_token = self.instagram_access_token.clone();
+        }
+        profile
+    }
+
     /// 設定をファイルまたは環境変数から読み込む
     pub fn load() -> Result<Self, config::ConfigError> {
         let settings = config::Config::builder()
@@ -75,6 +1567,7 @@ impl FactoryConfig {
             .set_default("youtube_api_key", std::env::var("YOUTUBE_API_KEY").unwrap_or_else(|_| "".to_string()))?
             .set_default("gemini_api_key", std::env::var("GEMINI_API_KEY").unwrap_or_else(|_| "".to_string()))?
             .set_default("tiktok_api_key", std::env::var("TIKTOK_API_KEY").unwrap_or_else(|_| "".to_string()))?
+            .set_default("instagram_access_token", std::env::var("INSTAGRAM_ACCESS_TOKEN").unwrap_or_else(|_| "".to_string()))?
             .set_default("unleashed_mode", std::env::var("UNLEASHED_MODE").map(|v| v.to_lowercase() == "true").unwrap_or(false))?
             // config.toml があれば読み込む
             .add_source(config::File::with_name("config").required(false))
@@ -82,7 +1575,9 @@ impl FactoryConfig {
             .add_source(config::Environment::with_prefix("SHORTS_FACTORY"))
             .build()?;
 
-        settings.try_deserialize()
+        let config: Self = settings.try_deserialize()?;
+        config.cron.validate().map_err(config::ConfigError::Message)?;
+        Ok(config)
     }
 }
 
@@ -104,7 +1599,45 @@ impl Default for FactoryConfig {
                 youtube_api_key: std::env::var("YOUTUBE_API_KEY").unwrap_or_else(|_| "".to_string()),
                 gemini_api_key: std::env::var("GEMINI_API_KEY").unwrap_or_else(|_| "".to_string()),
                 tiktok_api_key: std::env::var("TIKTOK_API_KEY").unwrap_or_else(|_| "".to_string()),
+                instagram_access_token: std::env::var("INSTAGRAM_ACCESS_TOKEN").unwrap_or_else(|_| "".to_string()),
                 unleashed_mode: std::env::var("UNLEASHED_MODE").map(|v| v.to_lowercase() == "true").unwrap_or(false),
+                cron: CronConfig::default(),
+                subtitle_qa: SubtitleQaConfig::default(),
+                vision_qa: VisionQaConfig::default(),
+                aesthetic: AestheticConfig::default(),
+                safety: SafetyConfig::default(),
+                fingerprint: FingerprintConfig::default(),
+                sfx: SfxConfig::default(),
+                karma_embedding: KarmaEmbeddingConfig::default(),
+                sidecar: SidecarConfig::default(),
+                ports: PortsConfig::default(),
+                reframe: ReframeConfig::default(),
+                channels: BTreeMap::new(),
+                souls: BTreeMap::new(),
+                characters: BTreeMap::new(),
+                approval: ApprovalConfig::default(),
+                publisher: PublisherConfig::default(),
+                auth: AuthConfig::default(),
+                review: ReviewConfig::default(),
+                watch_folder: WatchFolderConfig::default(),
+                shutdown: ShutdownConfig::default(),
+                templates: TemplatesConfig::default(),
+                watchdog: WatchdogConfig::default(),
+                storage: StorageConfig::default(),
+                costs: CostsConfig::default(),
+                self_test: SelfTestConfig::default(),
+                encoding: EncodingConfig::default(),
+                encoder: EncoderConfig::default(),
+                export: ExportConfig::default(),
+                upscale: UpscaleConfig::default(),
+                trends: TrendsConfig::default(),
+                chat_memory: ChatMemoryConfig::default(),
+                topic_policy: TopicPolicyConfig::default(),
+                oracle: OracleConfig::default(),
+                discord_preview: DiscordPreviewConfig::default(),
+                notifiers: NotifiersConfig::default(),
+                admission: AdmissionConfig::default(),
+                worker: WorkerConfig::default(),
             }
         })
     }
@@ -134,6 +1667,7 @@ mod tests {
         writeln!(file, "batch_size = 5").unwrap();
         writeln!(file, "comfyui_timeout_secs = 60").unwrap();
         writeln!(file, "model_name = \"custom-model\"").unwrap();
+        writeln!(file, "script_model = \"gemini-2.0-flash\"").unwrap();
         writeln!(file, "comfyui_base_dir = \"custom_dir\"").unwrap();
         writeln!(file, "brave_api_key = \"\"").unwrap();
         writeln!(file, "export_dir = \"/tmp/exports\"").unwrap();
@@ -142,6 +1676,7 @@ mod tests {
         writeln!(file, "youtube_api_key = \"\"").unwrap();
         writeln!(file, "gemini_api_key = \"\"").unwrap();
         writeln!(file, "tiktok_api_key = \"\"").unwrap();
+        writeln!(file, "unleashed_mode = false").unwrap();
         
         // config::File::from(path) を使って明示的なファイルを読み込む
         // 拡張子があるためフォーマットは自動判別される
@@ -153,5 +1688,86 @@ mod tests {
         let config: FactoryConfig = settings.try_deserialize().unwrap();
         assert_eq!(config.ollama_url, "http://custom:11434/v1");
         assert_eq!(config.model_name, "custom-model");
+        // [cron] セクション省略時は従来のスケジュール
+        assert_eq!(config.cron, CronConfig::default());
+    }
+
+    #[test]
+    fn test_cron_section_overrides_and_disables() {
+        let mut file = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .unwrap();
+        writeln!(file, "[cron]").unwrap();
+        writeln!(file, "samsara = \"0 0 * * * *\"").unwrap();
+        writeln!(file, "morning_greeting = \"off\"").unwrap();
+
+        let settings = config::Config::builder()
+            .add_source(config::File::from(file.path()))
+            .build()
+            .unwrap();
+        let cron: CronConfig = settings.get("cron").unwrap();
+
+        assert_eq!(cron.samsara, "0 0 * * * *");
+        assert!(CronConfig::is_disabled(&cron.morning_greeting));
+        assert_eq!(cron.oracle, CronConfig::default().oracle);
+        assert!(cron.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cron_validation_rejects_malformed() {
+        let cron = CronConfig { sentinel: "every 4 hours".to_string(), ..CronConfig::default() };
+        assert!(cron.validate().is_err());
+
+        let cron = CronConfig { oracle: "0 0 * * *".to_string(), ..CronConfig::default() };
+        let err = cron.validate().unwrap_err();
+        assert!(err.contains("cron.oracle"));
+
+        // フィールド数と文字は正しくても値の範囲外は読めない
+        let cron = CronConfig { samsara: "0 0 25 * * *".to_string(), ..CronConfig::default() };
+        assert!(cron.validate().unwrap_err().contains("cron.samsara"));
+        let cron = CronConfig { samsara: "0 0 7 * * MON-FRI".to_string(), ..CronConfig::default() };
+        assert!(cron.validate().is_ok());
+
+        let cron = CronConfig { catch_up: vec!["samsra".to_string()], ..CronConfig::default() };
+        assert!(cron.validate().unwrap_err().contains("cron.catch_up"));
+    }
+
+    #[test]
+    fn test_channel_profiles_fall_back_to_globals() {
+        let mut file = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .unwrap();
+        writeln!(file, "[channels.tech]").unwrap();
+        writeln!(file, "soul_file = \"souls/tech.md\"").unwrap();
+        writeln!(file, "styles = [\"cinematic\", \"hype\"]").unwrap();
+        writeln!(file, "[channels.calm]").unwrap();
+        writeln!(file, "export_dir = \"/exports/calm\"").unwrap();
+        writeln!(file, "samsara = false").unwrap();
+
+        let settings = config::Config::builder()
+            .add_source(config::File::from(file.path()))
+            .build()
+            .unwrap();
+        let channels: BTreeMap<String, ChannelProfile> = settings.get("channels").unwrap();
+        let config = FactoryConfig { channels, export_dir: "/exports".to_string(), ..FactoryConfig::default() };
+
+        assert_eq!(config.channel_names(), vec!["calm".to_string(), "tech".to_string()]);
+
+        let tech = config.resolve_channel("tech");
+        assert_eq!(tech.soul_file, "souls/tech.md");
+        assert_eq!(tech.export_dir, "/exports");
+        assert!(tech.allows_style("hype"));
+        assert!(!tech.allows_style("documentary"));
+        assert_eq!(tech.karma_namespace(), None);
+        assert_eq!(ChannelProfile { styles: vec!["horror".to_string()], ..tech.clone() }.karma_namespace(), Some("horror"));
+        assert!(tech.samsara);
+
+        let calm = config.resolve_channel("calm");
+        assert_eq!(calm.soul_file, "SOUL.md");
+        assert_eq!(calm.export_dir, "/exports/calm");
+        assert!(calm.allows_style("documentary"));
+        assert!(!calm.samsara);
     }
 }
@@ -1,6 +1,8 @@
 use sysinfo::{System, Pid};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// 秘密情報をログ出力から保護するためのラッパー
 #[derive(Clone, Deserialize, Serialize)]
@@ -35,12 +37,154 @@ pub struct ResourceStatus {
     pub memory_usage_mb: u64,
     pub cpu_usage_percent: f32,
     pub open_files: Option<u64>,
+    /// マシン全体の空きメモリ (MB。ページキャッシュなど解放できる分を含む)
+    #[serde(default)]
+    pub system_available_mb: u64,
+    #[serde(default)]
+    pub system_total_mb: u64,
+    /// マシン全体の CPU 使用率 (%。初回の計測は 0)
+    #[serde(default)]
+    pub system_cpu_percent: f32,
+    /// GPU の使用状況 (計測できない環境では None)
+    #[serde(default)]
+    pub gpu: Option<GpuStatus>,
+}
+
+/// GPU の使用状況
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuStatus {
+    pub name: String,
+    pub vram_used_mb: u64,
+    /// Apple Silicon はユニファイドメモリなので搭載メモリ全体
+    pub vram_total_mb: u64,
+    pub utilization_percent: f32,
+}
+
+/// GPU の計測手段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GpuProbe {
+    /// NVIDIA: NVML の CLI (`nvidia-smi`)
+    Nvidia,
+    /// macOS: IOKit の IOAccelerator 統計 (`ioreg`)
+    AppleIoKit,
+    /// 計測手段が無い (一度失敗したら以後は呼ばない)
+    Unavailable,
+}
+
+/// これより短い間隔では GPU を計り直さない (外部コマンドを起動するため)
+const GPU_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// GPU の計測 (直近の値を保持し、間隔を空けて計り直す)。
+/// 外部コマンドは非同期に起動し、共有された監視の間でも同時に 1 つしか走らせない
+pub struct GpuMonitor {
+    state: tokio::sync::Mutex<GpuState>,
+    /// ユニファイドメモリの総量 (MB)
+    unified_total_mb: u64,
+}
+
+struct GpuState {
+    probe: GpuProbe,
+    last: Option<(Instant, Option<GpuStatus>)>,
+}
+
+impl GpuMonitor {
+    pub fn new(unified_total_mb: u64) -> Self {
+        let probe = if cfg!(target_os = "macos") { GpuProbe::AppleIoKit } else { GpuProbe::Nvidia };
+        Self { state: tokio::sync::Mutex::new(GpuState { probe, last: None }), unified_total_mb }
+    }
+
+    pub async fn sample(&self) -> Option<GpuStatus> {
+        let mut state = self.state.lock().await;
+        if let Some((at, status)) = &state.last {
+            if at.elapsed() < GPU_SAMPLE_INTERVAL {
+                return status.clone();
+            }
+        }
+        let status = match state.probe {
+            GpuProbe::Nvidia => run_probe(&mut state.probe, "nvidia-smi", &["--query-gpu=name,memory.used,memory.total,utilization.gpu", "--format=csv,noheader,nounits"])
+                .await
+                .and_then(|out| parse_nvidia_smi(&out)),
+            GpuProbe::AppleIoKit => run_probe(&mut state.probe, "ioreg", &["-r", "-d", "1", "-c", "IOAccelerator"])
+                .await
+                .and_then(|out| parse_ioreg_accelerator(&out, self.unified_total_mb)),
+            GpuProbe::Unavailable => None,
+        };
+        state.last = Some((Instant::now(), status.clone()));
+        status
+    }
+}
+
+/// 計測コマンドを実行する (起動できなければ以後は計測しない)
+async fn run_probe(probe: &mut GpuProbe, program: &str, args: &[&str]) -> Option<String> {
+    match tokio::process::Command::new(program).args(args).kill_on_drop(true).output().await {
+        Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).into_owned()),
+        Ok(out) => {
+            tracing::debug!("GPU probe {} exited with {}", program, out.status);
+            None
+        }
+        Err(e) => {
+            tracing::info!("📟 GPU metrics unavailable ({} not runnable: {}). VRAM will be reported as 0.", program, e);
+            *probe = GpuProbe::Unavailable;
+            None
+        }
+    }
+}
+
+/// `nvidia-smi --query-gpu=name,memory.used,memory.total,utilization.gpu --format=csv,noheader,nounits` の出力。
+/// 複数枚あれば VRAM の使用率が最も高い (最初に OOM しそうな) GPU を返す
+pub fn parse_nvidia_smi(output: &str) -> Option<GpuStatus> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [name, used, total, util] = fields.as_slice() else { return None };
+            Some(GpuStatus {
+                name: name.to_string(),
+                vram_used_mb: used.parse().ok()?,
+                vram_total_mb: total.parse().ok()?,
+                // 取れない項目は "[N/A]" になる
+                utilization_percent: util.parse().unwrap_or(0.0),
+            })
+        })
+        .max_by(|a, b| {
+            let ratio = |g: &GpuStatus| g.vram_used_mb as f64 / g.vram_total_mb.max(1) as f64;
+            ratio(a).total_cmp(&ratio(b))
+        })
+}
+
+/// `ioreg -r -d 1 -c IOAccelerator` の PerformanceStatistics (バイト単位の "In use system memory" と "Device Utilization %")
+pub fn parse_ioreg_accelerator(output: &str, unified_total_mb: u64) -> Option<GpuStatus> {
+    let number = |key: &str| -> Option<u64> {
+        let pos = output.find(&format!("\"{}\"=", key))?;
+        let rest = &output[pos + key.len() + 3..];
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse().ok()
+    };
+    let used_bytes = number("In use system memory")?;
+    let name = output
+        .find("\"model\" = \"")
+        .and_then(|pos| output[pos + 11..].split('"').next())
+        .unwrap_or("Apple GPU")
+        .to_string();
+    Some(GpuStatus {
+        name,
+        vram_used_mb: used_bytes / 1024 / 1024,
+        vram_total_mb: unified_total_mb,
+        utilization_percent: number("Device Utilization %").unwrap_or(0) as f32,
+    })
 }
 
 /// システムの状態を監視する
 pub struct HealthMonitor {
     sys: System,
     pid: Pid,
+    gpu: Arc<GpuMonitor>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HealthMonitor {
@@ -49,12 +193,20 @@ impl HealthMonitor {
         sys.refresh_all();
         // std::process::id() returns u32, sysinfo::Pid is platform dependent but often u32 or i32
         let pid = Pid::from(std::process::id() as usize);
-        Self { sys, pid }
+        let gpu = Arc::new(GpuMonitor::new(sys.total_memory() / 1024 / 1024));
+        Self { sys, pid, gpu }
     }
 
-    pub fn check(&mut self) -> ResourceStatus {
-        // 特定のプロセスのみリフレッシュ
+    /// GPU の計測 (テレメトリなど他の計測と共有する)
+    pub fn gpu(&self) -> Arc<GpuMonitor> {
+        self.gpu.clone()
+    }
+
+    pub async fn check(&mut self) -> ResourceStatus {
+        // 特定のプロセスと、マシン全体のメモリ・CPU だけリフレッシュ
         self.sys.refresh_process(self.pid);
+        self.sys.refresh_memory();
+        self.sys.refresh_cpu();
         
         let mut memory_usage_mb = 0;
         let mut cpu_usage_percent = 0.0;
@@ -69,6 +221,37 @@ impl HealthMonitor {
             memory_usage_mb,
             cpu_usage_percent,
             open_files: None,
+            system_available_mb: self.sys.available_memory() / 1024 / 1024,
+            system_total_mb: self.sys.total_memory() / 1024 / 1024,
+            system_cpu_percent: self.sys.global_cpu_info().cpu_usage(),
+            gpu: self.gpu.sample().await,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvidia_smi_picks_the_fullest_gpu() {
+        let out = "NVIDIA GeForce RTX 3060, 2048, 12288, 5\nNVIDIA GeForce RTX 4090, 22000, 24564, 97\n";
+        let gpu = parse_nvidia_smi(out).unwrap();
+        assert_eq!((gpu.name.as_str(), gpu.vram_used_mb, gpu.vram_total_mb), ("NVIDIA GeForce RTX 4090", 22000, 24564));
+        assert_eq!(gpu.utilization_percent, 97.0);
+        assert_eq!(parse_nvidia_smi("Tesla T4, 100, 15360, [N/A]").unwrap().utilization_percent, 0.0);
+        assert!(parse_nvidia_smi("").is_none());
+    }
+
+    #[test]
+    fn test_parse_ioreg_accelerator_statistics() {
+        let out = r#"+-o AGXAcceleratorG16X  <class AGXAcceleratorG16X>
+    {
+      "model" = "Apple M4 Pro"
+      "PerformanceStatistics" = {"In use system memory"=6442450944,"Device Utilization %"=42,"Alloc system memory"=9663676416}
+    }"#;
+        let gpu = parse_ioreg_accelerator(out, 49152).unwrap();
+        assert_eq!(gpu, GpuStatus { name: "Apple M4 Pro".into(), vram_used_mb: 6144, vram_total_mb: 49152, utilization_percent: 42.0 });
+        assert!(parse_ioreg_accelerator("{}", 49152).is_none());
+    }
+}
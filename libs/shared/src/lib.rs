@@ -8,3 +8,4 @@ pub mod security;
 pub mod zombie_killer;
 pub mod health;
 pub mod watchtower;
+pub mod notifiers;
@@ -0,0 +1,349 @@
+//! # Notifiers — Discord 以外の通知先 (Slack / Telegram)
+//!
+//! `watchtower::Notifier` の実装。押されたボタンは `ApprovalReply` として Core に戻す
+//! (Slack は Interactivity の POST、Telegram は getUpdates のロングポーリング)。
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::watchtower::{approval_action_id, parse_approval_action, ApprovalPrompt, CompletedTask, Notifier};
+use crate::zombie_killer::http_client_with_timeout;
+
+/// 1 回の getUpdates で待つ秒数 (ロングポーリング)
+pub const TELEGRAM_POLL_SECS: u64 = 30;
+
+/// 通知先で押された承認・却下ボタン
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApprovalReply {
+    pub id: Uuid,
+    pub approved: bool,
+    /// 押したユーザー (監査ログの actor)
+    pub actor: String,
+}
+
+fn completion_text(task: &CompletedTask) -> String {
+    format!(
+        "{} {}\nTopic: {}\nStyle: {}\nJob ID: {}\n{}",
+        if task.succeeded() { "✅" } else { "❌" },
+        if task.succeeded() { "Job Completed" } else { "Job Failed" },
+        task.topic,
+        task.style,
+        task.job_id,
+        task.result
+    )
+}
+
+// --- Slack ---
+
+pub struct SlackNotifier {
+    http: reqwest::Client,
+    token: String,
+    channel: String,
+}
+
+impl SlackNotifier {
+    pub fn new(token: &str, channel: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            http: http_client_with_timeout(Duration::from_secs(15))?,
+            token: token.to_string(),
+            channel: channel.to_string(),
+        })
+    }
+
+    /// 完了通知の Block Kit (サムネイルがあれば画像付き)
+    pub fn completion_blocks(task: &CompletedTask) -> Value {
+        let mut blocks = vec![json!({
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(
+                    "*{} {}*\n*Topic:* {}\n*Style:* {}\n*Job ID:* `{}`\n{}",
+                    if task.succeeded() { "✅" } else { "❌" },
+                    if task.succeeded() { "Job Completed" } else { "Job Failed" },
+                    task.topic, task.style, task.job_id, task.result
+                ),
+            },
+        })];
+        if let Some(url) = &task.thumbnail_url {
+            blocks.push(json!({ "type": "image", "image_url": url, "alt_text": task.topic }));
+        }
+        Value::Array(blocks)
+    }
+
+    /// 承認依頼の Block Kit (承認・却下ボタン付き)
+    pub fn approval_blocks(prompt: &ApprovalPrompt) -> Value {
+        json!([
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("*🚨 Approval Required*\n{}", prompt.description) },
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    {
+                        "type": "button",
+                        "text": { "type": "plain_text", "text": "✅ Approve" },
+                        "style": "primary",
+                        "action_id": approval_action_id(prompt.id, true),
+                        "value": prompt.id.to_string(),
+                    },
+                    {
+                        "type": "button",
+                        "text": { "type": "plain_text", "text": "❌ Reject" },
+                        "style": "danger",
+                        "action_id": approval_action_id(prompt.id, false),
+                        "value": prompt.id.to_string(),
+                    },
+                ],
+            },
+        ])
+    }
+
+    async fn post(&self, text: String, blocks: Value) -> anyhow::Result<()> {
+        let res: Value = self.http
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&self.token)
+            .json(&json!({ "channel": self.channel, "text": text, "blocks": blocks }))
+            .send()
+            .await?
+            .json()
+            .await?;
+        if res["ok"].as_bool() != Some(true) {
+            return Err(anyhow!("chat.postMessage failed: {}", res["error"].as_str().unwrap_or("unknown error")));
+        }
+        Ok(())
+    }
+
+    /// ボタンを押されたメッセージを結果の文面に置き換える (Interactivity の `response_url`)
+    pub async fn replace_original(&self, response_url: &str, text: &str) -> anyhow::Result<()> {
+        self.http
+            .post(response_url)
+            .json(&json!({ "replace_original": true, "text": text }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Slack の Interactivity ペイロード (`payload=` の JSON) から承認操作と `response_url` を取り出す
+pub fn parse_slack_action(payload: &str) -> Option<(ApprovalReply, Option<String>)> {
+    let payload: Value = serde_json::from_str(payload).ok()?;
+    if payload["type"].as_str() != Some("block_actions") {
+        return None;
+    }
+    let (id, approved) = payload["actions"].as_array()?
+        .iter()
+        .find_map(|a| a["action_id"].as_str().and_then(parse_approval_action))?;
+    let user = &payload["user"];
+    let actor = format!(
+        "slack:{} ({})",
+        user["username"].as_str().or(user["name"].as_str()).unwrap_or("unknown"),
+        user["id"].as_str().unwrap_or("?")
+    );
+    let response_url = payload["response_url"].as_str().map(str::to_string);
+    Some((ApprovalReply { id, approved, actor }, response_url))
+}
+
+#[async_trait::async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn task_completed(&self, task: &CompletedTask) -> anyhow::Result<()> {
+        self.post(completion_text(task), Self::completion_blocks(task)).await
+    }
+
+    async fn approval_request(&self, prompt: &ApprovalPrompt) -> anyhow::Result<()> {
+        self.post(format!("🚨 Approval Required\n{}", prompt.description), Self::approval_blocks(prompt)).await
+    }
+}
+
+// --- Telegram ---
+
+pub struct TelegramNotifier {
+    http: reqwest::Client,
+    token: String,
+    chat_id: String,
+    /// 次に受け取る update_id
+    offset: AtomicI64,
+}
+
+/// getUpdates で届いたボタン操作 1 件
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelegramCallback {
+    pub callback_id: String,
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub reply: ApprovalReply,
+}
+
+impl TelegramNotifier {
+    pub fn new(token: &str, chat_id: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            // ロングポーリングより長く待つ
+            http: http_client_with_timeout(Duration::from_secs(TELEGRAM_POLL_SECS + 30))?,
+            token: token.to_string(),
+            chat_id: chat_id.to_string(),
+            offset: AtomicI64::new(0),
+        })
+    }
+
+    /// 承認依頼のインラインキーボード
+    pub fn approval_keyboard(prompt: &ApprovalPrompt) -> Value {
+        json!({
+            "inline_keyboard": [[
+                { "text": "✅ Approve", "callback_data": approval_action_id(prompt.id, true) },
+                { "text": "❌ Reject", "callback_data": approval_action_id(prompt.id, false) },
+            ]]
+        })
+    }
+
+    async fn call(&self, method: &str, body: Value) -> anyhow::Result<Value> {
+        let res: Value = self.http
+            .post(format!("https://api.telegram.org/bot{}/{}", self.token, method))
+            .json(&body)
+            .send()
+            .await
+            // URL にトークンが入るのでエラーからは外す
+            .map_err(|e| anyhow!("Telegram {} request failed: {}", method, e.without_url()))?
+            .json()
+            .await
+            .map_err(|e| anyhow!("Telegram {} returned an unreadable response: {}", method, e.without_url()))?;
+        if res["ok"].as_bool() != Some(true) {
+            return Err(anyhow!("Telegram {} failed: {}", method, res["description"].as_str().unwrap_or("unknown error")));
+        }
+        Ok(res["result"].clone())
+    }
+
+    /// 押されたボタンを待って受け取り、押されたメッセージからボタンを外す
+    pub async fn poll_replies(&self) -> anyhow::Result<Vec<ApprovalReply>> {
+        let updates = self.call("getUpdates", json!({
+            "offset": self.offset.load(Ordering::Relaxed),
+            "timeout": TELEGRAM_POLL_SECS,
+            "allowed_updates": ["callback_query"],
+        })).await?;
+        let (callbacks, next) = parse_telegram_updates(&updates);
+        if let Some(next) = next {
+            self.offset.store(next, Ordering::Relaxed);
+        }
+
+        let mut replies = Vec::new();
+        for cb in callbacks {
+            // 設定したチャット以外で押されたボタンは受け付けない (公開チャンネル名の指定なら照合しない)
+            if !self.chat_id.starts_with('@') && self.chat_id != cb.chat_id.to_string() {
+                continue;
+            }
+            let verdict = if cb.reply.approved { "✅ Approved" } else { "❌ Rejected" };
+            let _ = self.call("answerCallbackQuery", json!({ "callback_query_id": cb.callback_id, "text": verdict })).await;
+            let _ = self.call("editMessageReplyMarkup", json!({
+                "chat_id": cb.chat_id,
+                "message_id": cb.message_id,
+                "reply_markup": { "inline_keyboard": [] },
+            })).await;
+            let _ = self.call("sendMessage", json!({
+                "chat_id": cb.chat_id,
+                "reply_to_message_id": cb.message_id,
+                "text": format!("{} by {}", verdict, cb.reply.actor),
+            })).await;
+            replies.push(cb.reply);
+        }
+        Ok(replies)
+    }
+}
+
+/// getUpdates の結果から承認ボタンの操作と、次の offset を取り出す
+pub fn parse_telegram_updates(updates: &Value) -> (Vec<TelegramCallback>, Option<i64>) {
+    let Some(updates) = updates.as_array() else { return (Vec::new(), None) };
+    let next = updates.iter().filter_map(|u| u["update_id"].as_i64()).max().map(|id| id + 1);
+    let callbacks = updates.iter()
+        .filter_map(|u| {
+            let cb = &u["callback_query"];
+            let (id, approved) = parse_approval_action(cb["data"].as_str()?)?;
+            let from = &cb["from"];
+            let name = from["username"].as_str().or(from["first_name"].as_str()).unwrap_or("unknown");
+            Some(TelegramCallback {
+                callback_id: cb["id"].as_str()?.to_string(),
+                chat_id: cb["message"]["chat"]["id"].as_i64()?,
+                message_id: cb["message"]["message_id"].as_i64()?,
+                reply: ApprovalReply { id, approved, actor: format!("telegram:{} ({})", name, from["id"].as_i64().unwrap_or_default()) },
+            })
+        })
+        .collect();
+    (callbacks, next)
+}
+
+#[async_trait::async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn task_completed(&self, task: &CompletedTask) -> anyhow::Result<()> {
+        self.call("sendMessage", json!({ "chat_id": self.chat_id, "text": completion_text(task) })).await?;
+        Ok(())
+    }
+
+    async fn approval_request(&self, prompt: &ApprovalPrompt) -> anyhow::Result<()> {
+        self.call("sendMessage", json!({
+            "chat_id": self.chat_id,
+            "text": format!("🚨 Approval Required\n{}", prompt.description),
+            "reply_markup": Self::approval_keyboard(prompt),
+        })).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slack_buttons_round_trip_through_the_interaction_payload() {
+        let prompt = ApprovalPrompt { id: Uuid::new_v4(), description: "First render".into() };
+        let blocks = SlackNotifier::approval_blocks(&prompt);
+        let reject = blocks[1]["elements"][1]["action_id"].as_str().unwrap().to_string();
+
+        let payload = json!({
+            "type": "block_actions",
+            "user": { "id": "U42", "username": "alice" },
+            "actions": [{ "action_id": reject, "value": prompt.id.to_string() }],
+            "response_url": "https://hooks.slack.com/actions/T/1/x",
+        });
+        let (reply, response_url) = parse_slack_action(&payload.to_string()).unwrap();
+        assert_eq!(reply, ApprovalReply { id: prompt.id, approved: false, actor: "slack:alice (U42)".into() });
+        assert_eq!(response_url.as_deref(), Some("https://hooks.slack.com/actions/T/1/x"));
+
+        // 承認ボタン以外の操作は無視する
+        let other = json!({ "type": "block_actions", "user": {}, "actions": [{ "action_id": "retry_x" }] });
+        assert!(parse_slack_action(&other.to_string()).is_none());
+    }
+
+    #[test]
+    fn test_telegram_updates_yield_callbacks_and_next_offset() {
+        let id = Uuid::new_v4();
+        let keyboard = TelegramNotifier::approval_keyboard(&ApprovalPrompt { id, description: String::new() });
+        let data = keyboard["inline_keyboard"][0][0]["callback_data"].as_str().unwrap();
+        assert!(data.len() <= 64, "Telegram caps callback_data at 64 bytes");
+
+        let updates = json!([
+            { "update_id": 7, "message": { "text": "hello" } },
+            { "update_id": 8, "callback_query": {
+                "id": "cb1",
+                "from": { "id": 99, "first_name": "Bob" },
+                "data": data,
+                "message": { "message_id": 5, "chat": { "id": -100 } },
+            } },
+        ]);
+        let (callbacks, next) = parse_telegram_updates(&updates);
+        assert_eq!(next, Some(9));
+        assert_eq!(callbacks.len(), 1);
+        assert_eq!((callbacks[0].chat_id, callbacks[0].message_id), (-100, 5));
+        assert_eq!(callbacks[0].reply, ApprovalReply { id, approved: true, actor: "telegram:Bob (99)".into() });
+    }
+}
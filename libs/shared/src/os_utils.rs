@@ -3,6 +3,7 @@
 //! App Nap の防止、Spotlight インデックス対象外の設定など、
 //! macOS 上での長時間稼働を安定させるためのユーティリティ。
 
+#[cfg(target_os = "macos")]
 use std::process::Command;
 use std::path::Path;
 
@@ -18,7 +19,7 @@ pub fn prevent_app_nap() -> Result<std::process::Child, std::io::Error> {
         // -d: prevent display sleep (optional, but good for visibility)
         // -m: prevent disk idle sleep
         Command::new("caffeinate")
-            .args(&["-i", "-m"])
+            .args(["-i", "-m"])
             .spawn()
     }
     #[cfg(not(target_os = "macos"))]
@@ -1,12 +1,45 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, schemars::JsonSchema)]
 pub struct SystemStatus {
     pub cpu_usage: f32,
     pub memory_used_mb: u64,
     pub vram_used_mb: u64,
     pub active_job_id: Option<String>,
+    /// VRAM の総量 (0 なら GPU を計測できていない。Apple Silicon はユニファイドメモリ全体)
+    #[serde(default)]
+    pub vram_total_mb: u64,
+    /// GPU の使用率 (%)
+    #[serde(default)]
+    pub gpu_utilization: Option<f32>,
+    /// 実行中の工程 (concept / voice / ...)
+    #[serde(default)]
+    pub stage: Option<String>,
+    /// 実行を待っているジョブの数
+    #[serde(default)]
+    pub queue_depth: i64,
+    /// 自律エンジンが一時停止中 (`/pause`。実行中のジョブは最後まで作る)
+    #[serde(default)]
+    pub paused: bool,
+    /// Global API サーキットブレーカーの連続失敗数
+    #[serde(default)]
+    pub api_failures: i64,
+    /// ブレーカーが開く連続失敗数 (0 なら不明)
+    #[serde(default)]
+    pub api_failure_limit: i64,
+}
+
+impl SystemStatus {
+    /// VRAM の使用率 (0.0 - 1.0、総量が不明なら None)
+    pub fn vram_ratio(&self) -> Option<f64> {
+        (self.vram_total_mb > 0).then(|| self.vram_used_mb as f64 / self.vram_total_mb as f64)
+    }
+
+    /// Global API サーキットブレーカーが開いている (Sentinel / Oracle が止まっている)
+    pub fn breaker_open(&self) -> bool {
+        self.api_failure_limit > 0 && self.api_failures >= self.api_failure_limit
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,11 +61,154 @@ pub enum CoreEvent {
         topic: String,
         style: String,
         thumbnail_url: Option<String>,
+        /// 添付する動画 (Core と同じホスト上の絶対パス。Discord の上限に収まるものだけ)
+        #[serde(default)]
+        preview_path: Option<String>,
     },
     /// コアからの対話応答
     ChatResponse { response: String, channel_id: u64 },
     /// 自律的な話しかけ（プッシュ通知）
     ProactiveTalk { message: String, channel_id: u64 },
+    /// サイドカー (TTS 等) の再起動・フラップ通知
+    SidecarAlert { name: String, message: String, flapping: bool },
+    /// 失敗ジョブ (Dead-letter) の一覧 (`/retry` の応答)
+    FailedJobs { jobs: Vec<FailedJobSummary>, channel_id: u64 },
+    /// Watch folder に手動レンダーが届いた (`/ingest` でトピックとスタイルを付けるよう促す)
+    IngestPrompt { ingest_id: String, file_name: String, size_bytes: u64 },
+    /// 夜間セルフテストの失敗 (朝の Samsara の前に環境の故障を知らせる)
+    SelfTestFailed { project_id: String, problems: Vec<String> },
+    /// 編集方針 (`[topic_policy]`) に反した題材を積まずに捨てた (`origin` は samsara / rest / discord)
+    TopicBlocked { origin: String, topic: String, reason: String },
+    /// Karma Distiller が蒸留した教訓のレビュー依頼 (承認ボタンの ID は教訓の ID。`replaced` は置き換える教訓の抜粋)
+    KarmaReviewRequest { karma_id: Uuid, skill: String, lesson: String, replaced: Vec<String> },
+    /// 実行中・待機中のジョブ一覧の 1 ページ (`/queue` の応答。`page` は 1 始まり、`total` は全件数)
+    JobList { jobs: Vec<ActiveJobSummary>, page: u32, page_size: u32, total: i64, channel_id: u64 },
+    /// ジョブ 1 件の詳細 (`/job` の応答。見つからなければ `job` は None)
+    JobDetail { job_id: String, job: Option<JobDetail>, channel_id: u64 },
+    /// 制作レポート (`/report` の応答と定期配信。`channel_id` が 0 ならコマンドチャンネル)
+    ProductionReport { report: ProductionReport, channel_id: u64 },
+    /// styles.toml のスタイル一覧 (`ListStyles` の応答。`request` は依頼時の値をそのまま返す)
+    StyleList { styles: Vec<StyleOption>, channel_id: u64, request: Option<String> },
+    /// 負荷が高く ComfyUI レンダーの開始を保留した (`cleared` は待ちが解けて始めたときの知らせ)
+    Backpressure { reason: String, waited_secs: u64, cleared: bool },
+}
+
+/// Dead-letter キューの 1 件
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
+pub struct FailedJobSummary {
+    pub job_id: String,
+    pub topic: String,
+    pub channel: String,
+    pub error_message: Option<String>,
+    pub failed_at: String,
+    /// これまでに手動リトライされた回数
+    pub requeue_count: i64,
+}
+
+/// 待ち行列の 1 件 (`/status` ページ)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct QueuedJobSummary {
+    pub job_id: String,
+    pub topic: String,
+    pub style: String,
+    pub channel: String,
+    /// 予約投入の時刻 (RFC 3339)
+    pub scheduled_for: Option<String>,
+    /// 完了を待っている親ジョブ
+    pub depends_on: Option<String>,
+}
+
+/// 実行中・待機中のジョブの 1 件 (`/queue`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ActiveJobSummary {
+    pub job_id: String,
+    pub topic: String,
+    pub style: String,
+    pub channel: String,
+    /// Pending / Processing
+    pub status: String,
+    /// 最後に記録された工程 (未着手なら None)
+    pub stage: Option<String>,
+    /// 予約投入の時刻 (RFC 3339)
+    pub scheduled_for: Option<String>,
+}
+
+/// ジョブ 1 件の詳細 (`/job`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct JobDetail {
+    pub job_id: String,
+    pub topic: String,
+    pub style: String,
+    pub channel: String,
+    pub status: String,
+    /// 最後に記録された工程
+    pub stage: Option<String>,
+    pub error_message: Option<String>,
+    pub started_at: Option<String>,
+    pub scheduled_for: Option<String>,
+    pub depends_on: Option<String>,
+    /// 納品物 (言語・画角ごとの URL、無ければローカルのパス)
+    pub outputs: Vec<String>,
+    /// 公開済みなら視聴 URL
+    pub video_url: Option<String>,
+}
+
+/// 直近 `days` 日の制作レポート (`/report`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProductionReport {
+    pub days: u32,
+    pub completed: i64,
+    /// 失敗したジョブ (オペレーターの取り消しは含めない)
+    pub failed: i64,
+    /// 完了したジョブの着手から完了までの平均 (秒)
+    pub avg_render_secs: Option<f64>,
+    /// 期間中に増えた教訓 (レビュー待ちは含めない)
+    pub karma_gained: i64,
+    /// 期間中に計測した公開動画の再生数上位
+    pub top_videos: Vec<TopVideo>,
+}
+
+impl ProductionReport {
+    /// 完了・失敗のうち失敗の割合 (どちらも無ければ None)
+    pub fn failure_rate(&self) -> Option<f64> {
+        let finished = self.completed + self.failed;
+        (finished > 0).then(|| self.failed as f64 / finished as f64)
+    }
+}
+
+/// 制作レポートの上位動画の 1 件
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TopVideo {
+    pub job_id: String,
+    pub topic: String,
+    pub channel: String,
+    pub views: i64,
+    pub likes: i64,
+    /// 視聴 URL (プラットフォームが分かるときだけ)
+    pub video_url: Option<String>,
+}
+
+/// 選べるスタイルの 1 件 (`/generate` の選択メニュー)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct StyleOption {
+    pub name: String,
+    pub description: String,
+}
+
+/// 入力されたスタイル名を一覧と照合する (大文字小文字は区別しない)
+pub fn match_style<'a>(styles: &'a [StyleOption], requested: &str) -> Option<&'a StyleOption> {
+    let requested = requested.trim();
+    styles.iter().find(|s| s.name.eq_ignore_ascii_case(requested))
+}
+
+/// 完了したジョブの 1 件 (`/status` ページ)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CompletedJobSummary {
+    pub job_id: String,
+    pub topic: String,
+    pub channel: String,
+    pub completed_at: String,
+    pub creative_rating: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -58,6 +234,114 @@ pub enum ControlCommand {
         topic: String,
         style: Option<String>,
     },
+    /// 過去のジョブ (またはプロジェクト) を作り直す。`step` を指定すると保存済みコンセプトから再開する
+    Remix {
+        job_id: String,
+        style: Option<String>,
+        step: Option<String>,
+        channel_id: u64,
+    },
+    /// シリーズ制作: トピックを順に親子ジョブとしてキューに積む
+    GenerateSeries {
+        topics: Vec<String>,
+        style: Option<String>,
+        /// 投稿先チャンネル (`[channels]` のキー。省略時は default)
+        #[serde(default)]
+        channel: Option<String>,
+        channel_id: u64,
+    },
+    /// 指定時刻 (at) から every_hours 間隔で count 本のジョブを予約する (series 指定時は次話として)
+    Schedule {
+        at: String,
+        topic: Option<String>,
+        series: Option<String>,
+        style: Option<String>,
+        count: u32,
+        every_hours: i64,
+        /// 投稿先チャンネル (`[channels]` のキー。省略時は default)
+        #[serde(default)]
+        channel: Option<String>,
+        channel_id: u64,
+    },
+    /// ジョブの来歴 (Samsara がなぜそれを選んだか) を説明させる
+    Why {
+        job_id: String,
+        channel_id: u64,
+    },
+    /// 公開済み動画の取り下げ (delete = false なら非公開化、true なら削除)
+    Takedown {
+        job_id: String,
+        delete: bool,
+        channel_id: u64,
+    },
+    /// 失敗ジョブの再投入 (job_id が None なら直近の失敗一覧を返す)
+    Retry {
+        job_id: Option<String>,
+        channel_id: u64,
+    },
+    /// 実行中・待機中のジョブ一覧 (`page` は 1 始まり)
+    ListJobs {
+        page: u32,
+        channel_id: u64,
+    },
+    /// ジョブ 1 件の詳細
+    GetJob {
+        job_id: String,
+        channel_id: u64,
+    },
+    /// styles.toml のスタイル一覧 (`request` は応答の `StyleList` にそのまま返る、呼び出し側の控え)
+    ListStyles {
+        channel_id: u64,
+        #[serde(default)]
+        request: Option<String>,
+    },
+    /// 直近 `days` 日 (省略時は `[cron] production_report_days`) の制作レポート
+    Report {
+        days: Option<u32>,
+        channel_id: u64,
+    },
+    /// 自律エンジンを一時停止する (新しいジョブを取り出さず、Samsara も企画しない。実行中のジョブは最後まで作る)
+    Pause {
+        channel_id: u64,
+    },
+    /// 一時停止を解く
+    Resume {
+        channel_id: u64,
+    },
+    /// 手動レンダーをジョブ化する (ingest_id が None なら取り込み待ちの一覧を返す)
+    Ingest {
+        ingest_id: Option<String>,
+        topic: Option<String>,
+        style: Option<String>,
+        channel_id: u64,
+    },
+    /// cron ジョブの実行履歴 (job 指定ならそのジョブだけ)
+    CronRuns {
+        job: Option<String>,
+        channel_id: u64,
+    },
+    /// コスト集計 (job_id 指定ならそのジョブ、無ければ直近 days 日)
+    Costs {
+        job_id: Option<String>,
+        days: Option<u32>,
+        channel_id: u64,
+    },
+    /// 先に送ったファイル (`transfer_id`) を素材にして動画を作る。
+    /// 画像は全シーンの参照画像に、音声は `lang` のナレーションになる
+    GenerateFromAttachment {
+        transfer_id: Uuid,
+        topic: String,
+        style: Option<String>,
+        #[serde(default)]
+        lang: Option<String>,
+        channel_id: u64,
+    },
+    /// チャンネルの会話履歴と記憶サマリーを消去する (/forget の確認後)
+    ForgetMemory {
+        channel_id: u64,
+        /// 監査記録に残す依頼者 (Discord ユーザー)
+        requested_by: String,
+    },
     StopGracefully,
     /// Hybrid Nuke Protocol: 即時強制終了要求
     EmergencyShutdown,
@@ -69,5 +353,351 @@ pub enum ControlCommand {
         job_id: String,
         platform: String,
         video_id: String,
+        /// 公開したタイトル案のラベル (省略時は A 案)
+        #[serde(default)]
+        variant: Option<String>,
     },
+    /// 操作した Discord ユーザーを添えたコマンド (Core の監査ログに actor として残る)
+    AsActor {
+        actor: String,
+        command: Box<ControlCommand>,
+    },
+}
+
+impl ControlCommand {
+    /// (操作者, 中身) に分ける。名乗りの無いコマンドは None
+    pub fn into_actor(self) -> (Option<String>, ControlCommand) {
+        match self {
+            ControlCommand::AsActor { actor, command } => {
+                let (inner, command) = command.into_actor();
+                (inner.or(Some(actor)), command)
+            }
+            other => (None, other),
+        }
+    }
+
+    /// 監査ログに残す操作名。参照系・対話は None
+    pub fn audit_action(&self) -> Option<&'static str> {
+        Some(match self {
+            ControlCommand::Generate { .. } => "enqueue",
+            ControlCommand::GenerateSeries { .. } => "enqueue_series",
+            ControlCommand::GenerateFromAttachment { .. } => "enqueue_attachment",
+            ControlCommand::Remix { .. } => "remix",
+            ControlCommand::Schedule { .. } => "schedule",
+            ControlCommand::Takedown { .. } => "takedown",
+            ControlCommand::Retry { job_id: Some(_), .. } => "retry",
+            ControlCommand::Ingest { ingest_id: Some(_), .. } => "ingest",
+            ControlCommand::ForgetMemory { .. } => "forget_memory",
+            ControlCommand::Pause { .. } => "pause",
+            ControlCommand::Resume { .. } => "resume",
+            ControlCommand::StopGracefully => "stop",
+            ControlCommand::EmergencyShutdown => "nuke",
+            ControlCommand::ApprovalResponse { .. } => "approval",
+            ControlCommand::SetCreativeRating { .. } => "rating",
+            ControlCommand::LinkSns { .. } => "link_sns",
+            ControlCommand::AsActor { command, .. } => return command.audit_action(),
+            _ => return None,
+        })
+    }
+}
+
+// --- 通知先 (Discord / Slack / Telegram) ---
+
+/// 完了通知の中身 (`CoreEvent::TaskCompleted`)
+#[derive(Debug, Clone, Default)]
+pub struct CompletedTask {
+    pub job_id: String,
+    pub result: String,
+    pub topic: String,
+    pub style: String,
+    pub thumbnail_url: Option<String>,
+    pub preview_path: Option<String>,
+}
+
+impl CompletedTask {
+    /// 結果の文言から成否を判定する
+    pub fn succeeded(&self) -> bool {
+        let result = self.result.to_lowercase();
+        result.contains("success") || result.contains("completed")
+    }
+}
+
+/// 承認依頼 (ボタンの ID は `id`。Orchestrator のチェックポイントか、レビュー待ちの Synthesized Karma)
+#[derive(Debug, Clone)]
+pub struct ApprovalPrompt {
+    pub id: Uuid,
+    pub description: String,
+}
+
+impl CoreEvent {
+    pub fn completed_task(&self) -> Option<CompletedTask> {
+        match self {
+            CoreEvent::TaskCompleted { job_id, result, topic, style, thumbnail_url, preview_path } => Some(CompletedTask {
+                job_id: job_id.clone(),
+                result: result.clone(),
+                topic: topic.clone(),
+                style: style.clone(),
+                thumbnail_url: thumbnail_url.clone(),
+                preview_path: preview_path.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// 承認ボタンを付けて送るイベント (Karma レビューは置き換える教訓を文面に含める)
+    pub fn approval_prompt(&self) -> Option<ApprovalPrompt> {
+        match self {
+            CoreEvent::ApprovalRequest { transition_id, description } => Some(ApprovalPrompt { id: *transition_id, description: description.clone() }),
+            CoreEvent::KarmaReviewRequest { karma_id, skill, lesson, replaced } => Some(ApprovalPrompt {
+                id: *karma_id,
+                description: format!(
+                    "🧬 Synthesized Karma Review [{}]\nNew lesson: {}\nReplaces {} lesson(s):\n{}",
+                    skill,
+                    lesson,
+                    replaced.len(),
+                    replaced.iter().take(5).map(|l| format!("• {}", l.chars().take(120).collect::<String>())).collect::<Vec<_>>().join("\n")
+                ),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// 承認ボタンの ID (`approve_<uuid>` / `reject_<uuid>`)
+pub fn approval_action_id(id: Uuid, approved: bool) -> String {
+    format!("{}_{}", if approved { "approve" } else { "reject" }, id)
+}
+
+/// `approval_action_id` を (ID, 承認したか) に戻す
+pub fn parse_approval_action(action: &str) -> Option<(Uuid, bool)> {
+    let (approved, id) = match action.split_once('_')? {
+        ("approve", id) => (true, id),
+        ("reject", id) => (false, id),
+        _ => return None,
+    };
+    Uuid::parse_str(id).ok().map(|id| (id, approved))
+}
+
+/// 完了通知と承認依頼の送り先。押されたボタンは各通知先から `ApprovalResponse` として Core に戻る
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    /// ログ用の名前 ("discord" / "slack" / "telegram")
+    fn name(&self) -> &'static str;
+    async fn task_completed(&self, task: &CompletedTask) -> anyhow::Result<()>;
+    async fn approval_request(&self, prompt: &ApprovalPrompt) -> anyhow::Result<()>;
+}
+
+/// 完了通知・承認依頼を全通知先へ送る (それ以外のイベントは無視。失敗はログに残して続ける)
+pub async fn notify_all(notifiers: &[std::sync::Arc<dyn Notifier>], event: &CoreEvent) {
+    if let Some(task) = event.completed_task() {
+        for notifier in notifiers {
+            if let Err(e) = notifier.task_completed(&task).await {
+                tracing::warn!("⚠️ Notifier {}: Failed to send completion of {}: {}", notifier.name(), task.job_id, e);
+            }
+        }
+    } else if let Some(prompt) = event.approval_prompt() {
+        for notifier in notifiers {
+            if let Err(e) = notifier.approval_request(&prompt).await {
+                tracing::warn!("⚠️ Notifier {}: Failed to send approval request {}: {}", notifier.name(), prompt.id, e);
+            }
+        }
+    }
+}
+
+// --- ファイル転送 (Watchtower -> Core) ---
+//
+// UDS のフレームは通常 JSON の ControlCommand だが、先頭が `FILE_FRAME_TAG` のフレームはファイルのチャンク:
+// `[FILE_FRAME_TAG][ヘッダ長 u32 BE][FileChunkHeader の JSON][本体]`。
+// 0xFB は UTF-8 の先頭バイトになり得ないため、JSON のフレームと取り違えることはない。
+// 全チャンクを送ってから、`transfer_id` を指す ControlCommand を送る。
+
+pub const FILE_FRAME_TAG: u8 = 0xFB;
+/// 1 フレームに載せる本体の大きさ (LengthDelimitedCodec の上限 8MB より十分小さく)
+pub const FILE_CHUNK_BYTES: usize = 256 * 1024;
+/// 1 ファイルの上限 (Discord の添付上限に合わせる)
+pub const MAX_FILE_TRANSFER_BYTES: usize = 25 * 1024 * 1024;
+/// Core が同時に保持する受信済み・受信中ファイルの数
+const MAX_HELD_TRANSFERS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChunkHeader {
+    pub transfer_id: Uuid,
+    /// 0 始まりの連番
+    pub seq: u32,
+    /// 最後のチャンクなら true
+    pub last: bool,
+    /// 元のファイル名 (拡張子で画像か音声かを判断する)
+    pub file_name: String,
+}
+
+/// ファイルを送信用のフレーム列にする
+pub fn encode_file_frames(transfer_id: Uuid, file_name: &str, data: &[u8]) -> Vec<Vec<u8>> {
+    let chunks: Vec<&[u8]> = if data.is_empty() { vec![&[][..]] } else { data.chunks(FILE_CHUNK_BYTES).collect() };
+    let count = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let header = FileChunkHeader { transfer_id, seq: i as u32, last: i + 1 == count, file_name: file_name.to_string() };
+            let header = serde_json::to_vec(&header).unwrap_or_default();
+            let mut frame = Vec::with_capacity(5 + header.len() + chunk.len());
+            frame.push(FILE_FRAME_TAG);
+            frame.extend_from_slice(&(header.len() as u32).to_be_bytes());
+            frame.extend_from_slice(&header);
+            frame.extend_from_slice(chunk);
+            frame
+        })
+        .collect()
+}
+
+/// ファイルのフレームならヘッダと本体に分ける
+pub fn decode_file_frame(frame: &[u8]) -> Option<(FileChunkHeader, &[u8])> {
+    let rest = frame.strip_prefix(&[FILE_FRAME_TAG])?;
+    let len = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+    let header = serde_json::from_slice(rest.get(4..4 + len)?).ok()?;
+    Some((header, &rest[4 + len..]))
+}
+
+/// 受信し終えたファイル
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceivedFile {
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+struct PartialFile {
+    file: ReceivedFile,
+    next_seq: u32,
+    complete: bool,
+}
+
+/// チャンクを組み立て、コマンドから参照されるまで保持する
+#[derive(Default)]
+pub struct FileAssembler {
+    /// 古い順
+    transfers: Vec<(Uuid, PartialFile)>,
+}
+
+impl FileAssembler {
+    /// チャンクを追加する。ファイルが揃えば Ok(true)
+    pub fn push(&mut self, header: FileChunkHeader, data: &[u8]) -> Result<bool, String> {
+        let id = header.transfer_id;
+        let index = match self.transfers.iter().position(|(t, _)| *t == id) {
+            Some(i) => i,
+            None if header.seq == 0 => {
+                if self.transfers.len() >= MAX_HELD_TRANSFERS {
+                    self.transfers.remove(0);
+                }
+                let file = ReceivedFile { file_name: header.file_name.clone(), data: Vec::new() };
+                self.transfers.push((id, PartialFile { file, next_seq: 0, complete: false }));
+                self.transfers.len() - 1
+            }
+            None => return Err(format!("chunk {} of unknown transfer {}", header.seq, id)),
+        };
+        let partial = &mut self.transfers[index].1;
+        if partial.complete || header.seq != partial.next_seq {
+            self.transfers.remove(index);
+            return Err(format!("transfer {} is out of order (chunk {})", id, header.seq));
+        }
+        if partial.file.data.len() + data.len() > MAX_FILE_TRANSFER_BYTES {
+            self.transfers.remove(index);
+            return Err(format!("transfer {} exceeds {} MB", id, MAX_FILE_TRANSFER_BYTES / 1_048_576));
+        }
+        partial.file.data.extend_from_slice(data);
+        partial.next_seq += 1;
+        partial.complete = header.last;
+        Ok(header.last)
+    }
+
+    /// 揃ったファイルを取り出す (受信中・未知なら None)
+    pub fn take(&mut self, transfer_id: Uuid) -> Option<ReceivedFile> {
+        let index = self.transfers.iter().position(|(t, p)| *t == transfer_id && p.complete)?;
+        Some(self.transfers.remove(index).1.file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_envelope_and_audit_actions() {
+        let cmd = ControlCommand::AsActor {
+            actor: "alice (42)".to_string(),
+            command: Box::new(ControlCommand::Takedown { job_id: "j1".into(), delete: false, channel_id: 1 }),
+        };
+        assert_eq!(cmd.audit_action(), Some("takedown"));
+        let json = serde_json::to_string(&cmd).unwrap();
+        let (actor, inner) = serde_json::from_str::<ControlCommand>(&json).unwrap().into_actor();
+        assert_eq!(actor.as_deref(), Some("alice (42)"));
+        assert!(matches!(inner, ControlCommand::Takedown { .. }));
+
+        let remix = ControlCommand::Remix { job_id: "j1".into(), style: None, step: Some("voice".into()), channel_id: 1 };
+        assert_eq!(remix.audit_action(), Some("remix"));
+        assert_eq!(ControlCommand::Pause { channel_id: 1 }.audit_action(), Some("pause"));
+        assert_eq!(ControlCommand::Resume { channel_id: 1 }.audit_action(), Some("resume"));
+        assert_eq!(ControlCommand::Retry { job_id: None, channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::ListJobs { page: 1, channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::GetJob { job_id: "j".into(), channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::Report { days: None, channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::CronRuns { job: None, channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::ListStyles { channel_id: 1, request: None }.audit_action(), None);
+        assert_eq!(ControlCommand::Chat { message: "hi".into(), channel_id: 1 }.audit_action(), None);
+        assert_eq!(ControlCommand::GetStatus.into_actor().0, None);
+    }
+
+    #[test]
+    fn test_match_style_ignores_case_and_whitespace() {
+        let styles = vec![
+            StyleOption { name: "cinematic".into(), description: "Slow pans".into() },
+            StyleOption { name: "Hype".into(), description: String::new() },
+        ];
+        assert_eq!(match_style(&styles, " Cinematic ").map(|s| s.name.as_str()), Some("cinematic"));
+        assert_eq!(match_style(&styles, "hype").map(|s| s.name.as_str()), Some("Hype"));
+        assert!(match_style(&styles, "cinematic-noir").is_none());
+    }
+
+    #[test]
+    fn test_approval_action_ids_round_trip() {
+        let id = Uuid::new_v4();
+        assert_eq!(parse_approval_action(&approval_action_id(id, true)), Some((id, true)));
+        assert_eq!(parse_approval_action(&approval_action_id(id, false)), Some((id, false)));
+        assert_eq!(parse_approval_action(&format!("retry_{}", id)), None);
+        assert_eq!(parse_approval_action("approve_not-a-uuid"), None);
+
+        let review = CoreEvent::KarmaReviewRequest { karma_id: id, skill: "script".into(), lesson: "Open cold".into(), replaced: vec!["a".into(), "b".into()] };
+        let prompt = review.approval_prompt().unwrap();
+        assert_eq!(prompt.id, id);
+        assert!(prompt.description.contains("Replaces 2 lesson(s)"));
+        assert!(CoreEvent::ChatResponse { response: "hi".into(), channel_id: 1 }.approval_prompt().is_none());
+    }
+
+    #[test]
+    fn test_file_frames_round_trip_through_the_assembler() {
+        let id = Uuid::new_v4();
+        let data: Vec<u8> = (0..FILE_CHUNK_BYTES * 2 + 10).map(|i| (i % 251) as u8).collect();
+        let frames = encode_file_frames(id, "cat.png", &data);
+        assert_eq!(frames.len(), 3);
+        // A JSON command frame is never mistaken for a file frame
+        assert!(decode_file_frame(&serde_json::to_vec(&ControlCommand::GetStatus).unwrap()).is_none());
+
+        let mut assembler = FileAssembler::default();
+        for (i, frame) in frames.iter().enumerate() {
+            let (header, body) = decode_file_frame(frame).unwrap();
+            assert_eq!(assembler.push(header, body).unwrap(), i == 2);
+        }
+        let file = assembler.take(id).unwrap();
+        assert_eq!(file.file_name, "cat.png");
+        assert_eq!(file.data, data);
+        assert!(assembler.take(id).is_none());
+
+        // Skipped chunks drop the transfer
+        let other = Uuid::new_v4();
+        let frames = encode_file_frames(other, "a.wav", &data);
+        let (h0, b0) = decode_file_frame(&frames[0]).unwrap();
+        let (h2, b2) = decode_file_frame(&frames[2]).unwrap();
+        assembler.push(h0, b0).unwrap();
+        assert!(assembler.push(h2, b2).is_err());
+        assert!(assembler.take(other).is_none());
+    }
 }
@@ -1,11 +1,85 @@
+use std::collections::VecDeque;
 use std::process::{Child, Command};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
 use sysinfo::{System, Pid};
 use tracing::{info, warn, error};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
+/// `wait_until_ready` のポーリング間隔
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// `wait_until_ready` の1回あたりのプローブタイムアウト
+const READY_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// `wait_until_ready` の経過ログ間隔
+const READY_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// `port` に今バインドできるか (全インターフェースで確認する)
+pub fn is_port_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+/// `start` から順に `count` 個のポートを試し、最初に空いていたものを返す
+pub fn find_free_port(start: u16, count: u16) -> Option<u16> {
+    (0..count).filter_map(|i| start.checked_add(i)).find(|p| is_port_free(*p))
+}
+
+/// URL のポートだけを差し替える (プローブ URL を実際のポートに合わせる)
+pub fn with_port(url: &str, port: u16) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => match parsed.set_port(Some(port)) {
+            Ok(()) => parsed.to_string(),
+            Err(()) => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+/// サイドカー監視ループの設定
+#[derive(Debug, Clone)]
+pub struct SupervisionPolicy {
+    /// 通知に使う識別名 (例: "qwen3-tts")
+    pub name: String,
+    /// HTTP レディネスプローブの URL (2xx を健全とみなす)
+    pub probe_url: String,
+    /// プローブ間隔
+    pub probe_interval: Duration,
+    /// 1回のプローブのタイムアウト
+    pub probe_timeout: Duration,
+    /// 起動直後のプローブ猶予 (モデルロード等)
+    pub startup_grace: Duration,
+    /// 再起動に至る連続プローブ失敗回数
+    pub failure_threshold: u32,
+    /// 再起動バックオフの初期値 (失敗ごとに倍増)
+    pub initial_backoff: Duration,
+    /// 再起動バックオフの上限
+    pub max_backoff: Duration,
+    /// フラップ判定の時間窓
+    pub flap_window: Duration,
+    /// 時間窓内の再起動回数がこれ以上でフラップとみなす
+    pub flap_threshold: usize,
+}
+
+impl SupervisionPolicy {
+    /// `attempt` 回目 (0始まり) の再起動前に待つ時間
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(16));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// 監視ループから上位 (Watchtower 等) へ送るイベント
+#[derive(Debug, Clone)]
+pub enum SidecarEvent {
+    /// 異常を検知して再起動した
+    Restarted { name: String, attempt: u32, reason: String },
+    /// 短時間に再起動を繰り返している
+    Flapping { name: String, restarts: usize, window: Duration, last_reason: String },
+    /// 再起動そのものに失敗した
+    RestartFailed { name: String, error: String },
+}
+
 /// サイドカー・プロセスの管理を司る構造体 ("The Reaper")
 pub struct SidecarManager {
     /// 管理下の子プロセス
@@ -112,6 +186,160 @@ impl SidecarManager {
         
         Ok(())
     }
+
+    /// 管理下のプロセスが既に終了していれば、その終了ステータスを返す
+    async fn exited_status(&self) -> Option<String> {
+        let mut guard = self.child.lock().await;
+        match guard.as_mut() {
+            Some(child) => match child.try_wait() {
+                Ok(Some(status)) => Some(format!("process exited ({})", status)),
+                Ok(None) => None,
+                Err(e) => Some(format!("failed to poll process: {}", e)),
+            },
+            None => Some("process not running".to_string()),
+        }
+    }
+
+    /// 現在の子プロセスを (グループごと) 終了させて回収する
+    pub async fn reap_current(&self) {
+        let child = self.child.lock().await.take();
+        if let Some(mut child) = child {
+            let pid = Pid::from(child.id() as usize);
+            self.graceful_kill(pid).await;
+            let _ = child.wait();
+        }
+    }
+
+    /// HTTP レディネスプローブ (2xx のみ健全)
+    pub async fn probe(url: &str, timeout: Duration) -> Result<(), String> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| e.to_string())?;
+        match client.get(url).send().await {
+            Ok(res) if res.status().is_success() => Ok(()),
+            Ok(res) => Err(format!("probe returned HTTP {}", res.status())),
+            Err(e) => Err(format!("probe failed: {}", e)),
+        }
+    }
+
+    /// ヘルスエンドポイントが応答するまでポーリングし、待機時間を返す
+    ///
+    /// モデルロード中の接続拒否・タイムアウトは想定内として待ち続ける。
+    /// プロセスが先に終了した場合や `timeout` を超えた場合はエラー。
+    pub async fn wait_until_ready(&self, url: &str, timeout: Duration) -> anyhow::Result<Duration> {
+        let started = Instant::now();
+        let mut last_report = Duration::ZERO;
+        info!("⏳ SidecarManager: Waiting for {} to become ready (timeout {:?})...", url, timeout);
+
+        loop {
+            match Self::probe(url, READY_PROBE_TIMEOUT).await {
+                Ok(()) => {
+                    let elapsed = started.elapsed();
+                    info!("✅ SidecarManager: {} is ready after {:.1}s", url, elapsed.as_secs_f32());
+                    return Ok(elapsed);
+                }
+                Err(e) => {
+                    if let Some(reason) = self.exited_status().await {
+                        anyhow::bail!("sidecar died before becoming ready after {:.1}s: {}", started.elapsed().as_secs_f32(), reason);
+                    }
+                    let elapsed = started.elapsed();
+                    if elapsed >= timeout {
+                        anyhow::bail!("sidecar not ready after {:.1}s: {}", elapsed.as_secs_f32(), e);
+                    }
+                    if elapsed - last_report >= READY_REPORT_INTERVAL {
+                        info!("⏳ SidecarManager: Still waiting for {} ({:.0}s elapsed): {}", url, elapsed.as_secs_f32(), e);
+                        last_report = elapsed;
+                    }
+                }
+            }
+            sleep(READY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// 監視ループを開始する ("The Shepherd")
+    ///
+    /// プロセス終了または連続プローブ失敗を検知したら、指数バックオフを挟んで
+    /// `make_command` から再生成したコマンドで再起動する。
+    /// 時間窓内の再起動回数が閾値に達すると `SidecarEvent::Flapping` を送る。
+    pub fn supervise<F>(
+        self: &Arc<Self>,
+        policy: SupervisionPolicy,
+        make_command: F,
+        events: mpsc::Sender<SidecarEvent>,
+    ) -> JoinHandle<()>
+    where
+        F: Fn() -> Command + Send + Sync + 'static,
+    {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            info!("🐑 SidecarManager: Supervising '{}' (probe: {})", policy.name, policy.probe_url);
+            sleep(policy.startup_grace).await;
+
+            let mut consecutive_failures = 0u32;
+            let mut attempt = 0u32;
+            let mut restarts: VecDeque<Instant> = VecDeque::new();
+
+            loop {
+                sleep(policy.probe_interval).await;
+
+                let reason = match manager.exited_status().await {
+                    Some(reason) => Some(reason),
+                    None => match Self::probe(&policy.probe_url, policy.probe_timeout).await {
+                        Ok(()) => {
+                            consecutive_failures = 0;
+                            attempt = 0;
+                            None
+                        }
+                        Err(e) => {
+                            consecutive_failures += 1;
+                            warn!("🩺 SidecarManager: '{}' probe failure {}/{}: {}", policy.name, consecutive_failures, policy.failure_threshold, e);
+                            (consecutive_failures >= policy.failure_threshold).then_some(e)
+                        }
+                    },
+                };
+
+                let Some(reason) = reason else { continue };
+
+                // --- Restart with exponential backoff ---
+                let backoff = policy.backoff_for(attempt);
+                error!("💥 SidecarManager: '{}' is unhealthy ({}). Restarting in {:?} (attempt {})...", policy.name, reason, backoff, attempt + 1);
+                manager.reap_current().await;
+                sleep(backoff).await;
+
+                match manager.spawn(make_command()).await {
+                    Ok(()) => {
+                        attempt += 1;
+                        consecutive_failures = 0;
+                        let _ = events.send(SidecarEvent::Restarted { name: policy.name.clone(), attempt, reason: reason.clone() }).await;
+
+                        let now = Instant::now();
+                        restarts.push_back(now);
+                        while restarts.front().is_some_and(|t| now.duration_since(*t) > policy.flap_window) {
+                            restarts.pop_front();
+                        }
+                        if restarts.len() >= policy.flap_threshold {
+                            warn!("🔁 SidecarManager: '{}' is flapping ({} restarts within {:?})", policy.name, restarts.len(), policy.flap_window);
+                            let _ = events.send(SidecarEvent::Flapping {
+                                name: policy.name.clone(),
+                                restarts: restarts.len(),
+                                window: policy.flap_window,
+                                last_reason: reason,
+                            }).await;
+                        }
+
+                        // 再起動後もモデルロードの猶予を与える
+                        sleep(policy.startup_grace).await;
+                    }
+                    Err(e) => {
+                        attempt += 1;
+                        error!("❌ SidecarManager: Failed to restart '{}': {}", policy.name, e);
+                        let _ = events.send(SidecarEvent::RestartFailed { name: policy.name.clone(), error: e.to_string() }).await;
+                    }
+                }
+            }
+        })
+    }
 }
 
 /// RA-02: 道連れ終了 (Drop Trait)
@@ -148,3 +376,77 @@ impl Drop for SidecarManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_port_skips_a_bound_port() {
+        let held = std::net::TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let busy = held.local_addr().unwrap().port();
+        assert!(!is_port_free(busy));
+        assert_ne!(find_free_port(busy, 1), Some(busy));
+        if let Some(port) = find_free_port(busy, 20) {
+            assert!(port > busy && port < busy.saturating_add(20));
+        }
+        assert_eq!(find_free_port(u16::MAX, 0), None);
+    }
+
+    /// 最初の `failures` 回は 503、以降は 200 を返すヘルスエンドポイント
+    async fn health_server(failures: usize) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for served in 0.. {
+                let Ok((mut stream, _)) = listener.accept().await else { break };
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let status = if served < failures { "503 Service Unavailable" } else { "200 OK" };
+                let _ = stream.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    /// `program` を子プロセスとして起動した Manager (ドロップで回収される)
+    async fn running_manager(program: &str, args: &[&str]) -> SidecarManager {
+        let manager = SidecarManager::new(vec![]);
+        let mut command = Command::new(program);
+        command.args(args);
+        manager.spawn(command).await.unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_retries_until_healthy() {
+        let url = health_server(2).await;
+        let manager = running_manager("sleep", &["30"]).await;
+
+        let waited = manager.wait_until_ready(&url, Duration::from_secs(10)).await.unwrap();
+        // 503 を 2 回受けたので、少なくとも 2 回分のポーリング間隔を待っている
+        assert!(waited >= READY_POLL_INTERVAL * 2, "waited only {:?}", waited);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_times_out_and_notices_an_early_exit() {
+        let url = health_server(usize::MAX).await;
+        let manager = running_manager("sleep", &["30"]).await;
+        let err = manager.wait_until_ready(&url, Duration::from_secs(1)).await.unwrap_err();
+        assert!(err.to_string().contains("not ready"), "{}", err);
+        assert!(err.to_string().contains("HTTP 503"), "{}", err);
+        drop(manager);
+
+        let manager = running_manager("true", &[]).await;
+        let err = manager.wait_until_ready(&url, Duration::from_secs(10)).await.unwrap_err();
+        assert!(err.to_string().contains("died before becoming ready"), "{}", err);
+    }
+
+    #[test]
+    fn test_with_port_rewrites_only_the_port() {
+        assert_eq!(with_port("http://127.0.0.1:8188/system_stats", 8190), "http://127.0.0.1:8190/system_stats");
+        assert_eq!(with_port("http://localhost/health", 5001), "http://localhost:5001/health");
+        assert_eq!(with_port("not a url", 5001), "not a url");
+    }
+}
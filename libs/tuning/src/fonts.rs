@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+use factory_core::error::FactoryError;
+
+/// 言語 (または文字体系) ごとの字幕・タイトルの書体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FontProfile {
+    /// 第一候補のフォント (fontconfig のファミリー名)
+    pub font: String,
+    /// 第一候補が無い場合に順に試すフォント
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    /// 焼き込み字幕のサイズ (libass の force_style 基準)
+    pub subtitle_size: i32,
+    /// オープニング・タイトルのサイズ (PlayResY=1920 基準)
+    pub title_size: u32,
+}
+
+impl FontProfile {
+    fn new(font: &str, fallbacks: &[&str], subtitle_size: i32, title_size: u32) -> Self {
+        Self {
+            font: font.to_string(),
+            fallbacks: fallbacks.iter().map(|f| f.to_string()).collect(),
+            subtitle_size,
+            title_size,
+        }
+    }
+
+    fn candidates(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.font).chain(self.fallbacks.iter())
+    }
+}
+
+/// 組み込みの `default` プロファイル (どの言語にも当たらないときの最後の候補)
+fn builtin_default() -> &'static FontProfile {
+    static BUILTIN: OnceLock<FontProfile> = OnceLock::new();
+    BUILTIN.get_or_init(|| FontProfile::new("Noto Sans Bold", &["Noto Sans", "DejaVu Sans"], 16, 88))
+}
+
+/// 言語コードから文字体系のキーを引く (fonts.toml では言語コードと文字体系のどちらでも指定できる)
+pub fn script_for_lang(lang: &str) -> Option<&'static str> {
+    let primary = lang.split(['-', '_']).next().unwrap_or(lang).to_ascii_lowercase();
+    Some(match primary.as_str() {
+        "hi" | "mr" | "ne" | "sa" => "devanagari",
+        "th" => "thai",
+        "ko" => "hangul",
+        "zh" => "han_sc",
+        "ar" | "fa" | "ur" => "arabic",
+        "bn" | "as" => "bengali",
+        "ta" => "tamil",
+        "he" | "yi" => "hebrew",
+        "ru" | "uk" | "bg" | "sr" => "cyrillic",
+        _ => return None,
+    })
+}
+
+/// 言語別フォントの一覧 (fonts.toml)
+///
+/// 起動時に `validate` で実際にインストールされているかを確かめ、
+/// 第一候補が無い言語はフォールバックへ切り替える (どれも無ければ警告のうえ指定どおり使う)。
+#[derive(Debug, Clone)]
+pub struct FontRegistry {
+    profiles: BTreeMap<String, FontProfile>,
+    /// 検証で確認したインストール済みファミリー (小文字)。None なら未検証
+    installed: Option<HashSet<String>>,
+}
+
+impl Default for FontRegistry {
+    fn default() -> Self {
+        let profiles = [
+            ("default", builtin_default().clone()),
+            ("ja", FontProfile::new("Noto Sans JP Black", &["Noto Sans CJK JP", "Hiragino Sans"], 18, 96)),
+            // 英語は単語数が多くなりやすいため字幕を大幅に縮小
+            ("en", FontProfile::new("Inter Bold", &["Noto Sans Bold", "Helvetica Neue"], 12, 84)),
+            ("devanagari", FontProfile::new("Noto Sans Devanagari Bold", &["Noto Sans Devanagari", "Kohinoor Devanagari"], 16, 88)),
+            ("thai", FontProfile::new("Noto Sans Thai Bold", &["Noto Sans Thai", "Thonburi"], 16, 88)),
+            ("hangul", FontProfile::new("Noto Sans KR Black", &["Noto Sans CJK KR", "Apple SD Gothic Neo"], 18, 96)),
+            ("han_sc", FontProfile::new("Noto Sans SC Black", &["Noto Sans CJK SC", "PingFang SC"], 18, 96)),
+            ("arabic", FontProfile::new("Noto Sans Arabic Bold", &["Noto Sans Arabic", "Geeza Pro"], 16, 88)),
+        ];
+        Self {
+            profiles: profiles.into_iter().map(|(k, p)| (k.to_string(), p)).collect(),
+            installed: None,
+        }
+    }
+}
+
+impl FontRegistry {
+    /// fonts.toml を読み、組み込みの既定値に上書きする
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, FactoryError> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to read fonts.toml: {}", e),
+        })?;
+        let overrides: BTreeMap<String, FontProfile> = toml::from_str(&content).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to parse fonts.toml: {}", e),
+        })?;
+        let mut registry = Self::default();
+        registry.profiles.extend(overrides.into_iter().map(|(k, p)| (k.to_ascii_lowercase(), p)));
+        Ok(registry)
+    }
+
+    /// 言語コード → 文字体系 → default の順に引く
+    pub fn profile(&self, lang: &str) -> &FontProfile {
+        let lang = lang.to_ascii_lowercase();
+        let primary = lang.split(['-', '_']).next().unwrap_or(&lang);
+        self.profiles
+            .get(lang.as_str())
+            .or_else(|| self.profiles.get(primary))
+            .or_else(|| script_for_lang(&lang).and_then(|s| self.profiles.get(s)))
+            .or_else(|| self.profiles.get("default"))
+            .unwrap_or_else(|| builtin_default())
+    }
+
+    /// 実際に使うフォント: インストール済みの最初の候補 (未検証・全滅なら第一候補)
+    pub fn font_for(&self, lang: &str) -> String {
+        let profile = self.profile(lang);
+        self.installed
+            .as_ref()
+            .and_then(|installed| profile.candidates().find(|f| installed.contains(&f.to_lowercase())))
+            .unwrap_or(&profile.font)
+            .clone()
+    }
+
+    /// 焼き込み字幕の force_style (言語ごとに書体とサイズを差し込む)
+    pub fn subtitle_force_style(&self, lang: &str) -> String {
+        format!("Fontname={},FontSize={}", self.font_for(lang), self.profile(lang).subtitle_size)
+    }
+
+    /// インストール済みのファミリーと照合し、問題を返す (以降の `font_for` は照合結果に従う)
+    pub fn validate(&mut self, installed: HashSet<String>) -> Vec<String> {
+        let installed: HashSet<String> = installed.into_iter().map(|f| f.to_lowercase()).collect();
+        let mut problems = Vec::new();
+        for (key, profile) in &self.profiles {
+            match profile.candidates().position(|f| installed.contains(&f.to_lowercase())) {
+                Some(0) => {}
+                Some(i) => problems.push(format!(
+                    "font '{}' for '{}' is not installed; falling back to '{}'", profile.font, key, profile.fallbacks[i - 1]
+                )),
+                None => problems.push(format!(
+                    "no installed font for '{}' (tried: {}); subtitles may render as tofu",
+                    key, profile.candidates().cloned().collect::<Vec<_>>().join(", ")
+                )),
+            }
+        }
+        self.installed = Some(installed);
+        problems
+    }
+
+    /// fontconfig (`fc-list`) が知っているファミリー名。fc-list が無ければ None
+    pub fn installed_families() -> Option<HashSet<String>> {
+        let output = std::process::Command::new("fc-list").arg(":").arg("family").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(parse_fc_families(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// `fc-list : family` の出力 (1 行に別名がカンマ区切り) をファミリー名の集合にする
+fn parse_fc_families(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .flat_map(|line| line.split(','))
+        .map(|name| name.trim().replace("\\-", "-"))
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_language_script_and_fallback() {
+        let mut registry = FontRegistry::default();
+        assert_eq!(registry.font_for("ja"), "Noto Sans JP Black");
+        assert_eq!(registry.profile("hi").font, "Noto Sans Devanagari Bold");
+        assert_eq!(registry.profile("th-TH").font, "Noto Sans Thai Bold");
+        assert_eq!(registry.profile("pt-BR").font, "Noto Sans Bold");
+        assert_eq!(registry.subtitle_force_style("en"), "Fontname=Inter Bold,FontSize=12");
+
+        let installed = parse_fc_families("Noto Sans Thai,Noto Sans Thai Bold\nHiragino Sans\nNoto Sans\n");
+        let problems = registry.validate(installed);
+        assert_eq!(registry.font_for("th"), "Noto Sans Thai Bold");
+        assert_eq!(registry.font_for("ja"), "Hiragino Sans");
+        assert_eq!(registry.font_for("hi"), "Noto Sans Devanagari Bold");
+        assert!(problems.iter().any(|p| p.contains("no installed font for 'devanagari'")));
+        assert!(problems.iter().any(|p| p.contains("falling back to 'Hiragino Sans'")));
+    }
+
+    #[test]
+    fn test_fonts_toml_overrides_builtin_profiles() {
+        let path = std::env::temp_dir().join(format!("fonts_test_{}.toml", std::process::id()));
+        std::fs::write(&path, "[hi]\nfont = \"Mukta Bold\"\nsubtitle_size = 20\ntitle_size = 90\n").unwrap();
+        let registry = FontRegistry::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        // A language entry wins over its script entry; other languages keep the built-ins
+        assert_eq!(registry.profile("hi").font, "Mukta Bold");
+        assert_eq!(registry.profile("mr").font, "Noto Sans Devanagari Bold");
+        assert_eq!(registry.profile("ja").subtitle_size, 18);
+    }
+}
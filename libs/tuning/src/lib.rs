@@ -1,3 +1,9 @@
 pub mod style;
+pub mod fonts;
+pub mod pipeline;
+pub mod subtitle_theme;
 
-pub use style::{StyleProfile, StyleManager};
+pub use style::{CaptionPosition, DualCaptionLayout, StyleProfile, StyleManager};
+pub use fonts::{FontProfile, FontRegistry};
+pub use pipeline::{Pipeline, PipelineStage, STAGE_REGISTRY};
+pub use subtitle_theme::{SubtitleAnimation, SubtitlePosition, SubtitleTheme};
@@ -0,0 +1,193 @@
+//! # Pipeline — スタイルごとの工程表
+//!
+//! 以前は「画像 → 音声 → 組み立て → 効果音 → BGM → タイトル → 字幕 → 書き出し → サムネイル」の順が
+//! Orchestrator に直書きで、BGM を使わない・アップスケールを挟むといった形式の違いはコードの変更が要った。
+//! `styles.toml` の `pipeline = [...]` に工程名を並べると、ここの登録簿で検証してから Orchestrator がその順に実行する。
+//! 台本 (コンセプト) は常に最初に走るので書かない。未指定なら `DEFAULT_PIPELINE`。
+//!
+//! 並べ替えられるのは素材づくり (images / upscale / voice) だけ。`assemble` と `render` の間の
+//! sfx → bgm → title_overlay → captions は最終エンコードへ順に積み上げるため、省略はできても順序は変えられない
+//! (登録簿の `after` で検証時に弾く)。
+//!
+//! ```toml
+//! [podcast_like]
+//! pipeline = ["voice", "images", "upscale", "assemble", "captions", "render"]
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// 工程の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// シーン画像の生成 (ComfyUI)
+    Images,
+    /// 生成した静止画を拡大する (Ken Burns のズームで粗が出ないように。方式は `[upscale]`)
+    Upscale,
+    /// ナレーション (TTS / 持ち込み音声)
+    Voice,
+    /// 幕ごとのクリップ・字幕タイミングを作って映像とナレーションを結合する
+    Assemble,
+    /// 効果音をナレーションに重ねる
+    Sfx,
+    /// BGM のミックス (無ければナレーションの音量を揃えるだけ)
+    Bgm,
+    /// 冒頭のタイトルオーバーレイ
+    TitleOverlay,
+    /// 字幕の焼き込み (二言語字幕を含む)
+    Captions,
+    /// 最終エンコードと納品
+    Render,
+    /// サムネイル
+    Thumbnail,
+}
+
+/// 登録簿の 1 項目
+pub struct StageSpec {
+    pub stage: PipelineStage,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// 省略できない工程か
+    pub required: bool,
+    /// これより前に必ず置く工程
+    pub requires: &'static [PipelineStage],
+    /// 両方あるならこれより前に置く工程
+    pub after: &'static [PipelineStage],
+}
+
+use PipelineStage::*;
+
+/// Orchestrator が実装している工程の一覧 (`PipelineStage` の宣言順に並べる)
+pub const STAGE_REGISTRY: &[StageSpec] = &[
+    StageSpec { stage: Images, name: "images", description: "Render scene images with ComfyUI", required: false, requires: &[], after: &[] },
+    StageSpec { stage: Upscale, name: "upscale", description: "Upscale rendered stills (ffmpeg, ComfyUI ESRGAN or Real-ESRGAN) before Ken Burns", required: false, requires: &[Images], after: &[] },
+    StageSpec { stage: Voice, name: "voice", description: "Narration via TTS or a supplied voiceover", required: true, requires: &[], after: &[] },
+    StageSpec { stage: Assemble, name: "assemble", description: "Build act clips and subtitle timing, join video and narration", required: true, requires: &[Voice], after: &[Images, Upscale] },
+    StageSpec { stage: Sfx, name: "sfx", description: "Overlay sound effects on the narration", required: false, requires: &[Assemble], after: &[] },
+    StageSpec { stage: Bgm, name: "bgm", description: "Mix background music (otherwise loudness-normalize narration only)", required: false, requires: &[Assemble], after: &[Sfx] },
+    StageSpec { stage: TitleOverlay, name: "title_overlay", description: "Title overlay in the opening seconds", required: false, requires: &[Assemble], after: &[Sfx, Bgm] },
+    StageSpec { stage: Captions, name: "captions", description: "Burn in captions (including dual captions)", required: false, requires: &[Assemble], after: &[Sfx, Bgm, TitleOverlay] },
+    StageSpec { stage: Render, name: "render", description: "Final encode and delivery", required: true, requires: &[Assemble], after: &[Sfx, Bgm, TitleOverlay, Captions] },
+    StageSpec { stage: Thumbnail, name: "thumbnail", description: "Pick a thumbnail frame and add the title", required: false, requires: &[Render], after: &[] },
+];
+
+/// 未指定のスタイルの工程 (従来の直書きの順)
+pub const DEFAULT_PIPELINE: &[PipelineStage] = &[Images, Voice, Assemble, Sfx, Bgm, TitleOverlay, Captions, Render, Thumbnail];
+
+impl PipelineStage {
+    pub fn spec(&self) -> &'static StageSpec {
+        &STAGE_REGISTRY[*self as usize]
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        STAGE_REGISTRY.iter().find(|s| s.name == name.trim()).map(|s| s.stage)
+    }
+}
+
+/// 検証済みの工程表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    steps: Vec<PipelineStage>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self { steps: DEFAULT_PIPELINE.to_vec() }
+    }
+}
+
+impl Pipeline {
+    /// 工程名の並びを登録簿と突き合わせる (空なら既定の工程)
+    pub fn parse(names: &[String]) -> Result<Self, String> {
+        if names.is_empty() {
+            return Ok(Self::default());
+        }
+        let mut steps: Vec<PipelineStage> = Vec::new();
+        for name in names {
+            let stage = PipelineStage::from_name(name).ok_or_else(|| {
+                let known: Vec<&str> = STAGE_REGISTRY.iter().map(|s| s.name).collect();
+                format!("pipeline stage '{}' is unknown (known: {})", name, known.join(", "))
+            })?;
+            if steps.contains(&stage) {
+                return Err(format!("pipeline stage '{}' appears twice", name));
+            }
+            steps.push(stage);
+        }
+        for (i, stage) in steps.iter().enumerate() {
+            let spec = stage.spec();
+            for dep in spec.requires {
+                if !steps[..i].contains(dep) {
+                    return Err(format!("pipeline stage '{}' needs '{}' before it", spec.name, dep.name()));
+                }
+            }
+            if let Some(later) = spec.after.iter().find(|a| steps[i + 1..].contains(a)) {
+                return Err(format!("pipeline stage '{}' must come after '{}'", spec.name, later.name()));
+            }
+        }
+        if let Some(missing) = STAGE_REGISTRY.iter().find(|s| s.required && !steps.contains(&s.stage)) {
+            return Err(format!("pipeline is missing the required stage '{}'", missing.name));
+        }
+        Ok(Self { steps })
+    }
+
+    pub fn steps(&self) -> &[PipelineStage] {
+        &self.steps
+    }
+
+    pub fn runs(&self, stage: PipelineStage) -> bool {
+        self.steps.contains(&stage)
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.steps.iter().map(|s| s.name()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_registry_follows_the_declaration_order() {
+        for (i, spec) in STAGE_REGISTRY.iter().enumerate() {
+            assert_eq!(spec.stage as usize, i, "'{}' is out of order", spec.name);
+        }
+        assert_eq!(Thumbnail.spec().name, "thumbnail");
+    }
+
+    #[test]
+    fn test_empty_pipeline_is_the_default_order() {
+        let pipeline = Pipeline::parse(&[]).unwrap();
+        assert_eq!(pipeline.names(), vec!["images", "voice", "assemble", "sfx", "bgm", "title_overlay", "captions", "render", "thumbnail"]);
+        assert!(Pipeline::parse(&names(&pipeline.names())).is_ok());
+    }
+
+    #[test]
+    fn test_custom_pipeline_skips_and_reorders() {
+        let pipeline = Pipeline::parse(&names(&["voice", "images", "upscale", "assemble", "captions", "render"])).unwrap();
+        assert!(pipeline.runs(Upscale) && !pipeline.runs(Bgm) && !pipeline.runs(Thumbnail));
+        assert_eq!(pipeline.steps()[0], Voice);
+    }
+
+    #[test]
+    fn test_invalid_pipelines_are_rejected() {
+        let err = |list: &[&str]| Pipeline::parse(&names(list)).unwrap_err();
+        assert!(err(&["voice", "broll", "assemble", "render"]).contains("unknown"));
+        assert!(err(&["voice", "voice", "assemble", "render"]).contains("twice"));
+        assert!(err(&["assemble", "voice", "render"]).contains("needs 'voice'"));
+        assert!(err(&["voice", "assemble", "render", "bgm"]).contains("must come after 'bgm'"));
+        assert!(err(&["voice", "assemble", "bgm", "sfx", "render"]).contains("'bgm' must come after 'sfx'"));
+        assert!(err(&["voice", "assemble", "captions", "title_overlay", "render"]).contains("'captions' must come after 'title_overlay'"));
+        assert!(err(&["voice", "assemble", "title_overlay", "bgm", "render"]).contains("'title_overlay' must come after 'bgm'"));
+        assert!(err(&["voice", "upscale", "assemble", "render"]).contains("needs 'images'"));
+        assert!(err(&["voice", "assemble"]).contains("required stage 'render'"));
+    }
+}
@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::SystemTime;
+use factory_core::contracts::{ModelStack, SceneTransition};
 use factory_core::error::FactoryError;
+use crate::pipeline::Pipeline;
+use crate::subtitle_theme::{SubtitlePosition, SubtitleTheme};
 
 /// 演出プロファイル（スタイル）の定義
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct StyleProfile {
     /// プロファイル名
     pub name: String,
@@ -26,6 +31,68 @@ pub struct StyleProfile {
     pub ducking_ratio: f32,
     /// フェードアウト時間 (秒)
     pub fade_duration: f32,
+
+    // --- タイポグラフィ (Opening Hook) ---
+    /// 冒頭 0〜2 秒にタイトルをオーバーレイするか
+    #[serde(default)]
+    pub title_overlay: bool,
+    /// タイトル用フォント (未指定なら言語別の字幕フォント)
+    #[serde(default)]
+    pub title_font: Option<String>,
+    /// 二言語字幕: 主言語の字幕に副言語の小さな行を添える (未指定なら主言語のみ)
+    #[serde(default)]
+    pub dual_captions: Option<DualCaptionLayout>,
+    /// 焼き込み字幕の見た目 (未指定なら従来の白文字・黒縁・下寄せ)
+    #[serde(default)]
+    pub subtitle_theme: Option<SubtitleTheme>,
+    /// 幕のつなぎ目のトランジション (未指定ならカットでつなぐ)
+    #[serde(default)]
+    pub transition: Option<SceneTransition>,
+
+    // --- 映像生成 (ComfyUI) ---
+    /// シーン (Intro, Body, Outro) ごとのワークフロー。空・欠けは既定のワークフロー
+    #[serde(default)]
+    pub scene_workflows: Vec<String>,
+    /// チェックポイント・LoRA の差し替え (`GET /api/comfy/models` で使える名前を確認できる)
+    #[serde(default)]
+    pub models: ModelStack,
+
+    // --- 工程 ---
+    /// 工程名の並び (`crate::pipeline` の登録簿で検証する)。空なら既定の工程
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pipeline: Vec<String>,
+}
+
+/// 副言語の行を主字幕の上下どちらに置くか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptionPosition {
+    #[default]
+    Above,
+    Below,
+}
+
+/// 二言語字幕のレイアウト (`[<style>.dual_captions]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DualCaptionLayout {
+    /// 副言語。この言語の台本から 2 行目を作る (主言語と同じ言語の動画には付けない)
+    pub secondary_lang: String,
+    /// 主字幕に対する副字幕の文字サイズの比率
+    #[serde(default = "default_secondary_scale")]
+    pub secondary_scale: f32,
+    #[serde(default)]
+    pub position: CaptionPosition,
+    /// 主字幕との間隔 (1080x1920 基準の px)
+    #[serde(default = "default_caption_gap")]
+    pub gap: u32,
+}
+
+fn default_secondary_scale() -> f32 {
+    0.65
+}
+
+fn default_caption_gap() -> u32 {
+    12
 }
 
 impl Default for StyleProfile {
@@ -39,47 +106,143 @@ impl Default for StyleProfile {
             ducking_threshold: 0.1, // sidechaincompress の threshold
             ducking_ratio: 0.4,
             fade_duration: 3.0,
+            title_overlay: true,
+            title_font: None,
+            dual_captions: None,
+            subtitle_theme: None,
+            transition: None,
+            scene_workflows: Vec::new(),
+            models: ModelStack::default(),
+            pipeline: Vec::new(),
+        }
+    }
+}
+
+impl StyleProfile {
+    /// 値域チェック (REST から受け取ったプロファイルを保存する前に呼ぶ)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() || !self.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("name '{}' must be non-empty and contain only [A-Za-z0-9_-]", self.name));
+        }
+        if let Some(id) = self.scene_workflows.iter().find(|id| !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')) {
+            return Err(format!("scene_workflows entry '{}' must contain only [A-Za-z0-9_-]", id));
+        }
+        let ranges: [(&str, f64, f64, f64); 6] = [
+            ("zoom_speed", self.zoom_speed, 0.0, 0.02),
+            ("pan_intensity", self.pan_intensity, 0.0, 2.0),
+            ("bgm_volume", self.bgm_volume as f64, 0.0, 1.0),
+            ("ducking_threshold", self.ducking_threshold as f64, 0.0, 1.0),
+            ("ducking_ratio", self.ducking_ratio as f64, 0.0, 1.0),
+            ("fade_duration", self.fade_duration as f64, 0.0, 30.0),
+        ];
+        for (field, value, min, max) in ranges {
+            if !(min..=max).contains(&value) {
+                return Err(format!("{} = {} is out of range ({} - {})", field, value, min, max));
+            }
+        }
+        if let Some(dual) = &self.dual_captions {
+            if dual.secondary_lang.trim().is_empty() {
+                return Err("dual_captions.secondary_lang must not be empty".to_string());
+            }
+            if !(0.3..=1.0).contains(&dual.secondary_scale) {
+                return Err(format!("dual_captions.secondary_scale = {} is out of range (0.3 - 1.0)", dual.secondary_scale));
+            }
         }
+        if let Some(theme) = &self.subtitle_theme {
+            theme.validate()?;
+            // 中央寄せはマージンが効かず、副言語の行を上下に積めない
+            if self.dual_captions.is_some() && theme.position == SubtitlePosition::Middle {
+                return Err("subtitle_theme.position = \"middle\" cannot be combined with dual_captions".to_string());
+            }
+        }
+        if let Some(transition) = &self.transition {
+            if transition.kind.is_empty() || !transition.kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("transition.kind '{}' must be an xfade transition name", transition.kind));
+            }
+            if !(0.1..=2.0).contains(&transition.duration) {
+                return Err(format!("transition.duration = {} is out of range (0.1 - 2.0)", transition.duration));
+            }
+        }
+        if self.models.checkpoint.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err("models.checkpoint must not be empty".to_string());
+        }
+        for lora in &self.models.loras {
+            if lora.name.trim().is_empty() || !(-4.0..=4.0).contains(&lora.strength) {
+                return Err(format!("models.loras entry '{}' needs a name and a strength within -4 - 4", lora.name));
+            }
+        }
+        Pipeline::parse(&self.pipeline).map(|_| ())
+    }
+
+    /// 検証済みの工程表
+    pub fn pipeline(&self) -> Result<Pipeline, String> {
+        Pipeline::parse(&self.pipeline)
+    }
+
+    /// 焼き込み字幕のテーマ (未指定なら既定のテーマ)
+    pub fn subtitle_theme(&self) -> SubtitleTheme {
+        self.subtitle_theme.clone().unwrap_or_default()
     }
 }
 
 /// 演出スタイルを管理するマネージャ
+///
+/// プロファイルは実行中に差し替え可能 (REST API での編集・styles.toml の変更検知)。
+/// Orchestrator は `get_style` のたびに最新の値を読む。
 pub struct StyleManager {
-    profiles: HashMap<String, StyleProfile>,
+    profiles: RwLock<HashMap<String, StyleProfile>>,
+    /// 永続化先 (None ならメモリ上のみ)
+    path: Option<PathBuf>,
+    /// 最後に読み込んだ / 書き込んだ時点の styles.toml の更新時刻
+    loaded_mtime: Mutex<Option<SystemTime>>,
 }
 
 impl StyleManager {
     /// styles.toml からプロファイルをロードする
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, FactoryError> {
-        let content = std::fs::read_to_string(path).map_err(|e| FactoryError::ConfigLoad {
-            source: anyhow::anyhow!("Failed to read styles.toml: {}", e),
-        })?;
-        
-        let config: HashMap<String, StyleProfile> = toml::from_str(&content).map_err(|e| FactoryError::ConfigLoad {
-            source: anyhow::anyhow!("Failed to parse styles.toml: {}", e),
-        })?;
-        
-        Ok(Self { profiles: config })
+        let path = path.as_ref().to_path_buf();
+        let profiles = Self::read_profiles(&path)?;
+        Ok(Self {
+            profiles: RwLock::new(profiles),
+            loaded_mtime: Mutex::new(mtime(&path)),
+            path: Some(path),
+        })
     }
 
     /// デフォルト設定のみのマネージャを作成
     pub fn new_empty() -> Self {
         let mut profiles = HashMap::new();
         profiles.insert("default".into(), StyleProfile::default());
-        Self { profiles }
+        Self { profiles: RwLock::new(profiles), path: None, loaded_mtime: Mutex::new(None) }
+    }
+
+    fn read_profiles(path: &Path) -> Result<HashMap<String, StyleProfile>, FactoryError> {
+        let content = std::fs::read_to_string(path).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to read styles.toml: {}", e),
+        })?;
+        
+        toml::from_str(&content).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to parse styles.toml: {}", e),
+        })
     }
 
     /// 特定のスタイルを取得（存在しない場合は default）
     pub fn get_style(&self, name: &str) -> StyleProfile {
-        self.profiles.get(name).cloned().unwrap_or_else(|| {
+        let profiles = self.profiles.read().unwrap_or_else(|e| e.into_inner());
+        profiles.get(name).cloned().unwrap_or_else(|| {
             tracing::warn!("Style '{}' not found, falling back to default", name);
-            self.profiles.get("default").cloned().unwrap_or_default()
+            profiles.get("default").cloned().unwrap_or_default()
         })
     }
 
+    /// 特定のスタイルを取得（存在しない場合は None）
+    pub fn find_style(&self, name: &str) -> Option<StyleProfile> {
+        self.profiles.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned()
+    }
+
     /// 利用可能なスタイル名の一覧を取得（LLM提示用）
     pub fn list_available_styles(&self) -> Vec<String> {
-        let mut keys: Vec<String> = self.profiles.keys().cloned().collect();
+        let mut keys: Vec<String> = self.profiles.read().unwrap_or_else(|e| e.into_inner()).keys().cloned().collect();
         keys.sort();
         keys
     }
@@ -87,9 +250,244 @@ impl StyleManager {
     /// プロファイルの説明を含めた詳細な一覧を取得（LLM提示用）
     pub fn get_style_descriptions(&self) -> String {
         let mut desc = String::new();
-        for profile in self.profiles.values() {
+        for profile in self.profiles.read().unwrap_or_else(|e| e.into_inner()).values() {
             desc.push_str(&format!("- {}: {}\n", profile.name, profile.description));
         }
         desc
     }
+
+    /// プロファイルを追加・更新して styles.toml に書き戻す。新規作成なら true。
+    pub fn upsert_style(&self, profile: StyleProfile) -> Result<bool, FactoryError> {
+        profile.validate().map_err(|reason| FactoryError::ConfigLoad { source: anyhow::anyhow!(reason) })?;
+        let mut profiles = self.profiles.write().map_err(poisoned)?;
+        let mut next = profiles.clone();
+        let created = next.insert(profile.name.clone(), profile).is_none();
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(created)
+    }
+
+    /// プロファイルを削除して styles.toml に書き戻す。存在しなければ false。
+    pub fn remove_style(&self, name: &str) -> Result<bool, FactoryError> {
+        if name == "default" {
+            return Err(FactoryError::ConfigLoad { source: anyhow::anyhow!("The 'default' style cannot be deleted") });
+        }
+        let mut profiles = self.profiles.write().map_err(poisoned)?;
+        if !profiles.contains_key(name) {
+            return Ok(false);
+        }
+        let mut next = profiles.clone();
+        next.remove(name);
+        self.persist(&next)?;
+        *profiles = next;
+        Ok(true)
+    }
+
+    /// styles.toml を読み直す。パースに失敗した場合は現行のプロファイルを維持する。
+    pub fn reload(&self) -> Result<usize, FactoryError> {
+        let Some(path) = &self.path else { return Ok(self.profiles.read().map_err(poisoned)?.len()) };
+        let loaded = Self::read_profiles(path)?;
+        if let Some((name, reason)) = loaded.iter().find_map(|(k, p)| p.validate().err().map(|r| (k.clone(), r))) {
+            return Err(FactoryError::ConfigLoad { source: anyhow::anyhow!("style '{}': {}", name, reason) });
+        }
+        let count = loaded.len();
+        *self.profiles.write().map_err(poisoned)? = loaded;
+        *self.loaded_mtime.lock().map_err(poisoned)? = mtime(path);
+        Ok(count)
+    }
+
+    /// styles.toml が外部で更新されていれば読み直す。読み直した場合は true。
+    pub fn reload_if_changed(&self) -> Result<bool, FactoryError> {
+        let Some(path) = &self.path else { return Ok(false) };
+        let current = mtime(path);
+        {
+            let mut loaded = self.loaded_mtime.lock().map_err(poisoned)?;
+            if current.is_none() || current == *loaded {
+                return Ok(false);
+            }
+            // 読み直しに失敗しても、同じ内容で警告し続けないよう見た時刻は残す
+            *loaded = current;
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// 一時ファイルに書いてから rename し、途中状態の styles.toml を残さない。
+    /// 既存のファイルに値だけを差し込むので、手で書いたコメントや並び順は残る
+    fn persist(&self, profiles: &HashMap<String, StyleProfile>) -> Result<(), FactoryError> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let sorted: BTreeMap<&String, &StyleProfile> = profiles.iter().collect();
+        let serialized = toml::to_string_pretty(&sorted).map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to serialize styles.toml: {}", e),
+        })?;
+        let fresh: toml_edit::Document = serialized.parse().map_err(|e| FactoryError::ConfigLoad {
+            source: anyhow::anyhow!("Failed to serialize styles.toml: {}", e),
+        })?;
+        let mut doc = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|current| current.parse::<toml_edit::Document>().ok())
+            .unwrap_or_default();
+        merge_table(doc.as_table_mut(), fresh.as_table());
+        let tmp = path.with_extension("toml.tmp");
+        std::fs::write(&tmp, doc.to_string())
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| FactoryError::ConfigLoad { source: anyhow::anyhow!("Failed to write styles.toml: {}", e) })?;
+        *self.loaded_mtime.lock().map_err(poisoned)? = mtime(path);
+        Ok(())
+    }
+}
+
+fn mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 別スレッドが保持中に panic したロック
+fn poisoned<T>(_: PoisonError<T>) -> FactoryError {
+    FactoryError::Infrastructure { reason: "StyleManager lock poisoned".to_string() }
+}
+
+/// `current` を `fresh` と同じ内容にする。残るキーはそのまま書き換えるので、キーや表に付いたコメントは消えない
+fn merge_table(current: &mut toml_edit::Table, fresh: &toml_edit::Table) {
+    current.retain(|key, _| fresh.contains_key(key));
+    for (key, item) in fresh.iter() {
+        match current.get_mut(key) {
+            Some(existing) => merge_item(existing, item),
+            None => {
+                current.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+fn merge_item(current: &mut toml_edit::Item, fresh: &toml_edit::Item) {
+    match (current, fresh) {
+        (toml_edit::Item::Table(current), toml_edit::Item::Table(fresh)) => merge_table(current, fresh),
+        (toml_edit::Item::Value(current), toml_edit::Item::Value(fresh)) => {
+            let bare = |v: &toml_edit::Value| {
+                let mut v = v.clone();
+                v.decor_mut().clear();
+                v.to_string()
+            };
+            // f32 のフィールドは 0.1 が 0.10000000149011612 のように書き出されるので、数値は誤差込みで比べる
+            let same = match (current.as_float(), fresh.as_float()) {
+                (Some(a), Some(b)) => (a - b).abs() <= 1e-6 * a.abs().max(1.0),
+                _ => bare(current) == bare(fresh),
+            };
+            if !same {
+                // 行末コメントは値の装飾に付いているので引き継ぐ
+                let decor = current.decor().clone();
+                *current = fresh.clone();
+                *current.decor_mut() = decor;
+            }
+        }
+        (current, fresh) => *current = fresh.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_and_remove_persist_to_file() {
+        let path = std::env::temp_dir().join(format!("styles_test_{}.toml", std::process::id()));
+        std::fs::write(&path, "[default]\nname = \"default\"\ndescription = \"d\"\nzoom_speed = 0.0015\npan_intensity = 0.5\nbgm_volume = 0.15\nducking_threshold = 0.1\nducking_ratio = 0.4\nfade_duration = 3.0\n").unwrap();
+        let manager = StyleManager::load_from_file(&path).unwrap();
+
+        let profile = StyleProfile { name: "calm".into(), zoom_speed: 0.0005, ..StyleProfile::default() };
+        assert!(manager.upsert_style(profile).unwrap());
+        assert!(StyleManager::load_from_file(&path).unwrap().find_style("calm").is_some());
+
+        let invalid = StyleProfile { name: "loud".into(), bgm_volume: 3.0, ..StyleProfile::default() };
+        assert!(manager.upsert_style(invalid).is_err());
+        assert!(manager.remove_style("default").is_err());
+
+        assert!(manager.remove_style("calm").unwrap());
+        assert_eq!(StyleManager::load_from_file(&path).unwrap().list_available_styles(), vec!["default"]);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_persist_keeps_comments_and_reload_failures_are_not_retried() {
+        let path = std::env::temp_dir().join(format!("styles_comments_test_{}.toml", std::process::id()));
+        std::fs::write(&path, "# 手で書いたコメント\n[default]\nname = \"default\"\ndescription = \"d\"\nzoom_speed = 0.0015\npan_intensity = 0.5\nbgm_volume = 0.15\nducking_threshold = 0.1 # sidechaincompress の threshold\nducking_ratio = 0.4\nfade_duration = 3.0\n").unwrap();
+        let manager = StyleManager::load_from_file(&path).unwrap();
+
+        let profile = StyleProfile { name: "default".into(), description: "d".into(), bgm_volume: 0.2, ..StyleProfile::default() };
+        assert!(!manager.upsert_style(profile).unwrap());
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("# 手で書いたコメント"));
+        assert!(written.contains("ducking_threshold = 0.1 # sidechaincompress の threshold"));
+        assert!(written.contains("bgm_volume = 0.2"));
+
+        // 壊れたファイルは 1 度だけ失敗し、次の確認では読み直さない
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        std::fs::write(&path, "[default\n").unwrap();
+        assert!(manager.reload_if_changed().is_err());
+        assert!(!manager.reload_if_changed().unwrap());
+        assert!((manager.get_style("default").bgm_volume - 0.2).abs() < 1e-6);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_shipped_styles_toml_is_valid() {
+        let manager = StyleManager::load_from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/../../styles.toml")).unwrap();
+        assert!(manager.reload().unwrap() > 1);
+        assert!(manager.find_style("fast_cuts").is_some());
+    }
+
+    #[test]
+    fn test_scene_workflows_must_be_plain_ids() {
+        let toml = "name = \"mixed\"\ndescription = \"d\"\nzoom_speed = 0.001\npan_intensity = 0.5\nbgm_volume = 0.1\nducking_threshold = 0.1\nducking_ratio = 0.4\nfade_duration = 3.0\nscene_workflows = [\"img2img_intro_v1\", \"svd_body_v1\"]\n";
+        let mut profile: StyleProfile = toml::from_str(toml).unwrap();
+        assert_eq!(profile.scene_workflows.len(), 2);
+        assert!(profile.validate().is_ok());
+        profile.scene_workflows.push("../../etc/passwd".into());
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_dual_captions_layout_defaults_and_range() {
+        let toml = "name = \"bilingual\"\ndescription = \"d\"\nzoom_speed = 0.001\npan_intensity = 0.5\nbgm_volume = 0.1\nducking_threshold = 0.1\nducking_ratio = 0.4\nfade_duration = 3.0\n[dual_captions]\nsecondary_lang = \"en\"\nposition = \"below\"\n";
+        let mut profile: StyleProfile = toml::from_str(toml).unwrap();
+        let dual = profile.dual_captions.clone().unwrap();
+        assert_eq!((dual.secondary_lang.as_str(), dual.position, dual.secondary_scale, dual.gap), ("en", CaptionPosition::Below, 0.65, 12));
+        assert!(profile.validate().is_ok());
+        profile.dual_captions.as_mut().unwrap().secondary_scale = 1.5;
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_subtitle_theme_is_validated_with_dual_captions() {
+        let toml = "name = \"loud\"\ndescription = \"d\"\nzoom_speed = 0.001\npan_intensity = 0.5\nbgm_volume = 0.1\nducking_threshold = 0.1\nducking_ratio = 0.4\nfade_duration = 3.0\n[subtitle_theme]\nprimary_color = \"#FFE600\"\nposition = \"middle\"\n";
+        let mut profile: StyleProfile = toml::from_str(toml).unwrap();
+        assert!(profile.validate().is_ok());
+        assert_eq!(profile.subtitle_theme().margin_v, 200);
+        profile.dual_captions = Some(DualCaptionLayout { secondary_lang: "en".into(), secondary_scale: 0.65, position: CaptionPosition::Above, gap: 12 });
+        assert!(profile.validate().unwrap_err().contains("dual_captions"));
+        assert_eq!(StyleProfile::default().subtitle_theme(), SubtitleTheme::default());
+    }
+
+    #[test]
+    fn test_transition_defaults_and_validation() {
+        let toml = "name = \"smooth\"\ndescription = \"d\"\nzoom_speed = 0.001\npan_intensity = 0.5\nbgm_volume = 0.1\nducking_threshold = 0.1\nducking_ratio = 0.4\nfade_duration = 3.0\n[transition]\nkind = \"wipeleft\"\n";
+        let mut profile: StyleProfile = toml::from_str(toml).unwrap();
+        assert_eq!(profile.transition, Some(SceneTransition { kind: "wipeleft".into(), duration: 0.5 }));
+        assert!(profile.validate().is_ok());
+        profile.transition = Some(SceneTransition { kind: "fade:offset=0".into(), duration: 0.5 });
+        assert!(profile.validate().is_err());
+        profile.transition = Some(SceneTransition { duration: 3.0, ..SceneTransition::default() });
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_pipeline_is_validated_against_the_registry() {
+        let mut profile = StyleProfile { name: "narrated".into(), ..StyleProfile::default() };
+        assert_eq!(profile.pipeline().unwrap(), Pipeline::default());
+        profile.pipeline = ["voice", "images", "assemble", "captions", "render"].iter().map(|s| s.to_string()).collect();
+        assert!(profile.validate().is_ok());
+        assert!(!profile.pipeline().unwrap().runs(crate::PipelineStage::Bgm));
+        profile.pipeline.push("broll".into());
+        assert!(profile.validate().unwrap_err().contains("unknown"));
+    }
 }
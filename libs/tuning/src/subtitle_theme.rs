@@ -0,0 +1,185 @@
+//! # SubtitleTheme — 焼き込み字幕の見た目
+//!
+//! 以前の字幕は SRT に `combine_assets` 直書きの `force_style` を上書きするだけで、色も位置もスタイルごとに変えられなかった。
+//! `styles.toml` の `[<style>.subtitle_theme]` に書体・色・縁取り・位置・アニメーションを書くと、
+//! MediaForge がそのスタイル定義を持つ ASS を書き出して焼き込む。未指定なら従来の白文字・黒縁・下寄せ。
+//!
+//! ```toml
+//! [fast_cuts.subtitle_theme]
+//! primary_color = "#FFE600"
+//! outline = 10.0
+//! position = "middle"
+//! animation = "pop"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// 字幕を置く位置 (ASS の Alignment: 下・中央・上の中寄せ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitlePosition {
+    #[default]
+    Bottom,
+    Middle,
+    Top,
+}
+
+/// 1 行ごとの出方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleAnimation {
+    #[default]
+    None,
+    /// 短いフェードイン・アウト
+    Fade,
+    /// 少し小さく出て元の大きさに弾む
+    Pop,
+}
+
+/// 焼き込み字幕のテーマ (`[<style>.subtitle_theme]`)。寸法は 1080x1920 基準の px
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct SubtitleTheme {
+    /// 書体 (未指定なら fonts.toml の言語別フォント)
+    pub font: Option<String>,
+    /// 文字サイズ (未指定なら fonts.toml の `subtitle_size` を換算)
+    pub size: Option<u32>,
+    pub bold: bool,
+    /// `#RRGGBB` または不透明度付きの `#RRGGBBAA`
+    pub primary_color: String,
+    pub outline_color: String,
+    /// 影 (`boxed` なら背景の箱) の色
+    pub back_color: String,
+    pub outline: f32,
+    pub shadow: f32,
+    /// 縁取りの代わりに `outline_color` の箱を敷く
+    pub boxed: bool,
+    pub position: SubtitlePosition,
+    /// 下寄せ・上寄せのときの画面端からの距離
+    pub margin_v: u32,
+    pub animation: SubtitleAnimation,
+}
+
+impl Default for SubtitleTheme {
+    /// 従来の force_style (白文字・黒縁 2・影 1・下マージン 30 を SRT 座標から換算) と同じ見た目
+    fn default() -> Self {
+        Self {
+            font: None,
+            size: None,
+            bold: false,
+            primary_color: "#FFFFFF".to_string(),
+            outline_color: "#000000".to_string(),
+            back_color: "#0000007F".to_string(),
+            outline: 13.0,
+            shadow: 7.0,
+            boxed: false,
+            position: SubtitlePosition::Bottom,
+            margin_v: 200,
+            animation: SubtitleAnimation::None,
+        }
+    }
+}
+
+/// `#RRGGBB` / `#RRGGBBAA` を ASS の `&HAABBGGRR` に直す (ASS のアルファは 00 が不透明)
+pub fn ass_color(hex: &str) -> Result<String, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("color '{}' must be #RRGGBB or #RRGGBBAA", hex));
+    }
+    let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or(0);
+    let opacity = if digits.len() == 8 { byte(6) } else { 0xFF };
+    Ok(format!("&H{:02X}{:02X}{:02X}{:02X}", 0xFF - opacity, byte(4), byte(2), byte(0)))
+}
+
+impl SubtitleTheme {
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, color) in [("primary_color", &self.primary_color), ("outline_color", &self.outline_color), ("back_color", &self.back_color)] {
+            ass_color(color).map_err(|e| format!("subtitle_theme.{}: {}", field, e))?;
+        }
+        if self.font.as_deref().is_some_and(|f| f.trim().is_empty() || f.contains(',')) {
+            return Err("subtitle_theme.font must be a non-empty family name without commas".to_string());
+        }
+        if let Some(size) = self.size.filter(|s| !(20..=300).contains(s)) {
+            return Err(format!("subtitle_theme.size = {} is out of range (20 - 300)", size));
+        }
+        for (field, value) in [("outline", self.outline), ("shadow", self.shadow)] {
+            if !(0.0..=40.0).contains(&value) {
+                return Err(format!("subtitle_theme.{} = {} is out of range (0 - 40)", field, value));
+            }
+        }
+        if self.margin_v > 1600 {
+            return Err(format!("subtitle_theme.margin_v = {} is out of range (0 - 1600)", self.margin_v));
+        }
+        Ok(())
+    }
+
+    /// ASS の Alignment (テンキー配置)
+    pub fn alignment(&self) -> u8 {
+        match self.position {
+            SubtitlePosition::Bottom => 2,
+            SubtitlePosition::Middle => 5,
+            SubtitlePosition::Top => 8,
+        }
+    }
+
+    /// `[V4+ Styles]` の 1 行 (色は validate 済みの前提。読めない色は白に落とす)
+    pub fn style_line(&self, name: &str, font: &str, size: u32, margin_v: u32) -> String {
+        let color = |hex: &str| ass_color(hex).unwrap_or_else(|_| "&H00FFFFFF".to_string());
+        format!(
+            "Style: {name},{font},{size},{primary},&H00FFFFFF,{outline_color},{back},{bold},0,0,0,100,100,0,0,{border},{outline},{shadow},{align},60,60,{margin},1",
+            name = name,
+            font = self.font.as_deref().unwrap_or(font),
+            size = self.size.unwrap_or(size),
+            primary = color(&self.primary_color),
+            outline_color = color(&self.outline_color),
+            back = color(&self.back_color),
+            bold = if self.bold { -1 } else { 0 },
+            border = if self.boxed { 3 } else { 1 },
+            outline = self.outline,
+            shadow = self.shadow,
+            align = self.alignment(),
+            margin = margin_v,
+        )
+    }
+
+    /// 各行の先頭に付けるオーバーライドタグ
+    pub fn animation_tags(&self) -> &'static str {
+        match self.animation {
+            SubtitleAnimation::None => "",
+            SubtitleAnimation::Fade => "{\\fad(120,80)}",
+            SubtitleAnimation::Pop => "{\\fscx80\\fscy80\\t(0,120,\\fscx100\\fscy100)}",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ass_color_converts_and_rejects() {
+        assert_eq!(ass_color("#FFE600").unwrap(), "&H0000E6FF");
+        assert_eq!(ass_color("#0000007F").unwrap(), "&H80000000");
+        assert!(ass_color("yellow").is_err());
+        assert!(ass_color("#FFF").is_err());
+    }
+
+    #[test]
+    fn test_theme_defaults_style_line_and_validation() {
+        let toml = "primary_color = \"#FFE600\"\nbold = true\nposition = \"top\"\nanimation = \"pop\"\n";
+        let mut theme: SubtitleTheme = toml::from_str(toml).unwrap();
+        assert!(theme.validate().is_ok());
+        assert_eq!(
+            theme.style_line("Caption", "Inter Bold", 80, 200),
+            "Style: Caption,Inter Bold,80,&H0000E6FF,&H00FFFFFF,&H00000000,&H80000000,-1,0,0,0,100,100,0,0,1,13,7,8,60,60,200,1"
+        );
+        assert!(theme.animation_tags().contains("\\t(0,120"));
+
+        theme.font = Some("Bebas Neue".into());
+        theme.boxed = true;
+        assert!(theme.style_line("Caption", "Inter Bold", 80, 200).starts_with("Style: Caption,Bebas Neue,80,"));
+        theme.back_color = "#12345".into();
+        assert!(theme.validate().unwrap_err().contains("back_color"));
+        assert!(SubtitleTheme { size: Some(5), ..SubtitleTheme::default() }.validate().is_err());
+    }
+}